@@ -0,0 +1,113 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,0,0,-3>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,0,0,0,-1>
+(0,0) -> (3,0) -> (4,0) -> (21,0)
+(1,0) -> (6,0) -> (7,0) -> (21,1)
+(2,0) -> (9,0) -> (10,0) -> (22,1)
+(3,2) -> (4,1)
+(4,2) -> (5,0)
+(6,2) -> (7,1)
+(7,2) -> (8,0)
+(9,2) -> (10,1)
+(10,2) -> (11,0)
+(12,0) -> (13,0) -> (33,0) -> (47,0)
+(12,2) -> (13,1)
+(13,2) -> (14,0)
+(15,0) -> (16,0) -> (33,1) -> (47,1)
+(15,2) -> (16,1)
+(16,2) -> (17,0)
+(18,0) -> (19,0) -> (34,1) -> (48,1)
+(18,2) -> (19,1)
+(19,2) -> (20,0)
+(21,2) -> (22,0)
+(22,2) -> (24,0)
+(23,0) -> (25,0) -> (50,0)
+(24,1) -> (25,1)
+(25,2) -> (26,1) -> (30,1)
+(26,2) -> (29,0)
+(27,0) -> (30,0) -> (32,0)
+(27,1) -> (28,0)
+(28,2) -> (29,1)
+(30,2) -> (31,0)
+(33,2) -> (34,0)
+(34,2) -> (36,0)
+(35,0) -> (37,0)
+(36,1) -> (37,1)
+(37,2) -> (38,1) -> (42,1)
+(38,2) -> (41,0)
+(39,0) -> (42,0) -> (44,0)
+(39,1) -> (40,0)
+(40,2) -> (41,1)
+(42,2) -> (43,0)
+(44,1) -> (45,0)
+(45,2) -> (46,0)
+(47,2) -> (48,0)
+(48,2) -> (49,0)
+(49,1) -> (50,1)
+(50,2) -> (51,1) -> (55,1)
+(51,2) -> (54,0)
+(52,0) -> (55,0) -> (57,0)
+(52,1) -> (53,0)
+(53,2) -> (54,1)
+(55,2) -> (56,0)
+(57,1) -> (58,0)
+(58,2) -> (59,0)
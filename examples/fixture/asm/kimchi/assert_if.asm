@@ -0,0 +1,21 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,0,-1,0,1>
+(0,0) -> (2,0) -> (3,0) -> (7,0)
+(1,0) -> (6,0) -> (9,0) -> (10,0)
+(2,2) -> (3,1)
+(3,2) -> (4,0)
+(5,1) -> (6,1)
+(6,2) -> (7,1)
+(7,2) -> (8,0)
+(9,2) -> (10,2)
@@ -0,0 +1,11 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,0,0,0,-1>
+(0,0) -> (2,0)
+(1,1) -> (2,1)
+(2,2) -> (3,0)
+(3,2) -> (4,0)
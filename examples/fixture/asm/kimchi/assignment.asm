@@ -7,13 +7,11 @@ DoubleGeneric<1,0,-1,0,2>
 DoubleGeneric<1,0,-1,0,3>
 DoubleGeneric<1,1,-1>
 DoubleGeneric<1,1,-1>
-DoubleGeneric<1,-1>
 DoubleGeneric<1,0,0,0,-4>
 DoubleGeneric<1,0,0,0,-4>
 DoubleGeneric<1,0,0,0,-5>
 (0,0) -> (1,0) -> (3,0) -> (4,0) -> (5,0) -> (5,1) -> (6,0) -> (6,1)
 (1,2) -> (2,0)
-(3,2) -> (9,0)
-(4,2) -> (10,0)
-(5,2) -> (7,0) -> (8,0)
-(6,2) -> (7,1)
+(3,2) -> (8,0)
+(4,2) -> (9,0)
+(5,2) -> (7,0) -> (6,2)
@@ -0,0 +1,78 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<2,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<2,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,0,0,0,-1>
+(0,0) -> (1,0) -> (2,0) -> (7,0) -> (11,0) -> (12,0) -> (16,0) -> (18,0) -> (22,0) -> (33,0) -> (33,1) -> (35,0) -> (35,1)
+(1,2) -> (2,1)
+(2,2) -> (3,0)
+(4,0) -> (5,0) -> (7,1) -> (11,1) -> (12,1) -> (16,1) -> (18,1) -> (22,1) -> (25,0) -> (25,1) -> (26,0) -> (26,1)
+(4,2) -> (5,1)
+(5,2) -> (6,0)
+(7,2) -> (8,0)
+(8,1) -> (9,0)
+(9,2) -> (10,0)
+(11,2) -> (13,0)
+(12,2) -> (14,0)
+(13,1) -> (14,1)
+(14,2) -> (15,0)
+(16,2) -> (17,0)
+(17,2) -> (19,0)
+(18,2) -> (20,0)
+(19,1) -> (20,1)
+(20,2) -> (21,0)
+(22,2) -> (23,0)
+(23,1) -> (24,0)
+(24,2) -> (31,0)
+(25,2) -> (27,0)
+(26,2) -> (28,0)
+(27,1) -> (28,1)
+(28,2) -> (29,0)
+(29,1) -> (30,0)
+(30,2) -> (31,1)
+(31,2) -> (32,0)
+(33,2) -> (34,0)
+(34,2) -> (36,0)
+(35,2) -> (37,0)
+(36,1) -> (37,1)
+(37,2) -> (38,0)
+(38,1) -> (39,0)
+(39,2) -> (40,0)
@@ -0,0 +1,90 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,0,0,-2>
+Lookup
+Lookup
+Lookup
+Lookup
+Lookup
+Lookup
+Lookup
+Lookup
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,-1>
+Lookup
+Lookup
+Lookup
+Lookup
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+Lookup
+Lookup
+Lookup
+Lookup
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+Lookup
+Lookup
+Lookup
+Lookup
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<256,0,-1>
+DoubleGeneric<1,1,-1>
+Lookup
+Lookup
+DoubleGeneric<1,0,-1,0,512>
+DoubleGeneric<1,-1>
+(0,0) -> (52,0)
+(2,0) -> (4,2) -> (4,4) -> (4,6) -> (5,2) -> (5,4) -> (5,6) -> (6,2) -> (6,4) -> (6,6) -> (7,2) -> (7,4) -> (7,6) -> (8,2) -> (8,4) -> (8,6) -> (9,2) -> (9,4) -> (9,6) -> (10,2) -> (10,4) -> (10,6) -> (11,2) -> (11,4) -> (11,6) -> (19,2) -> (19,4) -> (19,6) -> (20,2) -> (20,4) -> (20,6) -> (21,2) -> (21,4) -> (21,6) -> (22,2) -> (22,4) -> (22,6) -> (29,2) -> (29,4) -> (29,6) -> (30,2) -> (30,4) -> (30,6) -> (31,2) -> (31,4) -> (31,6) -> (32,2) -> (32,4) -> (32,6) -> (39,2) -> (39,4) -> (39,6) -> (40,2) -> (40,4) -> (40,6) -> (41,2) -> (41,4) -> (41,6) -> (42,2) -> (42,4) -> (42,6) -> (49,2) -> (49,4) -> (49,6) -> (50,2) -> (50,4) -> (50,6)
+(3,0) -> (4,0) -> (5,0) -> (6,0) -> (7,0) -> (8,0) -> (9,0) -> (10,0) -> (11,0) -> (19,0) -> (20,0) -> (21,0) -> (22,0) -> (29,0) -> (30,0) -> (31,0) -> (32,0) -> (39,0) -> (40,0) -> (41,0) -> (42,0) -> (49,0) -> (50,0)
+(4,1) -> (4,3) -> (4,5) -> (39,1) -> (39,3) -> (39,5) -> (48,1)
+(5,1) -> (5,3) -> (5,5) -> (40,1) -> (40,3) -> (40,5) -> (46,1)
+(6,1) -> (6,3) -> (6,5) -> (41,1) -> (41,3) -> (41,5) -> (44,1)
+(7,1) -> (7,3) -> (7,5) -> (42,1) -> (42,3) -> (42,5) -> (43,0)
+(8,1) -> (8,3) -> (8,5) -> (17,1) -> (19,1) -> (19,3) -> (19,5) -> (28,1) -> (29,1) -> (29,3) -> (29,5) -> (33,0) -> (49,1) -> (49,3) -> (49,5) -> (50,1) -> (50,3) -> (50,5) -> (51,0)
+(9,1) -> (9,3) -> (9,5) -> (15,1) -> (20,1) -> (20,3) -> (20,5) -> (26,1) -> (30,1) -> (30,3) -> (30,5) -> (34,1)
+(10,1) -> (10,3) -> (10,5) -> (13,1) -> (21,1) -> (21,3) -> (21,5) -> (24,1) -> (31,1) -> (31,3) -> (31,5) -> (36,1)
+(11,1) -> (11,3) -> (11,5) -> (12,0) -> (22,1) -> (22,3) -> (22,5) -> (23,0) -> (32,1) -> (32,3) -> (32,5) -> (38,1)
+(12,2) -> (13,0)
+(13,2) -> (14,0)
+(14,2) -> (15,0)
+(15,2) -> (16,0)
+(16,2) -> (17,0)
+(17,2) -> (18,0)
+(23,2) -> (24,0)
+(24,2) -> (25,0)
+(25,2) -> (26,0)
+(26,2) -> (27,0)
+(27,2) -> (28,0)
+(28,2) -> (1,0) -> (18,1)
+(33,2) -> (34,0)
+(34,2) -> (35,0)
+(35,2) -> (36,0)
+(36,2) -> (37,0)
+(37,2) -> (38,0)
+(38,2) -> (48,2)
+(43,2) -> (44,0)
+(44,2) -> (45,0)
+(45,2) -> (46,0)
+(46,2) -> (47,0)
+(47,2) -> (48,0)
+(51,2) -> (52,1)
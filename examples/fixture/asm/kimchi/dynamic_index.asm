@@ -0,0 +1,96 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,0,0,-2>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,0,0,-3>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,-1>
+(0,0) -> (49,0)
+(1,0) -> (3,0) -> (12,0) -> (22,0) -> (32,0)
+(2,0) -> (4,0)
+(3,1) -> (4,1)
+(4,2) -> (5,1) -> (9,1)
+(5,2) -> (8,0)
+(6,0) -> (9,0) -> (20,0) -> (42,0)
+(6,1) -> (7,0)
+(7,2) -> (8,1)
+(9,2) -> (10,0)
+(11,0) -> (13,0)
+(12,1) -> (13,1)
+(13,2) -> (14,1) -> (18,1)
+(14,2) -> (17,0)
+(15,0) -> (18,0) -> (20,1) -> (43,0)
+(15,1) -> (16,0)
+(16,2) -> (17,1)
+(18,2) -> (19,0)
+(20,2) -> (30,0)
+(21,0) -> (23,0)
+(22,1) -> (23,1)
+(23,2) -> (24,1) -> (28,1)
+(24,2) -> (27,0)
+(25,0) -> (28,0) -> (30,1) -> (45,0)
+(25,1) -> (26,0)
+(26,2) -> (27,1)
+(28,2) -> (29,0)
+(30,2) -> (40,0)
+(31,0) -> (33,0)
+(32,1) -> (33,1)
+(33,2) -> (34,1) -> (38,1)
+(34,2) -> (37,0)
+(35,0) -> (38,0) -> (40,1) -> (47,0)
+(35,1) -> (36,0)
+(36,2) -> (37,1)
+(38,2) -> (39,0)
+(40,2) -> (41,0)
+(42,2) -> (44,0)
+(43,2) -> (44,1)
+(44,2) -> (46,0)
+(45,2) -> (46,1)
+(46,2) -> (48,0)
+(47,2) -> (48,1)
+(48,2) -> (49,1)
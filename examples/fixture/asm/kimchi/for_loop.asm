@@ -3,7 +3,5 @@
 DoubleGeneric<1>
 DoubleGeneric<1,1,-1>
 DoubleGeneric<1,1,-1>
-DoubleGeneric<1,-1>
-(0,0) -> (3,1)
 (1,2) -> (2,0)
-(2,2) -> (3,0)
+(2,2) -> (0,0)
@@ -0,0 +1,20 @@
+@ noname.0.7.0
+
+c0 = -7237005577332262213973186563042994240840764120485390178988669191087491907584
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<c0,0,-1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<6,0,-1>
+DoubleGeneric<1,-1>
+(0,0) -> (9,0)
+(1,0) -> (2,0) -> (4,1)
+(2,1) -> (4,0) -> (8,0)
+(2,2) -> (3,0)
+(4,2) -> (5,0)
+(6,2) -> (7,0)
+(8,2) -> (9,1)
@@ -0,0 +1,151 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<5,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<5,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<5,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,0,0,-1>
+(0,0) -> (5,0) -> (6,0) -> (14,0) -> (53,0) -> (54,0)
+(1,0) -> (30,0) -> (31,0) -> (38,0)
+(2,0) -> (3,0) -> (6,1) -> (7,0) -> (22,0) -> (26,0)
+(2,2) -> (3,1)
+(3,2) -> (4,0)
+(5,2) -> (9,1)
+(6,2) -> (10,0)
+(7,1) -> (8,0)
+(8,2) -> (9,0)
+(9,2) -> (10,1)
+(10,2) -> (12,1)
+(11,0) -> (11,1)
+(11,2) -> (12,0)
+(13,0) -> (15,0) -> (39,0) -> (67,0)
+(14,1) -> (15,1)
+(15,2) -> (16,1) -> (20,1)
+(16,2) -> (19,0)
+(17,0) -> (20,0) -> (24,0)
+(17,1) -> (18,0)
+(18,2) -> (19,1)
+(20,2) -> (21,0)
+(22,1) -> (23,0)
+(23,2) -> (24,1)
+(24,2) -> (25,0)
+(27,0) -> (28,0) -> (31,1) -> (32,0) -> (46,0) -> (50,0)
+(27,2) -> (28,1)
+(28,2) -> (29,0)
+(30,2) -> (34,1)
+(31,2) -> (35,0)
+(32,1) -> (33,0)
+(33,2) -> (34,0)
+(34,2) -> (35,1)
+(35,2) -> (37,1)
+(36,0) -> (36,1)
+(36,2) -> (37,0)
+(38,1) -> (39,1)
+(39,2) -> (40,1) -> (44,1)
+(40,2) -> (43,0)
+(41,0) -> (44,0) -> (48,0)
+(41,1) -> (42,0)
+(42,2) -> (43,1)
+(44,2) -> (45,0)
+(46,1) -> (47,0)
+(47,2) -> (48,1)
+(48,2) -> (49,0)
+(50,1) -> (51,0)
+(51,2) -> (52,0)
+(53,1) -> (54,1)
+(54,2) -> (58,0) -> (59,0) -> (66,0)
+(55,0) -> (56,0) -> (59,1) -> (60,0) -> (74,0) -> (78,0)
+(55,2) -> (56,1)
+(56,2) -> (57,0)
+(58,2) -> (62,1)
+(59,2) -> (63,0)
+(60,1) -> (61,0)
+(61,2) -> (62,0)
+(62,2) -> (63,1)
+(63,2) -> (65,1)
+(64,0) -> (64,1)
+(64,2) -> (65,0)
+(66,1) -> (67,1)
+(67,2) -> (68,1) -> (72,1)
+(68,2) -> (71,0)
+(69,0) -> (72,0) -> (76,0)
+(69,1) -> (70,0)
+(70,2) -> (71,1)
+(72,2) -> (73,0)
+(74,1) -> (75,0)
+(75,2) -> (76,1)
+(76,2) -> (77,0)
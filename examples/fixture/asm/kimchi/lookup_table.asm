@@ -0,0 +1,11 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,0,0,-2>
+Lookup
+DoubleGeneric<1,-1>
+(0,0) -> (4,0)
+(1,0) -> (3,1) -> (3,3) -> (3,5)
+(2,0) -> (3,0)
+(3,2) -> (3,4) -> (3,6) -> (4,1)
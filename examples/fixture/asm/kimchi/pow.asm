@@ -0,0 +1,23 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,-1>
+(0,0) -> (10,0)
+(2,0) -> (2,1) -> (4,1)
+(2,2) -> (3,0) -> (3,1)
+(3,2) -> (4,0)
+(4,2) -> (1,0) -> (5,0) -> (5,1) -> (6,1) -> (9,1)
+(5,2) -> (6,0)
+(6,2) -> (7,0) -> (7,1)
+(7,2) -> (8,0) -> (8,1)
+(8,2) -> (9,0)
+(9,2) -> (10,1)
@@ -0,0 +1,111 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,0,0,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,0,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,0,0,0,-1>
+(0,0) -> (2,0) -> (5,0) -> (8,0) -> (12,0) -> (15,0) -> (20,0) -> (23,0) -> (26,0) -> (39,0) -> (42,0)
+(1,0) -> (19,0)
+(1,1) -> (2,1)
+(2,2) -> (3,0)
+(3,2) -> (6,0)
+(4,0) -> (22,0)
+(4,1) -> (5,1)
+(5,2) -> (6,1)
+(6,2) -> (9,0)
+(7,0) -> (25,0)
+(7,1) -> (8,1)
+(8,2) -> (9,1)
+(9,2) -> (10,0)
+(11,0) -> (38,0)
+(11,1) -> (12,1)
+(12,2) -> (13,0)
+(13,2) -> (16,0)
+(14,0) -> (41,0)
+(14,1) -> (15,1)
+(15,2) -> (16,1)
+(16,2) -> (17,0)
+(17,2) -> (18,0)
+(19,1) -> (20,1)
+(20,2) -> (21,0)
+(21,2) -> (24,0)
+(22,1) -> (23,1)
+(23,2) -> (24,1)
+(24,2) -> (27,0)
+(25,1) -> (26,1)
+(26,2) -> (27,1)
+(27,2) -> (29,0)
+(28,0) -> (30,0) -> (45,0)
+(29,1) -> (30,1)
+(30,2) -> (31,1) -> (35,1)
+(31,2) -> (34,0)
+(32,0) -> (35,0) -> (37,0)
+(32,1) -> (33,0)
+(33,2) -> (34,1)
+(35,2) -> (36,0)
+(38,1) -> (39,1)
+(39,2) -> (40,0)
+(40,2) -> (43,0)
+(41,1) -> (42,1)
+(42,2) -> (43,1)
+(43,2) -> (44,0)
+(44,1) -> (45,1)
+(45,2) -> (46,1) -> (50,1)
+(46,2) -> (49,0)
+(47,0) -> (50,0) -> (52,0)
+(47,1) -> (48,0)
+(48,2) -> (49,1)
+(50,2) -> (51,0)
+(52,1) -> (53,0)
+(53,2) -> (54,0)
@@ -0,0 +1,9 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,0,0,-2>
+Lookup
+(0,0) -> (3,1) -> (3,3) -> (3,5)
+(1,0) -> (3,2) -> (3,4) -> (3,6)
+(2,0) -> (3,0)
@@ -0,0 +1,56 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1,0,-1,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<5,0,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<1,-1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,0,-1,0,1>
+DoubleGeneric<0,0,-1,1>
+DoubleGeneric<1>
+DoubleGeneric<1,-1>
+DoubleGeneric<1,-1>
+(0,0) -> (27,0)
+(1,0) -> (28,0)
+(2,0) -> (6,0) -> (7,0) -> (15,0)
+(3,0) -> (4,0) -> (7,1) -> (8,0) -> (23,0) -> (27,1)
+(3,2) -> (4,1)
+(4,2) -> (5,0)
+(6,2) -> (10,1)
+(7,2) -> (11,0)
+(8,1) -> (9,0)
+(9,2) -> (10,0)
+(10,2) -> (11,1)
+(11,2) -> (13,1)
+(12,0) -> (12,1) -> (28,1)
+(12,2) -> (13,0)
+(14,0) -> (16,0)
+(15,1) -> (16,1)
+(16,2) -> (17,1) -> (21,1)
+(17,2) -> (20,0)
+(18,0) -> (21,0) -> (25,0)
+(18,1) -> (19,0)
+(19,2) -> (20,1)
+(21,2) -> (22,0)
+(23,1) -> (24,0)
+(24,2) -> (25,1)
+(25,2) -> (26,0)
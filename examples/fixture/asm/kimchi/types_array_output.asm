@@ -9,20 +9,16 @@ DoubleGeneric<1>
 DoubleGeneric<2,0,-1>
 DoubleGeneric<2,0,-1>
 DoubleGeneric<2,0,-1>
-DoubleGeneric<1,-1>
 DoubleGeneric<2,0,-1>
 DoubleGeneric<1,-1>
 DoubleGeneric<1,-1>
 DoubleGeneric<1,-1>
 DoubleGeneric<1,-1>
-DoubleGeneric<1,-1>
-(0,0) -> (12,0)
-(1,0) -> (13,0)
-(2,0) -> (14,0)
-(3,0) -> (15,0)
-(4,0) -> (6,0) -> (8,0) -> (14,1)
-(5,0) -> (7,0) -> (10,0) -> (13,1)
-(6,2) -> (9,1) -> (12,1)
-(7,2) -> (11,1) -> (15,1)
-(8,2) -> (9,0)
-(10,2) -> (11,0)
+(0,0) -> (10,0)
+(1,0) -> (11,0)
+(2,0) -> (12,0)
+(3,0) -> (13,0)
+(4,0) -> (6,0) -> (8,0) -> (12,1)
+(5,0) -> (7,0) -> (9,0) -> (11,1)
+(8,2) -> (6,2) -> (10,1)
+(9,2) -> (7,2) -> (13,1)
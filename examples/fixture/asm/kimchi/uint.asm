@@ -0,0 +1,27 @@
+@ noname.0.7.0
+
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1>
+Xor16
+Xor16
+Xor16
+Xor16
+Zero
+Xor16
+Xor16
+Xor16
+Xor16
+Zero
+DoubleGeneric<1,1,-1>
+Xor16
+Xor16
+Xor16
+Xor16
+Zero
+DoubleGeneric<1,-1>
+(0,0) -> (19,0)
+(1,0) -> (3,0) -> (13,0)
+(2,0) -> (3,1) -> (7,0) -> (7,1) -> (7,2) -> (8,1) -> (12,0) -> (12,1) -> (12,2) -> (14,1) -> (18,0) -> (18,1) -> (18,2)
+(8,0) -> (13,1)
+(13,2) -> (14,0) -> (19,1)
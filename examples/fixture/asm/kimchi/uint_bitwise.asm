@@ -0,0 +1,47 @@
+@ noname.0.7.0
+
+c0 = -14474011154664524427946373126085988481681528240970780357977338382174983815168
+c1 = -2694881440
+c2 = -4210752250
+DoubleGeneric<1>
+DoubleGeneric<1>
+DoubleGeneric<1>
+Xor16
+Xor16
+Zero
+Xor16
+Xor16
+Zero
+Xor16
+Xor16
+Zero
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<c0,0,-1>
+DoubleGeneric<1,0,0,0,c1>
+Xor16
+Xor16
+Zero
+DoubleGeneric<1,1,-1>
+DoubleGeneric<1,1,-1>
+DoubleGeneric<c0,0,-1>
+DoubleGeneric<1,0,0,0,c2>
+Xor16
+Xor16
+Zero
+DoubleGeneric<1,-1>
+(0,0) -> (27,0)
+(1,0) -> (3,0) -> (9,0) -> (12,0) -> (17,0) -> (20,0) -> (24,0)
+(2,0) -> (3,1) -> (5,0) -> (5,1) -> (5,2) -> (6,1) -> (8,0) -> (8,1) -> (8,2) -> (11,0) -> (11,1) -> (11,2) -> (19,0) -> (19,1) -> (19,2) -> (26,0) -> (26,1) -> (26,2)
+(6,0) -> (9,1) -> (12,1) -> (17,1) -> (20,1) -> (24,1)
+(9,2) -> (13,0)
+(12,2) -> (14,0)
+(13,1) -> (14,1)
+(14,2) -> (15,0)
+(15,2) -> (16,0)
+(17,2) -> (21,1)
+(20,2) -> (21,0)
+(21,2) -> (22,0)
+(22,2) -> (23,0)
+(24,2) -> (27,1)
@@ -0,0 +1,32 @@
+@ noname.0.7.0
+
+v_4 == (v_1) * (v_1 + -1)
+0 == (v_4) * (1)
+v_5 == (v_2) * (v_2 + -1)
+0 == (v_5) * (1)
+v_6 == (v_3) * (v_3 + -1)
+0 == (v_6) * (1)
+v_10 == (v_7) * (v_7 + -1)
+0 == (v_10) * (1)
+v_11 == (v_8) * (v_8 + -1)
+0 == (v_11) * (1)
+v_12 == (v_9) * (v_9 + -1)
+0 == (v_12) * (1)
+3 == (v_13) * (1)
+v_15 == (v_14) * (-1 * v_1 + -1 * v_2 + -1 * v_3 + v_13)
+-1 * v_16 + 1 == (v_15) * (1)
+v_17 == (v_16) * (-1 * v_1 + -1 * v_2 + -1 * v_3 + v_13)
+0 == (v_17) * (1)
+1 == (v_16) * (1)
+0 == (v_18) * (1)
+v_20 == (v_19) * (-1 * v_7 + -1 * v_8 + -1 * v_9 + v_18)
+-1 * v_21 + 1 == (v_20) * (1)
+v_22 == (v_21) * (-1 * v_7 + -1 * v_8 + -1 * v_9 + v_18)
+0 == (v_22) * (1)
+1 == (-1 * v_21 + 1) * (1)
+3 == (v_23) * (1)
+v_25 == (v_24) * (-1 * v_7 + -1 * v_8 + -1 * v_9 + v_23)
+-1 * v_26 + 1 == (v_25) * (1)
+v_27 == (v_26) * (-1 * v_7 + -1 * v_8 + -1 * v_9 + v_23)
+0 == (v_27) * (1)
+1 == (-1 * v_26 + 1) * (1)
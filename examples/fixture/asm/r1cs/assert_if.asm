@@ -0,0 +1,7 @@
+@ noname.0.7.0
+
+v_3 == (v_1) * (v_1 + -1)
+0 == (v_3) * (1)
+v_5 == (v_1) * (v_2 + -1 * v_4)
+0 == (v_5) * (1)
+v_2 + 1 == (v_2 + 1) * (1)
@@ -0,0 +1,18 @@
+@ noname.0.7.0
+
+v_2 == (v_1) * (v_1 + -1)
+0 == (v_2) * (1)
+v_4 == (v_3) * (v_3 + -1)
+0 == (v_4) * (1)
+v_5 == (v_1) * (v_3)
+1 == (-1 * v_5 + 1) * (1)
+v_6 == (v_1) * (v_3)
+1 == (v_1 + v_3 + -1 * v_6) * (1)
+v_7 == (v_1) * (v_3)
+1 == (v_1 + v_3 + -2 * v_7) * (1)
+v_8 == (v_1) * (v_3)
+v_9 == (v_3) * (v_3)
+v_10 == (-1 * v_8 + 1) * (-2 * v_3 + v_9 + 1)
+1 == (v_10) * (1)
+v_11 == (v_1) * (v_1)
+1 == (-2 * v_1 + 2 * v_11 + 1) * (1)
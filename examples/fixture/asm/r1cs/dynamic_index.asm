@@ -0,0 +1,28 @@
+@ noname.0.7.0
+
+0 == (v_7) * (1)
+v_9 == (v_8) * (-1 * v_2 + v_7)
+-1 * v_10 + 1 == (v_9) * (1)
+v_11 == (v_10) * (-1 * v_2 + v_7)
+0 == (v_11) * (1)
+1 == (v_12) * (1)
+v_14 == (v_13) * (-1 * v_2 + v_12)
+-1 * v_15 + 1 == (v_14) * (1)
+v_16 == (v_15) * (-1 * v_2 + v_12)
+0 == (v_16) * (1)
+2 == (v_17) * (1)
+v_19 == (v_18) * (-1 * v_2 + v_17)
+-1 * v_20 + 1 == (v_19) * (1)
+v_21 == (v_20) * (-1 * v_2 + v_17)
+0 == (v_21) * (1)
+3 == (v_22) * (1)
+v_24 == (v_23) * (-1 * v_2 + v_22)
+-1 * v_25 + 1 == (v_24) * (1)
+v_26 == (v_25) * (-1 * v_2 + v_22)
+0 == (v_26) * (1)
+1 == (v_10 + v_15 + v_20 + v_25) * (1)
+v_27 == (v_10) * (v_3)
+v_28 == (v_15) * (v_4)
+v_29 == (v_20) * (v_5)
+v_30 == (v_25) * (v_6)
+v_27 + v_28 + v_29 + v_30 == (v_1) * (1)
@@ -0,0 +1,8 @@
+@ noname.0.7.0
+
+v_5 == (v_2) * (v_4)
+1 == (v_5) * (1)
+v_6 == (v_4) * (v_2)
+1 == (v_6) * (1)
+1 == (-13108968793781547619861935127046491459422638125131909455650914674984645296128 * v_3) * (1)
+6 * v_4 == (v_1) * (1)
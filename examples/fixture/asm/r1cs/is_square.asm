@@ -0,0 +1,44 @@
+@ noname.0.7.0
+
+v_4 == (v_3) * (v_3 + -1)
+0 == (v_4) * (1)
+v_5 == (v_1) * (v_3)
+v_6 == (-1 * v_3 + 1) * (5 * v_1)
+v_8 == (v_7) * (v_7)
+v_5 + v_6 == (v_8) * (1)
+0 == (v_9) * (1)
+v_11 == (v_10) * (-1 * v_1 + v_9)
+-1 * v_12 + 1 == (v_11) * (1)
+v_13 == (v_12) * (-1 * v_1 + v_9)
+0 == (v_13) * (1)
+v_14 == (v_12) * (-1 * v_3 + 1)
+0 == (v_14) * (1)
+1 == (v_3) * (1)
+v_16 == (v_15) * (v_15 + -1)
+0 == (v_16) * (1)
+v_17 == (v_2) * (v_15)
+v_18 == (-1 * v_15 + 1) * (5 * v_2)
+v_20 == (v_19) * (v_19)
+v_17 + v_18 == (v_20) * (1)
+0 == (v_21) * (1)
+v_23 == (v_22) * (-1 * v_2 + v_21)
+-1 * v_24 + 1 == (v_23) * (1)
+v_25 == (v_24) * (-1 * v_2 + v_21)
+0 == (v_25) * (1)
+v_26 == (v_24) * (-1 * v_15 + 1)
+0 == (v_26) * (1)
+1 == (-1 * v_15 + 1) * (1)
+v_28 == (v_27) * (v_27 + -1)
+0 == (v_28) * (1)
+v_29 == (0 * v_1) * (v_27)
+v_30 == (-1 * v_27 + 1) * (0 * v_1)
+v_32 == (v_31) * (v_31)
+v_29 + v_30 == (v_32) * (1)
+0 == (v_33) * (1)
+v_35 == (v_34) * (0 * v_1 + v_33)
+-1 * v_36 + 1 == (v_35) * (1)
+v_37 == (v_36) * (0 * v_1 + v_33)
+0 == (v_37) * (1)
+v_38 == (v_36) * (-1 * v_27 + 1)
+0 == (v_38) * (1)
+1 == (v_27) * (1)
@@ -1,5 +1,3 @@
 @ noname.0.7.0
 
 42 == (v_1) * (1)
-42 == (v_1) * (1)
-42 == (v_1) * (1)
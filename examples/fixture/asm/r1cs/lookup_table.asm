@@ -0,0 +1,44 @@
+@ noname.0.7.0
+
+0 == (v_3) * (1)
+v_5 == (v_4) * (-1 * v_2 + v_3)
+-1 * v_6 + 1 == (v_5) * (1)
+v_7 == (v_6) * (-1 * v_2 + v_3)
+0 == (v_7) * (1)
+1 == (v_8) * (1)
+v_10 == (v_9) * (-1 * v_2 + v_8)
+-1 * v_11 + 1 == (v_10) * (1)
+v_12 == (v_11) * (-1 * v_2 + v_8)
+0 == (v_12) * (1)
+2 == (v_13) * (1)
+v_15 == (v_14) * (-1 * v_2 + v_13)
+-1 * v_16 + 1 == (v_15) * (1)
+v_17 == (v_16) * (-1 * v_2 + v_13)
+0 == (v_17) * (1)
+3 == (v_18) * (1)
+v_20 == (v_19) * (-1 * v_2 + v_18)
+-1 * v_21 + 1 == (v_20) * (1)
+v_22 == (v_21) * (-1 * v_2 + v_18)
+0 == (v_22) * (1)
+4 == (v_23) * (1)
+v_25 == (v_24) * (-1 * v_2 + v_23)
+-1 * v_26 + 1 == (v_25) * (1)
+v_27 == (v_26) * (-1 * v_2 + v_23)
+0 == (v_27) * (1)
+5 == (v_28) * (1)
+v_30 == (v_29) * (-1 * v_2 + v_28)
+-1 * v_31 + 1 == (v_30) * (1)
+v_32 == (v_31) * (-1 * v_2 + v_28)
+0 == (v_32) * (1)
+6 == (v_33) * (1)
+v_35 == (v_34) * (-1 * v_2 + v_33)
+-1 * v_36 + 1 == (v_35) * (1)
+v_37 == (v_36) * (-1 * v_2 + v_33)
+0 == (v_37) * (1)
+7 == (v_38) * (1)
+v_40 == (v_39) * (-1 * v_2 + v_38)
+-1 * v_41 + 1 == (v_40) * (1)
+v_42 == (v_41) * (-1 * v_2 + v_38)
+0 == (v_42) * (1)
+1 == (v_6 + v_11 + v_16 + v_21 + v_26 + v_31 + v_36 + v_41) * (1)
+5 * v_6 + 12 * v_11 + 31 * v_16 + 69 * v_21 + 128 * v_26 + 187 * v_31 + 225 * v_36 + 244 * v_41 == (v_1) * (1)
@@ -1,6 +1,5 @@
 @ noname.0.7.0
 
-2 == (v_1) * (1)
 2 == (v_1) * (1)
 5 == (v_1 + v_2) * (1)
 10 == (2 * v_1 + 2 * v_2) * (1)
@@ -0,0 +1,13 @@
+@ noname.0.7.0
+
+v_2 == (v_2) * (1)
+v_4 == (v_3) * (v_3)
+v_5 == (v_4) * (v_4)
+v_6 == (v_5) * (v_3)
+v_2 == (v_6) * (1)
+v_7 == (v_2) * (v_2)
+v_8 == (v_7) * (v_2)
+v_9 == (v_8) * (v_8)
+v_10 == (v_9) * (v_9)
+v_11 == (v_10) * (v_2)
+v_11 == (v_1) * (1)
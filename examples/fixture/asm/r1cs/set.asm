@@ -0,0 +1,23 @@
+@ noname.0.7.0
+
+v_7 == (v_1 + -1 * v_2) * (v_1 + -1 * v_3)
+v_8 == (v_7) * (v_1 + -1 * v_4)
+0 == (v_8) * (1)
+v_9 == (v_1 + -1 * v_5) * (v_1 + -1 * v_6)
+v_11 == (v_9) * (v_10)
+1 == (v_11) * (1)
+v_12 == (v_1 + -1 * v_2) * (v_1 + -1 * v_3)
+v_13 == (v_12) * (v_1 + -1 * v_4)
+0 == (v_14) * (1)
+v_16 == (v_15) * (-1 * v_13 + v_14)
+-1 * v_17 + 1 == (v_16) * (1)
+v_18 == (v_17) * (-1 * v_13 + v_14)
+0 == (v_18) * (1)
+1 == (v_17) * (1)
+v_19 == (v_1 + -1 * v_5) * (v_1 + -1 * v_6)
+0 == (v_20) * (1)
+v_22 == (v_21) * (-1 * v_19 + v_20)
+-1 * v_23 + 1 == (v_22) * (1)
+v_24 == (v_23) * (-1 * v_19 + v_20)
+0 == (v_24) * (1)
+1 == (-1 * v_23 + 1) * (1)
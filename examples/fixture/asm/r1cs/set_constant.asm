@@ -0,0 +1,6 @@
+@ noname.0.7.0
+
+v_2 == (v_1 + -3) * (v_1 + -5)
+v_3 == (v_2) * (v_1 + -7)
+v_4 == (v_3) * (v_1 + -5)
+0 == (v_4) * (1)
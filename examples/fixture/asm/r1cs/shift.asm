@@ -0,0 +1,42 @@
+@ noname.0.7.0
+
+v_7 == (v_6) * (v_6 + -1)
+0 == (v_7) * (1)
+v_9 == (v_8) * (v_8 + -1)
+0 == (v_9) * (1)
+v_11 == (v_10) * (v_10 + -1)
+0 == (v_11) * (1)
+v_13 == (v_12) * (v_12 + -1)
+0 == (v_13) * (1)
+v_5 == (v_6 + 2 * v_8 + 4 * v_10 + 8 * v_12) * (1)
+v_15 == (v_14) * (v_14 + -1)
+0 == (v_15) * (1)
+v_17 == (v_16) * (v_16 + -1)
+0 == (v_17) * (1)
+v_19 == (v_18) * (v_18 + -1)
+0 == (v_19) * (1)
+v_21 == (v_20) * (v_20 + -1)
+0 == (v_21) * (1)
+v_5 == (v_14 + 2 * v_16 + 4 * v_18 + 8 * v_20) * (1)
+v_23 == (v_22) * (v_22 + -1)
+0 == (v_23) * (1)
+v_25 == (v_24) * (v_24 + -1)
+0 == (v_25) * (1)
+v_27 == (v_26) * (v_26 + -1)
+0 == (v_27) * (1)
+v_29 == (v_28) * (v_28 + -1)
+0 == (v_29) * (1)
+v_5 == (v_22 + 2 * v_24 + 4 * v_26 + 8 * v_28) * (1)
+v_31 == (v_30) * (v_30 + -1)
+0 == (v_31) * (1)
+v_33 == (v_32) * (v_32 + -1)
+0 == (v_33) * (1)
+v_35 == (v_34) * (v_34 + -1)
+0 == (v_35) * (1)
+v_37 == (v_36) * (v_36 + -1)
+0 == (v_37) * (1)
+v_5 == (v_30 + 2 * v_32 + 4 * v_34 + 8 * v_36) * (1)
+2 * v_6 + 4 * v_8 + 8 * v_10 == (v_1) * (1)
+v_16 + 2 * v_18 + 4 * v_20 == (v_2) * (1)
+2 * v_22 + 4 * v_24 + 8 * v_26 + v_28 == (v_3) * (1)
+8 * v_30 + v_32 + 2 * v_34 + 4 * v_36 == (v_4) * (1)
@@ -0,0 +1,5 @@
+@ noname.0.7.0
+
+v_4 == (v_3) * (v_3)
+v_2 == (v_4) * (1)
+v_3 == (v_1) * (1)
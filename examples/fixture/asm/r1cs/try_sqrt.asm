@@ -0,0 +1,17 @@
+@ noname.0.7.0
+
+v_5 == (v_4) * (v_4 + -1)
+0 == (v_5) * (1)
+v_6 == (v_3) * (v_4)
+v_7 == (-1 * v_4 + 1) * (5 * v_3)
+v_9 == (v_8) * (v_8)
+v_6 + v_7 == (v_9) * (1)
+0 == (v_10) * (1)
+v_12 == (v_11) * (-1 * v_3 + v_10)
+-1 * v_13 + 1 == (v_12) * (1)
+v_14 == (v_13) * (-1 * v_3 + v_10)
+0 == (v_14) * (1)
+v_15 == (v_13) * (-1 * v_4 + 1)
+0 == (v_15) * (1)
+v_4 == (v_1) * (1)
+v_8 == (v_2) * (1)
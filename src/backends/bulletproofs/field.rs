@@ -0,0 +1,138 @@
+//! The scalar field of ristretto255 (the order `l = 2^252 + 27742317777372353535851937790883648493` of the curve25519 subgroup) for arkworks,
+//! so that circuits can be written on it (with the R1CS backend).
+
+use ark_ff::{biginteger::BigInteger256 as BigInteger, fields::*};
+
+use crate::backends::BackendField;
+
+pub type R1csRistrettoField = Fp256<RistrettoParameters>;
+
+impl BackendField for R1csRistrettoField {}
+
+pub struct RistrettoParameters;
+
+impl Fp256Parameters for RistrettoParameters {}
+
+impl FftParameters for RistrettoParameters {
+    type BigInt = BigInteger;
+
+    const TWO_ADICITY: u32 = 2;
+
+    /// TWO_ADIC_ROOT_OF_UNITY = GENERATOR^T, in Montgomery form
+    #[rustfmt::skip]
+    const TWO_ADIC_ROOT_OF_UNITY: BigInteger = BigInteger([
+        0x7c790e32b42f0e7d,
+        0x4c8ce706a7ae2cc8,
+        0xd73823cc921779ad,
+        0x05599959893f562a,
+    ]);
+}
+
+impl FpParameters for RistrettoParameters {
+    /// MODULUS = 2^252 + 27742317777372353535851937790883648493
+    #[rustfmt::skip]
+    const MODULUS: BigInteger = BigInteger([
+        0x5812631a5cf5d3ed,
+        0x14def9dea2f79cd6,
+        0x0000000000000000,
+        0x1000000000000000,
+    ]);
+
+    const MODULUS_BITS: u32 = 253;
+
+    const CAPACITY: u32 = Self::MODULUS_BITS - 1;
+
+    const REPR_SHAVE_BITS: u32 = 3;
+
+    /// R = pow(2, 256) % MODULUS
+    #[rustfmt::skip]
+    const R: BigInteger = BigInteger([
+        0xd6ec31748d98951d,
+        0xc6ef5bf4737dcf70,
+        0xfffffffffffffffe,
+        0x0fffffffffffffff,
+    ]);
+
+    /// R2 = R * R % MODULUS
+    #[rustfmt::skip]
+    const R2: BigInteger = BigInteger([
+        0xa40611e3449c0f01,
+        0xd00e1ba768859347,
+        0xceec73d217f5be65,
+        0x0399411b7c309a3d,
+    ]);
+
+    /// INV = (-MODULUS) ^ {-1} % pow(2, 64)
+    const INV: u64 = 0xd2b51da312547e1b;
+
+    /// GENERATOR = 2, in Montgomery form
+    #[rustfmt::skip]
+    const GENERATOR: BigInteger = BigInteger([
+        0x55c5ffcebe3b564d,
+        0x78ffbe0a4404020b,
+        0xfffffffffffffffd,
+        0x0fffffffffffffff,
+    ]);
+
+    /// (MODULUS - 1)/2
+    #[rustfmt::skip]
+    const MODULUS_MINUS_ONE_DIV_TWO: BigInteger = BigInteger([
+        0x2c09318d2e7ae9f6,
+        0x0a6f7cef517bce6b,
+        0x0000000000000000,
+        0x0800000000000000,
+    ]);
+
+    // T and T_MINUS_ONE_DIV_TWO, where r - 1 = 2^s * t
+
+    /// T = (MODULUS - 1) / 2^s
+    #[rustfmt::skip]
+    const T: BigInteger = BigInteger([
+        0x960498c6973d74fb,
+        0x0537be77a8bde735,
+        0x0000000000000000,
+        0x0400000000000000,
+    ]);
+
+    /// (T - 1) / 2
+    #[rustfmt::skip]
+    const T_MINUS_ONE_DIV_TWO: BigInteger = BigInteger([
+        0xcb024c634b9eba7d,
+        0x029bdf3bd45ef39a,
+        0x0000000000000000,
+        0x0200000000000000,
+    ]);
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{FftField, Field, One, SquareRootField};
+    use num_bigint::BigUint;
+
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let l = (BigUint::from(1u64) << 252)
+            + BigUint::parse_bytes(b"27742317777372353535851937790883648493", 10).unwrap();
+        let l_minus_one: BigUint = (-R1csRistrettoField::one()).into();
+        assert_eq!(l_minus_one + 1u64, l);
+
+        let a = R1csRistrettoField::from(123456789u64);
+        assert_eq!(a * a.inverse().unwrap(), R1csRistrettoField::one());
+
+        // the generator has order l - 1, and the root of unity has order 4
+        let generator = R1csRistrettoField::multiplicative_generator();
+        assert_eq!(generator, R1csRistrettoField::from(2u64));
+        let root = R1csRistrettoField::two_adic_root_of_unity();
+        assert_eq!(root.square(), -R1csRistrettoField::one());
+
+        // square roots
+        let square = a.square();
+        assert_eq!(square.sqrt().unwrap().square(), square);
+        assert!(generator.sqrt().is_none());
+
+        // literals that don't fit are rejected
+        assert!(R1csRistrettoField::try_from(l).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Bulletproofs (https://eprint.iacr.org/2017/1066) for circuits written with the R1CS backend over the scalar field of ristretto255
+//! (see [field::R1csRistrettoField]).
+//!
+//! Bulletproofs need no setup at all (the generators are derived by hashing), at the cost of proofs that are slower to verify
+//! (linear in the size of the circuit): they are best suited to short range or arithmetic statements.
+//!
+//! The private variables of the circuit are allocated as wires of the Bulletproofs constraint system,
+//! while the public inputs and outputs are known to the verifier and appear as constants in the linear combinations.
+//! Each constraint `a * b = c` becomes a multiplication gate `a * b = o`, and the linear constraint `o - c = 0`.
+
+pub mod field;
+
+use ark_ff::{BigInteger, PrimeField as _};
+use bulletproofs::{
+    r1cs::{
+        ConstraintSystem, LinearCombination as BulletproofsLinearCombination, Prover, R1CSError,
+        R1CSProof, Variable, Verifier,
+    },
+    BulletproofGens, PedersenGens,
+};
+use curve25519_dalek_ng::scalar::Scalar;
+use merlin::Transcript;
+
+use crate::{
+    backends::r1cs::{LinearCombination, R1CS},
+    compiler::{generate_witness, Sources},
+    inputs::JsonInputs,
+    witness::CompiledCircuit,
+};
+
+use self::field::R1csRistrettoField;
+
+/// The label of the transcripts of the proofs.
+const TRANSCRIPT_LABEL: &[u8] = b"noname bulletproofs";
+
+//
+// Data Structures
+//
+
+pub struct ProverIndex {
+    pc_gens: PedersenGens,
+    bp_gens: BulletproofGens,
+    compiled_circuit: CompiledCircuit<R1CS<R1csRistrettoField>>,
+}
+
+pub struct VerifierIndex {
+    pc_gens: PedersenGens,
+    bp_gens: BulletproofGens,
+
+    /// The constraints of the circuit, which the verifier replays.
+    r1cs: R1CS<R1csRistrettoField>,
+}
+
+/// Converts a field element from arkworks to dalek.
+fn to_scalar(value: &R1csRistrettoField) -> Scalar {
+    let bytes: [u8; 32] = value
+        .into_repr()
+        .to_bytes_le()
+        .try_into()
+        .expect("the field has 32-byte elements");
+    Scalar::from_bytes_mod_order(bytes)
+}
+
+/// A variable of the circuit, from the point of view of the Bulletproofs constraint system.
+#[derive(Clone, Copy)]
+enum Wire {
+    /// The constant 1, or a public input or output: a value known to the verifier.
+    Known(Scalar),
+
+    /// A private variable.
+    Allocated(Variable),
+}
+
+/// Adds the constraints of the circuit to the constraint system of the prover (with a witness) or the verifier (without).
+fn synthesize<CS: ConstraintSystem>(
+    cs: &mut CS,
+    r1cs: &R1CS<R1csRistrettoField>,
+    full_public_inputs: &[R1csRistrettoField],
+    witness: Option<&[R1csRistrettoField]>,
+) -> Result<(), R1CSError> {
+    // the first var is always 1, and the public vars are known to the verifier
+    let mut wires: Vec<Option<Wire>> = vec![None; r1cs.num_vars()];
+    wires[0] = Some(Wire::Known(Scalar::one()));
+    for (index, value) in r1cs
+        .public_var_indices()
+        .into_iter()
+        .zip(full_public_inputs)
+    {
+        wires[index] = Some(Wire::Known(to_scalar(value)));
+    }
+
+    for (index, wire) in wires.iter_mut().enumerate() {
+        if wire.is_none() {
+            let value = witness.map(|witness| to_scalar(&witness[index]));
+            *wire = Some(Wire::Allocated(cs.allocate(value)?));
+        }
+    }
+    let wires: Vec<_> = wires.into_iter().map(Option::unwrap).collect();
+
+    let to_lc = |lc: &LinearCombination<R1csRistrettoField>| {
+        let mut constant = to_scalar(&lc.constant);
+        let mut terms = vec![];
+        for (var, factor) in &lc.terms {
+            match wires[var.index] {
+                Wire::Known(value) => constant += to_scalar(factor) * value,
+                Wire::Allocated(variable) => terms.push((variable, to_scalar(factor))),
+            }
+        }
+        terms.push((Variable::One(), constant));
+        terms.into_iter().collect::<BulletproofsLinearCombination>()
+    };
+
+    for constraint in r1cs.constraints() {
+        let (_, _, output) = cs.multiply(to_lc(&constraint.a), to_lc(&constraint.b));
+        cs.constrain(output - to_lc(&constraint.c));
+    }
+
+    Ok(())
+}
+
+/// Returns a transcript bound to the public inputs of the verifier.
+fn transcript(full_public_inputs: &[R1csRistrettoField]) -> Transcript {
+    let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+    for value in full_public_inputs {
+        transcript.append_message(b"public input", to_scalar(value).as_bytes());
+    }
+    transcript
+}
+
+//
+// Setup
+//
+
+impl CompiledCircuit<R1CS<R1csRistrettoField>> {
+    /// Derives the generators needed by the circuit (there is no trusted setup).
+    pub fn compile_to_bulletproofs_indexes(self) -> miette::Result<(ProverIndex, VerifierIndex)> {
+        // each private var takes half a multiplication gate, and each constraint a full one
+        let r1cs = &self.circuit.backend;
+        let num_gates = r1cs.num_vars() + r1cs.constraints().len();
+        let capacity = num_gates.next_power_of_two();
+
+        let verifier_index = VerifierIndex {
+            pc_gens: PedersenGens::default(),
+            bp_gens: BulletproofGens::new(capacity, 1),
+            r1cs: r1cs.clone(),
+        };
+        let prover_index = ProverIndex {
+            pc_gens: PedersenGens::default(),
+            bp_gens: BulletproofGens::new(capacity, 1),
+            compiled_circuit: self,
+        };
+
+        Ok((prover_index, verifier_index))
+    }
+}
+
+//
+// Proving
+//
+
+impl ProverIndex {
+    /// returns a proof, the public inputs of the verifier (the public outputs, then the public inputs), and the public output
+    pub fn prove(
+        &self,
+        sources: &Sources,
+        public_inputs: JsonInputs,
+        private_inputs: JsonInputs,
+    ) -> miette::Result<(R1CSProof, Vec<R1csRistrettoField>, Vec<R1csRistrettoField>)> {
+        // generate the witness (this also checks the constraints)
+        let generated_witness = generate_witness(
+            &self.compiled_circuit,
+            sources,
+            public_inputs,
+            private_inputs,
+        )?;
+        let witness = &generated_witness.witness;
+
+        let r1cs = &self.compiled_circuit.circuit.backend;
+        let full_public_inputs: Vec<_> = r1cs
+            .public_var_indices()
+            .into_iter()
+            .map(|index| witness[index])
+            .collect();
+
+        // create proof
+        let mut transcript = transcript(&full_public_inputs);
+        let mut prover = Prover::new(&self.pc_gens, &mut transcript);
+        let proof = synthesize(&mut prover, r1cs, &full_public_inputs, Some(witness))
+            .and_then(|_| prover.prove(&self.bp_gens))
+            .map_err(|err| {
+                miette::miette!(
+                    "bulletproofs: could not create a proof with the given inputs: {err}"
+                )
+            })?;
+
+        Ok((proof, full_public_inputs, generated_witness.outputs))
+    }
+}
+
+//
+// Verifying
+//
+
+impl VerifierIndex {
+    /// Verifies a proof, given the public outputs followed by the public inputs.
+    pub fn verify(
+        &self,
+        full_public_inputs: &[R1csRistrettoField],
+        proof: &R1CSProof,
+    ) -> miette::Result<()> {
+        let r1cs = &self.r1cs;
+        if full_public_inputs.len() != r1cs.public_var_indices().len() {
+            miette::bail!("bulletproofs: wrong number of public inputs");
+        }
+
+        let mut transcript = transcript(full_public_inputs);
+        let mut verifier = Verifier::new(&mut transcript);
+        synthesize(&mut verifier, r1cs, full_public_inputs, None)
+            .and_then(|_| verifier.verify(proof, &self.pc_gens, &self.bp_gens))
+            .map_err(|err| miette::miette!("bulletproofs: failed to verify the proof: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS, WRONG_PRIVATE_INPUTS},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_prove_and_verify() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csRistrettoField>::new())?;
+        let (prover_index, verifier_index) = compiled_circuit.compile_to_bulletproofs_indexes()?;
+
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(PRIVATE_INPUTS)?;
+        let (proof, full_public_inputs, public_output) =
+            prover_index.prove(&sources, public_inputs, private_inputs)?;
+        assert_eq!(public_output, vec![R1csRistrettoField::from(8u64)]);
+        assert_eq!(
+            full_public_inputs,
+            vec![
+                R1csRistrettoField::from(8u64),
+                R1csRistrettoField::from(2u64)
+            ]
+        );
+        verifier_index.verify(&full_public_inputs, &proof)?;
+
+        // a wrong public output is rejected
+        let wrong_inputs = [
+            R1csRistrettoField::from(9u64),
+            R1csRistrettoField::from(2u64),
+        ];
+        assert!(verifier_index.verify(&wrong_inputs, &proof).is_err());
+
+        // so is a tampered public input
+        let tampered_inputs = [
+            R1csRistrettoField::from(8u64),
+            R1csRistrettoField::from(3u64),
+        ];
+        assert!(verifier_index.verify(&tampered_inputs, &proof).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_wrong_witness() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csRistrettoField>::new())?;
+        let (prover_index, _) = compiled_circuit.compile_to_bulletproofs_indexes()?;
+
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(WRONG_PRIVATE_INPUTS)?;
+        assert!(prover_index
+            .prove(&sources, public_inputs, private_inputs)
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,365 @@
+//! The phase 2 of a trusted setup ceremony for Groth16 (https://eprint.iacr.org/2017/1050, as in snarkjs and phase2-bn254),
+//! so that the circuit-specific toxic waste `δ` is safe as long as one contributor discards their randomness.
+//!
+//! The ceremony starts from the proving key given by the powers of tau of phase 1 with `γ = δ = 1` (see [Ceremony::new]),
+//! which anyone can recompute. Each contribution multiplies `δ` by a secret `δ'` (and divides the H and L queries by `δ'`),
+//! and comes with a proof of knowledge of `δ'`: `[s]1` and `[s·δ']1` for a random `s`,
+//! and `[r·δ']2` for a point `r` derived from the transcript, so that contributions are chained.
+//! The hash of each contribution is printed to the contributor, who can then check that it appears in the transcript.
+
+use ark_bn254::{Bn254, Fq2, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_groth16::ProvingKey;
+use camino::Utf8Path;
+use miette::{Context, IntoDiagnostic};
+use rand::{rngs::OsRng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+
+use super::{to_bytes, ProverIndex, VerifierIndex};
+use crate::{
+    backends::r1cs::{R1csBn254Field, R1CS},
+    serialization::SerdeAs,
+    witness::CompiledCircuit,
+};
+
+/// The state of a ceremony: the current proving key, and the transcript of the contributions.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct Ceremony {
+    /// The hash of the initial proving key.
+    initial_hash: [u8; 32],
+
+    #[serde_as(as = "SerdeAs")]
+    proving_key: ProvingKey<Bn254>,
+
+    contributions: Vec<Contribution>,
+}
+
+/// A contribution, with the proof of knowledge of its secret `δ'`.
+#[serde_as]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Contribution {
+    /// `[δ]1` after the contribution.
+    #[serde_as(as = "SerdeAs")]
+    pub delta_g1: G1Affine,
+
+    /// `[δ]2` after the contribution.
+    #[serde_as(as = "SerdeAs")]
+    pub delta_g2: G2Affine,
+
+    /// A random point `[s]1`.
+    #[serde_as(as = "SerdeAs")]
+    pub s: G1Affine,
+
+    /// `[s·δ']1`.
+    #[serde_as(as = "SerdeAs")]
+    pub s_delta: G1Affine,
+
+    /// `[r·δ']2`, where `r` is derived from the previous hash, `s` and `s_delta`.
+    #[serde_as(as = "SerdeAs")]
+    pub r_delta: G2Affine,
+
+    /// The hash of the transcript after the contribution.
+    pub hash: [u8; 32],
+}
+
+pub fn to_hex(hash: &[u8; 32]) -> String {
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Derives a point of G2 from a transcript, with a try-and-increment on the x coordinate.
+fn hash_to_g2(bytes: &[u8]) -> G2Affine {
+    let mut rng = ChaCha20Rng::from_seed(Sha256::digest(bytes).into());
+    loop {
+        let x = Fq2::rand(&mut rng);
+        if let Some(point) = G2Affine::get_point_from_x(x, rng.gen()) {
+            let point = point.mul_by_cofactor();
+            if !point.is_zero() {
+                return point;
+            }
+        }
+    }
+}
+
+/// Returns `r`, the point of G2 that a contribution proves knowledge of `δ'` with.
+fn contribution_point(previous_hash: &[u8; 32], s: &G1Affine, s_delta: &G1Affine) -> G2Affine {
+    let mut transcript = previous_hash.to_vec();
+    transcript.extend(to_bytes(s));
+    transcript.extend(to_bytes(s_delta));
+    hash_to_g2(&transcript)
+}
+
+impl Contribution {
+    fn compute_hash(&self, previous_hash: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(previous_hash);
+        hasher.update(to_bytes(&self.delta_g1));
+        hasher.update(to_bytes(&self.delta_g2));
+        hasher.update(to_bytes(&self.s));
+        hasher.update(to_bytes(&self.s_delta));
+        hasher.update(to_bytes(&self.r_delta));
+        hasher.finalize().into()
+    }
+}
+
+/// Checks that `a2 / a1 = b2 / b1`, as the ratios of two pairs of points of G1 and G2.
+fn same_ratio(g1: (G1Affine, G1Affine), g2: (G2Affine, G2Affine)) -> bool {
+    Bn254::pairing(g1.0, g2.1) == Bn254::pairing(g1.1, g2.0)
+}
+
+/// Returns a random linear combination of the points of `before` and `after`, with the same coefficients.
+fn combine(before: &[G1Affine], after: &[G1Affine]) -> (G1Affine, G1Affine) {
+    let scalars: Vec<_> = (0..before.len())
+        .map(|_| Fr::rand(&mut OsRng).into_repr())
+        .collect();
+    (
+        VariableBaseMSM::multi_scalar_mul(before, &scalars).into_affine(),
+        VariableBaseMSM::multi_scalar_mul(after, &scalars).into_affine(),
+    )
+}
+
+impl Ceremony {
+    /// Starts a ceremony for the circuit, from the powers of tau of phase 1 (a `.ptau` file of snarkjs).
+    pub fn new(
+        compiled_circuit: &CompiledCircuit<R1CS<R1csBn254Field>>,
+        ptau_path: &Utf8Path,
+    ) -> miette::Result<Self> {
+        let proving_key =
+            compiled_circuit.groth16_proving_key_from_ptau(ptau_path, Fr::one(), Fr::one())?;
+
+        Ok(Self {
+            initial_hash: Sha256::digest(to_bytes(&proving_key)).into(),
+            proving_key,
+            contributions: vec![],
+        })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> miette::Result<Self> {
+        rmp_serde::from_slice(bytes)
+            .into_diagnostic()
+            .wrap_err("groth16: could not deserialize the ceremony")
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        rmp_serde::to_vec_named(self).expect("serializing a ceremony can't fail")
+    }
+
+    pub fn contributions(&self) -> &[Contribution] {
+        &self.contributions
+    }
+
+    /// The hash of the transcript, that the next contribution is chained to.
+    pub fn hash(&self) -> [u8; 32] {
+        self.contributions
+            .last()
+            .map(|contribution| contribution.hash)
+            .unwrap_or(self.initial_hash)
+    }
+
+    /// Contributes a secret `δ'` sampled from the OS and from the given entropy, and drops it.
+    /// Returns the hash of the contribution.
+    pub fn contribute(&mut self, entropy: &[u8]) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        let mut rng = ChaCha20Rng::from_seed(
+            Sha256::new()
+                .chain_update(seed)
+                .chain_update(entropy)
+                .finalize()
+                .into(),
+        );
+
+        let delta = Fr::rand(&mut rng);
+        let delta_repr = delta.into_repr();
+        let delta_inverse = delta.inverse().unwrap().into_repr();
+
+        // the proof of knowledge of δ'
+        let s = G1Projective::rand(&mut rng).into_affine();
+        let s_delta = s.mul(delta_repr).into_affine();
+        let previous_hash = self.hash();
+        let r = contribution_point(&previous_hash, &s, &s_delta);
+        let r_delta = r.mul(delta_repr).into_affine();
+
+        // update the key
+        let pk = &mut self.proving_key;
+        pk.delta_g1 = pk.delta_g1.mul(delta_repr).into_affine();
+        pk.vk.delta_g2 = pk.vk.delta_g2.mul(delta_repr).into_affine();
+        for query in [&mut pk.h_query, &mut pk.l_query] {
+            let updated: Vec<_> = query.iter().map(|point| point.mul(delta_inverse)).collect();
+            *query = G1Projective::batch_normalization_into_affine(&updated);
+        }
+
+        let mut contribution = Contribution {
+            delta_g1: pk.delta_g1,
+            delta_g2: pk.vk.delta_g2,
+            s,
+            s_delta,
+            r_delta,
+            hash: [0; 32],
+        };
+        let hash = contribution.compute_hash(&previous_hash);
+        contribution.hash = hash;
+        self.contributions.push(contribution);
+
+        hash
+    }
+
+    /// Verifies the transcript of the ceremony for the circuit and the powers of tau:
+    /// that the ceremony started from the right key, that each contribution proves knowledge of its secret,
+    /// and that the current key only differs from the initial key by the product of the secrets.
+    pub fn verify(
+        &self,
+        compiled_circuit: &CompiledCircuit<R1CS<R1csBn254Field>>,
+        ptau_path: &Utf8Path,
+    ) -> miette::Result<()> {
+        let initial = Self::new(compiled_circuit, ptau_path)?;
+        if initial.initial_hash != self.initial_hash {
+            miette::bail!(
+                "groth16: the ceremony didn't start from the proving key of this circuit and these powers of tau"
+            );
+        }
+
+        // only δ, and the queries divided by δ, change
+        let (before, after) = (&initial.proving_key, &self.proving_key);
+        if before.vk.alpha_g1 != after.vk.alpha_g1
+            || before.vk.beta_g2 != after.vk.beta_g2
+            || before.vk.gamma_g2 != after.vk.gamma_g2
+            || before.vk.gamma_abc_g1 != after.vk.gamma_abc_g1
+            || before.beta_g1 != after.beta_g1
+            || before.a_query != after.a_query
+            || before.b_g1_query != after.b_g1_query
+            || before.b_g2_query != after.b_g2_query
+            || before.h_query.len() != after.h_query.len()
+            || before.l_query.len() != after.l_query.len()
+        {
+            miette::bail!("groth16: the ceremony changed more than δ in the proving key");
+        }
+
+        // the chain of contributions
+        let mut delta = (before.delta_g1, before.vk.delta_g2);
+        let mut hash = self.initial_hash;
+        for (index, contribution) in self.contributions.iter().enumerate() {
+            let r = contribution_point(&hash, &contribution.s, &contribution.s_delta);
+            let valid =
+                same_ratio(
+                    (contribution.s, contribution.s_delta),
+                    (r, contribution.r_delta),
+                ) && same_ratio((delta.0, contribution.delta_g1), (r, contribution.r_delta))
+                    && same_ratio(
+                        (G1Affine::prime_subgroup_generator(), contribution.delta_g1),
+                        (G2Affine::prime_subgroup_generator(), contribution.delta_g2),
+                    );
+            if !valid {
+                miette::bail!(
+                    "groth16: the contribution {} of the ceremony is invalid",
+                    index + 1
+                );
+            }
+            if contribution.compute_hash(&hash) != contribution.hash {
+                miette::bail!(
+                    "groth16: the hash of the contribution {} of the ceremony is wrong",
+                    index + 1
+                );
+            }
+
+            delta = (contribution.delta_g1, contribution.delta_g2);
+            hash = contribution.hash;
+        }
+
+        // the key has the δ of the last contribution, and its queries are divided by it
+        if (after.delta_g1, after.vk.delta_g2) != delta {
+            miette::bail!(
+                "groth16: the proving key doesn't match the last contribution of the ceremony"
+            );
+        }
+        for (query_before, query_after) in [
+            (&before.h_query, &after.h_query),
+            (&before.l_query, &after.l_query),
+        ] {
+            let combined = combine(query_before, query_after);
+            if !same_ratio(combined, (after.vk.delta_g2, before.vk.delta_g2)) {
+                miette::bail!("groth16: the queries of the proving key are not divided by δ");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the indexes of the circuit, with the key of the ceremony.
+    /// This should only be used after verifying the ceremony, and with at least one contribution.
+    pub fn into_indexes(
+        self,
+        compiled_circuit: CompiledCircuit<R1CS<R1csBn254Field>>,
+    ) -> (ProverIndex, VerifierIndex) {
+        let verifier_index = VerifierIndex {
+            verifying_key: self.proving_key.vk.clone(),
+        };
+        let prover_index = ProverIndex {
+            proving_key: self.proving_key,
+            compiled_circuit,
+        };
+
+        (prover_index, verifier_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+
+    use crate::{
+        backends::{
+            groth16::ptau::tests::write_ptau,
+            r1cs::{R1csBn254Field, R1CS},
+        },
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS},
+    };
+
+    use super::Ceremony;
+
+    #[test]
+    fn test_ceremony() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new())?;
+
+        let ptau = write_ptau(4, Fr::from(5u64), Fr::from(7u64), Fr::from(11u64));
+        let path =
+            std::env::temp_dir().join(format!("noname-ceremony-{}.ptau", std::process::id()));
+        std::fs::write(&path, ptau).unwrap();
+        let path: camino::Utf8PathBuf = path.try_into().unwrap();
+
+        // two contributions, going through serialization
+        let mut ceremony = Ceremony::new(&compiled_circuit, &path)?;
+        let first = ceremony.contribute(b"first");
+        let mut ceremony = Ceremony::from_bytes(&ceremony.to_bytes())?;
+        let second = ceremony.contribute(b"second");
+        assert_ne!(first, second);
+        assert_eq!(ceremony.hash(), second);
+        ceremony.verify(&compiled_circuit, &path)?;
+
+        // a tampered transcript is rejected
+        let mut tampered = Ceremony::from_bytes(&ceremony.to_bytes())?;
+        tampered.contributions[0].hash[0] ^= 1;
+        assert!(tampered.verify(&compiled_circuit, &path).is_err());
+        std::fs::remove_file(&path).unwrap();
+
+        // the keys of the ceremony prove and verify
+        let (prover_index, verifier_index) = ceremony.into_indexes(compiled_circuit);
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(PRIVATE_INPUTS)?;
+        let (proof, full_public_inputs, _) =
+            prover_index.prove(&sources, public_inputs, private_inputs)?;
+        verifier_index.verify(&full_public_inputs, &proof)?;
+
+        // but not with a tampered public input
+        let tampered_inputs = [R1csBn254Field::from(8u64), R1csBn254Field::from(3u64)];
+        assert!(verifier_index.verify(&tampered_inputs, &proof).is_err());
+
+        Ok(())
+    }
+}
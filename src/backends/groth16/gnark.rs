@@ -0,0 +1,168 @@
+//! An export of Groth16 keys and proofs to the binary formats of gnark (https://github.com/Consensys/gnark, v0.10),
+//! with a Go package that embeds the verifying key and verifies proofs with gnark (see `gnark/verifier.go`).
+//!
+//! Points are written uncompressed (the "raw" encoding of gnark-crypto): big-endian coordinates,
+//! where the coordinates of G2 points are written as `A1 || A0`, and the point at infinity is all zeros.
+//! The circuits of noname have no commitments, so the corresponding fields of gnark are empty.
+
+use ark_bn254::{Bn254, Fq, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::ProvingKey;
+
+use super::{from_bytes, Groth16Proof};
+
+/// The template of the Go verifier.
+const VERIFIER_GO: &str = include_str!("gnark/verifier.go");
+
+/// The Go module of the verifier.
+const GO_MOD: &str = "module {{PACKAGE}}
+
+go 1.21
+
+require (
+	github.com/consensys/gnark v0.10.0
+	github.com/consensys/gnark-crypto v0.12.1
+)
+";
+
+/// A Go package verifying the proofs of a circuit with gnark.
+pub struct GnarkVerifier {
+    /// The verifying key, in the format of gnark (`verifying_key.bin`).
+    pub verifying_key: Vec<u8>,
+
+    /// The sources of the package (`verifier.go`).
+    pub verifier_go: String,
+
+    /// The Go module of the package (`go.mod`), to run `go mod tidy` in.
+    pub go_mod: String,
+}
+
+fn write_fq(value: &Fq, out: &mut Vec<u8>) {
+    let bytes = value.into_repr().to_bytes_be();
+
+    // pad to 32 bytes
+    out.extend(std::iter::repeat(0).take(32 - bytes.len()));
+    out.extend(bytes);
+}
+
+fn write_g1(point: &G1Affine, out: &mut Vec<u8>) {
+    if point.infinity {
+        out.extend([0; 64]);
+    } else {
+        write_fq(&point.x, out);
+        write_fq(&point.y, out);
+    }
+}
+
+fn write_g2(point: &G2Affine, out: &mut Vec<u8>) {
+    if point.infinity {
+        out.extend([0; 128]);
+    } else {
+        for coordinate in [&point.x, &point.y] {
+            write_fq(&coordinate.c1, out);
+            write_fq(&coordinate.c0, out);
+        }
+    }
+}
+
+/// Writes the length of a slice, as gnark-crypto does.
+fn write_len(len: usize, out: &mut Vec<u8>) {
+    let len = u32::try_from(len).expect("slices of gnark have 32-bit lengths");
+    out.extend(len.to_be_bytes());
+}
+
+/// Converts a proving key of noname to the verifying key of gnark.
+/// The proving key is needed, as the verifying key of gnark also has `[β]1` and `[δ]1`.
+pub fn verifying_key_to_gnark(proving_key: &ProvingKey<Bn254>) -> Vec<u8> {
+    let vk = &proving_key.vk;
+    let mut out = vec![];
+
+    // [α]1, [β]1, [β]2, [γ]2, [δ]1, [δ]2
+    write_g1(&vk.alpha_g1, &mut out);
+    write_g1(&proving_key.beta_g1, &mut out);
+    write_g2(&vk.beta_g2, &mut out);
+    write_g2(&vk.gamma_g2, &mut out);
+    write_g1(&proving_key.delta_g1, &mut out);
+    write_g2(&vk.delta_g2, &mut out);
+
+    // the points of the public inputs, with the constant first
+    write_len(vk.gamma_abc_g1.len(), &mut out);
+    for point in &vk.gamma_abc_g1 {
+        write_g1(point, &mut out);
+    }
+
+    // no public inputs are committed, and there are no commitment keys
+    write_len(0, &mut out);
+    write_len(0, &mut out);
+
+    out
+}
+
+/// Converts a proof of noname to the format of gnark.
+pub fn proof_to_gnark(proof: &Groth16Proof) -> Vec<u8> {
+    let mut out = vec![];
+    write_g1(&proof.a, &mut out);
+    write_g2(&proof.b, &mut out);
+    write_g1(&proof.c, &mut out);
+
+    // no commitments, and the proof of knowledge of the commitments is the point at infinity
+    write_len(0, &mut out);
+    out.extend([0; 64]);
+
+    out
+}
+
+/// Returns a Go package named `package` that verifies the proofs of a circuit, given its proving key (see [super::ProverIndex::to_bytes]).
+pub fn gnark_verifier(proving_key: &[u8], package: &str) -> miette::Result<GnarkVerifier> {
+    let proving_key: ProvingKey<Bn254> = from_bytes(proving_key, "proving key")?;
+    let nb_public_inputs = proving_key.vk.gamma_abc_g1.len() - 1;
+
+    Ok(GnarkVerifier {
+        verifying_key: verifying_key_to_gnark(&proving_key),
+        verifier_go: VERIFIER_GO
+            .replace("{{PACKAGE}}", package)
+            .replace("{{NB_PUBLIC_INPUTS}}", &nb_public_inputs.to_string()),
+        go_mod: GO_MOD.replace("{{PACKAGE}}", package),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{BigInteger, PrimeField};
+
+    use crate::{
+        backends::r1cs::{R1csBn254Field, R1CS},
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS},
+    };
+
+    use super::{gnark_verifier, proof_to_gnark};
+
+    #[test]
+    fn test_gnark_export() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new())?;
+        let (prover_index, _) = compiled_circuit.compile_to_groth16_indexes()?;
+
+        // 3 points of G1 and 3 points of G2, the points of the constant, the public output and the public input, and 3 lengths
+        let verifier = gnark_verifier(&prover_index.to_bytes(), "verifier")?;
+        assert_eq!(
+            verifier.verifying_key.len(),
+            3 * 64 + 3 * 128 + 3 * 64 + 3 * 4
+        );
+        assert!(verifier.verifier_go.contains("package verifier"));
+        assert!(verifier.verifier_go.contains("const NbPublicInputs = 2"));
+
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(PRIVATE_INPUTS)?;
+        let (proof, _, _) = prover_index.prove(&sources, public_inputs, private_inputs)?;
+
+        // A (with big-endian coordinates), B, C, no commitments, and the point at infinity
+        let bytes = proof_to_gnark(&proof);
+        assert_eq!(bytes.len(), 64 + 128 + 64 + 4 + 64);
+        assert_eq!(bytes[..32], proof.a.x.into_repr().to_bytes_be());
+        assert!(bytes[260..].iter().all(|byte| *byte == 0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+// Package {{PACKAGE}} verifies the Groth16 proofs of a noname circuit with gnark.
+// It was written by `noname groth16 gnark-verifier`: regenerate it after changing the circuit or running a new setup.
+package {{PACKAGE}}
+
+import (
+	"bytes"
+	_ "embed"
+	"fmt"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	"github.com/consensys/gnark/backend/groth16"
+	"github.com/consensys/gnark/backend/witness"
+)
+
+// the verifying key of the circuit, in the binary format of gnark
+//
+//go:embed verifying_key.bin
+var verifyingKeyBytes []byte
+
+// NbPublicInputs is the number of public inputs of the verifier: the public outputs, then the public inputs of the circuit.
+const NbPublicInputs = {{NB_PUBLIC_INPUTS}}
+
+// VerifyingKey returns the verifying key of the circuit.
+func VerifyingKey() (groth16.VerifyingKey, error) {
+	vk := groth16.NewVerifyingKey(ecc.BN254)
+	if _, err := vk.ReadFrom(bytes.NewReader(verifyingKeyBytes)); err != nil {
+		return nil, fmt.Errorf("could not read the verifying key: %w", err)
+	}
+	return vk, nil
+}
+
+// ReadProof reads a proof written by `noname groth16 prove --gnark-proof-path`.
+func ReadProof(proofBytes []byte) (groth16.Proof, error) {
+	proof := groth16.NewProof(ecc.BN254)
+	if _, err := proof.ReadFrom(bytes.NewReader(proofBytes)); err != nil {
+		return nil, fmt.Errorf("could not read the proof: %w", err)
+	}
+	return proof, nil
+}
+
+// PublicWitness returns the public witness of the verifier from its values (e.g. the decimal strings of `public.json`).
+func PublicWitness(values []any) (witness.Witness, error) {
+	if len(values) != NbPublicInputs {
+		return nil, fmt.Errorf("expected %d public inputs, got %d", NbPublicInputs, len(values))
+	}
+	publicWitness, err := witness.New(ecc.BN254.ScalarField())
+	if err != nil {
+		return nil, err
+	}
+	ch := make(chan any, len(values))
+	for _, value := range values {
+		ch <- value
+	}
+	close(ch)
+	if err := publicWitness.Fill(len(values), 0, ch); err != nil {
+		return nil, fmt.Errorf("invalid public inputs: %w", err)
+	}
+	return publicWitness, nil
+}
+
+// Verify verifies a proof of the circuit against its public witness.
+func Verify(proof groth16.Proof, publicWitness witness.Witness) error {
+	vk, err := VerifyingKey()
+	if err != nil {
+		return err
+	}
+	return groth16.Verify(proof, vk, publicWitness)
+}
@@ -0,0 +1,273 @@
+//! Groth16 proofs (https://eprint.iacr.org/2016/260) over bn254, with arkworks.
+//!
+//! Circuits are written with the R1CS backend over the scalar field of bn254 (see [R1CS]),
+//! and this module turns them into a proof system: the compiled constraints are replayed into an arkworks constraint system (see [replay_into]),
+//! with the public outputs and the public inputs as its instance (in this order, as in the snarkjs format).
+//! Proofs are three points of bn254, which are cheap to verify on EVM chains thanks to its pairing precompiles.
+//!
+//! Note that the setup is specific to each circuit, and that its randomness (the "toxic waste") must be discarded:
+//! the setup of [CompiledCircuit::compile_to_groth16_indexes] samples it from the OS and drops it right away.
+
+pub mod ceremony;
+pub mod gnark;
+pub mod ptau;
+pub mod wasm;
+
+use ark_bn254::Bn254;
+use ark_groth16::{
+    create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof, Proof,
+    ProvingKey, VerifyingKey,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use miette::{Context, IntoDiagnostic};
+use rand::rngs::OsRng;
+
+use crate::{
+    backends::r1cs::{
+        arkworks::{replay_into, PublicVars},
+        R1csBn254Field, R1CS,
+    },
+    compiler::{generate_witness, Sources},
+    inputs::JsonInputs,
+    witness::CompiledCircuit,
+};
+
+//
+// Data Structures
+//
+
+/// A Groth16 proof over bn254.
+pub type Groth16Proof = Proof<Bn254>;
+
+pub struct ProverIndex {
+    proving_key: ProvingKey<Bn254>,
+    compiled_circuit: CompiledCircuit<R1CS<R1csBn254Field>>,
+}
+
+pub struct VerifierIndex {
+    verifying_key: VerifyingKey<Bn254>,
+}
+
+/// The compiled constraints, with the witness when proving (and without it during the setup).
+struct Synthesizer<'a> {
+    r1cs: &'a R1CS<R1csBn254Field>,
+    witness: Option<&'a [R1csBn254Field]>,
+}
+
+impl ConstraintSynthesizer<R1csBn254Field> for Synthesizer<'_> {
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<R1csBn254Field>,
+    ) -> Result<(), SynthesisError> {
+        replay_into(self.r1cs, cs, self.witness, PublicVars::Instance)?;
+        Ok(())
+    }
+}
+
+//
+// Serialization
+//
+
+/// Serializes a key or a proof.
+pub fn to_bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = vec![];
+    value
+        .serialize(&mut bytes)
+        .expect("serializing to a vector can't fail");
+    bytes
+}
+
+/// Deserializes a key or a proof, where `what` describes it in the error.
+pub fn from_bytes<T: CanonicalDeserialize>(bytes: &[u8], what: &str) -> miette::Result<T> {
+    T::deserialize(bytes)
+        .into_diagnostic()
+        .wrap_err(format!("groth16: could not deserialize the {what}"))
+}
+
+//
+// Setup
+//
+
+impl CompiledCircuit<R1CS<R1csBn254Field>> {
+    /// Runs the setup of Groth16 for this circuit.
+    pub fn compile_to_groth16_indexes(self) -> miette::Result<(ProverIndex, VerifierIndex)> {
+        let synthesizer = Synthesizer {
+            r1cs: &self.circuit.backend,
+            witness: None,
+        };
+        let proving_key = generate_random_parameters::<Bn254, _, _>(synthesizer, &mut OsRng)
+            .into_diagnostic()
+            .wrap_err("groth16: could not run the setup of the given circuit")?;
+
+        let verifier_index = VerifierIndex {
+            verifying_key: proving_key.vk.clone(),
+        };
+        let prover_index = ProverIndex {
+            proving_key,
+            compiled_circuit: self,
+        };
+
+        Ok((prover_index, verifier_index))
+    }
+}
+
+//
+// Proving
+//
+
+impl ProverIndex {
+    /// Loads a proving key produced by the setup of the same circuit.
+    pub fn from_bytes(
+        compiled_circuit: CompiledCircuit<R1CS<R1csBn254Field>>,
+        bytes: &[u8],
+    ) -> miette::Result<Self> {
+        let proving_key: ProvingKey<Bn254> = from_bytes(bytes, "proving key")?;
+
+        // the key has a query per variable, and a point per public input (plus one for the constant)
+        let r1cs = &compiled_circuit.circuit.backend;
+        if proving_key.a_query.len() != r1cs.num_vars()
+            || proving_key.vk.gamma_abc_g1.len() != r1cs.public_var_indices().len() + 1
+        {
+            miette::bail!("groth16: the proving key was not produced by the setup of this circuit");
+        }
+
+        Ok(Self {
+            proving_key,
+            compiled_circuit,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        to_bytes(&self.proving_key)
+    }
+
+    /// returns a proof, the public inputs of the verifier, and the public output
+    #[allow(clippy::type_complexity)]
+    pub fn prove(
+        &self,
+        sources: &Sources,
+        public_inputs: JsonInputs,
+        private_inputs: JsonInputs,
+    ) -> miette::Result<(Groth16Proof, Vec<R1csBn254Field>, Vec<R1csBn254Field>)> {
+        // generate the witness (this also checks the constraints)
+        let generated_witness = generate_witness(
+            &self.compiled_circuit,
+            sources,
+            public_inputs,
+            private_inputs,
+        )?;
+
+        // create proof
+        let r1cs = &self.compiled_circuit.circuit.backend;
+        let synthesizer = Synthesizer {
+            r1cs,
+            witness: Some(&generated_witness.witness),
+        };
+        let proof = create_random_proof(synthesizer, &self.proving_key, &mut OsRng)
+            .into_diagnostic()
+            .wrap_err("groth16: could not create a proof with the given inputs")?;
+
+        let full_public_inputs = r1cs
+            .public_var_indices()
+            .into_iter()
+            .map(|index| generated_witness.witness[index])
+            .collect();
+
+        Ok((proof, full_public_inputs, generated_witness.outputs))
+    }
+}
+
+//
+// Verifying
+//
+
+impl VerifierIndex {
+    pub fn from_bytes(bytes: &[u8]) -> miette::Result<Self> {
+        Ok(Self {
+            verifying_key: from_bytes(bytes, "verifying key")?,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        to_bytes(&self.verifying_key)
+    }
+
+    /// Verifies a proof, given the public outputs followed by the public inputs.
+    pub fn verify(
+        &self,
+        full_public_inputs: &[R1csBn254Field],
+        proof: &Groth16Proof,
+    ) -> miette::Result<()> {
+        let prepared_key = prepare_verifying_key(&self.verifying_key);
+        let valid = verify_proof(&prepared_key, proof, full_public_inputs)
+            .into_diagnostic()
+            .wrap_err("groth16: the public inputs don't match the verifying key")?;
+
+        if !valid {
+            miette::bail!("groth16: failed to verify the proof");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS, WRONG_PRIVATE_INPUTS},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_prove_and_verify() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new())?;
+
+        let (prover_index, verifier_index) = compiled_circuit.compile_to_groth16_indexes()?;
+
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(PRIVATE_INPUTS)?;
+        let (proof, full_public_inputs, public_output) =
+            prover_index.prove(&sources, public_inputs, private_inputs)?;
+
+        // the public output comes first
+        assert_eq!(public_output, vec![R1csBn254Field::from(8u64)]);
+        assert_eq!(
+            full_public_inputs,
+            vec![R1csBn254Field::from(8u64), R1csBn254Field::from(2u64)]
+        );
+
+        // keys and proofs go through serialization
+        let verifier_index = VerifierIndex::from_bytes(&verifier_index.to_bytes())?;
+        let proof: Groth16Proof = from_bytes(&to_bytes(&proof), "proof")?;
+        verifier_index.verify(&full_public_inputs, &proof)?;
+
+        // a wrong public output is rejected
+        let wrong_inputs = [R1csBn254Field::from(9u64), R1csBn254Field::from(2u64)];
+        assert!(verifier_index.verify(&wrong_inputs, &proof).is_err());
+
+        // so is a tampered public input
+        let tampered_inputs = [R1csBn254Field::from(8u64), R1csBn254Field::from(3u64)];
+        assert!(verifier_index.verify(&tampered_inputs, &proof).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_wrong_witness() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new())?;
+        let (prover_index, _) = compiled_circuit.compile_to_groth16_indexes()?;
+
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(WRONG_PRIVATE_INPUTS)?;
+        assert!(prover_index
+            .prove(&sources, public_inputs, private_inputs)
+            .is_err());
+
+        Ok(())
+    }
+}
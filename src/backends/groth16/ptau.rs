@@ -0,0 +1,508 @@
+//! The setup of Groth16 from the powers of tau of a ceremony, in the `.ptau` format of snarkjs
+//! (for example, the files of the perpetual powers of tau ceremony of Polygon Hermez).
+//!
+//! A `.ptau` file starts with the magic bytes `ptau`, a version and a number of sections,
+//! followed by the sections (a type and a size, as `u32` and `u64`, then the data).
+//! Points are written as their little-endian coordinates in Montgomery form, and the point at infinity is all zeros.
+//! The sections that the setup uses are the header, `[τ^i]1`, `[τ^i]2`, `[ατ^i]1`, `[βτ^i]1` and `[β]2`.
+//!
+//! The circuit-specific part of the setup evaluates the QAP of arkworks (see [CompiledCircuit::compile_to_groth16_indexes_with_ptau])
+//! in the exponent: the powers of tau are turned into the lagrange basis of the domain of the circuit with an inverse FFT over the curve.
+//! Only `γ` and `δ` are sampled, and dropped right away.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+};
+
+use ark_bn254::{Bn254, Fq, Fq2, FqParameters, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{BigInteger, BigInteger256, Field, FpParameters, PrimeField, UniformRand, Zero};
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisMode,
+};
+use camino::Utf8Path;
+use miette::{Context, IntoDiagnostic};
+use rand::rngs::OsRng;
+
+use super::{ProverIndex, Synthesizer, VerifierIndex};
+use crate::{
+    backends::r1cs::{R1csBn254Field, R1CS},
+    witness::CompiledCircuit,
+};
+
+const MAGIC: &[u8; 4] = b"ptau";
+
+// the sections used by the setup
+const HEADER: u32 = 1;
+const TAU_G1: u32 = 2;
+const TAU_G2: u32 = 3;
+const ALPHA_TAU_G1: u32 = 4;
+const BETA_TAU_G1: u32 = 5;
+const BETA_G2: u32 = 6;
+
+/// The size of a coordinate of bn254.
+const N8: usize = 32;
+
+/// The powers of tau needed by the setup of a circuit whose domain has `domain_size` elements.
+pub struct PowersOfTau {
+    /// The power of the ceremony (it supports domains of up to `2^power` elements).
+    pub power: u32,
+
+    /// `[τ^i]1`, for `i < 2 * domain_size - 1`.
+    pub tau_g1: Vec<G1Affine>,
+
+    /// `[τ^i]2`, for `i < domain_size`.
+    pub tau_g2: Vec<G2Affine>,
+
+    /// `[ατ^i]1`, for `i < domain_size`.
+    pub alpha_tau_g1: Vec<G1Affine>,
+
+    /// `[βτ^i]1`, for `i < domain_size`.
+    pub beta_tau_g1: Vec<G1Affine>,
+
+    /// `[β]2`.
+    pub beta_g2: G2Affine,
+}
+
+struct PtauReader {
+    reader: BufReader<File>,
+    sections: HashMap<u32, u64>,
+}
+
+impl PtauReader {
+    fn open(path: &Utf8Path) -> miette::Result<Self> {
+        let file = File::open(path)
+            .into_diagnostic()
+            .wrap_err(format!("could not open the powers of tau at `{path}`"))?;
+        let mut reader = PtauReader {
+            reader: BufReader::new(file),
+            sections: HashMap::new(),
+        };
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            miette::bail!("`{path}` is not a `.ptau` file");
+        }
+        let _version = reader.read_u32()?;
+
+        // index the sections
+        let num_sections = reader.read_u32()?;
+        for _ in 0..num_sections {
+            let section = reader.read_u32()?;
+            let size = reader.read_u64()?;
+            let position = reader.reader.stream_position().into_diagnostic()?;
+            reader.sections.entry(section).or_insert(position);
+            reader
+                .reader
+                .seek(SeekFrom::Current(size as i64))
+                .into_diagnostic()?;
+        }
+
+        Ok(reader)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> miette::Result<()> {
+        self.reader
+            .read_exact(buf)
+            .into_diagnostic()
+            .wrap_err("the `.ptau` file is truncated")
+    }
+
+    fn read_u32(&mut self) -> miette::Result<u32> {
+        let mut bytes = [0; 4];
+        self.read_exact(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn read_u64(&mut self) -> miette::Result<u64> {
+        let mut bytes = [0; 8];
+        self.read_exact(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn seek_section(&mut self, section: u32) -> miette::Result<()> {
+        let Some(position) = self.sections.get(&section) else {
+            miette::bail!("the `.ptau` file has no section {section}");
+        };
+        self.reader
+            .seek(SeekFrom::Start(*position))
+            .into_diagnostic()?;
+        Ok(())
+    }
+
+    fn read_fq(&mut self) -> miette::Result<Fq> {
+        let mut bytes = [0; N8];
+        self.read_exact(&mut bytes)?;
+
+        // the coordinates are in Montgomery form (`x * R`)
+        let limbs = std::array::from_fn(|i| {
+            u64::from_le_bytes(bytes[8 * i..8 * i + 8].try_into().unwrap())
+        });
+        let montgomery = Fq::from_repr(BigInteger256::new(limbs))
+            .ok_or_else(|| miette::miette!("the `.ptau` file has a coordinate out of the field"))?;
+        let r = Fq::from_repr(FqParameters::R).unwrap();
+
+        Ok(montgomery * r.inverse().unwrap())
+    }
+
+    fn read_g1(&mut self) -> miette::Result<G1Affine> {
+        let x = self.read_fq()?;
+        let y = self.read_fq()?;
+        if x.is_zero() && y.is_zero() {
+            return Ok(G1Affine::zero());
+        }
+
+        let point = G1Affine::new(x, y, false);
+        if !point.is_on_curve() {
+            miette::bail!("the `.ptau` file has a point that is not on the curve");
+        }
+        Ok(point)
+    }
+
+    fn read_g2(&mut self) -> miette::Result<G2Affine> {
+        let x = Fq2::new(self.read_fq()?, self.read_fq()?);
+        let y = Fq2::new(self.read_fq()?, self.read_fq()?);
+        if x.is_zero() && y.is_zero() {
+            return Ok(G2Affine::zero());
+        }
+
+        let point = G2Affine::new(x, y, false);
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            miette::bail!("the `.ptau` file has a point that is not in the group");
+        }
+        Ok(point)
+    }
+
+    fn read_points<T>(
+        &mut self,
+        section: u32,
+        len: usize,
+        read: impl Fn(&mut Self) -> miette::Result<T>,
+    ) -> miette::Result<Vec<T>> {
+        self.seek_section(section)?;
+        (0..len).map(|_| read(self)).collect()
+    }
+}
+
+impl PowersOfTau {
+    /// Reads the powers of tau needed for a domain of `domain_size` elements (a power of two),
+    /// and checks that they are consistent.
+    pub fn read(path: &Utf8Path, domain_size: usize) -> miette::Result<Self> {
+        let mut reader = PtauReader::open(path)?;
+
+        // the header has the size of the coordinates, the modulus, and the power
+        reader.seek_section(HEADER)?;
+        let n8 = reader.read_u32()? as usize;
+        let mut modulus = vec![0; n8];
+        reader.read_exact(&mut modulus)?;
+        if n8 != N8 || modulus != FqParameters::MODULUS.to_bytes_le() {
+            miette::bail!("the powers of tau at `{path}` are not over bn254");
+        }
+        let power = reader.read_u32()?;
+        if domain_size > 1 << power {
+            miette::bail!(
+                "the powers of tau at `{path}` support circuits of up to 2^{power} constraints, but the circuit needs {domain_size} (use a `.ptau` file of a larger power)"
+            );
+        }
+
+        let ptau = Self {
+            power,
+            tau_g1: reader.read_points(TAU_G1, 2 * domain_size - 1, PtauReader::read_g1)?,
+            tau_g2: reader.read_points(TAU_G2, domain_size, PtauReader::read_g2)?,
+            alpha_tau_g1: reader.read_points(ALPHA_TAU_G1, domain_size, PtauReader::read_g1)?,
+            beta_tau_g1: reader.read_points(BETA_TAU_G1, domain_size, PtauReader::read_g1)?,
+            beta_g2: reader.read_points(BETA_G2, 1, PtauReader::read_g2)?[0],
+        };
+        ptau.check()
+            .wrap_err(format!("the powers of tau at `{path}` are invalid"))?;
+
+        Ok(ptau)
+    }
+
+    /// Checks that the powers start from the generators, and that `τ` and `β` are the same in both groups.
+    /// (This doesn't verify the whole ceremony, which `snarkjs powersoftau verify` does.)
+    fn check(&self) -> miette::Result<()> {
+        let g1 = G1Affine::prime_subgroup_generator();
+        let g2 = G2Affine::prime_subgroup_generator();
+        if self.tau_g1[0] != g1 || self.tau_g2[0] != g2 {
+            miette::bail!("the first powers are not the generators");
+        }
+
+        if self.tau_g1.len() > 1
+            && Bn254::pairing(self.tau_g1[1], g2) != Bn254::pairing(g1, self.tau_g2[1])
+        {
+            miette::bail!("τ differs in G1 and G2");
+        }
+        if Bn254::pairing(self.beta_tau_g1[0], g2) != Bn254::pairing(g1, self.beta_g2) {
+            miette::bail!("β differs in G1 and G2");
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `[L_i(τ)]` for the lagrange polynomials of the domain, given `[τ^i]` for `i < domain.size()`.
+/// As `L_i(τ) = 1/n * Σ_j ω^{-ij} τ^j`, this is the inverse FFT of the powers.
+fn lagrange<G: ProjectiveCurve<ScalarField = Fr>>(
+    domain: &GeneralEvaluationDomain<Fr>,
+    powers: &[G::Affine],
+) -> Vec<G> {
+    let powers: Vec<G> = powers.iter().map(|power| power.into_projective()).collect();
+    domain.ifft(&powers)
+}
+
+impl CompiledCircuit<R1CS<R1csBn254Field>> {
+    /// Runs the setup of Groth16 for this circuit, with the powers of tau of a ceremony (in the `.ptau` format of snarkjs).
+    /// The keys are compatible with the prover and the verifier of arkworks, as the ones of [Self::compile_to_groth16_indexes].
+    pub fn compile_to_groth16_indexes_with_ptau(
+        self,
+        ptau_path: &Utf8Path,
+    ) -> miette::Result<(ProverIndex, VerifierIndex)> {
+        // the circuit-specific toxic waste
+        let gamma = Fr::rand(&mut OsRng);
+        let delta = Fr::rand(&mut OsRng);
+        let proving_key = self.groth16_proving_key_from_ptau(ptau_path, gamma, delta)?;
+
+        let verifier_index = VerifierIndex {
+            verifying_key: proving_key.vk.clone(),
+        };
+        let prover_index = ProverIndex {
+            proving_key,
+            compiled_circuit: self,
+        };
+
+        Ok((prover_index, verifier_index))
+    }
+
+    /// Returns the proving key of this circuit for the powers of tau at `ptau_path`, and the given `γ` and `δ`.
+    pub(super) fn groth16_proving_key_from_ptau(
+        &self,
+        ptau_path: &Utf8Path,
+        gamma: Fr,
+        delta: Fr,
+    ) -> miette::Result<ProvingKey<Bn254>> {
+        // the constraints, as replayed by the prover of arkworks
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Setup);
+        let synthesizer = Synthesizer {
+            r1cs: &self.circuit.backend,
+            witness: None,
+        };
+        synthesizer
+            .generate_constraints(cs.clone())
+            .into_diagnostic()
+            .wrap_err("groth16: could not synthesize the given circuit")?;
+        cs.finalize();
+        let matrices = cs
+            .to_matrices()
+            .expect("the constraint system is in setup mode");
+
+        // the QAP of arkworks has a row per constraint, then a row per instance variable
+        let num_inputs = matrices.num_instance_variables;
+        let num_vars = num_inputs + matrices.num_witness_variables;
+        let num_constraints = matrices.num_constraints;
+        let domain = GeneralEvaluationDomain::<Fr>::new(num_constraints + num_inputs)
+            .ok_or_else(|| miette::miette!("groth16: the circuit is too large"))?;
+        let n = domain.size();
+
+        let ptau = PowersOfTau::read(ptau_path, n)?;
+        let lagrange_g1: Vec<G1Projective> = lagrange(&domain, &ptau.tau_g1[..n]);
+        let lagrange_g2: Vec<G2Projective> = lagrange(&domain, &ptau.tau_g2);
+        let alpha_lagrange_g1: Vec<G1Projective> = lagrange(&domain, &ptau.alpha_tau_g1);
+        let beta_lagrange_g1: Vec<G1Projective> = lagrange(&domain, &ptau.beta_tau_g1);
+
+        // evaluate A_i(τ), B_i(τ), and (βA_i + αB_i + C_i)(τ) for each variable
+        let mut a = vec![G1Projective::zero(); num_vars];
+        let mut b_g1 = vec![G1Projective::zero(); num_vars];
+        let mut b_g2 = vec![G2Projective::zero(); num_vars];
+        let mut abc = vec![G1Projective::zero(); num_vars];
+
+        for (row, constraint) in matrices.a.iter().enumerate() {
+            for (coeff, var) in constraint {
+                let coeff = coeff.into_repr();
+                a[*var] += lagrange_g1[row].mul(coeff);
+                abc[*var] += beta_lagrange_g1[row].mul(coeff);
+            }
+        }
+        for var in 0..num_inputs {
+            a[var] += lagrange_g1[num_constraints + var];
+            abc[var] += beta_lagrange_g1[num_constraints + var];
+        }
+        for (row, constraint) in matrices.b.iter().enumerate() {
+            for (coeff, var) in constraint {
+                let coeff = coeff.into_repr();
+                b_g1[*var] += lagrange_g1[row].mul(coeff);
+                b_g2[*var] += lagrange_g2[row].mul(coeff);
+                abc[*var] += alpha_lagrange_g1[row].mul(coeff);
+            }
+        }
+        for (row, constraint) in matrices.c.iter().enumerate() {
+            for (coeff, var) in constraint {
+                abc[*var] += lagrange_g1[row].mul(coeff.into_repr());
+            }
+        }
+
+        let gamma_inverse = gamma.inverse().unwrap().into_repr();
+        let delta_inverse = delta.inverse().unwrap().into_repr();
+
+        let gamma_abc: Vec<_> = abc[..num_inputs]
+            .iter()
+            .map(|point| point.mul(gamma_inverse))
+            .collect();
+        let l: Vec<_> = abc[num_inputs..]
+            .iter()
+            .map(|point| point.mul(delta_inverse))
+            .collect();
+
+        // [τ^i Z(τ) / δ]1 with Z(τ) = τ^n - 1, for i < n - 1
+        let h: Vec<_> = (0..n - 1)
+            .map(|i| {
+                (ptau.tau_g1[i + n].into_projective() - ptau.tau_g1[i].into_projective())
+                    .mul(delta_inverse)
+            })
+            .collect();
+
+        let g1 = G1Affine::prime_subgroup_generator();
+        let g2 = G2Affine::prime_subgroup_generator();
+        Ok(ProvingKey::<Bn254> {
+            vk: VerifyingKey {
+                alpha_g1: ptau.alpha_tau_g1[0],
+                beta_g2: ptau.beta_g2,
+                gamma_g2: g2.mul(gamma.into_repr()).into_affine(),
+                delta_g2: g2.mul(delta.into_repr()).into_affine(),
+                gamma_abc_g1: G1Projective::batch_normalization_into_affine(&gamma_abc),
+            },
+            beta_g1: ptau.beta_tau_g1[0],
+            delta_g1: g1.mul(delta.into_repr()).into_affine(),
+            a_query: G1Projective::batch_normalization_into_affine(&a),
+            b_g1_query: G1Projective::batch_normalization_into_affine(&b_g1),
+            b_g2_query: G2Projective::batch_normalization_into_affine(&b_g2),
+            h_query: G1Projective::batch_normalization_into_affine(&h),
+            l_query: G1Projective::batch_normalization_into_affine(&l),
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use ark_bn254::{Fq, FqParameters, Fr, G1Affine, G2Affine};
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{BigInteger, Field, FpParameters, PrimeField};
+
+    use crate::{
+        backends::r1cs::{R1csBn254Field, R1CS},
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS, WRONG_PRIVATE_INPUTS},
+    };
+
+    use super::{ALPHA_TAU_G1, BETA_G2, BETA_TAU_G1, HEADER, MAGIC, TAU_G1, TAU_G2};
+
+    fn write_fq(value: Fq, out: &mut Vec<u8>) {
+        let montgomery = value * Fq::from_repr(FqParameters::R).unwrap();
+        out.extend(montgomery.into_repr().to_bytes_le());
+    }
+
+    fn write_g1(point: G1Affine, out: &mut Vec<u8>) {
+        write_fq(point.x, out);
+        write_fq(point.y, out);
+    }
+
+    fn write_g2(point: G2Affine, out: &mut Vec<u8>) {
+        for coordinate in [point.x, point.y] {
+            write_fq(coordinate.c0, out);
+            write_fq(coordinate.c1, out);
+        }
+    }
+
+    /// Writes the `.ptau` file of a ceremony of the given power, with known secrets.
+    pub(crate) fn write_ptau(power: u32, tau: Fr, alpha: Fr, beta: Fr) -> Vec<u8> {
+        let g1 = G1Affine::prime_subgroup_generator();
+        let g2 = G2Affine::prime_subgroup_generator();
+        let powers = |len: usize, scale: Fr| (0..len as u64).map(move |i| scale * tau.pow([i]));
+        let size = 1usize << power;
+
+        let mut sections: Vec<(u32, Vec<u8>)> = vec![];
+        let mut header = 32u32.to_le_bytes().to_vec();
+        header.extend(FqParameters::MODULUS.to_bytes_le());
+        header.extend(power.to_le_bytes());
+        header.extend(power.to_le_bytes());
+        sections.push((HEADER, header));
+
+        let mut data = vec![];
+        for scalar in powers(2 * size - 1, Fr::from(1u64)) {
+            write_g1(g1.mul(scalar.into_repr()).into_affine(), &mut data);
+        }
+        sections.push((TAU_G1, data));
+
+        let mut data = vec![];
+        for scalar in powers(size, Fr::from(1u64)) {
+            write_g2(g2.mul(scalar.into_repr()).into_affine(), &mut data);
+        }
+        sections.push((TAU_G2, data));
+
+        for (section, scale) in [(ALPHA_TAU_G1, alpha), (BETA_TAU_G1, beta)] {
+            let mut data = vec![];
+            for scalar in powers(size, scale) {
+                write_g1(g1.mul(scalar.into_repr()).into_affine(), &mut data);
+            }
+            sections.push((section, data));
+        }
+
+        let mut data = vec![];
+        write_g2(g2.mul(beta.into_repr()).into_affine(), &mut data);
+        sections.push((BETA_G2, data));
+
+        let mut out = MAGIC.to_vec();
+        out.extend(1u32.to_le_bytes());
+        out.extend((sections.len() as u32).to_le_bytes());
+        for (section, data) in sections {
+            out.extend(section.to_le_bytes());
+            out.extend((data.len() as u64).to_le_bytes());
+            out.extend(data);
+        }
+        out
+    }
+
+    #[test]
+    fn test_ptau_setup() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new())?;
+
+        let ptau = write_ptau(4, Fr::from(5u64), Fr::from(7u64), Fr::from(11u64));
+        let path = std::env::temp_dir().join(format!("noname-{}.ptau", std::process::id()));
+        std::fs::write(&path, ptau).unwrap();
+        let path: camino::Utf8PathBuf = path.try_into().unwrap();
+
+        let (prover_index, verifier_index) =
+            compiled_circuit.compile_to_groth16_indexes_with_ptau(&path)?;
+        std::fs::remove_file(&path).unwrap();
+
+        // the keys work with the prover and the verifier of arkworks
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(PRIVATE_INPUTS)?;
+        let (proof, full_public_inputs, _) =
+            prover_index.prove(&sources, public_inputs, private_inputs)?;
+        verifier_index.verify(&full_public_inputs, &proof)?;
+
+        // a wrong public output or a tampered public input is rejected
+        let wrong_inputs = [R1csBn254Field::from(9u64), R1csBn254Field::from(2u64)];
+        assert!(verifier_index.verify(&wrong_inputs, &proof).is_err());
+        let tampered_inputs = [R1csBn254Field::from(8u64), R1csBn254Field::from(3u64)];
+        assert!(verifier_index.verify(&tampered_inputs, &proof).is_err());
+
+        // and no proof is made for a wrong witness
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(WRONG_PRIVATE_INPUTS)?;
+        assert!(prover_index
+            .prove(&sources, public_inputs, private_inputs)
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+//! A bundle to verify Groth16 proofs in the browser: a small wasm verifier (see `wasm/verifier.rs`),
+//! and a JS wrapper (see `wasm/verifier.js`) that embeds the verifying key
+//! and encodes the public inputs from the signature of the main function.
+//!
+//! The wasm verifier doesn't depend on the circuit, so it is built once with cargo (for `wasm32-unknown-unknown`)
+//! and reused, while the JS wrapper is generated for each circuit and setup.
+
+use std::process::Command;
+
+use camino::Utf8Path;
+use miette::{Context, IntoDiagnostic};
+use serde_json::{json, Value};
+
+use super::{to_bytes, VerifierIndex};
+use crate::{
+    backends::r1cs::{R1csBn254Field, R1CS},
+    imports::FnKind,
+    parser::types::TyKind,
+    type_checker::FullyQualified,
+    witness::CompiledCircuit,
+};
+
+/// The sources of the wasm verifier.
+const VERIFIER_LIB: &str = include_str!("wasm/verifier.rs");
+
+/// The template of the JS wrapper.
+const VERIFIER_JS: &str = include_str!("wasm/verifier.js");
+
+/// The manifest of the wasm verifier, with the same arkworks version as noname (so that keys and proofs deserialize).
+const VERIFIER_MANIFEST: &str = r#"[package]
+name = "noname-groth16-verifier"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+crate-type = ["cdylib"]
+
+[dependencies]
+ark-bn254 = "0.3.0"
+ark-groth16 = { version = "0.3.0", default-features = false }
+ark-serialize = "0.3.0"
+
+[profile.release]
+opt-level = "z"
+lto = true
+codegen-units = 1
+panic = "abort"
+strip = true
+
+# not part of any enclosing workspace
+[workspace]
+"#;
+
+const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+/// Builds the wasm verifier in the given directory, and returns the wasm module.
+/// This requires cargo, and the `wasm32-unknown-unknown` target (`rustup target add wasm32-unknown-unknown`).
+pub fn build_verifier_wasm(build_dir: &Utf8Path) -> miette::Result<Vec<u8>> {
+    let src_dir = build_dir.join("src");
+    std::fs::create_dir_all(&src_dir)
+        .into_diagnostic()
+        .wrap_err(format!("could not create the directory `{src_dir}`"))?;
+
+    // only rewrite the sources when they change, so that cargo doesn't rebuild the verifier
+    for (path, contents) in [
+        (build_dir.join("Cargo.toml"), VERIFIER_MANIFEST),
+        (src_dir.join("lib.rs"), VERIFIER_LIB),
+    ] {
+        if std::fs::read_to_string(&path).ok().as_deref() != Some(contents) {
+            std::fs::write(&path, contents)
+                .into_diagnostic()
+                .wrap_err(format!("could not write `{path}`"))?;
+        }
+    }
+
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .args(["build", "--release", "--target", WASM_TARGET])
+        .current_dir(build_dir)
+        .output()
+        .into_diagnostic()
+        .wrap_err("could not run cargo to build the wasm verifier")?;
+
+    if !output.status.success() {
+        miette::bail!(
+            "could not build the wasm verifier (is the target installed? `rustup target add {WASM_TARGET}`):\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let wasm_path = build_dir
+        .join("target")
+        .join(WASM_TARGET)
+        .join("release")
+        .join("noname_groth16_verifier.wasm");
+    std::fs::read(&wasm_path)
+        .into_diagnostic()
+        .wrap_err(format!("could not read the wasm verifier at `{wasm_path}`"))
+}
+
+impl CompiledCircuit<R1CS<R1csBn254Field>> {
+    /// Returns the JS wrapper of the wasm verifier, for this circuit and the verifying key of its setup.
+    pub fn groth16_js_verifier(&self, verifier_index: &VerifierIndex) -> miette::Result<String> {
+        // the key has a point per public input (plus one for the constant)
+        let r1cs = &self.circuit.backend;
+        if verifier_index.verifying_key.gamma_abc_g1.len() != r1cs.public_var_indices().len() + 1 {
+            miette::bail!(
+                "groth16: the verifying key was not produced by the setup of this circuit"
+            );
+        }
+
+        let main_sig = match &self.main_info().kind {
+            FnKind::BuiltIn(_, _) => unreachable!(),
+            FnKind::Native(fn_sig) => &fn_sig.sig,
+        };
+        let public_output = match &main_sig.return_type {
+            Some(typ) => self.js_type(&typ.kind),
+            None => Value::Null,
+        };
+        let public_arguments: Vec<_> = main_sig
+            .arguments
+            .iter()
+            .filter(|arg| arg.is_public())
+            .map(|arg| json!([arg.name.value, self.js_type(&arg.typ.kind)]))
+            .collect();
+
+        let verifying_key: String = to_bytes(&verifier_index.verifying_key)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+
+        Ok(VERIFIER_JS
+            .replace("{{VERIFYING_KEY}}", &verifying_key)
+            .replace("{{PUBLIC_OUTPUT}}", &public_output.to_string())
+            .replace(
+                "{{PUBLIC_ARGUMENTS}}",
+                &Value::Array(public_arguments).to_string(),
+            ))
+    }
+
+    /// Describes a type of the main function to the JS wrapper, which flattens values of this type into field elements.
+    fn js_type(&self, typ: &TyKind) -> Value {
+        match typ {
+            TyKind::Field => json!("field"),
+            TyKind::Bool => json!("bool"),
+            TyKind::I64 => json!("i64"),
+            TyKind::U8 | TyKind::U16 | TyKind::U32 | TyKind::U64 => {
+                json!({ "uint": typ.uint_bits() })
+            }
+            TyKind::Array(elem, size) => json!({ "array": [self.js_type(elem), size] }),
+            TyKind::Custom { module, name } => {
+                let qualified = FullyQualified::new(module, name);
+                let struct_info = self
+                    .circuit
+                    .struct_info(&qualified)
+                    .expect("compiler bug: couldn't find the struct of a main argument");
+                let fields: Vec<_> = struct_info
+                    .fields
+                    .iter()
+                    .map(|(name, typ)| json!([name, self.js_type(typ)]))
+                    .collect();
+                json!({ "struct": fields })
+            }
+            TyKind::BigInt | TyKind::GenericArray(..) => {
+                unreachable!("the main function can't have generic or constant arguments")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        backends::r1cs::{R1csBn254Field, R1CS},
+        tests::compile_with_sources,
+    };
+
+    const CODE: &str = "
+struct Point {
+    xx: Field,
+    yy: Field,
+}
+
+fn main(pub point: Point, pub flags: [Bool; 2], secret: Field) -> Field {
+    let res = point.xx * secret;
+    assert(flags[0]);
+    return res + point.yy;
+}
+";
+
+    #[test]
+    fn test_js_verifier() -> miette::Result<()> {
+        let (compiled_circuit, _) = compile_with_sources(CODE, R1CS::<R1csBn254Field>::new())?;
+        let (prover_index, verifier_index) = compiled_circuit.compile_to_groth16_indexes()?;
+        let js = prover_index
+            .compiled_circuit
+            .groth16_js_verifier(&verifier_index)?;
+
+        assert!(js.contains(r#"const PUBLIC_OUTPUT = "field";"#));
+        assert!(js.contains(
+            r#"const PUBLIC_ARGUMENTS = [["point",{"struct":[["xx","field"],["yy","field"]]}],["flags",{"array":["bool",2]}]];"#
+        ));
+        assert!(!js.contains("{{"));
+
+        Ok(())
+    }
+}
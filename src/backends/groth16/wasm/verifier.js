@@ -0,0 +1,147 @@
+// Verifies Groth16 proofs of a noname circuit in the browser (or in node).
+// Written by `noname groth16 wasm-verifier`: regenerate it after changing the circuit or running a new setup.
+//
+//     const verifier = await loadVerifier();
+//     const publicInputs = encodePublicInputs(publicOutput, { xx: "2" });
+//     const valid = verifier.verify(proofBytes, publicInputs);
+
+// the scalar field of bn254
+const MODULUS = 21888242871839275222246405745257275088548364400416034343698204186575808495617n;
+
+// the verifying key, serialized with arkworks
+const VERIFYING_KEY = hexToBytes("{{VERIFYING_KEY}}");
+
+// the types of the public output and of the public arguments of `main`, in the order of the verifier
+const PUBLIC_OUTPUT = {{PUBLIC_OUTPUT}};
+const PUBLIC_ARGUMENTS = {{PUBLIC_ARGUMENTS}};
+
+function hexToBytes(hex) {
+  const bytes = new Uint8Array(hex.length / 2);
+  for (let i = 0; i < bytes.length; i++) {
+    bytes[i] = parseInt(hex.slice(2 * i, 2 * i + 2), 16);
+  }
+  return bytes;
+}
+
+function toBigInt(value, what) {
+  try {
+    return BigInt(value);
+  } catch (_) {
+    throw new Error(`${what}: expected an integer, got \`${value}\``);
+  }
+}
+
+// flattens a value into field elements, as the JSON inputs of noname are parsed
+function flatten(ty, value, what, out) {
+  if (ty === "field") {
+    const element = toBigInt(value, what);
+    if (element < 0n || element >= MODULUS) {
+      throw new Error(`${what}: \`${value}\` is not a field element`);
+    }
+    out.push(element);
+  } else if (ty === "bool") {
+    if (typeof value !== "boolean") {
+      throw new Error(`${what}: expected a boolean`);
+    }
+    out.push(value ? 1n : 0n);
+  } else if (ty === "i64") {
+    const integer = toBigInt(value, what);
+    if (integer < -(1n << 63n) || integer >= 1n << 63n) {
+      throw new Error(`${what}: \`${value}\` doesn't fit in an i64`);
+    }
+    out.push(integer < 0n ? MODULUS + integer : integer);
+  } else if (ty.uint !== undefined) {
+    const integer = toBigInt(value, what);
+    if (integer < 0n || integer >= 1n << BigInt(ty.uint)) {
+      throw new Error(`${what}: \`${value}\` doesn't fit in ${ty.uint} bits`);
+    }
+    out.push(integer);
+  } else if (ty.array !== undefined) {
+    const [element, size] = ty.array;
+    if (!Array.isArray(value) || value.length !== size) {
+      throw new Error(`${what}: expected an array of ${size} elements`);
+    }
+    value.forEach((item, i) => flatten(element, item, `${what}[${i}]`, out));
+  } else {
+    for (const [name, field] of ty.struct) {
+      if (value === null || typeof value !== "object" || !(name in value)) {
+        throw new Error(`${what}: missing the field \`${name}\``);
+      }
+      flatten(field, value[name], `${what}.${name}`, out);
+    }
+  }
+}
+
+function encodeElements(elements) {
+  const bytes = new Uint8Array(32 * elements.length);
+  elements.forEach((element, i) => {
+    for (let j = 0; j < 32; j++) {
+      bytes[32 * i + j] = Number((element >> BigInt(8 * j)) & 0xffn);
+    }
+  });
+  return bytes;
+}
+
+// Encodes the public inputs of the verifier: the public output returned by `main` (if any),
+// then its public arguments given as an object (e.g. `{ xx: "2" }`), with the same values as the JSON inputs of noname.
+export function encodePublicInputs(publicOutput, publicArguments) {
+  const elements = [];
+  if (PUBLIC_OUTPUT !== null) {
+    flatten(PUBLIC_OUTPUT, publicOutput, "public output", elements);
+  }
+  for (const [name, ty] of PUBLIC_ARGUMENTS) {
+    if (!(name in publicArguments)) {
+      throw new Error(`missing the public argument \`${name}\``);
+    }
+    flatten(ty, publicArguments[name], name, elements);
+  }
+  return encodeElements(elements);
+}
+
+// Encodes the public inputs written by `noname groth16 prove` (the decimal strings of `public.json`).
+export function encodePublicJson(publicJson) {
+  const elements = publicJson.map((value, i) => {
+    const element = toBigInt(value, `public input ${i}`);
+    if (element < 0n || element >= MODULUS) {
+      throw new Error(`public input ${i}: \`${value}\` is not a field element`);
+    }
+    return element;
+  });
+  return encodeElements(elements);
+}
+
+// Instantiates the wasm verifier, from a URL (by default `verifier.wasm` next to this file) or from its bytes.
+export async function loadVerifier(source = new URL("./verifier.wasm", import.meta.url)) {
+  const bytes = source instanceof URL || typeof source === "string"
+    ? await (await fetch(source)).arrayBuffer()
+    : source;
+  const { instance } = await WebAssembly.instantiate(bytes, {});
+  const wasm = instance.exports;
+
+  const copy = (data) => {
+    const ptr = wasm.alloc(data.length);
+    new Uint8Array(wasm.memory.buffer, ptr, data.length).set(data);
+    return ptr;
+  };
+
+  return {
+    // Verifies a proof (the bytes of `proof.groth16`) against encoded public inputs.
+    verify(proof, publicInputs) {
+      const buffers = [VERIFYING_KEY, proof, publicInputs];
+      const ptrs = buffers.map(copy);
+      try {
+        const res = wasm.verify(
+          ptrs[0], VERIFYING_KEY.length,
+          ptrs[1], proof.length,
+          ptrs[2], publicInputs.length,
+        );
+        if (res < 0) {
+          throw new Error("malformed proof or public inputs");
+        }
+        return res === 1;
+      } finally {
+        ptrs.forEach((ptr, i) => wasm.dealloc(ptr, buffers[i].length));
+      }
+    },
+  };
+}
@@ -0,0 +1,65 @@
+//! The wasm verifier of Groth16 proofs over bn254, written by `noname groth16 wasm-verifier`.
+//!
+//! It is built for `wasm32-unknown-unknown` without wasm-bindgen, to stay small:
+//! the JS wrapper copies the verifying key, the proof, and the public inputs (32-byte little-endian field elements)
+//! into buffers obtained with `alloc`, and calls `verify`.
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{prepare_verifying_key, verify_proof, Proof, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+
+#[no_mangle]
+pub extern "C" fn alloc(len: usize) -> *mut u8 {
+    let mut buffer = Vec::<u8>::with_capacity(len);
+    let ptr = buffer.as_mut_ptr();
+    std::mem::forget(buffer);
+    ptr
+}
+
+/// # Safety
+///
+/// The buffer must have been returned by `alloc` with the same length.
+#[no_mangle]
+pub unsafe extern "C" fn dealloc(ptr: *mut u8, len: usize) {
+    drop(Vec::from_raw_parts(ptr, 0, len));
+}
+
+/// Returns 1 if the proof is valid, 0 if it is invalid, and -1 if the inputs are malformed.
+///
+/// # Safety
+///
+/// The buffers must have been returned by `alloc` with the same lengths, and filled.
+#[no_mangle]
+pub unsafe extern "C" fn verify(
+    vk_ptr: *const u8,
+    vk_len: usize,
+    proof_ptr: *const u8,
+    proof_len: usize,
+    inputs_ptr: *const u8,
+    inputs_len: usize,
+) -> i32 {
+    let vk = std::slice::from_raw_parts(vk_ptr, vk_len);
+    let proof = std::slice::from_raw_parts(proof_ptr, proof_len);
+    let inputs = std::slice::from_raw_parts(inputs_ptr, inputs_len);
+
+    match verify_bytes(vk, proof, inputs) {
+        Some(true) => 1,
+        Some(false) => 0,
+        None => -1,
+    }
+}
+
+fn verify_bytes(vk: &[u8], proof: &[u8], inputs: &[u8]) -> Option<bool> {
+    let vk = VerifyingKey::<Bn254>::deserialize(vk).ok()?;
+    let proof = Proof::<Bn254>::deserialize(proof).ok()?;
+    if inputs.len() % 32 != 0 {
+        return None;
+    }
+    let inputs = inputs
+        .chunks(32)
+        .map(Fr::deserialize)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+
+    verify_proof(&prepare_verifying_key(&vk), &proof, &inputs).ok()
+}
@@ -0,0 +1,580 @@
+//! halo2 proofs (with KZG commitments over bn256), for circuits written with the R1CS backend over bn254 (see [R1CS]).
+//!
+//! The compiled constraints are lowered to the standard PLONK gate
+//! `q_l a + q_r b + q_o c + q_m a b + q_c = 0`, with three advice columns and five fixed columns.
+//! A simple layouter writes all of them in a single region, row after row:
+//!
+//! - a linear combination takes one row per term after the first one (and at least one row),
+//!   which accumulates the terms in the `c` column,
+//! - a constraint `a * b = c` then takes one more row, which copies the three accumulators and multiplies them.
+//!
+//! Each variable of the circuit is copied (with the permutation argument) from the first cell it was assigned to,
+//! and the public outputs and the public inputs are copied to the instance column (in this order, as for [super::groth16]).
+//!
+//! The circuit ([Halo2Circuit]) can be used with the rest of the halo2 tooling,
+//! and the parameters can come from a trusted setup (see [CompiledCircuit::compile_to_halo2_indexes_with]).
+
+use std::collections::HashMap;
+
+use ark_ff::{BigInteger, PrimeField};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Advice, Circuit, Column,
+        ConstraintSystem, Error, Fixed, Instance, ProvingKey, VerifyingKey,
+    },
+    poly::{
+        commitment::{Params, ParamsProver},
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverSHPLONK, VerifierSHPLONK},
+            strategy::SingleStrategy,
+        },
+        Rotation,
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+};
+use itertools::Itertools as _;
+use rand::rngs::OsRng;
+
+use crate::{
+    backends::r1cs::{LinearCombination, R1csBn254Field, R1CS},
+    compiler::{generate_witness, Sources},
+    inputs::JsonInputs,
+    witness::CompiledCircuit,
+};
+
+/// The rows at the end of the domain that can't be used by the circuit (for the blinding factors of halo2).
+const UNUSABLE_ROWS: usize = 6;
+
+/// The smallest size of the domain (`2^MIN_K` rows).
+const MIN_K: u32 = 4;
+
+//
+// Data Structures
+//
+
+pub struct ProverIndex {
+    params: ParamsKZG<Bn256>,
+    proving_key: ProvingKey<G1Affine>,
+    compiled_circuit: CompiledCircuit<R1CS<R1csBn254Field>>,
+}
+
+pub struct VerifierIndex {
+    params: ParamsKZG<Bn256>,
+    verifying_key: VerifyingKey<G1Affine>,
+}
+
+/// The columns of the standard PLONK gate.
+#[derive(Clone, Debug)]
+pub struct Halo2Config {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    c: Column<Advice>,
+    q_l: Column<Fixed>,
+    q_r: Column<Fixed>,
+    q_o: Column<Fixed>,
+    q_m: Column<Fixed>,
+    q_c: Column<Fixed>,
+    instance: Column<Instance>,
+}
+
+/// The compiled constraints, with the witness when proving (and without it during the setup).
+#[derive(Clone)]
+pub struct Halo2Circuit<'a> {
+    r1cs: &'a R1CS<R1csBn254Field>,
+    witness: Option<&'a [R1csBn254Field]>,
+}
+
+impl<'a> Halo2Circuit<'a> {
+    pub fn new(r1cs: &'a R1CS<R1csBn254Field>, witness: Option<&'a [R1csBn254Field]>) -> Self {
+        Self { r1cs, witness }
+    }
+
+    /// Returns the number of rows used by the circuit.
+    pub fn num_rows(&self) -> usize {
+        // a linear combination takes one row per term after the first one (and at least one row),
+        // and the multiplication takes one more row
+        self.r1cs
+            .constraints()
+            .iter()
+            .map(|constraint| {
+                [&constraint.a, &constraint.b, &constraint.c]
+                    .iter()
+                    .map(|lc| lc.terms.len().max(2) - 1)
+                    .sum::<usize>()
+                    + 1
+            })
+            .sum()
+    }
+
+    /// Returns the smallest `k` such that the circuit fits in `2^k` rows.
+    pub fn k(&self) -> u32 {
+        let rows = self.num_rows() + UNUSABLE_ROWS;
+        rows.next_power_of_two().trailing_zeros().max(MIN_K)
+    }
+}
+
+impl Circuit<Fr> for Halo2Circuit<'_> {
+    type Config = Halo2Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            r1cs: self.r1cs,
+            witness: None,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let config = Halo2Config {
+            a: meta.advice_column(),
+            b: meta.advice_column(),
+            c: meta.advice_column(),
+            q_l: meta.fixed_column(),
+            q_r: meta.fixed_column(),
+            q_o: meta.fixed_column(),
+            q_m: meta.fixed_column(),
+            q_c: meta.fixed_column(),
+            instance: meta.instance_column(),
+        };
+
+        meta.enable_equality(config.a);
+        meta.enable_equality(config.b);
+        meta.enable_equality(config.c);
+        meta.enable_equality(config.instance);
+
+        meta.create_gate("standard", |meta| {
+            let a = meta.query_advice(config.a, Rotation::cur());
+            let b = meta.query_advice(config.b, Rotation::cur());
+            let c = meta.query_advice(config.c, Rotation::cur());
+            let q_l = meta.query_fixed(config.q_l, Rotation::cur());
+            let q_r = meta.query_fixed(config.q_r, Rotation::cur());
+            let q_o = meta.query_fixed(config.q_o, Rotation::cur());
+            let q_m = meta.query_fixed(config.q_m, Rotation::cur());
+            let q_c = meta.query_fixed(config.q_c, Rotation::cur());
+
+            vec![q_l * a.clone() + q_r * b.clone() + q_o * c + q_m * a * b + q_c]
+        });
+
+        config
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let public_cells = layouter.assign_region(
+            || "r1cs",
+            |region| {
+                let mut layout = RegionLayout {
+                    region,
+                    config: &config,
+                    witness: self.witness,
+                    row: 0,
+                    vars: HashMap::new(),
+                };
+
+                for constraint in self.r1cs.constraints() {
+                    let a = layout.linear_combination(&constraint.a)?;
+                    let b = layout.linear_combination(&constraint.b)?;
+                    let c = layout.linear_combination(&constraint.c)?;
+                    layout.mul(&a, &b, &c)?;
+                }
+
+                // every variable ends up in a constraint (see the finalization of the R1CS backend)
+                let public_cells: Vec<_> = self
+                    .r1cs
+                    .public_var_indices()
+                    .into_iter()
+                    .map(|index| layout.vars[&index].clone())
+                    .collect();
+                Ok(public_cells)
+            },
+        )?;
+
+        for (row, cell) in public_cells.iter().enumerate() {
+            layouter.constrain_instance(cell.cell(), config.instance, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An operand of a row.
+enum Operand<'a> {
+    /// A variable of the R1CS circuit.
+    Var(usize),
+
+    /// A cell assigned in a previous row.
+    Cell(&'a AssignedCell<Fr, Fr>),
+
+    /// An unused operand, set to zero.
+    Unused,
+}
+
+/// Writes the rows of the circuit in a single region.
+struct RegionLayout<'r, 'a> {
+    region: Region<'r, Fr>,
+    config: &'a Halo2Config,
+    witness: Option<&'a [R1csBn254Field]>,
+
+    /// The next free row.
+    row: usize,
+
+    /// The first cell assigned to each variable of the R1CS circuit.
+    vars: HashMap<usize, AssignedCell<Fr, Fr>>,
+}
+
+impl RegionLayout<'_, '_> {
+    /// Returns a cell containing the value of a linear combination.
+    fn linear_combination(
+        &mut self,
+        lc: &LinearCombination<R1csBn254Field>,
+    ) -> Result<AssignedCell<Fr, Fr>, Error> {
+        let mut terms = lc
+            .terms
+            .iter()
+            .sorted_by_key(|(var, _)| var.index)
+            .map(|(var, factor)| (var.index, to_halo2(factor)));
+
+        // the first row computes the first two terms plus the constant
+        let (lhs, lhs_factor) = terms
+            .next()
+            .map_or((Operand::Unused, Fr::ZERO), |(index, factor)| {
+                (Operand::Var(index), factor)
+            });
+        let (rhs, rhs_factor) = terms
+            .next()
+            .map_or((Operand::Unused, Fr::ZERO), |(index, factor)| {
+                (Operand::Var(index), factor)
+            });
+        let mut acc = self.add(lhs, lhs_factor, rhs, rhs_factor, to_halo2(&lc.constant))?;
+
+        // the next rows add one term to the accumulator
+        for (index, factor) in terms {
+            acc = self.add(
+                Operand::Cell(&acc),
+                Fr::ONE,
+                Operand::Var(index),
+                factor,
+                Fr::ZERO,
+            )?;
+        }
+
+        Ok(acc)
+    }
+
+    /// Assigns a row `c = lhs_factor * lhs + rhs_factor * rhs + constant`, and returns `c`.
+    fn add(
+        &mut self,
+        lhs: Operand,
+        lhs_factor: Fr,
+        rhs: Operand,
+        rhs_factor: Fr,
+        constant: Fr,
+    ) -> Result<AssignedCell<Fr, Fr>, Error> {
+        let a = self.assign(self.config.a, lhs)?;
+        let b = self.assign(self.config.b, rhs)?;
+
+        let value = a
+            .value()
+            .zip(b.value())
+            .map(|(a, b)| lhs_factor * a + rhs_factor * b + constant);
+        let c = self
+            .region
+            .assign_advice(|| "c", self.config.c, self.row, || value)?;
+
+        self.selectors(lhs_factor, rhs_factor, -Fr::ONE, Fr::ZERO, constant)?;
+        self.row += 1;
+
+        Ok(c)
+    }
+
+    /// Assigns a row `a * b = c`, where the three cells were assigned in previous rows.
+    fn mul(
+        &mut self,
+        a: &AssignedCell<Fr, Fr>,
+        b: &AssignedCell<Fr, Fr>,
+        c: &AssignedCell<Fr, Fr>,
+    ) -> Result<(), Error> {
+        self.assign(self.config.a, Operand::Cell(a))?;
+        self.assign(self.config.b, Operand::Cell(b))?;
+        self.assign(self.config.c, Operand::Cell(c))?;
+
+        self.selectors(Fr::ZERO, Fr::ZERO, -Fr::ONE, Fr::ONE, Fr::ZERO)?;
+        self.row += 1;
+
+        Ok(())
+    }
+
+    /// Assigns an operand to a column of the current row, with the copy constraints it needs.
+    fn assign(
+        &mut self,
+        column: Column<Advice>,
+        operand: Operand,
+    ) -> Result<AssignedCell<Fr, Fr>, Error> {
+        match operand {
+            Operand::Var(index) => {
+                let value = match self.witness {
+                    Some(witness) => Value::known(to_halo2(&witness[index])),
+                    None => Value::unknown(),
+                };
+                let cell = self.region.assign_advice(
+                    || format!("var {index}"),
+                    column,
+                    self.row,
+                    || value,
+                )?;
+
+                match self.vars.get(&index) {
+                    Some(first) => self.region.constrain_equal(first.cell(), cell.cell())?,
+                    None => {
+                        self.vars.insert(index, cell.clone());
+                    }
+                }
+
+                Ok(cell)
+            }
+            Operand::Cell(cell) => cell.copy_advice(|| "copy", &mut self.region, column, self.row),
+            Operand::Unused => {
+                self.region
+                    .assign_advice(|| "unused", column, self.row, || Value::known(Fr::ZERO))
+            }
+        }
+    }
+
+    /// Assigns the fixed columns of the current row.
+    fn selectors(&mut self, q_l: Fr, q_r: Fr, q_o: Fr, q_m: Fr, q_c: Fr) -> Result<(), Error> {
+        let columns = [
+            (self.config.q_l, q_l),
+            (self.config.q_r, q_r),
+            (self.config.q_o, q_o),
+            (self.config.q_m, q_m),
+            (self.config.q_c, q_c),
+        ];
+        for (column, value) in columns {
+            self.region
+                .assign_fixed(|| "selector", column, self.row, || Value::known(value))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts an element of the scalar field of bn254 from arkworks to halo2.
+fn to_halo2(value: &R1csBn254Field) -> Fr {
+    let bytes: [u8; 32] = value
+        .into_repr()
+        .to_bytes_le()
+        .try_into()
+        .expect("the field has 32-byte elements");
+    Fr::from_bytes(&bytes).unwrap()
+}
+
+//
+// Setup
+//
+
+impl CompiledCircuit<R1CS<R1csBn254Field>> {
+    /// Generates the keys of this circuit, with KZG parameters sampled from the OS.
+    /// This is only meant for testing: the parameters of a real deployment come from a trusted setup.
+    pub fn compile_to_halo2_indexes(self) -> miette::Result<(ProverIndex, VerifierIndex)> {
+        let k = Halo2Circuit::new(&self.circuit.backend, None).k();
+        let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+        self.compile_to_halo2_indexes_with(params)
+    }
+
+    /// Generates the keys of this circuit, with the given KZG parameters (e.g. from a powers-of-tau ceremony),
+    /// which are downsized to the size of the circuit.
+    pub fn compile_to_halo2_indexes_with(
+        self,
+        mut params: ParamsKZG<Bn256>,
+    ) -> miette::Result<(ProverIndex, VerifierIndex)> {
+        let circuit = Halo2Circuit::new(&self.circuit.backend, None);
+        let k = circuit.k();
+        if params.k() < k {
+            miette::bail!(
+                "halo2: the circuit needs parameters for 2^{k} rows, but the given ones only support 2^{}",
+                params.k()
+            );
+        }
+        params.downsize(k);
+
+        let verifying_key = keygen_vk(&params, &circuit).map_err(|err| {
+            miette::miette!("halo2: could not generate the verifying key: {err:?}")
+        })?;
+        let proving_key = keygen_pk(&params, verifying_key.clone(), &circuit)
+            .map_err(|err| miette::miette!("halo2: could not generate the proving key: {err:?}"))?;
+
+        let verifier_index = VerifierIndex {
+            params: params.clone(),
+            verifying_key,
+        };
+        let prover_index = ProverIndex {
+            params,
+            proving_key,
+            compiled_circuit: self,
+        };
+
+        Ok((prover_index, verifier_index))
+    }
+}
+
+//
+// Proving
+//
+
+impl ProverIndex {
+    /// returns a proof, the public inputs of the verifier, and the public output
+    #[allow(clippy::type_complexity)]
+    pub fn prove(
+        &self,
+        sources: &Sources,
+        public_inputs: JsonInputs,
+        private_inputs: JsonInputs,
+    ) -> miette::Result<(Vec<u8>, Vec<R1csBn254Field>, Vec<R1csBn254Field>)> {
+        // generate the witness (this also checks the constraints)
+        let generated_witness = generate_witness(
+            &self.compiled_circuit,
+            sources,
+            public_inputs,
+            private_inputs,
+        )?;
+
+        let r1cs = &self.compiled_circuit.circuit.backend;
+        let full_public_inputs: Vec<_> = r1cs
+            .public_var_indices()
+            .into_iter()
+            .map(|index| generated_witness.witness[index])
+            .collect();
+        let instance: Vec<_> = full_public_inputs.iter().map(to_halo2).collect();
+
+        // create proof
+        let circuit = Halo2Circuit::new(r1cs, Some(&generated_witness.witness));
+        let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+        create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+            &self.params,
+            &self.proving_key,
+            &[circuit],
+            &[&[&instance[..]]],
+            OsRng,
+            &mut transcript,
+        )
+        .map_err(|err| {
+            miette::miette!("halo2: could not create a proof with the given inputs: {err:?}")
+        })?;
+
+        Ok((
+            transcript.finalize(),
+            full_public_inputs,
+            generated_witness.outputs,
+        ))
+    }
+}
+
+//
+// Verifying
+//
+
+impl VerifierIndex {
+    /// Verifies a proof, given the public outputs followed by the public inputs.
+    pub fn verify(
+        &self,
+        full_public_inputs: &[R1csBn254Field],
+        proof: &[u8],
+    ) -> miette::Result<()> {
+        let instance: Vec<_> = full_public_inputs.iter().map(to_halo2).collect();
+
+        let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof);
+        verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+            self.params.verifier_params(),
+            &self.verifying_key,
+            SingleStrategy::new(&self.params),
+            &[&[&instance[..]]],
+            &mut transcript,
+        )
+        .map_err(|err| miette::miette!("halo2: failed to verify the proof: {err:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+
+    use crate::{
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS, WRONG_PRIVATE_INPUTS},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_prove_and_verify() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new())?;
+
+        // the lowered circuit is satisfied by the witness
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(PRIVATE_INPUTS)?;
+        let generated_witness = generate_witness(
+            &compiled_circuit,
+            &sources,
+            public_inputs.clone(),
+            private_inputs.clone(),
+        )?;
+        let r1cs = &compiled_circuit.circuit.backend;
+        let circuit = Halo2Circuit::new(r1cs, Some(&generated_witness.witness));
+        let instance: Vec<_> = r1cs
+            .public_var_indices()
+            .into_iter()
+            .map(|index| to_halo2(&generated_witness.witness[index]))
+            .collect();
+        let prover = MockProver::run(circuit.k(), &circuit, vec![instance.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // but not with a tampered public input
+        let mut tampered_instance = instance;
+        let last = tampered_instance.len() - 1;
+        tampered_instance[last] = to_halo2(&R1csBn254Field::from(3u64));
+        let prover = MockProver::run(circuit.k(), &circuit, vec![tampered_instance]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // and proofs verify
+        let (prover_index, verifier_index) = compiled_circuit.compile_to_halo2_indexes()?;
+        let (proof, full_public_inputs, public_output) =
+            prover_index.prove(&sources, public_inputs, private_inputs)?;
+        assert_eq!(public_output, vec![R1csBn254Field::from(8u64)]);
+        verifier_index.verify(&full_public_inputs, &proof)?;
+
+        // a wrong public output is rejected
+        let wrong_inputs = [R1csBn254Field::from(9u64), R1csBn254Field::from(2u64)];
+        assert!(verifier_index.verify(&wrong_inputs, &proof).is_err());
+
+        // so is a tampered public input
+        let tampered_inputs = [R1csBn254Field::from(8u64), R1csBn254Field::from(3u64)];
+        assert!(verifier_index.verify(&tampered_inputs, &proof).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_wrong_witness() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new())?;
+        let (prover_index, _) = compiled_circuit.compile_to_halo2_indexes()?;
+
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(WRONG_PRIVATE_INPUTS)?;
+        assert!(prover_index
+            .prove(&sources, public_inputs, private_inputs)
+            .is_err());
+
+        Ok(())
+    }
+}
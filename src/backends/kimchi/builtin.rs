@@ -5,7 +5,7 @@ use kimchi::circuits::polynomials::poseidon::{POS_ROWS_PER_HASH, ROUNDS_PER_ROW}
 use kimchi::mina_poseidon::constants::{PlonkSpongeConstantsKimchi, SpongeConstants};
 use kimchi::mina_poseidon::permutation::full_round;
 
-use super::{KimchiCellVar, KimchiVesta, VestaField};
+use super::{Kimchi, KimchiCellVar, PastaCurve};
 use crate::backends::kimchi::NUM_REGISTERS;
 use crate::backends::Backend;
 
@@ -17,11 +17,11 @@ use crate::{
     var::{ConstOrCell, Value, Var},
 };
 
-pub fn poseidon(
-    compiler: &mut CircuitWriter<KimchiVesta>,
-    vars: &[VarInfo<VestaField, KimchiCellVar>],
+pub fn poseidon<C: PastaCurve>(
+    compiler: &mut CircuitWriter<Kimchi<C>>,
+    vars: &[VarInfo<C::Field, KimchiCellVar>],
     span: Span,
-) -> Result<Option<Var<VestaField, KimchiCellVar>>> {
+) -> Result<Option<Var<C::Field, KimchiCellVar>>> {
     //
     // sanity checks
     //
@@ -67,7 +67,7 @@ pub fn poseidon(
     }
 
     // get constants needed for poseidon
-    let poseidon_params = kimchi::mina_poseidon::pasta::fp_kimchi::params();
+    let poseidon_params = C::poseidon_params();
 
     let rc = &poseidon_params.round_constants;
     let width = PlonkSpongeConstantsKimchi::SPONGE_WIDTH;
@@ -75,7 +75,7 @@ pub fn poseidon(
     // pad the input (for the capacity)
     let zero_var = compiler.backend.add_constant(
         Some("encoding constant 0 for the capacity of poseidon"),
-        VestaField::zero(),
+        C::Field::zero(),
         span,
     );
     cells.push(zero_var);
@@ -105,8 +105,8 @@ pub fn poseidon(
                         let mut acc = vec![x1, x2, x3];
 
                         // Do one full round on the previous value
-                        full_round::<VestaField, PlonkSpongeConstantsKimchi>(
-                            &kimchi::mina_poseidon::pasta::fp_kimchi::params(),
+                        full_round::<C::Field, PlonkSpongeConstantsKimchi>(
+                            &C::poseidon_params(),
                             &mut acc,
                             offset + i,
                         );
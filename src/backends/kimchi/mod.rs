@@ -1,20 +1,33 @@
 pub mod asm;
 pub mod builtin;
 pub mod prover;
+pub mod srs;
 
 use educe::Educe;
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt::Write,
+    marker::PhantomData,
     ops::Neg as _,
+    sync::Arc,
 };
 
 use itertools::{izip, Itertools};
-use kimchi::circuits::polynomials::generic::{GENERIC_COEFFS, GENERIC_REGISTERS};
+use kimchi::{
+    circuits::{
+        polynomials::generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+        wires::PERMUTS,
+    },
+    mina_curves::pasta::{Pallas, Vesta},
+    mina_poseidon::{
+        pasta::{fp_kimchi, fq_kimchi},
+        poseidon::ArithmeticSpongeParams,
+    },
+};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    backends::kimchi::asm::parse_coeffs,
+    backends::{kimchi::asm::parse_coeffs, Capabilities, CustomGate},
     circuit_writer::{
         writer::{AnnotatedCell, Cell, PendingGate},
         DebugInfo, Gate, GateKind, Wiring,
@@ -23,39 +36,95 @@ use crate::{
     constants::Span,
     error::{Error, ErrorKind, Result},
     helpers::PrettyField,
-    var::{Value, Var},
+    var::{ConstOrCell, Value, Var},
     witness::WitnessEnv,
 };
 
 use ark_ff::{One, Zero};
+use num_bigint::BigUint;
 
 use self::asm::{extract_vars_from_coeffs, OrderedHashSet};
 
-/// We use the scalar field of Vesta as our circuit field.
+/// The scalar field of Vesta, which is the circuit field of [KimchiVesta].
 pub type VestaField = kimchi::mina_curves::pasta::Fp;
 
+/// The scalar field of Pallas, which is the circuit field of [KimchiPallas].
+pub type PallasField = kimchi::mina_curves::pasta::Fq;
+
 /// Number of columns in the execution trace.
 pub const NUM_REGISTERS: usize = kimchi::circuits::wires::COLUMNS;
 
+/// Number of bits XORed by a single `Xor16` gate, as 4 lookups of 4 bits.
+const XOR_BITS_PER_ROW: usize = 16;
+
+/// The name of the custom gate that XORs two values of 16 bits (see [Backend::custom_gates]).
+pub const XOR16_GATE: &str = "xor16";
+
+/// The ID of the first lookup table of the circuit,
+/// as kimchi reserves the first IDs for its own tables (XOR and range checks).
+pub(crate) const FIRST_LOOKUP_TABLE_ID: i32 = 2;
+
 use super::{Backend, BackendField, BackendVar};
 
 impl BackendField for VestaField {}
+impl BackendField for PallasField {}
+
+/// A curve of the Pasta cycle, whose scalar field is the circuit field of the kimchi backend.
+/// As the scalar field of each curve is the base field of the other,
+/// a circuit over one curve can do the arithmetic of the other (to verify its proofs, for recursion).
+pub trait PastaCurve: Clone + 'static {
+    /// The scalar field of the curve.
+    type Field: BackendField;
+
+    /// The identifier of the backend over this curve, in verification keys, proof files, and the SRS cache.
+    const BACKEND_ID: &'static str;
+
+    /// The parameters of the Poseidon gate over the scalar field.
+    fn poseidon_params() -> ArithmeticSpongeParams<Self::Field>;
+}
+
+impl PastaCurve for Vesta {
+    type Field = VestaField;
+
+    const BACKEND_ID: &'static str = "kimchi-vesta";
+
+    fn poseidon_params() -> ArithmeticSpongeParams<VestaField> {
+        fp_kimchi::params()
+    }
+}
+
+impl PastaCurve for Pallas {
+    type Field = PallasField;
+
+    const BACKEND_ID: &'static str = "kimchi-pallas";
+
+    fn poseidon_params() -> ArithmeticSpongeParams<PallasField> {
+        fq_kimchi::params()
+    }
+}
+
+/// The kimchi backend over Vesta.
+pub type KimchiVesta = Kimchi<Vesta>;
+
+/// The kimchi backend over Pallas.
+pub type KimchiPallas = Kimchi<Pallas>;
 
 #[derive(Debug)]
-pub struct Witness(Vec<[VestaField; NUM_REGISTERS]>);
+pub struct Witness<F>(Vec<[F; NUM_REGISTERS]>);
 
 // TODO: refine this struct as full_public_inputs and public_outputs overlap with all_witness
-pub struct GeneratedWitness {
+pub struct GeneratedWitness<F> {
     /// contains all the witness values
-    pub all_witness: Witness,
+    pub all_witness: Witness<F>,
     /// contains the public inputs, which are also part of the all_witness
-    pub full_public_inputs: Vec<VestaField>,
+    pub full_public_inputs: Vec<F>,
     /// contains the public outputs, which are also part of the all_witness
-    pub public_outputs: Vec<VestaField>,
+    pub public_outputs: Vec<F>,
 }
 
+/// The kimchi backend, whose circuits are over the scalar field of the curve `C`.
 #[derive(Clone)]
-pub struct KimchiVesta {
+pub struct Kimchi<C: PastaCurve> {
     /// This is used to give a distinct number to each variable during circuit generation.
     pub(crate) next_variable: usize,
 
@@ -70,22 +139,24 @@ pub struct KimchiVesta {
 
     /// We cache the association between a constant and its _constrained_ variable,
     /// this is to avoid creating a new constraint every time we need to hardcode the same constant.
-    pub(crate) cached_constants: HashMap<VestaField, KimchiCellVar>,
+    pub(crate) cached_constants: HashMap<C::Field, KimchiCellVar>,
 
     /// The gates created by the circuit generation.
-    gates: Vec<Gate>,
+    gates: Vec<Gate<C::Field>>,
 
     /// The wiring of the circuit.
     /// It is created during circuit generation.
     pub(crate) wiring: HashMap<usize, Wiring>,
 
+    /// If set to true, two consecutive generic gates are packed in a single double generic gate
+    /// (which halves the number of rows of the generic gates).
     /// If set to false, a single generic gate will be used per double generic gate.
     /// This can be useful for debugging.
     pub(crate) double_generic_gate_optimization: bool,
 
     /// This is used to implement the double generic gate,
     /// which encodes two generic gates.
-    pub(crate) pending_generic_gate: Option<PendingGate>,
+    pub(crate) pending_generic_gate: Option<PendingGate<C::Field>>,
 
     /// A vector of debug information that maps to each row of the created circuit.
     pub(crate) debug_info: Vec<DebugInfo>,
@@ -100,11 +171,21 @@ pub struct KimchiVesta {
     /// Indexes used by the private inputs
     /// (this is useful to check that they appear in the circuit)
     pub(crate) private_input_cell_vars: Vec<KimchiCellVar>,
+
+    /// The constant tables of `(index, value)` pairs used by lookups,
+    /// whose IDs start at [FIRST_LOOKUP_TABLE_ID].
+    pub(crate) lookup_tables: Vec<Vec<(C::Field, C::Field)>>,
+
+    /// The equalities asserted with [Backend::assert_eq_vars],
+    /// which are enforced by the wiring once all the gates are known (see [Kimchi::wire_equalities]).
+    pub(crate) wired_equalities: Vec<(KimchiCellVar, KimchiCellVar, Span)>,
+
+    curve: PhantomData<C>,
 }
 
-impl Witness {
+impl<F: BackendField> Witness<F> {
     /// kimchi uses a transposed witness
-    pub fn to_kimchi_witness(&self) -> [Vec<VestaField>; NUM_REGISTERS] {
+    pub fn to_kimchi_witness(&self) -> [Vec<F>; NUM_REGISTERS] {
         let transposed = vec![Vec::with_capacity(self.0.len()); NUM_REGISTERS];
         let mut transposed: [_; NUM_REGISTERS] = transposed.try_into().unwrap();
         for row in &self.0 {
@@ -131,7 +212,7 @@ impl Witness {
     }
 }
 
-impl KimchiVesta {
+impl<C: PastaCurve> Kimchi<C> {
     pub fn new(double_generic_gate_optimization: bool) -> Self {
         Self {
             next_variable: 0,
@@ -146,6 +227,9 @@ impl KimchiVesta {
             finalized: false,
             public_input_size: 0,
             private_input_cell_vars: vec![],
+            lookup_tables: vec![],
+            wired_equalities: vec![],
+            curve: PhantomData,
         }
     }
 
@@ -155,7 +239,7 @@ impl KimchiVesta {
         note: &'static str,
         typ: GateKind,
         vars: Vec<Option<KimchiCellVar>>,
-        coeffs: Vec<VestaField>,
+        coeffs: Vec<C::Field>,
         span: Span,
     ) {
         // sanitize
@@ -208,12 +292,12 @@ impl KimchiVesta {
         &mut self,
         label: &'static str,
         mut vars: Vec<Option<KimchiCellVar>>,
-        mut coeffs: Vec<VestaField>,
+        mut coeffs: Vec<C::Field>,
         span: Span,
     ) {
         // padding
         let coeffs_padding = GENERIC_COEFFS.checked_sub(coeffs.len()).unwrap();
-        coeffs.extend(std::iter::repeat(VestaField::zero()).take(coeffs_padding));
+        coeffs.extend(std::iter::repeat(C::Field::zero()).take(coeffs_padding));
 
         let vars_padding = GENERIC_REGISTERS.checked_sub(vars.len()).unwrap();
         vars.extend(std::iter::repeat(None).take(vars_padding));
@@ -242,6 +326,299 @@ impl KimchiVesta {
             });
         }
     }
+
+    /// Adds the pending generic gate, if any, as a single generic gate.
+    fn flush_pending_generic_gate(&mut self) {
+        if let Some(pending) = self.pending_generic_gate.take() {
+            self.add_gate(
+                pending.label,
+                GateKind::DoubleGeneric,
+                pending.vars,
+                pending.coeffs,
+                pending.span,
+            );
+        }
+    }
+
+    /// Enforces the equalities asserted with [Backend::assert_eq_vars] by merging the wiring cycles of their vars,
+    /// which costs no row.
+    /// The vars that are not only in permutable columns (or not in any gate) are constrained with generic gates instead.
+    /// This must be called once all the other gates are added, as the merged vars lose their own wiring.
+    fn wire_equalities(&mut self) {
+        fn root(merged_into: &HashMap<usize, usize>, mut var: usize) -> usize {
+            while let Some(next) = merged_into.get(&var) {
+                var = *next;
+            }
+            var
+        }
+
+        let is_permutable = |wiring: Option<&Wiring>| match wiring {
+            None => false,
+            Some(Wiring::NotWired(cell)) => cell.cell.col < PERMUTS,
+            Some(Wiring::Wired(cells)) => cells.iter().all(|cell| cell.cell.col < PERMUTS),
+        };
+
+        // the var whose wiring cycle now contains the cells of a merged var
+        let mut merged_into = HashMap::new();
+        let mut unwired = vec![];
+
+        for (lhs, rhs, span) in self.wired_equalities.clone() {
+            let lhs_root = root(&merged_into, lhs.index);
+            let rhs_root = root(&merged_into, rhs.index);
+            if lhs_root == rhs_root {
+                continue;
+            }
+
+            if !is_permutable(self.wiring.get(&lhs_root))
+                || !is_permutable(self.wiring.get(&rhs_root))
+            {
+                unwired.push((lhs, rhs, span));
+                continue;
+            }
+
+            let mut cells = vec![];
+            for root in [lhs_root, rhs_root] {
+                match self.wiring.remove(&root) {
+                    Some(Wiring::NotWired(cell)) => cells.push(cell),
+                    Some(Wiring::Wired(wired)) => cells.extend(wired),
+                    None => unreachable!("the var is wired"),
+                }
+            }
+            self.wiring.insert(lhs_root, Wiring::Wired(cells));
+            merged_into.insert(rhs_root, lhs_root);
+        }
+
+        // the generic gates use the vars that hold the merged wiring
+        for (lhs, rhs, span) in unwired {
+            let lhs = KimchiCellVar::new(root(&merged_into, lhs.index), lhs.span);
+            let rhs = KimchiCellVar::new(root(&merged_into, rhs.index), rhs.span);
+            self.add_generic_gate(
+                "constrain lhs - rhs = 0 to assert that they are equal",
+                vec![Some(lhs), Some(rhs)],
+                vec![C::Field::one(), C::Field::one().neg()],
+                span,
+            );
+        }
+    }
+
+    /// Removes the generic gates that are identical to a previous one, up to the wiring
+    /// (for example, the same assertion written twice, or the same product computed twice and wired to be equal),
+    /// and packs the remaining single generic gates by two, which also removes their cells from the wiring cycles.
+    /// The other gates keep their order, as the `Poseidon` and `Xor16` gates constrain their next row,
+    /// and so do the rows of the public inputs.
+    fn optimize_generic_gates(&mut self) {
+        // the wiring cycle of each cell, to compare the vars of the gates up to their equalities
+        let mut cycle_of = BTreeMap::new();
+        for (var, wiring) in &self.wiring {
+            let cells = match wiring {
+                Wiring::NotWired(cell) => std::slice::from_ref(cell),
+                Wiring::Wired(cells) => cells.as_slice(),
+            };
+            for annotated_cell in cells {
+                cycle_of.insert(annotated_cell.cell, *var);
+            }
+        }
+
+        let gates = std::mem::take(&mut self.gates);
+        let witness_table = std::mem::take(&mut self.witness_table);
+        let debug_info = std::mem::take(&mut self.debug_info);
+
+        // the new position of the cells that are kept
+        let mut moved_cells = BTreeMap::new();
+        let mut seen_generic_gates = HashSet::new();
+
+        // the last row with a single generic gate, which can take the next one
+        let mut half_row: Option<usize> = None;
+
+        for (row, (gate, vars, debug_info)) in izip!(gates, witness_table, debug_info).enumerate() {
+            let after_chain = self
+                .gates
+                .last()
+                .is_some_and(|prev| matches!(prev.typ, GateKind::Poseidon | GateKind::Xor16));
+            let is_generic = matches!(gate.typ, GateKind::DoubleGeneric);
+            if row < self.public_input_size || !is_generic || after_chain {
+                let new_row = self.gates.len();
+                for col in 0..vars.len() {
+                    moved_cells.insert(Cell { row, col }, Cell { row: new_row, col });
+                }
+                if is_generic {
+                    half_row = None;
+                }
+                self.gates.push(gate);
+                self.witness_table.push(vars);
+                self.debug_info.push(debug_info);
+                continue;
+            }
+
+            let num_halves = if gate.coeffs.len() > GENERIC_COEFFS || vars.len() > GENERIC_REGISTERS
+            {
+                2
+            } else {
+                1
+            };
+            for half in 0..num_halves {
+                let first_col = half * GENERIC_REGISTERS;
+                let mut coeffs = gate
+                    .coeffs
+                    .iter()
+                    .skip(half * GENERIC_COEFFS)
+                    .take(GENERIC_COEFFS)
+                    .copied()
+                    .collect_vec();
+                coeffs.resize(GENERIC_COEFFS, C::Field::zero());
+                let half_vars = (first_col..first_col + GENERIC_REGISTERS)
+                    .map(|col| vars.get(col).copied().flatten())
+                    .collect_vec();
+
+                let cycles = (first_col..first_col + GENERIC_REGISTERS)
+                    .map(|col| cycle_of.get(&Cell { row, col }).copied())
+                    .collect_vec();
+                if !seen_generic_gates.insert((coeffs.clone(), cycles)) {
+                    continue;
+                }
+
+                let (new_row, new_first_col) = match half_row.take() {
+                    Some(half_row) => {
+                        self.gates[half_row].coeffs.extend(coeffs);
+                        self.witness_table[half_row].extend(half_vars);
+                        (half_row, GENERIC_REGISTERS)
+                    }
+                    None => {
+                        let new_row = self.gates.len();
+                        self.gates.push(Gate {
+                            typ: GateKind::DoubleGeneric,
+                            coeffs,
+                        });
+                        self.witness_table.push(half_vars);
+                        self.debug_info.push(debug_info.clone());
+                        half_row = Some(new_row);
+                        (new_row, 0)
+                    }
+                };
+                for offset in 0..GENERIC_REGISTERS {
+                    moved_cells.insert(
+                        Cell {
+                            row,
+                            col: first_col + offset,
+                        },
+                        Cell {
+                            row: new_row,
+                            col: new_first_col + offset,
+                        },
+                    );
+                }
+            }
+        }
+
+        // the cells of the removed gates leave the wiring
+        let wiring = std::mem::take(&mut self.wiring);
+        for (var, wiring) in wiring {
+            let cells = match wiring {
+                Wiring::NotWired(cell) => vec![cell],
+                Wiring::Wired(cells) => cells,
+            };
+            let mut cells = cells
+                .into_iter()
+                .filter_map(|AnnotatedCell { cell, debug }| {
+                    moved_cells
+                        .get(&cell)
+                        .map(|cell| AnnotatedCell { cell: *cell, debug })
+                })
+                .collect_vec();
+            let wiring = match cells.len() {
+                0 => continue,
+                1 => Wiring::NotWired(cells.remove(0)),
+                _ => Wiring::Wired(cells),
+            };
+            self.wiring.insert(var, wiring);
+        }
+    }
+
+    /// Constrains `out` to be the XOR of `lhs` and `rhs`, on `num_bits` bits (a multiple of 16),
+    /// with a chain of [GateKind::Xor16] gates.
+    fn constrain_xor(
+        &mut self,
+        lhs: &KimchiCellVar,
+        rhs: &KimchiCellVar,
+        out: &KimchiCellVar,
+        num_bits: usize,
+        span: Span,
+    ) {
+        // each row XORs 16 bits: its first columns hold the operands and the result shifted by the previous rows,
+        // and the other columns their next 4-bit chunks, which are looked up in the XOR table.
+        // The gate checks that the chunks and the first columns of the next row recompose the first columns.
+        // The zero constant is added first, as its generic gate can't be between the rows.
+        let zero = self.add_constant(Some("the remaining bits of a XOR"), C::Field::zero(), span);
+        for row in 0..num_bits / XOR_BITS_PER_ROW {
+            let offset = row * XOR_BITS_PER_ROW;
+            let operands = [*lhs, *rhs, *out];
+
+            let mut vars = Vec::with_capacity(NUM_REGISTERS);
+            for var in operands {
+                let shifted = if row == 0 {
+                    var
+                } else {
+                    self.new_internal_var(
+                        Value::Compute(
+                            vec![ConstOrCell::Cell(var)],
+                            Arc::new(move |vals: &[C::Field]| xor_chunk(vals[0], offset, None)),
+                        ),
+                        span,
+                    )
+                };
+                vars.push(Some(shifted));
+            }
+            for var in operands {
+                for nibble in 0..4 {
+                    let nibble = self.new_internal_var(
+                        Value::Compute(
+                            vec![ConstOrCell::Cell(var)],
+                            Arc::new(move |vals: &[C::Field]| {
+                                xor_chunk(vals[0], offset + 4 * nibble, Some(4))
+                            }),
+                        ),
+                        span,
+                    );
+                    vars.push(Some(nibble));
+                }
+            }
+
+            self.add_gate(
+                "XOR 16 bits with lookups",
+                GateKind::Xor16,
+                vars,
+                vec![],
+                span,
+            );
+        }
+
+        // nothing must remain after the last row
+        self.add_gate(
+            "constrain the XORed values to fit in the number of bits",
+            GateKind::Zero,
+            vec![Some(zero); 3],
+            vec![],
+            span,
+        );
+    }
+}
+
+/// Returns the XOR of two values, as the witness hint of [XOR16_GATE].
+fn xor_hint<F: BackendField>(vals: &[F]) -> Vec<F> {
+    let lhs: BigUint = vals[0].into();
+    let rhs: BigUint = vals[1].into();
+    vec![F::try_from(lhs ^ rhs).unwrap_or_else(|_| unreachable!("the result fits in the field"))]
+}
+
+/// Returns `len` bits of a field element starting at bit `offset`
+/// (or all the remaining bits if `len` is `None`), as laid out in the `Xor16` gates.
+fn xor_chunk<F: BackendField>(value: F, offset: usize, len: Option<usize>) -> F {
+    let value: BigUint = value.into();
+    let mut chunk = value >> offset;
+    if let Some(len) = len {
+        chunk &= (BigUint::from(1u8) << len) - 1u8;
+    }
+    F::try_from(chunk).unwrap_or_else(|_| unreachable!("the chunk fits in the field"))
 }
 
 #[derive(Default, Clone, Copy, Debug, Eq, Hash, Serialize, Deserialize, PartialEq, Ord, Educe)]
@@ -260,16 +637,16 @@ impl KimchiCellVar {
     }
 }
 
-impl Backend for KimchiVesta {
-    type Field = VestaField;
+impl<C: PastaCurve> Backend for Kimchi<C> {
+    type Field = C::Field;
     type Var = KimchiCellVar;
-    type GeneratedWitness = GeneratedWitness;
+    type GeneratedWitness = GeneratedWitness<C::Field>;
 
     fn poseidon() -> crate::imports::FnHandle<Self> {
         builtin::poseidon
     }
 
-    fn new_internal_var(&mut self, val: Value<KimchiVesta>, span: Span) -> KimchiCellVar {
+    fn new_internal_var(&mut self, val: Value<Self>, span: Span) -> KimchiCellVar {
         // create new var
         let var = KimchiCellVar::new(self.next_variable, span);
         self.next_variable += 1;
@@ -283,7 +660,7 @@ impl Backend for KimchiVesta {
     fn add_constant(
         &mut self,
         label: Option<&'static str>,
-        value: VestaField,
+        value: C::Field,
         span: Span,
     ) -> KimchiCellVar {
         if let Some(cvar) = self.cached_constants.get(&value) {
@@ -293,12 +670,12 @@ impl Backend for KimchiVesta {
         let var = self.new_internal_var(Value::Constant(value), span);
         self.cached_constants.insert(value, var);
 
-        let zero = VestaField::zero();
+        let zero = C::Field::zero();
 
         let _ = &self.add_generic_gate(
             label.unwrap_or("hardcode a constant"),
             vec![Some(var)],
-            vec![VestaField::one(), zero, zero, zero, value.neg()],
+            vec![C::Field::one(), zero, zero, zero, value.neg()],
             span,
         );
 
@@ -313,15 +690,11 @@ impl Backend for KimchiVesta {
     ) -> Result<()> {
         // TODO: the current tests pass even this is commented out. Add a test case for this one.
         // important: there might still be a pending generic gate
-        if let Some(pending) = self.pending_generic_gate.take() {
-            self.add_gate(
-                pending.label,
-                GateKind::DoubleGeneric,
-                pending.vars,
-                pending.coeffs,
-                pending.span,
-            );
-        }
+        self.flush_pending_generic_gate();
+
+        // the wiring of the equalities can only be done once no more gates use the vars
+        self.wire_equalities();
+        self.flush_pending_generic_gate();
 
         // for sanity check, we make sure that every cellvar created has ended up in a gate
         let mut written_vars = HashSet::new();
@@ -375,19 +748,27 @@ impl Backend for KimchiVesta {
         Ok(())
     }
 
+    /// Removes the duplicated generic gates and packs the single ones (see [Kimchi::optimize_generic_gates]),
+    /// unless the double generic gate optimization is disabled, which keeps a gate per row for debugging.
+    fn optimize(&mut self) {
+        if self.double_generic_gate_optimization {
+            self.optimize_generic_gates();
+        }
+    }
+
     fn compute_var(
         &self,
         env: &mut crate::witness::WitnessEnv<Self::Field>,
         var: &Self::Var,
     ) -> crate::error::Result<Self::Field> {
         let val = self.vars_to_value.get(&var.index).unwrap();
-        self.compute_val(env, val, var.index)
+        self.compute_val(env, val, var.index, var.span)
     }
 
     fn generate_witness(
         &self,
-        witness_env: &mut WitnessEnv<VestaField>,
-    ) -> Result<GeneratedWitness> {
+        witness_env: &mut WitnessEnv<C::Field>,
+    ) -> Result<GeneratedWitness<C::Field>> {
         if !self.finalized {
             unreachable!("the circuit must be finalized before generating a witness");
         }
@@ -471,6 +852,13 @@ impl Backend for KimchiVesta {
             }
         }
 
+        // the wired equalities would only fail in the prover, without pointing to the assertion
+        for (lhs, rhs, span) in &self.wired_equalities {
+            if self.compute_var(witness_env, lhs)? != self.compute_var(witness_env, rhs)? {
+                return Err(Error::new("runtime", ErrorKind::AssertionFailed, *span));
+            }
+        }
+
         // extract full public input (containing the public output)
         let mut full_public_inputs = Vec::with_capacity(self.public_input_size);
 
@@ -497,7 +885,7 @@ impl Backend for KimchiVesta {
         res.push_str(&crate::utils::noname_version());
 
         // vars
-        let mut vars: OrderedHashSet<VestaField> = OrderedHashSet::default();
+        let mut vars: OrderedHashSet<C::Field> = OrderedHashSet::default();
 
         for Gate { coeffs, .. } in self.gates.iter() {
             extract_vars_from_coeffs(&mut vars, coeffs);
@@ -642,7 +1030,6 @@ impl Backend for KimchiVesta {
         let res = self.new_internal_var(Value::LinearCombination(vec![(one, *var)], *cst), span);
 
         // create a gate to store the result
-        // TODO: we should use an add_generic function that takes advantage of the double generic gate
         self.add_generic_gate(
             "add a constant with a variable",
             vec![Some(*var), None, Some(res)],
@@ -679,7 +1066,6 @@ impl Backend for KimchiVesta {
         let res = self.new_internal_var(Value::Scale(*cst, *var), span);
 
         // create a gate to store the result
-        // TODO: we should use an add_generic function that takes advantage of the double generic gate
         self.add_generic_gate(
             "add a constant with a variable",
             vec![Some(*var), None, Some(res)],
@@ -715,6 +1101,111 @@ impl Backend for KimchiVesta {
         );
     }
 
+    fn assert_eq_vars(&mut self, pairs: &[(KimchiCellVar, KimchiCellVar)], span: Span) {
+        // the equalities are enforced by the permutation, when finalizing the circuit
+        self.wired_equalities
+            .extend(pairs.iter().map(|(lhs, rhs)| (*lhs, *rhs, span)));
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            lookup: true,
+            xor_lookup: true,
+            native_poseidon: true,
+        }
+    }
+
+    fn custom_gates(&self) -> Vec<CustomGate<C::Field>> {
+        vec![CustomGate {
+            name: XOR16_GATE,
+            num_inputs: 2,
+            num_outputs: 1,
+            hint: xor_hint,
+        }]
+    }
+
+    fn add_custom_gate(
+        &mut self,
+        name: &str,
+        inputs: &[KimchiCellVar],
+        outputs: &[KimchiCellVar],
+        span: Span,
+    ) -> Result<()> {
+        match (name, inputs, outputs) {
+            (XOR16_GATE, [lhs, rhs], [out]) => {
+                self.constrain_xor(lhs, rhs, out, XOR_BITS_PER_ROW, span);
+                Ok(())
+            }
+            _ => Err(Error::new(
+                "constraint-generation",
+                ErrorKind::UnknownCustomGate(name.to_string()),
+                span,
+            )),
+        }
+    }
+
+    fn xor_lookup(
+        &mut self,
+        lhs: &KimchiCellVar,
+        rhs: &KimchiCellVar,
+        num_bits: usize,
+        span: Span,
+    ) -> KimchiCellVar {
+        assert!(num_bits > 0 && num_bits % XOR_BITS_PER_ROW == 0);
+
+        // the result is computed out of circuit
+        let out = self.new_internal_var(
+            Value::Compute(
+                vec![ConstOrCell::Cell(*lhs), ConstOrCell::Cell(*rhs)],
+                Arc::new(|vals: &[C::Field]| xor_hint(vals)[0]),
+            ),
+            span,
+        );
+
+        self.constrain_xor(lhs, rhs, &out, num_bits, span);
+
+        out
+    }
+
+    fn lookup(
+        &mut self,
+        table: &[(C::Field, C::Field)],
+        index: &KimchiCellVar,
+        value: &KimchiCellVar,
+        span: Span,
+    ) {
+        // tables are registered the first time they are used
+        let position = match self.lookup_tables.iter().position(|t| t == table) {
+            Some(position) => position,
+            None => {
+                self.lookup_tables.push(table.to_vec());
+                self.lookup_tables.len() - 1
+            }
+        };
+        let table_id = FIRST_LOOKUP_TABLE_ID + position as i32;
+        let table_id = self.add_constant(
+            Some("the ID of a lookup table"),
+            C::Field::from(table_id as u64),
+            span,
+        );
+
+        // a lookup gate checks 3 pairs of the table in its first column,
+        // the unused pairs repeat the looked up one
+        let mut vars = vec![Some(table_id)];
+        for _ in 0..3 {
+            vars.push(Some(*index));
+            vars.push(Some(*value));
+        }
+
+        self.add_gate(
+            "look up an entry of a constant table",
+            GateKind::Lookup,
+            vars,
+            vec![],
+            span,
+        );
+    }
+
     fn add_public_input(&mut self, val: Value<Self>, span: Span) -> KimchiCellVar {
         // create the var
         let cvar = self.new_internal_var(val, span);
@@ -745,8 +1236,10 @@ impl Backend for KimchiVesta {
         let cvar = self.new_internal_var(val, span);
 
         // create the associated generic gate
-        self.add_generic_gate(
+        // (like public inputs, public outputs need their own row, so they are never packed)
+        self.add_gate(
             "add public output",
+            GateKind::DoubleGeneric,
             vec![Some(cvar)],
             vec![Self::Field::one()],
             span,
@@ -757,3 +1250,166 @@ impl Backend for KimchiVesta {
         cvar
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        backends::{
+            kimchi::{KimchiVesta, VestaField, NUM_REGISTERS},
+            Backend,
+        },
+        circuit_writer::{writer::Cell, GateKind, Wiring},
+        constants::Span,
+        var::Value,
+    };
+
+    /// The note of the generic gates that assert the equalities that couldn't be wired.
+    const FALLBACK_NOTE: &str = "constrain lhs - rhs = 0 to assert that they are equal";
+
+    /// Returns a backend with two private inputs, and a zero gate that uses them in the given columns.
+    fn zero_gate_with_inputs(lhs_col: usize, rhs_col: usize) -> KimchiVesta {
+        let mut kimchi = KimchiVesta::new(false);
+        let span = Span::default();
+        let lhs = kimchi.add_private_input(Value::External("lhs".to_string(), 0), span);
+        let rhs = kimchi.add_private_input(Value::External("rhs".to_string(), 0), span);
+
+        let mut vars = vec![None; NUM_REGISTERS];
+        vars[lhs_col] = Some(lhs);
+        vars[rhs_col] = Some(rhs);
+        kimchi.add_gate("use the inputs", GateKind::Zero, vars, vec![], span);
+
+        kimchi.assert_eq_vars(&[(lhs, rhs)], span);
+        kimchi.wire_equalities();
+        kimchi.flush_pending_generic_gate();
+
+        kimchi
+    }
+
+    #[test]
+    fn test_equality_is_wired() {
+        // both inputs are in permutable columns
+        let kimchi = zero_gate_with_inputs(0, 1);
+
+        // no gate is added, and the wiring cycles are merged
+        assert_eq!(kimchi.gates.len(), 1);
+        assert_eq!(kimchi.wiring.len(), 1);
+        assert!(
+            matches!(kimchi.wiring.values().next(), Some(Wiring::Wired(cells)) if cells.len() == 2)
+        );
+    }
+
+    #[test]
+    fn test_equality_falls_back_to_generic_gate() {
+        // the rhs is only in the last column, which isn't permutable
+        let kimchi = zero_gate_with_inputs(0, NUM_REGISTERS - 1);
+
+        // the equality is asserted by a generic gate instead
+        assert_eq!(kimchi.gates.len(), 2);
+        assert!(matches!(kimchi.gates[1].typ, GateKind::DoubleGeneric));
+        assert_eq!(kimchi.debug_info[1].note, FALLBACK_NOTE);
+        assert_eq!(kimchi.wiring.len(), 2);
+    }
+
+    #[test]
+    fn test_xor_rows_are_followed_by_zero_gate() {
+        // without the optimization, the zero constant gets its own generic gate
+        let mut kimchi = KimchiVesta::new(false);
+        let span = Span::default();
+        let lhs = kimchi.add_private_input(Value::External("lhs".to_string(), 0), span);
+        let rhs = kimchi.add_private_input(Value::External("rhs".to_string(), 0), span);
+
+        kimchi.xor_lookup(&lhs, &rhs, 32, span);
+
+        let kinds: Vec<_> = kimchi.gates.iter().map(|gate| gate.typ).collect();
+        assert_eq!(kinds.len(), 4);
+        assert!(matches!(kinds[0], GateKind::DoubleGeneric));
+        assert!(matches!(kinds[1], GateKind::Xor16));
+        assert!(matches!(kinds[2], GateKind::Xor16));
+        assert!(matches!(kinds[3], GateKind::Zero));
+    }
+
+    /// Returns the cells of the wiring cycle of a var.
+    fn cells(kimchi: &KimchiVesta, var: usize) -> Vec<Cell> {
+        match &kimchi.wiring[&var] {
+            Wiring::NotWired(cell) => vec![cell.cell],
+            Wiring::Wired(cells) => cells.iter().map(|cell| cell.cell).collect(),
+        }
+    }
+
+    #[test]
+    fn test_optimize_removes_duplicated_generic_gates() {
+        let mut kimchi = KimchiVesta::new(true);
+        let span = Span::default();
+        let xx = kimchi.add_private_input(Value::External("xx".to_string(), 0), span);
+        let yy = kimchi.add_private_input(Value::External("yy".to_string(), 0), span);
+
+        // the product and the first assertion share a row, and the repeated assertion gets its own
+        let zz = kimchi.mul(&xx, &yy, span);
+        kimchi.assert_eq_const(&zz, VestaField::from(6u64), span);
+        kimchi.assert_eq_const(&zz, VestaField::from(6u64), span);
+        kimchi.flush_pending_generic_gate();
+        assert_eq!(kimchi.gates.len(), 2);
+
+        kimchi.optimize();
+
+        assert_eq!(kimchi.gates.len(), 1);
+        assert_eq!(kimchi.witness_table.len(), 1);
+        assert_eq!(kimchi.debug_info.len(), 1);
+        assert_eq!(
+            cells(&kimchi, zz.index),
+            vec![Cell { row: 0, col: 0 }, Cell { row: 0, col: 5 }]
+        );
+        assert_eq!(cells(&kimchi, xx.index), vec![Cell { row: 0, col: 3 }]);
+    }
+
+    #[test]
+    fn test_optimize_packs_single_generic_gates() {
+        let mut kimchi = KimchiVesta::new(true);
+        let span = Span::default();
+        let xx = kimchi.add_private_input(Value::External("xx".to_string(), 0), span);
+        let yy = kimchi.add_private_input(Value::External("yy".to_string(), 0), span);
+
+        // each assertion gets its own row, after a gate that doesn't constrain its next row
+        kimchi.assert_eq_const(&xx, VestaField::from(2u64), span);
+        kimchi.add_gate(
+            "use the inputs",
+            GateKind::Zero,
+            vec![Some(xx), Some(yy)],
+            vec![],
+            span,
+        );
+        kimchi.flush_pending_generic_gate();
+        kimchi.assert_eq_const(&yy, VestaField::from(3u64), span);
+        kimchi.flush_pending_generic_gate();
+        assert_eq!(kimchi.gates.len(), 3);
+
+        kimchi.optimize();
+
+        let kinds: Vec<_> = kimchi.gates.iter().map(|gate| gate.typ).collect();
+        assert_eq!(kinds.len(), 2);
+        assert!(matches!(kinds[0], GateKind::Zero));
+        assert!(matches!(kinds[1], GateKind::DoubleGeneric));
+        assert_eq!(kimchi.gates[1].coeffs.len(), 10);
+        assert_eq!(
+            cells(&kimchi, xx.index),
+            vec![Cell { row: 0, col: 0 }, Cell { row: 1, col: 0 }]
+        );
+        assert_eq!(
+            cells(&kimchi, yy.index),
+            vec![Cell { row: 0, col: 1 }, Cell { row: 1, col: 3 }]
+        );
+    }
+
+    #[test]
+    fn test_optimize_is_disabled_for_debugging() {
+        let mut kimchi = KimchiVesta::new(false);
+        let span = Span::default();
+        let xx = kimchi.add_private_input(Value::External("xx".to_string(), 0), span);
+
+        kimchi.assert_eq_const(&xx, VestaField::from(2u64), span);
+        kimchi.assert_eq_const(&xx, VestaField::from(2u64), span);
+        kimchi.optimize();
+
+        assert_eq!(kimchi.gates.len(), 2);
+    }
+}
@@ -3,72 +3,233 @@
 use std::iter::once;
 
 use crate::{
-    backends::kimchi::{KimchiVesta, VestaField},
+    backends::kimchi::{srs::SrsCache, Kimchi, PastaCurve, FIRST_LOOKUP_TABLE_ID, NUM_REGISTERS},
     circuit_writer::Wiring,
     compiler::{generate_witness, Sources},
     inputs::JsonInputs,
+    proof_file::CommitmentScheme,
     witness::CompiledCircuit,
 };
 
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
 use itertools::chain;
-use kimchi::mina_curves::pasta::{Vesta, VestaParameters};
+use kimchi::mina_curves::pasta::{Pallas, PallasParameters, Vesta, VestaParameters};
 use kimchi::mina_poseidon::constants::PlonkSpongeConstantsKimchi;
 use kimchi::mina_poseidon::sponge::{DefaultFqSponge, DefaultFrSponge};
 use kimchi::poly_commitment::commitment::CommitmentCurve;
 use kimchi::poly_commitment::evaluation_proof::OpeningProof;
 use kimchi::proof::ProverProof;
 use kimchi::{
-    circuits::constraints::ConstraintSystem, groupmap::GroupMap, mina_curves::pasta::Pallas,
+    circuits::{constraints::ConstraintSystem, gate::CircuitGate, lookup::tables::LookupTable},
+    groupmap::GroupMap,
     poly_commitment::srs::SRS,
 };
 
 use miette::{Context, IntoDiagnostic};
-use once_cell::sync::Lazy;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 //
-// aliases
+// Proof system
 //
 
-type Curve = Vesta;
-type OtherCurve = Pallas;
-type SpongeParams = PlonkSpongeConstantsKimchi;
-type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
-type ScalarSponge = DefaultFrSponge<kimchi::mina_curves::pasta::Fp, SpongeParams>;
+/// The setup, the prover, and the verifier of kimchi over a curve of the Pasta cycle.
+/// The types of kimchi are only generic over the curve behind many bounds,
+/// so this is implemented with the same code for both curves (see `impl_proof_system`).
+pub trait ProofSystem: PastaCurve {
+    /// The SRS of the curve, with its lagrange basis.
+    type Srs: Serialize + DeserializeOwned;
 
-//
-// Lazy static
-//
+    type ProverIndex;
+
+    type VerifierIndex: Serialize + DeserializeOwned;
+
+    type Proof: Clone + Serialize + DeserializeOwned;
+
+    /// The polynomial commitment scheme of the proofs.
+    /// The Pasta curves have no pairing, so kimchi commits with the inner product argument over them, and not with KZG.
+    const COMMITMENT: CommitmentScheme = CommitmentScheme::Ipa;
+
+    /// Creates the SRS of the given size (a power of two), with its lagrange basis.
+    fn create_srs(size: usize) -> Self::Srs;
+
+    /// Returns the size of the SRS.
+    fn srs_size(srs: &Self::Srs) -> usize;
+
+    /// Computes the lagrange basis of the SRS, if it is missing.
+    fn add_lagrange_basis(srs: &mut Self::Srs);
+
+    /// Creates the indexes of a circuit, with the SRS of the given cache (if any) instead of a new SRS.
+    fn create_indexes(
+        gates: Vec<CircuitGate<Self::Field>>,
+        public_input_size: usize,
+        lookup_tables: Vec<LookupTable<Self::Field>>,
+        srs_cache: Option<&SrsCache<Self>>,
+    ) -> miette::Result<(Self::ProverIndex, Self::VerifierIndex)>;
+
+    /// Creates a proof (after checking that the witness satisfies the constraints, if `debug` is set).
+    fn prove(
+        index: &Self::ProverIndex,
+        witness: [Vec<Self::Field>; NUM_REGISTERS],
+        full_public_inputs: &[Self::Field],
+        debug: bool,
+    ) -> miette::Result<Self::Proof>;
+
+    fn verify(
+        index: &Self::VerifierIndex,
+        proof: &Self::Proof,
+        full_public_inputs: &[Self::Field],
+    ) -> miette::Result<()>;
+
+    /// Returns the number of public inputs of the verifier (including the public output).
+    fn public_input_size(index: &Self::VerifierIndex) -> usize;
+
+    /// Recreates the parts of a deserialized verifier index that are not serialized (the SRS and the endomorphism),
+    /// as during the setup.
+    fn restore_verifier_index(index: &mut Self::VerifierIndex);
+}
+
+/// Implements [ProofSystem] for a curve, given the other curve of the cycle and the parameters of the curve.
+macro_rules! impl_proof_system {
+    ($curve:ty, $other_curve:ty, $params:ty) => {
+        impl ProofSystem for $curve {
+            type Srs = SRS<$curve>;
+            type ProverIndex = kimchi::prover_index::ProverIndex<$curve, OpeningProof<$curve>>;
+            type VerifierIndex =
+                kimchi::verifier_index::VerifierIndex<$curve, OpeningProof<$curve>>;
+            type Proof = ProverProof<$curve, OpeningProof<$curve>>;
+
+            fn create_srs(size: usize) -> Self::Srs {
+                let mut srs = SRS::<$curve>::create(size);
+                Self::add_lagrange_basis(&mut srs);
+                srs
+            }
+
+            fn srs_size(srs: &Self::Srs) -> usize {
+                srs.g.len()
+            }
+
+            fn add_lagrange_basis(srs: &mut Self::Srs) {
+                let domain = Radix2EvaluationDomain::new(srs.g.len())
+                    .expect("the size of an SRS is a power of two");
+                srs.add_lagrange_basis(domain);
+            }
+
+            fn create_indexes(
+                gates: Vec<CircuitGate<<$curve as PastaCurve>::Field>>,
+                public_input_size: usize,
+                lookup_tables: Vec<LookupTable<<$curve as PastaCurve>::Field>>,
+                srs_cache: Option<&SrsCache<Self>>,
+            ) -> miette::Result<(Self::ProverIndex, Self::VerifierIndex)> {
+                // create constraint system
+                let cs = ConstraintSystem::create(gates)
+                    .public(public_input_size)
+                    .lookup(lookup_tables)
+                    .build()
+                    .into_diagnostic()
+                    .wrap_err("kimchi: could not create a constraint system with the given circuit and public input size")?;
+
+                // create SRS (for the curve whose scalar field is the field of the circuit)
+                let srs_size = cs.domain.d1.size as usize;
+                let srs = match srs_cache {
+                    Some(srs_cache) => srs_cache.get_or_create(srs_size)?,
+                    None => Self::create_srs(srs_size),
+                };
+                let srs = std::sync::Arc::new(srs);
+
+                println!("using an SRS of size {}", srs.g.len());
+
+                // create indexes
+                let (endo_q, _endo_r) = kimchi::poly_commitment::srs::endos::<$other_curve>();
+
+                let prover_index = kimchi::prover_index::ProverIndex::<$curve, OpeningProof<$curve>>::create(
+                    cs, endo_q, srs,
+                );
+                let verifier_index = prover_index.verifier_index();
+
+                Ok((prover_index, verifier_index))
+            }
+
+            fn prove(
+                index: &Self::ProverIndex,
+                witness: [Vec<<$curve as PastaCurve>::Field>; NUM_REGISTERS],
+                full_public_inputs: &[<$curve as PastaCurve>::Field],
+                debug: bool,
+            ) -> miette::Result<Self::Proof> {
+                // verify the witness
+                if debug {
+                    index.verify(&witness, full_public_inputs).unwrap();
+                }
+
+                let group_map = <$curve as CommitmentCurve>::Map::setup();
+                ProverProof::create::<
+                    DefaultFqSponge<$params, PlonkSpongeConstantsKimchi>,
+                    DefaultFrSponge<<$curve as PastaCurve>::Field, PlonkSpongeConstantsKimchi>,
+                >(&group_map, witness, &[], index)
+                .into_diagnostic()
+                .wrap_err("kimchi: could not create a proof with the given inputs")
+            }
+
+            fn verify(
+                index: &Self::VerifierIndex,
+                proof: &Self::Proof,
+                full_public_inputs: &[<$curve as PastaCurve>::Field],
+            ) -> miette::Result<()> {
+                let group_map = <$curve as CommitmentCurve>::Map::setup();
+                kimchi::verifier::verify::<
+                    $curve,
+                    DefaultFqSponge<$params, PlonkSpongeConstantsKimchi>,
+                    DefaultFrSponge<<$curve as PastaCurve>::Field, PlonkSpongeConstantsKimchi>,
+                    OpeningProof<$curve>,
+                >(&group_map, index, proof, full_public_inputs)
+                .into_diagnostic()
+                .wrap_err("kimchi: failed to verify the proof")
+            }
+
+            fn public_input_size(index: &Self::VerifierIndex) -> usize {
+                index.public
+            }
+
+            fn restore_verifier_index(index: &mut Self::VerifierIndex) {
+                let mut srs = SRS::<$curve>::create(index.max_poly_size);
+                srs.add_lagrange_basis(index.domain);
+                index.srs = std::sync::Arc::new(srs);
+
+                let (endo_q, _endo_r) = kimchi::poly_commitment::srs::endos::<$other_curve>();
+                index.endo = endo_q;
+            }
+        }
+    };
+}
 
-static GROUP_MAP: Lazy<<Curve as CommitmentCurve>::Map> =
-    Lazy::new(<Curve as CommitmentCurve>::Map::setup);
+impl_proof_system!(Vesta, Pallas, VestaParameters);
+impl_proof_system!(Pallas, Vesta, PallasParameters);
 
 //
 // Data Structures
 //
 
 //#[derive(Serialize, Deserialize)]
-pub struct ProverIndex {
-    index: kimchi::prover_index::ProverIndex<Curve, OpeningProof<Curve>>,
-    compiled_circuit: CompiledCircuit<KimchiVesta>,
+pub struct ProverIndex<C: ProofSystem> {
+    index: C::ProverIndex,
+    compiled_circuit: CompiledCircuit<Kimchi<C>>,
 }
 
 #[derive(Serialize, Deserialize)]
-pub struct VerifierIndex {
-    index: kimchi::verifier_index::VerifierIndex<Curve, OpeningProof<Curve>>,
+#[serde(bound = "")]
+pub struct VerifierIndex<C: ProofSystem> {
+    index: C::VerifierIndex,
 }
 
 //
 // Setup
 //
 
-impl KimchiVesta {
+impl<C: ProofSystem> Kimchi<C> {
+    /// Compiles the circuit to kimchi indexes, with the SRS of the given cache (if any) instead of a new SRS.
     pub fn compile_to_indexes(
         &self,
-    ) -> miette::Result<(
-        kimchi::prover_index::ProverIndex<Curve, OpeningProof<Curve>>,
-        kimchi::verifier_index::VerifierIndex<Curve, OpeningProof<Curve>>,
-    )> {
+        srs_cache: Option<&SrsCache<C>>,
+    ) -> miette::Result<(C::ProverIndex, C::VerifierIndex)> {
         // convert gates to kimchi gates
         let mut gates: Vec<_> = self
             .gates
@@ -99,35 +260,42 @@ impl KimchiVesta {
             }
         }
 
-        // create constraint system
-        let cs = ConstraintSystem::create(gates)
-            .public(self.public_input_size)
-            .build()
-            .into_diagnostic()
-            .wrap_err("kimchi: could not create a constraint system with the given circuit and public input size")?;
-
-        // create SRS (for vesta, as the circuit is in Fp)
-        let mut srs = SRS::<Curve>::create(cs.domain.d1.size as usize);
-        srs.add_lagrange_basis(cs.domain.d1);
-        let srs = std::sync::Arc::new(srs);
-
-        println!("using an SRS of size {}", srs.g.len());
+        // the constant tables of the lookups, as a column of indices and a column of values
+        let lookup_tables = self
+            .lookup_tables
+            .iter()
+            .enumerate()
+            .map(|(position, table)| LookupTable {
+                id: FIRST_LOOKUP_TABLE_ID + position as i32,
+                data: vec![
+                    table.iter().map(|(index, _)| *index).collect(),
+                    table.iter().map(|(_, value)| *value).collect(),
+                ],
+            })
+            .collect();
 
-        // create indexes
-        let (endo_q, _endo_r) = kimchi::poly_commitment::srs::endos::<OtherCurve>();
+        C::create_indexes(gates, self.public_input_size, lookup_tables, srs_cache)
+    }
+}
 
-        let prover_index = kimchi::prover_index::ProverIndex::<Curve, OpeningProof<Curve>>::create(
-            cs, endo_q, srs,
-        );
-        let verifier_index = prover_index.verifier_index();
+impl<C: ProofSystem> CompiledCircuit<Kimchi<C>> {
+    pub fn compile_to_indexes(self) -> miette::Result<(ProverIndex<C>, VerifierIndex<C>)> {
+        self.compile_to_indexes_with(None)
+    }
 
-        Ok((prover_index, verifier_index))
+    /// Like [Self::compile_to_indexes], but reuses the SRS of the cache (and caches it if it is new).
+    pub fn compile_to_indexes_with_srs_cache(
+        self,
+        srs_cache: &SrsCache<C>,
+    ) -> miette::Result<(ProverIndex<C>, VerifierIndex<C>)> {
+        self.compile_to_indexes_with(Some(srs_cache))
     }
-}
 
-impl CompiledCircuit<KimchiVesta> {
-    pub fn compile_to_indexes(self) -> miette::Result<(ProverIndex, VerifierIndex)> {
-        let (prover_index, verifier_index) = self.circuit.backend.compile_to_indexes()?;
+    fn compile_to_indexes_with(
+        self,
+        srs_cache: Option<&SrsCache<C>>,
+    ) -> miette::Result<(ProverIndex<C>, VerifierIndex<C>)> {
+        let (prover_index, verifier_index) = self.circuit.backend.compile_to_indexes(srs_cache)?;
         // wrap
         let prover_index = {
             ProverIndex {
@@ -148,7 +316,7 @@ impl CompiledCircuit<KimchiVesta> {
 // Proving
 //
 
-impl ProverIndex {
+impl<C: ProofSystem> ProverIndex<C> {
     pub fn asm(&self, sources: &Sources, debug: bool) -> String {
         self.compiled_circuit.asm(sources, debug)
     }
@@ -161,6 +329,10 @@ impl ProverIndex {
         self.len() == 0
     }
 
+    pub fn compiled_circuit(&self) -> &CompiledCircuit<Kimchi<C>> {
+        &self.compiled_circuit
+    }
+
     /// returns a proof and a public output
     #[allow(clippy::type_complexity)]
     pub fn prove(
@@ -169,11 +341,7 @@ impl ProverIndex {
         public_inputs: JsonInputs,
         private_inputs: JsonInputs,
         debug: bool,
-    ) -> miette::Result<(
-        ProverProof<Curve, OpeningProof<Curve>>,
-        Vec<VestaField>,
-        Vec<VestaField>,
-    )> {
+    ) -> miette::Result<(C::Proof, Vec<C::Field>, Vec<C::Field>)> {
         // generate the witness
         let generated_witness = generate_witness(
             &self.compiled_circuit,
@@ -190,18 +358,13 @@ impl ProverIndex {
         // convert to kimchi format
         let witness = generated_witness.all_witness.to_kimchi_witness();
 
-        // verify the witness
-        if debug {
-            self.index
-                .verify(&witness, &generated_witness.full_public_inputs)
-                .unwrap();
-        }
-
         // create proof
-        let proof =
-            ProverProof::create::<BaseSponge, ScalarSponge>(&GROUP_MAP, witness, &[], &self.index)
-                .into_diagnostic()
-                .wrap_err("kimchi: could not create a proof with the given inputs")?;
+        let proof = C::prove(
+            &self.index,
+            witness,
+            &generated_witness.full_public_inputs,
+            debug,
+        )?;
 
         // return proof + public output
         Ok((
@@ -216,35 +379,122 @@ impl ProverIndex {
 // Verifying
 //
 
-impl VerifierIndex {
-    pub fn verify(
-        &self,
-        full_public_inputs: Vec<VestaField>,
-        proof: ProverProof<Curve, OpeningProof<Curve>>,
-    ) -> miette::Result<()> {
+impl<C: ProofSystem> VerifierIndex<C> {
+    pub fn verify(&self, full_public_inputs: Vec<C::Field>, proof: C::Proof) -> miette::Result<()> {
         // verify the proof
-        kimchi::verifier::verify::<Curve, BaseSponge, ScalarSponge, OpeningProof<Curve>>(
-            &GROUP_MAP,
-            &self.index,
-            &proof,
-            &full_public_inputs,
-        )
-        .into_diagnostic()
-        .wrap_err("kimchi: failed to verify the proof")
+        C::verify(&self.index, &proof, &full_public_inputs)
+    }
+
+    /// Returns the hash identifying the circuit and its setup, which proof files are checked against.
+    pub fn circuit_hash(&self) -> [u8; 32] {
+        let bytes =
+            rmp_serde::to_vec_named(&self.index).expect("serializing a verifier index can't fail");
+        crate::proof_file::circuit_hash(&bytes)
+    }
+
+    /// Returns the verification key, to share with verifiers.
+    pub fn into_verification_key(self) -> VerificationKey<C> {
+        VerificationKey {
+            version: VERIFICATION_KEY_VERSION,
+            backend: C::BACKEND_ID.to_string(),
+            public_input_size: C::public_input_size(&self.index),
+            index: self.index,
+        }
+    }
+}
+
+//
+// Verification key
+//
+
+/// The version of the format of [VerificationKey], which is bumped on incompatible changes.
+pub const VERIFICATION_KEY_VERSION: u32 = 1;
+
+/// A versioned verification key, that verifiers can use without building the prover index (see `noname vk`).
+/// It is serialized as JSON, or as MessagePack for a compact binary format.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct VerificationKey<C: ProofSystem> {
+    /// The version of the format (see [VERIFICATION_KEY_VERSION]).
+    pub version: u32,
+
+    /// The proof system of the key (see [PastaCurve::BACKEND_ID]).
+    pub backend: String,
+
+    /// The number of public inputs of the verifier (including the public output).
+    pub public_input_size: usize,
+
+    index: C::VerifierIndex,
+}
+
+impl<C: ProofSystem> VerificationKey<C> {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("serializing a verification key can't fail")
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        rmp_serde::to_vec_named(self).expect("serializing a verification key can't fail")
+    }
+
+    /// Deserializes a verification key, from JSON or from its binary format.
+    pub fn from_bytes(bytes: &[u8]) -> miette::Result<Self> {
+        let is_json = bytes
+            .iter()
+            .find(|byte| !byte.is_ascii_whitespace())
+            .is_some_and(|byte| *byte == b'{');
+        let vk: Self = if is_json {
+            serde_json::from_slice(bytes)
+                .into_diagnostic()
+                .wrap_err("could not deserialize the verification key as JSON")?
+        } else {
+            rmp_serde::from_slice(bytes)
+                .into_diagnostic()
+                .wrap_err("could not deserialize the verification key")?
+        };
+
+        if vk.version != VERIFICATION_KEY_VERSION {
+            miette::bail!(
+                "the verification key has version {}, but this version of noname reads version {VERIFICATION_KEY_VERSION}",
+                vk.version
+            );
+        }
+        if vk.backend != C::BACKEND_ID {
+            miette::bail!(
+                "the verification key is for the backend `{}`, not `{}`",
+                vk.backend,
+                C::BACKEND_ID
+            );
+        }
+
+        Ok(vk)
+    }
+
+    /// Returns the verifier index, with the parts that are not serialized (the SRS and the endomorphism) recreated as during the setup.
+    pub fn into_verifier_index(self) -> VerifierIndex<C> {
+        let mut index = self.index;
+        C::restore_verifier_index(&mut index);
+
+        VerifierIndex { index }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use kimchi::circuits::constraints::GateError;
+    use kimchi::{
+        circuits::constraints::GateError,
+        mina_curves::pasta::{Pallas, Vesta},
+    };
+    use num_bigint::BigUint;
 
     use crate::{
-        backends::kimchi::{KimchiVesta, VestaField},
+        backends::kimchi::{Kimchi, KimchiVesta, VestaField},
         compiler::{compile, generate_witness, typecheck_next_file, Sources},
         inputs::parse_inputs,
         type_checker::TypeChecker,
     };
 
+    use super::{ProofSystem, VerificationKey};
+
     #[test]
     fn test_public_output_constraint() -> miette::Result<()> {
         let code = r#"fn main(pub public_input: Field, private_input: Field) -> Field {
@@ -361,4 +611,184 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_verification_key() -> miette::Result<()> {
+        let code = r#"fn main(pub public_input: Field, private_input: Field) -> Field {
+            let xx = private_input + public_input;
+            assert_eq(xx, 2);
+            return xx + 6;
+        }"#;
+
+        let mut sources = Sources::new();
+        let mut tast = TypeChecker::new();
+        typecheck_next_file(
+            &mut tast,
+            None,
+            &mut sources,
+            "inline_test_vk.no".to_string(),
+            code.to_owned(),
+            0,
+        )?;
+        let compiled_circuit = compile(&sources, tast, KimchiVesta::new(false))?;
+        let (prover_index, verifier_index) = compiled_circuit.compile_to_indexes()?;
+
+        let public_inputs = parse_inputs(r#"{"public_input": "1"}"#)?;
+        let private_inputs = parse_inputs(r#"{"private_input": "1"}"#)?;
+        let (proof, full_public_inputs, _) =
+            prover_index.prove(&sources, public_inputs, private_inputs, false)?;
+
+        // both formats can be read back, and verify proofs without the prover index
+        let vk = verifier_index.into_verification_key();
+        assert_eq!(vk.public_input_size, 2);
+        for bytes in [vk.to_bytes(), vk.to_json().into_bytes()] {
+            let verifier_index =
+                VerificationKey::<Vesta>::from_bytes(&bytes)?.into_verifier_index();
+            verifier_index.verify(full_public_inputs.clone(), proof.clone())?;
+        }
+
+        // other versions of the format are rejected
+        let json = vk.to_json().replacen("\"version\": 1", "\"version\": 0", 1);
+        assert!(VerificationKey::<Vesta>::from_bytes(json.as_bytes()).is_err());
+
+        // and so are the keys of the other curve
+        assert!(VerificationKey::<Pallas>::from_bytes(&vk.to_bytes()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_double_generic_gate() -> miette::Result<()> {
+        let code = r#"fn main(pub public_input: Field, private_input: Field) -> Field {
+            let xx = private_input + public_input;
+            let yy = xx * private_input;
+            assert_eq(yy, 4);
+            let zz = yy + 3;
+            return zz * xx;
+        }"#;
+
+        let mut gate_counts = vec![];
+        for double in [false, true] {
+            let mut sources = Sources::new();
+            let mut tast = TypeChecker::new();
+            typecheck_next_file(
+                &mut tast,
+                None,
+                &mut sources,
+                "inline_test_double.no".to_string(),
+                code.to_owned(),
+                0,
+            )?;
+            let compiled_circuit = compile(&sources, tast, KimchiVesta::new(double))?;
+            let (prover_index, verifier_index) = compiled_circuit.compile_to_indexes()?;
+            gate_counts.push(prover_index.compiled_circuit.circuit.backend.gates.len());
+
+            let public_inputs = parse_inputs(r#"{"public_input": "0"}"#)?;
+            let private_inputs = parse_inputs(r#"{"private_input": "2"}"#)?;
+            let (proof, full_public_inputs, _) =
+                prover_index.prove(&sources, public_inputs, private_inputs, false)?;
+            verifier_index.verify(full_public_inputs, proof)?;
+        }
+
+        // the public input and output keep a row each, and the other generic gates are packed by two
+        assert!(gate_counts[1] < gate_counts[0]);
+        assert_eq!(gate_counts[1], 2 + (gate_counts[0] - 2).div_ceil(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimized_circuit() -> miette::Result<()> {
+        let code = r#"fn main(pub xx: Field, yy: Field) -> Field {
+            let zz = xx * yy;
+            assert_eq(zz, 6);
+            assert_eq(zz, 6);
+            return zz + xx;
+        }"#;
+
+        let mut sources = Sources::new();
+        let mut tast = TypeChecker::new();
+        typecheck_next_file(
+            &mut tast,
+            None,
+            &mut sources,
+            "inline_test_optimize.no".to_string(),
+            code.to_owned(),
+            0,
+        )?;
+        let compiled_circuit = compile(&sources, tast, KimchiVesta::new(true))?;
+        let (prover_index, verifier_index) = compiled_circuit.compile_to_indexes()?;
+
+        // the public output and input keep a row each, the repeated assertion is removed,
+        // and the four other generic gates are packed in two rows
+        assert_eq!(prover_index.len(), 4);
+
+        let public_inputs = parse_inputs(r#"{"xx": "2"}"#)?;
+        let private_inputs = parse_inputs(r#"{"yy": "3"}"#)?;
+        let (proof, full_public_inputs, public_output) =
+            prover_index.prove(&sources, public_inputs, private_inputs, false)?;
+        assert_eq!(public_output, vec![VestaField::from(8u64)]);
+        verifier_index.verify(full_public_inputs, proof)?;
+
+        // the removed assertion is still enforced by the one that is kept
+        let public_inputs = parse_inputs(r#"{"xx": "2"}"#)?;
+        let private_inputs = parse_inputs(r#"{"yy": "4"}"#)?;
+        assert!(prover_index
+            .prove(&sources, public_inputs, private_inputs, false)
+            .is_err());
+
+        Ok(())
+    }
+
+    /// Compiles the code for the curve `C`, and returns its public output once its proof is verified.
+    fn prove_and_verify<C: ProofSystem>(
+        code: &str,
+        public_inputs: &str,
+        private_inputs: &str,
+    ) -> miette::Result<Vec<BigUint>> {
+        let mut sources = Sources::new();
+        let mut tast = TypeChecker::new();
+        typecheck_next_file(
+            &mut tast,
+            None,
+            &mut sources,
+            "inline_test_cycle.no".to_string(),
+            code.to_owned(),
+            0,
+        )?;
+        let compiled_circuit = compile(&sources, tast, Kimchi::<C>::new(true))?;
+        let (prover_index, verifier_index) = compiled_circuit.compile_to_indexes()?;
+
+        let public_inputs = parse_inputs(public_inputs)?;
+        let private_inputs = parse_inputs(private_inputs)?;
+        let (proof, full_public_inputs, public_output) =
+            prover_index.prove(&sources, public_inputs, private_inputs, false)?;
+        verifier_index.verify(full_public_inputs, proof)?;
+
+        Ok(public_output.into_iter().map(Into::into).collect())
+    }
+
+    #[test]
+    fn test_pasta_cycle() -> miette::Result<()> {
+        let code = r#"use std::crypto;
+
+        fn main(pub public_input: Field, private_input: Field) -> Field {
+            let digest = crypto::poseidon([public_input, private_input]);
+            return digest[0];
+        }"#;
+        let public_inputs = r#"{"public_input": "2"}"#;
+        let private_inputs = r#"{"private_input": "3"}"#;
+
+        let public_output = prove_and_verify::<Vesta>(code, public_inputs, private_inputs)?;
+        let expected: BigUint =
+            crate::helpers::poseidon([VestaField::from(2u64), VestaField::from(3u64)]).into();
+        assert_eq!(public_output, vec![expected.clone()]);
+
+        // the same source is proven over Pallas, with the Poseidon gate over its scalar field
+        let public_output = prove_and_verify::<Pallas>(code, public_inputs, private_inputs)?;
+        assert_eq!(public_output.len(), 1);
+        assert_ne!(public_output, vec![expected]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,292 @@
+//! A cache of the structured reference strings (SRS) of kimchi, so that they are not regenerated on every build.
+//!
+//! The SRS of a given size is cached as `srs-<size>.bin` (in MessagePack, with its lagrange basis),
+//! next to `srs-<size>.sha256`, the checksum it is verified against when it is loaded.
+
+use std::marker::PhantomData;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use miette::{Context, IntoDiagnostic};
+use sha2::{Digest, Sha256};
+
+use super::prover::ProofSystem;
+
+/// A directory of cached SRS of the curve `C` (for example `~/.noname/srs/kimchi-vesta`).
+pub struct SrsCache<C> {
+    dir: Utf8PathBuf,
+    curve: PhantomData<C>,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+impl<C: ProofSystem> SrsCache<C> {
+    /// A cache in the given directory, which is created when an SRS is first stored.
+    pub fn new(dir: Utf8PathBuf) -> Self {
+        Self {
+            dir,
+            curve: PhantomData,
+        }
+    }
+
+    pub fn dir(&self) -> &Utf8Path {
+        &self.dir
+    }
+
+    /// The path of the SRS of the given size (a power of two, the size of the domain of the circuit).
+    pub fn path(&self, size: usize) -> Utf8PathBuf {
+        self.dir.join(format!("srs-{size}.bin"))
+    }
+
+    fn checksum_path(&self, size: usize) -> Utf8PathBuf {
+        self.dir.join(format!("srs-{size}.sha256"))
+    }
+
+    /// Returns the sizes of the cached SRS, in increasing order.
+    pub fn list(&self) -> miette::Result<Vec<usize>> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let entries = self
+            .dir
+            .read_dir_utf8()
+            .into_diagnostic()
+            .wrap_err(format!("could not read the SRS cache at `{}`", self.dir))?;
+
+        let mut sizes = vec![];
+        for entry in entries {
+            let entry = entry.into_diagnostic()?;
+            let size = entry
+                .file_name()
+                .strip_prefix("srs-")
+                .and_then(|name| name.strip_suffix(".bin"))
+                .and_then(|size| size.parse().ok());
+            if let Some(size) = size {
+                sizes.push(size);
+            }
+        }
+        sizes.sort_unstable();
+
+        Ok(sizes)
+    }
+
+    /// Loads the SRS of the given size, if it is cached, after verifying its checksum.
+    pub fn load(&self, size: usize) -> miette::Result<Option<C::Srs>> {
+        let path = self.path(size);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let bytes = std::fs::read(&path)
+            .into_diagnostic()
+            .wrap_err(format!("could not read the cached SRS at `{path}`"))?;
+        let checksum_path = self.checksum_path(size);
+        let checksum = std::fs::read_to_string(&checksum_path)
+            .into_diagnostic()
+            .wrap_err(format!("could not read the checksum at `{checksum_path}`"))?;
+        if checksum.trim() != sha256_hex(&bytes) {
+            miette::bail!(
+                "the cached SRS at `{path}` doesn't match its checksum (run `noname srs clear` to remove it)"
+            );
+        }
+
+        let srs = deserialize::<C>(&bytes, size)
+            .wrap_err(format!("could not deserialize the cached SRS at `{path}`"))?;
+
+        Ok(Some(srs))
+    }
+
+    /// Stores an SRS (with its lagrange basis) and its checksum, and returns its path.
+    pub fn store(&self, srs: &C::Srs) -> miette::Result<Utf8PathBuf> {
+        let bytes = rmp_serde::to_vec(srs).expect("serializing an SRS can't fail");
+        self.store_bytes(C::srs_size(srs), &bytes)
+    }
+
+    fn store_bytes(&self, size: usize, bytes: &[u8]) -> miette::Result<Utf8PathBuf> {
+        std::fs::create_dir_all(&self.dir)
+            .into_diagnostic()
+            .wrap_err(format!("could not create the SRS cache at `{}`", self.dir))?;
+
+        let path = self.path(size);
+        let checksum_path = self.checksum_path(size);
+        std::fs::write(&path, bytes)
+            .into_diagnostic()
+            .wrap_err(format!("could not write the SRS to `{path}`"))?;
+        std::fs::write(&checksum_path, sha256_hex(bytes))
+            .into_diagnostic()
+            .wrap_err(format!("could not write the checksum to `{checksum_path}`"))?;
+
+        Ok(path)
+    }
+
+    /// Returns the SRS of the given size from the cache, or creates and caches it.
+    pub fn get_or_create(&self, size: usize) -> miette::Result<C::Srs> {
+        if let Some(srs) = self.load(size)? {
+            return Ok(srs);
+        }
+
+        let srs = C::create_srs(size);
+        self.store(&srs)?;
+
+        Ok(srs)
+    }
+
+    /// Downloads the SRS of the given size from a URL (with the `srs-download` feature), or copies it from a local path,
+    /// verifies it (against the expected SHA-256 checksum, which is required for URLs), and caches it.
+    pub fn fetch(
+        &self,
+        size: usize,
+        source: &str,
+        checksum: Option<&str>,
+    ) -> miette::Result<Utf8PathBuf> {
+        let bytes = if source.starts_with("https://") || source.starts_with("http://") {
+            if checksum.is_none() {
+                miette::bail!(
+                    "the SRS from `{source}` can't be trusted without its SHA-256 checksum"
+                );
+            }
+            download(source)?
+        } else {
+            std::fs::read(source)
+                .into_diagnostic()
+                .wrap_err(format!("could not read the SRS at `{source}`"))?
+        };
+
+        if let Some(expected) = checksum {
+            let found = sha256_hex(&bytes);
+            if !found.eq_ignore_ascii_case(expected.trim()) {
+                miette::bail!(
+                    "the SRS from `{source}` has the checksum `{found}`, instead of `{expected}`"
+                );
+            }
+        }
+
+        deserialize::<C>(&bytes, size).wrap_err(format!("the SRS from `{source}` is invalid"))?;
+
+        self.store_bytes(size, &bytes)
+    }
+
+    /// Removes all the cached SRS.
+    pub fn clear(&self) -> miette::Result<()> {
+        if self.dir.exists() {
+            std::fs::remove_dir_all(&self.dir)
+                .into_diagnostic()
+                .wrap_err(format!("could not remove the SRS cache at `{}`", self.dir))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "srs-download")]
+fn download(url: &str) -> miette::Result<Vec<u8>> {
+    use std::io::Read as _;
+
+    let response = ureq::get(url)
+        .call()
+        .into_diagnostic()
+        .wrap_err(format!("could not download the SRS from `{url}`"))?;
+    let mut bytes = vec![];
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .into_diagnostic()
+        .wrap_err(format!("could not download the SRS from `{url}`"))?;
+
+    Ok(bytes)
+}
+
+#[cfg(not(feature = "srs-download"))]
+fn download(url: &str) -> miette::Result<Vec<u8>> {
+    miette::bail!(
+        "noname was built without the `srs-download` feature: download the SRS from `{url}` yourself, and fetch it from its path"
+    )
+}
+
+/// Deserializes an SRS, and checks that it has the expected size.
+fn deserialize<C: ProofSystem>(bytes: &[u8], size: usize) -> miette::Result<C::Srs> {
+    if !size.is_power_of_two() {
+        miette::bail!("the size of an SRS must be a power of two, not {size}");
+    }
+
+    let mut srs: C::Srs = rmp_serde::from_slice(bytes).into_diagnostic()?;
+    if C::srs_size(&srs) != size {
+        miette::bail!(
+            "the SRS has {} points, instead of the expected {size}",
+            C::srs_size(&srs)
+        );
+    }
+
+    // the lagrange basis is only computed if it is missing
+    C::add_lagrange_basis(&mut srs);
+
+    Ok(srs)
+}
+
+#[cfg(test)]
+mod tests {
+    use kimchi::mina_curves::pasta::Vesta;
+
+    use super::SrsCache;
+
+    #[test]
+    fn test_srs_cache() -> miette::Result<()> {
+        let dir = std::env::temp_dir().join(format!("noname-srs-cache-{}", std::process::id()));
+        let cache = SrsCache::<Vesta>::new(dir.try_into().unwrap());
+
+        assert!(cache.load(8)?.is_none());
+        let srs = cache.get_or_create(8)?;
+        assert_eq!(cache.list()?, vec![8]);
+
+        let cached = cache.load(8)?.unwrap();
+        assert_eq!(cached.g, srs.g);
+        assert_eq!(cached.h, srs.h);
+
+        // a corrupted SRS is detected
+        let path = cache.path(8);
+        let mut bytes = std::fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(cache.load(8).is_err());
+
+        cache.clear()?;
+        assert!(cache.list()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_srs_fetch() -> miette::Result<()> {
+        let dir = std::env::temp_dir().join(format!("noname-srs-fetch-{}", std::process::id()));
+        let source = SrsCache::<Vesta>::new(dir.join("source").try_into().unwrap());
+        let cache = SrsCache::<Vesta>::new(dir.join("cache").try_into().unwrap());
+
+        source.get_or_create(8)?;
+        let path = source.path(8);
+        let checksum = std::fs::read_to_string(source.checksum_path(8)).unwrap();
+
+        // the checksum is required for URLs, and checked when given
+        assert!(cache
+            .fetch(8, "https://example.com/srs-8.bin", None)
+            .is_err());
+        assert!(cache
+            .fetch(8, path.as_str(), Some(&"0".repeat(64)))
+            .is_err());
+        assert!(cache.fetch(16, path.as_str(), Some(&checksum)).is_err());
+        assert!(cache.list()?.is_empty());
+
+        cache.fetch(8, path.as_str(), Some(&checksum))?;
+        assert_eq!(cache.list()?, vec![8]);
+        assert_eq!(cache.load(8)?.unwrap().g, source.load(8)?.unwrap().g);
+
+        source.clear()?;
+        cache.clear()?;
+
+        Ok(())
+    }
+}
@@ -1,30 +1,51 @@
-use std::{fmt::Debug, hash::Hash, str::FromStr};
+use std::{fmt::Debug, hash::Hash, str::FromStr, sync::Arc};
 
-use ark_ff::{Field, Zero};
+use ark_ff::{Field, One, SquareRootField, Zero};
 use num_bigint::BigUint;
 
 use crate::{
     compiler::Sources,
     constants::Span,
+    constraints::field,
     error::{Error, ErrorKind, Result},
     helpers::PrettyField,
     imports::FnHandle,
     parser::FunctionDef,
-    var::{Value, Var},
+    var::{ConstOrCell, Value, Var},
     witness::WitnessEnv,
 };
 
+#[cfg(feature = "bulletproofs")]
+use self::bulletproofs::field::R1csRistrettoField;
 use self::{
-    kimchi::KimchiVesta,
-    r1cs::{R1csBls12381Field, R1csBn254Field, R1CS},
+    kimchi::{KimchiPallas, KimchiVesta},
+    r1cs::{R1csBls12381Field, R1csBn254Field, R1csPallasField, R1CS},
 };
 
+// the proof systems other than kimchi are behind cargo features of the same name
+#[cfg(feature = "bulletproofs")]
+pub mod bulletproofs;
+#[cfg(feature = "groth16")]
+pub mod groth16;
+#[cfg(feature = "halo2")]
+pub mod halo2;
 pub mod kimchi;
+#[cfg(feature = "nova")]
+pub mod nova;
+pub mod plonky2;
 pub mod r1cs;
+#[cfg(feature = "spartan")]
+pub mod spartan;
 
 /// This trait serves as an alias for a bundle of traits
 pub trait BackendField:
-    Field + FromStr + TryFrom<BigUint> + TryInto<BigUint> + Into<BigUint> + PrettyField
+    Field
+    + SquareRootField
+    + FromStr
+    + TryFrom<BigUint>
+    + TryInto<BigUint>
+    + Into<BigUint>
+    + PrettyField
 {
 }
 
@@ -32,10 +53,45 @@ pub trait BackendField:
 /// It is intended to make it opaque to the frondend.
 pub trait BackendVar: Clone + Debug + PartialEq + Eq {}
 
+/// What a backend supports natively (see [Backend::capabilities]).
+/// Gadgets fall back to generic arithmetic for what is not supported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The backend can look up entries in constant tables (see [Backend::lookup]).
+    pub lookup: bool,
+
+    /// The backend can XOR values with lookup tables (see [Backend::xor_lookup]).
+    pub xor_lookup: bool,
+
+    /// The Poseidon builtin of the backend (see [Backend::poseidon]) is constrained with native gates,
+    /// so that gadgets needing a hash (but not a specific one) should prefer it to generic arithmetic.
+    pub native_poseidon: bool,
+}
+
+/// A custom gate that a backend exposes to the gadgets, by name (see [Backend::custom_gates]).
+#[derive(Clone, Copy)]
+pub struct CustomGate<F> {
+    /// The name that gadgets use to target the gate.
+    pub name: &'static str,
+
+    /// The number of vars that the gate takes as inputs.
+    pub num_inputs: usize,
+
+    /// The number of vars that the gate outputs.
+    pub num_outputs: usize,
+
+    /// Computes the values of the outputs from the values of the inputs, to generate the witness.
+    pub hint: fn(&[F]) -> Vec<F>,
+}
+
 pub enum BackendKind {
     KimchiVesta(KimchiVesta),
+    KimchiPallas(KimchiPallas),
     R1csBls12_381(R1CS<R1csBls12381Field>),
     R1csBn254(R1CS<R1csBn254Field>),
+    R1csPallas(R1CS<R1csPallasField>),
+    #[cfg(feature = "bulletproofs")]
+    Bulletproofs(R1CS<R1csRistrettoField>),
 }
 
 impl BackendKind {
@@ -43,6 +99,10 @@ impl BackendKind {
         Self::KimchiVesta(KimchiVesta::new(use_double_generic))
     }
 
+    pub fn new_kimchi_pallas(use_double_generic: bool) -> Self {
+        Self::KimchiPallas(KimchiPallas::new(use_double_generic))
+    }
+
     pub fn new_r1cs_bls12_381() -> Self {
         Self::R1csBls12_381(R1CS::new())
     }
@@ -50,6 +110,15 @@ impl BackendKind {
     pub fn new_r1cs_bn254() -> Self {
         Self::R1csBn254(R1CS::new())
     }
+
+    pub fn new_r1cs_pallas() -> Self {
+        Self::R1csPallas(R1CS::new())
+    }
+
+    #[cfg(feature = "bulletproofs")]
+    pub fn new_bulletproofs() -> Self {
+        Self::Bulletproofs(R1CS::new())
+    }
 }
 
 // TODO: should it be cloneable? It is now so because FnInfo needs to be cloneable.
@@ -104,6 +173,71 @@ pub trait Backend: Clone {
     /// add a constraint to assert a var equals another var
     fn assert_eq_var(&mut self, lhs: &Self::Var, rhs: &Self::Var, span: Span);
 
+    /// Asserts that the vars of each pair are equal.
+    /// Backends can override this to enforce several equalities at once with fewer constraints.
+    fn assert_eq_vars(&mut self, pairs: &[(Self::Var, Self::Var)], span: Span) {
+        for (lhs, rhs) in pairs {
+            self.assert_eq_var(lhs, rhs, span);
+        }
+    }
+
+    /// Returns what the backend supports natively,
+    /// so that gadgets can pick the cheapest construction for it (see [Capabilities]).
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
+    /// Returns the custom gates of the backend, that gadgets can target with [Backend::add_custom_gate]
+    /// (see [crate::constraints::custom]).
+    fn custom_gates(&self) -> Vec<CustomGate<Self::Field>> {
+        vec![]
+    }
+
+    /// Constrains the outputs of the custom gate `name` (one of [Backend::custom_gates]) to be computed from its inputs.
+    /// The outputs are already computed out of circuit with the hint of the gate.
+    /// Returns an error if the backend has no such gate.
+    fn add_custom_gate(
+        &mut self,
+        name: &str,
+        _inputs: &[Self::Var],
+        _outputs: &[Self::Var],
+        span: Span,
+    ) -> Result<()> {
+        Err(Error::new(
+            "constraint-generation",
+            ErrorKind::UnknownCustomGate(name.to_string()),
+            span,
+        ))
+    }
+
+    /// XORs two values of `num_bits` bits (a multiple of 16) with lookup tables,
+    /// which also constrains both values to fit in `num_bits` bits.
+    /// This is only called if the backend has the [Capabilities::xor_lookup] capability,
+    /// so backends with that capability must override it.
+    fn xor_lookup(
+        &mut self,
+        _lhs: &Self::Var,
+        _rhs: &Self::Var,
+        _num_bits: usize,
+        _span: Span,
+    ) -> Self::Var {
+        unreachable!("xor_lookup is only called on backends with the xor_lookup capability")
+    }
+
+    /// Constrains `(index, value)` to be an entry of a constant table of `(index, value)` pairs,
+    /// whose indices are distinct.
+    /// This is only called if the backend has the [Capabilities::lookup] capability,
+    /// so backends with that capability must override it.
+    fn lookup(
+        &mut self,
+        _table: &[(Self::Field, Self::Field)],
+        _index: &Self::Var,
+        _value: &Self::Var,
+        _span: Span,
+    ) {
+        unreachable!("lookup is only called on backends with the lookup capability")
+    }
+
     /// Process a public input
     fn add_public_input(&mut self, val: Value<Self>, span: Span) -> Self::Var;
 
@@ -129,11 +263,14 @@ pub trait Backend: Clone {
         var: &Self::Var,
     ) -> Result<Self::Field>;
 
+    /// Computes the value of a var, given how it is computed.
+    /// The span is where the var was created, which errors point to when the value can't be computed.
     fn compute_val(
         &self,
         env: &mut WitnessEnv<Self::Field>,
         val: &Value<Self>,
         cache_key: usize,
+        span: Span,
     ) -> Result<Self::Field> {
         if let Some(res) = env.cached_values.get(&cache_key) {
             return Ok(*res);
@@ -168,6 +305,75 @@ pub trait Backend: Clone {
                 env.cached_values.insert(cache_key, res); // cache
                 Ok(res)
             }
+            Value::NonZeroInverse(v) => {
+                let v = self.compute_var(env, v)?;
+                let res = v
+                    .inverse()
+                    .ok_or_else(|| Error::new("runtime", ErrorKind::InverseOfZero, span))?;
+                env.cached_values.insert(cache_key, res); // cache
+                Ok(res)
+            }
+            Value::Sqrt(v) => {
+                let v = self.compute_var(env, v)?;
+                let res = field::smallest_root(v)
+                    .ok_or_else(|| Error::new("runtime", ErrorKind::NotASquare, span))?;
+                env.cached_values.insert(cache_key, res); // cache
+                Ok(res)
+            }
+            Value::NthBit(v, ith) => {
+                let v = self.compute_var(env, v)?;
+                let bigint: BigUint = v.into();
+                let res = if bigint.bit(*ith as u64) {
+                    Self::Field::one()
+                } else {
+                    Self::Field::zero()
+                };
+                env.cached_values.insert(cache_key, res); // cache
+                Ok(res)
+            }
+            Value::DivMod(lhs, rhs, remainder) => {
+                let lhs: BigUint = self.compute_var(env, lhs)?.into();
+                let rhs: BigUint = self.compute_var(env, rhs)?.into();
+                if rhs.is_zero() {
+                    return Err(Error::new("runtime", ErrorKind::DivisionByZero, span));
+                }
+
+                let res = if *remainder { lhs % rhs } else { lhs / rhs };
+                let res = Self::Field::try_from(res)
+                    .unwrap_or_else(|_| unreachable!("the result is smaller than the dividend"));
+                env.cached_values.insert(cache_key, res); // cache
+                Ok(res)
+            }
+            Value::Compute(inputs, func) => {
+                let inputs = inputs
+                    .iter()
+                    .map(|input| match input {
+                        ConstOrCell::Const(cst) => Ok(*cst),
+                        ConstOrCell::Cell(var) => self.compute_var(env, var),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let res = func(&inputs);
+                env.cached_values.insert(cache_key, res); // cache
+                Ok(res)
+            }
+            Value::ComputeNth(inputs, func, nth) => {
+                // the function is shared by several vars, so its values are only computed once
+                let key = Arc::as_ptr(func) as *const () as usize;
+                if !env.shared_values.contains_key(&key) {
+                    let inputs = inputs
+                        .iter()
+                        .map(|input| match input {
+                            ConstOrCell::Const(cst) => Ok(*cst),
+                            ConstOrCell::Cell(var) => self.compute_var(env, var),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    env.shared_values.insert(key, func(&inputs));
+                }
+
+                let res = env.shared_values[&key][*nth];
+                env.cached_values.insert(cache_key, res); // cache
+                Ok(res)
+            }
             Value::External(name, idx) => Ok(env.get_external(name)[*idx]),
             Value::PublicOutput(var) => {
                 // var can be none. what could be the better way to pass in the span in that case?
@@ -192,6 +398,13 @@ pub trait Backend: Clone {
         main_span: Span,
     ) -> Result<()>;
 
+    /// Optimizes the circuit once it is written and finalized,
+    /// before it is compiled to the indexes (or keys) of the prover.
+    /// The optimizations must preserve the vars, so that the witness is generated as before.
+    fn optimize(&mut self) {
+        // no optimization by default
+    }
+
     /// Generate the witness for a backend.
     fn generate_witness(
         &self,
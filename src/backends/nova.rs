@@ -0,0 +1,440 @@
+//! Incremental computation (IVC) with Nova folding (https://eprint.iacr.org/2021/370),
+//! for long repeated computations (e.g. the steps of a VM) that would make a single circuit too large.
+//!
+//! The program is a step function, compiled once with the R1CS backend over the scalar field of Vesta:
+//!
+//! ```ignore
+//! fn main(pub state: [Field; N], ...private inputs) -> [Field; N]
+//! ```
+//!
+//! Its only public argument is the state `z_i` and it returns the next state `z_{i+1}`,
+//! while the private inputs can change at every step.
+//! Its constraints are replayed into the primary circuit of Nova (over the Vesta/Pallas cycle, with a trivial secondary circuit),
+//! and each step is folded into a recursive SNARK, whose size doesn't depend on the number of steps.
+//!
+//! The witness of each step is generated out of circuit first, by running the step function on the previous state.
+
+use std::sync::Arc;
+
+use ark_ff::{BigInteger, PrimeField as _};
+use bellpepper_core::{
+    num::AllocatedNum, ConstraintSystem, LinearCombination as BellpepperLinearCombination,
+    SynthesisError, Variable,
+};
+use ff::{Field as _, PrimeField as _};
+use itertools::Itertools as _;
+use nova_snark::{
+    provider::{PallasEngine, VestaEngine},
+    traits::{
+        circuit::{StepCircuit, TrivialCircuit},
+        snark::default_ck_hint,
+        Engine,
+    },
+    PublicParams, RecursiveSNARK,
+};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{
+        kimchi::VestaField,
+        r1cs::{LinearCombination, R1CS},
+    },
+    compiler::{generate_witness, Sources},
+    imports::FnKind,
+    inputs::JsonInputs,
+    parser::types::TyKind,
+    witness::CompiledCircuit,
+};
+
+//
+// aliases
+//
+
+type E1 = VestaEngine;
+type E2 = PallasEngine;
+
+/// The scalar field of Vesta (the field of the circuit), in Nova.
+pub(crate) type F1 = <E1 as Engine>::Scalar;
+
+/// The scalar field of Pallas (the field of the secondary circuit), in Nova.
+type F2 = <E2 as Engine>::Scalar;
+
+type SecondaryCircuit = TrivialCircuit<F2>;
+
+/// A recursive SNARK proving a number of steps.
+pub type IvcProof = RecursiveSNARK<E1, E2, Step, SecondaryCircuit>;
+
+//
+// Data Structures
+//
+
+/// The constraints of the step function, independent of the witness.
+struct StepShape {
+    /// The number of variables, including the first one that is always 1.
+    num_vars: usize,
+
+    /// The linear combinations `(terms, constant)` of each constraint `a * b = c`.
+    constraints: Vec<[(Vec<(usize, F1)>, F1); 3]>,
+
+    /// The public inputs (the state given to the step function).
+    inputs: Vec<usize>,
+
+    /// The public outputs (the state returned by the step function).
+    outputs: Vec<usize>,
+}
+
+/// A step of the computation, with its witness when proving (and without it during the setup).
+#[derive(Clone)]
+pub struct Step {
+    shape: Arc<StepShape>,
+    witness: Option<Arc<Vec<F1>>>,
+}
+
+impl Step {
+    /// Returns the step of a circuit, without witness.
+    pub(crate) fn new(r1cs: &R1CS<VestaField>) -> Self {
+        let shape = StepShape {
+            num_vars: r1cs.num_vars(),
+            constraints: r1cs
+                .constraints()
+                .iter()
+                .map(|constraint| {
+                    [
+                        to_nova_lc(&constraint.a),
+                        to_nova_lc(&constraint.b),
+                        to_nova_lc(&constraint.c),
+                    ]
+                })
+                .collect(),
+            inputs: r1cs.public_input_indices(),
+            outputs: r1cs.public_output_indices(),
+        };
+
+        Self {
+            shape: Arc::new(shape),
+            witness: None,
+        }
+    }
+
+    /// Returns the same step, with a witness.
+    pub(crate) fn with_witness(&self, witness: &[VestaField]) -> Self {
+        Self {
+            shape: self.shape.clone(),
+            witness: Some(Arc::new(witness.iter().map(to_nova).collect())),
+        }
+    }
+}
+
+pub struct IvcIndex {
+    params: PublicParams<E1, E2, Step, SecondaryCircuit>,
+
+    /// The step, without witness.
+    step: Step,
+
+    /// The name of the state argument of the step function.
+    state_name: String,
+
+    compiled_circuit: CompiledCircuit<R1CS<VestaField>>,
+}
+
+impl StepCircuit<F1> for Step {
+    fn arity(&self) -> usize {
+        self.shape.inputs.len()
+    }
+
+    fn synthesize<CS: ConstraintSystem<F1>>(
+        &self,
+        cs: &mut CS,
+        z: &[AllocatedNum<F1>],
+    ) -> Result<Vec<AllocatedNum<F1>>, SynthesisError> {
+        let shape = &self.shape;
+        let value = |index: usize| {
+            self.witness
+                .as_ref()
+                .map(|witness| witness[index])
+                .ok_or(SynthesisError::AssignmentMissing)
+        };
+
+        // the first var is always 1
+        let mut vars: Vec<Option<Variable>> = vec![None; shape.num_vars];
+        vars[0] = Some(CS::one());
+
+        // the state is given by Nova
+        for (index, input) in shape.inputs.iter().zip(z) {
+            vars[*index] = Some(input.get_variable());
+        }
+
+        // the next state is returned to Nova
+        let mut outputs = Vec::with_capacity(shape.outputs.len());
+        for &index in &shape.outputs {
+            let output =
+                AllocatedNum::alloc(cs.namespace(|| format!("output {index}")), || value(index))?;
+            vars[index] = Some(output.get_variable());
+            outputs.push(output);
+        }
+
+        // the rest of the witness
+        for (index, var) in vars.iter_mut().enumerate() {
+            if var.is_none() {
+                *var = Some(cs.alloc(|| format!("var {index}"), || value(index))?);
+            }
+        }
+        let vars: Vec<_> = vars.into_iter().map(Option::unwrap).collect();
+
+        let to_lc = |(terms, constant): &(Vec<(usize, F1)>, F1),
+                     lc: BellpepperLinearCombination<F1>| {
+            terms
+                .iter()
+                .fold(lc + (*constant, CS::one()), |lc, (index, factor)| {
+                    lc + (*factor, vars[*index])
+                })
+        };
+        for (ii, [a, b, c]) in shape.constraints.iter().enumerate() {
+            cs.enforce(
+                || format!("constraint {ii}"),
+                |lc| to_lc(a, lc),
+                |lc| to_lc(b, lc),
+                |lc| to_lc(c, lc),
+            );
+        }
+
+        Ok(outputs)
+    }
+}
+
+/// Converts a field element from arkworks to Nova.
+pub(crate) fn to_nova(value: &VestaField) -> F1 {
+    let bytes: [u8; 32] = value
+        .into_repr()
+        .to_bytes_le()
+        .try_into()
+        .expect("the field has 32-byte elements");
+    F1::from_repr(bytes).unwrap()
+}
+
+/// Converts a field element from Nova to arkworks.
+pub(crate) fn from_nova(value: &F1) -> VestaField {
+    VestaField::from_le_bytes_mod_order(value.to_repr().as_ref())
+}
+
+/// Converts a linear combination to Nova's field, with its terms sorted by variable.
+fn to_nova_lc(lc: &LinearCombination<VestaField>) -> (Vec<(usize, F1)>, F1) {
+    let terms = lc
+        .terms
+        .iter()
+        .sorted_by_key(|(var, _)| var.index)
+        .map(|(var, factor)| (var.index, to_nova(factor)))
+        .collect();
+    (terms, to_nova(&lc.constant))
+}
+
+//
+// Setup
+//
+
+impl CompiledCircuit<R1CS<VestaField>> {
+    /// Generates the public parameters of Nova for this step function.
+    pub fn compile_to_nova(self) -> miette::Result<IvcIndex> {
+        // the step function takes the state as its only public argument, and returns the next state
+        let sig = match &self.main_info().kind {
+            FnKind::BuiltIn(_, _) => unreachable!(),
+            FnKind::Native(func) => &func.sig,
+        };
+        let mut public_args = sig.arguments.iter().filter(|arg| arg.is_public());
+        let state = match (public_args.next(), public_args.next()) {
+            (Some(state), None) => state,
+            _ => miette::bail!(
+                "nova: the step function must have a single public argument, the state"
+            ),
+        };
+        let is_state =
+            |typ: &TyKind| matches!(typ, TyKind::Array(inner, _) if **inner == TyKind::Field);
+        if !is_state(&state.typ.kind)
+            || sig.return_type.as_ref().map(|typ| &typ.kind) != Some(&state.typ.kind)
+        {
+            miette::bail!(
+                "nova: the step function must take a state of type `[Field; N]`, and return the next state of the same type"
+            );
+        }
+        let state_name = state.name.value.clone();
+
+        let step = Step::new(&self.circuit.backend);
+
+        let params = PublicParams::setup(
+            &step,
+            &SecondaryCircuit::default(),
+            &*default_ck_hint(),
+            &*default_ck_hint(),
+        )
+        .map_err(|err| {
+            miette::miette!("nova: could not generate the public parameters: {err:?}")
+        })?;
+
+        Ok(IvcIndex {
+            params,
+            step,
+            state_name,
+            compiled_circuit: self,
+        })
+    }
+}
+
+//
+// Proving
+//
+
+impl IvcIndex {
+    /// Runs one step per element of `private_inputs`, starting from `initial_state`,
+    /// and returns a proof of all the steps and the final state.
+    pub fn prove(
+        &self,
+        sources: &Sources,
+        initial_state: &[VestaField],
+        private_inputs: Vec<JsonInputs>,
+    ) -> miette::Result<(IvcProof, Vec<VestaField>)> {
+        if private_inputs.is_empty() {
+            miette::bail!("nova: at least one step must be proven");
+        }
+
+        // run the step function out of circuit, to get the witness of each step
+        let mut state = initial_state.to_vec();
+        let mut steps = Vec::with_capacity(private_inputs.len());
+        for private_inputs in private_inputs {
+            let generated_witness = generate_witness(
+                &self.compiled_circuit,
+                sources,
+                self.state_inputs(&state),
+                private_inputs,
+            )?;
+            state = generated_witness.outputs;
+            steps.push(self.step.with_witness(&generated_witness.witness));
+        }
+
+        // fold the steps
+        let initial_state: Vec<_> = initial_state.iter().map(to_nova).collect();
+        let secondary = SecondaryCircuit::default();
+        let mut proof = RecursiveSNARK::new(
+            &self.params,
+            &steps[0],
+            &secondary,
+            &initial_state,
+            &[F2::ZERO],
+        )
+        .map_err(|err| miette::miette!("nova: could not start the computation: {err:?}"))?;
+
+        for (ii, step) in steps.iter().enumerate() {
+            proof
+                .prove_step(&self.params, step, &secondary)
+                .map_err(|err| miette::miette!("nova: could not prove step {ii}: {err:?}"))?;
+        }
+
+        Ok((proof, state))
+    }
+
+    /// Returns the public inputs of a step, which only contain the state.
+    fn state_inputs(&self, state: &[VestaField]) -> JsonInputs {
+        let state = state
+            .iter()
+            .map(|value| serde_json::Value::String(BigUint::from(*value).to_string()))
+            .collect();
+
+        let mut inputs = JsonInputs::default();
+        inputs
+            .0
+            .insert(self.state_name.clone(), serde_json::Value::Array(state));
+        inputs
+    }
+
+    //
+    // Verifying
+    //
+
+    /// Verifies that `num_steps` steps lead from `initial_state` to `final_state`.
+    pub fn verify(
+        &self,
+        proof: &IvcProof,
+        num_steps: usize,
+        initial_state: &[VestaField],
+        final_state: &[VestaField],
+    ) -> miette::Result<()> {
+        let initial_state: Vec<_> = initial_state.iter().map(to_nova).collect();
+        let (state, _) = proof
+            .verify(&self.params, num_steps, &initial_state, &[F2::ZERO])
+            .map_err(|err| miette::miette!("nova: failed to verify the proof: {err:?}"))?;
+
+        let state: Vec<_> = state.iter().map(from_nova).collect();
+        if state != final_state {
+            miette::bail!("nova: the proof leads to a different final state");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{inputs::parse_inputs, tests::compile_with_sources};
+
+    use super::*;
+
+    /// A step function, which only accepts increments of 0 or 1.
+    const CODE: &str = "
+fn main(pub state: [Field; 2], increment: Field) -> [Field; 2] {
+    assert_eq(increment * increment, increment);
+    let next = state[0] + state[1] + increment;
+    let res = [state[1], next];
+    return res;
+}
+";
+
+    fn increments(increments: &[&str]) -> miette::Result<Vec<JsonInputs>> {
+        let inputs = increments
+            .iter()
+            .map(|increment| parse_inputs(&format!(r#"{{"increment": "{increment}"}}"#)))
+            .collect::<Result<_, _>>()?;
+        Ok(inputs)
+    }
+
+    #[test]
+    fn test_prove_and_verify() -> miette::Result<()> {
+        let (compiled_circuit, sources) = compile_with_sources(CODE, R1CS::<VestaField>::new())?;
+        let index = compiled_circuit.compile_to_nova()?;
+
+        // [1, 1] -> [1, 2] -> [2, 3] -> [3, 6]
+        let initial_state = [VestaField::from(1u64), VestaField::from(1u64)];
+        let private_inputs = increments(&["0", "0", "1"])?;
+        let (proof, final_state) = index.prove(&sources, &initial_state, private_inputs)?;
+
+        let expected = [VestaField::from(3u64), VestaField::from(6u64)];
+        assert_eq!(final_state, expected);
+        index.verify(&proof, 3, &initial_state, &expected)?;
+
+        // the proof doesn't hold for another number of steps, or another final state
+        assert!(index.verify(&proof, 2, &initial_state, &expected).is_err());
+        let wrong_state = [VestaField::from(3u64), VestaField::from(7u64)];
+        assert!(index
+            .verify(&proof, 3, &initial_state, &wrong_state)
+            .is_err());
+
+        // nor for a tampered initial state
+        let tampered_state = [VestaField::from(1u64), VestaField::from(2u64)];
+        assert!(index.verify(&proof, 3, &tampered_state, &expected).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_wrong_witness() -> miette::Result<()> {
+        let (compiled_circuit, sources) = compile_with_sources(CODE, R1CS::<VestaField>::new())?;
+        let index = compiled_circuit.compile_to_nova()?;
+
+        // the second step doesn't satisfy the assertion
+        let initial_state = [VestaField::from(1u64), VestaField::from(1u64)];
+        let private_inputs = increments(&["0", "2", "1"])?;
+        assert!(index
+            .prove(&sources, &initial_state, private_inputs)
+            .is_err());
+
+        Ok(())
+    }
+}
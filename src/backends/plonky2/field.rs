@@ -0,0 +1,146 @@
+//! The Goldilocks field `p = 2^64 - 2^32 + 1` for arkworks, so that circuits can be written on it (with the R1CS backend).
+//!
+//! The 64-bit fields of arkworks assume that the modulus leaves a spare bit (additions would overflow otherwise),
+//! which is not the case of Goldilocks: its elements are stored in 256-bit Montgomery form instead,
+//! so that all the arithmetic of arkworks applies unchanged.
+
+use ark_ff::{biginteger::BigInteger256 as BigInteger, fields::*};
+
+use crate::backends::BackendField;
+
+pub type R1csGoldilocksField = Fp256<GoldilocksParameters>;
+
+impl BackendField for R1csGoldilocksField {}
+
+pub struct GoldilocksParameters;
+
+impl Fp256Parameters for GoldilocksParameters {}
+
+impl FftParameters for GoldilocksParameters {
+    type BigInt = BigInteger;
+
+    const TWO_ADICITY: u32 = 32;
+
+    /// TWO_ADIC_ROOT_OF_UNITY = GENERATOR^T, in Montgomery form
+    #[rustfmt::skip]
+    const TWO_ADIC_ROOT_OF_UNITY: BigInteger = BigInteger([
+        0xda58878b0d514e98,
+        0,
+        0,
+        0,
+    ]);
+}
+
+impl FpParameters for GoldilocksParameters {
+    /// MODULUS = 2^64 - 2^32 + 1 = 18446744069414584321
+    #[rustfmt::skip]
+    const MODULUS: BigInteger = BigInteger([
+        0xffffffff00000001,
+        0,
+        0,
+        0,
+    ]);
+
+    const MODULUS_BITS: u32 = 64;
+
+    const CAPACITY: u32 = Self::MODULUS_BITS - 1;
+
+    const REPR_SHAVE_BITS: u32 = 192;
+
+    /// R = pow(2, 256) % MODULUS = 2^32 - 1
+    #[rustfmt::skip]
+    const R: BigInteger = BigInteger([
+        0xffffffff,
+        0,
+        0,
+        0,
+    ]);
+
+    /// R2 = R * R % MODULUS
+    #[rustfmt::skip]
+    const R2: BigInteger = BigInteger([
+        0xfffffffe00000001,
+        0,
+        0,
+        0,
+    ]);
+
+    /// INV = (-MODULUS) ^ {-1} % pow(2, 64) = 2^64 - 2^32 - 1
+    const INV: u64 = 0xfffffffeffffffff;
+
+    /// GENERATOR = 7, in Montgomery form
+    #[rustfmt::skip]
+    const GENERATOR: BigInteger = BigInteger([
+        0x6fffffff9,
+        0,
+        0,
+        0,
+    ]);
+
+    /// (MODULUS - 1)/2
+    #[rustfmt::skip]
+    const MODULUS_MINUS_ONE_DIV_TWO: BigInteger = BigInteger([
+        0x7fffffff80000000,
+        0,
+        0,
+        0,
+    ]);
+
+    // T and T_MINUS_ONE_DIV_TWO, where r - 1 = 2^s * t
+
+    /// T = (MODULUS - 1) / 2^s = 2^32 - 1
+    #[rustfmt::skip]
+    const T: BigInteger = BigInteger([
+        0xffffffff,
+        0,
+        0,
+        0,
+    ]);
+
+    /// (T - 1) / 2
+    #[rustfmt::skip]
+    const T_MINUS_ONE_DIV_TWO: BigInteger = BigInteger([
+        0x7fffffff,
+        0,
+        0,
+        0,
+    ]);
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{FftField, Field, One, PrimeField, SquareRootField, Zero};
+    use num_bigint::BigUint;
+
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let p_minus_one = -R1csGoldilocksField::one();
+        let p_minus_one: BigUint = p_minus_one.into();
+        assert_eq!(p_minus_one, BigUint::from(0xffffffff00000000u64));
+
+        // additions and multiplications that overflow 64 bits
+        let a = R1csGoldilocksField::from(0xffffffff00000000u64);
+        assert_eq!(a + a, a - R1csGoldilocksField::one());
+        assert_eq!(a * a, R1csGoldilocksField::one());
+
+        // the generator has order p - 1, and the root of unity has order 2^32
+        let generator = R1csGoldilocksField::multiplicative_generator();
+        assert_eq!(generator, R1csGoldilocksField::from(7u64));
+        let root = R1csGoldilocksField::two_adic_root_of_unity();
+        assert_eq!(root.pow([1u64 << 31]), -R1csGoldilocksField::one());
+
+        // square roots
+        let square = R1csGoldilocksField::from(1234567u64).square();
+        assert_eq!(square.sqrt().unwrap().square(), square);
+        assert!(generator.sqrt().is_none());
+
+        // literals of 64 bits or more don't always fit
+        assert!(R1csGoldilocksField::try_from(BigUint::from(u64::MAX)).is_err());
+        assert!(
+            R1csGoldilocksField::from_repr(BigInteger([0xffffffff00000000, 0, 0, 0])).is_some()
+        );
+        assert!(!R1csGoldilocksField::zero().is_one());
+    }
+}
@@ -0,0 +1,19 @@
+//! Plonky2 proofs (https://github.com/0xPolygonZero/plonky2) over the Goldilocks field,
+//! for circuits written with the R1CS backend over Goldilocks (see [field::R1csGoldilocksField]).
+//!
+//! Each variable of the compiled circuit becomes a target of a plonky2 circuit,
+//! and each constraint `a * b = c` becomes arithmetic gates that compute the three linear combinations,
+//! followed by a copy constraint between `a * b` and `c`.
+//! The public outputs and the public inputs are registered as public inputs (in this order, as for [super::groth16]).
+//!
+//! The circuits use the standard recursion configuration of plonky2, so proofs can be verified (and aggregated) by other plonky2 circuits.
+//! Since the field only has 64 bits, the type checker rejects the literals that don't fit in it.
+
+pub mod field;
+
+// the field is always available, as circuits can be written on it without proving them with plonky2
+#[cfg(feature = "plonky2")]
+mod prover;
+
+#[cfg(feature = "plonky2")]
+pub use prover::*;
@@ -0,0 +1,281 @@
+//! The plonky2 circuits, proofs, and verification of the circuits compiled over Goldilocks.
+
+use ark_ff::PrimeField as _;
+use itertools::Itertools as _;
+use plonky2::{
+    field::{
+        goldilocks_field::GoldilocksField,
+        types::{Field as _, PrimeField64 as _},
+    },
+    iop::{
+        target::Target,
+        witness::{PartialWitness, WitnessWrite as _},
+    },
+    plonk::{
+        circuit_builder::CircuitBuilder,
+        circuit_data::{CircuitConfig, CircuitData, VerifierCircuitData},
+        config::PoseidonGoldilocksConfig,
+        proof::ProofWithPublicInputs,
+    },
+};
+
+use crate::{
+    backends::r1cs::{LinearCombination, R1CS},
+    compiler::{generate_witness, Sources},
+    inputs::JsonInputs,
+    witness::CompiledCircuit,
+};
+
+use super::field::R1csGoldilocksField;
+
+//
+// aliases
+//
+
+/// The extension degree used by the proofs.
+const D: usize = 2;
+
+type Config = PoseidonGoldilocksConfig;
+
+/// A Plonky2 proof, with its public inputs.
+pub type Plonky2Proof = ProofWithPublicInputs<GoldilocksField, Config, D>;
+
+//
+// Data Structures
+//
+
+pub struct ProverIndex {
+    data: CircuitData<GoldilocksField, Config, D>,
+
+    /// The target of each variable of the R1CS circuit.
+    targets: Vec<Target>,
+
+    compiled_circuit: CompiledCircuit<R1CS<R1csGoldilocksField>>,
+}
+
+pub struct VerifierIndex {
+    data: VerifierCircuitData<GoldilocksField, Config, D>,
+}
+
+/// Converts a field element from arkworks to plonky2.
+fn to_plonky2(value: &R1csGoldilocksField) -> GoldilocksField {
+    GoldilocksField::from_canonical_u64(value.into_repr().0[0])
+}
+
+/// Converts a field element from plonky2 to arkworks.
+fn from_plonky2(value: GoldilocksField) -> R1csGoldilocksField {
+    R1csGoldilocksField::from(value.to_canonical_u64())
+}
+
+/// Returns a target containing the value of a linear combination.
+fn linear_combination(
+    builder: &mut CircuitBuilder<GoldilocksField, D>,
+    lc: &LinearCombination<R1csGoldilocksField>,
+    targets: &[Target],
+) -> Target {
+    let constant = builder.constant(to_plonky2(&lc.constant));
+
+    lc.terms
+        .iter()
+        .sorted_by_key(|(var, _)| var.index)
+        .fold(constant, |acc, (var, factor)| {
+            builder.mul_const_add(to_plonky2(factor), targets[var.index], acc)
+        })
+}
+
+//
+// Setup
+//
+
+impl CompiledCircuit<R1CS<R1csGoldilocksField>> {
+    /// Builds the plonky2 circuit of this circuit.
+    pub fn compile_to_plonky2_indexes(self) -> miette::Result<(ProverIndex, VerifierIndex)> {
+        let r1cs = &self.circuit.backend;
+        let mut builder =
+            CircuitBuilder::<GoldilocksField, D>::new(CircuitConfig::standard_recursion_config());
+
+        // the first var is always 1
+        let one = builder.one();
+        let targets: Vec<_> = (0..r1cs.num_vars())
+            .map(|index| {
+                if index == 0 {
+                    one
+                } else {
+                    builder.add_virtual_target()
+                }
+            })
+            .collect();
+
+        for index in r1cs.public_var_indices() {
+            builder.register_public_input(targets[index]);
+        }
+
+        for constraint in r1cs.constraints() {
+            let a = linear_combination(&mut builder, &constraint.a, &targets);
+            let b = linear_combination(&mut builder, &constraint.b, &targets);
+            let c = linear_combination(&mut builder, &constraint.c, &targets);
+            let ab = builder.mul(a, b);
+            builder.connect(ab, c);
+        }
+
+        let data = builder.build::<Config>();
+        let verifier_index = VerifierIndex {
+            data: data.verifier_data(),
+        };
+        let prover_index = ProverIndex {
+            data,
+            targets,
+            compiled_circuit: self,
+        };
+
+        Ok((prover_index, verifier_index))
+    }
+}
+
+//
+// Proving
+//
+
+impl ProverIndex {
+    /// returns a proof (which contains the public inputs of the verifier) and the public output
+    pub fn prove(
+        &self,
+        sources: &Sources,
+        public_inputs: JsonInputs,
+        private_inputs: JsonInputs,
+    ) -> miette::Result<(Plonky2Proof, Vec<R1csGoldilocksField>)> {
+        // generate the witness (this also checks the constraints)
+        let generated_witness = generate_witness(
+            &self.compiled_circuit,
+            sources,
+            public_inputs,
+            private_inputs,
+        )?;
+
+        // the first var is a constant of the circuit
+        let mut witness = PartialWitness::new();
+        for (target, value) in self.targets.iter().zip(&generated_witness.witness).skip(1) {
+            witness.set_target(*target, to_plonky2(value));
+        }
+
+        // create proof
+        let proof = self.data.prove(witness).map_err(|err| {
+            miette::miette!("plonky2: could not create a proof with the given inputs: {err}")
+        })?;
+
+        Ok((proof, generated_witness.outputs))
+    }
+}
+
+//
+// Verifying
+//
+
+impl VerifierIndex {
+    /// Verifies a proof, given the public outputs followed by the public inputs.
+    pub fn verify(
+        &self,
+        full_public_inputs: &[R1csGoldilocksField],
+        proof: Plonky2Proof,
+    ) -> miette::Result<()> {
+        let proof_public_inputs: Vec<_> = proof
+            .public_inputs
+            .iter()
+            .map(|value| from_plonky2(*value))
+            .collect();
+        if proof_public_inputs != full_public_inputs {
+            miette::bail!("plonky2: the proof was created for different public inputs");
+        }
+
+        self.data
+            .verify(proof)
+            .map_err(|err| miette::miette!("plonky2: failed to verify the proof: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS, WRONG_PRIVATE_INPUTS},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_prove_and_verify() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csGoldilocksField>::new())?;
+        let (prover_index, verifier_index) = compiled_circuit.compile_to_plonky2_indexes()?;
+
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(PRIVATE_INPUTS)?;
+        let (proof, public_output) = prover_index.prove(&sources, public_inputs, private_inputs)?;
+        assert_eq!(public_output, vec![R1csGoldilocksField::from(8u64)]);
+
+        let full_public_inputs = [
+            R1csGoldilocksField::from(8u64),
+            R1csGoldilocksField::from(2u64),
+        ];
+        verifier_index.verify(&full_public_inputs, proof.clone())?;
+
+        // a wrong public output is rejected
+        let wrong_inputs = [
+            R1csGoldilocksField::from(9u64),
+            R1csGoldilocksField::from(2u64),
+        ];
+        assert!(verifier_index.verify(&wrong_inputs, proof.clone()).is_err());
+
+        // so is a tampered public input
+        let tampered_inputs = [
+            R1csGoldilocksField::from(8u64),
+            R1csGoldilocksField::from(3u64),
+        ];
+        assert!(verifier_index.verify(&tampered_inputs, proof).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_wrong_witness() -> miette::Result<()> {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csGoldilocksField>::new())?;
+        let (prover_index, _) = compiled_circuit.compile_to_plonky2_indexes()?;
+
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(WRONG_PRIVATE_INPUTS)?;
+        assert!(prover_index
+            .prove(&sources, public_inputs, private_inputs)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arithmetic_modulo_goldilocks() -> miette::Result<()> {
+        let code = "
+fn main(pub xx: Field, yy: Field) -> Field {
+    let zz = xx * yy;
+    assert_eq(zz, 18446744069414584320);
+    return zz + xx;
+}
+";
+        let (compiled_circuit, sources) =
+            compile_with_sources(code, R1CS::<R1csGoldilocksField>::new())?;
+        let (prover_index, verifier_index) = compiled_circuit.compile_to_plonky2_indexes()?;
+
+        // -1 * 1 = -1, and -1 + 1 = 0 (the arithmetic is done modulo p)
+        let public_inputs = parse_inputs(r#"{"xx": "1"}"#)?;
+        let private_inputs = parse_inputs(r#"{"yy": "18446744069414584320"}"#)?;
+        let (proof, public_output) = prover_index.prove(&sources, public_inputs, private_inputs)?;
+        assert_eq!(public_output, vec![R1csGoldilocksField::from(0u64)]);
+
+        let full_public_inputs = [
+            R1csGoldilocksField::from(0u64),
+            R1csGoldilocksField::from(1u64),
+        ];
+        verifier_index.verify(&full_public_inputs, proof)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use acir::{
+    circuit::{Circuit, Opcode, Program, PublicInputs},
+    native_types::{Expression, Witness, WitnessMap, WitnessStack},
+    FieldElement,
+};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use miette::Diagnostic;
+use thiserror::Error;
+
+use super::{Constraint, GeneratedWitness, LinearCombination, R1CS};
+use crate::backends::BackendField;
+
+/// The modulus of the scalar field of BN254, the only field of ACIR.
+const BN254_MODULUS: &str =
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+#[derive(Diagnostic, Debug, Error)]
+pub enum Error {
+    #[error("ACIR circuits are over the scalar field of bn254 (use `--field bn254`)")]
+    UnsupportedField,
+
+    #[error("could not serialize to ACIR: {0}")]
+    Serialization(String),
+
+    #[error("could not write the ACIR file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A struct to export an r1cs circuit and its witness to ACIR, the intermediate representation of Noir,
+/// so that they can be proven with the backends of Barretenberg (e.g. UltraPlonk or UltraHonk).
+///
+/// ACIR has no wire for the constant 1, so the variable `i` of noname is the witness `i - 1` of ACIR,
+/// and each constraint `a * b = c` is expanded into a single `AssertZero` opcode of degree 2.
+pub struct AcirExporter<F>
+where
+    F: BackendField,
+{
+    /// A R1CS backend with the circuit finalized.
+    r1cs_backend: R1CS<F>,
+}
+
+impl<F> AcirExporter<F>
+where
+    F: BackendField,
+{
+    pub fn new(r1cs_backend: R1CS<F>) -> Result<AcirExporter<F>, Error> {
+        if r1cs_backend.prime().to_string() != BN254_MODULUS {
+            return Err(Error::UnsupportedField);
+        }
+
+        Ok(AcirExporter { r1cs_backend })
+    }
+
+    fn convert_field(value: &F) -> FieldElement {
+        FieldElement::from_be_bytes_reduce(&value.into_repr().to_bytes_be())
+    }
+
+    fn convert_var(index: usize) -> Witness {
+        assert_ne!(index, 0, "the constant 1 is not a witness of ACIR");
+        Witness(index as u32 - 1)
+    }
+
+    /// Splits a linear combination into its terms and its constant (including the factor of the variable 0).
+    fn split_lc(lc: &LinearCombination<F>) -> (Vec<(usize, F)>, F) {
+        let mut constant = lc.constant;
+        let mut terms = vec![];
+        for (var, factor) in &lc.terms {
+            if var.index == 0 {
+                constant += factor;
+            } else {
+                terms.push((var.index, *factor));
+            }
+        }
+        (terms, constant)
+    }
+
+    /// Expands `a * b - c` into a polynomial of degree 2 in the variables.
+    fn convert_constraint(constraint: &Constraint<F>) -> Expression {
+        let (a_terms, a_constant) = Self::split_lc(&constraint.a);
+        let (b_terms, b_constant) = Self::split_lc(&constraint.b);
+        let (c_terms, c_constant) = Self::split_lc(&constraint.c);
+
+        let mut mul_terms: BTreeMap<(usize, usize), F> = BTreeMap::new();
+        let mut linear_terms: BTreeMap<usize, F> = BTreeMap::new();
+
+        for (var_a, factor_a) in &a_terms {
+            for (var_b, factor_b) in &b_terms {
+                let key = (*var_a.min(var_b), *var_a.max(var_b));
+                *mul_terms.entry(key).or_default() += *factor_a * factor_b;
+            }
+            *linear_terms.entry(*var_a).or_default() += *factor_a * b_constant;
+        }
+        for (var_b, factor_b) in &b_terms {
+            *linear_terms.entry(*var_b).or_default() += *factor_b * a_constant;
+        }
+        for (var_c, factor_c) in &c_terms {
+            *linear_terms.entry(*var_c).or_default() -= factor_c;
+        }
+
+        Expression {
+            mul_terms: mul_terms
+                .into_iter()
+                .filter(|(_, factor)| !factor.is_zero())
+                .map(|((lhs, rhs), factor)| {
+                    (
+                        Self::convert_field(&factor),
+                        Self::convert_var(lhs),
+                        Self::convert_var(rhs),
+                    )
+                })
+                .collect(),
+            linear_combinations: linear_terms
+                .into_iter()
+                .filter(|(_, factor)| !factor.is_zero())
+                .map(|(var, factor)| (Self::convert_field(&factor), Self::convert_var(var)))
+                .collect(),
+            q_c: Self::convert_field(&(a_constant * b_constant - c_constant)),
+        }
+    }
+
+    /// Returns the ACIR program of the circuit.
+    pub fn program(&self) -> Program {
+        let r1cs = &self.r1cs_backend;
+        let to_witnesses = |indices: Vec<usize>| -> BTreeSet<Witness> {
+            indices.into_iter().map(Self::convert_var).collect()
+        };
+
+        let public_inputs = to_witnesses(r1cs.public_input_indices());
+        let public_outputs = to_witnesses(r1cs.public_output_indices());
+        let private_parameters = r1cs
+            .private_input_cell_vars
+            .iter()
+            .map(|var| Self::convert_var(var.index))
+            .collect();
+
+        let circuit = Circuit {
+            current_witness_index: r1cs.num_vars().saturating_sub(2) as u32,
+            opcodes: r1cs
+                .constraints()
+                .iter()
+                .map(|constraint| Opcode::AssertZero(Self::convert_constraint(constraint)))
+                .collect(),
+            private_parameters,
+            public_parameters: PublicInputs(public_inputs),
+            return_values: PublicInputs(public_outputs),
+            ..Circuit::default()
+        };
+
+        Program {
+            functions: vec![circuit],
+            ..Program::default()
+        }
+    }
+
+    /// Generate the ACIR file: the serialized (and compressed) program, as found in the `bytecode` of Noir artifacts.
+    pub fn gen_acir_file(&self, file: &str) -> Result<(), Error> {
+        let bytes = Program::serialize_program(&self.program());
+        std::fs::write(file, bytes)?;
+        Ok(())
+    }
+
+    /// Generate the witness file of ACIR: the serialized (and compressed) witness stack, as produced by `nargo execute`.
+    pub fn gen_witness_file(&self, file: &str, witness: &GeneratedWitness<F>) -> Result<(), Error> {
+        let witness_map = WitnessMap::from(
+            witness
+                .witness
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(index, value)| (Self::convert_var(index), Self::convert_field(value)))
+                .collect::<BTreeMap<_, _>>(),
+        );
+
+        let bytes: Vec<u8> = WitnessStack::from(witness_map)
+            .try_into()
+            .map_err(|err| Error::Serialization(format!("{err:?}")))?;
+        std::fs::write(file, bytes)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acir::{
+        circuit::{Opcode, Program},
+        native_types::{Expression, Witness},
+        FieldElement,
+    };
+
+    use crate::{
+        backends::r1cs::{R1csBls12381Field, R1csBn254Field, R1CS},
+        compiler::generate_witness,
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS, WRONG_PRIVATE_INPUTS},
+    };
+
+    use super::{AcirExporter, Error};
+
+    /// Evaluates an `AssertZero` expression on the values of the witnesses.
+    fn eval(expr: &Expression, values: &BTreeMap<Witness, FieldElement>) -> FieldElement {
+        let mut res = expr.q_c;
+        for (factor, lhs, rhs) in &expr.mul_terms {
+            res = res + *factor * values[lhs] * values[rhs];
+        }
+        for (factor, var) in &expr.linear_combinations {
+            res = res + *factor * values[var];
+        }
+        res
+    }
+
+    /// Checks that all the `AssertZero` opcodes of the program hold.
+    fn is_satisfied(program: &Program, values: &BTreeMap<Witness, FieldElement>) -> bool {
+        program.functions[0]
+            .opcodes
+            .iter()
+            .all(|opcode| match opcode {
+                Opcode::AssertZero(expr) => eval(expr, values).is_zero(),
+                _ => false,
+            })
+    }
+
+    #[test]
+    fn test_acir_program() {
+        let (compiled_circuit, _) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let r1cs = compiled_circuit.circuit.backend;
+        let num_constraints = r1cs.constraints().len();
+
+        let program = AcirExporter::new(r1cs).unwrap().program();
+        let circuit = &program.functions[0];
+
+        // the output is the first var after the constant 1, then the public input, then the private input
+        assert_eq!(circuit.return_values.0, [Witness(0)].into());
+        assert_eq!(circuit.public_parameters.0, [Witness(1)].into());
+        assert_eq!(circuit.private_parameters, [Witness(2)].into());
+
+        assert_eq!(circuit.opcodes.len(), num_constraints);
+        assert!(circuit
+            .opcodes
+            .iter()
+            .all(|opcode| matches!(opcode, Opcode::AssertZero(_))));
+    }
+
+    #[test]
+    fn test_acir_program_satisfied() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(PRIVATE_INPUTS).unwrap();
+        let generated_witness =
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs).unwrap();
+        let program = AcirExporter::new(compiled_circuit.circuit.backend)
+            .unwrap()
+            .program();
+
+        let values: BTreeMap<_, _> = generated_witness
+            .witness
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(index, value)| {
+                (
+                    AcirExporter::<R1csBn254Field>::convert_var(index),
+                    AcirExporter::convert_field(value),
+                )
+            })
+            .collect();
+        assert!(is_satisfied(&program, &values));
+
+        // a tampered public input (xx) doesn't satisfy the opcodes
+        let mut tampered = values.clone();
+        tampered.insert(Witness(1), FieldElement::from(3u128));
+        assert!(!is_satisfied(&program, &tampered));
+
+        // nor does a wrong private input (yy)
+        let mut wrong = values;
+        wrong.insert(Witness(2), FieldElement::from(4u128));
+        assert!(!is_satisfied(&program, &wrong));
+    }
+
+    #[test]
+    fn test_acir_wrong_witness() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(WRONG_PRIVATE_INPUTS).unwrap();
+
+        // no witness stack can be exported for inputs that fail the assertions
+        assert!(
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs).is_err()
+        );
+    }
+
+    #[test]
+    fn test_unsupported_field() {
+        let r1cs = R1CS::<R1csBls12381Field>::new();
+        assert!(matches!(
+            AcirExporter::new(r1cs),
+            Err(Error::UnsupportedField)
+        ));
+    }
+}
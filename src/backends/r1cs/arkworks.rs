@@ -0,0 +1,187 @@
+//! Replays compiled circuits into arkworks constraint systems (see [replay_into]),
+//! so that noname circuits can be used as gadgets inside larger arkworks circuits,
+//! or proven with the proof systems of arkworks (as [crate::backends::groth16] does).
+
+use ark_ff::Zero;
+use ark_relations::r1cs::{
+    ConstraintSystemRef, LinearCombination as ArkLinearCombination, SynthesisError, Variable,
+};
+use itertools::Itertools as _;
+
+use super::{LinearCombination, R1CS};
+use crate::backends::BackendField;
+
+/// How the public inputs and outputs of the noname circuit are allocated in the arkworks constraint system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicVars {
+    /// As instance variables: the public outputs, then the public inputs (as in the snarkjs format).
+    /// This is what a circuit made only of the noname circuit wants.
+    Instance,
+
+    /// As witness variables, that the caller can then constrain to the variables of a larger circuit.
+    Witness,
+}
+
+/// The variables allocated for the public inputs and outputs of a noname circuit.
+#[derive(Debug, Clone)]
+pub struct ReplayedVars {
+    pub public_outputs: Vec<Variable>,
+    pub public_inputs: Vec<Variable>,
+}
+
+/// Replays the constraints of a compiled circuit into an arkworks constraint system.
+/// The witness (see [crate::compiler::generate_witness]) is only needed when proving, not during a setup.
+pub fn replay_into<F: BackendField>(
+    r1cs: &R1CS<F>,
+    cs: ConstraintSystemRef<F>,
+    witness: Option<&[F]>,
+    public_vars: PublicVars,
+) -> Result<ReplayedVars, SynthesisError> {
+    let value = |index: usize| {
+        move || {
+            witness
+                .map(|witness| witness[index])
+                .ok_or(SynthesisError::AssignmentMissing)
+        }
+    };
+
+    // the first var is always 1, and is the constant variable of arkworks
+    let mut vars = vec![Variable::One; r1cs.num_vars()];
+
+    // the public vars come first, in the order of the public inputs of the verifier
+    let public_indices = r1cs.public_var_indices();
+    for &index in &public_indices {
+        vars[index] = match public_vars {
+            PublicVars::Instance => cs.new_input_variable(value(index))?,
+            PublicVars::Witness => cs.new_witness_variable(value(index))?,
+        };
+    }
+
+    // the rest of the witness
+    for (index, var) in vars.iter_mut().enumerate().skip(1) {
+        if !public_indices.contains(&index) {
+            *var = cs.new_witness_variable(value(index))?;
+        }
+    }
+
+    for constraint in r1cs.constraints() {
+        cs.enforce_constraint(
+            to_ark_lc(&constraint.a, &vars),
+            to_ark_lc(&constraint.b, &vars),
+            to_ark_lc(&constraint.c, &vars),
+        )?;
+    }
+
+    let to_vars = |indices: Vec<usize>| indices.into_iter().map(|index| vars[index]).collect();
+    Ok(ReplayedVars {
+        public_outputs: to_vars(r1cs.public_output_indices()),
+        public_inputs: to_vars(r1cs.public_input_indices()),
+    })
+}
+
+/// Converts a linear combination to arkworks, where the constant is the factor of [Variable::One].
+fn to_ark_lc<F: BackendField>(
+    lc: &LinearCombination<F>,
+    vars: &[Variable],
+) -> ArkLinearCombination<F> {
+    // sort the terms, so that the constraint system doesn't depend on the order of the hash map
+    let mut terms: Vec<_> = lc
+        .terms
+        .iter()
+        .sorted_by_key(|(var, _)| var.index)
+        .map(|(var, factor)| (*factor, vars[var.index]))
+        .collect();
+
+    if !lc.constant.is_zero() {
+        terms.push((lc.constant, Variable::One));
+    }
+
+    ArkLinearCombination(terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSystem, Variable},
+    };
+
+    use crate::{
+        backends::r1cs::{R1csBn254Field, R1CS},
+        compiler::generate_witness,
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS},
+    };
+
+    use super::{replay_into, PublicVars};
+
+    /// Replays the shared program into a new constraint system, with the given changes to its witness.
+    fn replay_with(changes: &[(usize, u64)]) -> bool {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(PRIVATE_INPUTS).unwrap();
+        let mut witness =
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs)
+                .unwrap()
+                .witness;
+        for (index, value) in changes {
+            witness[*index] = R1csBn254Field::from(*value);
+        }
+
+        let cs = ConstraintSystem::<R1csBn254Field>::new_ref();
+        replay_into(
+            &compiled_circuit.circuit.backend,
+            cs.clone(),
+            Some(&witness),
+            PublicVars::Instance,
+        )
+        .unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn test_replay_as_gadget() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(PRIVATE_INPUTS).unwrap();
+        let generated_witness =
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs).unwrap();
+
+        // a larger circuit, which exposes the double of the output of the noname circuit
+        let cs = ConstraintSystem::<R1csBn254Field>::new_ref();
+        let doubled = cs
+            .new_input_variable(|| Ok(R1csBn254Field::from(16u64)))
+            .unwrap();
+        let vars = replay_into(
+            &compiled_circuit.circuit.backend,
+            cs.clone(),
+            Some(&generated_witness.witness),
+            PublicVars::Witness,
+        )
+        .unwrap();
+        let output = vars.public_outputs[0];
+        cs.enforce_constraint(
+            lc!() + output + output,
+            lc!() + Variable::One,
+            lc!() + doubled,
+        )
+        .unwrap();
+
+        assert_eq!(cs.num_instance_variables(), 2);
+        assert_eq!(cs.assigned_value(output), Some(R1csBn254Field::from(8u64)));
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_replay_tampered_witness() {
+        assert!(replay_with(&[]));
+
+        // a tampered public input (xx)
+        assert!(!replay_with(&[(2, 3)]));
+
+        // a wrong private input (yy)
+        assert!(!replay_with(&[(3, 4)]));
+    }
+}
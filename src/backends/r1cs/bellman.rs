@@ -0,0 +1,212 @@
+use std::marker::PhantomData;
+
+use ark_ff::{BigInteger, PrimeField as _};
+use bellman::{
+    Circuit, ConstraintSystem, LinearCombination as BellmanLinearCombination, SynthesisError,
+    Variable,
+};
+use ff::PrimeField;
+use itertools::Itertools as _;
+use miette::Diagnostic;
+use num_bigint::BigUint;
+use thiserror::Error;
+
+use super::{LinearCombination, R1CS};
+use crate::backends::BackendField;
+
+#[derive(Diagnostic, Debug, Error)]
+pub enum Error {
+    #[error("the scalar field of bellman ({expected}) is not the field of the circuit ({actual})")]
+    FieldMismatch { expected: BigUint, actual: BigUint },
+}
+
+/// A compiled noname circuit, as a [Circuit] of bellman (or of its fork bellperson),
+/// so that it can be proven with an existing bellman setup (e.g. Groth16 over bls12-381 with `--field bls12-381`).
+///
+/// The variable 0 is the constant 1 of bellman ([ConstraintSystem::one]),
+/// the public outputs and the public inputs are its inputs (in this order, as in the snarkjs format),
+/// and the other variables are auxiliary variables.
+pub struct NonameCircuit<'a, F, S>
+where
+    F: BackendField,
+    S: PrimeField,
+{
+    /// A R1CS backend with the circuit finalized.
+    r1cs: &'a R1CS<F>,
+
+    /// The witness (see [crate::compiler::generate_witness]), which is only needed when proving, not during a setup.
+    witness: Option<&'a [F]>,
+
+    _scalar: PhantomData<S>,
+}
+
+impl<'a, F, S> NonameCircuit<'a, F, S>
+where
+    F: BackendField,
+    S: PrimeField,
+{
+    /// The circuit without witness, to generate parameters.
+    pub fn new(r1cs: &'a R1CS<F>) -> Result<Self, Error> {
+        let expected = BigUint::parse_bytes(S::MODULUS.trim_start_matches("0x").as_bytes(), 16)
+            .expect("the modulus of a prime field is a hex string");
+        let actual = r1cs.prime();
+        if expected != actual {
+            return Err(Error::FieldMismatch { expected, actual });
+        }
+
+        Ok(Self {
+            r1cs,
+            witness: None,
+            _scalar: PhantomData,
+        })
+    }
+
+    /// The circuit with its witness, to create proofs.
+    pub fn with_witness(r1cs: &'a R1CS<F>, witness: &'a [F]) -> Result<Self, Error> {
+        let mut circuit = Self::new(r1cs)?;
+        circuit.witness = Some(witness);
+        Ok(circuit)
+    }
+
+    /// Converts a field element from arkworks to bellman, through its little-endian 64-bit limbs.
+    fn convert_field(value: &F) -> S {
+        let shift = S::from(u64::MAX) + S::ONE;
+        value
+            .into_repr()
+            .as_ref()
+            .iter()
+            .rev()
+            .fold(S::ZERO, |acc, limb| acc * shift + S::from(*limb))
+    }
+
+    /// Converts a linear combination, where the constant is the factor of [ConstraintSystem::one].
+    fn convert_lc<CS: ConstraintSystem<S>>(
+        lc: &LinearCombination<F>,
+        vars: &[Variable],
+    ) -> BellmanLinearCombination<S> {
+        // sort the terms, so that the constraint system doesn't depend on the order of the hash map
+        let terms = lc
+            .terms
+            .iter()
+            .sorted_by_key(|(var, _)| var.index)
+            .fold(BellmanLinearCombination::zero(), |acc, (var, factor)| {
+                acc + (Self::convert_field(factor), vars[var.index])
+            });
+
+        terms + (Self::convert_field(&lc.constant), CS::one())
+    }
+}
+
+impl<F, S> Circuit<S> for NonameCircuit<'_, F, S>
+where
+    F: BackendField,
+    S: PrimeField,
+{
+    fn synthesize<CS: ConstraintSystem<S>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        let r1cs = self.r1cs;
+        let witness = self.witness;
+        let value = |index: usize| {
+            witness
+                .map(|witness| Self::convert_field(&witness[index]))
+                .ok_or(SynthesisError::AssignmentMissing)
+        };
+
+        // the first var is always 1
+        let mut vars = vec![CS::one(); r1cs.num_vars()];
+
+        // the public vars come first, in the order of the public inputs of the verifier
+        let public_indices = r1cs.public_var_indices();
+        for &index in &public_indices {
+            vars[index] = cs.alloc_input(|| format!("public var {index}"), || value(index))?;
+        }
+
+        // the rest of the witness
+        for (index, var) in vars.iter_mut().enumerate().skip(1) {
+            if !public_indices.contains(&index) {
+                *var = cs.alloc(|| format!("var {index}"), || value(index))?;
+            }
+        }
+
+        for (index, constraint) in r1cs.constraints().iter().enumerate() {
+            cs.enforce(
+                || format!("constraint {index}"),
+                |_| Self::convert_lc::<CS>(&constraint.a, &vars),
+                |_| Self::convert_lc::<CS>(&constraint.b, &vars),
+                |_| Self::convert_lc::<CS>(&constraint.c, &vars),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bellman::{gadgets::test::TestConstraintSystem, Circuit};
+    use bls12_381::Scalar;
+
+    use crate::{
+        backends::r1cs::{R1csBls12381Field, R1csBn254Field, R1CS},
+        compiler::generate_witness,
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS},
+    };
+
+    use super::{Error, NonameCircuit};
+
+    #[test]
+    fn test_synthesize() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBls12381Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(PRIVATE_INPUTS).unwrap();
+        let generated_witness =
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs).unwrap();
+
+        let r1cs = &compiled_circuit.circuit.backend;
+        let circuit =
+            NonameCircuit::<_, Scalar>::with_witness(r1cs, &generated_witness.witness).unwrap();
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        circuit.synthesize(&mut cs).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), r1cs.constraints().len());
+
+        // the constant 1, the public output, and the public input
+        assert_eq!(cs.num_inputs(), 3);
+        assert_eq!(cs.get_input(1, "public var 1"), Scalar::from(8u64));
+        assert_eq!(cs.get_input(2, "public var 2"), Scalar::from(2u64));
+    }
+
+    #[test]
+    fn test_synthesize_tampered_witness() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBls12381Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(PRIVATE_INPUTS).unwrap();
+        let witness = generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs)
+            .unwrap()
+            .witness;
+        let r1cs = &compiled_circuit.circuit.backend;
+
+        // a tampered public input (xx), then a wrong private input (yy)
+        for (index, value) in [(2, 3u64), (3, 4u64)] {
+            let mut tampered = witness.clone();
+            tampered[index] = R1csBls12381Field::from(value);
+
+            let circuit = NonameCircuit::<_, Scalar>::with_witness(r1cs, &tampered).unwrap();
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            circuit.synthesize(&mut cs).unwrap();
+            assert!(!cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_field_mismatch() {
+        let r1cs = R1CS::<R1csBn254Field>::new();
+        assert!(matches!(
+            NonameCircuit::<_, Scalar>::new(&r1cs),
+            Err(Error::FieldMismatch { .. })
+        ));
+    }
+}
@@ -1,5 +1,13 @@
+#[cfg(feature = "acir")]
+pub mod acir;
+#[cfg(feature = "arkworks")]
+pub mod arkworks;
+#[cfg(feature = "bellman")]
+pub mod bellman;
 pub mod builtin;
 pub mod snarkjs;
+#[cfg(feature = "zkinterface")]
+pub mod zkinterface;
 
 use std::collections::{HashMap, HashSet};
 
@@ -19,13 +27,17 @@ use super::{Backend, BackendField, BackendVar};
 pub type R1csBls12381Field = ark_bls12_381::Fr;
 pub type R1csBn254Field = ark_bn254::Fr;
 
+/// The base field of pallas, which is also the scalar field of vesta
+/// (it is already a [BackendField], as the field of the kimchi backend).
+pub type R1csPallasField = super::kimchi::VestaField;
+
 // Because the associated field type is BackendField, we need to implement it for the actual field types in order to use them.
 impl BackendField for R1csBls12381Field {}
 impl BackendField for R1csBn254Field {}
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CellVar {
-    index: usize,
+    pub(crate) index: usize,
     pub span: Span,
 }
 
@@ -186,6 +198,23 @@ where
     fn as_array(&self) -> [&LinearCombination<F>; 3] {
         [&self.a, &self.b, &self.c]
     }
+
+    /// Returns the terms (sorted by var) and the constant of the 3 linear combinations,
+    /// which identify the constraint regardless of the spans.
+    fn key(&self) -> Vec<(Vec<(usize, F)>, F)> {
+        self.as_array()
+            .into_iter()
+            .map(|lc| {
+                let terms = lc
+                    .terms
+                    .iter()
+                    .map(|(var, factor)| (var.index, *factor))
+                    .sorted_by_key(|(index, _)| *index)
+                    .collect();
+                (terms, lc.constant)
+            })
+            .collect()
+    }
 }
 
 /// R1CS backend with bls12_381 field.
@@ -197,6 +226,8 @@ where
     /// Constraints in the r1cs.
     constraints: Vec<Constraint<F>>,
     witness_vector: Vec<Value<Self>>,
+    /// The span where each var of the witness vector was created, for the symbols of snarkjs.
+    var_spans: Vec<Span>,
     debug_info: Vec<DebugInfo>,
     /// Record the public inputs for reordering the witness vector
     public_inputs: Vec<CellVar>,
@@ -215,6 +246,7 @@ where
         Self {
             constraints: Vec::new(),
             witness_vector: Vec::new(),
+            var_spans: Vec::new(),
             debug_info: Vec::new(),
             public_inputs: Vec::new(),
             private_input_cell_vars: Vec::new(),
@@ -228,6 +260,34 @@ where
         self.constraints.len()
     }
 
+    /// Returns the constraints of the circuit.
+    pub(crate) fn constraints(&self) -> &[Constraint<F>] {
+        &self.constraints
+    }
+
+    /// Returns the number of variables, including the first one that is always 1.
+    pub(crate) fn num_vars(&self) -> usize {
+        self.witness_vector.len()
+    }
+
+    /// Returns the indices of the public inputs in the witness.
+    pub(crate) fn public_input_indices(&self) -> Vec<usize> {
+        self.public_inputs.iter().map(|var| var.index).collect()
+    }
+
+    /// Returns the indices of the public outputs in the witness.
+    pub(crate) fn public_output_indices(&self) -> Vec<usize> {
+        self.public_outputs.iter().map(|var| var.index).collect()
+    }
+
+    /// Returns the indices of the public variables in the witness:
+    /// the public outputs first, then the public inputs (as in the snarkjs format).
+    pub(crate) fn public_var_indices(&self) -> Vec<usize> {
+        let mut indices = self.public_output_indices();
+        indices.extend(self.public_input_indices());
+        indices
+    }
+
     /// Returns the prime for snarkjs based on the curve field.
     fn prime(&self) -> BigUint {
         F::Params::MODULUS.into()
@@ -310,6 +370,7 @@ where
         };
 
         self.witness_vector.insert(var.index, val);
+        self.var_spans.push(span);
 
         LinearCombination::from(var)
     }
@@ -391,6 +452,20 @@ where
         Ok(())
     }
 
+    /// Removes the constraints that are identical to a previous one (like repeated assertions).
+    fn optimize(&mut self) {
+        let constraints = std::mem::take(&mut self.constraints);
+        let debug_info = std::mem::take(&mut self.debug_info);
+
+        let mut seen = HashSet::new();
+        for (constraint, debug_info) in constraints.into_iter().zip(debug_info) {
+            if seen.insert(constraint.key()) {
+                self.constraints.push(constraint);
+                self.debug_info.push(debug_info);
+            }
+        }
+    }
+
     fn compute_var(
         &self,
         env: &mut crate::witness::WitnessEnv<Self::Field>,
@@ -400,7 +475,7 @@ where
 
         for (var, factor) in &lc.terms {
             let var_val = self.witness_vector.get(var.index).unwrap();
-            let calc = self.compute_val(env, var_val, var.index)? * factor;
+            let calc = self.compute_val(env, var_val, var.index, var.span)? * factor;
             val += calc;
         }
 
@@ -425,7 +500,7 @@ where
                     // Defer calculation for output vars.
                     // The reasoning behind this is to avoid deep recursion potentially triggered by the public output var at the beginning.
                     Value::PublicOutput(_) => Ok(F::zero()),
-                    _ => self.compute_val(witness_env, val, index),
+                    _ => self.compute_val(witness_env, val, index, self.var_spans[index]),
                 }
             })
             .collect::<crate::error::Result<Vec<F>>>()?;
@@ -605,15 +680,23 @@ mod tests {
             r1cs::{R1csBls12381Field, R1CS},
             Backend, BackendKind,
         },
+        constants::Span,
+        error::ErrorKind,
+        inputs::parse_inputs,
         lexer::Token,
         parser::{types::FnSig, FunctionDef, ParserCtx},
+        tests::compile_code,
+        var::{ComputeManyFn, Value},
+        witness::WitnessEnv,
     };
-    use ark_ff::One;
+    use ark_ff::{One, Zero};
     use rstest::rstest;
+    use std::{cell::Cell, rc::Rc, sync::Arc};
 
     #[rstest]
     #[case::bls12381(BackendKind::new_r1cs_bls12_381())]
     #[case::bn254(BackendKind::new_r1cs_bn254())]
+    #[case::pallas(BackendKind::new_r1cs_pallas())]
     fn test_prime(#[case] r1cs: BackendKind) {
         match r1cs {
             BackendKind::R1csBls12_381(r1cs) => {
@@ -630,6 +713,13 @@ mod tests {
                     "21888242871839275222246405745257275088548364400416034343698204186575808495617"
                 );
             }
+            BackendKind::R1csPallas(r1cs) => {
+                let prime = r1cs.prime().to_string();
+                assert_eq!(
+                    prime,
+                    "28948022309329048855892746252171976963363056481941560715954676764349967630337"
+                );
+            }
             _ => {
                 panic!("unexpected backend kind")
             }
@@ -650,4 +740,85 @@ mod tests {
             _ => panic!("unexpected value"),
         }
     }
+
+    #[test]
+    fn test_shared_computation_runs_once() {
+        let mut r1cs: R1CS<R1csBls12381Field> = R1CS::new();
+        let span = Span::default();
+
+        // a computation shared by two vars, which counts its runs
+        let runs = Rc::new(Cell::new(0));
+        let func: Arc<ComputeManyFn<R1csBls12381Field>> = {
+            let runs = runs.clone();
+            Arc::new(move |_| {
+                runs.set(runs.get() + 1);
+                vec![R1csBls12381Field::from(1u64), R1csBls12381Field::from(2u64)]
+            })
+        };
+        let vars = [0, 1]
+            .map(|nth| r1cs.new_internal_var(Value::ComputeNth(vec![], func.clone(), nth), span));
+
+        let mut env = WitnessEnv::default();
+        assert_eq!(
+            r1cs.compute_var(&mut env, &vars[0]).unwrap(),
+            R1csBls12381Field::from(1u64)
+        );
+        assert_eq!(
+            r1cs.compute_var(&mut env, &vars[1]).unwrap(),
+            R1csBls12381Field::from(2u64)
+        );
+        assert_eq!(runs.get(), 1);
+    }
+
+    #[test]
+    fn test_add_custom_gate_is_an_error() {
+        let mut r1cs: R1CS<R1csBls12381Field> = R1CS::new();
+        let err = r1cs
+            .add_custom_gate("xor16", &[], &[], Span::default())
+            .unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::UnknownCustomGate(name) if name == "xor16"));
+    }
+
+    #[test]
+    fn test_to_bits_strict_rejects_non_canonical_bits() {
+        let code = "
+use std::bits;
+
+fn main(pub xx: Field) {
+    let bits = bits::to_bits_strict(xx);
+}
+";
+        let compiled_circuit = compile_code(code, R1CS::<R1csBls12381Field>::new()).unwrap();
+        let r1cs = &compiled_circuit.circuit.backend;
+
+        // sets the bits of the decomposition of xx to the given integer, and generates the rest of the witness
+        let generate_witness = |bits_of: &num_bigint::BigUint| {
+            let public_inputs = parse_inputs(r#"{"xx": "1"}"#).unwrap();
+            let private_inputs = parse_inputs("{}").unwrap();
+            let mut env = compiled_circuit
+                .witness_env(public_inputs, private_inputs)
+                .unwrap();
+
+            for (index, val) in r1cs.witness_vector.iter().enumerate() {
+                if let Value::NthBit(_, ii) = val {
+                    let bit = if bits_of.bit(*ii as u64) {
+                        R1csBls12381Field::one()
+                    } else {
+                        R1csBls12381Field::zero()
+                    };
+                    env.cached_values.insert(index, bit);
+                }
+            }
+
+            r1cs.generate_witness(&mut env)
+        };
+
+        // the canonical decomposition of 1 is accepted
+        assert!(generate_witness(&1u8.into()).is_ok());
+
+        // 1 + p fits in the bits and recomposes to 1 as well, but is not canonical
+        let non_canonical = r1cs.prime() + 1u8;
+        let err = generate_witness(&non_canonical).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidWitness(_)));
+    }
 }
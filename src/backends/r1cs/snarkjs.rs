@@ -9,6 +9,9 @@ use std::io::{self, BufWriter, Seek, SeekFrom, Write};
 use std::vec;
 
 use super::{GeneratedWitness, LinearCombination, R1CS};
+use crate::compiler::Sources;
+use crate::utils::find_exact_line;
+use crate::var::Value;
 use num_bigint_dig::BigInt;
 
 #[derive(Diagnostic, Debug, Error)]
@@ -122,12 +125,20 @@ where
     /// Generate the r1cs file in snarkjs format.
     /// It uses the circom rust library to generate the r1cs file.
     /// The binary format spec: https://github.com/iden3/r1csfile/blob/master/doc/r1cs_bin_format.md
+    ///
+    /// The file contains the three sections expected by snarkjs:
+    /// - Header section: describes the prime field, and the number of wires, public inputs and outputs, and constraints.
+    /// - Constraint section: contains the constraints, as linear combinations of the wires.
+    /// - Wire to label map section: maps each wire to a label (the identity, as noname doesn't optimize wires away).
     pub fn gen_r1cs_file(&self, file: &str) -> Result<(), Error> {
         let prime = self.backend_prime();
         let field_size = field_size(&prime);
+        let total_wires = self.r1cs_backend.witness_vector.len();
 
-        let r1cs = R1CSWriter::new(file.to_string(), field_size, false).unwrap();
-        let mut constraint_section = R1CSWriter::start_constraints_section(r1cs).unwrap();
+        let r1cs = R1CSWriter::new(file.to_string(), field_size, false)
+            .map_err(|_| Error::R1CSWriterIo)?;
+        let mut constraint_section =
+            R1CSWriter::start_constraints_section(r1cs).map_err(|_| Error::R1CSWriterIo)?;
 
         let restructure_constraints = self.restructure_constraints();
 
@@ -141,8 +152,11 @@ where
             .map_err(|_| Error::R1CSWriterIo)?;
         }
 
-        let r1cs = constraint_section.end_section().unwrap();
-        let mut header_section = R1CSWriter::start_header_section(r1cs).unwrap();
+        let r1cs = constraint_section
+            .end_section()
+            .map_err(|_| Error::R1CSWriterIo)?;
+        let mut header_section =
+            R1CSWriter::start_header_section(r1cs).map_err(|_| Error::R1CSWriterIo)?;
         let header_data = HeaderData {
             // Snarkjs uses this meta data to determine which curve to use.
             field: prime,
@@ -155,24 +169,97 @@ where
             // There seems no use of this field in the snarkjs lib. It might be just a reference.
             private_inputs: self.r1cs_backend.private_input_number(),
             // Add one to take into account the first var that is only added during the witness formation for snarkjs.
-            total_wires: self.r1cs_backend.witness_vector.len(),
-            // This is for circom lang debugging, so we don't need it.
-            number_of_labels: 0,
+            total_wires,
+            // Each wire has its own label.
+            number_of_labels: total_wires,
             number_of_constraints: restructure_constraints.len(),
         };
         header_section
             .write_section(header_data)
             .map_err(|_| Error::R1CSWriterIo)?;
 
-        let r1cs = header_section.end_section().unwrap();
+        let r1cs = header_section
+            .end_section()
+            .map_err(|_| Error::R1CSWriterIo)?;
+        let mut signal_section =
+            R1CSWriter::start_signal_section(r1cs).map_err(|_| Error::R1CSWriterIo)?;
+        for wire in 0..total_wires {
+            signal_section
+                .write_signal_usize(wire)
+                .map_err(|_| Error::R1CSWriterIo)?;
+        }
+
+        let r1cs = signal_section
+            .end_section()
+            .map_err(|_| Error::R1CSWriterIo)?;
         R1CSWriter::finish_writing(r1cs).map_err(|_| Error::R1CSWriterIo)
     }
 
+    /// Generate the sym file in circom format, which maps each wire to a name,
+    /// so that external debuggers and the errors of snarkjs can refer to the variables of the program.
+    /// Each line is `wire,label,component,name` (the label is the wire, and there is a single component `main`),
+    /// where the name is the argument or the output of the main function that the wire holds (e.g. `main.xs[1]`),
+    /// or `main.var<wire>` for the other wires, followed by the location where the wire was created (e.g. `@src/main.no:3`).
+    pub fn gen_sym_file(&self, file: &str, sources: &Sources) -> Result<(), Error> {
+        let witness_vector = &self.r1cs_backend.witness_vector;
+
+        // arguments of size one are not indexed
+        let mut arg_sizes: HashMap<&str, usize> = HashMap::new();
+        for val in witness_vector {
+            if let Value::External(name, _) = val {
+                *arg_sizes.entry(name).or_default() += 1;
+            }
+        }
+        let num_outputs = self.r1cs_backend.public_outputs.len();
+
+        let mut writer = BufWriter::new(File::create(file)?);
+        let mut output_idx = 0;
+
+        // the first wire is the constant 1, which has no symbol
+        for (wire, (val, span)) in witness_vector
+            .iter()
+            .zip(&self.r1cs_backend.var_spans)
+            .enumerate()
+            .skip(1)
+        {
+            let mut name = match val {
+                Value::External(name, idx) if arg_sizes[name.as_str()] > 1 => {
+                    format!("main.{name}[{idx}]")
+                }
+                Value::External(name, _) => format!("main.{name}"),
+                Value::PublicOutput(_) => {
+                    output_idx += 1;
+                    if num_outputs > 1 {
+                        format!("main.return[{}]", output_idx - 1)
+                    } else {
+                        "main.return".to_string()
+                    }
+                }
+                _ => format!("main.var{wire}"),
+            };
+
+            if let Some((filename, source)) = sources.get(&span.filename_id) {
+                if span.filename_id != 0 && span.end() <= source.len() {
+                    let (line, _, _) = find_exact_line(source, *span);
+                    name.push_str(&format!("@{filename}:{line}"));
+                }
+            }
+
+            // commas separate the fields
+            writeln!(writer, "{wire},{wire},0,{}", name.replace(',', "_"))?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
     /// Generate the wtns file in snarkjs format.
+    /// Along with the r1cs file, it can be given to `snarkjs groth16 prove`,
+    /// as the wires are already in the order expected by snarkjs: the constant 1, the public outputs, the public inputs, and the rest.
     pub fn gen_wtns_file(&self, file: &str, witness: GeneratedWitness<F>) -> Result<(), Error> {
         let restructured_witness = self.restructure_witness(witness);
 
-        let mut witness_writer = WitnessWriter::new(file).unwrap();
+        let mut witness_writer = WitnessWriter::new(file)?;
 
         Ok(witness_writer.write(restructured_witness, &self.backend_prime())?)
     }
@@ -220,8 +307,7 @@ impl WitnessWriter {
             .write(true)
             .create(true)
             .truncate(true)
-            .open(path)
-            .unwrap();
+            .open(path)?;
         let mut writer = BufWriter::new(file);
 
         // Write the file type (magic string) as bytes
@@ -237,7 +323,7 @@ impl WitnessWriter {
         // Write the number of sections as a 32-bit unsigned integer in little endian
         writer.write_all(&n_sections.to_le_bytes())?;
 
-        let current_position = writer.stream_position().unwrap();
+        let current_position = writer.stream_position()?;
 
         Ok(WitnessWriter {
             inner: writer,
@@ -251,7 +337,7 @@ impl WitnessWriter {
         // Write the section ID as ULE32
         self.inner.write_all(&id_section.to_le_bytes())?;
         // Get the current position
-        self.section_size_position = self.inner.stream_position().unwrap();
+        self.section_size_position = self.inner.stream_position()?;
         // Temporarily write 0 as ULE64 for the section size
         self.inner.write_all(&0u64.to_le_bytes())?;
         self.writing_section = Some(WritingSection);
@@ -261,7 +347,7 @@ impl WitnessWriter {
 
     /// End the current section
     fn end_write_section(&mut self) -> Result<(), io::Error> {
-        let current_pos = self.inner.stream_position().unwrap();
+        let current_pos = self.inner.stream_position()?;
         // Calculate the size of the section
         let section_size = current_pos - self.section_size_position - 8;
 
@@ -319,3 +405,245 @@ impl WitnessWriter {
         self.inner.write_all(&buffer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ark_ff::PrimeField as _;
+
+    use crate::{
+        backends::r1cs::{R1csBn254Field, R1CS},
+        compiler::generate_witness,
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS, WRONG_PRIVATE_INPUTS},
+    };
+
+    use super::{field_size, SnarkjsExporter};
+
+    /// Reads the sections of a file in the binary format of snarkjs.
+    fn read_sections(bytes: &[u8], magic: &str) -> HashMap<u32, Vec<u8>> {
+        assert_eq!(&bytes[..4], magic.as_bytes());
+        let num_sections = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+
+        let mut sections = HashMap::new();
+        let mut offset = 12;
+        for _ in 0..num_sections {
+            let id = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+            let size = u64::from_le_bytes(bytes[offset + 4..offset + 12].try_into().unwrap());
+            let start = offset + 12;
+            let end = start + size as usize;
+            sections.insert(id, bytes[start..end].to_vec());
+            offset = end;
+        }
+        assert_eq!(offset, bytes.len());
+
+        sections
+    }
+
+    type SnarkjsLc = Vec<(usize, R1csBn254Field)>;
+
+    /// Reads the constraints section of a `.r1cs` file: for each constraint, the linear combinations a, b and c.
+    fn read_constraints(section: &[u8], n8: usize) -> Vec<[SnarkjsLc; 3]> {
+        let read_lc = |offset: &mut usize| {
+            let read_u32 = |at: usize| u32::from_le_bytes(section[at..at + 4].try_into().unwrap());
+            let num_terms = read_u32(*offset);
+            *offset += 4;
+            let mut lc = vec![];
+            for _ in 0..num_terms {
+                let wire = read_u32(*offset) as usize;
+                let factor = R1csBn254Field::from_le_bytes_mod_order(
+                    &section[*offset + 4..*offset + 4 + n8],
+                );
+                lc.push((wire, factor));
+                *offset += 4 + n8;
+            }
+            lc
+        };
+
+        let mut offset = 0;
+        let mut constraints = vec![];
+        while offset < section.len() {
+            let a = read_lc(&mut offset);
+            let b = read_lc(&mut offset);
+            let c = read_lc(&mut offset);
+            constraints.push([a, b, c]);
+        }
+        constraints
+    }
+
+    /// Checks that `a * b = c` holds for each constraint, as snarkjs does.
+    fn is_satisfied(constraints: &[[SnarkjsLc; 3]], values: &[R1csBn254Field]) -> bool {
+        let eval = |lc: &SnarkjsLc| -> R1csBn254Field {
+            lc.iter()
+                .map(|(wire, factor)| *factor * values[*wire])
+                .sum()
+        };
+        constraints
+            .iter()
+            .all(|[a, b, c]| eval(a) * eval(b) == eval(c))
+    }
+
+    #[test]
+    fn test_r1cs_file() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let exporter = SnarkjsExporter::new(compiled_circuit.circuit.backend);
+
+        let path = std::env::temp_dir().join("noname_test_r1cs_file.r1cs");
+        exporter.gen_r1cs_file(path.to_str().unwrap()).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let sections = read_sections(&bytes, "r1cs");
+        assert_eq!(sections.len(), 3);
+
+        // header: field size, prime, number of wires, outputs, inputs, private inputs, labels and constraints
+        let header = &sections[&1];
+        let field_size = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+        assert_eq!(field_size, 32);
+        let header = &header[4 + field_size..];
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+        let total_wires = read_u32(0) as usize;
+        assert_eq!(total_wires, exporter.r1cs_backend.witness_vector.len());
+        assert_eq!(read_u32(4), 1);
+        assert_eq!(read_u32(8), 1);
+        assert_eq!(read_u32(12) as usize, total_wires - 2);
+        let num_labels = u64::from_le_bytes(header[16..24].try_into().unwrap());
+        assert_eq!(num_labels as usize, total_wires);
+        let num_constraints = u32::from_le_bytes(header[24..28].try_into().unwrap());
+        assert_eq!(
+            num_constraints as usize,
+            exporter.r1cs_backend.constraints.len()
+        );
+
+        // wire to label map: the identity
+        let labels: Vec<_> = sections[&3]
+            .chunks(8)
+            .map(|label| u64::from_le_bytes(label.try_into().unwrap()))
+            .collect();
+        assert_eq!(labels, (0..total_wires as u64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_wtns_file() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(PRIVATE_INPUTS).unwrap();
+        let generated_witness =
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs).unwrap();
+        let witness = generated_witness.witness.clone();
+        let exporter = SnarkjsExporter::new(compiled_circuit.circuit.backend);
+
+        let path = std::env::temp_dir().join("noname_test_wtns_file.wtns");
+        exporter
+            .gen_wtns_file(path.to_str().unwrap(), generated_witness)
+            .unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let sections = read_sections(&bytes, "wtns");
+        assert_eq!(sections.len(), 2);
+
+        // header: field size, prime, and number of witness values
+        let header = &sections[&1];
+        let n8 = field_size(&exporter.backend_prime());
+        assert_eq!(
+            u32::from_le_bytes(header[..4].try_into().unwrap()) as usize,
+            n8
+        );
+        let num_values = u32::from_le_bytes(header[4 + n8..8 + n8].try_into().unwrap());
+        assert_eq!(num_values as usize, witness.len());
+
+        // witness: the constant 1, the public output (8), the public input (2), and the rest
+        let values: Vec<_> = sections[&2]
+            .chunks(n8)
+            .map(|value| R1csBn254Field::from_le_bytes_mod_order(value))
+            .collect();
+        assert_eq!(values, witness);
+        assert_eq!(
+            values[..3],
+            [
+                R1csBn254Field::from(1u64),
+                R1csBn254Field::from(8u64),
+                R1csBn254Field::from(2u64)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sym_file() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let exporter = SnarkjsExporter::new(compiled_circuit.circuit.backend);
+
+        let path = std::env::temp_dir().join("noname_test_sym_file.sym");
+        exporter
+            .gen_sym_file(path.to_str().unwrap(), &sources)
+            .unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), exporter.r1cs_backend.witness_vector.len() - 1);
+        assert_eq!(lines[0], "1,1,0,main.return@main.no:2");
+        assert_eq!(lines[1], "2,2,0,main.xx@main.no:2");
+        assert_eq!(lines[2], "3,3,0,main.yy@main.no:2");
+        assert!(lines[3].starts_with("4,4,0,main.var4@main.no:"));
+    }
+
+    #[test]
+    fn test_wtns_file_satisfies_r1cs_file() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(PRIVATE_INPUTS).unwrap();
+        let generated_witness =
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs).unwrap();
+        let exporter = SnarkjsExporter::new(compiled_circuit.circuit.backend);
+
+        let r1cs_path = std::env::temp_dir().join("noname_test_satisfied.r1cs");
+        let wtns_path = std::env::temp_dir().join("noname_test_satisfied.wtns");
+        exporter.gen_r1cs_file(r1cs_path.to_str().unwrap()).unwrap();
+        exporter
+            .gen_wtns_file(wtns_path.to_str().unwrap(), generated_witness)
+            .unwrap();
+        let r1cs_bytes = std::fs::read(&r1cs_path).unwrap();
+        let wtns_bytes = std::fs::read(&wtns_path).unwrap();
+        std::fs::remove_file(&r1cs_path).unwrap();
+        std::fs::remove_file(&wtns_path).unwrap();
+
+        let n8 = field_size(&exporter.backend_prime());
+        let constraints = read_constraints(&read_sections(&r1cs_bytes, "r1cs")[&2], n8);
+        let values: Vec<_> = read_sections(&wtns_bytes, "wtns")[&2]
+            .chunks(n8)
+            .map(R1csBn254Field::from_le_bytes_mod_order)
+            .collect();
+        assert!(is_satisfied(&constraints, &values));
+
+        // a tampered public input (xx) doesn't satisfy the constraints
+        let mut tampered = values.clone();
+        tampered[2] = R1csBn254Field::from(3u64);
+        assert!(!is_satisfied(&constraints, &tampered));
+
+        // nor does a wrong private input (yy)
+        let mut wrong = values;
+        wrong[3] = R1csBn254Field::from(4u64);
+        assert!(!is_satisfied(&constraints, &wrong));
+    }
+
+    #[test]
+    fn test_wtns_file_wrong_witness() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(WRONG_PRIVATE_INPUTS).unwrap();
+
+        // no witness, hence no .wtns file, is produced for inputs that fail the assertions
+        assert!(
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs).is_err()
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use ark_ff::{BigInteger, One, PrimeField, Zero};
+use itertools::Itertools as _;
+use miette::Diagnostic;
+use thiserror::Error;
+use zkinterface::{BilinearConstraint, CircuitHeader, ConstraintSystem, Variables, Witness};
+
+use super::{GeneratedWitness, LinearCombination, R1CS};
+use crate::backends::BackendField;
+
+#[derive(Diagnostic, Debug, Error)]
+pub enum Error {
+    #[error("could not write the zkinterface file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// An error type associated with the zkinterface writers, which are not specific.
+    #[error("could not write the zkinterface messages: {0}")]
+    Zkinterface(String),
+}
+
+/// A struct to export an r1cs circuit and its witness to zkinterface (https://github.com/QED-it/zkinterface),
+/// so that they can be consumed by the toolchains based on bellman or libsnark.
+///
+/// The variable 0 is the constant 1 in both noname and zkinterface, so the variables keep their indices:
+/// the public outputs and the public inputs are the instance variables, and the others are the witness.
+pub struct ZkinterfaceExporter<F>
+where
+    F: BackendField,
+{
+    /// A R1CS backend with the circuit finalized.
+    r1cs_backend: R1CS<F>,
+}
+
+impl<F> ZkinterfaceExporter<F>
+where
+    F: BackendField,
+{
+    pub fn new(r1cs_backend: R1CS<F>) -> ZkinterfaceExporter<F> {
+        ZkinterfaceExporter { r1cs_backend }
+    }
+
+    /// The number of bytes of each field element.
+    fn field_size(&self) -> usize {
+        (self.r1cs_backend.prime().bits() as usize + 7) / 8
+    }
+
+    /// Encodes field elements as zkinterface values: little-endian, with the same number of bytes each.
+    fn encode_values<'a>(&self, values: impl Iterator<Item = &'a F>) -> Vec<u8> {
+        let field_size = self.field_size();
+        values
+            .flat_map(|value| {
+                let mut bytes = value.into_repr().to_bytes_le();
+                bytes.resize(field_size, 0);
+                bytes
+            })
+            .collect()
+    }
+
+    /// Converts a linear combination, where the constant is the factor of the variable 0.
+    fn convert_lc(&self, lc: &LinearCombination<F>) -> Variables {
+        let mut terms: Vec<_> = lc
+            .terms
+            .iter()
+            .map(|(var, factor)| (var.index as u64, *factor))
+            .sorted_by_key(|(index, _)| *index)
+            .collect();
+        if !lc.constant.is_zero() {
+            terms.insert(0, (0, lc.constant));
+        }
+
+        Variables {
+            variable_ids: terms.iter().map(|(index, _)| *index).collect(),
+            values: Some(self.encode_values(terms.iter().map(|(_, factor)| factor))),
+        }
+    }
+
+    /// Generate a zkinterface file, which contains the messages of the circuit header and of the constraint system,
+    /// followed by the message of the witness if one is given.
+    /// With a witness, the header also contains the values of the instance variables.
+    pub fn gen_zkif_file(
+        &self,
+        file: &str,
+        witness: Option<&GeneratedWitness<F>>,
+    ) -> Result<(), Error> {
+        let r1cs = &self.r1cs_backend;
+        let public_vars = r1cs.public_var_indices();
+
+        let field_maximum = -F::one();
+        let header = CircuitHeader {
+            instance_variables: Variables {
+                variable_ids: public_vars.iter().map(|index| *index as u64).collect(),
+                values: witness.map(|witness| {
+                    self.encode_values(public_vars.iter().map(|index| &witness.witness[*index]))
+                }),
+            },
+            free_variable_id: r1cs.num_vars() as u64,
+            field_maximum: Some(self.encode_values(std::iter::once(&field_maximum))),
+            configuration: None,
+        };
+
+        let constraint_system = ConstraintSystem {
+            constraints: r1cs
+                .constraints()
+                .iter()
+                .map(|constraint| BilinearConstraint {
+                    linear_combination_a: self.convert_lc(&constraint.a),
+                    linear_combination_b: self.convert_lc(&constraint.b),
+                    linear_combination_c: self.convert_lc(&constraint.c),
+                })
+                .collect(),
+        };
+
+        let mut writer = BufWriter::new(File::create(file)?);
+        header
+            .write_into(&mut writer)
+            .map_err(|err| Error::Zkinterface(err.to_string()))?;
+        constraint_system
+            .write_into(&mut writer)
+            .map_err(|err| Error::Zkinterface(err.to_string()))?;
+
+        if let Some(witness) = witness {
+            // the witness contains all the variables that are not instance variables (nor the constant 1)
+            let private_vars: Vec<_> = (1..r1cs.num_vars())
+                .filter(|index| !public_vars.contains(index))
+                .collect();
+            let witness =
+                Witness {
+                    assigned_variables: Variables {
+                        variable_ids: private_vars.iter().map(|index| *index as u64).collect(),
+                        values: Some(self.encode_values(
+                            private_vars.iter().map(|index| &witness.witness[*index]),
+                        )),
+                    },
+                };
+            witness
+                .write_into(&mut writer)
+                .map_err(|err| Error::Zkinterface(err.to_string()))?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ark_ff::PrimeField as _;
+    use zkinterface::Reader;
+
+    use crate::{
+        backends::r1cs::{R1csBn254Field, R1CS},
+        compiler::generate_witness,
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS, WRONG_PRIVATE_INPUTS},
+    };
+
+    use super::ZkinterfaceExporter;
+
+    /// Exports the circuit and the witness of the shared program, and reads them back.
+    fn read_zkif_file(name: &str) -> (Reader, usize, usize) {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(PRIVATE_INPUTS).unwrap();
+        let generated_witness =
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs).unwrap();
+        let exporter = ZkinterfaceExporter::new(compiled_circuit.circuit.backend);
+
+        let path = std::env::temp_dir().join(name);
+        exporter
+            .gen_zkif_file(path.to_str().unwrap(), Some(&generated_witness))
+            .unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut reader = Reader::new();
+        reader.push_message(bytes).unwrap();
+
+        let r1cs = &exporter.r1cs_backend;
+        (reader, r1cs.constraints().len(), r1cs.num_vars())
+    }
+
+    /// Checks that the values of the instance and witness variables satisfy the constraints of the file.
+    fn is_satisfied(reader: &Reader, values: &HashMap<u64, R1csBn254Field>) -> bool {
+        reader.iter_constraints().all(|constraint| {
+            let [a, b, c] = [&constraint.a, &constraint.b, &constraint.c].map(|terms| {
+                terms
+                    .iter()
+                    .map(|term| {
+                        R1csBn254Field::from_le_bytes_mod_order(term.value) * values[&term.id]
+                    })
+                    .sum::<R1csBn254Field>()
+            });
+            a * b == c
+        })
+    }
+
+    #[test]
+    fn test_zkif_file() {
+        let (reader, num_constraints, num_vars) = read_zkif_file("noname_test_zkif_file.zkif");
+
+        // the public output (8) and the public input (2) are the instance variables
+        let instance: Vec<_> = reader
+            .instance_variables()
+            .unwrap()
+            .iter()
+            .map(|var| var.id)
+            .collect();
+        assert_eq!(instance, vec![1, 2]);
+
+        assert_eq!(reader.iter_constraints().count(), num_constraints);
+        assert_eq!(reader.iter_witness().count(), num_vars - 3);
+    }
+
+    #[test]
+    fn test_zkif_file_satisfied() {
+        let (reader, _, _) = read_zkif_file("noname_test_zkif_file_satisfied.zkif");
+
+        let mut values = HashMap::from([(0, R1csBn254Field::from(1u64))]);
+        for var in reader
+            .instance_variables()
+            .unwrap()
+            .into_iter()
+            .chain(reader.iter_witness())
+        {
+            values.insert(var.id, R1csBn254Field::from_le_bytes_mod_order(var.value));
+        }
+        assert!(is_satisfied(&reader, &values));
+
+        // a tampered public input (xx) doesn't satisfy the constraints
+        let mut tampered = values.clone();
+        tampered.insert(2, R1csBn254Field::from(3u64));
+        assert!(!is_satisfied(&reader, &tampered));
+
+        // nor does a wrong private input (yy)
+        let mut wrong = values;
+        wrong.insert(3, R1csBn254Field::from(4u64));
+        assert!(!is_satisfied(&reader, &wrong));
+    }
+
+    #[test]
+    fn test_zkif_file_wrong_witness() {
+        let (compiled_circuit, sources) =
+            compile_with_sources(CODE, R1CS::<R1csBn254Field>::new()).unwrap();
+        let public_inputs = parse_inputs(PUBLIC_INPUTS).unwrap();
+        let private_inputs = parse_inputs(WRONG_PRIVATE_INPUTS).unwrap();
+
+        // no witness message can be exported for inputs that fail the assertions
+        assert!(
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs).is_err()
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Spartan proofs (https://eprint.iacr.org/2019/550), which are transparent: they don't need a trusted setup.
+//!
+//! This reuses the lowering of [super::nova]: the constraints of a circuit written with the R1CS backend over the scalar field of Vesta
+//! are replayed into a circuit of the Nova library, which is then proven directly (without folding) with its Spartan SNARK,
+//! using inner-product-argument commitments over Vesta.
+//! The public inputs of that circuit are the public inputs of the program, followed by its public outputs.
+
+use nova_snark::{
+    provider::{ipa_pc::EvaluationEngine, VestaEngine},
+    spartan::{
+        direct::{self, DirectSNARK},
+        snark::RelaxedR1CSSNARK,
+    },
+};
+
+use crate::{
+    backends::{
+        kimchi::VestaField,
+        nova::{to_nova, Step},
+        r1cs::R1CS,
+    },
+    compiler::{generate_witness, Sources},
+    inputs::JsonInputs,
+    witness::CompiledCircuit,
+};
+
+//
+// aliases
+//
+
+type E = VestaEngine;
+
+type Snark = RelaxedR1CSSNARK<E, EvaluationEngine<E>>;
+
+/// A Spartan proof.
+pub type SpartanProof = DirectSNARK<E, Snark, Step>;
+
+//
+// Data Structures
+//
+
+pub struct ProverIndex {
+    prover_key: direct::ProverKey<E, Snark>,
+
+    /// The circuit, without witness.
+    step: Step,
+
+    compiled_circuit: CompiledCircuit<R1CS<VestaField>>,
+}
+
+pub struct VerifierIndex {
+    verifier_key: direct::VerifierKey<E, Snark>,
+
+    /// The number of public outputs, which come first in the public inputs of the verifier.
+    num_outputs: usize,
+}
+
+//
+// Setup
+//
+
+impl CompiledCircuit<R1CS<VestaField>> {
+    /// Generates the keys of the circuit (this doesn't need a trusted setup).
+    pub fn compile_to_spartan_indexes(self) -> miette::Result<(ProverIndex, VerifierIndex)> {
+        let step = Step::new(&self.circuit.backend);
+        let (prover_key, verifier_key) = SpartanProof::setup(step.clone())
+            .map_err(|err| miette::miette!("spartan: could not generate the keys: {err:?}"))?;
+
+        let verifier_index = VerifierIndex {
+            verifier_key,
+            num_outputs: self.circuit.backend.public_output_indices().len(),
+        };
+        let prover_index = ProverIndex {
+            prover_key,
+            step,
+            compiled_circuit: self,
+        };
+
+        Ok((prover_index, verifier_index))
+    }
+}
+
+//
+// Proving
+//
+
+impl ProverIndex {
+    /// returns a proof, the public inputs of the verifier (the public outputs, then the public inputs), and the public output
+    pub fn prove(
+        &self,
+        sources: &Sources,
+        public_inputs: JsonInputs,
+        private_inputs: JsonInputs,
+    ) -> miette::Result<(SpartanProof, Vec<VestaField>, Vec<VestaField>)> {
+        // generate the witness (this also checks the constraints)
+        let generated_witness = generate_witness(
+            &self.compiled_circuit,
+            sources,
+            public_inputs,
+            private_inputs,
+        )?;
+        let witness = &generated_witness.witness;
+
+        let r1cs = &self.compiled_circuit.circuit.backend;
+        let inputs: Vec<_> = r1cs
+            .public_input_indices()
+            .into_iter()
+            .map(|index| to_nova(&witness[index]))
+            .collect();
+        let full_public_inputs = r1cs
+            .public_var_indices()
+            .into_iter()
+            .map(|index| witness[index])
+            .collect();
+
+        // create proof
+        let proof = SpartanProof::prove(&self.prover_key, self.step.with_witness(witness), &inputs)
+            .map_err(|err| {
+                miette::miette!("spartan: could not create a proof with the given inputs: {err:?}")
+            })?;
+
+        Ok((proof, full_public_inputs, generated_witness.outputs))
+    }
+}
+
+//
+// Verifying
+//
+
+impl VerifierIndex {
+    /// Verifies a proof, given the public outputs followed by the public inputs.
+    pub fn verify(
+        &self,
+        full_public_inputs: &[VestaField],
+        proof: &SpartanProof,
+    ) -> miette::Result<()> {
+        if full_public_inputs.len() < self.num_outputs {
+            miette::bail!("spartan: missing public inputs");
+        }
+
+        // the circuit exposes the public inputs first
+        let (outputs, inputs) = full_public_inputs.split_at(self.num_outputs);
+        let io: Vec<_> = inputs.iter().chain(outputs).map(to_nova).collect();
+
+        proof
+            .verify(&self.verifier_key, &io)
+            .map_err(|err| miette::miette!("spartan: failed to verify the proof: {err:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        inputs::parse_inputs,
+        tests::{compile_with_sources, CODE, PRIVATE_INPUTS, PUBLIC_INPUTS, WRONG_PRIVATE_INPUTS},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_prove_and_verify() -> miette::Result<()> {
+        let (compiled_circuit, sources) = compile_with_sources(CODE, R1CS::<VestaField>::new())?;
+        let (prover_index, verifier_index) = compiled_circuit.compile_to_spartan_indexes()?;
+
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(PRIVATE_INPUTS)?;
+        let (proof, full_public_inputs, public_output) =
+            prover_index.prove(&sources, public_inputs, private_inputs)?;
+        assert_eq!(public_output, vec![VestaField::from(8u64)]);
+        assert_eq!(
+            full_public_inputs,
+            vec![VestaField::from(8u64), VestaField::from(2u64)]
+        );
+        verifier_index.verify(&full_public_inputs, &proof)?;
+
+        // a wrong public output is rejected
+        let wrong_inputs = [VestaField::from(9u64), VestaField::from(2u64)];
+        assert!(verifier_index.verify(&wrong_inputs, &proof).is_err());
+
+        // so is a tampered public input
+        let tampered_inputs = [VestaField::from(8u64), VestaField::from(3u64)];
+        assert!(verifier_index.verify(&tampered_inputs, &proof).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_wrong_witness() -> miette::Result<()> {
+        let (compiled_circuit, sources) = compile_with_sources(CODE, R1CS::<VestaField>::new())?;
+        let (prover_index, _) = compiled_circuit.compile_to_spartan_indexes()?;
+
+        let public_inputs = parse_inputs(PUBLIC_INPUTS)?;
+        let private_inputs = parse_inputs(WRONG_PRIVATE_INPUTS)?;
+        assert!(prover_index
+            .prove(&sources, public_inputs, private_inputs)
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
 use clap::Parser as _;
 use miette::Result;
 use noname::cli::{
-    cmd_build, cmd_check, cmd_init, cmd_new, cmd_prove, cmd_run, cmd_test, cmd_verify, CmdBuild,
-    CmdCheck, CmdInit, CmdNew, CmdProve, CmdRun, CmdTest, CmdVerify,
+    cmd_build, cmd_check, cmd_import_circom, cmd_init, cmd_new, cmd_prove, cmd_run, cmd_srs,
+    cmd_test, cmd_verify, cmd_vk, CmdBuild, CmdCheck, CmdImportCircom, CmdInit, CmdNew, CmdProve,
+    CmdRun, CmdSrs, CmdTest, CmdVerify, CmdVk,
 };
+#[cfg(feature = "groth16")]
+use noname::cli::{cmd_ceremony, cmd_groth16, CmdCeremony, CmdGroth16};
 
 #[derive(clap::Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -39,9 +42,26 @@ enum Commands {
     /// Run the main function and produce a proof
     Prove(CmdProve),
 
-    /// Verify a proof. This command does not currently work
+    /// Verify a proof produced by `noname prove`
     Verify(CmdVerify),
 
+    /// Produce the verification key of the current package, so that verifiers don't need to build the prover index
+    Vk(CmdVk),
+
+    /// Manage the cache of the structured reference strings (SRS), which are reused across builds
+    Srs(CmdSrs),
+
+    /// Set up, prove and verify with Groth16 over bn254 (for proofs that are cheap to verify on EVM chains)
+    #[cfg(feature = "groth16")]
+    Groth16(CmdGroth16),
+
+    /// Run the phase 2 trusted setup ceremony of Groth16 for the current package
+    #[cfg(feature = "groth16")]
+    Ceremony(CmdCeremony),
+
+    /// Translate a circom circuit (and the templates it includes) to noname
+    ImportCircom(CmdImportCircom),
+
     /// Tests a single file (as opposed to a package with a `Noname.toml` manifest file).
     /// This is intended for debugging, and should most likely not be used directly by users.
     /// This command will compile, attempt to create a proof, and verify it.
@@ -62,6 +82,13 @@ fn main() -> Result<()> {
         Commands::Run(args) => cmd_run(args),
         Commands::Prove(args) => cmd_prove(args),
         Commands::Verify(args) => cmd_verify(args),
+        Commands::Vk(args) => cmd_vk(args),
+        Commands::Srs(args) => cmd_srs(args),
+        #[cfg(feature = "groth16")]
+        Commands::Groth16(args) => cmd_groth16(args),
+        #[cfg(feature = "groth16")]
+        Commands::Ceremony(args) => cmd_ceremony(args),
+        Commands::ImportCircom(args) => cmd_import_circom(args),
 
         Commands::Test(args) => cmd_test(args),
     }
@@ -0,0 +1,134 @@
+//! Native translations of the templates of circomlib (https://github.com/iden3/circomlib)
+//! that compute their witness with unconstrained assignments (`<--`), which have no equivalent in noname.
+//! These templates are translated from the definitions below rather than from their sources.
+
+use itertools::Itertools as _;
+use num_bigint::BigUint;
+
+/// A template of circomlib, with a single input signal `in` and a single output signal `out`.
+pub(crate) struct Shim {
+    /// The name of the template in circomlib.
+    pub name: &'static str,
+
+    pub num_params: usize,
+
+    /// The size of the input signal, given the parameters of the template (or `None` for a single field element).
+    pub input_size: fn(&[usize]) -> Option<usize>,
+
+    /// The size of the output signal, given the parameters of the template (or `None` for a single field element).
+    pub output_size: fn(&[usize]) -> Option<usize>,
+
+    /// The statements of the noname function, which reads `in_` and returns the output.
+    pub body: fn(&[usize]) -> Vec<String>,
+
+    /// Whether the body uses the `bits` module of the standard library.
+    pub uses_bits: bool,
+}
+
+pub(crate) const SHIMS: &[Shim] = &[
+    Shim {
+        name: "Num2Bits",
+        num_params: 1,
+        input_size: |_| None,
+        output_size: |params| Some(params[0]),
+        body: num2bits,
+        uses_bits: true,
+    },
+    Shim {
+        name: "Bits2Num",
+        num_params: 1,
+        input_size: |params| Some(params[0]),
+        output_size: |_| None,
+        body: bits2num,
+        uses_bits: false,
+    },
+    Shim {
+        name: "IsZero",
+        num_params: 0,
+        input_size: |_| None,
+        output_size: |_| None,
+        body: |_| to_field("in_ == 0"),
+        uses_bits: false,
+    },
+    Shim {
+        name: "IsEqual",
+        num_params: 0,
+        input_size: |_| Some(2),
+        output_size: |_| None,
+        body: |_| to_field("in_[0] == in_[1]"),
+        uses_bits: false,
+    },
+    Shim {
+        name: "LessThan",
+        num_params: 1,
+        input_size: |_| Some(2),
+        output_size: |_| None,
+        body: |_| to_field("in_[0] < in_[1]"),
+        uses_bits: false,
+    },
+    Shim {
+        name: "LessEqThan",
+        num_params: 1,
+        input_size: |_| Some(2),
+        output_size: |_| None,
+        body: |_| to_field("in_[0] <= in_[1]"),
+        uses_bits: false,
+    },
+    Shim {
+        name: "GreaterThan",
+        num_params: 1,
+        input_size: |_| Some(2),
+        output_size: |_| None,
+        body: |_| to_field("in_[0] > in_[1]"),
+        uses_bits: false,
+    },
+    Shim {
+        name: "GreaterEqThan",
+        num_params: 1,
+        input_size: |_| Some(2),
+        output_size: |_| None,
+        body: |_| to_field("in_[0] >= in_[1]"),
+        uses_bits: false,
+    },
+];
+
+/// Returns the native translation of a template of circomlib, if there is one.
+pub(crate) fn find(name: &str) -> Option<&'static Shim> {
+    SHIMS.iter().find(|shim| shim.name == name)
+}
+
+/// Returns a condition as the field element 0 or 1, as circom does.
+fn to_field(condition: &str) -> Vec<String> {
+    vec![
+        format!("let cond = {condition};"),
+        "let out = if cond { 1 } else { 0 };".to_string(),
+        "return out;".to_string(),
+    ]
+}
+
+fn num2bits(params: &[usize]) -> Vec<String> {
+    let num_bits = params[0];
+    let mut lines = vec![format!("let le_bits = bits::unpack_le(in_, {num_bits});")];
+    for ii in 0..num_bits {
+        lines.push(format!("let bit_{ii} = le_bits[{ii}];"));
+        lines.push(format!("let out_{ii} = if bit_{ii} {{ 1 }} else {{ 0 }};"));
+    }
+    let out = (0..num_bits).map(|ii| format!("out_{ii}")).join(", ");
+    lines.push(format!("return [{out}];"));
+    lines
+}
+
+fn bits2num(params: &[usize]) -> Vec<String> {
+    let num_bits = params[0];
+    if num_bits == 0 {
+        return vec!["return 0;".to_string()];
+    }
+
+    let mut lines = vec!["let mut out = in_[0];".to_string()];
+    for ii in 1..num_bits {
+        let power = BigUint::from(1u32) << ii;
+        lines.push(format!("out = out + (in_[{ii}] * {power});"));
+    }
+    lines.push("return out;".to_string());
+    lines
+}
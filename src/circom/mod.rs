@@ -0,0 +1,284 @@
+//! Translates circom circuits into noname source code, to ease the migration of projects that depend on circomlib
+//! (see the `noname import-circom` command).
+//!
+//! Only a subset of circom is supported, where the templates can be unrolled at compile time:
+//!
+//! - the parameters of templates, the variables, the conditions of `if`s and ternary operators,
+//!   the bounds of loops, and the indices of arrays must be known at compile time
+//!   (variables can also accumulate signals, as in `var sum = 0; for (...) { sum += in[i]; }`),
+//! - signals are only assigned with constrained assignments (`<==`, `==>`) and constrained with `===`,
+//!   as noname has no unconstrained assignments (`<--`),
+//! - expressions on signals only use `+`, `-`, `*`, `/`, and `**` with constant exponents,
+//! - functions only compute values known at compile time.
+//!
+//! Each template instantiated with different parameters becomes a noname function (e.g. `Num2Bits(8)` becomes `num2_bits_8`),
+//! which takes the input signals as arguments, and returns the output signal (or a struct, if there are several).
+//! The templates of circomlib that rely on unconstrained assignments (e.g. `Num2Bits`, `IsZero` or `LessThan`)
+//! are translated natively (see [circomlib]), so their circomlib sources don't need to be found.
+
+use std::collections::{HashMap, HashSet};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use miette::Diagnostic;
+use thiserror::Error;
+
+use self::{
+    parser::{parse, Item, MainComponent},
+    translator::Translator,
+};
+
+mod circomlib;
+mod parser;
+mod translator;
+
+#[derive(Diagnostic, Debug, Error)]
+pub enum Error {
+    #[error("could not read `{path}`")]
+    Io {
+        path: Utf8PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("no main component is defined (expected `component main = Template(...);`)")]
+    NoMainComponent,
+
+    #[error("{file}:{line}: {msg}")]
+    Circom {
+        file: String,
+        line: usize,
+        msg: String,
+    },
+}
+
+impl Error {
+    pub(crate) fn circom(file: &str, line: usize, msg: impl Into<String>) -> Self {
+        Self::Circom {
+            file: file.to_string(),
+            line,
+            msg: msg.into(),
+        }
+    }
+}
+
+/// Translates a circom file, and the files it includes, into noname source code.
+/// Includes are looked up relative to the including file, then in the given include paths (like `-l` for circom).
+pub fn import_circom(path: &Utf8Path, include_paths: &[Utf8PathBuf]) -> Result<String, Error> {
+    let mut files = vec![];
+    let mut visited = HashSet::new();
+    let mut unresolved_includes = vec![];
+    let mut to_visit = vec![path.to_path_buf()];
+
+    while let Some(path) = to_visit.pop() {
+        let canonical = path.canonicalize_utf8().unwrap_or_else(|_| path.clone());
+        if !visited.insert(canonical) {
+            continue;
+        }
+
+        let code = std::fs::read_to_string(&path).map_err(|source| Error::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let items = parse(path.as_str(), &code)?;
+
+        let dir = path.parent().map(Utf8Path::to_path_buf).unwrap_or_default();
+        for item in &items {
+            let Item::Include(include) = item else {
+                continue;
+            };
+            let found = std::iter::once(&dir)
+                .chain(include_paths)
+                .map(|dir| dir.join(include))
+                .find(|candidate| candidate.is_file());
+            match found {
+                Some(found) => to_visit.push(found),
+                None => unresolved_includes.push(include.clone()),
+            }
+        }
+
+        files.push((path.to_string(), items));
+    }
+
+    translate_files(&files, &unresolved_includes)
+}
+
+/// Translates circom source code, which can't include other files, into noname source code.
+pub fn translate(filename: &str, code: &str) -> Result<String, Error> {
+    let items = parse(filename, code)?;
+    let unresolved_includes: Vec<_> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Include(include) => Some(include.clone()),
+            _ => None,
+        })
+        .collect();
+
+    translate_files(&[(filename.to_string(), items)], &unresolved_includes)
+}
+
+fn translate_files(
+    files: &[(String, Vec<Item>)],
+    unresolved_includes: &[String],
+) -> Result<String, Error> {
+    let mut templates = HashMap::new();
+    let mut functions = HashMap::new();
+    let mut main: Option<(&MainComponent, &str)> = None;
+
+    for (file, items) in files {
+        for item in items {
+            match item {
+                Item::Include(_) => (),
+                Item::Template(template) => {
+                    if templates
+                        .insert(template.name.as_str(), (template, file.as_str()))
+                        .is_some()
+                    {
+                        return Err(Error::circom(
+                            file,
+                            template.line,
+                            format!("the template `{}` is defined twice", template.name),
+                        ));
+                    }
+                }
+                Item::Function(function) => {
+                    if functions
+                        .insert(function.name.as_str(), (function, file.as_str()))
+                        .is_some()
+                    {
+                        return Err(Error::circom(
+                            file,
+                            function.line,
+                            format!("the function `{}` is defined twice", function.name),
+                        ));
+                    }
+                }
+                Item::Main(component) => {
+                    if main.is_some() {
+                        return Err(Error::circom(
+                            file,
+                            component.line,
+                            "the main component is defined twice",
+                        ));
+                    }
+                    main = Some((component, file));
+                }
+            }
+        }
+    }
+
+    let (main, file) = main.ok_or(Error::NoMainComponent)?;
+    Translator::new(templates, functions, unresolved_includes).translate(main, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::{
+        backends::r1cs::{R1csBn254Field, R1CS},
+        compiler::{compile, generate_witness, typecheck_next_file, Sources},
+        inputs::parse_inputs,
+        type_checker::TypeChecker,
+    };
+
+    use super::{translate, Error};
+
+    const CIRCOM: &str = r#"
+pragma circom 2.0.0;
+
+include "circomlib/circuits/bitify.circom";
+
+template Multiplier() {
+    signal input a;
+    signal input b;
+    signal output c;
+    c <== a * b;
+}
+
+// the sum of the products of two vectors, which must fit in 8 bits
+template SumOfProducts(n) {
+    signal input xs[n];
+    signal input ys[n];
+    signal output out;
+
+    component muls[n];
+    var sum = 0;
+    for (var i = 0; i < n; i++) {
+        muls[i] = Multiplier();
+        muls[i].a <== xs[i];
+        muls[i].b <== ys[i];
+        sum += muls[i].c;
+    }
+
+    component range = Num2Bits(8);
+    range.in <== sum;
+    out <== sum;
+}
+
+component main {public [xs]} = SumOfProducts(2);
+"#;
+
+    #[test]
+    fn test_translate() {
+        let code = translate("main.circom", CIRCOM).unwrap();
+        assert!(code.contains("fn main(pub xs: [Field; 2], ys: [Field; 2]) -> Field {"));
+
+        let mut sources = Sources::new();
+        let mut tast = TypeChecker::new();
+        typecheck_next_file(
+            &mut tast,
+            None,
+            &mut sources,
+            "main.no".to_string(),
+            code,
+            0,
+        )
+        .unwrap();
+        let compiled_circuit = compile(&sources, tast, R1CS::<R1csBn254Field>::new()).unwrap();
+        let public_inputs = parse_inputs(r#"{"xs": ["1", "2"]}"#).unwrap();
+        let private_inputs = parse_inputs(r#"{"ys": ["3", "4"]}"#).unwrap();
+        let generated_witness =
+            generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs).unwrap();
+
+        assert_eq!(generated_witness.outputs, vec![R1csBn254Field::from(11u64)]);
+    }
+
+    #[test]
+    fn test_unconstrained_assignment() {
+        let code = "
+template Inverse() {
+    signal input in;
+    signal output out;
+    out <-- 1 / in;
+    out * in === 1;
+}
+
+component main = Inverse();
+";
+        assert!(matches!(
+            translate("main.circom", code),
+            Err(Error::Circom { line: 5, .. })
+        ));
+    }
+
+    #[rstest]
+    #[case::template(
+        "template Main() {}\ntemplate Main() {}\ncomponent main = Main();",
+        "main.circom:2: the template `Main` is defined twice"
+    )]
+    #[case::function(
+        "function ff() { return 1; }\nfunction ff() { return 2; }\ncomponent main = Main();",
+        "main.circom:2: the function `ff` is defined twice"
+    )]
+    #[case::main(
+        "template Main() {}\ncomponent main = Main();\ncomponent main = Main();",
+        "main.circom:3: the main component is defined twice"
+    )]
+    #[case::no_main(
+        "template Main() {}",
+        "no main component is defined (expected `component main = Template(...);`)"
+    )]
+    fn test_items_errors(#[case] code: &str, #[case] expected: &str) {
+        let err = translate("main.circom", code).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+}
@@ -0,0 +1,1158 @@
+//! A lexer and a parser for the subset of circom that is translated to noname (see [super]).
+
+use num_bigint::BigUint;
+use num_traits::Num as _;
+
+use super::Error;
+
+//
+// Lexer
+//
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token {
+    Ident(String),
+    Number(BigUint),
+    Str(String),
+    Symbol(&'static str),
+}
+
+/// The symbols of circom, where the longest ones come first.
+const SYMBOLS: &[&str] = &[
+    "<==", "==>", "<--", "-->", "===", "**=", "<<=", ">>=", "==", "!=", "<=", ">=", "<<", ">>",
+    "&&", "||", "**", "++", "--", "+=", "-=", "*=", "/=", "\\=", "%=", "&=", "|=", "^=", "+", "-",
+    "*", "/", "\\", "%", "^", "&", "|", "~", "!", "?", ":", ";", ",", ".", "(", ")", "[", "]", "{",
+    "}", "=", "<", ">",
+];
+
+/// Splits circom code into tokens, along with their line.
+fn lex(file: &str, code: &str) -> Result<Vec<(Token, usize)>, Error> {
+    let bytes = code.as_bytes();
+    let mut tokens = vec![];
+    let mut line = 1;
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        let rest = &code[idx..];
+        let c = bytes[idx];
+
+        if c == b'\n' {
+            line += 1;
+            idx += 1;
+        } else if c.is_ascii_whitespace() {
+            idx += 1;
+        } else if rest.starts_with("//") {
+            idx += rest.find('\n').unwrap_or(rest.len());
+        } else if rest.starts_with("/*") {
+            let end = rest
+                .find("*/")
+                .ok_or_else(|| Error::circom(file, line, "unterminated comment"))?;
+            line += rest[..end].matches('\n').count();
+            idx += end + 2;
+        } else if c == b'"' {
+            let end = rest[1..]
+                .find('"')
+                .ok_or_else(|| Error::circom(file, line, "unterminated string"))?;
+            tokens.push((Token::Str(rest[1..end + 1].to_string()), line));
+            idx += end + 2;
+        } else if c.is_ascii_alphanumeric() || c == b'_' || c == b'$' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            let word = &rest[..len];
+            let token =
+                if c.is_ascii_digit() {
+                    let number = match word.strip_prefix("0x") {
+                        Some(hex) => BigUint::from_str_radix(hex, 16),
+                        None => BigUint::from_str_radix(word, 10),
+                    };
+                    Token::Number(number.map_err(|_| {
+                        Error::circom(file, line, format!("invalid number `{word}`"))
+                    })?)
+                } else {
+                    Token::Ident(word.to_string())
+                };
+            tokens.push((token, line));
+            idx += len;
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .copied()
+                .find(|symbol| rest.starts_with(symbol))
+                .ok_or_else(|| {
+                    let c = rest.chars().next().unwrap();
+                    Error::circom(file, line, format!("unexpected character `{c}`"))
+                })?;
+            tokens.push((Token::Symbol(symbol), line));
+            idx += symbol.len();
+        }
+    }
+
+    Ok(tokens)
+}
+
+//
+// AST
+//
+
+#[derive(Debug)]
+pub(crate) enum Item {
+    Include(String),
+    Template(Template),
+    Function(Function),
+    Main(MainComponent),
+}
+
+#[derive(Debug)]
+pub(crate) struct Template {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Vec<Stmt>,
+    pub line: usize,
+}
+
+/// A function of circom, which computes values known at compile time.
+#[derive(Debug)]
+pub(crate) struct Function {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Vec<Stmt>,
+    pub line: usize,
+}
+
+/// `component main {public [a, b]} = Template(args);`
+#[derive(Debug)]
+pub(crate) struct MainComponent {
+    pub public: Vec<String>,
+    pub call: Expr,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SignalKind {
+    Input,
+    Output,
+    Intermediate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AssignOp {
+    /// `=`, for variables and components.
+    Var,
+
+    /// `+=`, `*=`, etc., for variables.
+    Compound(BinOp),
+
+    /// `<==` (and `==>`).
+    Constrained,
+
+    /// `<--` (and `-->`).
+    Unconstrained,
+}
+
+#[derive(Debug)]
+pub(crate) struct Stmt {
+    pub kind: StmtKind,
+    pub line: usize,
+}
+
+#[derive(Debug)]
+pub(crate) enum StmtKind {
+    Signal {
+        kind: SignalKind,
+        name: String,
+        dims: Vec<Expr>,
+    },
+    Var {
+        name: String,
+        dims: Vec<Expr>,
+    },
+    Component {
+        name: String,
+        dims: Vec<Expr>,
+    },
+    Assign {
+        lhs: Expr,
+        op: AssignOp,
+        rhs: Expr,
+    },
+    /// `lhs === rhs`
+    Constrain {
+        lhs: Expr,
+        rhs: Expr,
+    },
+    If {
+        cond: Expr,
+        then_: Box<Stmt>,
+        else_: Option<Box<Stmt>>,
+    },
+    For {
+        init: Box<Stmt>,
+        cond: Expr,
+        step: Box<Stmt>,
+        body: Box<Stmt>,
+    },
+    While {
+        cond: Expr,
+        body: Box<Stmt>,
+    },
+    /// A block, with its own scope.
+    Block(Vec<Stmt>),
+    /// Several statements in the same scope (e.g. `var a = 1, b;`).
+    Seq(Vec<Stmt>),
+    Return(Expr),
+    Assert(Expr),
+    Log,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnaryOp {
+    Neg,
+    Not,
+    BitNot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    IntDiv,
+    Mod,
+    Pow,
+    Shl,
+    Shr,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+impl BinOp {
+    fn parse(symbol: &str) -> Option<Self> {
+        let op = match symbol {
+            "+" => Self::Add,
+            "-" => Self::Sub,
+            "*" => Self::Mul,
+            "/" => Self::Div,
+            "\\" => Self::IntDiv,
+            "%" => Self::Mod,
+            "**" => Self::Pow,
+            "<<" => Self::Shl,
+            ">>" => Self::Shr,
+            "&" => Self::BitAnd,
+            "|" => Self::BitOr,
+            "^" => Self::BitXor,
+            "==" => Self::Eq,
+            "!=" => Self::NotEq,
+            "<" => Self::Lt,
+            "<=" => Self::Le,
+            ">" => Self::Gt,
+            ">=" => Self::Ge,
+            "&&" => Self::And,
+            "||" => Self::Or,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// The binding power of the operator (higher binds tighter).
+    fn precedence(self) -> u8 {
+        match self {
+            Self::Or => 1,
+            Self::And => 2,
+            Self::BitOr => 3,
+            Self::BitXor => 4,
+            Self::BitAnd => 5,
+            Self::Eq | Self::NotEq => 6,
+            Self::Lt | Self::Le | Self::Gt | Self::Ge => 7,
+            Self::Shl | Self::Shr => 8,
+            Self::Add | Self::Sub => 9,
+            Self::Mul | Self::Div | Self::IntDiv | Self::Mod => 10,
+            Self::Pow => 11,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::IntDiv => "\\",
+            Self::Mod => "%",
+            Self::Pow => "**",
+            Self::Shl => "<<",
+            Self::Shr => ">>",
+            Self::BitAnd => "&",
+            Self::BitOr => "|",
+            Self::BitXor => "^",
+            Self::Eq => "==",
+            Self::NotEq => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::And => "&&",
+            Self::Or => "||",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Expr {
+    pub kind: ExprKind,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum ExprKind {
+    Number(BigUint),
+    Variable(String),
+    /// `array[idx]`
+    Index(Box<Expr>, Box<Expr>),
+    /// `component.signal`
+    Member(Box<Expr>, String),
+    Call(String, Vec<Expr>),
+    Array(Vec<Expr>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Ternary(Box<Expr>, Box<Expr>, Box<Expr>),
+}
+
+//
+// Parser
+//
+
+struct Parser<'a> {
+    file: &'a str,
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+/// Parses the items of a circom file.
+pub(crate) fn parse(file: &str, code: &str) -> Result<Vec<Item>, Error> {
+    let mut parser = Parser {
+        file,
+        tokens: lex(file, code)?,
+        pos: 0,
+    };
+
+    let mut items = vec![];
+    while parser.peek().is_some() {
+        if let Some(item) = parser.item()? {
+            items.push(item);
+        }
+    }
+    Ok(items)
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map(|(_, line)| *line)
+            .unwrap_or(1)
+    }
+
+    fn error(&self, msg: impl Into<String>) -> Error {
+        Error::circom(self.file, self.line(), msg)
+    }
+
+    fn bump(&mut self) -> Result<Token, Error> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .map(|(token, _)| token.clone())
+            .ok_or_else(|| self.error("unexpected end of file"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(s)) if s == keyword)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = self.is_symbol(symbol);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), Error> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{symbol}`")))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, Error> {
+        match self.bump()? {
+            Token::Ident(ident) => Ok(ident),
+            _ => {
+                self.pos -= 1;
+                Err(self.error("expected an identifier"))
+            }
+        }
+    }
+
+    /// Parses a comma-separated list of items until the `end` symbol.
+    fn list<T>(
+        &mut self,
+        end: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        let mut items = vec![];
+        while !self.eat_symbol(end) {
+            items.push(item(self)?);
+            if !self.eat_symbol(",") {
+                self.expect_symbol(end)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    //
+    // Items
+    //
+
+    /// Parses an item, or skips a pragma.
+    fn item(&mut self) -> Result<Option<Item>, Error> {
+        let line = self.line();
+        let keyword = self.expect_ident()?;
+        let item = match keyword.as_str() {
+            "pragma" => {
+                while !self.eat_symbol(";") {
+                    self.bump()?;
+                }
+                return Ok(None);
+            }
+            "include" => {
+                let Token::Str(path) = self.bump()? else {
+                    return Err(Error::circom(
+                        self.file,
+                        line,
+                        "expected the path of the included file",
+                    ));
+                };
+                self.expect_symbol(";")?;
+                Item::Include(path)
+            }
+            "template" => {
+                // the modifiers don't matter to the translation
+                let _ = self.eat_keyword("custom") || self.eat_keyword("parallel");
+                let name = self.expect_ident()?;
+                self.expect_symbol("(")?;
+                let params = self.list(")", Self::expect_ident)?;
+                let body = self.block()?;
+                Item::Template(Template {
+                    name,
+                    params,
+                    body,
+                    line,
+                })
+            }
+            "function" => {
+                let name = self.expect_ident()?;
+                self.expect_symbol("(")?;
+                let params = self.list(")", Self::expect_ident)?;
+                let body = self.block()?;
+                Item::Function(Function {
+                    name,
+                    params,
+                    body,
+                    line,
+                })
+            }
+            "component" if self.eat_keyword("main") => {
+                let mut public = vec![];
+                if self.eat_symbol("{") {
+                    if !self.eat_keyword("public") {
+                        return Err(self.error("expected `public`"));
+                    }
+                    self.expect_symbol("[")?;
+                    public = self.list("]", Self::expect_ident)?;
+                    self.expect_symbol("}")?;
+                }
+                self.expect_symbol("=")?;
+                let call = self.expr()?;
+                self.expect_symbol(";")?;
+                Item::Main(MainComponent { public, call, line })
+            }
+            _ => {
+                return Err(Error::circom(
+                    self.file,
+                    line,
+                    format!("unsupported item `{keyword}`"),
+                ))
+            }
+        };
+        Ok(Some(item))
+    }
+
+    //
+    // Statements
+    //
+
+    fn block(&mut self) -> Result<Vec<Stmt>, Error> {
+        self.expect_symbol("{")?;
+        let mut stmts = vec![];
+        while !self.eat_symbol("}") {
+            stmts.push(self.stmt()?);
+        }
+        Ok(stmts)
+    }
+
+    fn stmt(&mut self) -> Result<Stmt, Error> {
+        let line = self.line();
+        let stmt = |kind| Stmt { kind, line };
+
+        if self.is_symbol("{") {
+            return Ok(stmt(StmtKind::Block(self.block()?)));
+        }
+
+        let keyword = match self.peek() {
+            Some(Token::Ident(keyword)) => keyword.clone(),
+            _ => String::new(),
+        };
+        match keyword.as_str() {
+            "signal" | "var" | "component" => {
+                let stmt = self.declaration()?;
+                self.expect_symbol(";")?;
+                Ok(stmt)
+            }
+            "if" => {
+                self.pos += 1;
+                self.expect_symbol("(")?;
+                let cond = self.expr()?;
+                self.expect_symbol(")")?;
+                let then_ = Box::new(self.stmt()?);
+                let else_ = if self.eat_keyword("else") {
+                    Some(Box::new(self.stmt()?))
+                } else {
+                    None
+                };
+                Ok(stmt(StmtKind::If { cond, then_, else_ }))
+            }
+            "for" => {
+                self.pos += 1;
+                self.expect_symbol("(")?;
+                let init = Box::new(self.simple_stmt()?);
+                self.expect_symbol(";")?;
+                let cond = self.expr()?;
+                self.expect_symbol(";")?;
+                let step = Box::new(self.simple_stmt()?);
+                self.expect_symbol(")")?;
+                let body = Box::new(self.stmt()?);
+                Ok(stmt(StmtKind::For {
+                    init,
+                    cond,
+                    step,
+                    body,
+                }))
+            }
+            "while" => {
+                self.pos += 1;
+                self.expect_symbol("(")?;
+                let cond = self.expr()?;
+                self.expect_symbol(")")?;
+                let body = Box::new(self.stmt()?);
+                Ok(stmt(StmtKind::While { cond, body }))
+            }
+            "return" => {
+                self.pos += 1;
+                let expr = self.expr()?;
+                self.expect_symbol(";")?;
+                Ok(stmt(StmtKind::Return(expr)))
+            }
+            "assert" => {
+                self.pos += 1;
+                self.expect_symbol("(")?;
+                let expr = self.expr()?;
+                self.expect_symbol(")")?;
+                self.expect_symbol(";")?;
+                Ok(stmt(StmtKind::Assert(expr)))
+            }
+            "log" => {
+                // logs have no effect on the circuit
+                self.pos += 1;
+                self.expect_symbol("(")?;
+                let mut depth = 1;
+                while depth > 0 {
+                    match self.bump()? {
+                        Token::Symbol("(") => depth += 1,
+                        Token::Symbol(")") => depth -= 1,
+                        _ => (),
+                    }
+                }
+                self.expect_symbol(";")?;
+                Ok(stmt(StmtKind::Log))
+            }
+            _ => {
+                let stmt = self.simple_stmt()?;
+                self.expect_symbol(";")?;
+                Ok(stmt)
+            }
+        }
+    }
+
+    /// A declaration of signals, variables or components, with their initialization.
+    fn declaration(&mut self) -> Result<Stmt, Error> {
+        let line = self.line();
+        let keyword = self.expect_ident()?;
+        let signal_kind = if keyword == "signal" {
+            let kind = if self.eat_keyword("input") {
+                SignalKind::Input
+            } else if self.eat_keyword("output") {
+                SignalKind::Output
+            } else {
+                SignalKind::Intermediate
+            };
+
+            // tags (e.g. `{binary}`) are not checked by the translation
+            if self.eat_symbol("{") {
+                while !self.eat_symbol("}") {
+                    self.bump()?;
+                }
+            }
+            Some(kind)
+        } else {
+            None
+        };
+
+        let mut stmts = vec![];
+        loop {
+            let line = self.line();
+            let name = self.expect_ident()?;
+            let mut dims = vec![];
+            while self.eat_symbol("[") {
+                dims.push(self.expr()?);
+                self.expect_symbol("]")?;
+            }
+
+            let kind = match (signal_kind, keyword.as_str()) {
+                (Some(kind), _) => StmtKind::Signal {
+                    kind,
+                    name: name.clone(),
+                    dims,
+                },
+                (None, "var") => StmtKind::Var {
+                    name: name.clone(),
+                    dims,
+                },
+                _ => StmtKind::Component {
+                    name: name.clone(),
+                    dims,
+                },
+            };
+            stmts.push(Stmt { kind, line });
+
+            // initialization
+            let op = if self.eat_symbol("=") {
+                Some(AssignOp::Var)
+            } else if self.eat_symbol("<==") {
+                Some(AssignOp::Constrained)
+            } else if self.eat_symbol("<--") {
+                Some(AssignOp::Unconstrained)
+            } else {
+                None
+            };
+            if let Some(op) = op {
+                let lhs = Expr {
+                    kind: ExprKind::Variable(name),
+                    line,
+                };
+                let rhs = self.expr()?;
+                stmts.push(Stmt {
+                    kind: StmtKind::Assign { lhs, op, rhs },
+                    line,
+                });
+            }
+
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+
+        Ok(Stmt {
+            kind: StmtKind::Seq(stmts),
+            line,
+        })
+    }
+
+    /// A statement that is not terminated by `;`, as found in the header of for loops.
+    fn simple_stmt(&mut self) -> Result<Stmt, Error> {
+        if self.is_keyword("var") {
+            return self.declaration();
+        }
+
+        let line = self.line();
+        let lhs = self.expr()?;
+        let kind = match self.bump()? {
+            Token::Symbol("===") => StmtKind::Constrain {
+                lhs,
+                rhs: self.expr()?,
+            },
+            Token::Symbol(symbol @ ("++" | "--")) => {
+                let op = if symbol == "++" {
+                    BinOp::Add
+                } else {
+                    BinOp::Sub
+                };
+                let rhs = Expr {
+                    kind: ExprKind::Number(1u32.into()),
+                    line,
+                };
+                StmtKind::Assign {
+                    lhs,
+                    op: AssignOp::Compound(op),
+                    rhs,
+                }
+            }
+            // the value goes to the right
+            Token::Symbol(symbol @ ("==>" | "-->")) => {
+                let op = if symbol == "==>" {
+                    AssignOp::Constrained
+                } else {
+                    AssignOp::Unconstrained
+                };
+                StmtKind::Assign {
+                    lhs: self.expr()?,
+                    op,
+                    rhs: lhs,
+                }
+            }
+            Token::Symbol(symbol) => {
+                let op =
+                    match symbol {
+                        "=" => AssignOp::Var,
+                        "<==" => AssignOp::Constrained,
+                        "<--" => AssignOp::Unconstrained,
+                        _ => {
+                            let op = symbol.strip_suffix('=').and_then(BinOp::parse).ok_or_else(
+                                || Error::circom(self.file, line, format!("unexpected `{symbol}`")),
+                            )?;
+                            AssignOp::Compound(op)
+                        }
+                    };
+                StmtKind::Assign {
+                    lhs,
+                    op,
+                    rhs: self.expr()?,
+                }
+            }
+            _ => {
+                return Err(Error::circom(
+                    self.file,
+                    line,
+                    "expected an assignment or a constraint",
+                ))
+            }
+        };
+
+        Ok(Stmt { kind, line })
+    }
+
+    //
+    // Expressions
+    //
+
+    fn expr(&mut self) -> Result<Expr, Error> {
+        let cond = self.binary(0)?;
+        if !self.eat_symbol("?") {
+            return Ok(cond);
+        }
+
+        let then_ = self.expr()?;
+        self.expect_symbol(":")?;
+        let else_ = self.expr()?;
+        Ok(Expr {
+            line: cond.line,
+            kind: ExprKind::Ternary(Box::new(cond), Box::new(then_), Box::new(else_)),
+        })
+    }
+
+    /// Parses binary operations whose operators bind tighter than `min_precedence`.
+    fn binary(&mut self, min_precedence: u8) -> Result<Expr, Error> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol(symbol)) => BinOp::parse(symbol),
+                _ => None,
+            };
+            let Some(op) = op.filter(|op| op.precedence() > min_precedence) else {
+                return Ok(lhs);
+            };
+            self.pos += 1;
+
+            // exponentiation is right-associative
+            let precedence = if op == BinOp::Pow {
+                op.precedence() - 1
+            } else {
+                op.precedence()
+            };
+            let rhs = self.binary(precedence)?;
+            lhs = Expr {
+                line: lhs.line,
+                kind: ExprKind::Binary(op, Box::new(lhs), Box::new(rhs)),
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, Error> {
+        let line = self.line();
+        let op = if self.eat_symbol("-") {
+            UnaryOp::Neg
+        } else if self.eat_symbol("!") {
+            UnaryOp::Not
+        } else if self.eat_symbol("~") {
+            UnaryOp::BitNot
+        } else {
+            return self.postfix();
+        };
+
+        // unary operators bind tighter than any binary operator but exponentiation
+        let expr = self.binary(BinOp::Pow.precedence() - 1)?;
+        Ok(Expr {
+            kind: ExprKind::Unary(op, Box::new(expr)),
+            line,
+        })
+    }
+
+    fn postfix(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.primary()?;
+        loop {
+            let line = self.line();
+            if self.eat_symbol("[") {
+                let idx = self.expr()?;
+                self.expect_symbol("]")?;
+                expr = Expr {
+                    kind: ExprKind::Index(Box::new(expr), Box::new(idx)),
+                    line,
+                };
+            } else if self.eat_symbol(".") {
+                let member = self.expect_ident()?;
+                expr = Expr {
+                    kind: ExprKind::Member(Box::new(expr), member),
+                    line,
+                };
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, Error> {
+        let line = self.line();
+        let kind = match self.bump()? {
+            Token::Number(number) => ExprKind::Number(number),
+            Token::Ident(name) => {
+                if self.eat_symbol("(") {
+                    let args = self.list(")", Self::expr)?;
+                    if self.is_symbol("(") {
+                        return Err(self.error("anonymous components are not supported"));
+                    }
+                    ExprKind::Call(name, args)
+                } else {
+                    ExprKind::Variable(name)
+                }
+            }
+            Token::Symbol("(") => {
+                let expr = self.expr()?;
+                self.expect_symbol(")")?;
+                return Ok(expr);
+            }
+            Token::Symbol("[") => ExprKind::Array(self.list("]", Self::expr)?),
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("expected an expression"));
+            }
+        };
+        Ok(Expr { kind, line })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+    use rstest::rstest;
+
+    use super::{parse, Expr, ExprKind, Item, Stmt, StmtKind, UnaryOp};
+
+    /// Prints an expression with all its parenthesis, to check how it was parsed.
+    fn show(expr: &Expr) -> String {
+        match &expr.kind {
+            ExprKind::Number(number) => number.to_string(),
+            ExprKind::Variable(name) => name.clone(),
+            ExprKind::Index(array, idx) => format!("{}[{}]", show(array), show(idx)),
+            ExprKind::Member(component, member) => format!("{}.{member}", show(component)),
+            ExprKind::Call(name, args) => format!("{name}({})", args.iter().map(show).join(", ")),
+            ExprKind::Array(items) => format!("[{}]", items.iter().map(show).join(", ")),
+            ExprKind::Unary(op, inner) => {
+                let symbol = match op {
+                    UnaryOp::Neg => "-",
+                    UnaryOp::Not => "!",
+                    UnaryOp::BitNot => "~",
+                };
+                format!("({symbol}{})", show(inner))
+            }
+            ExprKind::Binary(op, lhs, rhs) => {
+                format!("({} {} {})", show(lhs), op.symbol(), show(rhs))
+            }
+            ExprKind::Ternary(cond, then_, else_) => {
+                format!("({} ? {} : {})", show(cond), show(then_), show(else_))
+            }
+        }
+    }
+
+    /// Prints a statement on a single line, to check how it was parsed.
+    fn show_stmt(stmt: &Stmt) -> String {
+        let dims = |dims: &[Expr]| dims.iter().map(|dim| format!("[{}]", show(dim))).join("");
+        match &stmt.kind {
+            StmtKind::Signal {
+                kind,
+                name,
+                dims: d,
+            } => format!("signal {kind:?} {name}{}", dims(d)),
+            StmtKind::Var { name, dims: d } => format!("var {name}{}", dims(d)),
+            StmtKind::Component { name, dims: d } => format!("component {name}{}", dims(d)),
+            StmtKind::Assign { lhs, op, rhs } => format!("{} {op:?} {}", show(lhs), show(rhs)),
+            StmtKind::Constrain { lhs, rhs } => format!("{} === {}", show(lhs), show(rhs)),
+            StmtKind::If { cond, then_, else_ } => {
+                let else_ = else_
+                    .as_ref()
+                    .map(|else_| format!(" else {}", show_stmt(else_)))
+                    .unwrap_or_default();
+                format!("if {} {}{else_}", show(cond), show_stmt(then_))
+            }
+            StmtKind::For {
+                init,
+                cond,
+                step,
+                body,
+            } => format!(
+                "for ({}; {}; {}) {}",
+                show_stmt(init),
+                show(cond),
+                show_stmt(step),
+                show_stmt(body)
+            ),
+            StmtKind::While { cond, body } => format!("while {} {}", show(cond), show_stmt(body)),
+            StmtKind::Block(stmts) => format!("{{ {} }}", stmts.iter().map(show_stmt).join("; ")),
+            StmtKind::Seq(stmts) => stmts.iter().map(show_stmt).join("; "),
+            StmtKind::Return(expr) => format!("return {}", show(expr)),
+            StmtKind::Assert(expr) => format!("assert {}", show(expr)),
+            StmtKind::Log => "log".to_string(),
+        }
+    }
+
+    /// Parses the body of a template, which starts on the second line.
+    fn parse_body(body: &str) -> Vec<Stmt> {
+        let code = format!("template Main() {{\n{body}\n}}\n");
+        let mut items = parse("main.circom", &code).unwrap();
+        match items.remove(0) {
+            Item::Template(template) => template.body,
+            item => panic!("expected a template, found {item:?}"),
+        }
+    }
+
+    #[test]
+    fn test_items() {
+        let code = r#"pragma circom 2.1.6;
+include "circomlib/circuits/bitify.circom";
+
+/* a template
+   and a function */
+template custom Main(nn, mm) {
+    signal input xx;
+}
+
+function double(aa) {
+    return aa * 2;
+}
+
+// the main component
+component main {public [xx]} = Main(1, 0x10);
+"#;
+        let items = parse("main.circom", code).unwrap();
+        assert_eq!(items.len(), 4);
+
+        let Item::Include(include) = &items[0] else {
+            panic!("expected an include");
+        };
+        assert_eq!(include, "circomlib/circuits/bitify.circom");
+
+        let Item::Template(template) = &items[1] else {
+            panic!("expected a template");
+        };
+        assert_eq!(template.name, "Main");
+        assert_eq!(template.params, ["nn", "mm"]);
+        assert_eq!(template.line, 6);
+        assert_eq!(template.body.len(), 1);
+
+        let Item::Function(function) = &items[2] else {
+            panic!("expected a function");
+        };
+        assert_eq!(function.name, "double");
+        assert_eq!(function.params, ["aa"]);
+        assert_eq!(function.line, 10);
+        assert_eq!(show_stmt(&function.body[0]), "return (aa * 2)");
+
+        let Item::Main(main) = &items[3] else {
+            panic!("expected the main component");
+        };
+        assert_eq!(main.public, ["xx"]);
+        assert_eq!(show(&main.call), "Main(1, 16)");
+        assert_eq!(main.line, 15);
+    }
+
+    #[test]
+    fn test_statements() {
+        let body = r#"    signal input {binary} flags[2][3];
+    signal output out <== 1, other;
+    var aa = 1, bb;
+    component cc[2];
+    cc[0] = Num2Bits(8);
+    cc[0].in <== aa;
+    cc[0].out ==> out;
+    out --> aa;
+    aa <-- 1;
+    out === aa;
+    aa += 2;
+    aa++;
+    bb--;
+    aa **= 2;
+    if (aa) { bb = 1; } else bb = 2;
+    for (var ii = 0; ii < 2; ii++) bb *= 2;
+    while (bb > 0) { bb \= 2; }
+    assert(aa > 0);
+    log("aa", (aa));
+    { var dd; }"#;
+        let stmts = parse_body(body);
+        let expected = [
+            "signal Input flags[2][3]",
+            "signal Output out; out Constrained 1; signal Output other",
+            "var aa; aa Var 1; var bb",
+            "component cc[2]",
+            "cc[0] Var Num2Bits(8)",
+            "cc[0].in Constrained aa",
+            "out Constrained cc[0].out",
+            "aa Unconstrained out",
+            "aa Unconstrained 1",
+            "out === aa",
+            "aa Compound(Add) 2",
+            "aa Compound(Add) 1",
+            "bb Compound(Sub) 1",
+            "aa Compound(Pow) 2",
+            "if aa { bb Var 1 } else bb Var 2",
+            "for (var ii; ii Var 0; (ii < 2); ii Compound(Add) 1) bb Compound(Mul) 2",
+            "while (bb > 0) { bb Compound(IntDiv) 2 }",
+            "assert (aa > 0)",
+            "log",
+            "{ var dd }",
+        ];
+        assert_eq!(stmts.iter().map(show_stmt).collect_vec(), expected);
+
+        // the body starts on the second line
+        let lines = stmts.iter().map(|stmt| stmt.line).collect_vec();
+        assert_eq!(lines, (2..22).collect_vec());
+    }
+
+    #[rstest]
+    #[case::precedence("1 + 2 * 3", "(1 + (2 * 3))")]
+    #[case::parenthesis("(1 + 2) * 3", "((1 + 2) * 3)")]
+    #[case::left_associative("1 - 2 - 3", "((1 - 2) - 3)")]
+    #[case::pow_right_associative("2 ** 3 ** 2", "(2 ** (3 ** 2))")]
+    #[case::neg_of_pow("-2 ** 2", "(-(2 ** 2))")]
+    #[case::neg_of_product("-aa * 2", "((-aa) * 2)")]
+    #[case::int_div_and_mod(r"0x1f % 4 \ 2", r"((31 % 4) \ 2)")]
+    #[case::bitwise("aa & 1 | bb ^ 2 << 1", "((aa & 1) | (bb ^ (2 << 1)))")]
+    #[case::boolean("aa < 1 && bb == 2 || !cc", "(((aa < 1) && (bb == 2)) || (!cc))")]
+    #[case::bit_not("~aa >= 1", "((~aa) >= 1)")]
+    #[case::ternary("aa ? bb : cc ? 1 : 2", "(aa ? bb : (cc ? 1 : 2))")]
+    #[case::accessors("cc[1].out[0]", "cc[1].out[0]")]
+    #[case::call_and_array("ff(1, [2, 3])", "ff(1, [2, 3])")]
+    fn test_expressions(#[case] expr: &str, #[case] expected: &str) {
+        let stmts = parse_body(&format!("    vv = {expr};"));
+        let StmtKind::Assign { rhs, .. } = &stmts[0].kind else {
+            panic!("expected an assignment");
+        };
+        assert_eq!(show(rhs), expected);
+    }
+
+    #[rstest]
+    #[case::unterminated_comment("/* a comment", "main.circom:1: unterminated comment")]
+    #[case::unterminated_string(r#"include "lib.circom;"#, "main.circom:1: unterminated string")]
+    #[case::invalid_number(
+        "template Main() {\n    var vv = 0xzz;\n}",
+        "main.circom:2: invalid number `0xzz`"
+    )]
+    #[case::unexpected_character(
+        "template Main() {\n    var vv = 1 # 2;\n}",
+        "main.circom:2: unexpected character `#`"
+    )]
+    #[case::end_of_file(
+        "template Main() {\n    var vv = 1;",
+        "main.circom:2: unexpected end of file"
+    )]
+    #[case::missing_semicolon(
+        "template Main() {\n    var vv = 1\n}",
+        "main.circom:3: expected `;`"
+    )]
+    #[case::missing_identifier("template 1() {}", "main.circom:1: expected an identifier")]
+    #[case::include_without_path(
+        "include lib;",
+        "main.circom:1: expected the path of the included file"
+    )]
+    #[case::main_without_public(
+        "component main {private [xx]} = Main();",
+        "main.circom:1: expected `public`"
+    )]
+    #[case::unsupported_item("bus Point() {}", "main.circom:1: unsupported item `bus`")]
+    #[case::unexpected_symbol("template Main() {\n    vv;\n}", "main.circom:2: unexpected `;`")]
+    #[case::not_an_assignment(
+        "template Main() {\n    vv ww;\n}",
+        "main.circom:2: expected an assignment or a constraint"
+    )]
+    #[case::anonymous_component(
+        "template Main() {\n    vv <== Other()(ww);\n}",
+        "main.circom:2: anonymous components are not supported"
+    )]
+    #[case::missing_expression(
+        "template Main() {\n    vv <== ;\n}",
+        "main.circom:2: expected an expression"
+    )]
+    fn test_parse_errors(#[case] code: &str, #[case] expected: &str) {
+        let err = parse("main.circom", code).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+}
@@ -0,0 +1,2215 @@
+//! The translation of circom templates into noname functions (see [super]).
+//!
+//! Templates are executed symbolically, as circom does when it generates constraints:
+//! the parameters, the variables that only hold constants, and the loops are evaluated at compile time,
+//! while the expressions that involve signals are emitted as noname code.
+
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use itertools::Itertools as _;
+use num_bigint::BigInt;
+use num_traits::{Signed as _, ToPrimitive as _, Zero as _};
+
+use super::{
+    circomlib::{self, Shim},
+    parser::{
+        AssignOp, BinOp, Expr, ExprKind, Function, MainComponent, SignalKind, Stmt, StmtKind,
+        Template, UnaryOp,
+    },
+    Error,
+};
+use crate::{lexer::Keyword, stdlib::BUILTIN_FN_NAMES};
+
+/// The number of iterations after which a loop is considered infinite.
+const MAX_ITERATIONS: usize = 1 << 20;
+
+/// The maximum depth of nested calls to circom functions.
+const MAX_CALL_DEPTH: usize = 256;
+
+const UNCONSTRAINED_ASSIGNMENT: &str = "unconstrained assignments (`<--`) have no equivalent in noname: \
+    use a template of circomlib that is translated natively (e.g. `Num2Bits` or `IsZero`), or rewrite the template with `<==`";
+
+/// Converts a circom name to a noname identifier: in snake case, with at least two characters, and not a keyword.
+fn sanitize(name: &str) -> String {
+    let mut ident = String::new();
+    let mut after_lowercase = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if after_lowercase {
+                ident.push('_');
+            }
+            ident.push(c.to_ascii_lowercase());
+            after_lowercase = false;
+        } else if c.is_ascii_alphanumeric() {
+            ident.push(c);
+            after_lowercase = true;
+        } else {
+            ident.push('_');
+            after_lowercase = false;
+        }
+    }
+
+    // identifiers start with a letter
+    let ident = ident.trim_start_matches(|c: char| !c.is_ascii_alphabetic());
+    match ident.len() {
+        0 => "value".to_string(),
+        1 => ident.repeat(2),
+        _ if Keyword::parse(ident).is_some() => format!("{ident}_"),
+        _ => ident.to_string(),
+    }
+}
+
+/// The name of an element of an array (e.g. `out_1_2` for `out[1][2]`), from its index in the flattened array.
+fn element_name(name: &str, dims: &[usize], index: usize) -> String {
+    if dims.is_empty() {
+        return name.to_string();
+    }
+
+    let mut indices = vec![];
+    let mut rest = index;
+    for dim in dims.iter().rev() {
+        indices.push(rest % dim);
+        rest /= dim;
+    }
+    format!("{name}_{}", indices.iter().rev().join("_"))
+}
+
+/// The noname type of a signal.
+fn signal_type(dims: &[usize]) -> String {
+    if dims.is_empty() {
+        "Field".to_string()
+    } else {
+        format!("[Field; {}]", dims.iter().product::<usize>())
+    }
+}
+
+/// A value during the translation: either known at compile time, or noname code.
+#[derive(Debug, Clone)]
+enum Value {
+    Const(BigInt),
+    Code {
+        text: String,
+
+        /// Whether the code can be the operand of an operation without parenthesis.
+        atomic: bool,
+
+        /// Whether the code reads a mutable variable, whose value might change later.
+        volatile: bool,
+    },
+}
+
+impl Value {
+    fn code(text: String, atomic: bool, volatile: bool) -> Self {
+        Self::Code {
+            text,
+            atomic,
+            volatile,
+        }
+    }
+
+    fn is_const(&self, value: i32) -> bool {
+        matches!(self, Self::Const(cst) if *cst == BigInt::from(value))
+    }
+
+    fn is_volatile(&self) -> bool {
+        matches!(self, Self::Code { volatile: true, .. })
+    }
+
+    /// The noname code of the value.
+    fn text(&self) -> String {
+        match self {
+            Self::Const(cst) if cst.is_negative() => format!("0 - {}", -cst),
+            Self::Const(cst) => cst.to_string(),
+            Self::Code { text, .. } => text.clone(),
+        }
+    }
+
+    /// The noname code of the value, as the operand of an operation
+    /// (noname requires parenthesis around nested operations).
+    fn operand(&self) -> String {
+        let atomic = match self {
+            Self::Const(cst) => !cst.is_negative(),
+            Self::Code { atomic, .. } => *atomic,
+        };
+        if atomic {
+            self.text()
+        } else {
+            format!("({})", self.text())
+        }
+    }
+}
+
+/// A signal of a translated template, as seen by the callers of its function.
+#[derive(Debug)]
+struct SignalInfo {
+    name: String,
+
+    /// The name of the argument (for inputs) or of the field of the returned struct (for outputs).
+    ident: String,
+
+    dims: Vec<usize>,
+}
+
+impl SignalInfo {
+    fn size(&self) -> usize {
+        self.dims.iter().product()
+    }
+}
+
+/// A template instantiated with some parameters, translated into a noname function.
+#[derive(Debug)]
+struct Instance {
+    fn_name: String,
+    inputs: Vec<SignalInfo>,
+    outputs: Vec<SignalInfo>,
+
+    /// The struct returned by the function, when the template has several outputs.
+    struct_name: Option<String>,
+}
+
+//
+// State of a template (or a function) being translated
+//
+
+#[derive(Debug, Clone, Copy)]
+enum Entity {
+    Signal(usize),
+    Var(usize),
+    Component(usize),
+}
+
+struct Signal {
+    kind: SignalKind,
+    name: String,
+
+    /// The name of the argument, for inputs.
+    ident: String,
+
+    dims: Vec<usize>,
+
+    /// The noname code of each element, once it is assigned.
+    elements: Vec<Option<String>>,
+}
+
+struct VarSlot {
+    /// The value, when it is known at compile time.
+    value: Option<BigInt>,
+
+    /// The mutable noname variable holding the value, once it was assigned a value that is not known at compile time.
+    ident: Option<String>,
+}
+
+struct Var {
+    name: String,
+    dims: Vec<usize>,
+    elements: Vec<VarSlot>,
+}
+
+#[derive(Default)]
+struct ComponentSlot {
+    instance: Option<Rc<Instance>>,
+
+    /// The value of each element of each input signal, once it is assigned.
+    inputs: Vec<Vec<Option<Value>>>,
+
+    /// The noname variable holding the outputs, once the function is called.
+    result: Option<String>,
+
+    called: bool,
+}
+
+struct Component {
+    name: String,
+    dims: Vec<usize>,
+    elements: Vec<ComponentSlot>,
+}
+
+/// What an expression refers to, where `start` and `len` select elements of the flattened arrays.
+enum Place {
+    Signal {
+        id: usize,
+        start: usize,
+        len: usize,
+    },
+    Var {
+        id: usize,
+        start: usize,
+        len: usize,
+    },
+    Component {
+        id: usize,
+        index: usize,
+    },
+    ComponentSignal {
+        id: usize,
+        index: usize,
+        output: bool,
+        signal: usize,
+        start: usize,
+        len: usize,
+    },
+}
+
+enum Accessor {
+    Index(BigInt),
+    Member(String),
+}
+
+enum Flow {
+    Next,
+    Return(Value),
+}
+
+struct Body {
+    file: String,
+
+    /// Whether this is a circom function, which only computes values known at compile time.
+    is_function: bool,
+
+    call_depth: usize,
+
+    /// The statements of the noname function.
+    lines: Vec<String>,
+
+    /// The names that are taken in the noname function.
+    names: HashSet<String>,
+
+    scopes: Vec<HashMap<String, Entity>>,
+    signals: Vec<Signal>,
+    vars: Vec<Var>,
+    components: Vec<Component>,
+
+    /// The input and output signals, in the order of their declaration.
+    inputs: Vec<usize>,
+    outputs: Vec<usize>,
+}
+
+impl Body {
+    fn new(file: &str, is_function: bool, reserved: &HashSet<String>) -> Self {
+        Self {
+            file: file.to_string(),
+            is_function,
+            call_depth: 0,
+            lines: vec![],
+            names: if is_function {
+                HashSet::new()
+            } else {
+                reserved.clone()
+            },
+            scopes: vec![HashMap::new()],
+            signals: vec![],
+            vars: vec![],
+            components: vec![],
+            inputs: vec![],
+            outputs: vec![],
+        }
+    }
+
+    fn error(&self, line: usize, msg: impl Into<String>) -> Error {
+        Error::circom(&self.file, line, msg)
+    }
+
+    /// Returns a new noname identifier, based on a circom name.
+    fn fresh(&mut self, name: &str) -> String {
+        let base = sanitize(name);
+        let mut ident = base.clone();
+        let mut counter = 2;
+        while self.names.contains(&ident) {
+            ident = format!("{base}_{counter}");
+            counter += 1;
+        }
+        self.names.insert(ident.clone());
+        ident
+    }
+
+    fn emit(&mut self, line: String) {
+        self.lines.push(format!("    {line}"));
+    }
+
+    fn declare(&mut self, name: &str, entity: Entity) {
+        self.scopes
+            .last_mut()
+            .expect("there is always a scope")
+            .insert(name.to_string(), entity);
+    }
+
+    fn lookup(&self, name: &str) -> Option<Entity> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+    }
+
+    fn declare_var(&mut self, name: &str, dims: Vec<usize>, value: BigInt) {
+        let size = dims.iter().product();
+        let elements = (0..size)
+            .map(|_| VarSlot {
+                value: Some(value.clone()),
+                ident: None,
+            })
+            .collect();
+        let id = self.vars.len();
+        self.vars.push(Var {
+            name: name.to_string(),
+            dims,
+            elements,
+        });
+        self.declare(name, Entity::Var(id));
+    }
+
+    fn read_var(&self, id: usize, index: usize) -> Value {
+        let slot = &self.vars[id].elements[index];
+        match (&slot.value, &slot.ident) {
+            (Some(value), _) => Value::Const(value.clone()),
+            (None, Some(ident)) => Value::code(ident.clone(), true, true),
+            (None, None) => unreachable!("a variable always has a value"),
+        }
+    }
+
+    fn write_var(&mut self, id: usize, index: usize, value: Value) {
+        if let Value::Const(value) = value {
+            // the noname variable (if any) is not read while the value is known
+            self.vars[id].elements[index].value = Some(value);
+            return;
+        }
+
+        let text = value.text();
+        match self.vars[id].elements[index].ident.clone() {
+            Some(ident) => self.emit(format!("{ident} = {text};")),
+            None => {
+                let var = &self.vars[id];
+                let name = element_name(&var.name, &var.dims, index);
+                let ident = self.fresh(&name);
+                self.emit(format!("let mut {ident} = {text};"));
+                self.vars[id].elements[index].ident = Some(ident);
+            }
+        }
+        self.vars[id].elements[index].value = None;
+    }
+
+    /// Calls the function of a component once all its inputs are assigned, as circom does.
+    fn call_if_ready(&mut self, id: usize, index: usize) {
+        let component = &self.components[id];
+        let slot = &component.elements[index];
+        let Some(instance) = slot.instance.clone() else {
+            return;
+        };
+        if slot.called || slot.inputs.iter().flatten().any(Option::is_none) {
+            return;
+        }
+
+        let args = instance
+            .inputs
+            .iter()
+            .zip(&slot.inputs)
+            .map(|(input, values)| {
+                let values = values.iter().flatten().map(Value::text);
+                if input.dims.is_empty() {
+                    values.collect::<String>()
+                } else {
+                    format!("[{}]", values.join(", "))
+                }
+            })
+            .join(", ");
+        let call = format!("{}({args})", instance.fn_name);
+
+        let result = if instance.outputs.is_empty() {
+            self.emit(format!("{call};"));
+            None
+        } else {
+            let name = element_name(&component.name, &component.dims, index);
+            let ident = self.fresh(&name);
+            self.emit(format!("let {ident} = {call};"));
+            Some(ident)
+        };
+
+        let slot = &mut self.components[id].elements[index];
+        slot.result = result;
+        slot.called = true;
+    }
+
+    /// Checks that the outputs are assigned, and that the components are called.
+    fn check_complete(&self, template: &Template) -> Result<(), Error> {
+        for &id in &self.outputs {
+            let signal = &self.signals[id];
+            if let Some(index) = signal.elements.iter().position(Option::is_none) {
+                let name = element_name(&signal.name, &signal.dims, index);
+                return Err(self.error(
+                    template.line,
+                    format!(
+                        "the output `{name}` of `{}` is never assigned",
+                        template.name
+                    ),
+                ));
+            }
+        }
+
+        for component in &self.components {
+            for (index, slot) in component.elements.iter().enumerate() {
+                if slot.instance.is_some() && !slot.called {
+                    let name = element_name(&component.name, &component.dims, index);
+                    return Err(self.error(
+                        template.line,
+                        format!("the inputs of the component `{name}` are never all assigned"),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//
+// Translator
+//
+
+pub(crate) struct Translator<'a> {
+    templates: HashMap<&'a str, (&'a Template, &'a str)>,
+    functions: HashMap<&'a str, (&'a Function, &'a str)>,
+
+    /// The includes that could not be found, to help with undefined templates.
+    unresolved_includes: &'a [String],
+
+    instances: HashMap<(String, Vec<BigInt>), Rc<Instance>>,
+
+    /// The instances being translated, to detect recursions.
+    in_progress: HashSet<(String, Vec<BigInt>)>,
+
+    /// The names that the variables of the functions can't take.
+    reserved: HashSet<String>,
+
+    fn_names: HashSet<String>,
+
+    /// The noname structs and functions, where the callees come before their callers.
+    items: Vec<String>,
+
+    uses_bits: bool,
+}
+
+impl<'a> Translator<'a> {
+    pub(crate) fn new(
+        templates: HashMap<&'a str, (&'a Template, &'a str)>,
+        functions: HashMap<&'a str, (&'a Function, &'a str)>,
+        unresolved_includes: &'a [String],
+    ) -> Self {
+        let mut reserved: HashSet<String> = BUILTIN_FN_NAMES.iter().cloned().collect();
+        reserved.extend(["main", "bits", "std"].map(String::from));
+        reserved.extend(templates.keys().map(|name| sanitize(name)));
+        reserved.extend(circomlib::SHIMS.iter().map(|shim| sanitize(shim.name)));
+
+        Self {
+            templates,
+            functions,
+            unresolved_includes,
+            instances: HashMap::new(),
+            in_progress: HashSet::new(),
+            reserved,
+            fn_names: HashSet::new(),
+            items: vec![],
+            uses_bits: false,
+        }
+    }
+
+    /// Translates the main component, and everything it instantiates, into a noname program.
+    pub(crate) fn translate(mut self, main: &MainComponent, file: &str) -> Result<String, Error> {
+        let ExprKind::Call(name, args) = &main.call.kind else {
+            return Err(Error::circom(
+                file,
+                main.line,
+                "the main component must instantiate a template",
+            ));
+        };
+
+        let mut body = Body::new(file, true, &self.reserved);
+        let args = args
+            .iter()
+            .map(|arg| self.eval_const(&mut body, arg, "the parameters of the main component"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if circomlib::find(name).is_some() {
+            return Err(Error::circom(
+                file,
+                main.line,
+                format!("the main component can't be `{name}`, which is translated natively"),
+            ));
+        }
+        let (template, template_file) = self.template(name, file, main.line)?;
+        if args.len() != template.params.len() {
+            return Err(Error::circom(
+                file,
+                main.line,
+                format!("`{name}` expects {} parameters", template.params.len()),
+            ));
+        }
+
+        let (_, items) =
+            self.translate_template(template, template_file, &args, Some(&main.public))?;
+        self.items.extend(items);
+
+        let mut source = format!("// translated from `{file}` by `noname import-circom`\n\n");
+        if self.uses_bits {
+            source.push_str("use std::bits;\n\n");
+        }
+        source.push_str(&self.items.join("\n"));
+        Ok(source)
+    }
+
+    fn template(
+        &self,
+        name: &str,
+        file: &str,
+        line: usize,
+    ) -> Result<(&'a Template, &'a str), Error> {
+        self.templates.get(name).copied().ok_or_else(|| {
+            let mut msg = format!("the template `{name}` is not defined");
+            if !self.unresolved_includes.is_empty() {
+                let includes = self.unresolved_includes.iter().join("`, `");
+                msg.push_str(&format!(" (these includes were not found: `{includes}`)"));
+            }
+            Error::circom(file, line, msg)
+        })
+    }
+
+    /// Returns the name of the function of a template instantiated with some parameters (e.g. `num2_bits_8`).
+    fn fn_name(&mut self, name: &str, args: &[BigInt]) -> String {
+        let mut fn_name = sanitize(name);
+        for arg in args {
+            if arg.is_negative() {
+                fn_name.push_str(&format!("_m{}", -arg));
+            } else {
+                fn_name.push_str(&format!("_{arg}"));
+            }
+        }
+
+        if BUILTIN_FN_NAMES.contains(&fn_name) || fn_name == "main" {
+            fn_name.push('_');
+        }
+        while !self.fn_names.insert(fn_name.clone()) {
+            fn_name.push('_');
+        }
+        self.reserved.insert(fn_name.clone());
+        fn_name
+    }
+
+    /// Returns the translation of a template instantiated with some parameters, translating it if needed.
+    fn instance(
+        &mut self,
+        name: &str,
+        args: Vec<BigInt>,
+        file: &str,
+        line: usize,
+    ) -> Result<Rc<Instance>, Error> {
+        let key = (name.to_string(), args);
+        if let Some(instance) = self.instances.get(&key) {
+            return Ok(instance.clone());
+        }
+        if !self.in_progress.insert(key.clone()) {
+            return Err(Error::circom(
+                file,
+                line,
+                format!("the template `{name}` instantiates itself"),
+            ));
+        }
+
+        let args = &key.1;
+        let result = if let Some(shim) = circomlib::find(name) {
+            self.translate_shim(shim, args, file, line)
+        } else {
+            let (template, template_file) = self.template(name, file, line)?;
+            if args.len() != template.params.len() {
+                return Err(Error::circom(
+                    file,
+                    line,
+                    format!("`{name}` expects {} parameters", template.params.len()),
+                ));
+            }
+            self.translate_template(template, template_file, args, None)
+        };
+        self.in_progress.remove(&key);
+
+        let (instance, items) = result?;
+        self.items.extend(items);
+        let instance = Rc::new(instance);
+        self.instances.insert(key, instance.clone());
+        Ok(instance)
+    }
+
+    /// Translates a template into a noname function (named `main` for the main component, whose public inputs are given),
+    /// and returns it along with the struct of its outputs if it needs one.
+    fn translate_template(
+        &mut self,
+        template: &Template,
+        file: &str,
+        args: &[BigInt],
+        public: Option<&[String]>,
+    ) -> Result<(Instance, Vec<String>), Error> {
+        let mut body = Body::new(file, false, &self.reserved);
+        for (param, arg) in template.params.iter().zip(args) {
+            body.declare_var(param, vec![], arg.clone());
+        }
+        for stmt in &template.body {
+            self.exec(&mut body, stmt)?;
+        }
+        body.check_complete(template)?;
+
+        let fn_name = match public {
+            Some(_) => "main".to_string(),
+            None => self.fn_name(&template.name, args),
+        };
+
+        let inputs: Vec<_> = body
+            .inputs
+            .iter()
+            .map(|&id| {
+                let signal = &body.signals[id];
+                SignalInfo {
+                    name: signal.name.clone(),
+                    ident: signal.ident.clone(),
+                    dims: signal.dims.clone(),
+                }
+            })
+            .collect();
+        let mut fields = HashSet::new();
+        let outputs: Vec<_> = body
+            .outputs
+            .iter()
+            .map(|&id| {
+                let signal = &body.signals[id];
+                let mut ident = sanitize(&signal.name);
+                while !fields.insert(ident.clone()) {
+                    ident.push('_');
+                }
+                SignalInfo {
+                    name: signal.name.clone(),
+                    ident,
+                    dims: signal.dims.clone(),
+                }
+            })
+            .collect();
+
+        // the arguments
+        if let Some(public) = public {
+            if let Some(name) = public
+                .iter()
+                .find(|name| !inputs.iter().any(|input| &input.name == *name))
+            {
+                return Err(Error::circom(
+                    file,
+                    template.line,
+                    format!("`{name}` is not an input of the main component"),
+                ));
+            }
+        }
+        let params = inputs
+            .iter()
+            .map(|input| {
+                let is_public = public.is_some_and(|public| public.contains(&input.name));
+                let visibility = if is_public { "pub " } else { "" };
+                format!("{visibility}{}: {}", input.ident, signal_type(&input.dims))
+            })
+            .join(", ");
+
+        // the returned value
+        let elements = |id: usize| -> Vec<String> {
+            body.signals[id]
+                .elements
+                .iter()
+                .map(|element| element.clone().expect("outputs are assigned"))
+                .collect()
+        };
+        let expr = |id: usize, dims: &[usize]| {
+            let elements = elements(id);
+            if dims.is_empty() {
+                elements[0].clone()
+            } else {
+                format!("[{}]", elements.join(", "))
+            }
+        };
+        let mut items = vec![];
+        let mut struct_name = None;
+        let ret = match outputs.len() {
+            0 => None,
+            1 => Some((
+                signal_type(&outputs[0].dims),
+                expr(body.outputs[0], &outputs[0].dims),
+            )),
+
+            // the public outputs of main are flattened into an array, in the order of the public signals of circom
+            _ if public.is_some() => {
+                let elements = body
+                    .outputs
+                    .iter()
+                    .flat_map(|&id| elements(id))
+                    .collect_vec();
+                Some((
+                    format!("[Field; {}]", elements.len()),
+                    format!("[{}]", elements.join(", ")),
+                ))
+            }
+
+            _ => {
+                let name = Self::struct_name(&fn_name);
+                let fields = outputs
+                    .iter()
+                    .map(|output| format!("    {}: {},\n", output.ident, signal_type(&output.dims)))
+                    .collect::<String>();
+                items.push(format!("struct {name} {{\n{fields}}}\n"));
+
+                let values = outputs
+                    .iter()
+                    .zip(&body.outputs)
+                    .map(|(output, &id)| format!("{}: {}", output.ident, expr(id, &output.dims)))
+                    .join(", ");
+                let ret = (name.clone(), format!("{name} {{ {values} }}"));
+                struct_name = Some(name);
+                Some(ret)
+            }
+        };
+
+        let mut lines = body.lines.clone();
+        if let Some((_, expr)) = &ret {
+            lines.push(format!("    return {expr};"));
+        }
+        let ret_type = ret.map(|(ty, _)| ty);
+        items.push(Self::function(
+            &fn_name,
+            &params,
+            ret_type.as_deref(),
+            &lines,
+        ));
+
+        let instance = Instance {
+            fn_name,
+            inputs,
+            outputs,
+            struct_name,
+        };
+        Ok((instance, items))
+    }
+
+    /// Translates a template of circomlib from its native translation.
+    fn translate_shim(
+        &mut self,
+        shim: &Shim,
+        args: &[BigInt],
+        file: &str,
+        line: usize,
+    ) -> Result<(Instance, Vec<String>), Error> {
+        if args.len() != shim.num_params {
+            return Err(Error::circom(
+                file,
+                line,
+                format!("`{}` expects {} parameters", shim.name, shim.num_params),
+            ));
+        }
+        let params = args
+            .iter()
+            .map(|arg| arg.to_usize())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                Error::circom(
+                    file,
+                    line,
+                    format!("invalid parameters for `{}`", shim.name),
+                )
+            })?;
+
+        let signal = |name: &str, size: Option<usize>| SignalInfo {
+            name: name.to_string(),
+            ident: sanitize(name),
+            dims: size.into_iter().collect(),
+        };
+        let input = signal("in", (shim.input_size)(&params));
+        let output = signal("out", (shim.output_size)(&params));
+
+        let fn_name = self.fn_name(shim.name, args);
+        let params_decl = format!("{}: {}", input.ident, signal_type(&input.dims));
+        let lines = (shim.body)(&params)
+            .into_iter()
+            .map(|line| format!("    {line}"))
+            .collect_vec();
+        let item = Self::function(
+            &fn_name,
+            &params_decl,
+            Some(&signal_type(&output.dims)),
+            &lines,
+        );
+        self.uses_bits |= shim.uses_bits;
+
+        let instance = Instance {
+            fn_name,
+            inputs: vec![input],
+            outputs: vec![output],
+            struct_name: None,
+        };
+        Ok((instance, vec![item]))
+    }
+
+    fn function(name: &str, params: &str, ret_type: Option<&str>, lines: &[String]) -> String {
+        let ret_type = ret_type.map(|ty| format!(" -> {ty}")).unwrap_or_default();
+        format!(
+            "fn {name}({params}){ret_type} {{\n{}\n}}\n",
+            lines.join("\n")
+        )
+    }
+
+    /// The name of the struct returned by a function (e.g. `Fulladder3` for `full_adder_3`).
+    fn struct_name(fn_name: &str) -> String {
+        let mut chars = fn_name.chars().filter(|c| *c != '_');
+        let first = chars.next().map(|c| c.to_ascii_uppercase());
+        first.into_iter().chain(chars).collect()
+    }
+
+    //
+    // Statements
+    //
+
+    fn exec_block(&mut self, body: &mut Body, stmts: &[Stmt]) -> Result<Flow, Error> {
+        body.scopes.push(HashMap::new());
+        let mut flow = Flow::Next;
+        for stmt in stmts {
+            flow = self.exec(body, stmt)?;
+            if let Flow::Return(_) = flow {
+                break;
+            }
+        }
+        body.scopes.pop();
+        Ok(flow)
+    }
+
+    fn exec(&mut self, body: &mut Body, stmt: &Stmt) -> Result<Flow, Error> {
+        let line = stmt.line;
+        match &stmt.kind {
+            StmtKind::Signal { kind, name, dims } => {
+                if body.is_function {
+                    return Err(body.error(line, "signals can't be declared in functions"));
+                }
+                let dims = self.dims(body, dims)?;
+                let size = dims.iter().product();
+
+                let (ident, elements) = if *kind == SignalKind::Input {
+                    let ident = body.fresh(name);
+                    let elements = if dims.is_empty() {
+                        vec![Some(ident.clone())]
+                    } else {
+                        (0..size).map(|ii| Some(format!("{ident}[{ii}]"))).collect()
+                    };
+                    (ident, elements)
+                } else {
+                    (String::new(), vec![None; size])
+                };
+
+                let id = body.signals.len();
+                body.signals.push(Signal {
+                    kind: *kind,
+                    name: name.clone(),
+                    ident,
+                    dims,
+                    elements,
+                });
+                match kind {
+                    SignalKind::Input => body.inputs.push(id),
+                    SignalKind::Output => body.outputs.push(id),
+                    SignalKind::Intermediate => (),
+                }
+                body.declare(name, Entity::Signal(id));
+            }
+
+            StmtKind::Var { name, dims } => {
+                let dims = self.dims(body, dims)?;
+                body.declare_var(name, dims, BigInt::zero());
+            }
+
+            StmtKind::Component { name, dims } => {
+                if body.is_function {
+                    return Err(body.error(line, "components can't be declared in functions"));
+                }
+                let dims = self.dims(body, dims)?;
+                let size = dims.iter().product();
+                let id = body.components.len();
+                body.components.push(Component {
+                    name: name.clone(),
+                    dims,
+                    elements: (0..size).map(|_| ComponentSlot::default()).collect(),
+                });
+                body.declare(name, Entity::Component(id));
+            }
+
+            StmtKind::Assign { lhs, op, rhs } => self.assign(body, lhs, *op, rhs, line)?,
+
+            StmtKind::Constrain { lhs, rhs } => {
+                if body.is_function {
+                    return Err(body.error(line, "constraints can't be used in functions"));
+                }
+                let lhs = self.eval_elements(body, lhs)?;
+                let rhs = self.eval_elements(body, rhs)?;
+                if lhs.len() != rhs.len() {
+                    return Err(
+                        body.error(line, "the sides of the constraint have different sizes")
+                    );
+                }
+                for (lhs, rhs) in lhs.iter().zip(&rhs) {
+                    match (lhs, rhs) {
+                        (Value::Const(lhs), Value::Const(rhs)) if lhs != rhs => {
+                            return Err(body.error(line, "the constraint is never satisfied"));
+                        }
+                        (Value::Const(_), Value::Const(_)) => (),
+                        _ => body.emit(format!("assert_eq({}, {});", lhs.text(), rhs.text())),
+                    }
+                }
+            }
+
+            StmtKind::If { cond, then_, else_ } => {
+                let cond = self.eval_const(body, cond, "the condition of an if")?;
+                let branch = if !cond.is_zero() {
+                    Some(then_)
+                } else {
+                    else_.as_ref()
+                };
+                if let Some(branch) = branch {
+                    return self.exec_block(body, std::slice::from_ref(&**branch));
+                }
+            }
+
+            StmtKind::For {
+                init,
+                cond,
+                step,
+                body: loop_body,
+            } => {
+                body.scopes.push(HashMap::new());
+                self.exec(body, init)?;
+                let flow = self.unroll(body, cond, Some(&**step), loop_body, line);
+                body.scopes.pop();
+                return flow;
+            }
+
+            StmtKind::While {
+                cond,
+                body: loop_body,
+            } => return self.unroll(body, cond, None, loop_body, line),
+
+            StmtKind::Block(stmts) => return self.exec_block(body, stmts),
+
+            StmtKind::Seq(stmts) => {
+                for stmt in stmts {
+                    if let Flow::Return(value) = self.exec(body, stmt)? {
+                        return Ok(Flow::Return(value));
+                    }
+                }
+            }
+
+            StmtKind::Return(expr) => {
+                if !body.is_function {
+                    return Err(body.error(line, "templates can't return values"));
+                }
+                return Ok(Flow::Return(self.eval(body, expr)?));
+            }
+
+            StmtKind::Assert(expr) => {
+                let cond = self.eval_const(body, expr, "the condition of an assert")?;
+                if cond.is_zero() {
+                    return Err(body.error(line, "the assertion fails"));
+                }
+            }
+
+            StmtKind::Log => (),
+        }
+
+        Ok(Flow::Next)
+    }
+
+    /// Unrolls a loop, whose condition must be known at compile time.
+    fn unroll(
+        &mut self,
+        body: &mut Body,
+        cond: &Expr,
+        step: Option<&Stmt>,
+        loop_body: &Stmt,
+        line: usize,
+    ) -> Result<Flow, Error> {
+        let mut iterations = 0;
+        while !self
+            .eval_const(body, cond, "the condition of a loop")?
+            .is_zero()
+        {
+            iterations += 1;
+            if iterations > MAX_ITERATIONS {
+                return Err(body.error(line, "the loop doesn't terminate"));
+            }
+
+            if let Flow::Return(value) = self.exec_block(body, std::slice::from_ref(loop_body))? {
+                return Ok(Flow::Return(value));
+            }
+            if let Some(step) = step {
+                self.exec(body, step)?;
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn dims(&mut self, body: &mut Body, dims: &[Expr]) -> Result<Vec<usize>, Error> {
+        dims.iter()
+            .map(|dim| {
+                self.eval_const(body, dim, "the size of an array")?
+                    .to_usize()
+                    .ok_or_else(|| body.error(dim.line, "invalid array size"))
+            })
+            .collect()
+    }
+
+    fn assign(
+        &mut self,
+        body: &mut Body,
+        lhs: &Expr,
+        op: AssignOp,
+        rhs: &Expr,
+        line: usize,
+    ) -> Result<(), Error> {
+        if op == AssignOp::Unconstrained {
+            return Err(body.error(line, UNCONSTRAINED_ASSIGNMENT));
+        }
+
+        match self.resolve(body, lhs)? {
+            Place::Component { id, index } => {
+                if op != AssignOp::Var {
+                    return Err(body.error(line, "components are created with `=`"));
+                }
+                let ExprKind::Call(name, args) = &rhs.kind else {
+                    return Err(body.error(line, "expected the instantiation of a template"));
+                };
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval_const(body, arg, "the parameters of a template"))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let instance = self.instance(name, args, &body.file.clone(), line)?;
+
+                let slot = &mut body.components[id].elements[index];
+                if slot.instance.is_some() {
+                    return Err(body.error(line, "the component is created twice"));
+                }
+                slot.inputs = instance
+                    .inputs
+                    .iter()
+                    .map(|input| vec![None; input.size()])
+                    .collect();
+                slot.instance = Some(instance);
+                body.call_if_ready(id, index);
+            }
+
+            Place::Var { id, start, len } => {
+                let values = match op {
+                    AssignOp::Var => self.eval_elements(body, rhs)?,
+                    AssignOp::Compound(bin_op) if len == 1 => {
+                        let current = body.read_var(id, start);
+                        let rhs = self.eval(body, rhs)?;
+                        vec![self.binary(body, bin_op, current, rhs, line)?]
+                    }
+                    AssignOp::Compound(_) => {
+                        return Err(body.error(line, "expected a single variable"))
+                    }
+                    _ => return Err(body.error(line, "variables are assigned with `=`")),
+                };
+                if values.len() != len {
+                    return Err(
+                        body.error(line, "the sides of the assignment have different sizes")
+                    );
+                }
+                for (offset, value) in values.into_iter().enumerate() {
+                    body.write_var(id, start + offset, value);
+                }
+            }
+
+            Place::Signal { id, start, len } => {
+                if op != AssignOp::Constrained {
+                    return Err(body.error(line, "signals are assigned with `<==`"));
+                }
+                if body.signals[id].kind == SignalKind::Input {
+                    return Err(body.error(line, "input signals can't be assigned"));
+                }
+                let values = self.eval_elements(body, rhs)?;
+                if values.len() != len {
+                    return Err(
+                        body.error(line, "the sides of the assignment have different sizes")
+                    );
+                }
+
+                for (offset, value) in values.into_iter().enumerate() {
+                    let index = start + offset;
+                    let signal = &body.signals[id];
+                    let name = element_name(&signal.name, &signal.dims, index);
+                    if signal.elements[index].is_some() {
+                        return Err(body.error(line, format!("`{name}` is assigned twice")));
+                    }
+                    let ident = body.fresh(&name);
+                    body.emit(format!("let {ident} = {};", value.text()));
+                    body.signals[id].elements[index] = Some(ident);
+                }
+            }
+
+            Place::ComponentSignal {
+                id,
+                index,
+                output,
+                signal,
+                start,
+                len,
+            } => {
+                if output {
+                    return Err(body.error(line, "the outputs of components can't be assigned"));
+                }
+                if op != AssignOp::Constrained {
+                    return Err(body.error(line, "signals are assigned with `<==`"));
+                }
+                let values = self.eval_elements(body, rhs)?;
+                if values.len() != len {
+                    return Err(
+                        body.error(line, "the sides of the assignment have different sizes")
+                    );
+                }
+
+                let component = &body.components[id];
+                let instance = component.elements[index]
+                    .instance
+                    .clone()
+                    .expect("resolved signals belong to created components");
+                let input = &instance.inputs[signal];
+                let component_name = element_name(&component.name, &component.dims, index);
+
+                for (offset, value) in values.into_iter().enumerate() {
+                    let element = start + offset;
+                    let name = format!(
+                        "{component_name}_{}",
+                        element_name(&input.name, &input.dims, element)
+                    );
+                    if body.components[id].elements[index].inputs[signal][element].is_some() {
+                        return Err(body.error(line, format!("`{name}` is assigned twice")));
+                    }
+
+                    // the current value of mutable variables is saved until the call
+                    let value = if value.is_volatile() {
+                        let ident = body.fresh(&name);
+                        body.emit(format!("let {ident} = {};", value.text()));
+                        Value::code(ident, true, false)
+                    } else {
+                        value
+                    };
+                    body.components[id].elements[index].inputs[signal][element] = Some(value);
+                }
+                body.call_if_ready(id, index);
+            }
+        }
+
+        Ok(())
+    }
+
+    //
+    // Expressions
+    //
+
+    /// Resolves what an expression like `a[i].b[j]` refers to.
+    fn resolve(&mut self, body: &mut Body, expr: &Expr) -> Result<Place, Error> {
+        // split the expression into its base name and its accessors
+        let mut accessors = vec![];
+        let mut current = expr;
+        let name = loop {
+            match &current.kind {
+                ExprKind::Variable(name) => break name,
+                ExprKind::Index(inner, idx) => {
+                    let idx = self.eval_const(body, idx, "the index of an array")?;
+                    accessors.push(Accessor::Index(idx));
+                    current = inner;
+                }
+                ExprKind::Member(inner, member) => {
+                    accessors.push(Accessor::Member(member.clone()));
+                    current = inner;
+                }
+                _ => {
+                    return Err(
+                        body.error(expr.line, "expected a signal, a variable, or a component")
+                    )
+                }
+            }
+        };
+        accessors.reverse();
+
+        let entity = body
+            .lookup(name)
+            .ok_or_else(|| body.error(expr.line, format!("`{name}` is not defined")))?;
+
+        // selects elements of an array, given the indices of the first dimensions
+        let select = |name: &str, dims: &[usize], indices: &[BigInt]| {
+            if indices.len() > dims.len() {
+                return Err(body.error(expr.line, format!("too many indices for `{name}`")));
+            }
+            let mut start = 0;
+            for (dim_idx, idx) in indices.iter().enumerate() {
+                let idx = idx
+                    .to_usize()
+                    .filter(|idx| *idx < dims[dim_idx])
+                    .ok_or_else(|| {
+                        body.error(
+                            expr.line,
+                            format!("the index {idx} is out of bounds for `{name}`"),
+                        )
+                    })?;
+                start += idx * dims[dim_idx + 1..].iter().product::<usize>();
+            }
+            let len = dims[indices.len()..].iter().product();
+            Ok((start, len))
+        };
+        let indices = |accessors: &[Accessor]| -> Option<Vec<BigInt>> {
+            accessors
+                .iter()
+                .map(|accessor| match accessor {
+                    Accessor::Index(idx) => Some(idx.clone()),
+                    Accessor::Member(_) => None,
+                })
+                .collect()
+        };
+        let no_member = || body.error(expr.line, format!("`{name}` has no members"));
+
+        match entity {
+            Entity::Signal(id) => {
+                let signal = &body.signals[id];
+                let indices = indices(&accessors).ok_or_else(no_member)?;
+                let (start, len) = select(name, &signal.dims, &indices)?;
+                Ok(Place::Signal { id, start, len })
+            }
+
+            Entity::Var(id) => {
+                let var = &body.vars[id];
+                let indices = indices(&accessors).ok_or_else(no_member)?;
+                let (start, len) = select(name, &var.dims, &indices)?;
+                Ok(Place::Var { id, start, len })
+            }
+
+            Entity::Component(id) => {
+                let component = &body.components[id];
+                let member_pos = accessors
+                    .iter()
+                    .position(|accessor| matches!(accessor, Accessor::Member(_)))
+                    .unwrap_or(accessors.len());
+                let component_indices =
+                    indices(&accessors[..member_pos]).expect("there is no member before");
+                if component_indices.len() != component.dims.len() {
+                    return Err(body.error(
+                        expr.line,
+                        format!("expected a single component of `{name}`"),
+                    ));
+                }
+                let (index, _) = select(name, &component.dims, &component_indices)?;
+                if member_pos == accessors.len() {
+                    return Ok(Place::Component { id, index });
+                }
+
+                let Accessor::Member(member) = &accessors[member_pos] else {
+                    unreachable!()
+                };
+                let instance = component.elements[index].instance.clone().ok_or_else(|| {
+                    body.error(
+                        expr.line,
+                        format!("the component `{name}` is used before it is created"),
+                    )
+                })?;
+                let (output, signal, info) = instance
+                    .outputs
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, info)| (true, idx, info))
+                    .chain(
+                        instance
+                            .inputs
+                            .iter()
+                            .enumerate()
+                            .map(|(idx, info)| (false, idx, info)),
+                    )
+                    .find(|(_, _, info)| &info.name == member)
+                    .ok_or_else(|| {
+                        body.error(expr.line, format!("`{name}` has no signal `{member}`"))
+                    })?;
+
+                let signal_indices = indices(&accessors[member_pos + 1..])
+                    .ok_or_else(|| body.error(expr.line, format!("`{member}` has no members")))?;
+                let (start, len) = select(member, &info.dims, &signal_indices)?;
+                Ok(Place::ComponentSignal {
+                    id,
+                    index,
+                    output,
+                    signal,
+                    start,
+                    len,
+                })
+            }
+        }
+    }
+
+    /// Reads the elements of a signal, a variable, or a signal of a component.
+    fn read(&mut self, body: &mut Body, expr: &Expr) -> Result<Vec<Value>, Error> {
+        match self.resolve(body, expr)? {
+            Place::Signal { id, start, len } => {
+                let signal = &body.signals[id];
+                (start..start + len)
+                    .map(|index| {
+                        signal.elements[index]
+                            .clone()
+                            .map(|ident| Value::code(ident, true, false))
+                            .ok_or_else(|| {
+                                let name = element_name(&signal.name, &signal.dims, index);
+                                body.error(
+                                    expr.line,
+                                    format!("`{name}` is read before it is assigned"),
+                                )
+                            })
+                    })
+                    .collect()
+            }
+
+            Place::Var { id, start, len } => Ok((start..start + len)
+                .map(|index| body.read_var(id, index))
+                .collect()),
+
+            Place::Component { .. } => Err(body.error(expr.line, "a component is not a value")),
+
+            Place::ComponentSignal {
+                id,
+                index,
+                output,
+                signal,
+                start,
+                len,
+            } => {
+                let component = &body.components[id];
+                let slot = &component.elements[index];
+                let instance = slot
+                    .instance
+                    .as_ref()
+                    .expect("resolved signals belong to created components");
+
+                if !output {
+                    return (start..start + len)
+                        .map(|element| {
+                            slot.inputs[signal][element].clone().ok_or_else(|| {
+                                body.error(expr.line, "the input is read before it is assigned")
+                            })
+                        })
+                        .collect();
+                }
+
+                let result = slot.result.as_ref().ok_or_else(|| {
+                    let name = element_name(&component.name, &component.dims, index);
+                    body.error(
+                        expr.line,
+                        format!(
+                            "the outputs of `{name}` are read before all its inputs are assigned"
+                        ),
+                    )
+                })?;
+                let info = &instance.outputs[signal];
+                let base = match &instance.struct_name {
+                    Some(_) => format!("{result}.{}", info.ident),
+                    None => result.clone(),
+                };
+                Ok((start..start + len)
+                    .map(|element| {
+                        let text = if info.dims.is_empty() {
+                            base.clone()
+                        } else {
+                            format!("{base}[{element}]")
+                        };
+                        Value::code(text, true, false)
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Evaluates an expression that might be an array, and returns its flattened elements.
+    fn eval_elements(&mut self, body: &mut Body, expr: &Expr) -> Result<Vec<Value>, Error> {
+        match &expr.kind {
+            ExprKind::Array(items) => {
+                let mut elements = vec![];
+                for item in items {
+                    elements.extend(self.eval_elements(body, item)?);
+                }
+                Ok(elements)
+            }
+            ExprKind::Variable(_) | ExprKind::Index(..) | ExprKind::Member(..) => {
+                self.read(body, expr)
+            }
+            _ => Ok(vec![self.eval(body, expr)?]),
+        }
+    }
+
+    fn eval_const(&mut self, body: &mut Body, expr: &Expr, what: &str) -> Result<BigInt, Error> {
+        match self.eval(body, expr)? {
+            Value::Const(value) => Ok(value),
+            Value::Code { .. } => {
+                Err(body.error(expr.line, format!("{what} must be known at compile time")))
+            }
+        }
+    }
+
+    fn eval(&mut self, body: &mut Body, expr: &Expr) -> Result<Value, Error> {
+        let line = expr.line;
+        match &expr.kind {
+            ExprKind::Number(number) => Ok(Value::Const(number.clone().into())),
+
+            ExprKind::Variable(_) | ExprKind::Index(..) | ExprKind::Member(..) => {
+                let mut values = self.read(body, expr)?;
+                if values.len() != 1 {
+                    return Err(body.error(line, "expected a single value, not an array"));
+                }
+                Ok(values.remove(0))
+            }
+
+            ExprKind::Call(name, args) => self.call(body, name, args, line),
+
+            ExprKind::Array(_) => Err(body.error(line, "arrays can only be assigned")),
+
+            ExprKind::Unary(op, inner) => {
+                let value = self.eval(body, inner)?;
+                match (*op, value) {
+                    (UnaryOp::Neg, Value::Const(value)) => Ok(Value::Const(-value)),
+                    (UnaryOp::Neg, value) => Ok(Value::code(
+                        format!("0 - {}", value.operand()),
+                        false,
+                        value.is_volatile(),
+                    )),
+                    (UnaryOp::Not, Value::Const(value)) => {
+                        Ok(Value::Const(BigInt::from(value.is_zero() as u8)))
+                    }
+                    (UnaryOp::Not, _) => Err(body.error(
+                        line,
+                        "the operator `!` is only supported on values known at compile time",
+                    )),
+                    (UnaryOp::BitNot, _) => {
+                        Err(body.error(line, "the operator `~` is not supported"))
+                    }
+                }
+            }
+
+            ExprKind::Binary(op, lhs, rhs) => {
+                let lhs = self.eval(body, lhs)?;
+                let rhs = self.eval(body, rhs)?;
+                self.binary(body, *op, lhs, rhs, line)
+            }
+
+            ExprKind::Ternary(cond, then_, else_) => {
+                let cond = self.eval_const(body, cond, "the condition of a ternary operator")?;
+                if !cond.is_zero() {
+                    self.eval(body, then_)
+                } else {
+                    self.eval(body, else_)
+                }
+            }
+        }
+    }
+
+    fn binary(
+        &mut self,
+        body: &Body,
+        op: BinOp,
+        lhs: Value,
+        rhs: Value,
+        line: usize,
+    ) -> Result<Value, Error> {
+        if let (Value::Const(lhs), Value::Const(rhs)) = (&lhs, &rhs) {
+            return Self::const_binary(op, lhs, rhs)
+                .map(Value::Const)
+                .map_err(|msg| body.error(line, msg));
+        }
+
+        let volatile = lhs.is_volatile() || rhs.is_volatile();
+        let text = match op {
+            BinOp::Add if lhs.is_const(0) => return Ok(rhs),
+            BinOp::Add | BinOp::Sub if rhs.is_const(0) => return Ok(lhs),
+            BinOp::Mul if lhs.is_const(1) => return Ok(rhs),
+            BinOp::Mul | BinOp::Div if rhs.is_const(1) => return Ok(lhs),
+            BinOp::Mul if lhs.is_const(0) || rhs.is_const(0) => {
+                return Ok(Value::Const(BigInt::zero()))
+            }
+            BinOp::Add | BinOp::Sub | BinOp::Mul => {
+                format!("{} {} {}", lhs.operand(), op.symbol(), rhs.operand())
+            }
+
+            // the division of circom is the one of the field
+            BinOp::Div => format!("{} * inv({})", lhs.operand(), rhs.text()),
+
+            BinOp::Pow => match &rhs {
+                Value::Const(exp) if !exp.is_negative() => {
+                    return Ok(Value::code(
+                        format!("pow({}, {exp})", lhs.text()),
+                        true,
+                        volatile,
+                    ))
+                }
+                _ => return Err(body.error(line, "exponents must be known at compile time")),
+            },
+
+            _ => {
+                return Err(body.error(
+                    line,
+                    format!(
+                        "the operator `{}` is only supported on values known at compile time",
+                        op.symbol()
+                    ),
+                ))
+            }
+        };
+        Ok(Value::code(text, false, volatile))
+    }
+
+    /// Evaluates an operation on values known at compile time.
+    fn const_binary(op: BinOp, lhs: &BigInt, rhs: &BigInt) -> Result<BigInt, String> {
+        let boolean = |cond: bool| BigInt::from(cond as u8);
+        let non_negative = || {
+            if lhs.is_negative() || rhs.is_negative() {
+                Err(format!(
+                    "the operator `{}` is only supported on non-negative values",
+                    op.symbol()
+                ))
+            } else {
+                Ok(())
+            }
+        };
+        let non_zero = || {
+            if rhs.is_zero() {
+                Err("division by zero".to_string())
+            } else {
+                Ok(())
+            }
+        };
+        let small = |value: &BigInt| {
+            value
+                .to_u32()
+                .ok_or_else(|| format!("the operand of `{}` is too large", op.symbol()))
+        };
+
+        let res = match op {
+            BinOp::Add => lhs + rhs,
+            BinOp::Sub => lhs - rhs,
+            BinOp::Mul => lhs * rhs,
+            BinOp::Div => {
+                non_zero()?;
+                if !(lhs % rhs).is_zero() {
+                    return Err("only exact divisions are supported on constants".to_string());
+                }
+                lhs / rhs
+            }
+            BinOp::IntDiv => {
+                non_negative()?;
+                non_zero()?;
+                lhs / rhs
+            }
+            BinOp::Mod => {
+                non_negative()?;
+                non_zero()?;
+                lhs % rhs
+            }
+            BinOp::Pow => lhs.pow(small(rhs)?),
+            BinOp::Shl => {
+                non_negative()?;
+                lhs << small(rhs)?
+            }
+            BinOp::Shr => {
+                non_negative()?;
+                lhs >> small(rhs)?
+            }
+            BinOp::BitAnd => {
+                non_negative()?;
+                lhs & rhs
+            }
+            BinOp::BitOr => {
+                non_negative()?;
+                lhs | rhs
+            }
+            BinOp::BitXor => {
+                non_negative()?;
+                lhs ^ rhs
+            }
+            BinOp::Eq => boolean(lhs == rhs),
+            BinOp::NotEq => boolean(lhs != rhs),
+            BinOp::Lt => boolean(lhs < rhs),
+            BinOp::Le => boolean(lhs <= rhs),
+            BinOp::Gt => boolean(lhs > rhs),
+            BinOp::Ge => boolean(lhs >= rhs),
+            BinOp::And => boolean(!lhs.is_zero() && !rhs.is_zero()),
+            BinOp::Or => boolean(!lhs.is_zero() || !rhs.is_zero()),
+        };
+        Ok(res)
+    }
+
+    /// Calls a circom function, whose arguments must be known at compile time.
+    fn call(
+        &mut self,
+        body: &mut Body,
+        name: &str,
+        args: &[Expr],
+        line: usize,
+    ) -> Result<Value, Error> {
+        if self.templates.contains_key(name) || circomlib::find(name).is_some() {
+            return Err(body.error(
+                line,
+                format!("the template `{name}` can only be instantiated by a component"),
+            ));
+        }
+        let (function, file) = self
+            .functions
+            .get(name)
+            .copied()
+            .ok_or_else(|| body.error(line, format!("the function `{name}` is not defined")))?;
+        if args.len() != function.params.len() {
+            return Err(body.error(
+                line,
+                format!("`{name}` expects {} arguments", function.params.len()),
+            ));
+        }
+        if body.call_depth >= MAX_CALL_DEPTH {
+            return Err(body.error(line, "too many nested function calls"));
+        }
+
+        let mut callee = Body::new(file, true, &self.reserved);
+        callee.call_depth = body.call_depth + 1;
+        for (param, arg) in function.params.iter().zip(args) {
+            let arg = self.eval_const(body, arg, "the arguments of a function")?;
+            callee.declare_var(param, vec![], arg);
+        }
+
+        match self.exec_block(&mut callee, &function.body)? {
+            Flow::Return(value @ Value::Const(_)) => Ok(value),
+            Flow::Return(_) => unreachable!("functions only compute constants"),
+            Flow::Next => Err(body.error(
+                function.line,
+                format!("the function `{name}` doesn't return a value"),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{element_name, sanitize, UNCONSTRAINED_ASSIGNMENT};
+    use crate::{
+        backends::r1cs::{R1csBn254Field, R1CS},
+        circom::translate,
+        compiler::generate_witness,
+        inputs::parse_inputs,
+        tests::compile_with_sources,
+    };
+
+    /// Translates a circom program, and returns the public outputs of the noname program for the given inputs.
+    fn run(circom: &str, public_inputs: &str, private_inputs: &str) -> Vec<R1csBn254Field> {
+        let code = translate("main.circom", circom).unwrap();
+        let (compiled_circuit, sources) =
+            compile_with_sources(&code, R1CS::<R1csBn254Field>::new())
+                .unwrap_or_else(|err| panic!("the translation doesn't compile: {err:?}\n{code}"));
+        let public_inputs = parse_inputs(public_inputs).unwrap();
+        let private_inputs = parse_inputs(private_inputs).unwrap();
+        generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs)
+            .unwrap()
+            .outputs
+    }
+
+    /// A main template with an input `xx` and an output `out`, whose other statements start on the fourth line.
+    fn main_template(body: &str) -> String {
+        format!(
+            "template Main() {{\n    signal input xx;\n    signal output out;\n{body}\n}}\n\ncomponent main = Main();\n"
+        )
+    }
+
+    fn outputs(values: &[u64]) -> Vec<R1csBn254Field> {
+        values
+            .iter()
+            .map(|value| R1csBn254Field::from(*value))
+            .collect()
+    }
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("inValue"), "in_value");
+        assert_eq!(sanitize("Num2Bits"), "num2_bits");
+        assert_eq!(sanitize("a"), "aa");
+        assert_eq!(sanitize("in"), "in_");
+        assert_eq!(sanitize("_x"), "xx");
+        assert_eq!(element_name("out", &[2, 3], 5), "out_1_2");
+    }
+
+    #[test]
+    fn test_signals_and_constraints() {
+        let circom = "
+template Main() {
+    signal input xx;
+    signal input yy;
+    signal output out;
+    signal prod;
+    prod <== xx * yy;
+    xx * yy === prod;
+    prod + 1 ==> out;
+}
+
+component main {public [xx]} = Main();
+";
+        assert_eq!(
+            run(circom, r#"{"xx": "2"}"#, r#"{"yy": "3"}"#),
+            outputs(&[7])
+        );
+    }
+
+    #[test]
+    fn test_control_flow_and_functions() {
+        let circom = r"
+function nbits(aa) {
+    var res = 0;
+    while ((1 << res) <= aa) {
+        res++;
+    }
+    return res;
+}
+
+template Sum(nn) {
+    signal input xs[nn];
+    signal output out;
+
+    var sum = 0;
+    for (var ii = 0; ii < nn; ii++) {
+        sum += xs[ii] * (ii % 2 == 0 ? 1 : 2);
+    }
+
+    var scale = 1;
+    if (nbits(nn) > 1) {
+        scale *= 3;
+    } else {
+        scale = 5;
+    }
+    out <== sum * scale;
+}
+
+component main = Sum(3);
+";
+        // (1 + 2 * 2 + 3) * 3
+        assert_eq!(
+            run(circom, "{}", r#"{"xs": ["1", "2", "3"]}"#),
+            outputs(&[24])
+        );
+    }
+
+    #[test]
+    fn test_components() {
+        let circom = "
+template Split() {
+    signal input in;
+    signal output lo;
+    signal output hi;
+    lo <== in * 2;
+    hi <== in * 3;
+}
+
+template Main() {
+    signal input xs[2];
+    signal output out;
+
+    component parts[2];
+    var acc = 0;
+    for (var ii = 0; ii < 2; ii++) {
+        parts[ii] = Split();
+        parts[ii].in <== xs[ii];
+        acc += parts[ii].lo + parts[ii].hi;
+    }
+    out <== acc;
+}
+
+component main {public [xs]} = Main();
+";
+        // (2 + 3) + (4 + 6)
+        assert_eq!(run(circom, r#"{"xs": ["1", "2"]}"#, "{}"), outputs(&[15]));
+    }
+
+    #[test]
+    fn test_circomlib() {
+        let circom = r#"
+include "circomlib/circuits/comparators.circom";
+
+template Main() {
+    signal input xx;
+    signal input yy;
+    signal output out;
+
+    component bits = Num2Bits(4);
+    bits.in <== xx;
+    component lt = LessThan(8);
+    lt.in[0] <== xx;
+    lt.in[1] <== yy;
+    component zero = IsZero();
+    zero.in <== xx - yy;
+
+    out <== bits.out[0] + bits.out[1] * 2 + lt.out * 10 + zero.out * 100;
+}
+
+component main = Main();
+"#;
+        // 5 is 0b0101, and 5 < 7
+        assert_eq!(
+            run(circom, "{}", r#"{"xx": "5", "yy": "7"}"#),
+            outputs(&[11])
+        );
+    }
+
+    #[test]
+    fn test_several_outputs() {
+        let circom = "
+template Main() {
+    signal input xx;
+    signal output aa;
+    signal output bb;
+    aa <== xx + 1;
+    bb <== xx * xx;
+}
+
+component main = Main();
+";
+        // the outputs of main are flattened into an array
+        assert_eq!(run(circom, "{}", r#"{"xx": "3"}"#), outputs(&[4, 9]));
+    }
+
+    #[test]
+    fn test_field_operations() {
+        let circom = main_template("    out <== xx ** 3 - xx / 2;");
+        assert_eq!(run(&circom, "{}", r#"{"xx": "4"}"#), outputs(&[62]));
+    }
+
+    #[test]
+    fn test_ignored_constructs() {
+        let circom = r#"
+pragma circom 2.1.6;
+
+/* the tags, the logs, and the assertions on constants
+   have no effect on the circuit */
+template Main(nn) {
+    assert(nn == 0x10);
+    signal input {binary} flag;
+    var aa = 1, bb = 2;
+    log("nn", nn);
+    signal output out <== flag * (aa + bb + nn);
+}
+
+component main = Main(16);
+"#;
+        assert_eq!(run(circom, "{}", r#"{"flag": "1"}"#), outputs(&[19]));
+    }
+
+    #[test]
+    fn test_unconstrained_assignment() {
+        let err = translate("main.circom", &main_template("    out <-- 1 / xx;")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("main.circom:4: {UNCONSTRAINED_ASSIGNMENT}")
+        );
+    }
+
+    #[rstest]
+    // signals
+    #[case::assign_signal_with_eq("    out = xx;", 4, "signals are assigned with `<==`")]
+    #[case::assign_input("    xx <== 1;", 4, "input signals can't be assigned")]
+    #[case::assign_twice("    out <== xx;\n    out <== xx;", 5, "`out` is assigned twice")]
+    #[case::assign_array_to_signal(
+        "    out <== [xx, xx];",
+        4,
+        "the sides of the assignment have different sizes"
+    )]
+    #[case::read_before_assign(
+        "    signal mid;\n    out <== mid;",
+        5,
+        "`mid` is read before it is assigned"
+    )]
+    #[case::constraint_sizes(
+        "    out <== xx;\n    xx === [xx, xx];",
+        5,
+        "the sides of the constraint have different sizes"
+    )]
+    #[case::constraint_never_satisfied(
+        "    out <== xx;\n    1 === 2;",
+        5,
+        "the constraint is never satisfied"
+    )]
+    // variables
+    #[case::assign_var_with_arrow(
+        "    var vv;\n    vv <== xx;",
+        5,
+        "variables are assigned with `=`"
+    )]
+    #[case::compound_on_array("    var vv[2];\n    vv += 1;", 5, "expected a single variable")]
+    #[case::undefined("    out <== yy;", 4, "`yy` is not defined")]
+    #[case::too_many_indices("    out <== xx[0];", 4, "too many indices for `xx`")]
+    #[case::out_of_bounds(
+        "    signal input ys[2];\n    out <== ys[2];",
+        5,
+        "the index 2 is out of bounds for `ys`"
+    )]
+    #[case::member_of_signal("    out <== xx.yy;", 4, "`xx` has no members")]
+    #[case::index_of_expression(
+        "    out <== (xx + 1)[0];",
+        4,
+        "expected a signal, a variable, or a component"
+    )]
+    #[case::array_as_value(
+        "    signal input ys[2];\n    out <== ys + 1;",
+        5,
+        "expected a single value, not an array"
+    )]
+    #[case::array_in_operation("    out <== xx + [1, 2];", 4, "arrays can only be assigned")]
+    // components
+    #[case::create_with_arrow(
+        "    component cc;\n    cc <== Num2Bits(2);",
+        5,
+        "components are created with `=`"
+    )]
+    #[case::create_without_template(
+        "    component cc;\n    cc = 3;",
+        5,
+        "expected the instantiation of a template"
+    )]
+    #[case::create_twice(
+        "    component cc = Num2Bits(2);\n    cc = Num2Bits(2);",
+        5,
+        "the component is created twice"
+    )]
+    #[case::assign_output_of_component(
+        "    component cc = Num2Bits(2);\n    cc.out[0] <== xx;",
+        5,
+        "the outputs of components can't be assigned"
+    )]
+    #[case::assign_input_of_component_with_eq(
+        "    component cc = IsZero();\n    cc.in = xx;",
+        5,
+        "signals are assigned with `<==`"
+    )]
+    #[case::assign_input_of_component_twice(
+        "    component cc = IsZero();\n    cc.in <== xx;\n    cc.in <== xx;",
+        6,
+        "`cc_in` is assigned twice"
+    )]
+    #[case::component_array_as_component(
+        "    component cc[2];\n    out <== cc.out;",
+        5,
+        "expected a single component of `cc`"
+    )]
+    #[case::component_before_creation(
+        "    component cc;\n    out <== cc.out;",
+        5,
+        "the component `cc` is used before it is created"
+    )]
+    #[case::unknown_signal_of_component(
+        "    component cc = IsZero();\n    out <== cc.foo;",
+        5,
+        "`cc` has no signal `foo`"
+    )]
+    #[case::member_of_signal_of_component(
+        "    component cc = IsZero();\n    out <== cc.out.foo;",
+        5,
+        "`out` has no members"
+    )]
+    #[case::component_as_value(
+        "    component cc;\n    out <== cc;",
+        5,
+        "a component is not a value"
+    )]
+    #[case::input_of_component_before_assign(
+        "    component cc = IsEqual();\n    cc.in[0] <== xx;\n    out <== cc.in[1];",
+        6,
+        "the input is read before it is assigned"
+    )]
+    #[case::output_of_component_before_call(
+        "    component cc = IsEqual();\n    cc.in[0] <== xx;\n    out <== cc.out;",
+        6,
+        "the outputs of `cc` are read before all its inputs are assigned"
+    )]
+    #[case::template_as_function(
+        "    out <== Num2Bits(2);",
+        4,
+        "the template `Num2Bits` can only be instantiated by a component"
+    )]
+    #[case::undefined_template(
+        "    component cc = Undefined();",
+        4,
+        "the template `Undefined` is not defined"
+    )]
+    #[case::shim_parameters(
+        "    component cc = Num2Bits(1, 2);",
+        4,
+        "`Num2Bits` expects 1 parameters"
+    )]
+    #[case::invalid_shim_parameters(
+        "    component cc = Num2Bits(0 - 1);",
+        4,
+        "invalid parameters for `Num2Bits`"
+    )]
+    #[case::template_parameters_from_signals(
+        "    component cc = Num2Bits(xx);",
+        4,
+        "the parameters of a template must be known at compile time"
+    )]
+    // values known at compile time
+    #[case::array_size_from_signal(
+        "    signal input ys[xx];",
+        4,
+        "the size of an array must be known at compile time"
+    )]
+    #[case::invalid_array_size("    var vv[0 - 1];", 4, "invalid array size")]
+    #[case::if_on_signal(
+        "    if (xx) { out <== 1; }",
+        4,
+        "the condition of an if must be known at compile time"
+    )]
+    #[case::loop_on_signal(
+        "    while (xx) { out <== 1; }",
+        4,
+        "the condition of a loop must be known at compile time"
+    )]
+    #[case::infinite_loop("    while (1) {}", 4, "the loop doesn't terminate")]
+    #[case::ternary_on_signal(
+        "    out <== xx ? 1 : 2;",
+        4,
+        "the condition of a ternary operator must be known at compile time"
+    )]
+    #[case::assertion_fails("    assert(0);", 4, "the assertion fails")]
+    #[case::assertion_on_signal(
+        "    assert(xx);",
+        4,
+        "the condition of an assert must be known at compile time"
+    )]
+    #[case::return_in_template("    return 1;", 4, "templates can't return values")]
+    // operators
+    #[case::not_on_signal(
+        "    out <== !xx;",
+        4,
+        "the operator `!` is only supported on values known at compile time"
+    )]
+    #[case::bit_not("    out <== ~1;", 4, "the operator `~` is not supported")]
+    #[case::exponent_from_signal(
+        "    out <== xx ** xx;",
+        4,
+        "exponents must be known at compile time"
+    )]
+    #[case::mod_on_signal(
+        "    out <== xx % 2;",
+        4,
+        "the operator `%` is only supported on values known at compile time"
+    )]
+    #[case::division_by_zero("    var vv = 1 / 0;", 4, "division by zero")]
+    #[case::inexact_division(
+        "    var vv = 3 / 2;",
+        4,
+        "only exact divisions are supported on constants"
+    )]
+    #[case::mod_of_negative(
+        "    var vv = (0 - 1) % 2;",
+        4,
+        "the operator `%` is only supported on non-negative values"
+    )]
+    #[case::large_exponent("    var vv = 2 ** 5000000000;", 4, "the operand of `**` is too large")]
+    #[case::undefined_function(
+        "    out <== undefined(1);",
+        4,
+        "the function `undefined` is not defined"
+    )]
+    fn test_template_errors(#[case] body: &str, #[case] line: usize, #[case] msg: &str) {
+        let err = translate("main.circom", &main_template(body)).unwrap_err();
+        assert_eq!(err.to_string(), format!("main.circom:{line}: {msg}"));
+    }
+
+    #[rstest]
+    #[case::signal_in_function(
+        "function ff() {\n    signal ss;\n    return 1;\n}",
+        2,
+        "signals can't be declared in functions"
+    )]
+    #[case::component_in_function(
+        "function ff() {\n    component cc;\n    return 1;\n}",
+        2,
+        "components can't be declared in functions"
+    )]
+    #[case::constraint_in_function(
+        "function ff() {\n    1 === 1;\n    return 1;\n}",
+        2,
+        "constraints can't be used in functions"
+    )]
+    #[case::no_return(
+        "function ff() {\n    var vv = 1;\n}",
+        1,
+        "the function `ff` doesn't return a value"
+    )]
+    #[case::arguments(
+        "function ff(aa, bb) {\n    return aa;\n}",
+        8,
+        "`ff` expects 2 arguments"
+    )]
+    #[case::recursion(
+        "function ff() {\n    return ff();\n}",
+        2,
+        "too many nested function calls"
+    )]
+    fn test_function_errors(#[case] function: &str, #[case] line: usize, #[case] msg: &str) {
+        // the template comes two lines after the function, and calls it on its fourth line
+        let circom = format!(
+            "{function}\n\ntemplate Main() {{\n    signal input xx;\n    signal output out;\n    out <== xx + ff();\n}}\n\ncomponent main = Main();\n"
+        );
+        let err = translate("main.circom", &circom).unwrap_err();
+        assert_eq!(err.to_string(), format!("main.circom:{line}: {msg}"));
+    }
+
+    #[test]
+    fn test_function_arguments_from_signals() {
+        let circom = "function ff(aa) {\n    return aa;\n}\n\n".to_string()
+            + &main_template("    out <== ff(xx);");
+        let err = translate("main.circom", &circom).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "main.circom:8: the arguments of a function must be known at compile time"
+        );
+    }
+
+    #[rstest]
+    #[case::self_instantiation(
+        "template Main() {\n    signal output out;\n    component cc = Main();\n    out <== 1;\n}\ncomponent main = Main();",
+        "main.circom:3: the template `Main` instantiates itself"
+    )]
+    #[case::output_never_assigned(
+        "template Main() {\n    signal input xx;\n    signal output out;\n}\ncomponent main = Main();",
+        "main.circom:1: the output `out` of `Main` is never assigned"
+    )]
+    #[case::component_never_called(
+        "template Main() {\n    signal input xx;\n    signal output out;\n    component cc = IsZero();\n    out <== xx;\n}\ncomponent main = Main();",
+        "main.circom:1: the inputs of the component `cc` are never all assigned"
+    )]
+    #[case::main_not_a_template(
+        "template Main() {\n    signal output out;\n    out <== 1;\n}\ncomponent main = 3;",
+        "main.circom:5: the main component must instantiate a template"
+    )]
+    #[case::main_from_circomlib(
+        "component main = IsZero();",
+        "main.circom:1: the main component can't be `IsZero`, which is translated natively"
+    )]
+    #[case::main_parameters(
+        "template Main() {\n    signal output out;\n    out <== 1;\n}\ncomponent main = Main(1);",
+        "main.circom:5: `Main` expects 0 parameters"
+    )]
+    #[case::public_output(
+        "template Main() {\n    signal output out;\n    out <== 1;\n}\ncomponent main {public [out]} = Main();",
+        "main.circom:1: `out` is not an input of the main component"
+    )]
+    #[case::unresolved_include(
+        "include \"lib.circom\";\ntemplate Main() {\n    signal output out;\n    component cc = Lib();\n}\ncomponent main = Main();",
+        "main.circom:4: the template `Lib` is not defined (these includes were not found: `lib.circom`)"
+    )]
+    fn test_main_errors(#[case] circom: &str, #[case] expected: &str) {
+        let err = translate("main.circom", circom).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+}
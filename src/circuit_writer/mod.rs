@@ -134,7 +134,7 @@ impl<B: Backend> CircuitWriter<B> {
 
 impl<B: Backend> CircuitWriter<B> {
     /// Creates a global environment from the one created by the type checker.
-    fn new(typed: TypeChecker<B>, backend: B) -> Self {
+    pub(crate) fn new(typed: TypeChecker<B>, backend: B) -> Self {
         Self {
             typed,
             backend,
@@ -168,7 +168,9 @@ impl<B: Backend> CircuitWriter<B> {
             circuit_writer.add_public_outputs(size_of, typ.span);
         }
 
-        // public inputs should be handled first
+        // public inputs should be handled first,
+        // and they are all added before being constrained, as kimchi expects the public inputs in the first rows
+        let mut public_args = vec![];
         for arg in function.sig.arguments.iter().filter(|arg| arg.is_public()) {
             match &arg.attribute {
                 Some(attr) => {
@@ -180,12 +182,18 @@ impl<B: Backend> CircuitWriter<B> {
                 }
                 None => panic!("public arguments must have a pub attribute"),
             }
-            circuit_writer.handle_arg(arg, fn_env, CircuitWriter::add_public_inputs)?;
+            let var = circuit_writer.add_arg(arg, CircuitWriter::add_public_inputs);
+            public_args.push((arg, var));
+        }
+
+        for (arg, var) in public_args {
+            circuit_writer.constrain_arg(arg, var, fn_env)?;
         }
 
         // then handle private inputs
         for arg in function.sig.arguments.iter().filter(|arg| !arg.is_public()) {
-            circuit_writer.handle_arg(arg, fn_env, CircuitWriter::add_private_inputs)?;
+            let var = circuit_writer.add_arg(arg, CircuitWriter::add_private_inputs);
+            circuit_writer.constrain_arg(arg, var, fn_env)?;
         }
 
         // compile function
@@ -207,6 +215,7 @@ impl<B: Backend> CircuitWriter<B> {
         circuit_writer
             .backend
             .finalize_circuit(public_output, returned_cells, main_span)?;
+        circuit_writer.backend.optimize();
 
         //
         Ok(CompiledCircuit::new(circuit_writer))
@@ -220,19 +229,19 @@ impl<B: Backend> CircuitWriter<B> {
         self.backend.generate_witness(witness_env)
     }
 
-    fn handle_arg(
+    /// Creates the variable of an argument of `main()`, with `handle_input`.
+    fn add_arg(
         &mut self,
         arg: &FnArg,
-        fn_env: &mut FnEnv<B::Field, B::Var>,
         handle_input: fn(&mut CircuitWriter<B>, String, usize, Span) -> Var<B::Field, B::Var>,
-    ) -> Result<()> {
+    ) -> Var<B::Field, B::Var> {
         let FnArg { name, typ, .. } = arg;
 
         // get length
         let len = match &typ.kind {
             TyKind::Field => 1,
             TyKind::Array(typ, len) => {
-                if !matches!(**typ, TyKind::Field) {
+                if !matches!(**typ, TyKind::Field | TyKind::Bool) && !typ.is_integer() {
                     unimplemented!();
                 }
                 *len as usize
@@ -242,7 +251,17 @@ impl<B: Backend> CircuitWriter<B> {
         };
 
         // create the variable
-        let var = handle_input(self, name.value.clone(), len, name.span);
+        handle_input(self, name.value.clone(), len, name.span)
+    }
+
+    /// Constrains the variable of an argument of `main()` to fit in its type, and adds it to the env.
+    fn constrain_arg(
+        &mut self,
+        arg: &FnArg,
+        var: Var<B::Field, B::Var>,
+        fn_env: &mut FnEnv<B::Field, B::Var>,
+    ) -> Result<()> {
+        let FnArg { name, typ, .. } = arg;
 
         // constrain what needs to be constrained
         // (for example, booleans need to be constrained to be 0 or 1)
@@ -1,16 +1,20 @@
 use std::fmt::{self, Display, Formatter};
 
-use ark_ff::{One, Zero};
+use ark_ff::{One, PrimeField, Zero};
 use kimchi::circuits::wires::Wire;
 use num_bigint::BigUint;
 use num_traits::Num as _;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    backends::{kimchi::VestaField, Backend},
+    backends::Backend,
     circuit_writer::{CircuitWriter, DebugInfo, FnEnv, VarInfo},
     constants::Span,
-    constraints::{boolean, field},
+    constraints::{
+        boolean,
+        field::{self, BitwiseOp},
+        packed,
+    },
     error::{ErrorKind, Result},
     imports::FnKind,
     parser::{
@@ -31,6 +35,8 @@ pub enum GateKind {
     Zero,
     DoubleGeneric,
     Poseidon,
+    Xor16,
+    Lookup,
 }
 
 impl From<GateKind> for kimchi::circuits::gate::GateType {
@@ -40,23 +46,25 @@ impl From<GateKind> for kimchi::circuits::gate::GateType {
             GateKind::Zero => Zero,
             GateKind::DoubleGeneric => Generic,
             GateKind::Poseidon => Poseidon,
+            GateKind::Xor16 => Xor16,
+            GateKind::Lookup => Lookup,
         }
     }
 }
 
 // TODO: this could also contain the span that defined the gate!
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Gate {
+pub struct Gate<F> {
     /// Type of gate
     pub typ: GateKind,
 
     /// Coefficients
     #[serde(skip)]
-    pub coeffs: Vec<VestaField>,
+    pub coeffs: Vec<F>,
 }
 
-impl Gate {
-    pub fn to_kimchi_gate(&self, row: usize) -> kimchi::circuits::gate::CircuitGate<VestaField> {
+impl<F: PrimeField> Gate<F> {
+    pub fn to_kimchi_gate(&self, row: usize) -> kimchi::circuits::gate::CircuitGate<F> {
         kimchi::circuits::gate::CircuitGate {
             typ: self.typ.into(),
             wires: Wire::for_row(row),
@@ -249,11 +257,94 @@ impl<B: Backend> CircuitWriter<B> {
                     offset += len;
                 }
             }
-            TyKind::BigInt => unreachable!(),
+            TyKind::U8 | TyKind::U16 | TyKind::U32 | TyKind::U64 | TyKind::I64 => {
+                assert_eq!(input.len(), 1);
+                self.constrain_integer(&input[0], input_typ, span)?;
+            }
+            TyKind::BigInt | TyKind::GenericArray(..) => unreachable!(),
         };
         Ok(())
     }
 
+    /// Constrains a value to fit in its type, if the type is an integer.
+    pub(crate) fn constrain_integer(
+        &mut self,
+        cvar: &ConstOrCell<B::Field, B::Var>,
+        typ: &TyKind,
+        span: Span,
+    ) -> Result<()> {
+        if let Some(num_bits) = typ.uint_bits() {
+            self.constrain_uint(cvar, num_bits, span)
+        } else if let Some(num_bits) = typ.int_bits() {
+            self.constrain_int(cvar, num_bits, span)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Constrains a value to fit in a signed integer of `num_bits` bits.
+    pub(crate) fn constrain_int(
+        &mut self,
+        cvar: &ConstOrCell<B::Field, B::Var>,
+        num_bits: usize,
+        span: Span,
+    ) -> Result<()> {
+        // x is in [-2^(num_bits-1), 2^(num_bits-1)) iff x + 2^(num_bits-1) is in [0, 2^num_bits)
+        let offset = field::signed_offset::<B>(num_bits);
+        let shifted = field::add(self, cvar, &ConstOrCell::Const(offset), span);
+        self.constrain_uint(&shifted[0], num_bits, span)
+    }
+
+    /// Constrains a value compared as a field element to fit in [field::comparison_bits] bits,
+    /// as the comparison gadget is only correct for values that fit (see [field::less_than]).
+    /// Integers already fit in their type, so they are not checked again,
+    /// but unsigned integers that don't fit in [field::comparison_bits] bits can't be compared.
+    pub(crate) fn constrain_comparable(
+        &mut self,
+        typ: Option<&TyKind>,
+        cvar: &ConstOrCell<B::Field, B::Var>,
+        span: Span,
+    ) -> Result<()> {
+        let comparison_bits = field::comparison_bits::<B::Field>();
+        match typ {
+            Some(typ) if typ.is_integer() => match typ.uint_bits() {
+                Some(num_bits) if num_bits > comparison_bits => {
+                    Err(self.error(ErrorKind::FieldTooSmall(num_bits), span))
+                }
+                _ => Ok(()),
+            },
+            _ => self.constrain_uint(cvar, comparison_bits, span),
+        }
+    }
+
+    /// Returns 1 if lhs < rhs, 0 otherwise.
+    /// The values are compared as signed integers of `signed_bits` bits if set,
+    /// and as field elements of [field::comparison_bits] bits otherwise.
+    fn less_than(
+        &mut self,
+        signed_bits: Option<usize>,
+        lhs: &ConstOrCell<B::Field, B::Var>,
+        rhs: &ConstOrCell<B::Field, B::Var>,
+        span: Span,
+    ) -> Result<Var<B::Field, B::Var>> {
+        match signed_bits {
+            Some(num_bits) => field::less_than_signed(self, num_bits, lhs, rhs, span),
+            None => field::less_than(self, field::comparison_bits::<B::Field>(), lhs, rhs, span),
+        }
+    }
+
+    /// Constrains a value to fit in an unsigned integer of `num_bits` bits.
+    pub(crate) fn constrain_uint(
+        &mut self,
+        cvar: &ConstOrCell<B::Field, B::Var>,
+        num_bits: usize,
+        span: Span,
+    ) -> Result<()> {
+        // a U64 over Goldilocks can't be constrained, for example
+        field::check_range_bits(self, num_bits, span)?;
+        field::range_check(self, cvar, num_bits, span)
+    }
+
     /// Compile a function. Used to compile `main()` only for now
     pub(crate) fn compile_main_function(
         &mut self,
@@ -291,6 +382,48 @@ impl<B: Backend> CircuitWriter<B> {
         }
     }
 
+    /// Compiles `array[idx] = rhs` where `idx` is only known at runtime.
+    fn dynamic_array_write(
+        &mut self,
+        fn_env: &mut FnEnv<B::Field, B::Var>,
+        array: &Expr,
+        idx_var: VarOrRef<B>,
+        rhs: &Expr,
+        span: Span,
+    ) -> Result<Option<VarOrRef<B>>> {
+        // the array must be a (part of a) mutable variable
+        let array_ref = self.compute_expr(fn_env, array)?.unwrap();
+        let (var_name, start, len) = match &array_ref {
+            VarOrRef::Var(_) => panic!("can't reassign this non-mutable variable"),
+            VarOrRef::Ref {
+                var_name,
+                start,
+                len,
+            } => (var_name.clone(), *start, *len),
+        };
+
+        let (elem_type, array_len) = match self.expr_type(array) {
+            Some(TyKind::Array(ty, array_len)) => (ty, *array_len as usize),
+            _ => panic!("expected array"),
+        };
+        let elem_len = self.size_of(elem_type);
+
+        let array_var = array_ref.value(self, fn_env);
+        let idx = idx_var.value(self, fn_env);
+        assert_eq!(idx.len(), 1);
+
+        let rhs = self
+            .compute_expr(fn_env, rhs)?
+            .ok_or_else(|| self.error(ErrorKind::CannotComputeExpression, span))?;
+        let rhs = rhs.value(self, fn_env);
+
+        let new_array =
+            field::dynamic_update(self, &array_var, array_len, elem_len, &idx[0], &rhs, span);
+        fn_env.reassign_var_range(&var_name, new_array, start, len);
+
+        Ok(None)
+    }
+
     fn compute_expr(
         &mut self,
         fn_env: &mut FnEnv<B::Field, B::Var>,
@@ -498,6 +631,18 @@ impl<B: Backend> CircuitWriter<B> {
             }
 
             ExprKind::Assignment { lhs, rhs } => {
+                // writing to an array with an index only known at runtime
+                // produces a new array where only the selected element changes
+                if let ExprKind::ArrayAccess { array, idx } = &lhs.kind {
+                    let idx_var = self
+                        .compute_expr(fn_env, idx)?
+                        .ok_or_else(|| self.error(ErrorKind::CannotComputeExpression, expr.span))?;
+
+                    if idx_var.constant().is_none() {
+                        return self.dynamic_array_write(fn_env, array, idx_var, rhs, expr.span);
+                    }
+                }
+
                 // figure out the local var  of lhs
                 let lhs = self.compute_expr(fn_env, lhs)?.unwrap();
 
@@ -532,23 +677,108 @@ impl<B: Backend> CircuitWriter<B> {
             }
 
             ExprKind::BinaryOp { op, lhs, rhs, .. } => {
+                // signed integers are compared differently
+                let signed_bits = [lhs, rhs]
+                    .iter()
+                    .find_map(|operand| self.expr_type(operand).and_then(TyKind::int_bits));
+
+                // bitwise operations on booleans don't need a bit decomposition
+                let is_bool = matches!(self.expr_type(lhs), Some(TyKind::Bool));
+
+                // field elements must fit in the bits of the comparison gadget
+                let is_ordering = matches!(
+                    op,
+                    Op2::LessThan
+                        | Op2::LessThanOrEqual
+                        | Op2::GreaterThan
+                        | Op2::GreaterThanOrEqual
+                );
+                let operand_types = [self.expr_type(lhs).cloned(), self.expr_type(rhs).cloned()];
+
                 let lhs = self.compute_expr(fn_env, lhs)?.unwrap();
                 let rhs = self.compute_expr(fn_env, rhs)?.unwrap();
 
                 let lhs = lhs.value(self, fn_env);
                 let rhs = rhs.value(self, fn_env);
 
+                if is_ordering && signed_bits.is_none() {
+                    for (operand, typ) in [&lhs, &rhs].into_iter().zip(&operand_types) {
+                        self.constrain_comparable(typ.as_ref(), &operand[0], expr.span)?;
+                    }
+                }
+
+                // integers have a known bit length
+                let res_typ = self.expr_type(expr).cloned();
+
+                // bitwise operations on unsigned integers can use lookups instead of a bit decomposition
+                let lookup_bits = res_typ
+                    .as_ref()
+                    .and_then(TyKind::uint_bits)
+                    .filter(|_| self.backend.capabilities().xor_lookup);
+
                 let res = match op {
                     Op2::Addition => field::add(self, &lhs[0], &rhs[0], expr.span),
                     Op2::Subtraction => field::sub(self, &lhs[0], &rhs[0], expr.span),
                     Op2::Multiplication => field::mul(self, &lhs[0], &rhs[0], expr.span),
                     Op2::Equality => field::equal(self, &lhs, &rhs, expr.span),
                     Op2::Inequality => field::not_equal(self, &lhs, &rhs, expr.span),
+                    Op2::LessThan => self.less_than(signed_bits, &lhs[0], &rhs[0], expr.span)?,
+                    Op2::LessThanOrEqual => {
+                        // lhs <= rhs <=> !(rhs < lhs)
+                        let rhs_lt_lhs =
+                            self.less_than(signed_bits, &rhs[0], &lhs[0], expr.span)?;
+                        boolean::not(self, &rhs_lt_lhs[0], expr.span)
+                    }
+                    Op2::GreaterThan => self.less_than(signed_bits, &rhs[0], &lhs[0], expr.span)?,
+                    Op2::GreaterThanOrEqual => {
+                        // lhs >= rhs <=> !(lhs < rhs)
+                        let lhs_lt_rhs =
+                            self.less_than(signed_bits, &lhs[0], &rhs[0], expr.span)?;
+                        boolean::not(self, &lhs_lt_rhs[0], expr.span)
+                    }
                     Op2::BoolAnd => boolean::and(self, &lhs[0], &rhs[0], expr.span),
                     Op2::BoolOr => boolean::or(self, &lhs[0], &rhs[0], expr.span),
-                    Op2::Division => todo!(),
+                    Op2::BitAnd if is_bool => boolean::and(self, &lhs[0], &rhs[0], expr.span),
+                    Op2::BitOr if is_bool => boolean::or(self, &lhs[0], &rhs[0], expr.span),
+                    Op2::BitXor if is_bool => boolean::xor(self, &lhs[0], &rhs[0], expr.span),
+                    Op2::BitAnd | Op2::BitOr | Op2::BitXor if lookup_bits.is_some() => {
+                        let op = match op {
+                            Op2::BitAnd => BitwiseOp::And,
+                            Op2::BitOr => BitwiseOp::Or,
+                            _ => BitwiseOp::Xor,
+                        };
+                        let num_bits = lookup_bits.unwrap();
+                        packed::bitwise(self, &lhs[0], &rhs[0], op, num_bits, expr.span)
+                    }
+                    Op2::BitAnd => {
+                        field::bitwise(self, &lhs[0], &rhs[0], BitwiseOp::And, expr.span)?
+                    }
+                    Op2::BitOr => field::bitwise(self, &lhs[0], &rhs[0], BitwiseOp::Or, expr.span)?,
+                    Op2::BitXor => {
+                        field::bitwise(self, &lhs[0], &rhs[0], BitwiseOp::Xor, expr.span)?
+                    }
+                    Op2::Division | Op2::Modulo => {
+                        if matches!(&rhs[0], ConstOrCell::Const(cst) if cst.is_zero()) {
+                            return Err(self.error(ErrorKind::DivisionByZero, expr.span));
+                        }
+
+                        let (quotient, remainder) =
+                            field::div_mod(self, &lhs[0], &rhs[0], expr.span)?;
+                        if matches!(op, Op2::Division) {
+                            quotient
+                        } else {
+                            remainder
+                        }
+                    }
                 };
 
+                // integer arithmetic is checked, the result must not overflow
+                if matches!(op, Op2::Addition | Op2::Subtraction | Op2::Multiplication) {
+                    if let Some(typ) = &res_typ {
+                        self.constrain_integer(&res[0], typ, expr.span)?;
+                    }
+                }
+
                 Ok(Some(VarOrRef::Var(res)))
             }
 
@@ -557,7 +787,14 @@ impl<B: Backend> CircuitWriter<B> {
 
                 let var = var.value(self, fn_env);
 
-                todo!()
+                let res = field::neg(self, &var[0], expr.span);
+
+                // negating the smallest signed integer overflows
+                if let Some(typ) = self.expr_type(expr).cloned() {
+                    self.constrain_integer(&res[0], &typ, expr.span)?;
+                }
+
+                Ok(Some(VarOrRef::Var(res)))
             }
 
             ExprKind::Not(b) => {
@@ -620,15 +857,30 @@ impl<B: Backend> CircuitWriter<B> {
                 let idx_var = self
                     .compute_expr(fn_env, idx)?
                     .ok_or_else(|| self.error(ErrorKind::CannotComputeExpression, expr.span))?;
-                let idx = idx_var
-                    .constant()
-                    .ok_or_else(|| self.error(ErrorKind::ExpectedConstant, expr.span))?;
-                let idx: BigUint = idx.into();
-                let idx: usize = idx.try_into().unwrap();
 
                 // retrieve the type of the elements in the array
                 let array_typ = self.expr_type(array).expect("cannot find type of array");
 
+                // if the index is only known at runtime, we use a multiplexer
+                let Some(idx) = idx_var.constant() else {
+                    let (elem_type, array_len) = match array_typ {
+                        TyKind::Array(ty, array_len) => (ty, *array_len as usize),
+                        _ => panic!("expected array"),
+                    };
+                    let elem_len = self.size_of(elem_type);
+
+                    let array = var.value(self, fn_env);
+                    let idx = idx_var.value(self, fn_env);
+                    assert_eq!(idx.len(), 1);
+
+                    let res =
+                        field::dynamic_index(self, &array, array_len, elem_len, &idx[0], expr.span);
+                    return Ok(Some(VarOrRef::Var(res)));
+                };
+
+                let idx: BigUint = idx.into();
+                let idx: usize = idx.try_into().unwrap();
+
                 let elem_type = match array_typ {
                     TyKind::Array(ty, array_len) => {
                         if idx >= (*array_len as usize) {
@@ -748,10 +1000,10 @@ impl<B: Backend> CircuitWriter<B> {
 }
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
-pub(crate) struct PendingGate {
+pub(crate) struct PendingGate<F> {
     pub label: &'static str,
     #[serde(skip)]
-    pub coeffs: Vec<VestaField>,
+    pub coeffs: Vec<F>,
     pub vars: Vec<Option<crate::backends::kimchi::KimchiCellVar>>,
     pub span: Span,
 }
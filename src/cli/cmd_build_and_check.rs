@@ -2,11 +2,17 @@ use camino::Utf8PathBuf as PathBuf;
 use clap::ValueEnum;
 use miette::{Context, IntoDiagnostic};
 
+#[cfg(feature = "acir")]
+use crate::backends::r1cs::acir::AcirExporter;
+#[cfg(feature = "zkinterface")]
+use crate::backends::r1cs::zkinterface::ZkinterfaceExporter;
+#[cfg(feature = "bulletproofs")]
+use crate::{backends::bulletproofs::field::R1csRistrettoField, witness::CompiledCircuit};
 use crate::{
     backends::{
         kimchi::{
-            prover::{ProverIndex, VerifierIndex},
-            KimchiVesta,
+            prover::{ProofSystem, ProverIndex, VerifierIndex},
+            Kimchi, KimchiVesta,
         },
         r1cs::{snarkjs::SnarkjsExporter, R1CS},
         Backend, BackendField, BackendKind,
@@ -17,6 +23,10 @@ use crate::{
     type_checker::TypeChecker,
 };
 
+use kimchi::mina_curves::pasta::{Pallas, Vesta};
+
+use super::cmd_srs::srs_cache;
+use super::manifest::{read_manifest, CurveOpt, FieldOpt};
 use super::packages::{
     get_deps_of_package, is_lib, validate_package_and_get_manifest, DependencyGraph, UserRepo,
 };
@@ -27,26 +37,88 @@ const COMPILED_DIR: &str = "compiled";
 #[derive(Clone, ValueEnum)]
 enum BackendOpt {
     KimchiVesta,
+    KimchiPallas,
     R1csBls12_381,
     R1csBn254,
+    /// R1CS over the field given by `--field`, or else by the manifest (bn254 by default).
+    R1cs,
+    #[cfg(feature = "bulletproofs")]
+    Bulletproofs,
 }
 
 impl From<BackendOpt> for BackendKind {
     fn from(value: BackendOpt) -> Self {
         match value {
-            BackendOpt::KimchiVesta => BackendKind::new_kimchi_vesta(false),
+            BackendOpt::KimchiVesta => BackendKind::new_kimchi_vesta(true),
+            BackendOpt::KimchiPallas => BackendKind::new_kimchi_pallas(true),
             BackendOpt::R1csBls12_381 => BackendKind::new_r1cs_bls12_381(),
-            BackendOpt::R1csBn254 => BackendKind::new_r1cs_bn254(),
+            BackendOpt::R1csBn254 | BackendOpt::R1cs => BackendKind::new_r1cs_bn254(),
+            #[cfg(feature = "bulletproofs")]
+            BackendOpt::Bulletproofs => BackendKind::new_bulletproofs(),
+        }
+    }
+}
+
+impl BackendOpt {
+    /// Returns the backend, where the field of the `r1cs` backend is given by `--field`, or else by the manifest.
+    fn into_backend_kind(
+        self,
+        field: Option<FieldOpt>,
+        manifest_field: Option<FieldOpt>,
+    ) -> miette::Result<BackendKind> {
+        match self {
+            BackendOpt::R1cs => Ok(match field.or(manifest_field).unwrap_or_default() {
+                FieldOpt::Bn254 => BackendKind::new_r1cs_bn254(),
+                FieldOpt::Bls12_381 => BackendKind::new_r1cs_bls12_381(),
+                FieldOpt::Pallas => BackendKind::new_r1cs_pallas(),
+            }),
+            _ if field.is_some() => {
+                miette::bail!("`--field` can only be used with the `r1cs` backend")
+            }
+            backend => Ok(backend.into()),
         }
     }
 }
 
+/// Returns the curve of the kimchi backend, given by `--curve`, or else by the manifest of the package (vesta by default).
+pub(crate) fn curve_of_package(
+    curr_dir: &PathBuf,
+    curve: Option<CurveOpt>,
+) -> miette::Result<CurveOpt> {
+    if let Some(curve) = curve {
+        return Ok(curve);
+    }
+
+    let manifest = validate_package_and_get_manifest(curr_dir, false)?;
+    Ok(manifest.package.curve.unwrap_or_default())
+}
+
+/// Returns the field of the r1cs backend given by the manifest of the package that contains a file,
+/// if the file is in a package.
+fn field_of_file(path: &PathBuf) -> miette::Result<Option<FieldOpt>> {
+    let Some(package_dir) = path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Noname.toml").exists())
+    else {
+        return Ok(None);
+    };
+
+    let manifest = read_manifest(&package_dir.to_path_buf())?;
+    Ok(manifest.package.field)
+}
+
 #[derive(clap::Parser)]
 pub struct CmdBuild {
     /// Path to the directory to create.
     #[clap(short, long, value_parser)]
     path: Option<PathBuf>,
 
+    /// Curve of the kimchi backend (defaults to the curve of the manifest, or to vesta).
+    #[clap(long)]
+    #[arg(value_enum)]
+    curve: Option<CurveOpt>,
+
     /// Prints an assembly-like encoding of the circuit.
     #[clap(long)]
     asm: bool,
@@ -71,9 +143,17 @@ pub struct CmdBuild {
 pub fn cmd_build(args: CmdBuild) -> miette::Result<()> {
     let curr_dir = args
         .path
+        .clone()
         .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
 
-    let (sources, prover_index, verifier_index) = build(&curr_dir, args.asm, args.debug)?;
+    match curve_of_package(&curr_dir, args.curve)? {
+        CurveOpt::Vesta => build_and_write::<Vesta>(args, curr_dir),
+        CurveOpt::Pallas => build_and_write::<Pallas>(args, curr_dir),
+    }
+}
+
+fn build_and_write<C: ProofSystem>(args: CmdBuild, curr_dir: PathBuf) -> miette::Result<()> {
+    let (sources, prover_index, verifier_index) = build::<C>(&curr_dir, args.asm, args.debug)?;
 
     // create COMPILED_DIR
     let compiled_path = curr_dir.join(COMPILED_DIR);
@@ -137,7 +217,9 @@ pub fn cmd_check(args: CmdCheck) -> miette::Result<()> {
     Ok(())
 }
 
-fn produce_all_asts<B: Backend>(path: &PathBuf) -> miette::Result<(Sources, TypeChecker<B>)> {
+pub(crate) fn produce_all_asts<B: Backend>(
+    path: &PathBuf,
+) -> miette::Result<(Sources, TypeChecker<B>)> {
     // find manifest
     let manifest = validate_package_and_get_manifest(&path, false)?;
 
@@ -209,19 +291,19 @@ fn produce_all_asts<B: Backend>(path: &PathBuf) -> miette::Result<(Sources, Type
     Ok((sources, tast))
 }
 
-pub fn build(
+pub fn build<C: ProofSystem>(
     curr_dir: &PathBuf,
     asm: bool,
     debug: bool,
-) -> miette::Result<(Sources, ProverIndex, VerifierIndex)> {
+) -> miette::Result<(Sources, ProverIndex<C>, VerifierIndex<C>)> {
     // produce all TASTs
     let (sources, tast) = produce_all_asts(curr_dir)?;
 
     // produce indexes
-    let double_generic_gate_optimization = false;
+    let double_generic_gate_optimization = true;
 
-    let kimchi_vesta = KimchiVesta::new(double_generic_gate_optimization);
-    let compiled_circuit = compile(&sources, tast, kimchi_vesta)?;
+    let kimchi = Kimchi::<C>::new(double_generic_gate_optimization);
+    let compiled_circuit = compile(&sources, tast, kimchi)?;
 
     if asm {
         println!("{}", compiled_circuit.asm(&sources, debug));
@@ -229,8 +311,9 @@ pub fn build(
 
     // TODO: cache artifacts
 
-    // produce indexes
-    let (prover_index, verifier_index) = compiled_circuit.compile_to_indexes()?;
+    // produce indexes, with the cached SRS
+    let (prover_index, verifier_index) =
+        compiled_circuit.compile_to_indexes_with_srs_cache(&srs_cache())?;
 
     Ok((sources, prover_index, verifier_index))
 }
@@ -243,10 +326,15 @@ pub struct CmdTest {
 
     /// Backend to use for running the noname file.
 
-    #[clap(short, long, default_value = "r1cs-bn254")]
+    #[clap(short, long, default_value = "r1cs")]
     #[arg(value_enum)]
     backend: BackendOpt,
 
+    /// Field of the r1cs backend (defaults to the field of the manifest, or to bn254).
+    #[clap(long)]
+    #[arg(value_enum)]
+    field: Option<FieldOpt>,
+
     /// public inputs in a JSON format using decimal values (e.g. {"a": "1", "b": "2"})
     #[clap(long)]
     public_inputs: Option<String>,
@@ -259,12 +347,20 @@ pub struct CmdTest {
     #[clap(short, long)]
     debug: bool,
 
-    /// enable the double generic gate optimization of kimchi (by default noname uses that optimization)
+    /// disable the double generic gate optimization of kimchi (by default noname uses that optimization)
     #[clap(long)]
+    no_double: bool,
+
+    /// deprecated: the double generic gate optimization is now enabled by default, so this flag does nothing
+    #[clap(long, hide = true)]
     double: bool,
 }
 
 pub fn cmd_test(args: CmdTest) -> miette::Result<()> {
+    if args.double {
+        eprintln!("warning: `--double` is deprecated, the optimization is enabled by default (see `--no-double`)");
+    }
+
     // parse inputs
     let public_inputs = if let Some(s) = args.public_inputs {
         parse_inputs(&s)?
@@ -278,27 +374,25 @@ pub fn cmd_test(args: CmdTest) -> miette::Result<()> {
         JsonInputs::default()
     };
 
-    match BackendKind::from(args.backend) {
+    let manifest_field = field_of_file(&args.path)?;
+    match args.backend.into_backend_kind(args.field, manifest_field)? {
         BackendKind::KimchiVesta(_) => {
-            let (tast, sources) = typecheck_file(&args.path)?;
-            let kimchi_vesta = KimchiVesta::new(args.double);
-            let compiled_circuit = compile(&sources, tast, kimchi_vesta)?;
-
-            let (prover_index, verifier_index) = compiled_circuit.compile_to_indexes()?;
-            println!("successfully compiled");
-
-            // print ASM
-            let asm = prover_index.asm(&sources, args.debug);
-            println!("{asm}");
-
-            // create proof
-            let (proof, full_public_inputs, _public_output) =
-                prover_index.prove(&sources, public_inputs, private_inputs, args.debug)?;
-            println!("proof created");
-
-            // verify proof
-            verifier_index.verify(full_public_inputs, proof)?;
-            println!("proof verified");
+            test_kimchi_backend::<Vesta>(
+                &args.path,
+                public_inputs,
+                private_inputs,
+                args.debug,
+                args.no_double,
+            )?;
+        }
+        BackendKind::KimchiPallas(_) => {
+            test_kimchi_backend::<Pallas>(
+                &args.path,
+                public_inputs,
+                private_inputs,
+                args.debug,
+                args.no_double,
+            )?;
         }
         BackendKind::R1csBls12_381(r1cs) => {
             test_r1cs_backend(r1cs, &args.path, public_inputs, private_inputs, args.debug)?;
@@ -306,6 +400,25 @@ pub fn cmd_test(args: CmdTest) -> miette::Result<()> {
         BackendKind::R1csBn254(r1cs) => {
             test_r1cs_backend(r1cs, &args.path, public_inputs, private_inputs, args.debug)?;
         }
+        BackendKind::R1csPallas(r1cs) => {
+            test_r1cs_backend(r1cs, &args.path, public_inputs, private_inputs, args.debug)?;
+        }
+        #[cfg(feature = "bulletproofs")]
+        BackendKind::Bulletproofs(r1cs) => {
+            let (tast, sources) = typecheck_file(&args.path)?;
+            let compiled_circuit = compile(&sources, tast, r1cs)?;
+
+            // print ASM
+            let asm = compiled_circuit.asm(&sources, args.debug);
+            println!("{asm}");
+
+            prove_and_verify_bulletproofs(
+                compiled_circuit,
+                &sources,
+                public_inputs,
+                private_inputs,
+            )?;
+        }
     }
 
     Ok(())
@@ -318,10 +431,15 @@ pub struct CmdRun {
     path: Option<PathBuf>,
 
     /// Backend to use for running the noname file.
-    #[clap(short, long, default_value = "r1cs-bn254")]
+    #[clap(short, long, default_value = "r1cs")]
     #[arg(value_enum)]
     backend: BackendOpt,
 
+    /// Field of the r1cs backend (defaults to the field of the manifest, or to bn254).
+    #[clap(long)]
+    #[arg(value_enum)]
+    field: Option<FieldOpt>,
+
     /// JSON encoding of the public inputs. For example: `--public-inputs {"a": "1", "b": ["2", "3"]}`.
     #[clap(long, value_parser, default_value = "{}")]
     public_inputs: Option<String>,
@@ -349,9 +467,13 @@ pub fn cmd_run(args: CmdRun) -> miette::Result<()> {
         JsonInputs::default()
     };
 
-    match BackendKind::from(args.backend) {
-        BackendKind::KimchiVesta(_) => {
-            unimplemented!("kimchi-vesta backend is not yet supported for this command")
+    let manifest = validate_package_and_get_manifest(&curr_dir, false)?;
+    match args
+        .backend
+        .into_backend_kind(args.field, manifest.package.field)?
+    {
+        BackendKind::KimchiVesta(_) | BackendKind::KimchiPallas(_) => {
+            unimplemented!("the kimchi backends are not yet supported for this command")
         }
         BackendKind::R1csBls12_381(r1cs) => {
             run_r1cs_backend(r1cs, &curr_dir, public_inputs, private_inputs)?
@@ -359,6 +481,20 @@ pub fn cmd_run(args: CmdRun) -> miette::Result<()> {
         BackendKind::R1csBn254(r1cs) => {
             run_r1cs_backend(r1cs, &curr_dir, public_inputs, private_inputs)?
         }
+        BackendKind::R1csPallas(r1cs) => {
+            run_r1cs_backend(r1cs, &curr_dir, public_inputs, private_inputs)?
+        }
+        #[cfg(feature = "bulletproofs")]
+        BackendKind::Bulletproofs(r1cs) => {
+            let (sources, tast) = produce_all_asts(&curr_dir)?;
+            let compiled_circuit = compile(&sources, tast, r1cs)?;
+            prove_and_verify_bulletproofs(
+                compiled_circuit,
+                &sources,
+                public_inputs,
+                private_inputs,
+            )?
+        }
     }
 
     Ok(())
@@ -381,18 +517,86 @@ where
     let generated_witness =
         generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs)?;
 
+    // the exporters of the optional formats are behind cargo features of the same name
+    #[cfg(feature = "zkinterface")]
+    {
+        let zkinterface_exporter =
+            ZkinterfaceExporter::new(compiled_circuit.circuit.backend.clone());
+        let zkif_output_path = curr_dir.join("output.zkif");
+        zkinterface_exporter.gen_zkif_file(
+            &zkif_output_path.clone().into_string(),
+            Some(&generated_witness),
+        )?;
+        println!("Zkinterface file generated at: {}", zkif_output_path);
+    }
+
+    // ACIR only supports bn254
+    #[cfg(feature = "acir")]
+    {
+        if let Ok(acir_exporter) = AcirExporter::new(compiled_circuit.circuit.backend.clone()) {
+            let acir_output_path = curr_dir.join("output.acir");
+            let acir_witness_output_path = curr_dir.join("output.gz");
+            acir_exporter.gen_acir_file(&acir_output_path.clone().into_string())?;
+            acir_exporter.gen_witness_file(
+                &acir_witness_output_path.clone().into_string(),
+                &generated_witness,
+            )?;
+            println!("ACIR file generated at: {}", acir_output_path);
+            println!(
+                "ACIR witness file generated at: {}",
+                acir_witness_output_path
+            );
+        }
+    }
+
     let snarkjs_exporter = SnarkjsExporter::new(compiled_circuit.circuit.backend);
 
     let r1cs_output_path = curr_dir.join("output.r1cs");
     let wtns_output_path = curr_dir.join("output.wtns");
+    let sym_output_path = curr_dir.join("output.sym");
 
     snarkjs_exporter.gen_r1cs_file(&r1cs_output_path.clone().into_string())?;
 
+    snarkjs_exporter.gen_sym_file(&sym_output_path.clone().into_string(), &sources)?;
+
     snarkjs_exporter.gen_wtns_file(&wtns_output_path.clone().into_string(), generated_witness)?;
 
     // display the info for the generated files
     println!("Snarkjs R1CS file generated at: {}", r1cs_output_path);
     println!("Snarkjs Witness file generated at: {}", wtns_output_path);
+    println!("Symbol file generated at: {}", sym_output_path);
+    println!("Once you have a zkey for the R1CS file, you can prove with: snarkjs groth16 prove <circuit.zkey> {wtns_output_path} proof.json public.json");
+
+    Ok(())
+}
+
+fn test_kimchi_backend<C: ProofSystem>(
+    path: &PathBuf,
+    public_inputs: JsonInputs,
+    private_inputs: JsonInputs,
+    debug: bool,
+    no_double: bool,
+) -> miette::Result<()> {
+    let (tast, sources) = typecheck_file(path)?;
+    let kimchi = Kimchi::<C>::new(!no_double);
+    let compiled_circuit = compile(&sources, tast, kimchi)?;
+
+    let (prover_index, verifier_index) =
+        compiled_circuit.compile_to_indexes_with_srs_cache(&srs_cache())?;
+    println!("successfully compiled");
+
+    // print ASM
+    let asm = prover_index.asm(&sources, debug);
+    println!("{asm}");
+
+    // create proof
+    let (proof, full_public_inputs, _public_output) =
+        prover_index.prove(&sources, public_inputs, private_inputs, debug)?;
+    println!("proof created");
+
+    // verify proof
+    verifier_index.verify(full_public_inputs, proof)?;
+    println!("proof verified");
 
     Ok(())
 }
@@ -420,6 +624,31 @@ where
     Ok(())
 }
 
+/// Bulletproofs don't need a setup, so the circuit is directly proven and verified.
+#[cfg(feature = "bulletproofs")]
+fn prove_and_verify_bulletproofs(
+    compiled_circuit: CompiledCircuit<R1CS<R1csRistrettoField>>,
+    sources: &Sources,
+    public_inputs: JsonInputs,
+    private_inputs: JsonInputs,
+) -> miette::Result<()> {
+    let (prover_index, verifier_index) = compiled_circuit.compile_to_bulletproofs_indexes()?;
+
+    // create proof
+    let (proof, full_public_inputs, public_output) =
+        prover_index.prove(sources, public_inputs, private_inputs)?;
+    println!("proof created ({} bytes)", proof.to_bytes().len());
+    if !public_output.is_empty() {
+        println!("the public output is `{public_output:?}`");
+    }
+
+    // verify proof
+    verifier_index.verify(&full_public_inputs, &proof)?;
+    println!("proof verified");
+
+    Ok(())
+}
+
 fn typecheck_file<B: Backend>(path: &PathBuf) -> miette::Result<(TypeChecker<B>, Sources)> {
     let code = std::fs::read_to_string(path)
         .into_diagnostic()
@@ -431,3 +660,99 @@ fn typecheck_file<B: Backend>(path: &PathBuf) -> miette::Result<(TypeChecker<B>,
 
     Ok((tast, sources))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::manifest::Manifest;
+
+    use super::*;
+
+    #[test]
+    fn test_field_opt_parsing() {
+        // from the command line
+        assert!(matches!(
+            FieldOpt::from_str("bn254", false),
+            Ok(FieldOpt::Bn254)
+        ));
+        assert!(matches!(
+            FieldOpt::from_str("bls12-381", false),
+            Ok(FieldOpt::Bls12_381)
+        ));
+        assert!(matches!(
+            FieldOpt::from_str("pallas", false),
+            Ok(FieldOpt::Pallas)
+        ));
+        assert!(FieldOpt::from_str("goldilocks", false).is_err());
+
+        // from the manifest
+        let manifest: Manifest = toml::from_str(
+            r#"
+            [package]
+            name = "mimoo/example"
+            version = "0.1.0"
+            field = "bls12-381"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(manifest.package.field, Some(FieldOpt::Bls12_381)));
+    }
+
+    #[test]
+    fn test_field_precedence() {
+        // `--field` overrides the manifest
+        let backend =
+            BackendOpt::R1cs.into_backend_kind(Some(FieldOpt::Pallas), Some(FieldOpt::Bn254));
+        assert!(matches!(backend, Ok(BackendKind::R1csPallas(_))));
+
+        // which overrides the default
+        let backend = BackendOpt::R1cs.into_backend_kind(None, Some(FieldOpt::Bls12_381));
+        assert!(matches!(backend, Ok(BackendKind::R1csBls12_381(_))));
+
+        let backend = BackendOpt::R1cs.into_backend_kind(None, None);
+        assert!(matches!(backend, Ok(BackendKind::R1csBn254(_))));
+
+        // the field of the manifest is ignored by the other backends
+        let backend = BackendOpt::KimchiVesta.into_backend_kind(None, Some(FieldOpt::Pallas));
+        assert!(matches!(backend, Ok(BackendKind::KimchiVesta(_))));
+
+        // but `--field` can't be used with them
+        let backend = BackendOpt::KimchiVesta.into_backend_kind(Some(FieldOpt::Pallas), None);
+        assert!(backend.is_err());
+    }
+
+    #[test]
+    fn test_field_of_file() {
+        let package_dir = std::env::temp_dir().join(format!("noname_field_{}", std::process::id()));
+        let package_dir = PathBuf::try_from(package_dir).unwrap();
+        std::fs::create_dir_all(package_dir.join("src")).unwrap();
+        std::fs::write(
+            package_dir.join("Noname.toml"),
+            "[package]\nname = \"mimoo/example\"\nversion = \"0.1.0\"\nfield = \"pallas\"\n",
+        )
+        .unwrap();
+
+        // the manifest is found from a file of the package
+        let field = field_of_file(&package_dir.join("src").join("main.no"));
+        assert!(matches!(field, Ok(Some(FieldOpt::Pallas))));
+
+        // a file outside of a package has no manifest
+        std::fs::remove_file(package_dir.join("Noname.toml")).unwrap();
+        let field = field_of_file(&package_dir.join("src").join("main.no"));
+        assert!(matches!(field, Ok(None)));
+
+        std::fs::remove_dir_all(package_dir).unwrap();
+    }
+
+    #[test]
+    fn test_double_flag_is_still_accepted() {
+        use clap::Parser;
+
+        let args = CmdTest::try_parse_from(["test", "--path", "main.no", "--double"]).unwrap();
+        assert!(args.double);
+        assert!(!args.no_double);
+
+        let args = CmdTest::try_parse_from(["test", "--path", "main.no", "--no-double"]).unwrap();
+        assert!(!args.double);
+        assert!(args.no_double);
+    }
+}
@@ -0,0 +1,157 @@
+use camino::Utf8PathBuf as PathBuf;
+
+use crate::backends::groth16::ceremony::{to_hex, Ceremony};
+
+use super::cmd_groth16::{build, read, write};
+
+#[derive(clap::Parser)]
+pub struct CmdCeremony {
+    #[command(subcommand)]
+    command: CeremonyCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum CeremonyCommand {
+    /// Start the phase 2 ceremony of the current package, from the powers of tau of phase 1
+    New(CmdNew),
+
+    /// Contribute randomness to the ceremony, and print the hash of the contribution
+    Contribute(CmdContribute),
+
+    /// Verify the transcript of the ceremony, and produce the proving and verifying keys
+    Verify(CmdVerify),
+}
+
+#[derive(clap::Parser)]
+struct CmdNew {
+    /// Path to the package.
+    #[clap(short, long, value_parser)]
+    path: Option<PathBuf>,
+
+    /// Path to the powers of tau of phase 1 (a `.ptau` file of snarkjs).
+    #[clap(long, value_parser)]
+    ptau: PathBuf,
+
+    /// Path to the resulting ceremony. Defaults to `groth16.ceremony`.
+    #[clap(long, value_parser)]
+    ceremony_path: Option<PathBuf>,
+}
+
+#[derive(clap::Parser)]
+struct CmdContribute {
+    /// Path to the ceremony, which is updated in place.
+    #[clap(long, value_parser, default_value = "groth16.ceremony")]
+    ceremony_path: PathBuf,
+
+    /// Additional entropy, mixed with the randomness of the OS.
+    #[clap(long, default_value = "")]
+    entropy: String,
+}
+
+#[derive(clap::Parser)]
+struct CmdVerify {
+    /// Path to the package.
+    #[clap(short, long, value_parser)]
+    path: Option<PathBuf>,
+
+    /// Path to the powers of tau of phase 1 that the ceremony started from.
+    #[clap(long, value_parser)]
+    ptau: PathBuf,
+
+    /// Path to the ceremony. Defaults to `groth16.ceremony`.
+    #[clap(long, value_parser)]
+    ceremony_path: Option<PathBuf>,
+
+    /// Path to the resulting proving key. Defaults to `groth16.pk`.
+    #[clap(long, value_parser)]
+    proving_key_path: Option<PathBuf>,
+
+    /// Path to the resulting verifying key. Defaults to `groth16.vk`.
+    #[clap(long, value_parser)]
+    verifying_key_path: Option<PathBuf>,
+}
+
+pub fn cmd_ceremony(args: CmdCeremony) -> miette::Result<()> {
+    match args.command {
+        CeremonyCommand::New(args) => cmd_new(args),
+        CeremonyCommand::Contribute(args) => cmd_contribute(args),
+        CeremonyCommand::Verify(args) => cmd_verify(args),
+    }
+}
+
+fn cmd_new(args: CmdNew) -> miette::Result<()> {
+    let curr_dir = args
+        .path
+        .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
+
+    let (_sources, compiled_circuit) = build(&curr_dir)?;
+    let ceremony = Ceremony::new(&compiled_circuit, &args.ptau)?;
+
+    let ceremony_path = args
+        .ceremony_path
+        .unwrap_or_else(|| curr_dir.join("groth16.ceremony"));
+    write(&ceremony_path, ceremony.to_bytes(), "ceremony")?;
+
+    println!(
+        "ceremony started at path `{ceremony_path}`, with the initial hash `{}`",
+        to_hex(&ceremony.hash())
+    );
+
+    Ok(())
+}
+
+fn cmd_contribute(args: CmdContribute) -> miette::Result<()> {
+    let mut ceremony = Ceremony::from_bytes(&read(&args.ceremony_path, "ceremony")?)?;
+    let hash = ceremony.contribute(args.entropy.as_bytes());
+    write(&args.ceremony_path, ceremony.to_bytes(), "ceremony")?;
+
+    println!(
+        "contribution {} added to `{}`, with the hash:\n{}",
+        ceremony.contributions().len(),
+        args.ceremony_path,
+        to_hex(&hash)
+    );
+
+    Ok(())
+}
+
+fn cmd_verify(args: CmdVerify) -> miette::Result<()> {
+    let curr_dir = args
+        .path
+        .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
+
+    let (_sources, compiled_circuit) = build(&curr_dir)?;
+    let ceremony_path = args
+        .ceremony_path
+        .unwrap_or_else(|| curr_dir.join("groth16.ceremony"));
+    let ceremony = Ceremony::from_bytes(&read(&ceremony_path, "ceremony")?)?;
+    ceremony.verify(&compiled_circuit, &args.ptau)?;
+
+    // the transcript, so that contributors can find their hash
+    for (index, contribution) in ceremony.contributions().iter().enumerate() {
+        println!("contribution {}: {}", index + 1, to_hex(&contribution.hash));
+    }
+    if ceremony.contributions().is_empty() {
+        miette::bail!("the ceremony has no contributions, so its keys are not secure");
+    }
+    println!("the ceremony at path `{ceremony_path}` is valid");
+
+    let (prover_index, verifier_index) = ceremony.into_indexes(compiled_circuit);
+    let proving_key_path = args
+        .proving_key_path
+        .unwrap_or_else(|| curr_dir.join("groth16.pk"));
+    let verifying_key_path = args
+        .verifying_key_path
+        .unwrap_or_else(|| curr_dir.join("groth16.vk"));
+    write(&proving_key_path, prover_index.to_bytes(), "proving key")?;
+    write(
+        &verifying_key_path,
+        verifier_index.to_bytes(),
+        "verifying key",
+    )?;
+
+    println!("proving key written at path `{proving_key_path}`");
+    println!("verifying key written at path `{verifying_key_path}`");
+
+    Ok(())
+}
@@ -0,0 +1,382 @@
+use std::str::FromStr;
+
+use camino::Utf8PathBuf as PathBuf;
+use miette::{Context, IntoDiagnostic};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{
+        groth16::{self, gnark, wasm, Groth16Proof, ProverIndex, VerifierIndex},
+        r1cs::{R1csBn254Field, R1CS},
+    },
+    compiler::{compile, Sources},
+    inputs::parse_inputs,
+    witness::CompiledCircuit,
+};
+
+use super::{cmd_build_and_check::produce_all_asts, NONAME_DIRECTORY};
+
+#[derive(clap::Parser)]
+pub struct CmdGroth16 {
+    #[command(subcommand)]
+    command: Groth16Command,
+}
+
+#[derive(clap::Subcommand)]
+enum Groth16Command {
+    /// Run the setup of the current package, and produce its proving and verifying keys
+    Setup(CmdSetup),
+
+    /// Run the main function and produce a proof
+    Prove(CmdProve),
+
+    /// Verify a proof
+    Verify(CmdVerify),
+
+    /// Produce a wasm verifier and its JS wrapper, to verify proofs in the browser
+    WasmVerifier(CmdWasmVerifier),
+
+    /// Produce a Go package that verifies proofs with gnark
+    GnarkVerifier(CmdGnarkVerifier),
+}
+
+#[derive(clap::Parser)]
+struct CmdSetup {
+    /// Path to the package.
+    #[clap(short, long, value_parser)]
+    path: Option<PathBuf>,
+
+    /// Path to the resulting proving key. Defaults to `groth16.pk`.
+    #[clap(long, value_parser)]
+    proving_key_path: Option<PathBuf>,
+
+    /// Path to the resulting verifying key. Defaults to `groth16.vk`.
+    #[clap(long, value_parser)]
+    verifying_key_path: Option<PathBuf>,
+
+    /// Path to the powers of tau of a ceremony (a `.ptau` file of snarkjs), to use instead of sampling them locally.
+    #[clap(long, value_parser)]
+    ptau: Option<PathBuf>,
+}
+
+#[derive(clap::Parser)]
+struct CmdProve {
+    /// Path to the package.
+    #[clap(short, long, value_parser)]
+    path: Option<PathBuf>,
+
+    /// Path to the proving key. Defaults to `groth16.pk`.
+    #[clap(long, value_parser)]
+    proving_key_path: Option<PathBuf>,
+
+    /// Path to the resulting proof. Defaults to `proof.groth16`.
+    #[clap(long, value_parser)]
+    proof_path: Option<PathBuf>,
+
+    /// Path to the resulting public inputs of the verifier. Defaults to `public.json`.
+    #[clap(long, value_parser)]
+    public_path: Option<PathBuf>,
+
+    /// Path to also write the proof in the format of gnark, for the Go verifier of `noname groth16 gnark-verifier`.
+    #[clap(long, value_parser)]
+    gnark_proof_path: Option<PathBuf>,
+
+    /// JSON encoding of the public inputs. For example: `--public-inputs {"a": "1", "b": ["2", "3"]}`.
+    #[clap(long, value_parser, default_value = "{}")]
+    public_inputs: String,
+
+    /// JSON encoding of the private inputs. Similar to `--public-inputs` but for private inputs.
+    #[clap(long, value_parser, default_value = "{}")]
+    private_inputs: String,
+}
+
+#[derive(clap::Parser)]
+struct CmdVerify {
+    /// Path to the package.
+    #[clap(short, long, value_parser)]
+    path: Option<PathBuf>,
+
+    /// Path to the verifying key. Defaults to `groth16.vk`.
+    #[clap(long, value_parser)]
+    verifying_key_path: Option<PathBuf>,
+
+    /// Path to the proof to verify. Defaults to `proof.groth16`.
+    #[clap(long, value_parser)]
+    proof_path: Option<PathBuf>,
+
+    /// Path to the public inputs of the verifier (the public outputs, then the public inputs). Defaults to `public.json`.
+    #[clap(long, value_parser)]
+    public_path: Option<PathBuf>,
+}
+
+#[derive(clap::Parser)]
+struct CmdWasmVerifier {
+    /// Path to the package.
+    #[clap(short, long, value_parser)]
+    path: Option<PathBuf>,
+
+    /// Path to the verifying key. Defaults to `groth16.vk`.
+    #[clap(long, value_parser)]
+    verifying_key_path: Option<PathBuf>,
+
+    /// Path to the resulting directory, with `verifier.wasm` and `verifier.js`. Defaults to `verifier`.
+    #[clap(long, value_parser)]
+    output_dir: Option<PathBuf>,
+}
+
+#[derive(clap::Parser)]
+struct CmdGnarkVerifier {
+    /// Path to the package.
+    #[clap(short, long, value_parser)]
+    path: Option<PathBuf>,
+
+    /// Path to the proving key, which has the points that gnark needs in its verifying key. Defaults to `groth16.pk`.
+    #[clap(long, value_parser)]
+    proving_key_path: Option<PathBuf>,
+
+    /// Path to the resulting Go package. Defaults to `verifier-go`.
+    #[clap(long, value_parser)]
+    output_dir: Option<PathBuf>,
+
+    /// Name of the Go package.
+    #[clap(long, value_parser, default_value = "verifier")]
+    package: String,
+}
+
+pub fn cmd_groth16(args: CmdGroth16) -> miette::Result<()> {
+    match args.command {
+        Groth16Command::Setup(args) => cmd_setup(args),
+        Groth16Command::Prove(args) => cmd_prove(args),
+        Groth16Command::Verify(args) => cmd_verify(args),
+        Groth16Command::WasmVerifier(args) => cmd_wasm_verifier(args),
+        Groth16Command::GnarkVerifier(args) => cmd_gnark_verifier(args),
+    }
+}
+
+fn cmd_setup(args: CmdSetup) -> miette::Result<()> {
+    let curr_dir = args
+        .path
+        .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
+
+    let (_sources, compiled_circuit) = build(&curr_dir)?;
+    let (prover_index, verifier_index) = match &args.ptau {
+        Some(ptau_path) => compiled_circuit.compile_to_groth16_indexes_with_ptau(ptau_path)?,
+        None => compiled_circuit.compile_to_groth16_indexes()?,
+    };
+
+    let proving_key_path = args
+        .proving_key_path
+        .unwrap_or_else(|| curr_dir.join("groth16.pk"));
+    let verifying_key_path = args
+        .verifying_key_path
+        .unwrap_or_else(|| curr_dir.join("groth16.vk"));
+
+    write(&proving_key_path, prover_index.to_bytes(), "proving key")?;
+    write(
+        &verifying_key_path,
+        verifier_index.to_bytes(),
+        "verifying key",
+    )?;
+
+    println!("proving key written at path `{proving_key_path}`");
+    println!("verifying key written at path `{verifying_key_path}`");
+
+    Ok(())
+}
+
+fn cmd_prove(args: CmdProve) -> miette::Result<()> {
+    let curr_dir = args
+        .path
+        .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
+
+    let (sources, compiled_circuit) = build(&curr_dir)?;
+
+    let proving_key_path = args
+        .proving_key_path
+        .unwrap_or_else(|| curr_dir.join("groth16.pk"));
+    let bytes = read(&proving_key_path, "proving key")?;
+    let prover_index = ProverIndex::from_bytes(compiled_circuit, &bytes)?;
+
+    // parse inputs
+    let public_inputs = parse_inputs(&args.public_inputs)?;
+    let private_inputs = parse_inputs(&args.private_inputs)?;
+
+    // create proof
+    let (proof, full_public_inputs, public_output) =
+        prover_index.prove(&sources, public_inputs, private_inputs)?;
+
+    let proof_path = args
+        .proof_path
+        .unwrap_or_else(|| curr_dir.join("proof.groth16"));
+    let public_path = args
+        .public_path
+        .unwrap_or_else(|| curr_dir.join("public.json"));
+
+    // the public inputs of the verifier are written as decimal strings, as snarkjs does
+    let public: Vec<String> = full_public_inputs
+        .into_iter()
+        .map(|value| BigUint::from(value).to_string())
+        .collect();
+
+    write(&proof_path, groth16::to_bytes(&proof), "proof")?;
+    write(
+        &public_path,
+        serde_json::to_vec(&public).unwrap(),
+        "public inputs",
+    )?;
+
+    println!("proof created at path `{proof_path}`, with the public inputs of the verifier at path `{public_path}`");
+
+    if let Some(gnark_proof_path) = args.gnark_proof_path {
+        write(
+            &gnark_proof_path,
+            gnark::proof_to_gnark(&proof),
+            "gnark proof",
+        )?;
+        println!("proof in the format of gnark written at path `{gnark_proof_path}`");
+    }
+
+    if !public_output.is_empty() {
+        println!("the public output is `{public_output:?}`");
+    }
+
+    Ok(())
+}
+
+fn cmd_verify(args: CmdVerify) -> miette::Result<()> {
+    let curr_dir = args
+        .path
+        .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
+
+    let verifying_key_path = args
+        .verifying_key_path
+        .unwrap_or_else(|| curr_dir.join("groth16.vk"));
+    let proof_path = args
+        .proof_path
+        .unwrap_or_else(|| curr_dir.join("proof.groth16"));
+    let public_path = args
+        .public_path
+        .unwrap_or_else(|| curr_dir.join("public.json"));
+
+    let verifier_index = VerifierIndex::from_bytes(&read(&verifying_key_path, "verifying key")?)?;
+    let proof: Groth16Proof = groth16::from_bytes(&read(&proof_path, "proof")?, "proof")?;
+
+    let public: Vec<String> = serde_json::from_slice(&read(&public_path, "public inputs")?)
+        .into_diagnostic()
+        .wrap_err(format!(
+            "could not parse the public inputs at `{public_path}`"
+        ))?;
+    let full_public_inputs = public
+        .iter()
+        .map(|value| {
+            R1csBn254Field::from_str(value)
+                .map_err(|_| miette::miette!("invalid public input `{value}` at `{public_path}`"))
+        })
+        .collect::<miette::Result<Vec<_>>>()?;
+
+    verifier_index.verify(&full_public_inputs, &proof)?;
+
+    println!("proof verified");
+
+    Ok(())
+}
+
+fn cmd_wasm_verifier(args: CmdWasmVerifier) -> miette::Result<()> {
+    let curr_dir = args
+        .path
+        .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
+
+    let (_sources, compiled_circuit) = build(&curr_dir)?;
+
+    let verifying_key_path = args
+        .verifying_key_path
+        .unwrap_or_else(|| curr_dir.join("groth16.vk"));
+    let verifier_index = VerifierIndex::from_bytes(&read(&verifying_key_path, "verifying key")?)?;
+    let js = compiled_circuit.groth16_js_verifier(&verifier_index)?;
+
+    // the wasm verifier is the same for all circuits, so it is built once under the noname directory
+    let home_dir: PathBuf = dirs::home_dir()
+        .expect("could not find home directory of current user")
+        .try_into()
+        .expect("invalid UTF8 path");
+    let build_dir = home_dir.join(NONAME_DIRECTORY).join("wasm-verifier");
+    let wasm = wasm::build_verifier_wasm(&build_dir)?;
+
+    let output_dir = args.output_dir.unwrap_or_else(|| curr_dir.join("verifier"));
+    std::fs::create_dir_all(&output_dir)
+        .into_diagnostic()
+        .wrap_err(format!("could not create the directory `{output_dir}`"))?;
+    write(&output_dir.join("verifier.wasm"), wasm, "wasm verifier")?;
+    write(
+        &output_dir.join("verifier.js"),
+        js.into_bytes(),
+        "JS wrapper",
+    )?;
+
+    println!("wasm verifier and its JS wrapper written in `{output_dir}`");
+
+    Ok(())
+}
+
+fn cmd_gnark_verifier(args: CmdGnarkVerifier) -> miette::Result<()> {
+    let curr_dir = args
+        .path
+        .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
+
+    let proving_key_path = args
+        .proving_key_path
+        .unwrap_or_else(|| curr_dir.join("groth16.pk"));
+    let verifier = gnark::gnark_verifier(&read(&proving_key_path, "proving key")?, &args.package)?;
+
+    let output_dir = args
+        .output_dir
+        .unwrap_or_else(|| curr_dir.join("verifier-go"));
+    std::fs::create_dir_all(&output_dir)
+        .into_diagnostic()
+        .wrap_err(format!("could not create the directory `{output_dir}`"))?;
+    write(
+        &output_dir.join("verifying_key.bin"),
+        verifier.verifying_key,
+        "gnark verifying key",
+    )?;
+    write(
+        &output_dir.join("verifier.go"),
+        verifier.verifier_go.into_bytes(),
+        "Go verifier",
+    )?;
+    write(
+        &output_dir.join("go.mod"),
+        verifier.go_mod.into_bytes(),
+        "Go module",
+    )?;
+
+    println!("Go verifier written in `{output_dir}` (run `go mod tidy` there to fetch gnark)");
+
+    Ok(())
+}
+
+/// Compiles the package with the R1CS backend over bn254.
+pub(crate) fn build(
+    curr_dir: &PathBuf,
+) -> miette::Result<(Sources, CompiledCircuit<R1CS<R1csBn254Field>>)> {
+    let (sources, tast) = produce_all_asts(curr_dir)?;
+    let compiled_circuit = compile(&sources, tast, R1CS::new())?;
+
+    Ok((sources, compiled_circuit))
+}
+
+pub(crate) fn read(path: &PathBuf, what: &str) -> miette::Result<Vec<u8>> {
+    if !path.exists() {
+        miette::bail!("{what} does not exist at path `{path}`");
+    }
+
+    std::fs::read(path)
+        .into_diagnostic()
+        .wrap_err(format!("could not read the {what} at `{path}`"))
+}
+
+pub(crate) fn write(path: &PathBuf, bytes: Vec<u8>, what: &str) -> miette::Result<()> {
+    std::fs::write(path, bytes)
+        .into_diagnostic()
+        .wrap_err(format!("could not write the {what} to `{path}`"))
+}
@@ -0,0 +1,34 @@
+use camino::Utf8PathBuf as PathBuf;
+use miette::{Context, IntoDiagnostic};
+
+use crate::circom::import_circom;
+
+#[derive(clap::Parser)]
+pub struct CmdImportCircom {
+    /// Path to the circom file that defines the main component.
+    #[clap(short, long, value_parser)]
+    path: PathBuf,
+
+    /// Paths where the included circom files are looked up (e.g. the `node_modules` directory containing circomlib).
+    #[clap(short = 'l', long = "include-path", value_parser)]
+    include_paths: Vec<PathBuf>,
+
+    /// Path to the resulting noname file. Defaults to the circom file with the `.no` extension.
+    #[clap(short, long, value_parser)]
+    output: Option<PathBuf>,
+}
+
+pub fn cmd_import_circom(args: CmdImportCircom) -> miette::Result<()> {
+    let code = import_circom(&args.path, &args.include_paths)?;
+
+    let output = args
+        .output
+        .unwrap_or_else(|| args.path.with_extension("no"));
+    std::fs::write(&output, code)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("could not write the noname file at path `{output}`"))?;
+
+    println!("translated `{}` to `{output}`", args.path);
+
+    Ok(())
+}
@@ -1,9 +1,18 @@
 use camino::Utf8PathBuf as PathBuf;
+use clap::ValueEnum;
+use kimchi::mina_curves::pasta::{Pallas, Vesta};
 use miette::{Context, IntoDiagnostic};
 
-use crate::inputs::parse_inputs;
+use crate::{
+    backends::kimchi::prover::ProofSystem,
+    inputs::parse_inputs,
+    proof_file::{CommitmentScheme, ProofFile},
+};
 
-use super::cmd_build_and_check::build;
+use super::{
+    cmd_build_and_check::{build, curve_of_package},
+    manifest::CurveOpt,
+};
 
 #[derive(clap::Parser)]
 pub struct CmdProve {
@@ -11,6 +20,15 @@ pub struct CmdProve {
     #[clap(short, long, value_parser)]
     path: Option<PathBuf>,
 
+    /// Curve of the kimchi backend (defaults to the curve of the manifest, or to vesta).
+    #[clap(long)]
+    #[arg(value_enum)]
+    curve: Option<CurveOpt>,
+
+    /// Polynomial commitment scheme of the proof.
+    #[clap(long, value_enum, default_value = "ipa")]
+    commitment: CommitmentScheme,
+
     /// Prints the witness.
     #[clap(long)]
     debug: bool,
@@ -31,9 +49,31 @@ pub struct CmdProve {
 pub fn cmd_prove(args: CmdProve) -> miette::Result<()> {
     let curr_dir = args
         .path
+        .clone()
         .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
 
-    let (sources, prover_index, verifier_index) = build(&curr_dir, false, args.debug)?;
+    match curve_of_package(&curr_dir, args.curve)? {
+        CurveOpt::Vesta => prove::<Vesta>(args, curr_dir),
+        CurveOpt::Pallas => prove::<Pallas>(args, curr_dir),
+    }
+}
+
+/// Checks that the kimchi backend over the curve `C` supports the given commitment scheme.
+fn check_commitment<C: ProofSystem>(commitment: CommitmentScheme) -> miette::Result<()> {
+    if commitment != C::COMMITMENT {
+        miette::bail!(
+            "the backend `{}` only supports {} commitments, not {commitment} commitments",
+            C::BACKEND_ID,
+            C::COMMITMENT
+        );
+    }
+
+    Ok(())
+}
+
+fn prove<C: ProofSystem>(args: CmdProve, curr_dir: PathBuf) -> miette::Result<()> {
+    check_commitment::<C>(args.commitment)?;
+    let (sources, prover_index, verifier_index) = build::<C>(&curr_dir, false, args.debug)?;
 
     // parse inputs
     let public_inputs = parse_inputs(&args.public_inputs).unwrap();
@@ -45,24 +85,29 @@ pub fn cmd_prove(args: CmdProve) -> miette::Result<()> {
 
     // verify proof
     if args.debug {
-        verifier_index.verify(full_public_inputs, proof.clone())?;
+        verifier_index.verify(full_public_inputs.clone(), proof.clone())?;
     }
 
-    // serialize proof
+    // serialize proof, along with what it was created for
+    let proof_file = ProofFile::new(
+        C::BACKEND_ID,
+        args.commitment,
+        verifier_index.circuit_hash(),
+        &full_public_inputs,
+        rmp_serde::to_vec(&proof).unwrap(),
+    );
     let proof_path = args
         .proof_path
         .unwrap_or_else(|| curr_dir.join("proof.nope"));
-    std::fs::write(&proof_path, rmp_serde::to_vec(&proof).unwrap())
+    std::fs::write(&proof_path, proof_file.to_bytes())
         .into_diagnostic()
         .wrap_err(format!("could not write the proof to `{proof_path}`"))?;
 
     // notification
     if public_output.is_empty() {
-        println!(
-            "proof created at path `{proof_path}`. You can use `noname --verify` to verify it. Note that you will need to pass the same JSON-encoded public inputs as you did when creating the proof. (If you didn't use the `--public-inputs` flag, then you don't need to pass any public inputs.)",
-        );
+        println!("proof created at path `{proof_path}`. You can use `noname verify` to verify it, by passing the same public inputs.",);
     } else {
-        println!("proof created at path `{proof_path}`. Since running the proof produced a  public output `{public:?}`, you will need to also pass the expected public output to the verifier (who can run `noname verify --public-output '{public:?}'`).", public=public_output);
+        println!("proof created at path `{proof_path}`, with the public output `{public:?}`. You can use `noname verify` to verify it, by passing the same public inputs and this public output.", public=public_output);
     }
 
     //
@@ -72,38 +117,46 @@ pub fn cmd_prove(args: CmdProve) -> miette::Result<()> {
 #[derive(clap::Parser)]
 pub struct CmdVerify {
     /// Path to the directory to create.
-    #[clap(short, long, value_parser)]
+    #[clap(long, value_parser)]
     path: Option<PathBuf>,
 
+    /// Curve of the kimchi backend (defaults to the curve of the manifest, or to vesta).
+    #[clap(long)]
+    #[arg(value_enum)]
+    curve: Option<CurveOpt>,
+
+    /// Polynomial commitment scheme of the proof.
+    #[clap(long, value_enum, default_value = "ipa")]
+    commitment: CommitmentScheme,
+
     /// Path to the proof to verify. Defaults to `proof.nope`.
-    #[clap(short, long, value_parser)]
+    #[clap(long, value_parser)]
     proof_path: Option<PathBuf>,
 
-    /// JSON encoding of the public inputs. For example: `--public-inputs {"a": "1", "b": ["2", "3"]}`.
-    #[clap(short, long, value_parser)]
+    /// JSON encoding of the public inputs that the proof must have been created for. For example: `--public-inputs {"a": "1", "b": ["2", "3"]}`.
+    #[clap(short, long, value_parser, default_value = "{}")]
     public_inputs: String,
 
-    /// An optional expected public output, in JSON format.
-    #[clap(short, long, value_parser)]
+    /// JSON encoding of the public output that the proof must have been created for, required if main returns one.
+    #[clap(long, value_parser)]
     public_output: Option<String>,
 }
 
 pub fn cmd_verify(args: CmdVerify) -> miette::Result<()> {
     let curr_dir = args
         .path
+        .clone()
         .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
 
-    let (_sources, _prover_index, verifier_index) = build(&curr_dir, false, false)?;
-
-    // parse inputs
-    let mut public_inputs = parse_inputs(&args.public_inputs).unwrap();
-
-    if let Some(public_output) = &args.public_output {
-        let public_output = parse_inputs(public_output).unwrap();
-
-        // TODO: add it to the public input
-        todo!();
+    match curve_of_package(&curr_dir, args.curve)? {
+        CurveOpt::Vesta => verify::<Vesta>(args, curr_dir),
+        CurveOpt::Pallas => verify::<Pallas>(args, curr_dir),
     }
+}
+
+fn verify<C: ProofSystem>(args: CmdVerify, curr_dir: PathBuf) -> miette::Result<()> {
+    check_commitment::<C>(args.commitment)?;
+    let (_sources, prover_index, verifier_index) = build::<C>(&curr_dir, false, false)?;
 
     // get proof
     let proof_path = args
@@ -114,21 +167,117 @@ pub fn cmd_verify(args: CmdVerify) -> miette::Result<()> {
         miette::bail!("proof does not exist at path `{proof_path}`. Perhaps pass the correct path via the `--proof-path` flag?");
     }
 
-    let proof = rmp_serde::from_read(std::fs::File::open(&proof_path).unwrap())
+    let bytes = std::fs::read(&proof_path)
+        .into_diagnostic()
+        .wrap_err(format!("could not read the proof at `{proof_path}`"))?;
+    let proof_file = ProofFile::from_bytes(&bytes)
+        .wrap_err(format!("could not read the proof at `{proof_path}`"))?;
+    proof_file.check(
+        C::BACKEND_ID,
+        args.commitment,
+        &verifier_index.circuit_hash(),
+    )?;
+
+    // the public inputs stored in the proof file are only used to check that they match the expected ones
+    let public_inputs = parse_inputs(&args.public_inputs)?;
+    let public_output = args
+        .public_output
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()
+        .into_diagnostic()
+        .wrap_err("could not parse the public output")?;
+    let full_public_inputs = prover_index
+        .compiled_circuit()
+        .full_public_inputs(public_inputs, public_output)?;
+    if full_public_inputs != proof_file.public_inputs::<C::Field>()? {
+        miette::bail!(
+            "the proof was created with other public inputs (or public output) than the ones given"
+        );
+    }
+
+    let proof = rmp_serde::from_slice(&proof_file.proof)
         .into_diagnostic()
         .wrap_err(format!(
             "could not deserialize the given proof at `{proof_path}`"
         ))?;
 
     // verify proof
-    unimplemented!();
-    /*
-        verifier_index
-            .verify(full_public_inputs, proof)
-            .into_diagnostic()
-            .wrap_err("Failed to verify proof")?;
-    */
-    //
+    verifier_index.verify(full_public_inputs, proof)?;
+
+    match &args.public_output {
+        Some(public_output) => println!(
+            "proof at path `{proof_path}` verified, for the public inputs `{}` and the public output `{public_output}`",
+            args.public_inputs
+        ),
+        None => println!(
+            "proof at path `{proof_path}` verified, for the public inputs `{}`",
+            args.public_inputs
+        ),
+    }
+
+    Ok(())
+}
+
+/// The formats of the verification key.
+#[derive(Clone, Copy, ValueEnum)]
+enum VkFormat {
+    /// A compact binary format (MessagePack).
+    Binary,
+    Json,
+}
+
+#[derive(clap::Parser)]
+pub struct CmdVk {
+    /// Path to the package.
+    #[clap(short, long, value_parser)]
+    path: Option<PathBuf>,
+
+    /// Curve of the kimchi backend (defaults to the curve of the manifest, or to vesta).
+    #[clap(long)]
+    #[arg(value_enum)]
+    curve: Option<CurveOpt>,
+
+    /// Format of the verification key.
+    #[clap(long, value_enum, default_value = "binary")]
+    format: VkFormat,
+
+    /// Path to the resulting verification key. Defaults to `vk.bin` (or `vk.json` with `--format json`).
+    #[clap(long, value_parser)]
+    vk_path: Option<PathBuf>,
+}
+
+pub fn cmd_vk(args: CmdVk) -> miette::Result<()> {
+    let curr_dir = args
+        .path
+        .clone()
+        .unwrap_or_else(|| std::env::current_dir().unwrap().try_into().unwrap());
+
+    match curve_of_package(&curr_dir, args.curve)? {
+        CurveOpt::Vesta => write_vk::<Vesta>(args, curr_dir),
+        CurveOpt::Pallas => write_vk::<Pallas>(args, curr_dir),
+    }
+}
+
+fn write_vk<C: ProofSystem>(args: CmdVk, curr_dir: PathBuf) -> miette::Result<()> {
+    let (_sources, _prover_index, verifier_index) = build::<C>(&curr_dir, false, false)?;
+    let vk = verifier_index.into_verification_key();
+
+    let (bytes, default_path) = match args.format {
+        VkFormat::Binary => (vk.to_bytes(), "vk.bin"),
+        VkFormat::Json => (vk.to_json().into_bytes(), "vk.json"),
+    };
+    let vk_path = args.vk_path.unwrap_or_else(|| curr_dir.join(default_path));
+    std::fs::write(&vk_path, bytes)
+        .into_diagnostic()
+        .wrap_err(format!(
+            "could not write the verification key to `{vk_path}`"
+        ))?;
+
+    println!(
+        "verification key (version {}) written at path `{vk_path}`",
+        vk.version
+    );
 
     Ok(())
 }
@@ -0,0 +1,101 @@
+use camino::Utf8PathBuf as PathBuf;
+use kimchi::mina_curves::pasta::{Pallas, Vesta};
+
+use crate::backends::kimchi::{prover::ProofSystem, srs::SrsCache};
+
+use super::{manifest::CurveOpt, NONAME_DIRECTORY, SRS_DIRECTORY};
+
+#[derive(clap::Parser)]
+pub struct CmdSrs {
+    /// Curve of the SRS (the curve of the kimchi backend).
+    #[clap(long, default_value = "vesta")]
+    #[arg(value_enum)]
+    curve: CurveOpt,
+
+    #[command(subcommand)]
+    command: SrsCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum SrsCommand {
+    /// Fetch the SRS of a given size (from a URL or a file, or else by creating it) into the cache
+    Fetch(CmdFetch),
+
+    /// List the cached SRS
+    List,
+
+    /// Remove the cached SRS
+    Clear,
+}
+
+#[derive(clap::Parser)]
+struct CmdFetch {
+    /// Size of the SRS (a power of two, the size of the domain of the circuit).
+    #[clap(long)]
+    size: usize,
+
+    /// URL (with the `srs-download` feature) or path of the SRS (as cached by noname). The SRS is created locally if this isn't given.
+    #[clap(long)]
+    from: Option<String>,
+
+    /// Expected SHA-256 checksum of the SRS, in hexadecimal (required for URLs).
+    #[clap(long, requires = "from")]
+    sha256: Option<String>,
+}
+
+/// Returns the cache of the SRS of the kimchi backend over the curve `C`, under the noname directory.
+pub fn srs_cache<C: ProofSystem>() -> SrsCache<C> {
+    let home_dir: PathBuf = dirs::home_dir()
+        .expect("could not find home directory of current user")
+        .try_into()
+        .expect("invalid UTF8 path");
+
+    SrsCache::new(
+        home_dir
+            .join(NONAME_DIRECTORY)
+            .join(SRS_DIRECTORY)
+            .join(C::BACKEND_ID),
+    )
+}
+
+pub fn cmd_srs(args: CmdSrs) -> miette::Result<()> {
+    match args.curve {
+        CurveOpt::Vesta => run_srs_command::<Vesta>(args.command),
+        CurveOpt::Pallas => run_srs_command::<Pallas>(args.command),
+    }
+}
+
+fn run_srs_command<C: ProofSystem>(command: SrsCommand) -> miette::Result<()> {
+    let cache = srs_cache::<C>();
+
+    match command {
+        SrsCommand::Fetch(args) => {
+            let path = match &args.from {
+                Some(source) => cache.fetch(args.size, source, args.sha256.as_deref())?,
+                None => {
+                    if !args.size.is_power_of_two() {
+                        miette::bail!("the size of an SRS must be a power of two");
+                    }
+                    cache.get_or_create(args.size)?;
+                    cache.path(args.size)
+                }
+            };
+            println!("SRS of size {} cached at path `{path}`", args.size);
+        }
+        SrsCommand::List => {
+            let sizes = cache.list()?;
+            if sizes.is_empty() {
+                println!("no SRS cached in `{}`", cache.dir());
+            }
+            for size in sizes {
+                println!("{size}\t{}", cache.path(size));
+            }
+        }
+        SrsCommand::Clear => {
+            cache.clear()?;
+            println!("removed the SRS cached in `{}`", cache.dir());
+        }
+    }
+
+    Ok(())
+}
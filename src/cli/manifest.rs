@@ -15,6 +15,42 @@ pub struct Package {
     pub description: Option<String>,
     // no versioning at the moment
     pub dependencies: Option<Vec<String>>,
+    /// The field of the r1cs backend (can be overridden with `--field`).
+    pub field: Option<FieldOpt>,
+    /// The curve of the kimchi backend (can be overridden with `--curve`).
+    pub curve: Option<CurveOpt>,
+}
+
+/// The prime fields that the r1cs backend can be parameterized with.
+#[derive(Clone, Copy, Default, clap::ValueEnum, serde::Deserialize)]
+pub enum FieldOpt {
+    /// The scalar field of bn254.
+    #[default]
+    #[serde(rename = "bn254")]
+    Bn254,
+
+    /// The scalar field of bls12-381.
+    #[value(name = "bls12-381")]
+    #[serde(rename = "bls12-381")]
+    Bls12_381,
+
+    /// The base field of pallas (which is the scalar field of vesta).
+    #[serde(rename = "pallas")]
+    Pallas,
+}
+
+/// The curves of the Pasta cycle that the kimchi backend can be instantiated with
+/// (the circuit is over the scalar field of the curve).
+#[derive(Clone, Copy, Default, clap::ValueEnum, serde::Deserialize)]
+pub enum CurveOpt {
+    /// Vesta, whose scalar field is the base field of pallas.
+    #[default]
+    #[serde(rename = "vesta")]
+    Vesta,
+
+    /// Pallas, whose scalar field is the base field of vesta.
+    #[serde(rename = "pallas")]
+    Pallas,
 }
 
 impl Manifest {
@@ -1,17 +1,32 @@
 pub mod cmd_build_and_check;
+#[cfg(feature = "groth16")]
+pub mod cmd_ceremony;
+#[cfg(feature = "groth16")]
+pub mod cmd_groth16;
+pub mod cmd_import_circom;
 pub mod cmd_new_and_init;
 pub mod cmd_prove_and_verify;
+pub mod cmd_srs;
 pub mod manifest;
 pub mod packages;
 
 pub use cmd_build_and_check::{
     cmd_build, cmd_check, cmd_run, cmd_test, CmdBuild, CmdCheck, CmdRun, CmdTest,
 };
+#[cfg(feature = "groth16")]
+pub use cmd_ceremony::{cmd_ceremony, CmdCeremony};
+#[cfg(feature = "groth16")]
+pub use cmd_groth16::{cmd_groth16, CmdGroth16};
+pub use cmd_import_circom::{cmd_import_circom, CmdImportCircom};
 pub use cmd_new_and_init::{cmd_init, cmd_new, CmdInit, CmdNew};
-pub use cmd_prove_and_verify::{cmd_prove, cmd_verify, CmdProve, CmdVerify};
+pub use cmd_prove_and_verify::{cmd_prove, cmd_verify, cmd_vk, CmdProve, CmdVerify, CmdVk};
+pub use cmd_srs::{cmd_srs, CmdSrs};
 
 /// The directory under the user home directory containing all noname-related files.
 pub const NONAME_DIRECTORY: &str = ".noname";
 
 /// The directory under [NONAME_DIRECTORY] containing all package-related files.
 pub const PACKAGE_DIRECTORY: &str = "packages";
+
+/// The directory under [NONAME_DIRECTORY] containing the cached SRS, per backend.
+pub const SRS_DIRECTORY: &str = "srs";
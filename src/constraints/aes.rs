@@ -0,0 +1,486 @@
+//! The AES-128 block cipher (FIPS 197), and its CTR and GCM modes of operation (NIST SP 800-38A and 800-38D).
+//!
+//! Bytes are represented as 8 boolean cells in little-endian order (see [super::words]).
+//! The S-box is a multiplexer over its table (see [field::constant_lookup]):
+//! the kimchi backend doesn't support lookup tables yet, so this costs about 130 constraints per byte.
+//! The linear layers (`MixColumns` with `AddRoundKey`, and the reduction of GHASH) XOR many bits at once,
+//! which only costs the decomposition of their sum (see [parity]).
+
+use ark_ff::Zero;
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    boolean, field,
+    words::{add, constant, xor, Word},
+};
+
+/// The size of a block, in bytes.
+const BLOCK_LEN: usize = 16;
+
+/// The number of rounds of AES-128.
+const ROUNDS: usize = 10;
+
+/// The round constants of the key expansion.
+const RCON: [u64; ROUNDS] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// The S-box.
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+/// A byte, as 8 booleans in little-endian order.
+type Byte<B> = Word<B>;
+
+/// Encrypts a block of 16 bytes with a key of 16 bytes.
+pub fn encrypt_block<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    key: &[ConstOrCell<B::Field, B::Var>],
+    block: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    assert_eq!(block.len(), BLOCK_LEN);
+    let round_keys = expand_key(compiler, key, span);
+    let block = to_bytes(compiler, block, span);
+
+    let res = cipher(compiler, &round_keys, &block, span);
+    from_bytes(compiler, &res, span)
+}
+
+/// Encrypts (or decrypts) bytes in CTR mode, where `iv` is the first counter block of 16 bytes.
+/// Like in GCM, the next counter blocks increment the last 4 bytes (in big-endian order) modulo `2^32`.
+pub fn ctr<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    key: &[ConstOrCell<B::Field, B::Var>],
+    iv: &[ConstOrCell<B::Field, B::Var>],
+    input: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    assert_eq!(iv.len(), BLOCK_LEN);
+    let round_keys = expand_key(compiler, key, span);
+    let iv = to_bytes(compiler, iv, span);
+    let input = to_bytes(compiler, input, span);
+
+    let res = apply_keystream(compiler, &round_keys, &iv, &input, span);
+    from_bytes(compiler, &res, span)
+}
+
+/// Encrypts bytes in GCM mode with an IV of 12 bytes, and returns the ciphertext (see [gcm_tag] for the tag).
+pub fn gcm_encrypt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    key: &[ConstOrCell<B::Field, B::Var>],
+    iv: &[ConstOrCell<B::Field, B::Var>],
+    plaintext: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let round_keys = expand_key(compiler, key, span);
+    let j0 = j0(compiler, iv, span);
+    let plaintext = to_bytes(compiler, plaintext, span);
+
+    let counter_block = inc32(compiler, &j0, 1, span);
+    let res = apply_keystream(compiler, &round_keys, &counter_block, &plaintext, span);
+    from_bytes(compiler, &res, span)
+}
+
+/// Returns the authentication tag of GCM (of 16 bytes) for additional authenticated data and a ciphertext.
+pub fn gcm_tag<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    key: &[ConstOrCell<B::Field, B::Var>],
+    iv: &[ConstOrCell<B::Field, B::Var>],
+    aad: &[ConstOrCell<B::Field, B::Var>],
+    ciphertext: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let round_keys = expand_key(compiler, key, span);
+    let j0 = j0(compiler, iv, span);
+    let aad = to_bytes(compiler, aad, span);
+    let ciphertext = to_bytes(compiler, ciphertext, span);
+
+    let res = compute_tag(compiler, &round_keys, &j0, &aad, &ciphertext, span);
+    from_bytes(compiler, &res, span)
+}
+
+/// Decrypts bytes in GCM mode with an IV of 12 bytes, after checking the authentication tag.
+pub fn gcm_decrypt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    key: &[ConstOrCell<B::Field, B::Var>],
+    iv: &[ConstOrCell<B::Field, B::Var>],
+    aad: &[ConstOrCell<B::Field, B::Var>],
+    ciphertext: &[ConstOrCell<B::Field, B::Var>],
+    tag: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let round_keys = expand_key(compiler, key, span);
+    let j0 = j0(compiler, iv, span);
+    let aad = to_bytes(compiler, aad, span);
+    let ciphertext = to_bytes(compiler, ciphertext, span);
+
+    let expected = compute_tag(compiler, &round_keys, &j0, &aad, &ciphertext, span);
+    let expected = from_bytes(compiler, &expected, span);
+    for (expected, tag) in expected.cvars.iter().zip(tag) {
+        field::assert_eq_cells(compiler, expected, tag, span)?;
+    }
+
+    let counter_block = inc32(compiler, &j0, 1, span);
+    let res = apply_keystream(compiler, &round_keys, &counter_block, &ciphertext, span);
+    Ok(from_bytes(compiler, &res, span))
+}
+
+/// Decomposes bytes into bits, which constrains them to fit in 8 bits.
+fn to_bytes<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bytes: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<Byte<B>> {
+    bytes
+        .iter()
+        .map(|byte| field::to_bits(compiler, byte, 8, span).cvars)
+        .collect()
+}
+
+/// Packs bytes given as bits.
+fn from_bytes<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bytes: &[Byte<B>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let bytes = bytes
+        .iter()
+        .map(|byte| field::from_bits(compiler, byte, span)[0].clone())
+        .collect();
+    Var::new(bytes, span)
+}
+
+fn xor_bytes<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[Byte<B>],
+    rhs: &[Byte<B>],
+    span: Span,
+) -> Vec<Byte<B>> {
+    lhs.iter()
+        .zip(rhs)
+        .map(|(lhs, rhs)| xor(compiler, lhs, rhs, span))
+        .collect()
+}
+
+/// Returns the XOR of bits, as the least significant bit of their sum.
+/// This only costs the decomposition of the sum, instead of a multiplication per XOR.
+fn parity<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    match bits {
+        [] => ConstOrCell::Const(B::Field::zero()),
+        [bit] => bit.clone(),
+        [lhs, rhs] => boolean::xor(compiler, lhs, rhs, span)[0].clone(),
+        _ => {
+            let mut sum = ConstOrCell::Const(B::Field::zero());
+            for bit in bits {
+                sum = field::add(compiler, &sum, bit, span)[0].clone();
+            }
+
+            let num_bits = (usize::BITS - bits.len().leading_zeros()) as usize;
+            field::to_bits(compiler, &sum, num_bits, span)[0].clone()
+        }
+    }
+}
+
+/// Expands a key of 16 bytes into the 11 round keys.
+fn expand_key<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    key: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<Vec<Byte<B>>> {
+    assert_eq!(key.len(), BLOCK_LEN);
+    let key = to_bytes(compiler, key, span);
+    let mut words: Vec<Vec<Byte<B>>> = key.chunks(4).map(<[_]>::to_vec).collect();
+
+    for ii in 4..4 * (ROUNDS + 1) {
+        let mut temp = words[ii - 1].clone();
+
+        // SubWord(RotWord(temp)) xor Rcon
+        if ii % 4 == 0 {
+            temp.rotate_left(1);
+            temp = temp
+                .iter()
+                .map(|byte| sub_byte(compiler, byte, span))
+                .collect();
+            temp[0] = xor(
+                compiler,
+                &temp[0],
+                &constant::<B>(RCON[ii / 4 - 1], 8),
+                span,
+            );
+        }
+
+        let word = xor_bytes(compiler, &words[ii - 4], &temp, span);
+        words.push(word);
+    }
+
+    words.chunks(4).map(<[_]>::concat).collect()
+}
+
+/// Encrypts a block with the round keys.
+fn cipher<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    round_keys: &[Vec<Byte<B>>],
+    block: &[Byte<B>],
+    span: Span,
+) -> Vec<Byte<B>> {
+    let mut state = xor_bytes(compiler, block, &round_keys[0], span);
+
+    for (round, round_key) in round_keys.iter().enumerate().skip(1) {
+        let substituted: Vec<_> = state
+            .iter()
+            .map(|byte| sub_byte(compiler, byte, span))
+            .collect();
+        let shifted = shift_rows::<B>(&substituted);
+
+        // the last round doesn't mix the columns
+        state = if round == ROUNDS {
+            xor_bytes(compiler, &shifted, round_key, span)
+        } else {
+            mix_columns(compiler, &shifted, round_key, span)
+        };
+    }
+
+    state
+}
+
+/// Applies the S-box to a byte.
+fn sub_byte<B: Backend>(compiler: &mut CircuitWriter<B>, byte: &Byte<B>, span: Span) -> Byte<B> {
+    let table: Vec<_> = SBOX.iter().map(|val| B::Field::from(*val as u64)).collect();
+    let res = field::constant_lookup(compiler, &table, byte, span);
+    field::to_bits(compiler, &res, 8, span).cvars
+}
+
+/// Shifts the row `r` of the state (made of the bytes `r`, `r + 4`, `r + 8`, and `r + 12`) to the left by `r` positions.
+fn shift_rows<B: Backend>(state: &[Byte<B>]) -> Vec<Byte<B>> {
+    (0..BLOCK_LEN)
+        .map(|ii| {
+            let (row, col) = (ii % 4, ii / 4);
+            state[row + 4 * ((col + row) % 4)].clone()
+        })
+        .collect()
+}
+
+/// Mixes the columns of the state, and adds the round key:
+/// every bit of the result is the XOR of bits of the state and of the round key.
+fn mix_columns<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    state: &[Byte<B>],
+    round_key: &[Byte<B>],
+    span: Span,
+) -> Vec<Byte<B>> {
+    let mut res = Vec::with_capacity(BLOCK_LEN);
+
+    for (col, column) in state.chunks(4).enumerate() {
+        for row in 0..4 {
+            // 2 a_r + 3 a_(r+1) + a_(r+2) + a_(r+3), where 3 a = 2 a + a
+            let mut terms = xtime::<B>(&column[row]);
+            let doubled = xtime::<B>(&column[(row + 1) % 4]);
+
+            for (ii, terms) in terms.iter_mut().enumerate() {
+                terms.extend(doubled[ii].iter().cloned());
+                for offset in 1..4 {
+                    terms.push(column[(row + offset) % 4][ii].clone());
+                }
+                terms.push(round_key[4 * col + row][ii].clone());
+            }
+
+            let byte = terms
+                .iter()
+                .map(|terms| parity(compiler, terms, span))
+                .collect();
+            res.push(byte);
+        }
+    }
+
+    res
+}
+
+/// Multiplies a byte by `x` in GF(2^8) (modulo `x^8 + x^4 + x^3 + x + 1`),
+/// and returns, for each bit of the result, the bits to XOR.
+fn xtime<B: Backend>(byte: &Byte<B>) -> Vec<Vec<ConstOrCell<B::Field, B::Var>>> {
+    let high = &byte[7];
+
+    (0..8)
+        .map(|ii| {
+            let mut terms = if ii == 0 {
+                vec![]
+            } else {
+                vec![byte[ii - 1].clone()]
+            };
+            if (0x1b >> ii) & 1 == 1 {
+                terms.push(high.clone());
+            }
+            terms
+        })
+        .collect()
+}
+
+/// Increments the last 4 bytes of a counter block (in big-endian order) modulo `2^32`.
+fn inc32<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    block: &[Byte<B>],
+    increment: usize,
+    span: Span,
+) -> Vec<Byte<B>> {
+    if increment == 0 {
+        return block.to_vec();
+    }
+
+    // the counter, as a word in little-endian order
+    let counter: Word<B> = block[12..].iter().rev().flatten().cloned().collect();
+    let counter = add(
+        compiler,
+        &[&counter, &constant::<B>(increment as u64, 32)],
+        span,
+    );
+
+    let mut res = block[..12].to_vec();
+    res.extend(counter.chunks(8).rev().map(<[_]>::to_vec));
+    res
+}
+
+/// XORs bytes with the keystream of the counter blocks, starting from the given one.
+fn apply_keystream<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    round_keys: &[Vec<Byte<B>>],
+    counter_block: &[Byte<B>],
+    input: &[Byte<B>],
+    span: Span,
+) -> Vec<Byte<B>> {
+    input
+        .chunks(BLOCK_LEN)
+        .enumerate()
+        .flat_map(|(ii, chunk)| {
+            let counter_block = inc32(compiler, counter_block, ii, span);
+            let keystream = cipher(compiler, round_keys, &counter_block, span);
+            xor_bytes(compiler, chunk, &keystream, span)
+        })
+        .collect()
+}
+
+/// Returns the first counter block of GCM, `IV || 1`, for an IV of 12 bytes.
+fn j0<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    iv: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<Byte<B>> {
+    assert_eq!(iv.len(), 12);
+    let mut block = to_bytes(compiler, iv, span);
+    block.extend([0, 0, 0, 1].map(|byte| constant::<B>(byte, 8)));
+    block
+}
+
+/// Computes the authentication tag of GCM: `E(K, J0) xor GHASH(H, A, C)`, where `H = E(K, 0)`.
+fn compute_tag<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    round_keys: &[Vec<Byte<B>>],
+    j0: &[Byte<B>],
+    aad: &[Byte<B>],
+    ciphertext: &[Byte<B>],
+    span: Span,
+) -> Vec<Byte<B>> {
+    let zero = vec![constant::<B>(0, 8); BLOCK_LEN];
+    let hash_key = cipher(compiler, round_keys, &zero, span);
+    let hash_key = to_poly::<B>(&hash_key);
+
+    // the AAD and the ciphertext are padded with zeros to full blocks,
+    // and followed by a block with their lengths in bits
+    let mut blocks = vec![];
+    for bytes in [aad, ciphertext] {
+        for chunk in bytes.chunks(BLOCK_LEN) {
+            let mut block = chunk.to_vec();
+            block.resize(BLOCK_LEN, constant::<B>(0, 8));
+            blocks.push(block);
+        }
+    }
+    let lengths = [aad.len(), ciphertext.len()]
+        .iter()
+        .flat_map(|len| (8 * *len as u64).to_be_bytes())
+        .map(|byte| constant::<B>(byte as u64, 8))
+        .collect();
+    blocks.push(lengths);
+
+    // GHASH: X_i = (X_(i-1) xor B_i) H
+    let mut acc = vec![ConstOrCell::Const(B::Field::zero()); 8 * BLOCK_LEN];
+    for block in blocks {
+        let sum = xor(compiler, &acc, &to_poly::<B>(&block), span);
+        acc = gf128_mul(compiler, &sum, &hash_key, span);
+    }
+
+    let mask = cipher(compiler, round_keys, j0, span);
+    xor_bytes(compiler, &from_poly::<B>(&acc), &mask, span)
+}
+
+/// Returns the coefficients of the element of GF(2^128) represented by a block:
+/// GCM maps the most significant bit of the first byte to the constant coefficient.
+fn to_poly<B: Backend>(block: &[Byte<B>]) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    block
+        .iter()
+        .flat_map(|byte| byte.iter().rev().cloned())
+        .collect()
+}
+
+/// The inverse of [to_poly].
+fn from_poly<B: Backend>(poly: &[ConstOrCell<B::Field, B::Var>]) -> Vec<Byte<B>> {
+    poly.chunks(8)
+        .map(|bits| bits.iter().rev().cloned().collect())
+        .collect()
+}
+
+/// Multiplies two elements of GF(2^128), as polynomials modulo `x^128 + x^7 + x^2 + x + 1`.
+/// Each coefficient of the result is the XOR of products of coefficients of the operands.
+fn gf128_mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[ConstOrCell<B::Field, B::Var>],
+    rhs: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    let degree = lhs.len();
+    let mut terms = vec![vec![]; 2 * degree - 1];
+
+    for (ii, lhs) in lhs.iter().enumerate() {
+        for (jj, rhs) in rhs.iter().enumerate() {
+            let prod = boolean::and(compiler, lhs, rhs, span);
+            terms[ii + jj].push(prod[0].clone());
+        }
+    }
+
+    // x^k = x^(k - 128) (x^7 + x^2 + x + 1), starting from the highest degree
+    for kk in (degree..2 * degree - 1).rev() {
+        let high = std::mem::take(&mut terms[kk]);
+        for shift in [0, 1, 2, 7] {
+            terms[kk - degree + shift].extend(high.iter().cloned());
+        }
+    }
+
+    terms[..degree]
+        .iter()
+        .map(|terms| parity(compiler, terms, span))
+        .collect()
+}
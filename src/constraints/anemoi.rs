@@ -0,0 +1,194 @@
+//! The Anemoi permutation (https://eprint.iacr.org/2022/840), with a state of 2 field elements `(x, y)`,
+//! and its Jive compression mode, which hashes 2 field elements into 1.
+//!
+//! Its parameters are derived for the field of the backend:
+//!
+//! - the S-box is the open Flystel with `Q(y) = g y^2` and `E(x) = x^alpha`,
+//!   with the smallest `alpha` that is a permutation of the field,
+//! - the linear layer is the matrix `[[1, g], [g, g^2 + 1]]`,
+//! - the round constants are derived from the digits of pi, as in the paper,
+//!
+//! where `g` is the smallest quadratic non-residue of the field.
+//! Note that the reference implementation uses a generator of the multiplicative group instead
+//! (which is also a non-residue, but not always the smallest one),
+//! so the digests don't match other implementations of Anemoi.
+//!
+//! The Flystel is cheap to verify in the circuit: `x^(1/alpha)` is computed out of circuit,
+//! and constrained with `x^alpha` (see [field::root]).
+
+use ark_ff::{Field, One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    var::{ConstOrCell, Var},
+};
+
+use super::field;
+
+/// The size of the state.
+pub const WIDTH: usize = 2;
+
+/// The first 100 decimals of pi, used to derive the round constants.
+const PI_0: &str = "1415926535897932384626433832795028841971693993751058209749445923078164062862089986280348253421170679";
+
+/// The next 100 decimals of pi.
+const PI_1: &str = "8214808651328230664709384460955058223172535940812848111745028410270193852110555964462294895493038196";
+
+/// The parameters of Anemoi for a given field.
+struct Params<F> {
+    /// The exponent of the S-box.
+    alpha: BigUint,
+
+    /// The exponent of the inverse S-box.
+    alpha_inv: BigUint,
+
+    /// The multiplier of the quadratic functions and of the linear layer.
+    g: F,
+
+    /// The constant added by the second quadratic function, `1 / g`.
+    delta: F,
+
+    /// The round constants `(c, d)`, added to `x` and `y` at the start of each round.
+    round_constants: Vec<(F, F)>,
+}
+
+impl<F: BackendField> Params<F> {
+    /// Derives the parameters for the field `F`.
+    fn generate() -> Self {
+        let alpha = field::smallest_permutation_exponent::<F>();
+        let alpha_inv = field::inverse_exponent::<F>(alpha);
+
+        let g = field::smallest_non_residue::<F>();
+        let delta = g.inverse().expect("a non-residue is not zero");
+
+        // c_r = g pi_0^r + (pi_0^r + pi_1)^alpha
+        // d_r = g pi_1 + (pi_0^r + pi_1)^alpha + delta
+        let pi_0 = Self::from_decimal(PI_0);
+        let pi_1 = Self::from_decimal(PI_1);
+        let mut pi_0_r = F::one();
+        let round_constants = (0..Self::rounds(alpha))
+            .map(|_| {
+                let sum = (pi_0_r + pi_1).pow([alpha]);
+                let constants = (g * pi_0_r + sum, g * pi_1 + sum + delta);
+                pi_0_r *= pi_0;
+                constants
+            })
+            .collect();
+
+        Self {
+            alpha: BigUint::from(alpha),
+            alpha_inv,
+            g,
+            delta,
+            round_constants,
+        }
+    }
+
+    /// Returns the number of rounds for 128 bits of security, with a state of 2 elements (see the paper).
+    fn rounds(alpha: u64) -> usize {
+        match alpha {
+            3 | 5 => 21,
+            7 => 20,
+            _ => 19,
+        }
+    }
+
+    /// Returns a decimal integer reduced modulo the field.
+    fn from_decimal(decimal: &str) -> F {
+        let modulus_minus_one: BigUint = (-F::one()).into();
+        let modulus = modulus_minus_one + 1u8;
+
+        let value: BigUint = decimal.parse().unwrap();
+        F::try_from(value % modulus).unwrap_or_else(|_| unreachable!())
+    }
+}
+
+/// Applies the Anemoi permutation to a state of [WIDTH] field elements.
+pub fn permutation<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    state: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    assert_eq!(state.len(), WIDTH);
+
+    let params = Params::<B::Field>::generate();
+    let mut x = state[0].clone();
+    let mut y = state[1].clone();
+
+    for (c, d) in &params.round_constants {
+        x = field::add(compiler, &x, &ConstOrCell::Const(*c), span)[0].clone();
+        y = field::add(compiler, &y, &ConstOrCell::Const(*d), span)[0].clone();
+        (x, y) = linear_layer(compiler, &params, &x, &y, span);
+        (x, y) = flystel(compiler, &params, &x, &y, span);
+    }
+
+    // the last linear layer
+    (x, y) = linear_layer(compiler, &params, &x, &y, span);
+
+    Var::new(vec![x, y], span)
+}
+
+/// Compresses two field elements into one with the Jive mode:
+/// the sum of the input and of its image by the permutation.
+pub fn jive<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    input: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let output = permutation(compiler, input, span);
+
+    let mut acc = ConstOrCell::Const(B::Field::zero());
+    for elem in input.iter().chain(&output.cvars) {
+        acc = field::add(compiler, &acc, elem, span)[0].clone();
+    }
+
+    Var::new_cvar(acc, span)
+}
+
+/// Returns `(x + g y, g x + (g^2 + 1) y)`.
+fn linear_layer<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params<B::Field>,
+    x: &ConstOrCell<B::Field, B::Var>,
+    y: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> (ConstOrCell<B::Field, B::Var>, ConstOrCell<B::Field, B::Var>) {
+    let g = ConstOrCell::Const(params.g);
+
+    // x += g y, then y += g x
+    let gy = field::mul(compiler, y, &g, span);
+    let x = field::add(compiler, x, &gy[0], span)[0].clone();
+    let gx = field::mul(compiler, &x, &g, span);
+    let y = field::add(compiler, y, &gx[0], span)[0].clone();
+
+    (x, y)
+}
+
+/// Applies the open Flystel:
+/// `x -= g y^2`, then `y -= x^(1/alpha)`, then `x += g y^2 + delta`.
+fn flystel<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params<B::Field>,
+    x: &ConstOrCell<B::Field, B::Var>,
+    y: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> (ConstOrCell<B::Field, B::Var>, ConstOrCell<B::Field, B::Var>) {
+    let g = ConstOrCell::Const(params.g);
+
+    let y2 = field::mul(compiler, y, y, span);
+    let gy2 = field::mul(compiler, &y2[0], &g, span);
+    let x = field::sub(compiler, x, &gy2[0], span);
+
+    let root = field::root(compiler, &x[0], &params.alpha, &params.alpha_inv, span);
+    let y = field::sub(compiler, y, &root, span)[0].clone();
+
+    let y2 = field::mul(compiler, &y, &y, span);
+    let gy2 = field::mul(compiler, &y2[0], &g, span);
+    let x = field::add(compiler, &x[0], &gy2[0], span);
+    let x = field::add(compiler, &x[0], &ConstOrCell::Const(params.delta), span)[0].clone();
+
+    (x, y)
+}
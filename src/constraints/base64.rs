@@ -0,0 +1,111 @@
+//! Decoding of base64url (RFC 4648, section 5), without padding, as used by JWTs.
+//!
+//! Each character is decoded into 6 bits out of circuit,
+//! which are constrained to select the character in the alphabet (with a multiplexer),
+//! so that any character outside of the alphabet makes the circuit unsatisfiable.
+//! The encoding must also be canonical: the unused bits of the last character must be zero.
+
+use std::sync::Arc;
+
+use ark_ff::Zero;
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::{ConstOrCell, Value, Var},
+};
+
+use super::field;
+
+/// The base64url alphabet.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Returns the number of bytes encoded by a number of characters, if it is a valid length.
+pub fn decoded_len(encoded_len: usize) -> Option<usize> {
+    // a single character can't encode a full byte
+    if encoded_len % 4 == 1 {
+        None
+    } else {
+        Some(encoded_len * 6 / 8)
+    }
+}
+
+/// Decodes characters of base64url, given as bytes.
+/// The number of characters must be a valid length (see [decoded_len]).
+pub fn decode<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    input: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let decoded_len = decoded_len(input.len()).expect("invalid length of base64url");
+
+    // the bits of the characters, starting from the most significant one
+    let mut bits = Vec::with_capacity(6 * input.len());
+    for char in input {
+        let mut char_bits = decode_char(compiler, char, span)?;
+        char_bits.reverse();
+        bits.extend(char_bits);
+    }
+
+    // the bits that don't fill a byte must be zero
+    let zero = ConstOrCell::Const(B::Field::zero());
+    for bit in &bits[8 * decoded_len..] {
+        field::assert_eq_cells(compiler, bit, &zero, span)?;
+    }
+
+    let bytes = bits[..8 * decoded_len]
+        .chunks(8)
+        .map(|byte| {
+            let le_bits: Vec<_> = byte.iter().rev().cloned().collect();
+            field::from_bits(compiler, &le_bits, span)[0].clone()
+        })
+        .collect();
+
+    Ok(Var::new(bytes, span))
+}
+
+/// Returns the 6 bits (in little-endian order) of the index of a character in the alphabet.
+fn decode_char<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    char: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Vec<ConstOrCell<B::Field, B::Var>>> {
+    // the index is computed out of circuit
+    let index = match char {
+        ConstOrCell::Const(cst) => ConstOrCell::Const(index_of(*cst)),
+        ConstOrCell::Cell(_) => {
+            let var = compiler.backend.new_internal_var(
+                Value::Compute(
+                    vec![char.clone()],
+                    Arc::new(|values: &[B::Field]| index_of(values[0])),
+                ),
+                span,
+            );
+            ConstOrCell::Cell(var)
+        }
+    };
+    let bits = field::to_bits(compiler, &index, 6, span).cvars;
+
+    // the character must be the entry of the alphabet at that index
+    let table: Vec<_> = ALPHABET
+        .iter()
+        .map(|alpha| B::Field::from(*alpha as u64))
+        .collect();
+    let expected = field::constant_lookup(compiler, &table, &bits, span);
+    field::assert_eq_cells(compiler, &expected, char, span)?;
+
+    Ok(bits)
+}
+
+/// Returns the index of a character in the alphabet (or zero if it isn't in the alphabet).
+fn index_of<F: BackendField>(char: F) -> F {
+    let char: BigUint = char.into();
+    let index = ALPHABET
+        .iter()
+        .position(|alpha| BigUint::from(*alpha) == char)
+        .unwrap_or(0);
+    F::from(index as u64)
+}
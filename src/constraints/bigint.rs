@@ -0,0 +1,351 @@
+//! Non-native arithmetic on big integers,
+//! represented as limbs of [LIMB_BITS] bits (in little-endian order).
+//! Big integers have [NUM_LIMBS] limbs by default, but the arithmetic supports up to [MAX_LIMBS] limbs
+//! (for example, 32 limbs for the 2048-bit integers of RSA).
+
+use std::sync::Arc;
+
+use ark_ff::{Field, One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::{ComputeManyFn, ConstOrCell, Value, Var},
+};
+
+use super::{boolean, field};
+
+/// The number of bits in a limb.
+pub const LIMB_BITS: usize = 64;
+
+/// The number of limbs in a big integer.
+pub const NUM_LIMBS: usize = 4;
+
+/// The maximum number of limbs in a big integer.
+pub const MAX_LIMBS: usize = 32;
+
+/// The carries of the limb-wise equations are signed, and fit in that many bits.
+/// (A limb of the equation is a sum of at most `2 * MAX_LIMBS` products of two limbs,
+/// so it fits in `2 * LIMB_BITS + 7` bits, and a carry in about `LIMB_BITS + 8` bits, including the sign.)
+const CARRY_BITS: usize = LIMB_BITS + 8;
+
+/// Computes `(lhs * rhs + addend) mod modulus`, where the big integers have the same number of limbs.
+///
+/// The limbs of `lhs`, `rhs`, and `addend` are constrained to fit in [LIMB_BITS] bits,
+/// and the result is constrained to be reduced (smaller than the modulus).
+/// Note that the result can only be computed if `lhs * rhs + addend < modulus * 2^(num_limbs * LIMB_BITS)`,
+/// which is the case if the inputs are reduced.
+pub fn mul_add_mod<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[ConstOrCell<B::Field, B::Var>],
+    rhs: &[ConstOrCell<B::Field, B::Var>],
+    addend: &[ConstOrCell<B::Field, B::Var>],
+    modulus: &BigUint,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let num_limbs = lhs.len();
+    assert!(!modulus.is_zero() && modulus.bits() as usize <= num_limbs * LIMB_BITS);
+
+    let modulus = constant_with_limbs::<B>(modulus, num_limbs);
+    mul_add_mod_var(compiler, lhs, rhs, addend, &modulus, span)
+}
+
+/// Like [mul_add_mod], but with a modulus given as limbs, which can be variables (for example, an RSA public key).
+/// The limbs of the modulus are also constrained to fit in [LIMB_BITS] bits,
+/// and a zero modulus makes the circuit unsatisfiable.
+pub fn mul_add_mod_var<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[ConstOrCell<B::Field, B::Var>],
+    rhs: &[ConstOrCell<B::Field, B::Var>],
+    addend: &[ConstOrCell<B::Field, B::Var>],
+    modulus: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let num_limbs = lhs.len();
+    assert!(num_limbs <= MAX_LIMBS);
+    assert_eq!(rhs.len(), num_limbs);
+    assert_eq!(addend.len(), num_limbs);
+    assert_eq!(modulus.len(), num_limbs);
+
+    // the limbs must be small enough so that the limb-wise equations can't wrap around the field modulus
+    for limb in lhs.iter().chain(rhs).chain(addend).chain(modulus) {
+        field::range_check(compiler, limb, LIMB_BITS, span)?;
+    }
+
+    // the quotient and the remainder are computed out of circuit,
+    // once for all their limbs (the limbs of the quotient come first)
+    let inputs: Vec<_> = lhs
+        .iter()
+        .chain(rhs)
+        .chain(addend)
+        .chain(modulus)
+        .cloned()
+        .collect();
+    let quotient_and_remainder: Arc<ComputeManyFn<B::Field>> =
+        Arc::new(move |values: &[B::Field]| {
+            let [lhs, rhs, addend, modulus] =
+                [0, 1, 2, 3].map(|jj| from_limbs(&values[jj * num_limbs..][..num_limbs]));
+
+            // the constraints can't be satisfied anyway
+            if modulus.is_zero() {
+                return vec![B::Field::zero(); 2 * num_limbs];
+            }
+
+            let res = lhs * rhs + addend;
+            let (quotient, remainder) = (&res / &modulus, res % &modulus);
+            (0..num_limbs)
+                .map(|ii| limb(&quotient, ii))
+                .chain((0..num_limbs).map(|ii| limb(&remainder, ii)))
+                .collect()
+        });
+    let mut quotient = Vec::with_capacity(num_limbs);
+    let mut remainder = Vec::with_capacity(num_limbs);
+
+    for ii in 0..num_limbs {
+        for (nth, limbs) in [(ii, &mut quotient), (num_limbs + ii, &mut remainder)] {
+            let var = compiler.backend.new_internal_var(
+                Value::ComputeNth(inputs.clone(), quotient_and_remainder.clone(), nth),
+                span,
+            );
+            let var = ConstOrCell::Cell(var);
+
+            field::range_check(compiler, &var, LIMB_BITS, span)?;
+            limbs.push(var);
+        }
+    }
+
+    // We constrain `lhs * rhs + addend - quotient * modulus - remainder = 0` limb by limb,
+    // starting from the least significant limb and propagating the carries:
+    // `eq_k + carry_{k-1} = carry_k * 2^LIMB_BITS`,
+    // where `eq_k` is the k-th limb of the equation.
+    let two = B::Field::from(2u64);
+    let base = two.pow([LIMB_BITS as u64]);
+    let carry_offset = two.pow([CARRY_BITS as u64 - 1]);
+
+    let mut carry = ConstOrCell::Const(B::Field::zero());
+    for kk in 0..(2 * num_limbs - 1) {
+        let mut acc = carry;
+
+        for ii in 0..num_limbs {
+            if kk < ii || kk - ii >= num_limbs {
+                continue;
+            }
+            let jj = kk - ii;
+
+            // acc += lhs_i * rhs_j
+            let prod = field::mul(compiler, &lhs[ii], &rhs[jj], span);
+            acc = field::add(compiler, &acc, &prod[0], span)[0].clone();
+
+            // acc -= quotient_i * modulus_j
+            let prod = field::mul(compiler, &quotient[ii], &modulus[jj], span);
+            acc = field::sub(compiler, &acc, &prod[0], span)[0].clone();
+        }
+
+        if kk < num_limbs {
+            // acc += addend_k - remainder_k
+            acc = field::add(compiler, &acc, &addend[kk], span)[0].clone();
+            acc = field::sub(compiler, &acc, &remainder[kk], span)[0].clone();
+        }
+
+        // there's nothing left to carry after the most significant limb
+        if kk == 2 * num_limbs - 2 {
+            assert_zero(compiler, &acc, span);
+            break;
+        }
+
+        // the carry is computed out of circuit
+        let next_carry = compiler.backend.new_internal_var(
+            Value::Compute(
+                vec![acc.clone()],
+                Arc::new(|values: &[B::Field]| signed_shift::<B::Field>(values[0])),
+            ),
+            span,
+        );
+        let next_carry = ConstOrCell::Cell(next_carry);
+
+        // and constrained to be a small signed value
+        let shifted_carry = field::add(
+            compiler,
+            &next_carry,
+            &ConstOrCell::Const(carry_offset),
+            span,
+        );
+        field::range_check(compiler, &shifted_carry[0], CARRY_BITS, span)?;
+
+        // acc - carry_k * 2^LIMB_BITS = 0
+        let carried = field::mul(compiler, &next_carry, &ConstOrCell::Const(base), span);
+        let diff = field::sub(compiler, &acc, &carried[0], span);
+        assert_zero(compiler, &diff[0], span);
+
+        carry = next_carry;
+    }
+
+    // the remainder must be smaller than the modulus,
+    // we compare them limb by limb starting from the most significant one
+    let mut is_lt = ConstOrCell::Const(B::Field::zero());
+    let mut is_eq = ConstOrCell::Const(B::Field::one());
+
+    for ii in (0..num_limbs).rev() {
+        let limb_lt = field::less_than(compiler, LIMB_BITS, &remainder[ii], &modulus[ii], span)?;
+        let limb_eq = field::equal_cells(compiler, &remainder[ii], &modulus[ii], span);
+
+        // is_lt |= is_eq & limb_lt
+        let smaller = boolean::and(compiler, &is_eq, &limb_lt[0], span);
+        is_lt = boolean::or(compiler, &is_lt, &smaller[0], span)[0].clone();
+
+        // is_eq &= limb_eq
+        is_eq = boolean::and(compiler, &is_eq, &limb_eq[0], span)[0].clone();
+    }
+
+    match is_lt {
+        ConstOrCell::Const(cst) => assert!(cst.is_one()),
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(&cvar, B::Field::one(), span),
+    }
+
+    Ok(Var::new(remainder, span))
+}
+
+/// Computes `(lhs - rhs) mod modulus`, as `rhs * (modulus - 1) + lhs`.
+pub fn sub_mod<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[ConstOrCell<B::Field, B::Var>],
+    rhs: &[ConstOrCell<B::Field, B::Var>],
+    modulus: &BigUint,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let minus_one = constant::<B>(&(modulus - 1u8));
+    mul_add_mod(compiler, rhs, &minus_one, lhs, modulus, span)
+}
+
+/// Computes the inverse of `val` modulo a prime `modulus`.
+/// The inverse is computed out of circuit, and constrained with `val * inverse = 1 mod modulus`,
+/// so this also constrains `val` to be non-zero modulo `modulus`.
+pub fn inv_mod<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    val: &[ConstOrCell<B::Field, B::Var>],
+    modulus: &BigUint,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    assert_eq!(val.len(), NUM_LIMBS);
+
+    // the inverse is computed out of circuit, once for all its limbs
+    let compute_inverse: Arc<ComputeManyFn<B::Field>> = {
+        let modulus = modulus.clone();
+        Arc::new(move |values: &[B::Field]| {
+            // by Fermat's little theorem
+            let val = from_limbs(values);
+            let inverse = val.modpow(&(&modulus - 2u8), &modulus);
+            (0..NUM_LIMBS).map(|ii| limb(&inverse, ii)).collect()
+        })
+    };
+    let inverse: Vec<_> = (0..NUM_LIMBS)
+        .map(|ii| {
+            let var = compiler.backend.new_internal_var(
+                Value::ComputeNth(val.to_vec(), compute_inverse.clone(), ii),
+                span,
+            );
+            ConstOrCell::Cell(var)
+        })
+        .collect();
+
+    // val * inverse = 1
+    let zero = constant::<B>(&BigUint::zero());
+    let one = constant::<B>(&BigUint::one());
+    let res = mul_add_mod(compiler, val, &inverse, &zero, modulus, span)?;
+    assert_eq(compiler, &res.cvars, &one, span);
+
+    Ok(Var::new(inverse, span))
+}
+
+/// Constrains two big integers to have the same limbs.
+pub fn assert_eq<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[ConstOrCell<B::Field, B::Var>],
+    rhs: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) {
+    assert_eq!(lhs.len(), rhs.len());
+
+    for (lhs, rhs) in lhs.iter().zip(rhs) {
+        let diff = field::sub(compiler, lhs, rhs, span);
+        assert_zero(compiler, &diff[0], span);
+    }
+}
+
+/// Returns the bits of a big integer (in little-endian order).
+pub fn to_bits<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    val: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    val.iter()
+        .flat_map(|limb| field::to_bits(compiler, limb, LIMB_BITS, span).cvars)
+        .collect()
+}
+
+/// Returns the limbs of a constant big integer.
+pub fn constant<B: Backend>(val: &BigUint) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    constant_with_limbs::<B>(val, NUM_LIMBS)
+}
+
+/// Returns the limbs of a constant big integer, with a given number of limbs.
+pub fn constant_with_limbs<B: Backend>(
+    val: &BigUint,
+    num_limbs: usize,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    assert!(val.bits() as usize <= num_limbs * LIMB_BITS);
+    (0..num_limbs)
+        .map(|ii| ConstOrCell::Const(limb(val, ii)))
+        .collect()
+}
+
+/// Recomposes a big integer from its limbs.
+pub fn from_limbs<F: BackendField>(limbs: &[F]) -> BigUint {
+    limbs.iter().rev().fold(BigUint::zero(), |acc, limb| {
+        let limb: BigUint = (*limb).into();
+        (acc << LIMB_BITS) + limb
+    })
+}
+
+/// Returns the `ii`-th limb of a big integer.
+pub fn limb<F: BackendField>(val: &BigUint, ii: usize) -> F {
+    let mask = (BigUint::one() << LIMB_BITS) - 1u8;
+    let limb = (val >> (ii * LIMB_BITS)) & mask;
+    F::try_from(limb).unwrap_or_else(|_| unreachable!("a limb always fits in a field element"))
+}
+
+/// Divides a signed value (where `p - x` encodes `-x`) by `2^LIMB_BITS`.
+fn signed_shift<F: BackendField>(val: F) -> F {
+    let pos: BigUint = val.into();
+    let neg: BigUint = (-val).into();
+
+    let shift = |val: BigUint| {
+        F::try_from(val >> LIMB_BITS)
+            .unwrap_or_else(|_| unreachable!("the value is smaller than the field modulus"))
+    };
+
+    if pos <= neg {
+        shift(pos)
+    } else {
+        -shift(neg)
+    }
+}
+
+/// Constrains a value to be zero.
+fn assert_zero<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    var: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) {
+    match var {
+        ConstOrCell::Const(cst) => assert!(cst.is_zero()),
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(cvar, B::Field::zero(), span),
+    }
+}
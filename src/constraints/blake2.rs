@@ -0,0 +1,252 @@
+//! The BLAKE2s and BLAKE2b hash functions (RFC 7693), unkeyed and with their full digest size,
+//! for messages of a length known at compile time.
+//!
+//! Both functions share the same structure, and only differ in their parameters (see [Params]).
+//! Words are represented as 32 (or 64) boolean cells (see [super::words]),
+//! so that rotations are free and only XOR and additions cost constraints.
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    field,
+    words::{add, constant, rotr, xor, Word},
+};
+
+/// The parameters of a function of the BLAKE2 family.
+struct Params {
+    /// The size of a word, in bits.
+    word_bits: usize,
+
+    /// The number of rounds of the compression function.
+    rounds: usize,
+
+    /// The rotations of the mixing function `G`.
+    rotations: [usize; 4],
+
+    /// The initialization vector (the same as the initial hash value of SHA-256 or SHA-512).
+    iv: [u64; 8],
+}
+
+impl Params {
+    /// The size of a block, in bytes.
+    fn block_len(&self) -> usize {
+        2 * self.word_bits
+    }
+
+    /// The size of the digest, in bytes.
+    fn digest_len(&self) -> usize {
+        self.word_bits
+    }
+}
+
+const BLAKE2S: Params = Params {
+    word_bits: 32,
+    rounds: 10,
+    rotations: [16, 12, 8, 7],
+    iv: [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ],
+};
+
+const BLAKE2B: Params = Params {
+    word_bits: 64,
+    rounds: 12,
+    rotations: [32, 24, 16, 63],
+    iv: [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ],
+};
+
+/// The permutations of the message words, one per round (modulo 10).
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The indices of the working vector mixed by `G`: first the columns, then the diagonals.
+const MIXES: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// Hashes a message of bytes with BLAKE2s, and returns the 32 bytes of the digest.
+/// The bytes of the message are constrained to fit in 8 bits.
+pub fn blake2s<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    hash(compiler, &BLAKE2S, message, span)
+}
+
+/// Hashes a message of bytes with BLAKE2b, and returns the 64 bytes of the digest.
+/// The bytes of the message are constrained to fit in 8 bits.
+pub fn blake2b<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    hash(compiler, &BLAKE2B, message, span)
+}
+
+fn hash<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let block_len = params.block_len();
+    let digest_len = params.digest_len();
+
+    // decompose the message into bits, and pad it with zeros to fill the last block
+    // (the empty message is padded to a single block)
+    let num_blocks = message.len().div_ceil(block_len).max(1);
+    let mut bits: Vec<_> = message
+        .iter()
+        .flat_map(|byte| field::to_bits(compiler, byte, 8, span).cvars)
+        .collect();
+    bits.extend(constant::<B>(
+        0,
+        8 * (num_blocks * block_len - message.len()),
+    ));
+
+    // the parameter block only sets the digest length and the fanout and depth (of 1)
+    let mut state: Vec<Word<B>> = params
+        .iv
+        .iter()
+        .map(|iv| constant::<B>(*iv, params.word_bits))
+        .collect();
+    let param_block = 0x01010000 ^ digest_len as u64;
+    state[0] = constant::<B>(params.iv[0] ^ param_block, params.word_bits);
+
+    for (ii, block) in bits.chunks(8 * block_len).enumerate() {
+        // the number of bytes hashed so far
+        let is_last = ii == num_blocks - 1;
+        let counter = if is_last {
+            message.len()
+        } else {
+            (ii + 1) * block_len
+        };
+
+        state = compress(
+            compiler,
+            params,
+            &state,
+            block,
+            counter as u128,
+            is_last,
+            span,
+        );
+    }
+
+    // the digest is the state in little-endian order
+    let digest: Vec<_> = state.into_iter().flatten().collect();
+    let digest = digest
+        .chunks(8)
+        .map(|byte| field::from_bits(compiler, byte, span)[0].clone())
+        .collect();
+
+    Var::new(digest, span)
+}
+
+/// Applies the compression function to a block of bits.
+fn compress<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params,
+    state: &[Word<B>],
+    block: &[ConstOrCell<B::Field, B::Var>],
+    counter: u128,
+    is_last: bool,
+    span: Span,
+) -> Vec<Word<B>> {
+    let word_bits = params.word_bits;
+    let message: Vec<Word<B>> = block.chunks(word_bits).map(<[_]>::to_vec).collect();
+
+    // initialize the working vector
+    let mut work = state.to_vec();
+    work.extend(params.iv.iter().map(|iv| constant::<B>(*iv, word_bits)));
+
+    let counter_low = constant::<B>(counter as u64, word_bits);
+    let counter_high = constant::<B>((counter >> word_bits) as u64, word_bits);
+    work[12] = xor(compiler, &work[12], &counter_low, span);
+    work[13] = xor(compiler, &work[13], &counter_high, span);
+
+    if is_last {
+        work[14] = xor(
+            compiler,
+            &work[14],
+            &constant::<B>(u64::MAX, word_bits),
+            span,
+        );
+    }
+
+    // mix the message into the working vector
+    for round in 0..params.rounds {
+        let sigma = &SIGMA[round % SIGMA.len()];
+        for (ii, indices) in MIXES.iter().enumerate() {
+            let x = &message[sigma[2 * ii]];
+            let y = &message[sigma[2 * ii + 1]];
+            mix(compiler, params, &mut work, *indices, x, y, span);
+        }
+    }
+
+    // xor the two halves of the working vector into the state
+    state
+        .iter()
+        .enumerate()
+        .map(|(ii, word)| {
+            let tmp = xor(compiler, word, &work[ii], span);
+            xor(compiler, &tmp, &work[ii + 8], span)
+        })
+        .collect()
+}
+
+/// The mixing function `G`.
+fn mix<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params,
+    work: &mut [Word<B>],
+    [a, b, c, d]: [usize; 4],
+    x: &Word<B>,
+    y: &Word<B>,
+    span: Span,
+) {
+    let [r0, r1, r2, r3] = params.rotations;
+
+    for (input, [rot_d, rot_b]) in [(x, [r0, r1]), (y, [r2, r3])] {
+        work[a] = add(compiler, &[&work[a], &work[b], input], span);
+        let tmp = xor(compiler, &work[d], &work[a], span);
+        work[d] = rotr::<B>(&tmp, rot_d);
+
+        work[c] = add(compiler, &[&work[c], &work[d]], span);
+        let tmp = xor(compiler, &work[b], &work[c], span);
+        work[b] = rotr::<B>(&tmp, rot_b);
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
     var::{ConstOrCell, Var},
 };
 
-use super::field::sub;
+use super::field::{self, sub};
 
 pub fn is_valid<F: Field>(f: F) -> bool {
     f.is_one() || f.is_zero()
@@ -100,8 +100,140 @@ pub fn or<B: Backend>(
     rhs: &ConstOrCell<B::Field, B::Var>,
     span: Span,
 ) -> Var<B::Field, B::Var> {
-    let not_lhs = not(compiler, lhs, span);
-    let not_rhs = not(compiler, rhs, span);
-    let both_false = and(compiler, &not_lhs[0], &not_rhs[0], span);
-    not(compiler, &both_false[0], span)
+    match (lhs, rhs) {
+        // two constants
+        (ConstOrCell::Const(lhs), ConstOrCell::Const(rhs)) => {
+            let value = if lhs.is_one() || rhs.is_one() {
+                B::Field::one()
+            } else {
+                B::Field::zero()
+            };
+
+            Var::new_constant(value, span)
+        }
+
+        // constant and a var
+        (ConstOrCell::Const(cst), ConstOrCell::Cell(cvar))
+        | (ConstOrCell::Cell(cvar), ConstOrCell::Const(cst)) => {
+            if cst.is_one() {
+                Var::new_constant(*cst, span)
+            } else {
+                Var::new_var(cvar.clone(), span)
+            }
+        }
+
+        // two vars
+        (ConstOrCell::Cell(lhs), ConstOrCell::Cell(rhs)) => {
+            // lhs + rhs - lhs * rhs
+            let prod = compiler.backend.mul(lhs, rhs, span);
+            let sum = compiler.backend.add(lhs, rhs, span);
+            let res = compiler.backend.sub(&sum, &prod, span);
+
+            Var::new_var(res, span)
+        }
+    }
+}
+
+/// Returns `!(lhs && rhs)`, with a single multiplication.
+pub fn nand<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let both = and(compiler, lhs, rhs, span);
+    not(compiler, &both[0], span)
+}
+
+/// Returns `!(lhs || rhs)`, with a single multiplication.
+pub fn nor<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let either = or(compiler, lhs, rhs, span);
+    not(compiler, &either[0], span)
+}
+
+pub fn xor<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    match (lhs, rhs) {
+        // two constants
+        (ConstOrCell::Const(lhs), ConstOrCell::Const(rhs)) => {
+            let value = if lhs != rhs {
+                B::Field::one()
+            } else {
+                B::Field::zero()
+            };
+
+            Var::new_constant(value, span)
+        }
+
+        // constant and a var
+        (ConstOrCell::Const(cst), ConstOrCell::Cell(cvar))
+        | (ConstOrCell::Cell(cvar), ConstOrCell::Const(cst)) => {
+            let var = ConstOrCell::Cell(cvar.clone());
+            if cst.is_one() {
+                not(compiler, &var, span)
+            } else {
+                Var::new_cvar(var, span)
+            }
+        }
+
+        // two vars
+        (ConstOrCell::Cell(lhs), ConstOrCell::Cell(rhs)) => {
+            // lhs + rhs - 2 * lhs * rhs
+            let two = B::Field::from(2u64);
+            let prod = compiler.backend.mul(lhs, rhs, span);
+            let double_prod = compiler.backend.mul_const(&prod, &two, span);
+            let sum = compiler.backend.add(lhs, rhs, span);
+            let res = compiler.backend.sub(&sum, &double_prod, span);
+
+            Var::new_var(res, span)
+        }
+    }
+}
+
+/// Returns true if all the booleans are true.
+/// Instead of a chain of `and`, we check that the number of true booleans is the number of booleans,
+/// which only costs a single equality check (on top of the additions).
+pub fn all<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    values: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let count = count(compiler, values, span);
+    let len = ConstOrCell::Const(B::Field::from(values.len() as u64));
+    field::equal_cells(compiler, &count, &len, span)
+}
+
+/// Returns true if at least one of the booleans is true.
+/// Like [all], this checks the number of true booleans instead of using a chain of `or`.
+pub fn any<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    values: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let count = count(compiler, values, span);
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let is_zero = field::equal_cells(compiler, &count, &zero, span);
+    not(compiler, &is_zero[0], span)
+}
+
+/// Returns the number of true booleans (which can't wrap around the field modulus).
+fn count<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    values: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    let mut count = ConstOrCell::Const(B::Field::zero());
+    for value in values {
+        count = field::add(compiler, &count, value, span)[0].clone();
+    }
+    count
 }
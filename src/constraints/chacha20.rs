@@ -0,0 +1,137 @@
+//! The ChaCha20 stream cipher (RFC 8439), for inputs of a length known at compile time.
+//!
+//! Words are represented as 32 boolean cells (see [super::words]),
+//! so that rotations are free and only XOR and additions cost constraints.
+//! The bytes of the key, the nonce, and the input are words in little-endian order.
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    field,
+    words::{add, constant, rotl, xor, Word},
+};
+
+/// The size of a block of the keystream, in bytes.
+const BLOCK_LEN: usize = 64;
+
+/// The first words of the state: "expand 32-byte k".
+const CONSTANTS: [u64; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+/// The indices of the state mixed by the quarter rounds of a double round: first the columns, then the diagonals.
+const QUARTER_ROUNDS: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// Encrypts (or decrypts) bytes with a key of 32 bytes and a nonce of 12 bytes,
+/// by XORing them with the keystream that starts at the block `counter` (a 32-bit integer).
+/// The counter wraps around if the input is too long.
+pub fn chacha20<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    key: &[ConstOrCell<B::Field, B::Var>],
+    nonce: &[ConstOrCell<B::Field, B::Var>],
+    counter: &ConstOrCell<B::Field, B::Var>,
+    input: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    assert_eq!(key.len(), 32);
+    assert_eq!(nonce.len(), 12);
+
+    let key = to_words(compiler, key, span);
+    let nonce = to_words(compiler, nonce, span);
+    let counter = field::to_bits(compiler, counter, 32, span).cvars;
+    let input: Vec<_> = input
+        .iter()
+        .flat_map(|byte| field::to_bits(compiler, byte, 8, span).cvars)
+        .collect();
+
+    let mut output = Vec::with_capacity(input.len());
+    for (ii, chunk) in input.chunks(8 * BLOCK_LEN).enumerate() {
+        let counter = add(compiler, &[&counter, &constant::<B>(ii as u64, 32)], span);
+
+        let mut state: Vec<Word<B>> = CONSTANTS
+            .iter()
+            .map(|cst| constant::<B>(*cst, 32))
+            .collect();
+        state.extend(key.iter().cloned());
+        state.push(counter);
+        state.extend(nonce.iter().cloned());
+
+        let keystream = block(compiler, &state, span);
+        let keystream: Vec<_> = keystream.into_iter().flatten().collect();
+        output.extend(xor(compiler, chunk, &keystream[..chunk.len()], span));
+    }
+
+    let output = output
+        .chunks(8)
+        .map(|byte| field::from_bits(compiler, byte, span)[0].clone())
+        .collect();
+
+    Var::new(output, span)
+}
+
+/// Decomposes bytes into words in little-endian order, which constrains the bytes to fit in 8 bits.
+fn to_words<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bytes: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<Word<B>> {
+    bytes
+        .chunks(4)
+        .map(|word| {
+            word.iter()
+                .flat_map(|byte| field::to_bits(compiler, byte, 8, span).cvars)
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns a block of the keystream: the state after 20 rounds, added to the initial state.
+fn block<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    initial: &[Word<B>],
+    span: Span,
+) -> Vec<Word<B>> {
+    let mut state = initial.to_vec();
+
+    for _ in 0..10 {
+        for indices in QUARTER_ROUNDS {
+            quarter_round(compiler, &mut state, indices, span);
+        }
+    }
+
+    state
+        .iter()
+        .zip(initial)
+        .map(|(word, initial)| add(compiler, &[word, initial], span))
+        .collect()
+}
+
+/// The quarter round, on 4 words of the state.
+fn quarter_round<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    state: &mut [Word<B>],
+    [a, b, c, d]: [usize; 4],
+    span: Span,
+) {
+    for [rot_d, rot_b] in [[16, 12], [8, 7]] {
+        state[a] = add(compiler, &[&state[a], &state[b]], span);
+        let tmp = xor(compiler, &state[d], &state[a], span);
+        state[d] = rotl::<B>(&tmp, rot_d);
+
+        state[c] = add(compiler, &[&state[c], &state[d]], span);
+        let tmp = xor(compiler, &state[b], &state[c], span);
+        state[b] = rotl::<B>(&tmp, rot_b);
+    }
+}
@@ -0,0 +1,172 @@
+//! The curve embedded in the field of the backend, whatever its model:
+//! a twisted Edwards curve for the R1CS backends (see [super::edwards]),
+//! and a short Weierstrass curve for the kimchi backend (see [super::weierstrass]).
+//!
+//! Points are given by their affine coordinates `[x, y]` in both cases.
+//! Note that the identity is the point `(0, 1)` on twisted Edwards curves,
+//! but it has no affine coordinates on short Weierstrass curves,
+//! where operations that would produce it (or add two points with the same `x`) make the circuit unsatisfiable,
+//! or are an error if the points are constant.
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::ConstOrCell,
+};
+
+use super::{eddsa, edwards, field, weierstrass};
+
+/// A point, in affine coordinates.
+pub type Point<B> = [ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>; 2];
+
+/// The curve embedded in the field of a backend.
+pub enum EmbeddedCurve<F> {
+    Edwards(edwards::Curve<F>),
+    Weierstrass(weierstrass::Curve<F>),
+}
+
+impl<F: BackendField> EmbeddedCurve<F> {
+    /// Returns the curve embedded in the field `F`, if there is one.
+    pub fn get() -> Option<Self> {
+        edwards::embedded_curve()
+            .map(Self::Edwards)
+            .or_else(|| weierstrass::embedded_curve().map(Self::Weierstrass))
+    }
+
+    /// Returns a generator of the prime-order subgroup:
+    /// the base point of EdDSA on twisted Edwards curves, and the generator of Mina on Pallas.
+    pub fn generator(&self) -> (F, F) {
+        match self {
+            Self::Edwards(curve) => eddsa::base(curve),
+            Self::Weierstrass(curve) => curve.generator,
+        }
+    }
+
+    /// Derives a generator of the prime-order subgroup from a seed,
+    /// whose discrete logarithm with respect to other generators is unknown.
+    pub fn independent_generator(&self, seed: &str) -> (F, F) {
+        match self {
+            Self::Edwards(curve) => curve.generator(seed),
+            Self::Weierstrass(curve) => curve.hash_to_point(seed),
+        }
+    }
+}
+
+/// Adds two points.
+pub fn add<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    lhs: &Point<B>,
+    rhs: &Point<B>,
+    span: Span,
+) -> Result<Point<B>> {
+    match curve {
+        EmbeddedCurve::Edwards(curve) => Ok(edwards::add(compiler, curve, lhs, rhs, span)),
+        EmbeddedCurve::Weierstrass(_) => weierstrass::add(compiler, lhs, rhs, span),
+    }
+}
+
+/// Doubles a point.
+pub fn double<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    point: &Point<B>,
+    span: Span,
+) -> Result<Point<B>> {
+    match curve {
+        EmbeddedCurve::Edwards(curve) => Ok(edwards::double(compiler, curve, point, span)),
+        EmbeddedCurve::Weierstrass(_) => weierstrass::double(compiler, point, span),
+    }
+}
+
+/// Multiplies a point by a scalar.
+pub fn scalar_mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    point: &Point<B>,
+    scalar: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Point<B>> {
+    let bits = field::to_bits_strict(compiler, scalar, span);
+
+    match curve {
+        EmbeddedCurve::Edwards(curve) => Ok(edwards::scalar_mul(
+            compiler,
+            curve,
+            point,
+            &bits.cvars,
+            span,
+        )),
+        EmbeddedCurve::Weierstrass(curve) => {
+            weierstrass::scalar_mul(compiler, curve, point, &bits.cvars, span)
+        }
+    }
+}
+
+/// Returns the sum of constant points multiplied by scalars.
+/// On short Weierstrass curves, the result can't be the point at infinity.
+pub fn multi_scalar_mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    bases: &[(B::Field, B::Field)],
+    scalars: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Point<B>> {
+    assert_eq!(bases.len(), scalars.len());
+    let bits: Vec<_> = scalars
+        .iter()
+        .map(|scalar| field::to_bits_strict(compiler, scalar, span).cvars)
+        .collect();
+
+    match curve {
+        EmbeddedCurve::Edwards(curve) => {
+            let mut acc = edwards::identity::<B>();
+            for (base, bits) in bases.iter().zip(&bits) {
+                let term = edwards::fixed_base_mul(compiler, curve, *base, bits, span);
+                acc = edwards::add(compiler, curve, &acc, &term, span);
+            }
+            Ok(acc)
+        }
+        EmbeddedCurve::Weierstrass(curve) => {
+            let terms: Vec<_> = bases
+                .iter()
+                .zip(&bits)
+                .map(|(base, bits)| (weierstrass::constant::<B>(*base), &bits[..]))
+                .collect();
+            weierstrass::multi_scalar_mul(compiler, curve, &terms, span)
+        }
+    }
+}
+
+/// Constrains a point to be on the curve.
+pub fn assert_on_curve<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    point: &Point<B>,
+    span: Span,
+) -> Result<()> {
+    match curve {
+        EmbeddedCurve::Edwards(curve) => edwards::assert_on_curve(compiler, curve, point, span),
+        EmbeddedCurve::Weierstrass(curve) => {
+            weierstrass::assert_on_curve(compiler, curve, point, span)
+        }
+    }
+}
+
+/// Constrains a point to be on the curve, and in the prime-order subgroup.
+/// Pallas has a prime order, so this is the same as [assert_on_curve] on the kimchi backend over Vesta.
+pub fn assert_in_subgroup<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    point: &Point<B>,
+    span: Span,
+) -> Result<()> {
+    match curve {
+        EmbeddedCurve::Edwards(curve) => edwards::assert_in_subgroup(compiler, curve, point, span),
+        EmbeddedCurve::Weierstrass(curve) => {
+            weierstrass::assert_on_curve(compiler, curve, point, span)
+        }
+    }
+}
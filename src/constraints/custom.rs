@@ -0,0 +1,83 @@
+//! Custom gates that backends expose by name (see [Backend::custom_gates]),
+//! so that gadgets can target them without the circuit writer knowing about them.
+//!
+//! The outputs of a custom gate are computed out of circuit with its witness hint,
+//! and the backend only constrains them with the inputs.
+
+use std::sync::Arc;
+
+use crate::{
+    backends::{Backend, CustomGate},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::{ErrorKind, Result},
+    var::{ConstOrCell, Value, Var},
+};
+
+/// Returns the custom gate of the backend with the given name, if it has one.
+pub fn find<B: Backend>(compiler: &CircuitWriter<B>, name: &str) -> Option<CustomGate<B::Field>> {
+    compiler
+        .backend
+        .custom_gates()
+        .into_iter()
+        .find(|gate| gate.name == name)
+}
+
+/// Constrains `inputs` with the custom gate `name` of the backend, and returns its outputs.
+pub fn gate<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    name: &str,
+    inputs: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let gate = find(compiler, name)
+        .ok_or_else(|| compiler.error(ErrorKind::UnknownCustomGate(name.to_string()), span))?;
+    assert_eq!(
+        inputs.len(),
+        gate.num_inputs,
+        "wrong number of inputs for the custom gate `{name}`"
+    );
+
+    // constants don't need a gate
+    let constants: Option<Vec<_>> = inputs.iter().map(ConstOrCell::cst).collect();
+    if let Some(constants) = constants {
+        let outputs = (gate.hint)(&constants);
+        assert_eq!(outputs.len(), gate.num_outputs);
+        let outputs = outputs.into_iter().map(ConstOrCell::Const).collect();
+        return Ok(Var::new(outputs, span));
+    }
+
+    // the gate needs actual variables
+    let input_vars: Vec<_> = inputs
+        .iter()
+        .map(|input| match input {
+            ConstOrCell::Const(cst) => {
+                compiler
+                    .backend
+                    .add_constant(Some("an input of a custom gate"), *cst, span)
+            }
+            ConstOrCell::Cell(cvar) => cvar.clone(),
+        })
+        .collect();
+
+    // the outputs are computed out of circuit
+    let output_vars: Vec<_> = (0..gate.num_outputs)
+        .map(|ii| {
+            let hint = gate.hint;
+            compiler.backend.new_internal_var(
+                Value::Compute(
+                    inputs.to_vec(),
+                    Arc::new(move |vals: &[B::Field]| hint(vals)[ii]),
+                ),
+                span,
+            )
+        })
+        .collect();
+
+    compiler
+        .backend
+        .add_custom_gate(name, &input_vars, &output_vars, span)?;
+
+    let outputs = output_vars.into_iter().map(ConstOrCell::Cell).collect();
+    Ok(Var::new(outputs, span))
+}
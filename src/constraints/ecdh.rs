@@ -0,0 +1,30 @@
+//! Elliptic-curve Diffie-Hellman key agreement over the curve embedded in the field of the backend
+//! (see [super::curve]).
+//!
+//! The shared secret of a secret key `k` and a public key `P` is `Poseidon2(S.x, S.y)`, where `S = [k] P`.
+//! The public key is constrained to be in the prime-order subgroup,
+//! so that a malicious public key can't leak the secret key modulo the cofactor.
+
+use crate::{
+    backends::Backend, circuit_writer::CircuitWriter, constants::Span, error::Result,
+    var::ConstOrCell,
+};
+
+use super::{
+    curve::{self, EmbeddedCurve, Point},
+    poseidon2,
+};
+
+/// Returns the shared secret of a secret key and a public key.
+pub fn shared_secret<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    public_key: &Point<B>,
+    secret_key: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    curve::assert_in_subgroup(compiler, curve, public_key, span)?;
+
+    let shared = curve::scalar_mul(compiler, curve, public_key, secret_key, span)?;
+    poseidon2::hash(compiler, &shared, span)
+}
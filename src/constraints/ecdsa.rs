@@ -0,0 +1,265 @@
+//! ECDSA signatures over secp256k1, verified with the non-native arithmetic of [super::bigint].
+//!
+//! Field elements of secp256k1 (and scalars) are big integers of [NUM_LIMBS] limbs.
+//! A signature `(r, s)` of a message hash `z` under the public key `Q` is valid if `0 < r, s < n` and
+//! `(u1 G + u2 Q).x = r mod n`, where `w = 1 / s mod n`, `u1 = z w mod n`, `u2 = r w mod n`,
+//! and `n` is the order of the curve.
+//!
+//! Points are added with the incomplete affine formulas (like [super::weierstrass]):
+//! the double scalar multiplication starts from an offset point with an unknown discrete logarithm,
+//! so the exceptional cases only happen with a negligible probability, and make the circuit unsatisfiable.
+//! Every backend uses the generic limb arithmetic, as the foreign field gates of kimchi aren't exposed by its backend.
+//! This is expensive: a signature costs millions of constraints.
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    bigint::{self, mul_add_mod, sub_mod, LIMB_BITS, NUM_LIMBS},
+    field,
+    keccak::keccak256_native,
+};
+
+/// The modulus of the base field of secp256k1.
+const P: &str = "115792089237316195423570985008687907853269984665640564039457584007908834671663";
+
+/// The order of secp256k1.
+const N: &str = "115792089237316195423570985008687907852837564279074904382605163141518161494337";
+
+/// The generator of secp256k1.
+const G: (&str, &str) = (
+    "55066263022277343669578718895168534326250603453777594175500187360389116729240",
+    "32670510020758816978083085130507043184471273380659243275938904335757337482424",
+);
+
+/// The coefficient `b` of the equation `y^2 = x^3 + b` of secp256k1.
+const CURVE_B: u64 = 7;
+
+/// A point of secp256k1, as the limbs of its affine coordinates.
+type Point<B> = [Vec<ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>>; 2];
+
+/// A point of secp256k1 out of the circuit.
+type NativePoint = (BigUint, BigUint);
+
+/// Verifies a signature `(r, s)` of a message hash under a public key,
+/// where each of them is given as the limbs of a big integer.
+pub fn verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    public_key: &[ConstOrCell<B::Field, B::Var>],
+    message_hash: &[ConstOrCell<B::Field, B::Var>],
+    signature: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<()> {
+    let p: BigUint = P.parse().unwrap();
+    let n: BigUint = N.parse().unwrap();
+
+    let public_key = [
+        public_key[..NUM_LIMBS].to_vec(),
+        public_key[NUM_LIMBS..].to_vec(),
+    ];
+    let (r, s) = signature.split_at(NUM_LIMBS);
+
+    // the public key is on the curve: y^2 = x^3 + b
+    let [x, y] = &public_key;
+    let y2 = mul(compiler, y, y, &p, span)?;
+    let x2 = mul(compiler, x, x, &p, span)?;
+    let rhs = mul_add_mod(
+        compiler,
+        &x2.cvars,
+        x,
+        &bigint::constant::<B>(&CURVE_B.into()),
+        &p,
+        span,
+    )?;
+    bigint::assert_eq(compiler, &y2.cvars, &rhs.cvars, span);
+
+    // 0 < r < n and 0 < s < n (a scalar is non-zero if it has an inverse)
+    for scalar in [r, s] {
+        let reduced = reduce(compiler, scalar, &n, span)?;
+        bigint::assert_eq(compiler, &reduced.cvars, scalar, span);
+    }
+    bigint::inv_mod(compiler, r, &n, span)?;
+    let w = bigint::inv_mod(compiler, s, &n, span)?;
+
+    // u1 = z w and u2 = r w
+    let u1 = mul(compiler, message_hash, &w.cvars, &n, span)?;
+    let u2 = mul(compiler, r, &w.cvars, &n, span)?;
+    let u1_bits = bigint::to_bits(compiler, &u1.cvars, span);
+    let u2_bits = bigint::to_bits(compiler, &u2.cvars, span);
+
+    // u1 G + u2 Q, with a double-and-add sharing the doublings, starting from an offset point
+    let offset = hash_to_point(&p, "noname_ecdsa_offset");
+    let generator = (G.0.parse().unwrap(), G.1.parse().unwrap());
+    let generator = constant::<B>(&generator);
+    let mut acc = constant::<B>(&offset);
+
+    for (u1_bit, u2_bit) in u1_bits.iter().zip(&u2_bits).rev() {
+        acc = double(compiler, &acc, &p, span)?;
+
+        let sum = add(compiler, &acc, &generator, &p, span)?;
+        acc = select(compiler, u1_bit, &sum, &acc, span);
+
+        let sum = add(compiler, &acc, &public_key, &p, span)?;
+        acc = select(compiler, u2_bit, &sum, &acc, span);
+    }
+
+    // subtract [2^256] offset
+    let mut shifted = offset;
+    for _ in 0..NUM_LIMBS * LIMB_BITS {
+        shifted = native_double(&p, &shifted);
+    }
+    let (x, y) = shifted;
+    let neg_shifted = constant::<B>(&(x, &p - y));
+    let point = add(compiler, &acc, &neg_shifted, &p, span)?;
+
+    // R.x = r mod n
+    let x = reduce(compiler, &point[0], &n, span)?;
+    bigint::assert_eq(compiler, &x.cvars, r, span);
+
+    Ok(())
+}
+
+/// Computes `(lhs * rhs) mod modulus`.
+fn mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[ConstOrCell<B::Field, B::Var>],
+    rhs: &[ConstOrCell<B::Field, B::Var>],
+    modulus: &BigUint,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let zero = bigint::constant::<B>(&BigUint::zero());
+    mul_add_mod(compiler, lhs, rhs, &zero, modulus, span)
+}
+
+/// Computes `val mod modulus`.
+fn reduce<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    val: &[ConstOrCell<B::Field, B::Var>],
+    modulus: &BigUint,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let one = bigint::constant::<B>(&BigUint::one());
+    mul(compiler, val, &one, modulus, span)
+}
+
+/// Returns a constant point.
+fn constant<B: Backend>((x, y): &NativePoint) -> Point<B> {
+    [bigint::constant::<B>(x), bigint::constant::<B>(y)]
+}
+
+/// Returns `then_` if `cond` is set, and `else_` otherwise.
+fn select<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cond: &ConstOrCell<B::Field, B::Var>,
+    then_: &Point<B>,
+    else_: &Point<B>,
+    span: Span,
+) -> Point<B> {
+    [0, 1].map(|coordinate| {
+        then_[coordinate]
+            .iter()
+            .zip(&else_[coordinate])
+            .map(|(then_, else_)| {
+                field::if_else_inner(compiler, cond, then_, else_, span)[0].clone()
+            })
+            .collect()
+    })
+}
+
+/// Adds two points with the incomplete addition formulas: the `x` coordinates must be different.
+fn add<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    [x1, y1]: &Point<B>,
+    [x2, y2]: &Point<B>,
+    p: &BigUint,
+    span: Span,
+) -> Result<Point<B>> {
+    // lambda = (y2 - y1) / (x2 - x1)
+    let num = sub_mod(compiler, y2, y1, p, span)?;
+    let den = sub_mod(compiler, x2, x1, p, span)?;
+    let den = bigint::inv_mod(compiler, &den.cvars, p, span)?;
+    let lambda = mul(compiler, &num.cvars, &den.cvars, p, span)?;
+
+    finish_add(compiler, &lambda.cvars, x1, y1, x2, p, span)
+}
+
+/// Doubles a point (which can't be of order 2, as the order of the curve is odd).
+fn double<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    [x, y]: &Point<B>,
+    p: &BigUint,
+    span: Span,
+) -> Result<Point<B>> {
+    // lambda = 3 x^2 / 2 y
+    let x2 = mul(compiler, x, x, p, span)?;
+    let num = mul(
+        compiler,
+        &x2.cvars,
+        &bigint::constant::<B>(&3u8.into()),
+        p,
+        span,
+    )?;
+    let den = mul(compiler, y, &bigint::constant::<B>(&2u8.into()), p, span)?;
+    let den = bigint::inv_mod(compiler, &den.cvars, p, span)?;
+    let lambda = mul(compiler, &num.cvars, &den.cvars, p, span)?;
+
+    finish_add(compiler, &lambda.cvars, x, y, x, p, span)
+}
+
+/// Returns `(x3, y3)` from the slope of the line through the points:
+/// `x3 = lambda^2 - x1 - x2` and `y3 = lambda (x1 - x3) - y1`.
+fn finish_add<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lambda: &[ConstOrCell<B::Field, B::Var>],
+    x1: &[ConstOrCell<B::Field, B::Var>],
+    y1: &[ConstOrCell<B::Field, B::Var>],
+    x2: &[ConstOrCell<B::Field, B::Var>],
+    p: &BigUint,
+    span: Span,
+) -> Result<Point<B>> {
+    let lambda2 = mul(compiler, lambda, lambda, p, span)?;
+    let x3 = sub_mod(compiler, &lambda2.cvars, x1, p, span)?;
+    let x3 = sub_mod(compiler, &x3.cvars, x2, p, span)?;
+
+    let diff = sub_mod(compiler, x1, &x3.cvars, p, span)?;
+    let y3 = mul(compiler, lambda, &diff.cvars, p, span)?;
+    let y3 = sub_mod(compiler, &y3.cvars, y1, p, span)?;
+
+    Ok([x3.cvars, y3.cvars])
+}
+
+/// Doubles a point out of the circuit.
+fn native_double(p: &BigUint, (x, y): &NativePoint) -> NativePoint {
+    let inv = |val: BigUint| val.modpow(&(p - 2u8), p);
+
+    let lambda = (x * x * 3u8) % p * inv(y * 2u8 % p) % p;
+    let x3 = (&lambda * &lambda + (p - x) * 2u8) % p;
+    let y3 = (lambda * ((x + p - &x3) % p) + (p - y)) % p;
+    (x3, y3)
+}
+
+/// Derives a point with an unknown discrete logarithm from a seed:
+/// candidate `x` coordinates are hashed until one is on the curve.
+fn hash_to_point(p: &BigUint, seed: &str) -> NativePoint {
+    for counter in 0u32.. {
+        let hash = keccak256_native(format!("{seed}_{counter}").as_bytes());
+        let x = BigUint::from_bytes_be(&hash) % p;
+
+        // p = 3 mod 4, so a square root of a square `a` is `a^((p + 1) / 4)`
+        let y2 = (&x * &x * &x + CURVE_B) % p;
+        let y = y2.modpow(&((p + 1u8) / 4u8), p);
+        if &y * &y % p == y2 {
+            return (x, y);
+        }
+    }
+
+    unreachable!()
+}
@@ -0,0 +1,96 @@
+//! EdDSA signatures over the twisted Edwards curve embedded in the field of the backend (see [super::edwards]).
+//!
+//! The scheme follows the EdDSA of circomlib, with Poseidon2 as the hash function:
+//! a signature `(R, s)` of a message `m` under the public key `A` is valid if `s < l` and `[s] B = R + [8 h] A`,
+//! where `h = Poseidon2(R.x, R.y, A.x, A.y, m)`, `l` is the order of the subgroup,
+//! and `B` is a generator of the subgroup (see [base]).
+//!
+//! Points are compressed as their `y` coordinate and the parity of their `x` coordinate,
+//! so that a public key is `[A.y, A.x is odd]`, and a signature is `[R.y, R.x is odd, s]`.
+
+use ark_ff::One;
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::{ErrorKind, Result},
+    var::ConstOrCell,
+};
+
+use super::{
+    edwards::{self, Curve, COFACTOR},
+    field, poseidon2,
+};
+
+/// Returns the base point of the signatures.
+pub fn base<F: BackendField>(curve: &Curve<F>) -> (F, F) {
+    curve.generator("noname_eddsa_base")
+}
+
+/// Verifies a signature of a message under a public key (both compressed as described in the module).
+pub fn verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    public_key: &[ConstOrCell<B::Field, B::Var>],
+    signature: &[ConstOrCell<B::Field, B::Var>],
+    message: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<()> {
+    let [public_key_y, public_key_is_odd] = public_key else {
+        unreachable!("a public key is made of 2 field elements");
+    };
+    let [r_y, r_is_odd, s] = signature else {
+        unreachable!("a signature is made of 3 field elements");
+    };
+
+    let public_key = edwards::decompress(compiler, curve, public_key_y, public_key_is_odd, span);
+    let r = edwards::decompress(compiler, curve, r_y, r_is_odd, span);
+
+    // the scalar must be reduced, so that signatures are not malleable
+    let num_bits = curve.order.bits() as usize;
+    if let Some(cst) = s.cst() {
+        let cst: BigUint = cst.into();
+        if cst >= curve.order {
+            return Err(compiler.error(ErrorKind::AssertionFailed, span));
+        }
+    }
+    let s_bits = field::to_bits(compiler, s, num_bits, span);
+    let is_reduced = field::bits_less_than(compiler, &s_bits.cvars, &curve.order, span);
+    let one = ConstOrCell::Const(B::Field::one());
+    field::assert_eq_cells(compiler, &is_reduced, &one, span)?;
+
+    // h = Poseidon2(R.x, R.y, A.x, A.y, m)
+    let input = [
+        r[0].clone(),
+        r[1].clone(),
+        public_key[0].clone(),
+        public_key[1].clone(),
+        message.clone(),
+    ];
+    let h = poseidon2::hash(compiler, &input, span)?;
+    let h_bits = field::to_bits_strict(compiler, &h, span);
+
+    // [s] B
+    let lhs = edwards::fixed_base_mul(compiler, curve, base(curve), &s_bits.cvars, span);
+
+    // R + [h] ([8] A)
+    let mut cleared = public_key;
+    for _ in 0..COFACTOR.trailing_zeros() {
+        cleared = edwards::double(compiler, curve, &cleared, span);
+    }
+    let rhs = edwards::scalar_mul(compiler, curve, &cleared, &h_bits.cvars, span);
+    let rhs = edwards::add(compiler, curve, &r, &rhs, span);
+
+    field::assert_eq_all(
+        compiler,
+        &[
+            (lhs[0].clone(), rhs[0].clone()),
+            (lhs[1].clone(), rhs[1].clone()),
+        ],
+        span,
+    )?;
+
+    Ok(())
+}
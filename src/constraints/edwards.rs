@@ -0,0 +1,357 @@
+//! Points of the twisted Edwards curve embedded in the field of the backend:
+//! Jubjub for BLS12-381, and Baby Jubjub for BN254.
+//! The pallas field (of the kimchi backend, and of the r1cs backend with `--field pallas`) has no embedded twisted Edwards curve, so it is not supported.
+//!
+//! Points are represented by their affine coordinates `(x, y)`,
+//! and added with the twisted Edwards formulas, which are complete on both curves
+//! (so that there is no special case for the identity `(0, 1)` or for doubling).
+
+use std::sync::Arc;
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::{ConstOrCell, Value},
+};
+
+use super::{boolean, field, keccak::keccak256_native};
+
+/// A point of the curve, in affine coordinates.
+pub type Point<B> = [ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>; 2];
+
+/// The modulus of the scalar field of BLS12-381, which is the base field of Jubjub.
+const BLS12_381_MODULUS: &str =
+    "52435875175126190479447740508185965837690552500527637822603658699938581184513";
+
+/// The modulus of the scalar field of BN254, which is the base field of Baby Jubjub.
+const BN254_MODULUS: &str =
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+/// The order of the prime-order subgroup of Jubjub.
+const JUBJUB_ORDER: &str =
+    "6554484396890773809930967563523245729705921265872317281365359162392183254199";
+
+/// The order of the prime-order subgroup of Baby Jubjub.
+const BABY_JUBJUB_ORDER: &str =
+    "2736030358979909402780800718157159386076813972158567259200215660948447373041";
+
+/// The cofactor of both curves.
+pub const COFACTOR: u64 = 8;
+
+/// A twisted Edwards curve `a x^2 + y^2 = 1 + d x^2 y^2`.
+#[derive(Clone)]
+pub struct Curve<F> {
+    pub a: F,
+    pub d: F,
+
+    /// The order of the prime-order subgroup.
+    pub order: BigUint,
+}
+
+/// Returns the twisted Edwards curve embedded in the field `F`, if there is one.
+pub fn embedded_curve<F: BackendField>() -> Option<Curve<F>> {
+    let modulus_minus_one: BigUint = (-F::one()).into();
+    let modulus = (modulus_minus_one + 1u8).to_string();
+
+    if modulus == BLS12_381_MODULUS {
+        // Jubjub
+        let d = F::from(10240u64) / F::from(10241u64);
+        Some(Curve {
+            a: -F::one(),
+            d: -d,
+            order: JUBJUB_ORDER.parse().unwrap(),
+        })
+    } else if modulus == BN254_MODULUS {
+        // Baby Jubjub
+        Some(Curve {
+            a: F::from(168700u64),
+            d: F::from(168696u64),
+            order: BABY_JUBJUB_ORDER.parse().unwrap(),
+        })
+    } else {
+        None
+    }
+}
+
+impl<F: BackendField> Curve<F> {
+    /// Adds two points out of the circuit.
+    pub fn add(&self, (x1, y1): (F, F), (x2, y2): (F, F)) -> (F, F) {
+        let t = self.d * x1 * x2 * y1 * y2;
+        let x3 = (x1 * y2 + y1 * x2) / (F::one() + t);
+        let y3 = (y1 * y2 - self.a * x1 * x2) / (F::one() - t);
+        (x3, y3)
+    }
+
+    /// Doubles a point out of the circuit.
+    pub fn double(&self, point: (F, F)) -> (F, F) {
+        self.add(point, point)
+    }
+
+    /// Multiplies a point by a scalar out of the circuit.
+    pub fn mul(&self, point: (F, F), scalar: &BigUint) -> (F, F) {
+        let mut acc = (F::zero(), F::one());
+        for ii in (0..scalar.bits()).rev() {
+            acc = self.double(acc);
+            if scalar.bit(ii) {
+                acc = self.add(acc, point);
+            }
+        }
+        acc
+    }
+
+    /// Derives a generator of the prime-order subgroup from a seed:
+    /// candidate `y` coordinates are hashed until one is on the curve, and the point is multiplied by the cofactor.
+    pub fn generator(&self, seed: &str) -> (F, F) {
+        let modulus_minus_one: BigUint = (-F::one()).into();
+        let modulus = modulus_minus_one + 1u8;
+
+        for counter in 0u32.. {
+            let hash = keccak256_native(format!("{seed}_{counter}").as_bytes());
+            let y = BigUint::from_bytes_be(&hash) % &modulus;
+            let y = F::try_from(y).unwrap_or_else(|_| unreachable!());
+
+            let Some(x) = field::smallest_root(self.x_squared(y)) else {
+                continue;
+            };
+
+            let mut point = (x, y);
+            for _ in 0..COFACTOR.trailing_zeros() {
+                point = self.double(point);
+            }
+
+            if point != (F::zero(), F::one()) {
+                return point;
+            }
+        }
+
+        unreachable!()
+    }
+
+    /// Returns `x^2` for the points of the curve with the given `y`: `(1 - y^2) / (a - d y^2)`.
+    /// The denominator is never zero, as `a` is a square and `d` is not.
+    fn x_squared(&self, y: F) -> F {
+        let y2 = y.square();
+        (F::one() - y2) / (self.a - self.d * y2)
+    }
+}
+
+/// Returns the identity `(0, 1)`.
+pub fn identity<B: Backend>() -> Point<B> {
+    [
+        ConstOrCell::Const(B::Field::zero()),
+        ConstOrCell::Const(B::Field::one()),
+    ]
+}
+
+/// Returns a constant point.
+pub fn constant<B: Backend>((x, y): (B::Field, B::Field)) -> Point<B> {
+    [ConstOrCell::Const(x), ConstOrCell::Const(y)]
+}
+
+/// Constrains a point to be on the curve.
+pub fn assert_on_curve<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    [x, y]: &Point<B>,
+    span: Span,
+) -> Result<()> {
+    // a x^2 + y^2 = 1 + d x^2 y^2
+    let x2 = field::mul(compiler, x, x, span);
+    let y2 = field::mul(compiler, y, y, span);
+    let ax2 = field::mul(compiler, &x2[0], &ConstOrCell::Const(curve.a), span);
+    let lhs = field::add(compiler, &ax2[0], &y2[0], span);
+
+    let x2y2 = field::mul(compiler, &x2[0], &y2[0], span);
+    let dx2y2 = field::mul(compiler, &x2y2[0], &ConstOrCell::Const(curve.d), span);
+    let one = ConstOrCell::Const(B::Field::one());
+    let rhs = field::add(compiler, &one, &dx2y2[0], span);
+
+    field::assert_eq_cells(compiler, &lhs[0], &rhs[0], span)
+}
+
+/// Constrains a point to be on the curve, and in the prime-order subgroup.
+///
+/// The points of the subgroup are exactly the multiples of [COFACTOR] of the points of the curve,
+/// so the point `Q = [1 / COFACTOR mod order] P` is computed out of circuit,
+/// and constrained to be on the curve and to satisfy `[COFACTOR] Q = P`.
+pub fn assert_in_subgroup<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    point: &Point<B>,
+    span: Span,
+) -> Result<()> {
+    assert_on_curve(compiler, curve, point, span)?;
+
+    let cofactor_inv = BigUint::from(COFACTOR).modpow(&(&curve.order - 2u8), &curve.order);
+
+    let quotient = [0, 1].map(|coordinate| match point {
+        [ConstOrCell::Const(x), ConstOrCell::Const(y)] => {
+            let quotient = curve.mul((*x, *y), &cofactor_inv);
+            ConstOrCell::Const([quotient.0, quotient.1][coordinate])
+        }
+        _ => {
+            let curve = curve.clone();
+            let cofactor_inv = cofactor_inv.clone();
+            let var = compiler.backend.new_internal_var(
+                Value::Compute(
+                    point.to_vec(),
+                    Arc::new(move |vals| {
+                        let quotient = curve.mul((vals[0], vals[1]), &cofactor_inv);
+                        [quotient.0, quotient.1][coordinate]
+                    }),
+                ),
+                span,
+            );
+            ConstOrCell::Cell(var)
+        }
+    });
+    assert_on_curve(compiler, curve, &quotient, span)?;
+
+    let mut multiple = quotient;
+    for _ in 0..COFACTOR.trailing_zeros() {
+        multiple = double(compiler, curve, &multiple, span);
+    }
+    field::assert_eq_all(
+        compiler,
+        &[
+            (multiple[0].clone(), point[0].clone()),
+            (multiple[1].clone(), point[1].clone()),
+        ],
+        span,
+    )
+}
+
+/// Adds two points with the (complete) twisted Edwards addition formulas.
+pub fn add<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    [x1, y1]: &Point<B>,
+    [x2, y2]: &Point<B>,
+    span: Span,
+) -> Point<B> {
+    let x1y2 = field::mul(compiler, x1, y2, span);
+    let y1x2 = field::mul(compiler, y1, x2, span);
+    let x1x2 = field::mul(compiler, x1, x2, span);
+    let y1y2 = field::mul(compiler, y1, y2, span);
+
+    // t = d x1 x2 y1 y2
+    let t = field::mul(compiler, &x1x2[0], &y1y2[0], span);
+    let t = field::mul(compiler, &t[0], &ConstOrCell::Const(curve.d), span);
+    let one = ConstOrCell::Const(B::Field::one());
+
+    // x3 = (x1 y2 + y1 x2) / (1 + t)
+    let num = field::add(compiler, &x1y2[0], &y1x2[0], span);
+    let den = field::add(compiler, &one, &t[0], span);
+    let den = field::inv(compiler, &den[0], span);
+    let x3 = field::mul(compiler, &num[0], &den[0], span);
+
+    // y3 = (y1 y2 - a x1 x2) / (1 - t)
+    let ax1x2 = field::mul(compiler, &x1x2[0], &ConstOrCell::Const(curve.a), span);
+    let num = field::sub(compiler, &y1y2[0], &ax1x2[0], span);
+    let den = field::sub(compiler, &one, &t[0], span);
+    let den = field::inv(compiler, &den[0], span);
+    let y3 = field::mul(compiler, &num[0], &den[0], span);
+
+    [x3[0].clone(), y3[0].clone()]
+}
+
+/// Doubles a point.
+pub fn double<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    point: &Point<B>,
+    span: Span,
+) -> Point<B> {
+    add(compiler, curve, point, point, span)
+}
+
+/// Returns the identity if `bit` is zero, and `point` otherwise.
+fn select<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bit: &ConstOrCell<B::Field, B::Var>,
+    point: &Point<B>,
+    span: Span,
+) -> Point<B> {
+    let [identity_x, identity_y] = identity::<B>();
+    let x = field::if_else_inner(compiler, bit, &point[0], &identity_x, span);
+    let y = field::if_else_inner(compiler, bit, &point[1], &identity_y, span);
+    [x[0].clone(), y[0].clone()]
+}
+
+/// Multiplies a point by a scalar given as bits (in little-endian order), with double-and-add.
+pub fn scalar_mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    point: &Point<B>,
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Point<B> {
+    let mut acc = identity::<B>();
+
+    for bit in bits.iter().rev() {
+        acc = double(compiler, curve, &acc, span);
+        let term = select(compiler, bit, point, span);
+        acc = add(compiler, curve, &acc, &term, span);
+    }
+
+    acc
+}
+
+/// Multiplies a constant point by a scalar given as bits (in little-endian order).
+/// The multiples `2^i * base` are computed at compile time, so this only costs one addition per bit.
+pub fn fixed_base_mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    mut base: (B::Field, B::Field),
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Point<B> {
+    let mut acc = identity::<B>();
+
+    for bit in bits {
+        let term = select(compiler, bit, &constant::<B>(base), span);
+        acc = add(compiler, curve, &acc, &term, span);
+        base = curve.double(base);
+    }
+
+    acc
+}
+
+/// Decompresses a point from its `y` coordinate and the parity of its `x` coordinate,
+/// which is constrained to be a boolean.
+/// This fails if there is no point with this `y` coordinate.
+pub fn decompress<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    y: &ConstOrCell<B::Field, B::Var>,
+    is_odd: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Point<B> {
+    boolean::check(compiler, is_odd, span);
+
+    // x^2 = (1 - y^2) / (a - d y^2)
+    let y2 = field::mul(compiler, y, y, span);
+    let one = ConstOrCell::Const(B::Field::one());
+    let num = field::sub(compiler, &one, &y2[0], span);
+    let dy2 = field::mul(compiler, &y2[0], &ConstOrCell::Const(curve.d), span);
+    let den = field::sub(compiler, &ConstOrCell::Const(curve.a), &dy2[0], span);
+    let den = field::inv(compiler, &den[0], span);
+    let x2 = field::mul(compiler, &num[0], &den[0], span);
+
+    // one of the roots has the right parity (they have different parities, as the modulus is odd)
+    let root = field::sqrt(compiler, &x2[0], span);
+    let root_bits = field::to_bits_strict(compiler, &root[0], span);
+    let flip = boolean::xor(compiler, &root_bits[0], is_odd, span);
+
+    // x = root * (1 - 2 flip)
+    let two_flip = field::add(compiler, &flip[0], &flip[0], span);
+    let sign = field::sub(compiler, &one, &two_flip[0], span);
+    let x = field::mul(compiler, &root[0], &sign[0], span);
+
+    [x[0].clone(), y.clone()]
+}
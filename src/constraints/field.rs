@@ -1,15 +1,57 @@
 use crate::{
-    backends::Backend,
+    backends::{Backend, BackendField},
     circuit_writer::CircuitWriter,
     constants::Span,
+    error::{ErrorKind, Result},
     var::{ConstOrCell, Value, Var},
 };
 
-use super::boolean;
+use super::{boolean, packed};
 
-use ark_ff::{One, Zero};
+use ark_ff::{Field, One, SquareRootField, Zero};
+use num_bigint::BigUint;
 
-use std::ops::Neg;
+use std::{ops::Neg, sync::Arc};
+
+/// The largest number of bits that field elements compared with `<`, `<=`, `>`, and `>=` must fit in
+/// (see [comparison_bits]).
+const MAX_COMPARISON_BITS: usize = 252;
+
+/// The largest number of bits that the operands of `&`, `|`, and `^` are decomposed into
+/// (see [bitwise_bits]).
+const MAX_BITWISE_BITS: usize = 252;
+
+/// The largest number of bits that [range_check] checks with a single lookup,
+/// in a table of all the values that fit.
+pub const TABLE_RANGE_CHECK_BITS: usize = 8;
+
+/// Returns the number of bits of the modulus of the field.
+pub fn modulus_bits<F: BackendField>() -> usize {
+    let modulus_minus_one: BigUint = (-F::one()).into();
+    let modulus = modulus_minus_one + 1u8;
+    modulus.bits() as usize
+}
+
+/// Returns the number of bits that field elements compared with `<`, `<=`, `>`, and `>=` must fit in
+/// (they are range-checked before being compared, as [less_than] is only correct for values that fit).
+/// We need one more bit for the comparison gadget, and that bit must not wrap around the field modulus,
+/// so small fields (like Goldilocks) compare fewer bits.
+pub fn comparison_bits<F: BackendField>() -> usize {
+    MAX_COMPARISON_BITS.min(modulus_bits::<F>() - 2)
+}
+
+/// Returns the number of bits that the operands of `&`, `|`, and `^` are decomposed into.
+/// The decomposition must be unique, so it is smaller than the field modulus.
+pub fn bitwise_bits<F: BackendField>() -> usize {
+    MAX_BITWISE_BITS.min(modulus_bits::<F>() - 1)
+}
+
+/// Returns the number of bits that the operands of `/` and `%` are assumed to fit in.
+/// This ensures that `quotient * divisor + remainder` can't wrap around the field modulus (see [div_mod]),
+/// and that the remainder can be compared to the divisor.
+pub fn division_bits<F: BackendField>() -> usize {
+    comparison_bits::<F>() / 2
+}
 
 /// Negates a field element
 pub fn neg<B: Backend>(
@@ -99,6 +141,242 @@ pub fn mul<B: Backend>(
     }
 }
 
+/// Returns the inverse of a field element, and constrains it to be non-zero.
+pub fn inv<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cvar: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    match cvar {
+        ConstOrCell::Const(cst) => {
+            let inverse = cst.inverse().expect("cannot compute the inverse of zero");
+            Var::new_constant(inverse, span)
+        }
+        ConstOrCell::Cell(cvar) => {
+            // the inverse is computed out of circuit
+            let inverse = compiler
+                .backend
+                .new_internal_var(Value::NonZeroInverse(cvar.clone()), span);
+
+            // cvar * inverse = 1
+            let res = compiler.backend.mul(cvar, &inverse, span);
+            compiler
+                .backend
+                .assert_eq_const(&res, B::Field::one(), span);
+
+            Var::new_var(inverse, span)
+        }
+    }
+}
+
+/// Returns a square root of a field element, and constrains it.
+/// The field element must be a square.
+pub fn sqrt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cvar: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    match cvar {
+        ConstOrCell::Const(cst) => {
+            let root = smallest_root(*cst).expect("cannot compute the square root of a non-square");
+            Var::new_constant(root, span)
+        }
+        ConstOrCell::Cell(cvar) => {
+            // the root is computed out of circuit
+            let root = compiler
+                .backend
+                .new_internal_var(Value::Sqrt(cvar.clone()), span);
+
+            // root * root = cvar
+            let res = compiler.backend.mul(&root, &root, span);
+            compiler.backend.assert_eq_var(&res, cvar, span);
+
+            Var::new_var(root, span)
+        }
+    }
+}
+
+/// Returns whether a field element is a square (1 if it is, 0 otherwise),
+/// as well as a square root of the element if it is a square,
+/// or a square root of the element multiplied by [smallest_non_residue] otherwise.
+/// Both cases are constrained, so this can be used to prove that an element is not a square.
+pub fn try_sqrt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cvar: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> (Var<B::Field, B::Var>, Var<B::Field, B::Var>) {
+    let non_residue = smallest_non_residue::<B::Field>();
+
+    if let ConstOrCell::Const(cst) = cvar {
+        let (is_square, selected) = if cst.legendre().is_qnr() {
+            (B::Field::zero(), *cst * non_residue)
+        } else {
+            (B::Field::one(), *cst)
+        };
+        let root = smallest_root(selected).expect("the selected element is a square");
+        return (
+            Var::new_constant(is_square, span),
+            Var::new_constant(root, span),
+        );
+    }
+
+    // whether the element is a square is computed out of circuit
+    let is_square = compiler.backend.new_internal_var(
+        Value::Compute(
+            vec![cvar.clone()],
+            Arc::new(|values: &[B::Field]| {
+                if values[0].legendre().is_qnr() {
+                    B::Field::zero()
+                } else {
+                    B::Field::one()
+                }
+            }),
+        ),
+        span,
+    );
+    let is_square = ConstOrCell::Cell(is_square);
+    boolean::check(compiler, &is_square, span);
+
+    // The product of a non-residue and a non-zero square is not a square,
+    // so the root can only exist if `is_square` is correct.
+    let scaled = mul(compiler, cvar, &ConstOrCell::Const(non_residue), span);
+    let selected = if_else_inner(compiler, &is_square, cvar, &scaled[0], span);
+    let root = sqrt(compiler, &selected[0], span);
+
+    // zero is a square, but the above doesn't prevent claiming otherwise (as `0 * non_residue = 0`)
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let is_zero = equal_cells(compiler, cvar, &zero, span);
+    let not_square = boolean::not(compiler, &is_square, span);
+    let zero_not_square = boolean::and(compiler, &is_zero[0], &not_square[0], span);
+    match &zero_not_square[0] {
+        ConstOrCell::Const(cst) => assert!(cst.is_zero()),
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(cvar, B::Field::zero(), span),
+    }
+
+    (Var::new_cvar(is_square, span), root)
+}
+
+/// Returns 1 if the field element is a square (i.e. its Legendre symbol is not -1), 0 otherwise.
+pub fn is_square<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cvar: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    // the root is only needed to constrain the result
+    let (is_square, _root) = try_sqrt(compiler, cvar, span);
+    is_square
+}
+
+/// Returns the smallest of the two square roots of a field element,
+/// or `None` if the field element is not a square.
+pub fn smallest_root<F: BackendField>(val: F) -> Option<F> {
+    let root = val.sqrt()?;
+    let neg_root = -root;
+
+    let root_big: BigUint = root.into();
+    let neg_root_big: BigUint = neg_root.into();
+    if root_big <= neg_root_big {
+        Some(root)
+    } else {
+        Some(neg_root)
+    }
+}
+
+/// Returns the smallest quadratic non-residue of the field.
+pub fn smallest_non_residue<F: BackendField>() -> F {
+    let mut candidate = F::from(2u64);
+    while !candidate.legendre().is_qnr() {
+        candidate += F::one();
+    }
+    candidate
+}
+
+/// Returns the smallest `alpha` such that `x -> x^alpha` is a permutation of the field
+/// (i.e. `alpha` is coprime with `p - 1`), as used by the S-boxes of arithmetization-oriented hash functions.
+pub fn smallest_permutation_exponent<F: BackendField>() -> u64 {
+    let modulus_minus_one: BigUint = (-F::one()).into();
+    [3u64, 5, 7, 11]
+        .into_iter()
+        .find(|alpha| !(&modulus_minus_one % *alpha).is_zero())
+        .expect("no small permutation exponent for this field")
+}
+
+/// Returns the inverse of a permutation exponent `alpha` modulo `p - 1`,
+/// so that `x -> x^(1/alpha)` is the inverse of `x -> x^alpha`.
+pub fn inverse_exponent<F: BackendField>(alpha: u64) -> BigUint {
+    let modulus_minus_one: BigUint = (-F::one()).into();
+
+    // the inverse is `(k * (p - 1) + 1) / alpha` for some `k < alpha`
+    (1..alpha)
+        .map(|kk| kk * &modulus_minus_one + 1u8)
+        .find(|multiple| (multiple % alpha).is_zero())
+        .map(|multiple| multiple / alpha)
+        .expect("alpha is coprime with p - 1")
+}
+
+/// Raises a field element to a constant power,
+/// using square-and-multiply so that only `O(log exp)` multiplications are needed.
+pub fn pow<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    base: &ConstOrCell<B::Field, B::Var>,
+    exp: &BigUint,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    // x^0 = 1
+    if exp.is_zero() {
+        return Var::new_constant(B::Field::one(), span);
+    }
+
+    // go through the bits of the exponent, starting from the most significant one
+    let mut acc = base.clone();
+    for ii in (0..exp.bits() - 1).rev() {
+        acc = mul(compiler, &acc, &acc, span)[0].clone();
+
+        if exp.bit(ii) {
+            acc = mul(compiler, &acc, base, span)[0].clone();
+        }
+    }
+
+    Var::new_cvar(acc, span)
+}
+
+/// Returns `x^(1/alpha)` (see [inverse_exponent]), which would be very expensive to compute in the circuit:
+/// instead, the root `y` is computed out of circuit, and constrained with `y^alpha = x`.
+pub fn root<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    x: &ConstOrCell<B::Field, B::Var>,
+    alpha: &BigUint,
+    alpha_inv: &BigUint,
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    let exponent = alpha_inv.to_u64_digits();
+
+    match x {
+        ConstOrCell::Const(cst) => ConstOrCell::Const(cst.pow(&exponent)),
+        ConstOrCell::Cell(cvar) => {
+            let root = compiler.backend.new_internal_var(
+                Value::Compute(
+                    vec![x.clone()],
+                    Arc::new(move |vals| vals[0].pow(&exponent)),
+                ),
+                span,
+            );
+            let root = ConstOrCell::Cell(root);
+
+            // root^alpha = x
+            let res = pow(compiler, &root, alpha, span);
+            match &res[0] {
+                ConstOrCell::Cell(res) => compiler.backend.assert_eq_var(res, cvar, span),
+                ConstOrCell::Const(_) => unreachable!("the root is a cell"),
+            }
+
+            root
+        }
+    }
+}
+
 /// This takes variables that can be anything, and returns a boolean
 // TODO: so perhaps it's not really relevant in this file?
 pub fn equal<B: Backend>(
@@ -114,15 +392,10 @@ pub fn equal<B: Backend>(
         return equal_cells(compiler, &lhs[0], &rhs[0], span);
     }
 
-    // create an accumulator
-    let one = B::Field::one();
-
-    let acc = compiler.backend.add_constant(
-        Some("start accumulator at 1 for the equality check"),
-        one,
-        span,
-    );
-    let mut acc = Var::new_var(acc, span);
+    // accumulate the product of the per-element equality bits,
+    // starting from the first bit (instead of a constant 1 encoded in the circuit)
+    // so that constant bits are folded and `n` elements only cost `n - 1` multiplications
+    let mut acc = Var::new_constant(B::Field::one(), span);
 
     for (l, r) in lhs.cvars.iter().zip(&rhs.cvars) {
         let res = equal_cells(compiler, l, r, span);
@@ -133,7 +406,7 @@ pub fn equal<B: Backend>(
 }
 
 /// Returns a new variable set to 1 if x1 is equal to x2, 0 otherwise.
-fn equal_cells<B: Backend>(
+pub fn equal_cells<B: Backend>(
     compiler: &mut CircuitWriter<B>,
     x1: &ConstOrCell<B::Field, B::Var>,
     x2: &ConstOrCell<B::Field, B::Var>,
@@ -295,6 +568,9 @@ fn is_zero_cell<B: Backend>(
     }
 }
 
+/// Returns `then_` if `cond` is true, `else_` otherwise, element by element.
+/// As with [if_else_inner], `cond` is assumed to be a boolean.
+/// This is cheaper than calling [if_else_inner] on each element, as `1 - cond` is only computed once.
 pub fn if_else<B: Backend>(
     compiler: &mut CircuitWriter<B>,
     cond: &Var<B::Field, B::Var>,
@@ -307,16 +583,33 @@ pub fn if_else<B: Backend>(
 
     let cond = &cond[0];
 
+    // if cond is constant, easy
+    if let ConstOrCell::Const(cond) = cond {
+        if cond.is_one() {
+            return then_.clone();
+        } else {
+            return else_.clone();
+        }
+    }
+
+    // `1 - cond` is shared by all the elements
+    let mut one_minus_cond = None;
+
     let mut vars = vec![];
 
     for (then_, else_) in then_.cvars.iter().zip(&else_.cvars) {
-        let var = if_else_inner(compiler, cond, then_, else_, span);
+        let var = select(compiler, cond, &mut one_minus_cond, then_, else_, span);
         vars.push(var[0].clone());
     }
 
     Var::new(vars, span)
 }
 
+/// Returns `then_` if `cond` is true, `else_` otherwise.
+///
+/// Note that `cond` is NOT constrained to be a boolean:
+/// the caller must make sure that it is (for example, because it was produced by a comparison,
+/// or because it has the type `Bool`). Otherwise, use [if_else_checked].
 pub fn if_else_inner<B: Backend>(
     compiler: &mut CircuitWriter<B>,
     cond: &ConstOrCell<B::Field, B::Var>,
@@ -339,9 +632,823 @@ pub fn if_else_inner<B: Backend>(
     }
 
     // determine the result via arithemtic
+    select(compiler, cond, &mut None, then_, else_, span)
+}
+
+/// Returns `cond * then_ + (1 - cond) * else_`,
+/// where `1 - cond` is only computed if it wasn't already (so that it can be reused).
+fn select<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cond: &ConstOrCell<B::Field, B::Var>,
+    one_minus_cond: &mut Option<ConstOrCell<B::Field, B::Var>>,
+    then_: &ConstOrCell<B::Field, B::Var>,
+    else_: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
     let cond_then = mul(compiler, then_, cond, span);
-    let one = ConstOrCell::Const(B::Field::one());
-    let one_minus_cond = sub(compiler, &one, cond, span);
-    let temp = mul(compiler, &one_minus_cond[0], else_, span);
+    let one_minus_cond = one_minus_cond.get_or_insert_with(|| {
+        let one = ConstOrCell::Const(B::Field::one());
+        sub(compiler, &one, cond, span)[0].clone()
+    });
+    let temp = mul(compiler, one_minus_cond, else_, span);
     add(compiler, &cond_then[0], &temp[0], span)
 }
+
+/// Like [if_else_inner], but also constrains `cond` to be a boolean,
+/// for conditions that are not known to be booleans.
+pub fn if_else_checked<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cond: &ConstOrCell<B::Field, B::Var>,
+    then_: &ConstOrCell<B::Field, B::Var>,
+    else_: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    boolean::check(compiler, cond, span);
+    if_else_inner(compiler, cond, then_, else_, span)
+}
+
+/// Returns the `idx`-th element of an array of `array_len` elements of `elem_len` cells each,
+/// where `idx` is only known at runtime.
+/// This is done with a multiplexer: the sum of the elements multiplied by the selectors `idx == i`.
+/// The index is constrained to be in bounds (see [index_selectors]).
+pub fn dynamic_index<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    array: &Var<B::Field, B::Var>,
+    array_len: usize,
+    elem_len: usize,
+    idx: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    assert_eq!(array.len(), array_len * elem_len);
+
+    let selectors = index_selectors(compiler, array_len, idx, span);
+    let mut res = vec![ConstOrCell::Const(B::Field::zero()); elem_len];
+
+    for (ii, is_selected) in selectors.iter().enumerate() {
+        // res += is_selected * array[ii]
+        for (jj, acc) in res.iter_mut().enumerate() {
+            let cell = &array[ii * elem_len + jj];
+            let selected = mul(compiler, is_selected, cell, span);
+            *acc = add(compiler, acc, &selected[0], span)[0].clone();
+        }
+    }
+
+    Var::new(res, span)
+}
+
+/// Returns a copy of an array of `array_len` elements of `elem_len` cells each,
+/// where the `idx`-th element is replaced by `value`, and `idx` is only known at runtime.
+/// Every element goes through a multiplexer keeping either the old element or `value`.
+/// The index is constrained to be in bounds (see [index_selectors]).
+pub fn dynamic_update<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    array: &Var<B::Field, B::Var>,
+    array_len: usize,
+    elem_len: usize,
+    idx: &ConstOrCell<B::Field, B::Var>,
+    value: &Var<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    assert_eq!(array.len(), array_len * elem_len);
+    assert_eq!(value.len(), elem_len);
+
+    let selectors = index_selectors(compiler, array_len, idx, span);
+    let mut res = Vec::with_capacity(array.len());
+
+    for (ii, is_selected) in selectors.into_iter().enumerate() {
+        let is_selected = Var::new_cvar(is_selected, span);
+        let elem = Var::new(array.range(ii * elem_len, elem_len).to_vec(), span);
+        let new_elem = if_else(compiler, &is_selected, value, &elem, span);
+        res.extend(new_elem.cvars);
+    }
+
+    Var::new(res, span)
+}
+
+/// Returns the selectors `idx == i` for every position `i` of an array of `array_len` elements,
+/// and constrains exactly one of them to be set (so that `idx` is in bounds).
+fn index_selectors<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    array_len: usize,
+    idx: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    let mut selectors = Vec::with_capacity(array_len);
+    let mut num_selected = ConstOrCell::Const(B::Field::zero());
+
+    for ii in 0..array_len {
+        let position = ConstOrCell::Const(B::Field::from(ii as u64));
+        let is_selected = equal_cells(compiler, idx, &position, span)[0].clone();
+        num_selected = add(compiler, &num_selected, &is_selected, span)[0].clone();
+        selectors.push(is_selected);
+    }
+
+    // the index must be in bounds
+    match &num_selected {
+        ConstOrCell::Const(cst) => assert!(cst.is_one(), "array index out of bounds"),
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(cvar, B::Field::one(), span),
+    }
+
+    selectors
+}
+
+/// Decomposes a field element into `num_bits` bits (in little-endian order),
+/// and constrains the bits to be booleans that recompose to the field element.
+pub fn to_bits<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    var: &ConstOrCell<B::Field, B::Var>,
+    num_bits: usize,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    match var {
+        ConstOrCell::Const(cst) => {
+            let bigint: BigUint = (*cst).into();
+            assert!(bigint.bits() as usize <= num_bits);
+
+            let bits = (0..num_bits)
+                .map(|ii| {
+                    let bit = if bigint.bit(ii as u64) {
+                        B::Field::one()
+                    } else {
+                        B::Field::zero()
+                    };
+                    ConstOrCell::Const(bit)
+                })
+                .collect();
+
+            Var::new(bits, span)
+        }
+        ConstOrCell::Cell(cvar) => {
+            let mut bits = Vec::with_capacity(num_bits);
+            let mut acc: Option<ConstOrCell<B::Field, B::Var>> = None;
+            let mut power_of_two = B::Field::one();
+
+            for ii in 0..num_bits {
+                // the bit is computed out of circuit
+                let bit = compiler
+                    .backend
+                    .new_internal_var(Value::NthBit(cvar.clone(), ii), span);
+                let bit = ConstOrCell::Cell(bit);
+
+                // and then constrained to be a boolean
+                boolean::check(compiler, &bit, span);
+
+                // acc += bit * 2^ii
+                let term = mul(compiler, &bit, &ConstOrCell::Const(power_of_two), span);
+                acc = Some(match acc {
+                    None => term[0].clone(),
+                    Some(acc) => add(compiler, &acc, &term[0], span)[0].clone(),
+                });
+
+                power_of_two.double_in_place();
+                bits.push(bit);
+            }
+
+            // the bits must recompose to the original value
+            if let Some(ConstOrCell::Cell(acc)) = acc {
+                compiler.backend.assert_eq_var(&acc, cvar, span);
+            }
+
+            Var::new(bits, span)
+        }
+    }
+}
+
+/// Decomposes a field element into bits (in little-endian order),
+/// using as many bits as the field modulus.
+/// Unlike [to_bits], this also constrains the decomposition to be smaller than the modulus,
+/// so that every field element has a unique (canonical) bit representation.
+pub fn to_bits_strict<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    var: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let modulus_minus_one: BigUint = (-B::Field::one()).into();
+    let modulus = modulus_minus_one + 1u8;
+    let num_bits = modulus.bits() as usize;
+
+    let bits = to_bits(compiler, var, num_bits, span);
+
+    // a constant is always decomposed canonically
+    if var.is_const() {
+        return bits;
+    }
+
+    // the decomposition must be smaller than the modulus
+    let is_lt = bits_less_than(compiler, &bits.cvars, &modulus, span);
+    match is_lt {
+        ConstOrCell::Const(cst) => assert!(cst.is_one()),
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(&cvar, B::Field::one(), span),
+    }
+
+    bits
+}
+
+/// Returns whether the integer represented by bits (in little-endian order) is smaller than a constant
+/// (1 if it is, 0 otherwise).
+pub fn bits_less_than<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    bound: &BigUint,
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    // a bound that doesn't fit in the bits is larger than any of them
+    if bound.bits() as usize > bits.len() {
+        return ConstOrCell::Const(B::Field::one());
+    }
+
+    // we compare the bits with the bound, starting from the most significant bit.
+    // `is_lt` is set once a bit is found to be smaller than the bound's bit,
+    // as long as all the previous bits were equal.
+    let mut is_lt = ConstOrCell::Const(B::Field::zero());
+    let mut is_eq = ConstOrCell::Const(B::Field::one());
+
+    for (ii, bit) in bits.iter().enumerate().rev() {
+        let not_bit = boolean::not(compiler, bit, span);
+
+        if bound.bit(ii as u64) {
+            // is_lt |= is_eq & !bit
+            let smaller = boolean::and(compiler, &is_eq, &not_bit[0], span);
+            is_lt = boolean::or(compiler, &is_lt, &smaller[0], span)[0].clone();
+
+            // is_eq &= bit
+            is_eq = boolean::and(compiler, &is_eq, bit, span)[0].clone();
+        } else {
+            // is_eq &= !bit
+            is_eq = boolean::and(compiler, &is_eq, &not_bit[0], span)[0].clone();
+        }
+    }
+
+    is_lt
+}
+
+/// Constrains two cells to be equal, and fails if they are different constants.
+pub fn assert_eq_cells<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<()> {
+    assert_eq_all(compiler, &[(lhs.clone(), rhs.clone())], span)
+}
+
+/// Constrains the cells of each pair to be equal, and fails if two constants are different.
+/// The equalities between variables are asserted together (see [Backend::assert_eq_vars]).
+pub fn assert_eq_all<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    pairs: &[(ConstOrCell<B::Field, B::Var>, ConstOrCell<B::Field, B::Var>)],
+    span: Span,
+) -> Result<()> {
+    let mut var_pairs = vec![];
+
+    for (lhs, rhs) in pairs {
+        match (lhs, rhs) {
+            (ConstOrCell::Const(lhs), ConstOrCell::Const(rhs)) => {
+                if lhs != rhs {
+                    return Err(compiler.error(ErrorKind::AssertionFailed, span));
+                }
+            }
+            (ConstOrCell::Const(cst), ConstOrCell::Cell(cvar))
+            | (ConstOrCell::Cell(cvar), ConstOrCell::Const(cst)) => {
+                compiler.backend.assert_eq_const(cvar, *cst, span)
+            }
+            (ConstOrCell::Cell(lhs), ConstOrCell::Cell(rhs)) => {
+                var_pairs.push((lhs.clone(), rhs.clone()))
+            }
+        }
+    }
+
+    if !var_pairs.is_empty() {
+        compiler.backend.assert_eq_vars(&var_pairs, span);
+    }
+
+    Ok(())
+}
+
+/// Returns the entry of a constant table at an index given as bits (in little-endian order),
+/// with a multiplexer: each bit halves the candidates, starting from the least significant one.
+/// The table must have `2^bits.len()` entries, and the bits are assumed to be booleans.
+pub fn constant_lookup<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    table: &[B::Field],
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    assert_eq!(table.len(), 1 << bits.len());
+
+    let mut candidates: Vec<_> = table
+        .iter()
+        .map(|entry| ConstOrCell::Const(*entry))
+        .collect();
+    for bit in bits {
+        candidates = candidates
+            .chunks(2)
+            .map(|pair| {
+                // pair[0] + bit * (pair[1] - pair[0])
+                let diff = sub(compiler, &pair[1], &pair[0], span);
+                let term = mul(compiler, bit, &diff[0], span);
+                add(compiler, &pair[0], &term[0], span)[0].clone()
+            })
+            .collect();
+    }
+
+    candidates.swap_remove(0)
+}
+
+/// Packs bits (in little-endian order) into a field element.
+pub fn from_bits<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let mut acc = ConstOrCell::Const(B::Field::zero());
+    let mut power_of_two = B::Field::one();
+
+    for bit in bits {
+        // acc += bit * 2^ii
+        let term = mul(compiler, bit, &ConstOrCell::Const(power_of_two), span);
+        acc = add(compiler, &acc, &term[0], span)[0].clone();
+
+        power_of_two.double_in_place();
+    }
+
+    Var::new_cvar(acc, span)
+}
+
+/// Applies a boolean operation bit by bit on the [bitwise_bits]-bit decompositions of two field elements,
+/// and packs the resulting bits back into a field element.
+/// A constant operand that doesn't fit in [bitwise_bits] bits is an error.
+pub fn bitwise<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    op: BitwiseOp,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let num_bits = bitwise_bits::<B::Field>();
+    for operand in [lhs, rhs] {
+        if let ConstOrCell::Const(cst) = operand {
+            let bigint: BigUint = (*cst).into();
+            if bigint.bits() as usize > num_bits {
+                return Err(compiler.error(ErrorKind::IntegerOverflow(num_bits), span));
+            }
+        }
+    }
+
+    let lhs_bits = to_bits(compiler, lhs, num_bits, span);
+    let rhs_bits = to_bits(compiler, rhs, num_bits, span);
+
+    let bits: Vec<_> = lhs_bits
+        .cvars
+        .iter()
+        .zip(&rhs_bits.cvars)
+        .map(|(lhs, rhs)| {
+            let res = match op {
+                BitwiseOp::And => boolean::and(compiler, lhs, rhs, span),
+                BitwiseOp::Or => boolean::or(compiler, lhs, rhs, span),
+                BitwiseOp::Xor => boolean::xor(compiler, lhs, rhs, span),
+            };
+            res[0].clone()
+        })
+        .collect();
+
+    Ok(from_bits(compiler, &bits, span))
+}
+
+/// The boolean operations supported by [bitwise].
+#[derive(Debug, Clone, Copy)]
+pub enum BitwiseOp {
+    And,
+    Or,
+    Xor,
+}
+
+/// Returns 1 if lhs < rhs, 0 otherwise.
+/// Both values are assumed to fit in `num_bits` bits,
+/// the result is meaningless otherwise.
+/// Comparing variables of more bits than the field can compare (see [comparison_bits]) is an error.
+pub fn less_than<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    num_bits: usize,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    match (lhs, rhs) {
+        // two constants
+        (ConstOrCell::Const(lhs), ConstOrCell::Const(rhs)) => {
+            let lhs: BigUint = (*lhs).into();
+            let rhs: BigUint = (*rhs).into();
+            let res = if lhs < rhs {
+                B::Field::one()
+            } else {
+                B::Field::zero()
+            };
+            Ok(Var::new_constant(res, span))
+        }
+
+        // at least one var
+        (lhs, rhs) => {
+            // the `num_bits + 1` bits of the difference must not wrap around the field modulus
+            if num_bits > modulus_bits::<B::Field>() - 2 {
+                return Err(compiler.error(ErrorKind::FieldTooSmall(num_bits), span));
+            }
+
+            // We compute `diff = lhs - rhs + 2^num_bits`.
+            // As both values fit in `num_bits` bits, `diff` fits in `num_bits + 1` bits,
+            // and its most significant bit is set if and only if `lhs >= rhs`.
+            let two = B::Field::from(2u64);
+            let power_of_two = two.pow([num_bits as u64]);
+
+            let diff = sub(compiler, lhs, rhs, span);
+            let diff = add(compiler, &diff[0], &ConstOrCell::Const(power_of_two), span);
+
+            let bits = to_bits(compiler, &diff[0], num_bits + 1, span);
+
+            Ok(boolean::not(compiler, &bits[num_bits], span))
+        }
+    }
+}
+
+/// Returns the offset `2^(num_bits - 1)` that maps signed integers of `num_bits` bits
+/// to unsigned integers of `num_bits` bits (while preserving their order).
+pub fn signed_offset<B: Backend>(num_bits: usize) -> B::Field {
+    B::Field::from(2u64).pow([num_bits as u64 - 1])
+}
+
+/// Returns 1 if lhs < rhs, 0 otherwise,
+/// where both values are signed integers that fit in `num_bits` bits
+/// (negative values `-x` being encoded as the field element `p - x`).
+pub fn less_than_signed<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    num_bits: usize,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let offset = ConstOrCell::Const(signed_offset::<B>(num_bits));
+    let lhs = add(compiler, lhs, &offset, span);
+    let rhs = add(compiler, rhs, &offset, span);
+
+    less_than(compiler, num_bits, &lhs[0], &rhs[0], span)
+}
+
+/// Returns the smallest of two values.
+/// The values are compared as signed integers of `signed_bits` bits if set,
+/// and as field elements of [comparison_bits] bits otherwise.
+pub fn min<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    signed_bits: Option<usize>,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let lhs_lt_rhs = compare(compiler, signed_bits, lhs, rhs, span)?;
+    Ok(if_else_inner(compiler, &lhs_lt_rhs[0], lhs, rhs, span))
+}
+
+/// Returns the largest of two values (see [min] for how they are compared).
+pub fn max<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    signed_bits: Option<usize>,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let lhs_lt_rhs = compare(compiler, signed_bits, lhs, rhs, span)?;
+    Ok(if_else_inner(compiler, &lhs_lt_rhs[0], rhs, lhs, span))
+}
+
+/// Returns the absolute value of a signed integer that fits in `num_bits` bits
+/// (negative values `-x` being encoded as the field element `p - x`).
+pub fn abs<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    num_bits: usize,
+    val: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let is_negative = less_than_signed(compiler, num_bits, val, &zero, span)?;
+    let negated = neg(compiler, val, span);
+    Ok(if_else_inner(
+        compiler,
+        &is_negative[0],
+        &negated[0],
+        val,
+        span,
+    ))
+}
+
+/// Returns 1 if lhs < rhs, 0 otherwise (see [min] for how they are compared).
+pub fn compare<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    signed_bits: Option<usize>,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    match signed_bits {
+        Some(num_bits) => less_than_signed(compiler, num_bits, lhs, rhs, span),
+        None => less_than(compiler, comparison_bits::<B::Field>(), lhs, rhs, span),
+    }
+}
+
+/// Returns an error if values of `num_bits` bits can't be range-checked in the field of the backend:
+/// from `modulus_bits - 1` bits, the range covers half of the field or more
+/// (and all of it from `modulus_bits` bits, like 64-bit integers over Goldilocks), so the check would be meaningless.
+pub fn check_range_bits<B: Backend>(
+    compiler: &CircuitWriter<B>,
+    num_bits: usize,
+    span: Span,
+) -> Result<()> {
+    if num_bits >= modulus_bits::<B::Field>() - 1 {
+        return Err(compiler.error(ErrorKind::FieldTooSmall(num_bits), span));
+    }
+
+    Ok(())
+}
+
+/// Constrains a field element to fit in `num_bits` bits.
+/// A constant that doesn't fit is an error, and so is a number of bits that the field is too small for
+/// (see [check_range_bits]).
+///
+/// Backends with lookup arguments check small values with a single lookup (see [TABLE_RANGE_CHECK_BITS]),
+/// and larger values with XOR lookups on chunks of 16 bits (see [packed::range_check]).
+/// Otherwise, the value is decomposed in bits.
+pub fn range_check<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    var: &ConstOrCell<B::Field, B::Var>,
+    num_bits: usize,
+    span: Span,
+) -> Result<()> {
+    check_range_bits(compiler, num_bits, span)?;
+
+    if let ConstOrCell::Const(cst) = var {
+        let bigint: BigUint = (*cst).into();
+        if bigint.bits() as usize > num_bits {
+            return Err(compiler.error(ErrorKind::IntegerOverflow(num_bits), span));
+        }
+
+        return Ok(());
+    }
+
+    if let ConstOrCell::Cell(cvar) = var {
+        // the pair (value, 0) is in the table iff the value fits
+        if num_bits <= TABLE_RANGE_CHECK_BITS && compiler.backend.capabilities().lookup {
+            let table: Vec<_> = (0..1u64 << num_bits)
+                .map(|value| (B::Field::from(value), B::Field::zero()))
+                .collect();
+            let zero = compiler.backend.add_constant(
+                Some("the value of a range check lookup"),
+                B::Field::zero(),
+                span,
+            );
+            compiler.backend.lookup(&table, cvar, &zero, span);
+            return Ok(());
+        }
+
+        // the padded value must not wrap around the field modulus once shifted
+        if num_bits.next_multiple_of(16) <= bitwise_bits::<B::Field>()
+            && compiler.backend.capabilities().xor_lookup
+        {
+            return packed::range_check(compiler, var, num_bits, span);
+        }
+    }
+
+    // the bit decomposition is enough to constrain the value
+    let _bits = to_bits(compiler, var, num_bits, span);
+
+    Ok(())
+}
+
+/// Computes the integer division of `lhs` by `rhs`, and returns the quotient and the remainder.
+/// The divisor, the quotient, and the remainder are constrained to fit in [division_bits] bits
+/// (a constant divisor that doesn't fit is an error).
+pub fn div_mod<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &ConstOrCell<B::Field, B::Var>,
+    rhs: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<(Var<B::Field, B::Var>, Var<B::Field, B::Var>)> {
+    // two constants
+    if let (ConstOrCell::Const(lhs), ConstOrCell::Const(rhs)) = (lhs, rhs) {
+        let lhs: BigUint = (*lhs).into();
+        let rhs: BigUint = (*rhs).into();
+        assert!(!rhs.is_zero());
+
+        let quotient = B::Field::try_from(&lhs / &rhs)
+            .unwrap_or_else(|_| unreachable!("the quotient is smaller than the dividend"));
+        let remainder = B::Field::try_from(lhs % rhs)
+            .unwrap_or_else(|_| unreachable!("the remainder is smaller than the divisor"));
+
+        return Ok((
+            Var::new_constant(quotient, span),
+            Var::new_constant(remainder, span),
+        ));
+    }
+
+    // a constant divisor must fit, like a variable one
+    let num_bits = division_bits::<B::Field>();
+    if let ConstOrCell::Const(cst) = rhs {
+        let bigint: BigUint = (*cst).into();
+        if bigint.bits() as usize > num_bits {
+            return Err(compiler.error(ErrorKind::IntegerOverflow(num_bits), span));
+        }
+    }
+
+    // the witness computation needs actual variables
+    let lhs_var = match lhs {
+        ConstOrCell::Const(cst) => {
+            compiler
+                .backend
+                .add_constant(Some("dividend of an integer division"), *cst, span)
+        }
+        ConstOrCell::Cell(cvar) => cvar.clone(),
+    };
+    let rhs_var = match rhs {
+        ConstOrCell::Const(cst) => {
+            compiler
+                .backend
+                .add_constant(Some("divisor of an integer division"), *cst, span)
+        }
+        ConstOrCell::Cell(cvar) => cvar.clone(),
+    };
+
+    // the quotient and remainder are computed out of circuit
+    let quotient = compiler
+        .backend
+        .new_internal_var(Value::DivMod(lhs_var.clone(), rhs_var.clone(), false), span);
+    let remainder = compiler
+        .backend
+        .new_internal_var(Value::DivMod(lhs_var, rhs_var, true), span);
+    let quotient = ConstOrCell::Cell(quotient);
+    let remainder = ConstOrCell::Cell(remainder);
+
+    // all values must be small enough so that
+    // `quotient * rhs + remainder` doesn't wrap around the modulus
+    range_check(compiler, &quotient, num_bits, span)?;
+    range_check(compiler, &remainder, num_bits, span)?;
+    range_check(compiler, rhs, num_bits, span)?;
+
+    // lhs = quotient * rhs + remainder
+    let res = mul(compiler, &quotient, rhs, span);
+    let res = add(compiler, &res[0], &remainder, span);
+    match (&res[0], lhs) {
+        (ConstOrCell::Cell(res), ConstOrCell::Const(cst)) => {
+            compiler.backend.assert_eq_const(res, *cst, span)
+        }
+        (ConstOrCell::Cell(res), ConstOrCell::Cell(cvar)) => {
+            compiler.backend.assert_eq_var(res, cvar, span)
+        }
+        (ConstOrCell::Const(_), _) => unreachable!("the remainder is a variable"),
+    }
+
+    // remainder < rhs
+    let is_smaller = less_than(compiler, num_bits, &remainder, rhs, span)?;
+    match &is_smaller[0] {
+        ConstOrCell::Cell(is_smaller) => {
+            compiler
+                .backend
+                .assert_eq_const(is_smaller, B::Field::one(), span)
+        }
+        ConstOrCell::Const(_) => unreachable!("the remainder is a variable"),
+    }
+
+    Ok((
+        Var::new_cvar(quotient, span),
+        Var::new_cvar(remainder, span),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backends::{
+            kimchi::{KimchiCellVar, KimchiVesta, VestaField},
+            r1cs::{R1csBls12381Field, R1CS},
+        },
+        type_checker::TypeChecker,
+        witness::WitnessEnv,
+    };
+
+    /// Creates a circuit with a private input of a single field element for each name.
+    fn circuit_with_inputs<B: Backend>(
+        backend: B,
+        names: &[&str],
+    ) -> (CircuitWriter<B>, Vec<ConstOrCell<B::Field, B::Var>>) {
+        let mut compiler = CircuitWriter::new(TypeChecker::new(), backend);
+        compiler.backend.init_circuit();
+
+        let inputs = names
+            .iter()
+            .map(|name| {
+                compiler.add_private_inputs(name.to_string(), 1, Span::default())[0].clone()
+            })
+            .collect();
+
+        (compiler, inputs)
+    }
+
+    /// Returns the number of rows that a gadget adds to a kimchi circuit with the given inputs
+    /// (without the double generic gate optimization, so that each generic gate is a row).
+    fn kimchi_rows(
+        names: &[&str],
+        gadget: impl FnOnce(&mut CircuitWriter<KimchiVesta>, &[ConstOrCell<VestaField, KimchiCellVar>]),
+    ) -> usize {
+        let (mut compiler, inputs) = circuit_with_inputs(KimchiVesta::new(false), names);
+        let before = compiler.backend.debug_info.len();
+        gadget(&mut compiler, &inputs);
+        compiler.backend.debug_info.len() - before
+    }
+
+    #[test]
+    fn test_if_else_checked_rejects_non_boolean_condition() {
+        // selects between 3 and 5, with the given condition
+        let generate_witness = |checked: bool, cond: u64| {
+            let span = Span::default();
+            let (mut compiler, inputs) = circuit_with_inputs(
+                R1CS::<R1csBls12381Field>::new(),
+                &["cond", "then_", "else_"],
+            );
+
+            if checked {
+                if_else_checked(&mut compiler, &inputs[0], &inputs[1], &inputs[2], span);
+            } else {
+                if_else_inner(&mut compiler, &inputs[0], &inputs[1], &inputs[2], span);
+            }
+            compiler.backend.finalize_circuit(None, None, span).unwrap();
+
+            let mut env = WitnessEnv::default();
+            env.add_value("cond".to_string(), vec![cond.into()]);
+            env.add_value("then_".to_string(), vec![3u64.into()]);
+            env.add_value("else_".to_string(), vec![5u64.into()]);
+            compiler.backend.generate_witness(&mut env)
+        };
+
+        // a boolean condition is accepted
+        assert!(generate_witness(true, 0).is_ok());
+        assert!(generate_witness(true, 1).is_ok());
+
+        // 2 selects neither branch (it yields `2 * 3 - 5`), which only the checked variant rejects
+        assert!(generate_witness(false, 2).is_ok());
+        let err = generate_witness(true, 2).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidWitness(_)));
+    }
+
+    #[test]
+    fn test_if_else_shares_one_minus_cond() {
+        let span = Span::default();
+        let names = ["cond", "t0", "t1", "t2", "t3", "e0", "e1", "e2", "e3"];
+
+        // selecting each element on its own computes `1 - cond` every time
+        let separate = kimchi_rows(&names, |compiler, inputs| {
+            for ii in 0..4 {
+                if_else_inner(compiler, &inputs[0], &inputs[1 + ii], &inputs[5 + ii], span);
+            }
+        });
+
+        // while selecting the whole array computes it once
+        let batched = kimchi_rows(&names, |compiler, inputs| {
+            let cond = Var::new_cvar(inputs[0].clone(), span);
+            let then_ = Var::new(inputs[1..5].to_vec(), span);
+            let else_ = Var::new(inputs[5..].to_vec(), span);
+            if_else(compiler, &cond, &then_, &else_, span);
+        });
+
+        let one_minus_cond = kimchi_rows(&["cond"], |compiler, inputs| {
+            let one = ConstOrCell::Const(VestaField::one());
+            sub(compiler, &one, &inputs[0], span);
+        });
+
+        assert!(one_minus_cond > 0);
+        assert_eq!(batched, separate - 3 * one_minus_cond);
+    }
+
+    #[test]
+    fn test_equal_rows() {
+        let span = Span::default();
+
+        let equal_cell = kimchi_rows(&["lhs", "rhs"], |compiler, inputs| {
+            equal_cells(compiler, &inputs[0], &inputs[1], span);
+        });
+        let and = kimchi_rows(&["lhs", "rhs"], |compiler, inputs| {
+            boolean::and(compiler, &inputs[0], &inputs[1], span);
+        });
+
+        // comparing arrays of 4 elements costs 4 equality checks, combined with 3 multiplications
+        // (and no row to encode the constant 1 that the product starts from)
+        let names = ["l0", "l1", "l2", "l3", "r0", "r1", "r2", "r3"];
+        let equal_array = kimchi_rows(&names, |compiler, inputs| {
+            let lhs = Var::new(inputs[..4].to_vec(), span);
+            let rhs = Var::new(inputs[4..].to_vec(), span);
+            equal(compiler, &lhs, &rhs, span);
+        });
+
+        assert_eq!(equal_array, 4 * equal_cell + 3 * and);
+    }
+}
@@ -0,0 +1,107 @@
+//! The Grain LFSR, used by the Poseidon specification to generate round constants
+//! (see [super::poseidon2] and [super::rescue]).
+//!
+//! The LFSR is seeded with a description of the instance (field, S-box, width, and numbers of rounds),
+//! so that different instances get unrelated constants.
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::backends::BackendField;
+
+/// The kind of S-box of the instance, which is part of the seed.
+#[derive(Debug, Clone, Copy)]
+pub enum SboxKind {
+    /// `x^alpha`
+    Power,
+    /// `x^(1/alpha)` (along with `x^alpha`)
+    Inverse,
+}
+
+/// The Grain LFSR, along with the size of the field elements it samples.
+pub struct Grain {
+    /// The last 80 bits produced.
+    state: Vec<bool>,
+
+    /// The number of bits of the field modulus.
+    field_bits: usize,
+
+    /// The field modulus.
+    modulus: BigUint,
+}
+
+impl Grain {
+    pub fn new<F: BackendField>(
+        sbox: SboxKind,
+        width: usize,
+        full_rounds: usize,
+        partial_rounds: usize,
+    ) -> Self {
+        let modulus_minus_one: BigUint = (-F::one()).into();
+        let modulus = modulus_minus_one + 1u8;
+        let field_bits = modulus.bits() as usize;
+
+        // the state is initialized with the description of the instance:
+        // a prime field, the S-box, the field size, the width, and the numbers of rounds
+        let mut state = vec![];
+        let mut push = |value: usize, num_bits: usize| {
+            state.extend((0..num_bits).rev().map(|ii| (value >> ii) & 1 == 1));
+        };
+        push(1, 2);
+        push(sbox as usize, 4);
+        push(field_bits, 12);
+        push(width, 12);
+        push(full_rounds, 10);
+        push(partial_rounds, 10);
+        push((1 << 30) - 1, 30);
+
+        let mut grain = Self {
+            state,
+            field_bits,
+            modulus,
+        };
+
+        // the first 160 bits are discarded
+        for _ in 0..160 {
+            grain.next_bit();
+        }
+
+        grain
+    }
+
+    fn next_bit(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.remove(0);
+        self.state.push(bit);
+        bit
+    }
+
+    /// Returns the next output bit: bits are produced in pairs, and the second bit is only kept if the first is set.
+    fn filtered_bit(&mut self) -> bool {
+        loop {
+            let keep = self.next_bit();
+            let bit = self.next_bit();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// Returns the next field element, sampling big-endian integers of the size of the modulus until one fits.
+    pub fn field_element<F: BackendField>(&mut self) -> F {
+        loop {
+            let mut value = BigUint::zero();
+            for _ in 0..self.field_bits {
+                value <<= 1;
+                if self.filtered_bit() {
+                    value += 1u8;
+                }
+            }
+
+            if value < self.modulus {
+                return F::try_from(value).unwrap_or_else(|_| unreachable!());
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Hashing to the curve embedded in the field of the backend (see [super::curve]), with try-and-increment.
+//!
+//! The input is hashed with Poseidon2 (after a domain tag) into a field element `u`,
+//! and the candidates `u, u + 1, ..., u + ATTEMPTS - 1` are tried in order:
+//! the first one that is the coordinate of a point of the curve is kept
+//! (the `x` coordinate on short Weierstrass curves, and the `y` coordinate on twisted Edwards curves).
+//!
+//! Every candidate is checked in the circuit, and the ones that are skipped are proven not to be on the curve,
+//! so that the hash is a function of the input (which matters for VRFs).
+//! The other coordinate is the even square root, and on twisted Edwards curves,
+//! the point is multiplied by the cofactor to land in the prime-order subgroup.
+
+use ark_ff::{One, Zero};
+
+use crate::{
+    backends::Backend, circuit_writer::CircuitWriter, constants::Span, error::Result,
+    var::ConstOrCell,
+};
+
+use super::{
+    boolean,
+    curve::{EmbeddedCurve, Point},
+    edwards::{self, COFACTOR},
+    field, poseidon2,
+};
+
+/// The number of candidates that are tried.
+/// Each candidate is on the curve with a probability of about one half,
+/// so hashing fails with a negligible probability (about `2^-ATTEMPTS`).
+const ATTEMPTS: usize = 128;
+
+/// Hashes field elements to a point of the embedded curve.
+pub fn hash_to_curve<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    input: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Point<B>> {
+    let tag = poseidon2::domain_tag::<B::Field>("noname_hash_to_curve");
+    let mut tagged = vec![ConstOrCell::Const(tag)];
+    tagged.extend_from_slice(input);
+    let u = poseidon2::hash(compiler, &tagged, span)?;
+
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let one = ConstOrCell::Const(B::Field::one());
+    let mut found = zero.clone();
+    let mut candidate = zero.clone();
+    let mut root = zero;
+
+    for ii in 0..ATTEMPTS {
+        let offset = ConstOrCell::Const(B::Field::from(ii as u64));
+        let coordinate = field::add(compiler, &u, &offset, span)[0].clone();
+
+        // the square of the other coordinate
+        let square = match curve {
+            EmbeddedCurve::Weierstrass(curve) => {
+                // x^3 + b
+                let x2 = field::mul(compiler, &coordinate, &coordinate, span);
+                let x3 = field::mul(compiler, &x2[0], &coordinate, span);
+                field::add(compiler, &x3[0], &ConstOrCell::Const(curve.b), span)
+            }
+            EmbeddedCurve::Edwards(curve) => {
+                // (1 - y^2) / (a - d y^2)
+                let y2 = field::mul(compiler, &coordinate, &coordinate, span);
+                let num = field::sub(compiler, &one, &y2[0], span);
+                let dy2 = field::mul(compiler, &y2[0], &ConstOrCell::Const(curve.d), span);
+                let den = field::sub(compiler, &ConstOrCell::Const(curve.a), &dy2[0], span);
+                let den = field::inv(compiler, &den[0], span);
+                field::mul(compiler, &num[0], &den[0], span)
+            }
+        };
+        let (is_square, square_root) = field::try_sqrt(compiler, &square[0], span);
+
+        // keep the first candidate that is on the curve
+        let not_found = boolean::not(compiler, &found, span);
+        let is_first = boolean::and(compiler, &is_square[0], &not_found[0], span);
+        let kept = field::mul(compiler, &is_first[0], &coordinate, span);
+        candidate = field::add(compiler, &candidate, &kept[0], span)[0].clone();
+        let kept = field::mul(compiler, &is_first[0], &square_root[0], span);
+        root = field::add(compiler, &root, &kept[0], span)[0].clone();
+        found = boolean::or(compiler, &found, &is_square[0], span)[0].clone();
+    }
+
+    field::assert_eq_cells(compiler, &found, &one, span)?;
+
+    // the other coordinate is the even root: the root is negated if it is odd
+    let root_bits = field::to_bits_strict(compiler, &root, span);
+    let two_odd = field::add(compiler, &root_bits[0], &root_bits[0], span);
+    let sign = field::sub(compiler, &one, &two_odd[0], span);
+    let root = field::mul(compiler, &root, &sign[0], span)[0].clone();
+
+    let point = match curve {
+        EmbeddedCurve::Weierstrass(_) => [candidate, root],
+        EmbeddedCurve::Edwards(curve) => {
+            let mut point = [root, candidate];
+            for _ in 0..COFACTOR.trailing_zeros() {
+                point = edwards::double(compiler, curve, &point, span);
+            }
+            point
+        }
+    };
+
+    Ok(point)
+}
@@ -0,0 +1,650 @@
+//! Extraction of a value from a JSON document (RFC 8259), at a path of keys known at compile time.
+//!
+//! The document is checked to be well-formed by an automaton that reads it byte by byte (see [State]),
+//! along with a stack of the containers (objects and arrays) that are open, of at most [MAX_DEPTH] levels.
+//! Strings are not checked to be valid UTF-8.
+//!
+//! The path is a sequence of keys separated by dots, like `claims.email`,
+//! which must lead from the root object to a value that appears exactly once in the document.
+//! Keys are compared with their raw bytes in the document, so a key with escaped characters never matches.
+//!
+//! The value is returned as the bytes that represent it in the document (with the quotes of strings),
+//! followed by zeros (which can't appear in JSON).
+//! There are no hints from the prover: the bytes of the value are flagged while reading the document,
+//! and moved to the start of the output with a barrel shifter.
+
+use ark_ff::{One, Zero};
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::{ConstOrCell, Var},
+};
+
+use super::{field, regex::byte_ranges};
+
+/// The maximum number of nested containers.
+pub const MAX_DEPTH: usize = 8;
+
+/// A cell of the circuit.
+type Cell<B> = ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>;
+
+/// A set of bytes.
+type ByteSet = [bool; 256];
+
+/// The states of the automaton, which tracks both the tokens and the grammar of JSON.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// Expects a value (at the start, after `:`, or after `,` in an array).
+    Value,
+
+    /// After `{`: expects a key or `}`.
+    ObjectStart,
+
+    /// After `[`: expects a value or `]`.
+    ArrayStart,
+
+    /// After `,` in an object: expects a key.
+    Key,
+
+    KeyString,
+    KeyEscape,
+
+    /// In a `\uXXXX` escape of a key, with the number of hexadecimal digits left.
+    KeyUnicode(u8),
+
+    /// After a key: expects `:`.
+    Colon,
+
+    String,
+    StringEscape,
+    StringUnicode(u8),
+
+    /// After a value: expects `,` or the end of the container.
+    AfterValue,
+
+    /// After `,`, which is replaced right away by [State::Key] in objects, and by [State::Value] in arrays.
+    Comma,
+
+    Minus,
+    Zero,
+    Integer,
+    Dot,
+    Fraction,
+    Exponent,
+    ExponentSign,
+    ExponentDigits,
+
+    /// In `true`, `false`, or `null`, with the bytes left.
+    Literal(&'static [u8]),
+}
+
+/// The states where the document can end (at depth 0).
+const FINAL_STATES: [State; 5] = [
+    State::AfterValue,
+    State::Zero,
+    State::Integer,
+    State::Fraction,
+    State::ExponentDigits,
+];
+
+/// What a transition of the automaton means for the stack and the path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Event {
+    OpenObject,
+    OpenArray,
+    CloseObject,
+    CloseArray,
+
+    /// The first byte of a string, a number, or a literal.
+    ScalarStart,
+
+    /// The last byte of a string or a literal.
+    ScalarEnd,
+
+    /// The byte following a number (which is not part of it).
+    NumberEnd,
+
+    KeyStart,
+    KeyEnd,
+}
+
+const NUM_EVENTS: usize = 9;
+
+/// A transition of the automaton.
+struct Transition {
+    from: State,
+    bytes: ByteSet,
+    to: State,
+    events: &'static [Event],
+}
+
+/// Returns the set of the bytes of a list.
+fn bytes(list: &[u8]) -> ByteSet {
+    let mut set = [false; 256];
+    for byte in list {
+        set[*byte as usize] = true;
+    }
+    set
+}
+
+/// Returns the set of the bytes in `[first, last]`.
+fn range(first: u8, last: u8) -> ByteSet {
+    let mut set = [false; 256];
+    for byte in first..=last {
+        set[byte as usize] = true;
+    }
+    set
+}
+
+/// Returns the transitions of the automaton.
+fn transitions() -> Vec<Transition> {
+    use Event::*;
+    use State::*;
+
+    let whitespace = bytes(b" \t\n\r");
+    let digits = range(b'0', b'9');
+    let mut hex_digits = digits;
+    for (first, last) in [(b'a', b'f'), (b'A', b'F')] {
+        for byte in first..=last {
+            hex_digits[byte as usize] = true;
+        }
+    }
+    let mut unescaped = range(0x20, 0xff);
+    unescaped[b'"' as usize] = false;
+    unescaped[b'\\' as usize] = false;
+
+    let mut res = vec![];
+    let mut add = |from: State, bytes: ByteSet, to: State, events: &'static [Event]| {
+        res.push(Transition {
+            from,
+            bytes,
+            to,
+            events,
+        })
+    };
+
+    // values
+    for from in [Value, ArrayStart] {
+        add(from, whitespace, from, &[]);
+        add(from, bytes(b"{"), ObjectStart, &[OpenObject]);
+        add(from, bytes(b"["), ArrayStart, &[OpenArray]);
+        add(from, bytes(b"\""), String, &[ScalarStart]);
+        add(from, bytes(b"-"), Minus, &[ScalarStart]);
+        add(from, bytes(b"0"), Zero, &[ScalarStart]);
+        add(from, range(b'1', b'9'), Integer, &[ScalarStart]);
+        add(from, bytes(b"t"), Literal(b"rue"), &[ScalarStart]);
+        add(from, bytes(b"f"), Literal(b"alse"), &[ScalarStart]);
+        add(from, bytes(b"n"), Literal(b"ull"), &[ScalarStart]);
+    }
+    add(ArrayStart, bytes(b"]"), AfterValue, &[CloseArray]);
+
+    // keys
+    add(ObjectStart, whitespace, ObjectStart, &[]);
+    add(ObjectStart, bytes(b"\""), KeyString, &[KeyStart]);
+    add(ObjectStart, bytes(b"}"), AfterValue, &[CloseObject]);
+    add(Key, whitespace, Key, &[]);
+    add(Key, bytes(b"\""), KeyString, &[KeyStart]);
+    add(Colon, whitespace, Colon, &[]);
+    add(Colon, bytes(b":"), Value, &[]);
+
+    // strings
+    let strings: [(_, _, _, _, &'static [Event]); 2] = [
+        (
+            KeyString,
+            KeyEscape,
+            KeyUnicode as fn(u8) -> State,
+            Colon,
+            &[KeyEnd],
+        ),
+        (
+            String,
+            StringEscape,
+            StringUnicode,
+            AfterValue,
+            &[ScalarEnd],
+        ),
+    ];
+    for (string, escape, unicode, end, end_events) in strings {
+        add(string, unescaped, string, &[]);
+        add(string, bytes(b"\""), end, end_events);
+        add(string, bytes(b"\\"), escape, &[]);
+        add(escape, bytes(b"\"\\/bfnrt"), string, &[]);
+        add(escape, bytes(b"u"), unicode(4), &[]);
+        for left in 1..=4 {
+            let next = if left == 1 { string } else { unicode(left - 1) };
+            add(unicode(left), hex_digits, next, &[]);
+        }
+    }
+
+    // after values
+    add(AfterValue, whitespace, AfterValue, &[]);
+    add(AfterValue, bytes(b","), Comma, &[]);
+    add(AfterValue, bytes(b"}"), AfterValue, &[CloseObject]);
+    add(AfterValue, bytes(b"]"), AfterValue, &[CloseArray]);
+
+    // numbers
+    add(Minus, bytes(b"0"), Zero, &[]);
+    add(Minus, range(b'1', b'9'), Integer, &[]);
+    add(Integer, digits, Integer, &[]);
+    for from in [Zero, Integer] {
+        add(from, bytes(b"."), Dot, &[]);
+    }
+    add(Dot, digits, Fraction, &[]);
+    add(Fraction, digits, Fraction, &[]);
+    for from in [Zero, Integer, Fraction] {
+        add(from, bytes(b"eE"), Exponent, &[]);
+    }
+    add(Exponent, bytes(b"+-"), ExponentSign, &[]);
+    add(Exponent, digits, ExponentDigits, &[]);
+    add(ExponentSign, digits, ExponentDigits, &[]);
+    add(ExponentDigits, digits, ExponentDigits, &[]);
+    for from in FINAL_STATES
+        .into_iter()
+        .filter(|state| *state != AfterValue)
+    {
+        add(from, whitespace, AfterValue, &[NumberEnd]);
+        add(from, bytes(b","), Comma, &[NumberEnd]);
+        add(from, bytes(b"}"), AfterValue, &[NumberEnd, CloseObject]);
+        add(from, bytes(b"]"), AfterValue, &[NumberEnd, CloseArray]);
+    }
+
+    // literals (where `true` and `false` share their last state)
+    let mut literals: Vec<&'static [u8]> = vec![];
+    for word in [&b"rue"[..], &b"alse"[..], &b"ull"[..]] {
+        for left in (0..word.len()).map(|ii| &word[ii..]) {
+            if !literals.contains(&left) {
+                literals.push(left);
+            }
+        }
+    }
+    for left in literals {
+        let (to, events): (_, &'static [Event]) = match left {
+            [_] => (AfterValue, &[ScalarEnd]),
+            _ => (Literal(&left[1..]), &[]),
+        };
+        add(Literal(left), bytes(&left[..1]), to, events);
+    }
+
+    res
+}
+
+/// The automaton, with its transitions on the ranges of bytes where it behaves the same.
+struct Automaton {
+    states: Vec<State>,
+
+    /// The first byte of each range of bytes.
+    range_starts: Vec<u8>,
+
+    /// The next state and the events, for every state and every range of bytes (`None` is the dead state).
+    transitions: Vec<Vec<Option<(usize, &'static [Event])>>>,
+}
+
+impl Automaton {
+    /// Builds the automaton, where the bytes of `singletons` are also in ranges of their own.
+    fn new(singletons: &[u8]) -> Self {
+        let transitions = transitions();
+
+        let mut states = vec![State::Value];
+        for transition in &transitions {
+            for state in [transition.from, transition.to] {
+                if !states.contains(&state) {
+                    states.push(state);
+                }
+            }
+        }
+
+        let range_starts: Vec<_> = (0..=255u8)
+            .filter(|byte| {
+                *byte == 0
+                    || singletons.contains(byte)
+                    || singletons.contains(&(byte - 1))
+                    || transitions.iter().any(|transition| {
+                        transition.bytes[*byte as usize] != transition.bytes[*byte as usize - 1]
+                    })
+            })
+            .collect();
+
+        let mut table = vec![vec![None; range_starts.len()]; states.len()];
+        for transition in &transitions {
+            let from = states.iter().position(|state| *state == transition.from);
+            let to = states.iter().position(|state| *state == transition.to);
+            let row = &mut table[from.unwrap()];
+
+            for (range, start) in range_starts.iter().enumerate() {
+                if transition.bytes[*start as usize] {
+                    let next = Some((to.unwrap(), transition.events));
+                    assert!(row[range].is_none() || row[range] == next);
+                    row[range] = next;
+                }
+            }
+        }
+
+        Self {
+            states,
+            range_starts,
+            transitions: table,
+        }
+    }
+
+    fn index(&self, state: State) -> usize {
+        self.states
+            .iter()
+            .position(|other| *other == state)
+            .unwrap()
+    }
+
+    /// Returns the range of a byte.
+    fn range(&self, byte: u8) -> usize {
+        self.range_starts.partition_point(|start| *start <= byte) - 1
+    }
+}
+
+/// Splits a path into its keys, which must be non-empty and without quotes, backslashes, or control characters.
+pub fn parse_path(path: &[u8]) -> std::result::Result<Vec<Vec<u8>>, String> {
+    let keys: Vec<_> = path
+        .split(|byte| *byte == b'.')
+        .map(<[u8]>::to_vec)
+        .collect();
+
+    if keys.len() > MAX_DEPTH {
+        return Err(format!("a path can't have more than {MAX_DEPTH} keys"));
+    }
+    for key in &keys {
+        if key.is_empty() {
+            return Err("a path can't have empty keys".to_string());
+        }
+        if key
+            .iter()
+            .any(|byte| *byte < 0x20 || *byte == b'"' || *byte == b'\\')
+        {
+            return Err("keys can't have quotes, backslashes, or control characters".to_string());
+        }
+    }
+
+    Ok(keys)
+}
+
+/// Returns the value at a path (see [parse_path]) of a JSON document given as bytes,
+/// as `out_len` bytes padded with zeros.
+/// The circuit is unsatisfiable if the document is not well-formed,
+/// if the value is not present exactly once, or if it doesn't fit in the output.
+/// The bytes of the document are range-checked.
+pub fn extract<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    input: &[Cell<B>],
+    path: &[Vec<u8>],
+    out_len: usize,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    assert!(!path.is_empty() && path.len() <= MAX_DEPTH);
+    let key_bytes: Vec<u8> = path.iter().flatten().copied().collect();
+    let automaton = Automaton::new(&key_bytes);
+
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let one = ConstOrCell::Const(B::Field::one());
+    let mul = |compiler: &mut CircuitWriter<B>, lhs: &Cell<B>, rhs: &Cell<B>| {
+        field::mul(compiler, lhs, rhs, span)[0].clone()
+    };
+    let add = |compiler: &mut CircuitWriter<B>, lhs: &Cell<B>, rhs: &Cell<B>| {
+        field::add(compiler, lhs, rhs, span)[0].clone()
+    };
+    let sub = |compiler: &mut CircuitWriter<B>, lhs: &Cell<B>, rhs: &Cell<B>| {
+        field::sub(compiler, lhs, rhs, span)[0].clone()
+    };
+
+    // the one-hot vector of the state
+    let mut states = vec![zero.clone(); automaton.states.len()];
+    states[0] = one.clone();
+
+    // the one-hot vector of the depth, and the stack of the open containers,
+    // where each container is encoded as `is_object + 2 * is_on_path`
+    let mut depth = vec![zero.clone(); MAX_DEPTH + 1];
+    depth[0] = one.clone();
+    let mut stack = vec![zero.clone(); MAX_DEPTH];
+
+    // for each key of the path, the one-hot vector of the number of bytes matched by the current key
+    let mut progress: Vec<_> = path
+        .iter()
+        .map(|key| vec![zero.clone(); key.len() + 1])
+        .collect();
+
+    // whether the next value is the one of a key of the path (an intermediate one, or the last one)
+    let mut descend = zero.clone();
+    let mut target = zero.clone();
+
+    // whether the current byte is in the value
+    let mut inside = zero.clone();
+
+    // the bytes of the value (and zeros elsewhere), its length, and where it starts
+    let mut masked = Vec::with_capacity(input.len());
+    let mut len = zero.clone();
+    let mut starts = Vec::with_capacity(input.len());
+
+    let key_string = automaton.index(State::KeyString);
+    let comma = automaton.index(State::Comma);
+    let key = automaton.index(State::Key);
+    let value = automaton.index(State::Value);
+
+    for byte in input {
+        let in_range = byte_ranges(compiler, byte, &automaton.range_starts, span);
+
+        // the next state, and the events of the transition
+        let mut next = vec![zero.clone(); automaton.states.len()];
+        let mut events = vec![zero.clone(); NUM_EVENTS];
+        for (state, row) in states.iter().zip(&automaton.transitions) {
+            if matches!(state, ConstOrCell::Const(cst) if cst.is_zero()) {
+                continue;
+            }
+
+            // the ranges are grouped by transition
+            let mut groups: Vec<(usize, &'static [Event], Cell<B>)> = vec![];
+            for (range, transition) in row.iter().enumerate() {
+                let Some((to, transition_events)) = transition else {
+                    continue;
+                };
+                match groups.iter_mut().find(|(other, other_events, _)| {
+                    other == to && *other_events == *transition_events
+                }) {
+                    Some((_, _, selected)) => *selected = add(compiler, selected, &in_range[range]),
+                    None => groups.push((*to, *transition_events, in_range[range].clone())),
+                }
+            }
+
+            for (to, transition_events, selected) in groups {
+                let taken = mul(compiler, state, &selected);
+                next[to] = add(compiler, &next[to], &taken);
+                for event in transition_events {
+                    let event = &mut events[*event as usize];
+                    *event = add(compiler, event, &taken);
+                }
+            }
+        }
+        let event = |event: Event| events[event as usize].clone();
+
+        // the container at the current depth
+        let mut current = zero.clone();
+        for (level, container) in depth[1..].iter().zip(&stack) {
+            let term = mul(compiler, level, container);
+            current = add(compiler, &current, &term);
+        }
+        let current = field::to_bits(compiler, &current, 2, span);
+        let (is_object, is_on_path) = (&current[0], &current[1]);
+
+        // containers are closed by the right bracket
+        let close_object = event(Event::CloseObject);
+        let closed = mul(compiler, &close_object, is_object);
+        field::assert_eq_cells(compiler, &closed, &close_object, span)?;
+        let closed = mul(compiler, &event(Event::CloseArray), is_object);
+        field::assert_eq_cells(compiler, &closed, &zero, span)?;
+
+        // a comma is followed by a key in objects, and by a value in arrays (but not at the root)
+        let after_comma = std::mem::replace(&mut next[comma], zero.clone());
+        let at_root = mul(compiler, &after_comma, &depth[0]);
+        field::assert_eq_cells(compiler, &at_root, &zero, span)?;
+        let in_object = mul(compiler, &after_comma, is_object);
+        next[key] = add(compiler, &next[key], &in_object);
+        let in_array = sub(compiler, &after_comma, &in_object);
+        next[value] = add(compiler, &next[value], &in_array);
+
+        // the root object, and the objects that are values of keys of the path, are on the path
+        let open_object = event(Event::OpenObject);
+        let open = add(compiler, &open_object, &event(Event::OpenArray));
+        let close = add(compiler, &close_object, &event(Event::CloseArray));
+        let on_path = add(compiler, &depth[0], &descend);
+        let on_path = mul(compiler, &open_object, &on_path);
+        let on_path = add(compiler, &on_path, &on_path);
+        let pushed = add(compiler, &open_object, &on_path);
+
+        // the stack and the depth are updated
+        let opened: Vec<_> = depth
+            .iter()
+            .map(|level| mul(compiler, level, &open))
+            .collect();
+        let closed: Vec<_> = depth
+            .iter()
+            .map(|level| mul(compiler, level, &close))
+            .collect();
+        for (container, opened) in stack.iter_mut().zip(&opened) {
+            // container + opened * (pushed - container)
+            let diff = sub(compiler, &pushed, container);
+            let term = mul(compiler, opened, &diff);
+            *container = add(compiler, container, &term);
+        }
+        let mut next_depth = vec![];
+        for level in 0..=MAX_DEPTH {
+            let mut res = sub(compiler, &depth[level], &opened[level]);
+            res = sub(compiler, &res, &closed[level]);
+            if level > 0 {
+                res = add(compiler, &res, &opened[level - 1]);
+            }
+            if level < MAX_DEPTH {
+                res = add(compiler, &res, &closed[level + 1]);
+            }
+            next_depth.push(res);
+        }
+
+        // keys are compared with the path in the objects on the path
+        let key_start = mul(compiler, &event(Event::KeyStart), is_on_path);
+        let key_end = event(Event::KeyEnd);
+        let mut matched = vec![];
+        for (ii, (key, key_progress)) in path.iter().zip(&mut progress).enumerate() {
+            matched.push(mul(compiler, &key_progress[key.len()], &key_end));
+
+            let mut next_progress = vec![mul(compiler, &depth[ii + 1], &key_start)];
+            for (matched_len, expected) in key.iter().enumerate() {
+                let is_expected = &in_range[automaton.range(*expected)];
+                let is_expected = mul(compiler, &states[key_string], is_expected);
+                next_progress.push(mul(compiler, &key_progress[matched_len], &is_expected));
+            }
+            *key_progress = next_progress;
+        }
+
+        // the value starts with the first value after the last key of the path
+        let value_start = add(compiler, &open, &event(Event::ScalarStart));
+        let start = mul(compiler, &target, &value_start);
+
+        // numbers end before the byte that follows them, and other values end with their last byte
+        let at_value_depth = &depth[path.len()];
+        let number_end = mul(compiler, &inside, &event(Event::NumberEnd));
+        let number_end = mul(compiler, &number_end, at_value_depth);
+        let flag = add(compiler, &start, &inside);
+        let flag = sub(compiler, &flag, &number_end);
+        let end = add(compiler, &event(Event::ScalarEnd), &close);
+        let end = mul(compiler, &end, &next_depth[path.len()]);
+        let end = mul(compiler, &flag, &end);
+        inside = sub(compiler, &flag, &end);
+
+        // the pending keys are consumed by the next value
+        for (pending, matched) in [
+            (&mut descend, &matched[..path.len() - 1]),
+            (&mut target, &matched[path.len() - 1..]),
+        ] {
+            let consumed = mul(compiler, pending, &value_start);
+            *pending = sub(compiler, pending, &consumed);
+            for matched in matched {
+                *pending = add(compiler, pending, matched);
+            }
+        }
+
+        masked.push(mul(compiler, &flag, byte));
+        len = add(compiler, &len, &flag);
+        starts.push(start);
+        states = next;
+        depth = next_depth;
+    }
+
+    // the document ends after the root value
+    field::assert_eq_cells(compiler, &depth[0], &one, span)?;
+    let mut is_final = zero.clone();
+    for state in FINAL_STATES {
+        is_final = add(compiler, &is_final, &states[automaton.index(state)]);
+    }
+    field::assert_eq_cells(compiler, &is_final, &one, span)?;
+
+    // the value is present exactly once, at `offset`
+    let mut num_values = zero.clone();
+    let mut offset = zero.clone();
+    for (ii, start) in starts.iter().enumerate() {
+        num_values = add(compiler, &num_values, start);
+        let term = mul(
+            compiler,
+            start,
+            &ConstOrCell::Const(B::Field::from(ii as u64)),
+        );
+        offset = add(compiler, &offset, &term);
+    }
+    field::assert_eq_cells(compiler, &num_values, &one, span)?;
+
+    // the value fits in the output
+    let num_bits = (usize::BITS - input.len().max(out_len).leading_zeros()) as usize;
+    let out_len_cst = ConstOrCell::Const(B::Field::from(out_len as u64));
+    let too_long = field::less_than(compiler, num_bits, &out_len_cst, &len, span)?;
+    field::assert_eq_cells(compiler, &too_long[0], &zero, span)?;
+
+    // the value is shifted to the start, one bit of the offset at a time
+    let offset_bits = (usize::BITS - input.len().saturating_sub(1).leading_zeros()) as usize;
+    let offset_bits = field::to_bits(compiler, &offset, offset_bits, span);
+    let mut shifted = masked;
+    for (ii, bit) in offset_bits.cvars.iter().enumerate() {
+        shifted = (0..shifted.len())
+            .map(|jj| {
+                let moved = shifted.get(jj + (1 << ii)).unwrap_or(&zero);
+                field::if_else_inner(compiler, bit, moved, &shifted[jj], span)[0].clone()
+            })
+            .collect();
+    }
+    shifted.resize(out_len.max(shifted.len()), zero);
+
+    Ok(Var::new(shifted[..out_len].to_vec(), span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path() {
+        let keys = parse_path(b"claims.email").unwrap();
+        assert_eq!(keys, vec![b"claims".to_vec(), b"email".to_vec()]);
+
+        let paths: [&[u8]; 6] = [b"", b"a..b", b"a.", b"a\"b", b"a\\b", b"a.b.c.d.e.f.g.h.i"];
+        for path in paths {
+            assert!(parse_path(path).is_err());
+        }
+    }
+
+    #[test]
+    fn test_automaton() {
+        // the transitions are deterministic (which is asserted while building the automaton),
+        // and the bytes of the keys are in their own ranges
+        let automaton = Automaton::new(b"email");
+        for byte in b"email" {
+            let range = automaton.range(*byte);
+            assert_eq!(automaton.range_starts[range], *byte);
+            assert_eq!(automaton.range(*byte + 1), range + 1);
+        }
+    }
+}
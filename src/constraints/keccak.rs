@@ -0,0 +1,332 @@
+//! The Keccak-f[1600] permutation, and the Keccak-256 hash function used by Ethereum
+//! (which only differs from SHA3-256 by its padding).
+//!
+//! Lanes are represented as 64 boolean cells (see [super::words]),
+//! so that the rotations of the permutation are free and only XOR and `chi` cost multiplications.
+//! If the backend can XOR values with lookups, lanes are packed in single cells instead (see [super::packed]),
+//! as a XOR then only costs a few rows of lookups.
+
+use ark_ff::Zero;
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    boolean, field, packed,
+    words::{constant, rotl, xor, Word},
+};
+
+/// A lane, as 64 booleans in little-endian order.
+type Lane<B> = Word<B>;
+
+const LANE_BITS: usize = 64;
+
+/// The number of rounds of Keccak-f[1600].
+const ROUNDS: usize = 24;
+
+/// The rate of Keccak-256, in bytes.
+const RATE: usize = 136;
+
+/// The size of the Keccak-256 digest, in bytes.
+const DIGEST_LEN: usize = 32;
+
+/// The round constants, XORed to the first lane at the end of each round.
+const RC: [u64; ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets of the `rho` step, indexed by `[x][y]`.
+const ROTATIONS: [[usize; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+/// Hashes a message of bytes with Keccak-256, and returns the 32 bytes of the digest.
+/// The bytes of the message are constrained to fit in 8 bits.
+pub fn keccak256<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    // decompose the message into bits, and pad it
+    let mut bits: Vec<_> = message
+        .iter()
+        .flat_map(|byte| field::to_bits(compiler, byte, 8, span).cvars)
+        .collect();
+    for byte in padding(message.len()) {
+        bits.extend(constant::<B>(byte as u64, 8));
+    }
+
+    // lanes are packed if the backend can XOR them with lookups
+    let digest = if packed::is_supported(compiler, LANE_BITS) {
+        sponge_packed(compiler, &bits, span)
+    } else {
+        sponge(compiler, &bits, span)
+    };
+    let digest = digest
+        .chunks(8)
+        .map(|byte| field::from_bits(compiler, byte, span)[0].clone())
+        .collect();
+
+    Var::new(digest, span)
+}
+
+/// Absorbs the blocks of a padded message (as bits), and squeezes the bits of the digest.
+fn sponge<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    // absorb the blocks (the state is indexed by `x + 5 * y`)
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let mut state: Vec<Lane<B>> = vec![vec![zero; LANE_BITS]; 25];
+
+    for block in bits.chunks(RATE * 8) {
+        for (lane, block_lane) in state.iter_mut().zip(block.chunks(LANE_BITS)) {
+            *lane = xor(compiler, lane, block_lane, span);
+        }
+        state = permutation(compiler, state, span);
+    }
+
+    // squeeze the digest, which fits in the first lanes
+    state.into_iter().flatten().take(DIGEST_LEN * 8).collect()
+}
+
+/// Same as [sponge], but with lanes packed in single cells (see [super::packed]).
+fn sponge_packed<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    let mut state: Vec<packed::Word<B>> = vec![packed::constant::<B>(0); 25];
+
+    for block in bits.chunks(RATE * 8) {
+        for (lane, block_lane) in state.iter_mut().zip(block.chunks(LANE_BITS)) {
+            let block_lane = packed::from_bits(compiler, block_lane, span);
+            *lane = packed::xor(compiler, lane, &block_lane, LANE_BITS, span);
+        }
+        state = permutation_packed(compiler, state, span);
+    }
+
+    state
+        .iter()
+        .take(DIGEST_LEN * 8 / LANE_BITS)
+        .flat_map(|lane| packed::to_bits(compiler, lane, LANE_BITS, span))
+        .collect()
+}
+
+/// Hashes a message of bytes with Keccak-256 out of the circuit (for example, to derive constants).
+pub fn keccak256_native(message: &[u8]) -> [u8; DIGEST_LEN] {
+    let mut padded = message.to_vec();
+    padded.extend(padding(message.len()));
+
+    let mut state = [0u64; 25];
+    for block in padded.chunks(RATE) {
+        for (lane, bytes) in state.iter_mut().zip(block.chunks(8)) {
+            *lane ^= u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        permutation_native(&mut state);
+    }
+
+    let mut digest = [0; DIGEST_LEN];
+    for (bytes, lane) in digest.chunks_mut(8).zip(state) {
+        bytes.copy_from_slice(&lane.to_le_bytes());
+    }
+    digest
+}
+
+/// Returns the bytes appended to a message of `len` bytes to fill the last block:
+/// the domain separation byte of Keccak (`0x01`), zeros, and a final 1 bit.
+fn padding(len: usize) -> Vec<u8> {
+    let mut padding = vec![0; RATE - len % RATE];
+    padding[0] = 0x01;
+    *padding.last_mut().unwrap() |= 0x80;
+    padding
+}
+
+/// The Keccak-f[1600] permutation, on a state of 25 lanes indexed by `x + 5 * y`.
+pub fn permutation<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    mut state: Vec<Lane<B>>,
+    span: Span,
+) -> Vec<Lane<B>> {
+    assert_eq!(state.len(), 25);
+
+    for rc in RC {
+        // theta
+        let mut parities = Vec::with_capacity(5);
+        for x in 0..5 {
+            let mut parity = state[x].clone();
+            for y in 1..5 {
+                parity = xor(compiler, &parity, &state[x + 5 * y], span);
+            }
+            parities.push(parity);
+        }
+
+        for x in 0..5 {
+            let rotated = rotl::<B>(&parities[(x + 1) % 5], 1);
+            let d = xor(compiler, &parities[(x + 4) % 5], &rotated, span);
+            for y in 0..5 {
+                state[x + 5 * y] = xor(compiler, &state[x + 5 * y], &d, span);
+            }
+        }
+
+        // rho and pi
+        let mut moved = state.clone();
+        for x in 0..5 {
+            for y in 0..5 {
+                moved[y + 5 * ((2 * x + 3 * y) % 5)] =
+                    rotl::<B>(&state[x + 5 * y], ROTATIONS[x][y]);
+            }
+        }
+
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                let lane = &moved[x + 5 * y];
+                let next = &moved[(x + 1) % 5 + 5 * y];
+                let next_next = &moved[(x + 2) % 5 + 5 * y];
+
+                let mut res = Vec::with_capacity(LANE_BITS);
+                for ii in 0..LANE_BITS {
+                    let not_next = boolean::not(compiler, &next[ii], span);
+                    let masked = boolean::and(compiler, &not_next[0], &next_next[ii], span);
+                    res.push(boolean::xor(compiler, &lane[ii], &masked[0], span)[0].clone());
+                }
+                state[x + 5 * y] = res;
+            }
+        }
+
+        // iota
+        state[0] = xor(compiler, &state[0], &constant::<B>(rc, LANE_BITS), span);
+    }
+
+    state
+}
+
+/// The Keccak-f[1600] permutation on packed lanes, with the same steps as [permutation].
+fn permutation_packed<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    mut state: Vec<packed::Word<B>>,
+    span: Span,
+) -> Vec<packed::Word<B>> {
+    assert_eq!(state.len(), 25);
+
+    for rc in RC {
+        // theta
+        let mut parities = Vec::with_capacity(5);
+        for x in 0..5 {
+            let mut parity = state[x].clone();
+            for y in 1..5 {
+                parity = packed::xor(compiler, &parity, &state[x + 5 * y], LANE_BITS, span);
+            }
+            parities.push(parity);
+        }
+
+        for x in 0..5 {
+            let rotated = packed::rotl(compiler, &parities[(x + 1) % 5], 1, LANE_BITS, span);
+            let d = packed::xor(compiler, &parities[(x + 4) % 5], &rotated, LANE_BITS, span);
+            for y in 0..5 {
+                state[x + 5 * y] = packed::xor(compiler, &state[x + 5 * y], &d, LANE_BITS, span);
+            }
+        }
+
+        // rho and pi
+        let mut moved = state.clone();
+        for x in 0..5 {
+            for y in 0..5 {
+                moved[y + 5 * ((2 * x + 3 * y) % 5)] = packed::rotl(
+                    compiler,
+                    &state[x + 5 * y],
+                    ROTATIONS[x][y],
+                    LANE_BITS,
+                    span,
+                );
+            }
+        }
+
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                let lane = &moved[x + 5 * y];
+                let next = &moved[(x + 1) % 5 + 5 * y];
+                let next_next = &moved[(x + 2) % 5 + 5 * y];
+
+                let not_next = packed::not(compiler, next, LANE_BITS, span);
+                let masked = packed::and(compiler, &not_next, next_next, LANE_BITS, span);
+                state[x + 5 * y] = packed::xor(compiler, lane, &masked, LANE_BITS, span);
+            }
+        }
+
+        // iota
+        let rc = packed::constant::<B>(rc);
+        state[0] = packed::xor(compiler, &state[0], &rc, LANE_BITS, span);
+    }
+
+    state
+}
+
+/// The Keccak-f[1600] permutation out of the circuit, with the same steps as [permutation].
+fn permutation_native(state: &mut [u64; 25]) {
+    for rc in RC {
+        // theta
+        let parities: [u64; 5] =
+            std::array::from_fn(|x| (0..5).fold(0, |parity, y| parity ^ state[x + 5 * y]));
+        for x in 0..5 {
+            let d = parities[(x + 4) % 5] ^ parities[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+
+        // rho and pi
+        let mut moved = *state;
+        for x in 0..5 {
+            for y in 0..5 {
+                moved[y + 5 * ((2 * x + 3 * y) % 5)] =
+                    state[x + 5 * y].rotate_left(ROTATIONS[x][y] as u32);
+            }
+        }
+
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5 * y] =
+                    moved[x + 5 * y] ^ (!moved[(x + 1) % 5 + 5 * y] & moved[(x + 2) % 5 + 5 * y]);
+            }
+        }
+
+        // iota
+        state[0] ^= rc;
+    }
+}
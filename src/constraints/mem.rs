@@ -0,0 +1,212 @@
+//! Offline memory checking.
+//!
+//! Instead of using a multiplexer over the whole memory for every access (see [field::dynamic_index]),
+//! we record every access as a tuple `(address, time, value, is_write)`,
+//! along with a tuple `(address, 0, initial_value, 1)` for every slot of the memory.
+//! The prover then provides the same tuples sorted by address and time,
+//! which we constrain to be a permutation of the original tuples (see [permutation]).
+//! In the sorted list, it is enough to check that consecutive tuples are consistent:
+//! every address starts with its initial value, times are increasing,
+//! and a read returns the value of the previous access to the same address.
+//! This costs a constant number of constraints per access and per memory slot.
+
+use std::sync::Arc;
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::{ComputeManyFn, ConstOrCell, Value, Var},
+};
+
+use super::{boolean, field, permutation};
+
+/// The number of values in an access tuple: `(address, time, value, is_write)`.
+const TUPLE_LEN: usize = 4;
+
+/// Performs a list of accesses (reads or writes) to a memory,
+/// and returns the value at the accessed address after each access
+/// (i.e. the value read, or the value written).
+/// For reads, the corresponding entry of `values` is ignored.
+/// The addresses are constrained to be smaller than the size of the memory.
+pub fn access<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    memory: &[ConstOrCell<B::Field, B::Var>],
+    indices: &[ConstOrCell<B::Field, B::Var>],
+    values: &[ConstOrCell<B::Field, B::Var>],
+    is_write: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let mem_len = memory.len();
+    let num_accesses = indices.len();
+    assert!(mem_len > 0);
+    assert_eq!(values.len(), num_accesses);
+    assert_eq!(is_write.len(), num_accesses);
+
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let one = ConstOrCell::Const(B::Field::one());
+
+    // the execution of the accesses is simulated out of circuit
+    let inputs: Vec<_> = memory
+        .iter()
+        .chain(indices)
+        .chain(values)
+        .chain(is_write)
+        .cloned()
+        .collect();
+
+    // the simulation is run once, and shared by all the hints
+    let simulation: Arc<ComputeManyFn<B::Field>> =
+        Arc::new(move |vals: &[B::Field]| simulate(mem_len, vals));
+    let hint = |compiler: &mut CircuitWriter<B>, output: usize| {
+        let var = compiler.backend.new_internal_var(
+            Value::ComputeNth(inputs.clone(), simulation.clone(), output),
+            span,
+        );
+        ConstOrCell::Cell(var)
+    };
+
+    // the value after each access: the written value, or the value read
+    let mut accessed = Vec::with_capacity(num_accesses);
+    for kk in 0..num_accesses {
+        let read = hint(compiler, kk);
+        let res = field::if_else_inner(compiler, &is_write[kk], &values[kk], &read, span);
+        accessed.push(res[0].clone());
+    }
+
+    // the original tuples
+    let mut tuples = Vec::with_capacity(mem_len + num_accesses);
+    for (ii, initial) in memory.iter().enumerate() {
+        let address = ConstOrCell::Const(B::Field::from(ii as u64));
+        tuples.push(vec![address, zero.clone(), initial.clone(), one.clone()]);
+    }
+    for kk in 0..num_accesses {
+        let time = ConstOrCell::Const(B::Field::from(kk as u64 + 1));
+        tuples.push(vec![
+            indices[kk].clone(),
+            time,
+            accessed[kk].clone(),
+            is_write[kk].clone(),
+        ]);
+    }
+
+    // the sorted tuples
+    let mut sorted = Vec::with_capacity(tuples.len());
+    for jj in 0..tuples.len() {
+        let tuple: Vec<_> = (0..TUPLE_LEN)
+            .map(|ii| hint(compiler, num_accesses + jj * TUPLE_LEN + ii))
+            .collect();
+        sorted.push(tuple);
+    }
+
+    permutation::assert_permutation(compiler, &tuples, &sorted, span)?;
+
+    // the sorted tuples start with the initial value of the first address,
+    // and end with an access to the last address
+    let first = &sorted[0];
+    let last = &sorted[sorted.len() - 1];
+    let last_address = ConstOrCell::Const(B::Field::from(mem_len as u64 - 1));
+    assert_zero(compiler, &first[0], span);
+    assert_zero(compiler, &first[1], span);
+    let diff = field::sub(compiler, &last[0], &last_address, span);
+    assert_zero(compiler, &diff[0], span);
+
+    // consecutive tuples must be consistent
+    let time_bits = (usize::BITS - num_accesses.leading_zeros()).max(1) as usize;
+    for pair in sorted.windows(2) {
+        let [address, time, value, _] = &pair[0][..] else {
+            unreachable!()
+        };
+        let [next_address, next_time, next_value, next_is_write] = &pair[1][..] else {
+            unreachable!()
+        };
+
+        // the address is either the same, or the next one
+        let is_new_address = field::sub(compiler, next_address, address, span)[0].clone();
+        boolean::check(compiler, &is_new_address, span);
+        let same_address = field::sub(compiler, &one, &is_new_address, span)[0].clone();
+
+        // a new address starts with its initial value
+        let new_time = field::mul(compiler, &is_new_address, next_time, span);
+        assert_zero(compiler, &new_time[0], span);
+
+        // for the same address, times are strictly increasing
+        let step = field::sub(compiler, next_time, time, span);
+        let step = field::sub(compiler, &step[0], &one, span);
+        let step = field::mul(compiler, &same_address, &step[0], span);
+        field::range_check(compiler, &step[0], time_bits, span)?;
+
+        // for the same address, a read returns the previous value
+        let is_read = field::sub(compiler, &one, next_is_write, span);
+        let is_same_read = field::mul(compiler, &same_address, &is_read[0], span);
+        let diff = field::sub(compiler, next_value, value, span);
+        let inconsistency = field::mul(compiler, &is_same_read[0], &diff[0], span);
+        assert_zero(compiler, &inconsistency[0], span);
+    }
+
+    Ok(Var::new(accessed, span))
+}
+
+/// Simulates the accesses to the memory, and returns the value read by each access,
+/// followed by the flattened access tuples sorted by address and time.
+/// The inputs are the memory, followed by the indices, the values, and the write flags of the accesses.
+fn simulate<F: BackendField>(mem_len: usize, inputs: &[F]) -> Vec<F> {
+    let (memory, accesses) = inputs.split_at(mem_len);
+    let num_accesses = accesses.len() / 3;
+    let (indices, accesses) = accesses.split_at(num_accesses);
+    let (values, is_write) = accesses.split_at(num_accesses);
+
+    let mut memory = memory.to_vec();
+    let mut reads = Vec::with_capacity(num_accesses);
+    let mut tuples: Vec<(BigUint, u64, F, F)> = (0..mem_len)
+        .map(|ii| (BigUint::from(ii), 0, memory[ii], F::one()))
+        .collect();
+
+    for kk in 0..num_accesses {
+        let address: BigUint = indices[kk].into();
+
+        // an out-of-bounds access can't satisfy the constraints anyway
+        let slot = usize::try_from(&address)
+            .ok()
+            .and_then(|ii| memory.get_mut(ii));
+        let accessed = match slot {
+            Some(slot) if is_write[kk].is_one() => {
+                *slot = values[kk];
+                values[kk]
+            }
+            Some(slot) => *slot,
+            None => F::zero(),
+        };
+
+        reads.push(accessed);
+        tuples.push((address, kk as u64 + 1, accessed, is_write[kk]));
+    }
+
+    tuples.sort_by(|lhs, rhs| (&lhs.0, lhs.1).cmp(&(&rhs.0, rhs.1)));
+
+    let mut res = reads;
+    for (address, time, value, is_write) in tuples {
+        let address = F::try_from(address)
+            .unwrap_or_else(|_| unreachable!("the address is smaller than the field modulus"));
+        res.extend([address, F::from(time), value, is_write]);
+    }
+    res
+}
+
+/// Constrains a value to be zero.
+fn assert_zero<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    var: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) {
+    match var {
+        ConstOrCell::Const(cst) => assert!(cst.is_zero()),
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(cvar, B::Field::zero(), span),
+    }
+}
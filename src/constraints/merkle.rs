@@ -0,0 +1,129 @@
+//! Merkle trees of field elements, where two nodes are compressed into their parent with a hash function.
+//!
+//! The gadgets are generic over the compression function (see [Compress]),
+//! which is the Poseidon of the backend by default (see [poseidon]).
+
+use ark_ff::Zero;
+
+use crate::{
+    backends::Backend,
+    circuit_writer::{CircuitWriter, VarInfo},
+    constants::Span,
+    error::Result,
+    parser::types::TyKind,
+    var::{ConstOrCell, Var},
+};
+
+use super::{field, poseidon2};
+
+/// A function compressing two nodes (the left one and the right one) into their parent.
+pub type Compress<B> = fn(
+    &mut CircuitWriter<B>,
+    &ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>,
+    &ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>,
+    Span,
+) -> Result<ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>>;
+
+/// Compresses two nodes with the Poseidon of the backend, and keeps the first element of its output.
+pub fn poseidon<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    left: &ConstOrCell<B::Field, B::Var>,
+    right: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    let input = Var::new(vec![left.clone(), right.clone()], span);
+    let input_typ = TyKind::Array(Box::new(TyKind::Field), 2);
+    let input = VarInfo::new(input, false, Some(input_typ));
+
+    let res = B::poseidon()(compiler, &[input], span)?.expect("poseidon returns a value");
+    Ok(res[0].clone())
+}
+
+/// Compresses two nodes with the Poseidon2 permutation (with a zero capacity element),
+/// and keeps the first element of its output.
+pub fn poseidon2<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    left: &ConstOrCell<B::Field, B::Var>,
+    right: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    let state = [
+        left.clone(),
+        right.clone(),
+        ConstOrCell::Const(B::Field::zero()),
+    ];
+    let res = poseidon2::permutation(compiler, &state, span)?;
+    Ok(res[0].clone())
+}
+
+/// Returns the root of the tree from a leaf and the siblings on its path (from the bottom of the tree).
+/// `index_bits[i]` is set if the node at height `i` of the path is a right child,
+/// i.e. they are the bits of the index of the leaf, in little-endian order.
+pub fn root<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    compress: Compress<B>,
+    leaf: &ConstOrCell<B::Field, B::Var>,
+    path: &[ConstOrCell<B::Field, B::Var>],
+    index_bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    assert_eq!(path.len(), index_bits.len());
+
+    let mut node = leaf.clone();
+    for (sibling, is_right) in path.iter().zip(index_bits) {
+        node = parent(compiler, compress, &node, sibling, is_right, span)?;
+    }
+
+    Ok(node)
+}
+
+/// Appends a leaf to an incremental Merkle tree (as in Tornado Cash),
+/// and returns the roots of the tree before and after the append.
+///
+/// `index_bits` are the bits of the index of the next (empty) leaf, in little-endian order,
+/// and `filled_subtrees[i]` is the root of the last full subtree of height `i` on the left of that leaf
+/// (it is only used when `index_bits[i]` is set).
+/// The other siblings are empty subtrees, which are computed at compile time when `compress` folds constants.
+pub fn append<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    compress: Compress<B>,
+    leaf: &ConstOrCell<B::Field, B::Var>,
+    index_bits: &[ConstOrCell<B::Field, B::Var>],
+    filled_subtrees: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<[ConstOrCell<B::Field, B::Var>; 2]> {
+    assert_eq!(index_bits.len(), filled_subtrees.len());
+
+    let mut empty = ConstOrCell::Const(B::Field::zero());
+    let mut old_node = empty.clone();
+    let mut new_node = leaf.clone();
+
+    for (is_right, filled) in index_bits.iter().zip(filled_subtrees) {
+        // a left child has an empty sibling, and a right child has a filled one
+        let sibling = field::if_else_inner(compiler, is_right, filled, &empty, span)[0].clone();
+
+        old_node = parent(compiler, compress, &old_node, &sibling, is_right, span)?;
+        new_node = parent(compiler, compress, &new_node, &sibling, is_right, span)?;
+        empty = compress(compiler, &empty, &empty, span)?;
+    }
+
+    Ok([old_node, new_node])
+}
+
+/// Returns the parent of a node and its sibling, where `is_right` is set if the node is a right child.
+fn parent<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    compress: Compress<B>,
+    node: &ConstOrCell<B::Field, B::Var>,
+    sibling: &ConstOrCell<B::Field, B::Var>,
+    is_right: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    // if the node is a right child, its sibling is on the left
+    let is_right = Var::new_cvar(is_right.clone(), span);
+    let then_ = Var::new(vec![sibling.clone(), node.clone()], span);
+    let else_ = Var::new(vec![node.clone(), sibling.clone()], span);
+    let children = field::if_else(compiler, &is_right, &then_, &else_, span);
+
+    compress(compiler, &children[0], &children[1], span)
+}
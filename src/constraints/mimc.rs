@@ -0,0 +1,97 @@
+//! The MiMC7 and MiMC-Feistel (also known as MiMCSponge) permutations, as implemented by circomlib.
+//!
+//! The round constants are derived like circomlib does: the first one is zero,
+//! and the next ones are obtained by iterating Keccak-256 on the seed, reduced modulo the field.
+//! On the BN254 field (the `r1cs-bn254` backend), the outputs match the ones of circomlib.
+//! On other fields, the constructions are the same, but the constants and outputs differ.
+//!
+//! Both exponents (7 and 5) must be permutations of the field,
+//! which is the case for the fields of all the backends.
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    var::{ConstOrCell, Var},
+};
+
+use super::{field, keccak::keccak256_native};
+
+/// The number of rounds of MiMC7.
+const MIMC7_ROUNDS: usize = 91;
+
+/// The number of rounds of MiMC-Feistel.
+const FEISTEL_ROUNDS: usize = 220;
+
+/// Returns the round constants for a seed: zero, followed by the iterated Keccak-256 hashes of the seed.
+fn round_constants<F: BackendField>(seed: &str, rounds: usize) -> Vec<F> {
+    let modulus_minus_one: BigUint = (-F::one()).into();
+    let modulus = modulus_minus_one + 1u8;
+
+    let mut constants = vec![F::zero()];
+    let mut hash = keccak256_native(seed.as_bytes());
+    for _ in 1..rounds {
+        hash = keccak256_native(&hash);
+        let cst = BigUint::from_bytes_be(&hash) % &modulus;
+        constants.push(F::try_from(cst).unwrap_or_else(|_| unreachable!()));
+    }
+
+    constants
+}
+
+/// Applies MiMC7 to `x` with the key `k`:
+/// each round computes `(x + k + c_i)^7`, and the key is added to the result.
+pub fn mimc7<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    x: &ConstOrCell<B::Field, B::Var>,
+    k: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let constants = round_constants::<B::Field>("mimc", MIMC7_ROUNDS);
+    let exponent = BigUint::from(7u8);
+
+    let mut x = x.clone();
+    for cst in constants {
+        let tmp = field::add(compiler, &x, k, span);
+        let tmp = field::add(compiler, &tmp[0], &ConstOrCell::Const(cst), span);
+        x = field::pow(compiler, &tmp[0], &exponent, span)[0].clone();
+    }
+
+    field::add(compiler, &x, k, span)
+}
+
+/// Applies MiMC-Feistel to `(left, right)` with the key `k`, and returns the new `[left, right]`:
+/// each round computes `(right + (left + k + c_i)^5, left)`, except for the last one which doesn't swap.
+pub fn feistel<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    left: &ConstOrCell<B::Field, B::Var>,
+    right: &ConstOrCell<B::Field, B::Var>,
+    k: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let mut constants = round_constants::<B::Field>("mimcsponge", FEISTEL_ROUNDS);
+    // the last constant is also zero
+    *constants.last_mut().unwrap() = B::Field::zero();
+    let exponent = BigUint::from(5u8);
+
+    let mut left = left.clone();
+    let mut right = right.clone();
+    for (round, cst) in constants.into_iter().enumerate() {
+        let tmp = field::add(compiler, &left, k, span);
+        let tmp = field::add(compiler, &tmp[0], &ConstOrCell::Const(cst), span);
+        let tmp = field::pow(compiler, &tmp[0], &exponent, span);
+        let mixed = field::add(compiler, &right, &tmp[0], span)[0].clone();
+
+        if round < FEISTEL_ROUNDS - 1 {
+            right = left;
+            left = mixed;
+        } else {
+            right = mixed;
+        }
+    }
+
+    Var::new(vec![left, right], span)
+}
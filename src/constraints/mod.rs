@@ -1,2 +1,38 @@
+pub mod aes;
+pub mod anemoi;
+pub mod base64;
+pub mod bigint;
+pub mod blake2;
 pub mod boolean;
+pub mod chacha20;
+pub mod curve;
+pub mod custom;
+pub mod ecdh;
+pub mod ecdsa;
+pub mod eddsa;
+pub mod edwards;
 pub mod field;
+pub mod grain;
+pub mod hash_to_curve;
+pub mod json;
+pub mod keccak;
+pub mod mem;
+pub mod merkle;
+pub mod mimc;
+pub mod packed;
+pub mod pedersen;
+pub mod permutation;
+pub mod poseidon;
+pub mod poseidon2;
+pub mod regex;
+pub mod rescue;
+pub mod rsa;
+pub mod schnorr;
+pub mod sha2;
+pub mod sinsemilla;
+pub mod sort;
+pub mod string;
+pub mod table;
+pub mod vector_commitment;
+pub mod weierstrass;
+pub mod words;
@@ -0,0 +1,331 @@
+//! Gadgets on words of bits packed in a single field element,
+//! for backends that can XOR values with lookup tables (see [crate::backends::Capabilities::xor_lookup]).
+//!
+//! Unlike the words of [super::words], XOR and AND only cost a few rows of lookups per word,
+//! but rotations and shifts are not free: the word is split in two parts,
+//! which are range-checked with lookups too.
+//! Words are assumed to fit in their number of bits,
+//! which holds for the results of all the gadgets of this module.
+
+use ark_ff::{Field as _, One, Zero};
+use num_bigint::BigUint;
+
+use std::sync::Arc;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::{ErrorKind, Result},
+    var::{ConstOrCell, Value, Var},
+};
+
+use super::field::{self, BitwiseOp};
+
+/// A word, packed in a single cell.
+pub type Word<B> = ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>;
+
+/// The lookups XOR values by chunks of 16 bits,
+/// so the number of bits they are done on is a multiple of it.
+const CHUNK_BITS: usize = 16;
+
+/// Returns true if words of `num_bits` bits can use the gadgets of this module with the current backend.
+pub fn is_supported<B: Backend>(compiler: &CircuitWriter<B>, num_bits: usize) -> bool {
+    num_bits > 0 && num_bits % CHUNK_BITS == 0 && compiler.backend.capabilities().xor_lookup
+}
+
+/// Returns a constant word.
+pub fn constant<B: Backend>(value: u64) -> Word<B> {
+    ConstOrCell::Const(B::Field::from(value))
+}
+
+/// Returns `len` bits of a field element, starting at bit `offset`.
+fn bits<F: BackendField>(value: F, offset: usize, len: usize) -> F {
+    let value: BigUint = value.into();
+    let mask = (BigUint::from(1u8) << len) - 1u8;
+    F::try_from((value >> offset) & mask)
+        .unwrap_or_else(|_| unreachable!("the bits are smaller than the field element"))
+}
+
+/// Returns `2^exponent` as a field element.
+fn power_of_two<F: BackendField>(exponent: usize) -> F {
+    F::from(2u64).pow([exponent as u64])
+}
+
+/// XORs two words of `num_bits` bits (a multiple of 16) with lookups,
+/// which also constrains them to fit in `num_bits` bits.
+pub fn xor<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &Word<B>,
+    rhs: &Word<B>,
+    num_bits: usize,
+    span: Span,
+) -> Word<B> {
+    assert_eq!(num_bits % CHUNK_BITS, 0);
+
+    // the lookups need actual variables
+    let (lhs, rhs) = match (lhs, rhs) {
+        (ConstOrCell::Const(lhs), ConstOrCell::Const(rhs)) => {
+            let lhs: BigUint = (*lhs).into();
+            let rhs: BigUint = (*rhs).into();
+            let res = lhs ^ rhs;
+            assert!(res.bits() as usize <= num_bits);
+            let res = B::Field::try_from(res)
+                .unwrap_or_else(|_| unreachable!("the word is smaller than the field element"));
+            return ConstOrCell::Const(res);
+        }
+        (ConstOrCell::Const(cst), ConstOrCell::Cell(cvar))
+        | (ConstOrCell::Cell(cvar), ConstOrCell::Const(cst)) => {
+            let cst = compiler
+                .backend
+                .add_constant(Some("constant operand of a XOR"), *cst, span);
+            (cvar.clone(), cst)
+        }
+        (ConstOrCell::Cell(lhs), ConstOrCell::Cell(rhs)) => (lhs.clone(), rhs.clone()),
+    };
+
+    ConstOrCell::Cell(compiler.backend.xor_lookup(&lhs, &rhs, num_bits, span))
+}
+
+/// Returns `lhs & rhs`, computed as `(lhs + rhs - (lhs ^ rhs)) / 2`.
+pub fn and<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &Word<B>,
+    rhs: &Word<B>,
+    num_bits: usize,
+    span: Span,
+) -> Word<B> {
+    let xored = xor(compiler, lhs, rhs, num_bits, span);
+    let sum = field::add(compiler, lhs, rhs, span);
+    let diff = field::sub(compiler, &sum[0], &xored, span);
+    halve(compiler, &diff[0], span)
+}
+
+/// Returns `lhs | rhs`, computed as `(lhs + rhs + (lhs ^ rhs)) / 2`.
+pub fn or<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &Word<B>,
+    rhs: &Word<B>,
+    num_bits: usize,
+    span: Span,
+) -> Word<B> {
+    let xored = xor(compiler, lhs, rhs, num_bits, span);
+    let sum = field::add(compiler, lhs, rhs, span);
+    let sum = field::add(compiler, &sum[0], &xored, span);
+    halve(compiler, &sum[0], span)
+}
+
+fn halve<B: Backend>(compiler: &mut CircuitWriter<B>, word: &Word<B>, span: Span) -> Word<B> {
+    let half = B::Field::from(2u64)
+        .inverse()
+        .expect("the field has an odd characteristic");
+    field::mul(compiler, word, &ConstOrCell::Const(half), span)[0].clone()
+}
+
+/// Returns `!word`, computed as `2^num_bits - 1 - word`.
+pub fn not<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    word: &Word<B>,
+    num_bits: usize,
+    span: Span,
+) -> Word<B> {
+    let ones = power_of_two::<B::Field>(num_bits) - B::Field::one();
+    field::sub(compiler, &ConstOrCell::Const(ones), word, span)[0].clone()
+}
+
+/// Applies a boolean operation on two words of `num_bits` bits, like [field::bitwise] does on their bits.
+pub fn bitwise<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &Word<B>,
+    rhs: &Word<B>,
+    op: BitwiseOp,
+    num_bits: usize,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let num_bits = num_bits.next_multiple_of(CHUNK_BITS);
+    let res = match op {
+        BitwiseOp::And => and(compiler, lhs, rhs, num_bits, span),
+        BitwiseOp::Or => or(compiler, lhs, rhs, num_bits, span),
+        BitwiseOp::Xor => xor(compiler, lhs, rhs, num_bits, span),
+    };
+    Var::new_cvar(res, span)
+}
+
+/// Constrains a value to fit in `num_bits` bits, with lookups.
+/// A constant that doesn't fit is an error.
+pub fn range_check<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    value: &Word<B>,
+    num_bits: usize,
+    span: Span,
+) -> Result<()> {
+    match value {
+        ConstOrCell::Const(cst) => {
+            let cst: BigUint = (*cst).into();
+            if cst.bits() as usize > num_bits {
+                return Err(compiler.error(ErrorKind::IntegerOverflow(num_bits), span));
+            }
+        }
+        ConstOrCell::Cell(cvar) => range_check_cell(compiler, cvar, num_bits, span),
+    }
+
+    Ok(())
+}
+
+/// Constrains a variable to fit in `num_bits` bits, with lookups (see [range_check]).
+fn range_check_cell<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cvar: &B::Var,
+    num_bits: usize,
+    span: Span,
+) {
+    let value = ConstOrCell::Cell(cvar.clone());
+
+    // XORing with zero checks the value on a multiple of 16 bits
+    let padded_bits = num_bits.next_multiple_of(CHUNK_BITS);
+    let zero = ConstOrCell::Const(B::Field::zero());
+    xor(compiler, &value, &zero, padded_bits, span);
+
+    // and a smaller value still fits once shifted to the top
+    // (which can't wrap around, as the value fits in the padded bits)
+    if padded_bits != num_bits {
+        let shift = ConstOrCell::Const(power_of_two(padded_bits - num_bits));
+        let shifted = field::mul(compiler, &value, &shift, span);
+        xor(compiler, &shifted[0], &zero, padded_bits, span);
+    }
+}
+
+/// Splits a word of `num_bits` bits in its `at` least significant bits and the remaining bits,
+/// which are both range-checked.
+fn split<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    word: &Word<B>,
+    at: usize,
+    num_bits: usize,
+    span: Span,
+) -> (Word<B>, Word<B>) {
+    assert!(0 < at && at < num_bits);
+
+    if let ConstOrCell::Const(cst) = word {
+        let low = bits(*cst, 0, at);
+        let high = bits(*cst, at, num_bits - at);
+        return (ConstOrCell::Const(low), ConstOrCell::Const(high));
+    }
+
+    // the parts are computed out of circuit
+    let low = compiler.backend.new_internal_var(
+        Value::Compute(
+            vec![word.clone()],
+            Arc::new(move |vals: &[B::Field]| bits(vals[0], 0, at)),
+        ),
+        span,
+    );
+    let high = compiler.backend.new_internal_var(
+        Value::Compute(
+            vec![word.clone()],
+            Arc::new(move |vals: &[B::Field]| bits(vals[0], at, num_bits - at)),
+        ),
+        span,
+    );
+
+    // the parts are variables, so they can't overflow at compile time
+    range_check_cell(compiler, &low, at, span);
+    range_check_cell(compiler, &high, num_bits - at, span);
+    let (low, high) = (ConstOrCell::Cell(low), ConstOrCell::Cell(high));
+
+    // word = low + high * 2^at
+    let shifted = field::mul(compiler, &high, &ConstOrCell::Const(power_of_two(at)), span);
+    let sum = field::add(compiler, &low, &shifted[0], span);
+    match (&sum[0], word) {
+        (ConstOrCell::Cell(sum), ConstOrCell::Cell(word)) => {
+            compiler.backend.assert_eq_var(sum, word, span)
+        }
+        _ => unreachable!("the parts are cells"),
+    }
+
+    (low, high)
+}
+
+/// Rotates a word to the left.
+pub fn rotl<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    word: &Word<B>,
+    rot: usize,
+    num_bits: usize,
+    span: Span,
+) -> Word<B> {
+    let rot = rot % num_bits;
+    if rot == 0 {
+        return word.clone();
+    }
+
+    // the high bits become the low bits
+    let (low, high) = split(compiler, word, num_bits - rot, num_bits, span);
+    let shifted = field::mul(compiler, &low, &ConstOrCell::Const(power_of_two(rot)), span);
+    field::add(compiler, &shifted[0], &high, span)[0].clone()
+}
+
+/// Rotates a word to the right.
+pub fn rotr<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    word: &Word<B>,
+    rot: usize,
+    num_bits: usize,
+    span: Span,
+) -> Word<B> {
+    rotl(compiler, word, num_bits - rot % num_bits, num_bits, span)
+}
+
+/// Shifts a word to the right, filling it with zeros.
+pub fn shr<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    word: &Word<B>,
+    shift: usize,
+    num_bits: usize,
+    span: Span,
+) -> Word<B> {
+    match shift {
+        0 => word.clone(),
+        _ if shift >= num_bits => ConstOrCell::Const(B::Field::zero()),
+        _ => split(compiler, word, shift, num_bits, span).1,
+    }
+}
+
+/// Adds words modulo `2^num_bits`, by splitting the carry bits off the sum.
+pub fn add<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    words: &[&Word<B>],
+    num_bits: usize,
+    span: Span,
+) -> Word<B> {
+    let mut sum = ConstOrCell::Const(B::Field::zero());
+    for word in words {
+        sum = field::add(compiler, &sum, word, span)[0].clone();
+    }
+
+    // the carry fits in a few bits, which we drop
+    let carry_bits = (usize::BITS - (words.len() - 1).leading_zeros()) as usize;
+    if carry_bits == 0 {
+        return sum;
+    }
+    split(compiler, &sum, num_bits, num_bits + carry_bits, span).0
+}
+
+/// Packs a word given as bits (in little-endian order).
+pub fn from_bits<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Word<B> {
+    field::from_bits(compiler, bits, span)[0].clone()
+}
+
+/// Decomposes a word into its `num_bits` bits (in little-endian order).
+pub fn to_bits<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    word: &Word<B>,
+    num_bits: usize,
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    field::to_bits(compiler, word, num_bits, span).cvars
+}
@@ -0,0 +1,148 @@
+//! Pedersen hashes and commitments over the twisted Edwards curve embedded in the field of the backend
+//! (see [super::edwards]).
+//!
+//! The message is hashed like in Sapling: it is split into segments of 3-bit chunks,
+//! each segment is encoded as a scalar (each chunk `(s0, s1, s2)` contributing `(1 - 2 s2) (1 + s0 + 2 s1) 2^(4j)`),
+//! and the hash is the sum of the segments multiplied by independent generators.
+//! The generators are derived with Keccak-256 from a fixed seed, so hashes don't match the ones of Zcash.
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    edwards::{self, Curve, Point},
+    field,
+};
+
+/// Returns the number of 3-bit chunks of a segment:
+/// the largest number such that the scalar of a segment stays smaller than half of the order of the subgroup.
+fn segment_chunks(order: &BigUint) -> usize {
+    // the largest scalar of `c` chunks is `4 * (1 + 2^4 + ... + 2^(4 (c - 1)))`
+    let half_order: BigUint = (order - 1u8) / 2u8;
+    let mut max_scalar = BigUint::zero();
+    let mut chunks = 0;
+    loop {
+        max_scalar = (max_scalar << 4) + 4u8;
+        if max_scalar > half_order {
+            return chunks;
+        }
+        chunks += 1;
+    }
+}
+
+/// Returns the x coordinate of the Pedersen hash of a message of bits.
+pub fn hash<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let point = hash_to_point(compiler, curve, message, span);
+    Var::new_cvar(point[0].clone(), span)
+}
+
+/// Returns the x coordinate of the Pedersen commitment to a message of bits:
+/// its hash, plus the blinding factor times an independent generator.
+pub fn commit<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    blinding: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let mut acc = hash_to_point(compiler, curve, message, span);
+
+    // add the blinding factor times an independent generator
+    let bits = field::to_bits_strict(compiler, blinding, span);
+    let base = curve.generator("noname_pedersen_blinding");
+    let blinding = edwards::fixed_base_mul(compiler, curve, base, &bits.cvars, span);
+    acc = edwards::add(compiler, curve, &acc, &blinding, span);
+
+    Var::new_cvar(acc[0].clone(), span)
+}
+
+fn hash_to_point<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Point<B> {
+    // pad the message with zeros to a multiple of 3 bits
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let mut bits = message.to_vec();
+    bits.resize(message.len().div_ceil(3) * 3, zero);
+
+    let mut acc = edwards::identity::<B>();
+
+    for (ii, segment) in bits.chunks(3 * segment_chunks(&curve.order)).enumerate() {
+        // the base of the chunk is `2^(4j) * G_i`
+        let mut base = curve.generator(&format!("noname_pedersen_{ii}"));
+
+        for chunk in segment.chunks(3) {
+            // the 4 possible values of `(1 + s0 + 2 s1) * base`
+            let mut table = vec![base];
+            for _ in 1..4 {
+                table.push(curve.add(*table.last().unwrap(), base));
+            }
+
+            let point = lookup(compiler, &table, chunk, span);
+            acc = edwards::add(compiler, curve, &acc, &point, span);
+
+            for _ in 0..4 {
+                base = curve.double(base);
+            }
+        }
+    }
+
+    acc
+}
+
+/// Returns `(1 - 2 s2) * table[s0 + 2 s1]`, with a bilinear interpolation of the table.
+fn lookup<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    table: &[(B::Field, B::Field)],
+    chunk: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Point<B> {
+    let [s0, s1, s2] = chunk else {
+        unreachable!("chunks are made of 3 bits");
+    };
+    let s0s1 = field::mul(compiler, s0, s1, span)[0].clone();
+
+    let mut interpolate = |coordinate: fn(&(B::Field, B::Field)) -> B::Field| {
+        let [c0, c1, c2, c3] = [0, 1, 2, 3].map(|ii| coordinate(&table[ii]));
+
+        let mut res = ConstOrCell::Const(c0);
+        for (bit, coeff) in [(s0, c1 - c0), (s1, c2 - c0), (&s0s1, c3 - c2 - c1 + c0)] {
+            let term = field::mul(compiler, bit, &ConstOrCell::Const(coeff), span);
+            res = field::add(compiler, &res, &term[0], span)[0].clone();
+        }
+        res
+    };
+    let x = interpolate(|point| point.0);
+    let y = interpolate(|point| point.1);
+
+    // the sign bit negates the x coordinate
+    let two_s2 = field::mul(
+        compiler,
+        s2,
+        &ConstOrCell::Const(B::Field::from(2u64)),
+        span,
+    );
+    let sign = field::sub(
+        compiler,
+        &ConstOrCell::Const(B::Field::one()),
+        &two_s2[0],
+        span,
+    );
+    let x = field::mul(compiler, &x, &sign[0], span);
+
+    [x[0].clone(), y]
+}
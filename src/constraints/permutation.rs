@@ -0,0 +1,140 @@
+//! Permutation (multiset equality) arguments.
+//!
+//! Two lists of tuples are permutations of each other if the products of `gamma - fingerprint(tuple)`
+//! over both lists are equal, where the fingerprint of a tuple is `x_0 + beta * x_1 + beta^2 * x_2 + ...`,
+//! and `beta` and `gamma` are random challenges.
+//! The challenges are derived in the circuit (Fiat-Shamir),
+//! by hashing all the values of both lists with the backend's poseidon,
+//! or with the generic Poseidon permutation (see [poseidon::permutation]) if the backend has no native Poseidon.
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::Backend,
+    circuit_writer::{CircuitWriter, VarInfo},
+    constants::Span,
+    error::Result,
+    parser::types::TyKind,
+    var::{ConstOrCell, Var},
+};
+
+use super::{field, poseidon};
+
+/// Constrains `lhs` and `rhs` to contain the same tuples, potentially in a different order.
+pub fn assert_permutation<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[Vec<ConstOrCell<B::Field, B::Var>>],
+    rhs: &[Vec<ConstOrCell<B::Field, B::Var>>],
+    span: Span,
+) -> Result<()> {
+    assert_eq!(lhs.len(), rhs.len());
+
+    let width = lhs.first().map(Vec::len).unwrap_or_default();
+    assert!(lhs.iter().chain(rhs).all(|tuple| tuple.len() == width));
+
+    // if everything is known at compile time, we can compare the lists directly
+    if let (Some(mut lhs), Some(mut rhs)) = (constant_tuples::<B>(lhs), constant_tuples::<B>(rhs)) {
+        lhs.sort();
+        rhs.sort();
+        assert!(lhs == rhs, "the lists are not permutations of each other");
+        return Ok(());
+    }
+
+    // derive the challenges from both lists
+    let values: Vec<_> = lhs.iter().chain(rhs).flatten().cloned().collect();
+    let [beta, gamma] = challenges(compiler, &values, span)?;
+
+    // compare the grand products
+    let lhs_prod = grand_product(compiler, lhs, &beta, &gamma, span);
+    let rhs_prod = grand_product(compiler, rhs, &beta, &gamma, span);
+
+    let diff = field::sub(compiler, &lhs_prod, &rhs_prod, span);
+    match &diff[0] {
+        ConstOrCell::Const(cst) => assert!(cst.is_zero()),
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(cvar, B::Field::zero(), span),
+    }
+
+    Ok(())
+}
+
+/// Absorbs the values that are not constants with the backend's poseidon
+/// (or the generic Poseidon permutation, see [crate::backends::Capabilities::native_poseidon]),
+/// and squeezes two challenges.
+/// Constants don't need to be absorbed, as they are part of the circuit.
+pub fn challenges<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    values: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<[ConstOrCell<B::Field, B::Var>; 2]> {
+    let input_typ = TyKind::Array(Box::new(TyKind::Field), 2);
+    let params = (!compiler.backend.capabilities().native_poseidon)
+        .then(|| poseidon::Params::generate(2))
+        .transpose()
+        .map_err(|kind| compiler.error(kind, span))?;
+
+    let mut state = ConstOrCell::Const(B::Field::zero());
+    let mut output = None;
+
+    for value in values.iter().filter(|value| !value.is_const()) {
+        let res = match &params {
+            None => {
+                let input = Var::new(vec![state, value.clone()], span);
+                let input = VarInfo::new(input, false, Some(input_typ.clone()));
+                B::poseidon()(compiler, &[input], span)?.expect("poseidon returns a value")
+            }
+            // the capacity element is zero
+            Some(params) => {
+                let zero = ConstOrCell::Const(B::Field::zero());
+                poseidon::permutation(compiler, params, &[state, value.clone(), zero], span)
+            }
+        };
+        state = res[0].clone();
+        output = Some(res);
+    }
+
+    let output = output.expect("there must be at least one value to absorb");
+    Ok([output[0].clone(), output[1].clone()])
+}
+
+/// Returns the product of `gamma - fingerprint(tuple)` over all tuples.
+fn grand_product<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    tuples: &[Vec<ConstOrCell<B::Field, B::Var>>],
+    beta: &ConstOrCell<B::Field, B::Var>,
+    gamma: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    let mut prod = ConstOrCell::Const(B::Field::one());
+
+    for tuple in tuples {
+        // fingerprint = x_0 + beta * (x_1 + beta * (x_2 + ...))
+        let mut fingerprint = ConstOrCell::Const(B::Field::zero());
+        for value in tuple.iter().rev() {
+            let shifted = field::mul(compiler, &fingerprint, beta, span);
+            fingerprint = field::add(compiler, &shifted[0], value, span)[0].clone();
+        }
+
+        let term = field::sub(compiler, gamma, &fingerprint, span);
+        prod = field::mul(compiler, &prod, &term[0], span)[0].clone();
+    }
+
+    prod
+}
+
+/// Returns the values of the tuples if they are all constants.
+fn constant_tuples<B: Backend>(
+    tuples: &[Vec<ConstOrCell<B::Field, B::Var>>],
+) -> Option<Vec<Vec<BigUint>>> {
+    tuples
+        .iter()
+        .map(|tuple| {
+            tuple
+                .iter()
+                .map(|value| value.cst().map(Into::into))
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,257 @@
+//! The Poseidon permutation (https://eprint.iacr.org/2019/458), with a configurable rate,
+//! and a sponge to hash arrays of field elements with it.
+//!
+//! Unlike the Poseidon builtin (which is implemented by each backend, with a single instance),
+//! the state has `rate + 1` elements for a rate in [RATES],
+//! and the parameters are derived for the field of the backend with the procedure of the specification:
+//!
+//! - the S-box is `x^alpha`, with the smallest `alpha` that is a permutation of the field,
+//! - the number of partial rounds depends on `alpha` and on the width, to reach 128 bits of security,
+//! - the round constants are the first outputs of the Grain LFSR (see [grain]),
+//! - the MDS matrix is the Cauchy matrix `1 / (x_i + y_j)`, where the `x_i` and `y_j` are its next outputs.
+//!
+//! The round constants can also be given by the user (e.g. to pin them in the source of a program),
+//! in which case they are checked against the generated ones (see [hash_with_constants]).
+//! The MDS matrices are not checked for invariant subspaces, as the reference implementation does.
+
+use ark_ff::{Field, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::{ErrorKind, Result},
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    field,
+    grain::{Grain, SboxKind},
+};
+
+/// The supported rates (the width of the state is one more, for the capacity).
+pub const RATES: &[usize] = &[1, 2, 4];
+
+/// The number of full rounds (half of them at the beginning, and half of them at the end).
+const FULL_ROUNDS: usize = 8;
+
+/// The parameters of Poseidon for a given field and rate.
+pub struct Params<F> {
+    /// The size of the state.
+    width: usize,
+
+    /// The exponent of the S-box.
+    alpha: BigUint,
+
+    /// The number of partial rounds.
+    partial_rounds: usize,
+
+    /// The round constants: `width` per round.
+    round_constants: Vec<F>,
+
+    /// The MDS matrix.
+    mds: Vec<Vec<F>>,
+}
+
+impl<F: BackendField> Params<F> {
+    /// Derives the parameters for the field `F`, for a rate in [RATES],
+    /// or returns an error if the number of partial rounds is unknown for the S-box of the field.
+    pub fn generate(rate: usize) -> std::result::Result<Self, ErrorKind> {
+        assert!(RATES.contains(&rate), "unsupported Poseidon rate {rate}");
+        let width = rate + 1;
+        let alpha = field::smallest_permutation_exponent::<F>();
+
+        // the number of partial rounds for 128 bits of security (with a security margin),
+        // from the table of the specification for fields of about 255 bits
+        let partial_rounds = match (alpha, width) {
+            (3, _) => 84,
+            (5, 2) => 56,
+            (5, 3) => 57,
+            (5, _) => 60,
+            (7, 2 | 3) => 57,
+            (7, _) => 60,
+            _ => return Err(ErrorKind::UnsupportedSbox(alpha)),
+        };
+
+        let mut grain = Grain::new::<F>(SboxKind::Power, width, FULL_ROUNDS, partial_rounds);
+        let round_constants = (0..(FULL_ROUNDS + partial_rounds) * width)
+            .map(|_| grain.field_element())
+            .collect();
+
+        // the 2 * width elements of the Cauchy matrix must be distinct
+        let (xs, ys) = loop {
+            let elems: Vec<F> = (0..2 * width).map(|_| grain.field_element()).collect();
+            let distinct = elems
+                .iter()
+                .enumerate()
+                .all(|(ii, elem)| !elems[..ii].contains(elem));
+            if distinct {
+                let (xs, ys) = elems.split_at(width);
+                break (xs.to_vec(), ys.to_vec());
+            }
+        };
+        let mds = xs
+            .iter()
+            .map(|x| {
+                ys.iter()
+                    .map(|y| (*x + y).inverse().expect("the matrix is well defined"))
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self {
+            width,
+            alpha: BigUint::from(alpha),
+            partial_rounds,
+            round_constants,
+            mds,
+        })
+    }
+}
+
+/// Applies the Poseidon permutation to a state of `rate + 1` field elements.
+pub fn permutation<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params<B::Field>,
+    state: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    assert_eq!(state.len(), params.width);
+
+    let mut constants = params.round_constants.chunks(params.width);
+    let mut state = state.to_vec();
+
+    for round in 0..FULL_ROUNDS + params.partial_rounds {
+        let is_full = round < FULL_ROUNDS / 2 || round >= FULL_ROUNDS / 2 + params.partial_rounds;
+
+        // add the round constants
+        for (elem, cst) in state.iter_mut().zip(constants.next().unwrap()) {
+            *elem = field::add(compiler, elem, &ConstOrCell::Const(*cst), span)[0].clone();
+        }
+
+        // apply the S-box to the whole state, or only to the first element
+        let num_sboxes = if is_full { params.width } else { 1 };
+        for elem in &mut state[..num_sboxes] {
+            *elem = field::pow(compiler, elem, &params.alpha, span)[0].clone();
+        }
+
+        state = mds_layer(compiler, &params.mds, &state, span);
+    }
+
+    Var::new(state, span)
+}
+
+/// Hashes field elements with a Poseidon sponge of rate `rate`, and returns the first element of the final state.
+/// The capacity is initialized with the number of elements, and the last block is padded with zeros.
+pub fn hash<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    rate: usize,
+    input: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    let params = Params::<B::Field>::generate(rate).map_err(|kind| compiler.error(kind, span))?;
+    Ok(sponge(compiler, &params, input, span))
+}
+
+/// Same as [hash], with round constants given by the user,
+/// which must be the ones generated for the field of the backend and for this rate.
+pub fn hash_with_constants<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    rate: usize,
+    input: &[ConstOrCell<B::Field, B::Var>],
+    round_constants: &[B::Field],
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    let params = Params::<B::Field>::generate(rate).map_err(|kind| compiler.error(kind, span))?;
+
+    if round_constants.len() != params.round_constants.len() {
+        return Err(compiler.error(
+            ErrorKind::InvalidRoundConstants(format!(
+                "expected {} round constants for a rate of {rate}, got {}",
+                params.round_constants.len(),
+                round_constants.len()
+            )),
+            span,
+        ));
+    }
+
+    let mismatch = round_constants
+        .iter()
+        .zip(&params.round_constants)
+        .position(|(given, generated)| given != generated);
+    if let Some(index) = mismatch {
+        return Err(compiler.error(
+            ErrorKind::InvalidRoundConstants(format!(
+                "the round constant at index {index} differs"
+            )),
+            span,
+        ));
+    }
+
+    Ok(sponge(compiler, &params, input, span))
+}
+
+fn sponge<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params<B::Field>,
+    input: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    let rate = params.width - 1;
+    let mut state = vec![ConstOrCell::Const(B::Field::zero()); rate];
+    state.push(ConstOrCell::Const(B::Field::from(input.len() as u64)));
+
+    for block in input.chunks(rate) {
+        for (elem, absorbed) in state.iter_mut().zip(block) {
+            *elem = field::add(compiler, elem, absorbed, span)[0].clone();
+        }
+        state = permutation(compiler, params, &state, span).cvars;
+    }
+
+    state[0].clone()
+}
+
+/// Returns `mds * state`.
+fn mds_layer<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    mds: &[Vec<B::Field>],
+    state: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    mds.iter()
+        .map(|row| {
+            let mut acc = ConstOrCell::Const(B::Field::zero());
+            for (coeff, elem) in row.iter().zip(state) {
+                let term = field::mul(compiler, elem, &ConstOrCell::Const(*coeff), span);
+                acc = field::add(compiler, &acc, &term[0], span)[0].clone();
+            }
+            acc
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backends::r1cs::R1csBls12381Field;
+
+    use super::*;
+
+    #[test]
+    fn test_params() {
+        for &rate in RATES {
+            let params = Params::<R1csBls12381Field>::generate(rate).unwrap();
+            assert_eq!(params.width, rate + 1);
+            assert_eq!(
+                params.round_constants.len(),
+                (FULL_ROUNDS + params.partial_rounds) * params.width
+            );
+            assert!(params.mds.iter().all(|row| row.len() == params.width));
+        }
+
+        // the width is part of the seed, so instances of different rates get unrelated constants
+        let rate_1 = Params::<R1csBls12381Field>::generate(1).unwrap();
+        let rate_2 = Params::<R1csBls12381Field>::generate(2).unwrap();
+        assert_ne!(rate_1.round_constants[0], rate_2.round_constants[0]);
+    }
+}
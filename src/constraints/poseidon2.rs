@@ -0,0 +1,259 @@
+//! The Poseidon2 permutation (https://eprint.iacr.org/2023/323), with a state of 3 field elements.
+//!
+//! Unlike the Poseidon builtin (which is implemented by each backend with its own parameters),
+//! Poseidon2 is written with generic gadgets, and its parameters are derived for the field of the backend:
+//!
+//! - the S-box is `x^alpha`, with the smallest `alpha` that is a permutation of the field,
+//! - the number of partial rounds depends on `alpha`, to reach 128 bits of security,
+//! - the round constants are generated with the Grain LFSR of the Poseidon specification.
+//!
+//! Poseidon2 replaces the MDS matrix of most rounds by much cheaper linear layers,
+//! which makes it faster than Poseidon outside of the circuit, and cheaper when the linear layers aren't free.
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::{ErrorKind, Result},
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    field,
+    grain::{Grain, SboxKind},
+    keccak::keccak256_native,
+};
+
+/// The size of the state.
+pub const WIDTH: usize = 3;
+
+/// The number of elements absorbed per permutation, when used as a sponge.
+const RATE: usize = WIDTH - 1;
+
+/// The number of full rounds (half of them at the beginning, and half of them at the end).
+const FULL_ROUNDS: usize = 8;
+
+/// The parameters of Poseidon2 for a given field.
+struct Params<F> {
+    /// The exponent of the S-box.
+    alpha: u64,
+
+    /// The number of partial rounds.
+    partial_rounds: usize,
+
+    /// The round constants: [WIDTH] per full round, and a single one per partial round.
+    round_constants: Vec<F>,
+}
+
+impl<F: BackendField> Params<F> {
+    /// Derives the parameters for the field `F`,
+    /// or returns an error if the number of partial rounds is unknown for the S-box of the field.
+    fn generate() -> std::result::Result<Self, ErrorKind> {
+        let alpha = field::smallest_permutation_exponent::<F>();
+
+        // the number of partial rounds for 128 bits of security, with a state of 3 elements
+        let partial_rounds = match alpha {
+            3 => 84,
+            5 => 56,
+            7 => 57,
+            _ => return Err(ErrorKind::UnsupportedSbox(alpha)),
+        };
+
+        let num_constants = FULL_ROUNDS * WIDTH + partial_rounds;
+        let mut grain = Grain::new::<F>(SboxKind::Power, WIDTH, FULL_ROUNDS, partial_rounds);
+        let round_constants = (0..num_constants).map(|_| grain.field_element()).collect();
+
+        Ok(Self {
+            alpha,
+            partial_rounds,
+            round_constants,
+        })
+    }
+}
+
+/// Applies the Poseidon2 permutation to a state of [WIDTH] field elements.
+pub fn permutation<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    state: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    assert_eq!(state.len(), WIDTH);
+
+    let params = Params::<B::Field>::generate().map_err(|kind| compiler.error(kind, span))?;
+    let alpha = BigUint::from(params.alpha);
+    let mut constants = params.round_constants.into_iter();
+
+    let mut state = state.to_vec();
+
+    // the first linear layer
+    state = external_layer(compiler, &state, span);
+
+    for round in 0..FULL_ROUNDS + params.partial_rounds {
+        let is_full = round < FULL_ROUNDS / 2 || round >= FULL_ROUNDS / 2 + params.partial_rounds;
+
+        if is_full {
+            // add the round constants, apply the S-box to the whole state
+            for elem in state.iter_mut() {
+                let rc = ConstOrCell::Const(constants.next().unwrap());
+                let tmp = field::add(compiler, elem, &rc, span);
+                *elem = field::pow(compiler, &tmp[0], &alpha, span)[0].clone();
+            }
+            state = external_layer(compiler, &state, span);
+        } else {
+            // only the first element goes through the S-box
+            let rc = ConstOrCell::Const(constants.next().unwrap());
+            let tmp = field::add(compiler, &state[0], &rc, span);
+            state[0] = field::pow(compiler, &tmp[0], &alpha, span)[0].clone();
+            state = internal_layer(compiler, &state, span);
+        }
+    }
+
+    Ok(Var::new(state, span))
+}
+
+/// Hashes field elements with a Poseidon2 sponge, and returns the first element of the final state.
+/// The capacity is initialized with the number of elements, and the last block is padded with zeros.
+pub fn hash<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    input: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    let mut state = vec![ConstOrCell::Const(B::Field::zero()); RATE];
+    state.push(ConstOrCell::Const(B::Field::from(input.len() as u64)));
+
+    for block in input.chunks(RATE) {
+        for (elem, absorbed) in state.iter_mut().zip(block) {
+            *elem = field::add(compiler, elem, absorbed, span)[0].clone();
+        }
+        state = permutation(compiler, &state, span)?.cvars;
+    }
+
+    Ok(state[0].clone())
+}
+
+/// Returns a constant that separates the hashes of a domain from other hashes (when hashed with them):
+/// the Keccak-256 hash of the name of the domain, reduced modulo the field.
+pub fn domain_tag<F: BackendField>(domain: &str) -> F {
+    let modulus_minus_one: BigUint = (-F::one()).into();
+    let modulus = modulus_minus_one + 1u8;
+
+    let hash = keccak256_native(domain.as_bytes());
+    let tag = BigUint::from_bytes_be(&hash) % modulus;
+    F::try_from(tag).unwrap_or_else(|_| unreachable!())
+}
+
+/// Hashes the first `len` elements of `input` with a Poseidon2 sponge, where `len` can be a variable.
+///
+/// The elements from position `len` are replaced by the padding (a one followed by zeros),
+/// and the capacity is initialized with `len` so that messages of different lengths are separated.
+/// Every block is absorbed, but the digest is the state after the block that contains the padding.
+/// `len` is constrained to be at most the size of `input`.
+pub fn hash_var_len<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    input: &[ConstOrCell<B::Field, B::Var>],
+    len: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let num_blocks = (input.len() + 1).div_ceil(RATE);
+
+    // `is_end[i]` is set at the position of the padding, and `is_msg` while we are still in the message
+    let mut is_msg = ConstOrCell::Const(B::Field::one());
+    let mut found_end = zero.clone();
+    let mut padded = Vec::with_capacity(num_blocks * RATE);
+    let mut is_end = Vec::with_capacity(num_blocks * RATE);
+
+    for ii in 0..num_blocks * RATE {
+        let end = if ii <= input.len() {
+            let position = ConstOrCell::Const(B::Field::from(ii as u64));
+            field::equal_cells(compiler, len, &position, span)[0].clone()
+        } else {
+            zero.clone()
+        };
+        is_msg = field::sub(compiler, &is_msg, &end, span)[0].clone();
+        found_end = field::add(compiler, &found_end, &end, span)[0].clone();
+
+        // elem = is_msg * input[i] + is_end
+        let elem = input.get(ii).unwrap_or(&zero);
+        let elem = field::mul(compiler, &is_msg, elem, span);
+        padded.push(field::add(compiler, &elem[0], &end, span)[0].clone());
+        is_end.push(end);
+    }
+
+    // the padding must have been found, which is the case iff `len <= input.len()`
+    match &found_end {
+        ConstOrCell::Const(cst) => {
+            assert!(cst.is_one(), "the length is larger than the input");
+        }
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(cvar, B::Field::one(), span),
+    }
+
+    // absorb all the blocks, and select the digest after the block that contains the padding
+    let mut state = vec![zero.clone(); RATE];
+    state.push(len.clone());
+    let mut digest = zero;
+
+    for (block, is_end) in padded.chunks(RATE).zip(is_end.chunks(RATE)) {
+        for (elem, absorbed) in state.iter_mut().zip(block) {
+            *elem = field::add(compiler, elem, absorbed, span)[0].clone();
+        }
+        state = permutation(compiler, &state, span)?.cvars;
+
+        // at most one position of the block can be the end
+        let mut is_last = is_end[0].clone();
+        for end in &is_end[1..] {
+            is_last = field::add(compiler, &is_last, end, span)[0].clone();
+        }
+        let selected = field::mul(compiler, &is_last, &state[0], span);
+        digest = field::add(compiler, &digest, &selected[0], span)[0].clone();
+    }
+
+    Ok(Var::new_cvar(digest, span))
+}
+
+/// The linear layer of the full rounds, with the matrix `circ(2, 1, 1)`:
+/// each element is added to the sum of all elements.
+fn external_layer<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    state: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    let sum = sum(compiler, state, span);
+    state
+        .iter()
+        .map(|elem| field::add(compiler, &sum, elem, span)[0].clone())
+        .collect()
+}
+
+/// The linear layer of the partial rounds, with the matrix `[[2, 1, 1], [1, 2, 1], [1, 1, 3]]`:
+/// each element is multiplied by its diagonal coefficient minus one, and added to the sum of all elements.
+fn internal_layer<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    state: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    let sum = sum(compiler, state, span);
+    let last = field::add(compiler, &state[2], &state[2], span);
+
+    [&state[0], &state[1], &last[0]]
+        .into_iter()
+        .map(|elem| field::add(compiler, &sum, elem, span)[0].clone())
+        .collect()
+}
+
+fn sum<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    state: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    let mut sum = ConstOrCell::Const(B::Field::zero());
+    for elem in state {
+        sum = field::add(compiler, &sum, elem, span)[0].clone();
+    }
+    sum
+}
@@ -0,0 +1,596 @@
+//! Regular expressions over bytes, compiled at compile time into a deterministic finite automaton (DFA)
+//! that is then simulated in the circuit.
+//!
+//! A pattern must match the whole input (as if it were surrounded by `^` and `$`).
+//! The supported syntax is a subset of the usual one: literal bytes, `.` (any byte),
+//! classes like `[a-z_]` or `[^0-9]`, the escapes `\d`, `\w`, `\s` (and their negations `\D`, `\W`, `\S`),
+//! `\n`, `\r`, `\t`, escaped special characters like `\.`, groups, alternations with `|`,
+//! and the quantifiers `*`, `+` and `?`.
+//!
+//! The pattern goes through a Thompson NFA, which is determinized with the subset construction.
+//! Bytes are grouped into ranges on which the automaton behaves the same,
+//! so that the circuit only checks in which range each input byte is (see [byte_ranges]).
+//! The automaton is then simulated with a one-hot vector of its states, where the dead state is implicit
+//! (the vector is all zeros once the input can't match anymore):
+//! the input matches if the vector ends on an accepting state.
+
+use std::collections::{BTreeSet, HashMap};
+
+use ark_ff::{One, Zero};
+
+use crate::{backends::Backend, circuit_writer::CircuitWriter, constants::Span, var::ConstOrCell};
+
+use super::field;
+
+/// The maximum number of states of an automaton, as each of them costs constraints for every input byte.
+pub const MAX_STATES: usize = 1024;
+
+/// A set of bytes.
+type ByteSet = [bool; 256];
+
+/// A deterministic finite automaton over bytes, whose initial state is 0.
+#[derive(Debug)]
+pub struct Dfa {
+    /// The first byte of each range of bytes on which the automaton behaves the same (starting with 0).
+    range_starts: Vec<u8>,
+
+    /// The next state, for every state and every range of bytes (`None` is the dead state).
+    transitions: Vec<Vec<Option<usize>>>,
+
+    /// Whether each state is accepting.
+    accepting: Vec<bool>,
+}
+
+impl Dfa {
+    /// Compiles a pattern into an automaton that matches the whole input.
+    pub fn compile(pattern: &[u8]) -> Result<Self, String> {
+        let node = Parser::new(pattern).parse()?;
+
+        let mut nfa = Nfa::default();
+        let start = nfa.new_state();
+        let end = nfa.build(&node, start);
+
+        // the ranges start wherever a set of the automaton changes
+        let mut range_starts = vec![0u8];
+        for byte in 1..256 {
+            if nfa
+                .edges
+                .iter()
+                .flatten()
+                .any(|(set, _)| set[byte] != set[byte - 1])
+            {
+                range_starts.push(byte as u8);
+            }
+        }
+
+        // subset construction
+        let mut states = vec![nfa.closure([start].into())];
+        let mut indices = HashMap::from([(states[0].clone(), 0)]);
+        let mut transitions = vec![];
+
+        while transitions.len() < states.len() {
+            let current = states[transitions.len()].clone();
+            let mut row = Vec::with_capacity(range_starts.len());
+
+            for &byte in &range_starts {
+                let targets: BTreeSet<_> = current
+                    .iter()
+                    .flat_map(|&state| &nfa.edges[state])
+                    .filter(|(set, _)| set[byte as usize])
+                    .map(|(_, target)| *target)
+                    .collect();
+
+                if targets.is_empty() {
+                    row.push(None);
+                    continue;
+                }
+
+                let targets = nfa.closure(targets);
+                let index = match indices.get(&targets) {
+                    Some(index) => *index,
+                    None => {
+                        if states.len() == MAX_STATES {
+                            return Err(format!(
+                                "the automaton of the pattern has more than {MAX_STATES} states"
+                            ));
+                        }
+                        indices.insert(targets.clone(), states.len());
+                        states.push(targets);
+                        states.len() - 1
+                    }
+                };
+                row.push(Some(index));
+            }
+
+            transitions.push(row);
+        }
+
+        let accepting = states.iter().map(|state| state.contains(&end)).collect();
+
+        Ok(Self {
+            range_starts,
+            transitions,
+            accepting,
+        })
+    }
+
+    /// Returns whether the automaton accepts an input, out of the circuit.
+    pub fn matches(&self, input: &[u8]) -> bool {
+        let mut state = Some(0);
+        for byte in input {
+            let range = self.range_starts.partition_point(|start| start <= byte) - 1;
+            state = state.and_then(|state| self.transitions[state][range]);
+        }
+        matches!(state, Some(state) if self.accepting[state])
+    }
+}
+
+/// Returns whether bytes are matched by an automaton (1 if they are, 0 otherwise).
+/// The bytes are range-checked.
+pub fn is_match<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    dfa: &Dfa,
+    input: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let num_states = dfa.transitions.len();
+
+    // the one-hot vector of the current state
+    let mut states = vec![zero.clone(); num_states];
+    states[0] = ConstOrCell::Const(B::Field::one());
+
+    for byte in input {
+        let in_range = byte_ranges(compiler, byte, &dfa.range_starts, span);
+
+        // the next state is the sum of the current states that transition to it on the range of the byte
+        let mut next = vec![zero.clone(); num_states];
+        for (state, row) in states.iter().zip(&dfa.transitions) {
+            if matches!(state, ConstOrCell::Const(cst) if cst.is_zero()) {
+                continue;
+            }
+
+            for (target, next_state) in next.iter_mut().enumerate() {
+                let mut selected = zero.clone();
+                for (range, transition) in row.iter().enumerate() {
+                    if *transition == Some(target) {
+                        selected =
+                            field::add(compiler, &selected, &in_range[range], span)[0].clone();
+                    }
+                }
+
+                if matches!(&selected, ConstOrCell::Const(cst) if cst.is_zero()) {
+                    continue;
+                }
+
+                let term = field::mul(compiler, state, &selected, span);
+                *next_state = field::add(compiler, next_state, &term[0], span)[0].clone();
+            }
+        }
+
+        states = next;
+    }
+
+    // the input matches if it ends on an accepting state
+    let mut res = zero;
+    for (state, accepting) in states.iter().zip(&dfa.accepting) {
+        if *accepting {
+            res = field::add(compiler, &res, state, span)[0].clone();
+        }
+    }
+    res
+}
+
+/// Returns whether a byte is in each range of bytes (1 if it is, 0 otherwise),
+/// where the ranges are given by their first byte (starting with 0), in increasing order.
+/// The byte is range-checked.
+///
+/// The byte is decomposed into one-hot vectors of its high and low nibbles,
+/// so that a range costs at most 2 multiplications (instead of a comparison per bound).
+pub fn byte_ranges<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    byte: &ConstOrCell<B::Field, B::Var>,
+    range_starts: &[u8],
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    assert_eq!(range_starts.first(), Some(&0));
+    let bits = field::to_bits(compiler, byte, 8, span);
+    let low = one_hot(compiler, &bits.cvars[..4], span);
+    let high = one_hot(compiler, &bits.cvars[4..], span);
+
+    // the sum of the low nibbles in [first, last]
+    let low_sum = |compiler: &mut CircuitWriter<B>, first: usize, last: usize| {
+        let mut sum = ConstOrCell::Const(B::Field::zero());
+        for nibble in &low[first..=last] {
+            sum = field::add(compiler, &sum, nibble, span)[0].clone();
+        }
+        sum
+    };
+
+    let mut res = Vec::with_capacity(range_starts.len());
+    for (ii, start) in range_starts.iter().enumerate() {
+        let first = *start as usize;
+        let last = range_starts
+            .get(ii + 1)
+            .map_or(255, |next| *next as usize - 1);
+        let (first_high, last_high) = (first >> 4, last >> 4);
+
+        let in_range = if first_high == last_high {
+            let low = low_sum(compiler, first & 0xf, last & 0xf);
+            field::mul(compiler, &high[first_high], &low, span)[0].clone()
+        } else {
+            // the partial high nibbles at both ends, and the full ones in between
+            let low = low_sum(compiler, first & 0xf, 0xf);
+            let mut sum = field::mul(compiler, &high[first_high], &low, span)[0].clone();
+            for full in &high[first_high + 1..last_high] {
+                sum = field::add(compiler, &sum, full, span)[0].clone();
+            }
+            let low = low_sum(compiler, 0, last & 0xf);
+            let term = field::mul(compiler, &high[last_high], &low, span);
+            field::add(compiler, &sum, &term[0], span)[0].clone()
+        };
+        res.push(in_range);
+    }
+
+    res
+}
+
+/// Returns the one-hot vector of a value given as bits (in little-endian order).
+fn one_hot<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    let mut res = vec![ConstOrCell::Const(B::Field::one())];
+    for bit in bits.iter().rev() {
+        res = res
+            .iter()
+            .flat_map(|entry| {
+                // entry * (1 - bit) and entry * bit
+                let set = field::mul(compiler, entry, bit, span)[0].clone();
+                let unset = field::sub(compiler, entry, &set, span)[0].clone();
+                [unset, set]
+            })
+            .collect();
+    }
+    res
+}
+
+/// A parsed pattern.
+#[derive(Debug)]
+enum Node {
+    /// The empty string.
+    Empty,
+
+    /// A single byte of a set.
+    Bytes(ByteSet),
+
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Star(Box<Node>),
+    Plus(Box<Node>),
+    Optional(Box<Node>),
+}
+
+/// A recursive descent parser of patterns.
+struct Parser<'a> {
+    pattern: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(pattern: &'a [u8]) -> Self {
+        Self { pattern, pos: 0 }
+    }
+
+    fn parse(mut self) -> Result<Node, String> {
+        let node = self.parse_alt()?;
+        match self.peek() {
+            None => Ok(node),
+            Some(byte) => Err(format!("unexpected `{}`", byte as char)),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.pattern.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let byte = self.peek();
+        self.pos += 1;
+        byte
+    }
+
+    /// a|b|c
+    fn parse_alt(&mut self) -> Result<Node, String> {
+        let mut alternatives = vec![self.parse_concat()?];
+        while self.peek() == Some(b'|') {
+            self.pos += 1;
+            alternatives.push(self.parse_concat()?);
+        }
+
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Node::Alt(alternatives)
+        })
+    }
+
+    /// abc
+    fn parse_concat(&mut self) -> Result<Node, String> {
+        let mut nodes = vec![];
+        while !matches!(self.peek(), None | Some(b'|') | Some(b')')) {
+            nodes.push(self.parse_repeat()?);
+        }
+
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().unwrap(),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    /// a*, a+ or a?
+    fn parse_repeat(&mut self) -> Result<Node, String> {
+        let mut node = self.parse_atom()?;
+        loop {
+            node = match self.peek() {
+                Some(b'*') => Node::Star(Box::new(node)),
+                Some(b'+') => Node::Plus(Box::new(node)),
+                Some(b'?') => Node::Optional(Box::new(node)),
+                _ => return Ok(node),
+            };
+            self.pos += 1;
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        let byte = self
+            .bump()
+            .expect("parse_atom is called on a non-empty pattern");
+        match byte {
+            b'(' => {
+                let node = self.parse_alt()?;
+                if self.bump() != Some(b')') {
+                    return Err("unclosed group".to_string());
+                }
+                Ok(node)
+            }
+            b'[' => self.parse_class().map(Node::Bytes),
+            b'.' => Ok(Node::Bytes([true; 256])),
+            b'\\' => self.parse_escape().map(Node::Bytes),
+            b'*' | b'+' | b'?' => Err(format!("`{}` doesn't follow anything", byte as char)),
+            b']' | b'{' | b'}' | b'^' | b'$' => Err(format!(
+                "`{}` is not supported, it must be escaped",
+                byte as char
+            )),
+            _ => Ok(Node::Bytes(single(byte))),
+        }
+    }
+
+    /// [a-z_] or [^0-9], after the `[`
+    fn parse_class(&mut self) -> Result<ByteSet, String> {
+        let negated = self.peek() == Some(b'^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut set = [false; 256];
+        loop {
+            let byte = self.bump().ok_or("unclosed class")?;
+            let first = match byte {
+                b']' => break,
+                b'\\' => {
+                    let escaped = self.parse_escape()?;
+                    union(&mut set, &escaped);
+                    continue;
+                }
+                _ => byte,
+            };
+
+            // a range, unless the `-` is the last byte of the class
+            let is_range = self.peek() == Some(b'-')
+                && !matches!(self.pattern.get(self.pos + 1), None | Some(b']'));
+            if !is_range {
+                set[first as usize] = true;
+                continue;
+            }
+            self.pos += 1;
+
+            let last = match self.bump().ok_or("unclosed class")? {
+                b'\\' => {
+                    let escaped = self.parse_escape()?;
+                    let mut bytes = (0..256).filter(|byte| escaped[*byte]);
+                    match (bytes.next(), bytes.next()) {
+                        (Some(byte), None) => byte as u8,
+                        _ => return Err("invalid bound of a range".to_string()),
+                    }
+                }
+                byte => byte,
+            };
+            if last < first {
+                return Err(format!(
+                    "invalid range `{}-{}`",
+                    first as char, last as char
+                ));
+            }
+            for byte in first..=last {
+                set[byte as usize] = true;
+            }
+        }
+
+        if negated {
+            set = set.map(|in_set| !in_set);
+        }
+        if !set.contains(&true) {
+            return Err("empty class".to_string());
+        }
+        Ok(set)
+    }
+
+    /// \d, \n, \. etc., after the `\`
+    fn parse_escape(&mut self) -> Result<ByteSet, String> {
+        let byte = self.bump().ok_or("trailing `\\`")?;
+
+        let class = |ranges: &[(u8, u8)]| {
+            let mut set = [false; 256];
+            for (first, last) in ranges {
+                for byte in *first..=*last {
+                    set[byte as usize] = true;
+                }
+            }
+            set
+        };
+        let digits = class(&[(b'0', b'9')]);
+        let words = class(&[(b'0', b'9'), (b'A', b'Z'), (b'_', b'_'), (b'a', b'z')]);
+        let spaces = class(&[(b'\t', b'\r'), (b' ', b' ')]);
+
+        Ok(match byte {
+            b'd' => digits,
+            b'D' => digits.map(|in_set| !in_set),
+            b'w' => words,
+            b'W' => words.map(|in_set| !in_set),
+            b's' => spaces,
+            b'S' => spaces.map(|in_set| !in_set),
+            b'n' => single(b'\n'),
+            b'r' => single(b'\r'),
+            b't' => single(b'\t'),
+            _ if byte.is_ascii_alphanumeric() => {
+                return Err(format!("unknown escape `\\{}`", byte as char))
+            }
+            _ => single(byte),
+        })
+    }
+}
+
+/// Returns the set of a single byte.
+fn single(byte: u8) -> ByteSet {
+    let mut set = [false; 256];
+    set[byte as usize] = true;
+    set
+}
+
+/// Adds the bytes of a set to another.
+fn union(set: &mut ByteSet, other: &ByteSet) {
+    for (in_set, in_other) in set.iter_mut().zip(other) {
+        *in_set |= *in_other;
+    }
+}
+
+/// A nondeterministic finite automaton, built with Thompson's construction.
+#[derive(Default)]
+struct Nfa {
+    /// The epsilon transitions of each state.
+    epsilons: Vec<Vec<usize>>,
+
+    /// The transitions of each state on a set of bytes.
+    edges: Vec<Vec<(ByteSet, usize)>>,
+}
+
+impl Nfa {
+    fn new_state(&mut self) -> usize {
+        self.epsilons.push(vec![]);
+        self.edges.push(vec![]);
+        self.epsilons.len() - 1
+    }
+
+    /// Adds the states matching a node from a state, and returns the state reached at the end of the match.
+    fn build(&mut self, node: &Node, from: usize) -> usize {
+        match node {
+            Node::Empty => from,
+            Node::Bytes(set) => {
+                let to = self.new_state();
+                self.edges[from].push((*set, to));
+                to
+            }
+            Node::Concat(nodes) => nodes.iter().fold(from, |from, node| self.build(node, from)),
+            Node::Alt(nodes) => {
+                let to = self.new_state();
+                for node in nodes {
+                    let start = self.new_state();
+                    self.epsilons[from].push(start);
+                    let end = self.build(node, start);
+                    self.epsilons[end].push(to);
+                }
+                to
+            }
+            Node::Star(inner) | Node::Plus(inner) => {
+                let start = self.new_state();
+                self.epsilons[from].push(start);
+                let end = self.build(inner, start);
+                self.epsilons[end].push(start);
+
+                // a star can skip the loop, but a plus has to go through it once
+                let exit = if matches!(node, Node::Star(_)) {
+                    start
+                } else {
+                    end
+                };
+                let to = self.new_state();
+                self.epsilons[exit].push(to);
+                to
+            }
+            Node::Optional(node) => {
+                let start = self.new_state();
+                self.epsilons[from].push(start);
+                let end = self.build(node, start);
+
+                let to = self.new_state();
+                self.epsilons[end].push(to);
+                self.epsilons[from].push(to);
+                to
+            }
+        }
+    }
+
+    /// Returns the states reachable from a set of states with epsilon transitions.
+    fn closure(&self, mut states: BTreeSet<usize>) -> BTreeSet<usize> {
+        let mut stack: Vec<_> = states.iter().copied().collect();
+        while let Some(state) = stack.pop() {
+            for next in &self.epsilons[state] {
+                if states.insert(*next) {
+                    stack.push(*next);
+                }
+            }
+        }
+        states
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dfa() {
+        let cases: &[(&str, &[&str], &[&str])] = &[
+            (
+                r"[a-z0-9._]+@[a-z]+\.(com|org)",
+                &["alice.b@mail.com", "x@y.org"],
+                &["@mail.com", "alice@mail.net", "Alice@mail.com"],
+            ),
+            (r"\d+(\.\d+)?", &["42", "3.14"], &["", "3.", ".5", "1a"]),
+            (
+                r"(ab|c)*d?",
+                &["", "abcab", "cd", "d"],
+                &["a", "dd", "abd c"],
+            ),
+            (r"[^a-c-]x", &["dx", "zx", "\nx"], &["ax", "-x", "x"]),
+            (r"a.c", &["abc", "a\nc"], &["ac", "abbc"]),
+        ];
+
+        for (pattern, matching, not_matching) in cases {
+            let dfa = Dfa::compile(pattern.as_bytes()).unwrap();
+            for input in *matching {
+                assert!(dfa.matches(input.as_bytes()), "{pattern} {input}");
+            }
+            for input in *not_matching {
+                assert!(!dfa.matches(input.as_bytes()), "{pattern} {input}");
+            }
+        }
+
+        for pattern in ["(a", "a)", "*a", "[a", "[b-a]", r"\q", "a{2}", "\\"] {
+            assert!(Dfa::compile(pattern.as_bytes()).is_err(), "{pattern}");
+        }
+    }
+}
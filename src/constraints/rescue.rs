@@ -0,0 +1,141 @@
+//! The Rescue-Prime permutation (https://eprint.iacr.org/2020/1143), with a state of 3 field elements.
+//!
+//! Its parameters are derived for the field of the backend:
+//!
+//! - the S-box is `x^alpha`, with the smallest `alpha` that is a permutation of the field,
+//!   and each round also applies the inverse S-box `x^(1/alpha)`,
+//! - the MDS matrix is the Cauchy matrix `1 / (i + j + 3)`,
+//! - the round constants are generated with the Grain LFSR (see [grain]).
+//!
+//! Note that the reference implementation derives its MDS matrix and round constants differently,
+//! so the digests don't match other implementations of Rescue-Prime.
+//!
+//! The inverse S-box is computed out of circuit, and constrained with the S-box (see [field::root]).
+
+use ark_ff::Field;
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    field,
+    grain::{Grain, SboxKind},
+};
+
+/// The size of the state.
+pub const WIDTH: usize = 3;
+
+/// The number of rounds (each made of two steps).
+/// This is a conservative number of rounds for 128 bits of security with a state of 3 elements.
+const ROUNDS: usize = 8;
+
+/// The parameters of Rescue-Prime for a given field.
+struct Params<F> {
+    /// The exponent of the S-box.
+    alpha: BigUint,
+
+    /// The exponent of the inverse S-box.
+    alpha_inv: BigUint,
+
+    /// The MDS matrix.
+    mds: [[F; WIDTH]; WIDTH],
+
+    /// The round constants: [WIDTH] per step.
+    round_constants: Vec<F>,
+}
+
+impl<F: BackendField> Params<F> {
+    /// Derives the parameters for the field `F`.
+    fn generate() -> Self {
+        let alpha = field::smallest_permutation_exponent::<F>();
+        let alpha_inv = field::inverse_exponent::<F>(alpha);
+
+        let mds = std::array::from_fn(|ii| {
+            std::array::from_fn(|jj| {
+                F::from((ii + jj + WIDTH) as u64)
+                    .inverse()
+                    .expect("the field is large enough")
+            })
+        });
+
+        let mut grain = Grain::new::<F>(SboxKind::Inverse, WIDTH, 2 * ROUNDS, 0);
+        let round_constants = (0..2 * ROUNDS * WIDTH)
+            .map(|_| grain.field_element())
+            .collect();
+
+        Self {
+            alpha: BigUint::from(alpha),
+            alpha_inv,
+            mds,
+            round_constants,
+        }
+    }
+}
+
+/// Applies the Rescue-Prime permutation to a state of [WIDTH] field elements.
+pub fn permutation<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    state: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    assert_eq!(state.len(), WIDTH);
+
+    let params = Params::<B::Field>::generate();
+    let mut constants = params.round_constants.chunks(WIDTH);
+
+    let mut state = state.to_vec();
+
+    for _ in 0..ROUNDS {
+        // the first step, with the S-box
+        for elem in state.iter_mut() {
+            *elem = field::pow(compiler, elem, &params.alpha, span)[0].clone();
+        }
+        state = linear_layer(
+            compiler,
+            &params.mds,
+            &state,
+            constants.next().unwrap(),
+            span,
+        );
+
+        // the second step, with the inverse S-box
+        for elem in state.iter_mut() {
+            *elem = field::root(compiler, elem, &params.alpha, &params.alpha_inv, span);
+        }
+        state = linear_layer(
+            compiler,
+            &params.mds,
+            &state,
+            constants.next().unwrap(),
+            span,
+        );
+    }
+
+    Var::new(state, span)
+}
+
+/// Returns `mds * state + constants`.
+fn linear_layer<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    mds: &[[B::Field; WIDTH]; WIDTH],
+    state: &[ConstOrCell<B::Field, B::Var>],
+    constants: &[B::Field],
+    span: Span,
+) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    mds.iter()
+        .zip(constants)
+        .map(|(row, cst)| {
+            let mut acc = ConstOrCell::Const(*cst);
+            for (coeff, elem) in row.iter().zip(state) {
+                let term = field::mul(compiler, elem, &ConstOrCell::Const(*coeff), span);
+                acc = field::add(compiler, &acc, &term[0], span)[0].clone();
+            }
+            acc
+        })
+        .collect()
+}
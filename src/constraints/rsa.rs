@@ -0,0 +1,201 @@
+//! RSA signatures with 2048-bit keys and the public exponent 65537 (RFC 8017),
+//! for messages hashed with SHA-256, verified with the non-native arithmetic of [super::bigint].
+//!
+//! The modulus and the signature are big integers of [NUM_LIMBS] limbs (in little-endian order),
+//! and the message hash is given as 32 bytes.
+//! The modulus can be a variable, but it must have exactly 2048 bits.
+//! The modular multiplications use the witnessed quotient and remainder of [bigint::mul_add_mod_var]
+//! rather than Montgomery multiplication, as checking a division costs as many constraints as a multiplication.
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    bigint::{self, LIMB_BITS},
+    field, sha2,
+    words::{constant, xor, Word},
+};
+
+/// The number of limbs of a 2048-bit integer.
+pub const NUM_LIMBS: usize = 2048 / LIMB_BITS;
+
+/// The size of the encoded message, in bytes.
+const EM_LEN: usize = 256;
+
+/// The size of a SHA-256 digest (and of the salt of PSS signatures), in bytes.
+const HASH_LEN: usize = 32;
+
+/// The DER encoding of the `DigestInfo` of SHA-256, without the digest.
+const SHA256_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// Verifies a PKCS#1 v1.5 signature: `s^e mod n` must be `0x00 || 0x01 || 0xff... || 0x00 || DigestInfo || H`.
+pub fn verify_pkcs1v15<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    modulus: &[ConstOrCell<B::Field, B::Var>],
+    signature: &[ConstOrCell<B::Field, B::Var>],
+    message_hash: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<()> {
+    let encoded = encrypt(compiler, modulus, signature, span)?;
+
+    // the padding and the DigestInfo are constant, and the digest fills the least significant limbs
+    let mut padding = vec![0x00, 0x01];
+    padding.resize(EM_LEN - SHA256_DIGEST_INFO.len() - HASH_LEN - 1, 0xff);
+    padding.push(0x00);
+    padding.extend(SHA256_DIGEST_INFO);
+    padding.extend([0; HASH_LEN]);
+    let mut expected =
+        bigint::constant_with_limbs::<B>(&BigUint::from_bytes_be(&padding), NUM_LIMBS);
+
+    let hash_bits: Vec<_> = message_hash
+        .iter()
+        .rev()
+        .flat_map(|byte| field::to_bits(compiler, byte, 8, span).cvars)
+        .collect();
+    for (limb, bits) in expected.iter_mut().zip(hash_bits.chunks(LIMB_BITS)) {
+        *limb = field::from_bits(compiler, bits, span)[0].clone();
+    }
+
+    bigint::assert_eq(compiler, &encoded, &expected, span);
+    Ok(())
+}
+
+/// Verifies a PSS signature, with MGF1 and SHA-256, and a salt of 32 bytes.
+pub fn verify_pss<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    modulus: &[ConstOrCell<B::Field, B::Var>],
+    signature: &[ConstOrCell<B::Field, B::Var>],
+    message_hash: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<()> {
+    let encoded = encrypt(compiler, modulus, signature, span)?;
+
+    // the bytes of the encoded message, in big-endian order
+    let bits = bigint::to_bits(compiler, &encoded, span);
+    let bytes: Vec<Word<B>> = bits.chunks(8).rev().map(<[_]>::to_vec).collect();
+    let zero = ConstOrCell::Const(B::Field::zero());
+
+    // EM = maskedDB || H || 0xbc
+    let (masked_db, rest) = bytes.split_at(EM_LEN - HASH_LEN - 1);
+    let (hash, trailer) = rest.split_at(HASH_LEN);
+    let trailer = field::from_bits(compiler, &trailer[0], span);
+    field::assert_eq_cells(
+        compiler,
+        &trailer[0],
+        &ConstOrCell::Const(B::Field::from(0xbcu64)),
+        span,
+    )?;
+
+    // the encoded message has 2047 bits, so the most significant bit of maskedDB is zero
+    field::assert_eq_cells(compiler, &masked_db[0][7], &zero, span)?;
+
+    // DB = maskedDB xor MGF1(H), ignoring the most significant bit
+    let hash: Vec<_> = hash
+        .iter()
+        .map(|byte| field::from_bits(compiler, byte, span)[0].clone())
+        .collect();
+    let mask = mgf1(compiler, &hash, masked_db.len(), span);
+    let mut db: Vec<Word<B>> = masked_db
+        .iter()
+        .zip(&mask)
+        .map(|(byte, mask)| xor(compiler, byte, mask, span))
+        .collect();
+    db[0][7] = zero.clone();
+
+    // DB = 0x00... || 0x01 || salt
+    let (padding, salt) = db.split_at(db.len() - HASH_LEN);
+    let (separator, padding) = padding.split_last().unwrap();
+    for bit in padding.iter().flatten() {
+        field::assert_eq_cells(compiler, bit, &zero, span)?;
+    }
+    for (bit, expected) in separator.iter().zip(constant::<B>(0x01, 8)) {
+        field::assert_eq_cells(compiler, bit, &expected, span)?;
+    }
+
+    // H = SHA-256(0x00 * 8 || mHash || salt)
+    let mut message = vec![zero; 8];
+    message.extend(message_hash.iter().cloned());
+    message.extend(
+        salt.iter()
+            .map(|byte| field::from_bits(compiler, byte, span)[0].clone()),
+    );
+    let expected = sha2::sha256(compiler, &message, span);
+    for (byte, expected) in hash.iter().zip(&expected.cvars) {
+        field::assert_eq_cells(compiler, byte, expected, span)?;
+    }
+
+    Ok(())
+}
+
+/// Computes `s^65537 mod n`, after checking that the modulus has 2048 bits and that the signature is reduced.
+fn encrypt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    modulus: &[ConstOrCell<B::Field, B::Var>],
+    signature: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Vec<ConstOrCell<B::Field, B::Var>>> {
+    assert_eq!(modulus.len(), NUM_LIMBS);
+    assert_eq!(signature.len(), NUM_LIMBS);
+
+    // the most significant bit of the modulus is set
+    let top_bits = field::to_bits(compiler, &modulus[NUM_LIMBS - 1], LIMB_BITS, span);
+    let one = ConstOrCell::Const(B::Field::one());
+    field::assert_eq_cells(compiler, &top_bits[LIMB_BITS - 1], &one, span)?;
+
+    // s < n
+    let zero = bigint::constant_with_limbs::<B>(&BigUint::zero(), NUM_LIMBS);
+    let one = bigint::constant_with_limbs::<B>(&BigUint::one(), NUM_LIMBS);
+    let reduced = bigint::mul_add_mod_var(compiler, signature, &one, &zero, modulus, span)?;
+    bigint::assert_eq(compiler, &reduced.cvars, signature, span);
+
+    // s^(2^16 + 1)
+    let mut acc = reduced.cvars;
+    for _ in 0..16 {
+        acc = bigint::mul_add_mod_var(compiler, &acc, &acc, &zero, modulus, span)?.cvars;
+    }
+    let res = bigint::mul_add_mod_var(compiler, &acc, signature, &zero, modulus, span)?;
+
+    Ok(res.cvars)
+}
+
+/// The mask generation function MGF1 with SHA-256, which returns `len` bytes (as bits).
+fn mgf1<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    seed: &[ConstOrCell<B::Field, B::Var>],
+    len: usize,
+    span: Span,
+) -> Vec<Word<B>> {
+    let mut mask = Vec::with_capacity(len);
+
+    for counter in 0..len.div_ceil(HASH_LEN) as u32 {
+        // SHA-256(seed || counter), with the counter in big-endian order
+        let mut input = seed.to_vec();
+        input.extend(
+            counter
+                .to_be_bytes()
+                .map(|byte| ConstOrCell::Const(B::Field::from(byte as u64))),
+        );
+
+        let digest = sha2::sha256(compiler, &input, span);
+        mask.extend(
+            digest
+                .cvars
+                .iter()
+                .map(|byte| field::to_bits(compiler, byte, 8, span).cvars),
+        );
+    }
+
+    mask.truncate(len);
+    mask
+}
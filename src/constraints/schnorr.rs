@@ -0,0 +1,61 @@
+//! Schnorr signatures over the short Weierstrass curve embedded in the field of the backend
+//! (see [super::weierstrass]), with the Poseidon of the backend for the challenge.
+//!
+//! The scheme follows the one of Mina: a signature `(r, s)` of a message `m` under the public key `P` is valid
+//! if the point `R = [s] G - [e] P` has an even `y` coordinate and `R.x = r`,
+//! where `e = H(H(H(P.x, P.y), r), m)`, `H` keeps the first element of the output of Poseidon, and `G` is the generator.
+//! Mina computes its challenge with a different sponge and a network prefix, so signatures are not interchangeable.
+//!
+//! `s` is given as a field element, but the field is slightly smaller than the order of the curve:
+//! signatures where `s` doesn't fit in the field (which happens with a negligible probability) can't be verified.
+
+use ark_ff::Zero;
+
+use crate::{
+    backends::Backend, circuit_writer::CircuitWriter, constants::Span, error::Result,
+    var::ConstOrCell,
+};
+
+use super::{
+    field, merkle,
+    weierstrass::{self, Curve},
+};
+
+/// Verifies a signature `[r, s]` of a message under a public key `[x, y]`.
+pub fn verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    public_key: &[ConstOrCell<B::Field, B::Var>],
+    signature: &[ConstOrCell<B::Field, B::Var>],
+    message: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<()> {
+    let [public_key_x, public_key_y] = public_key else {
+        unreachable!("a public key is made of 2 field elements");
+    };
+    let [r, s] = signature else {
+        unreachable!("a signature is made of 2 field elements");
+    };
+    let public_key = [public_key_x.clone(), public_key_y.clone()];
+    weierstrass::assert_on_curve(compiler, curve, &public_key, span)?;
+
+    // e = H(H(H(P.x, P.y), r), m)
+    let e = merkle::poseidon(compiler, public_key_x, public_key_y, span)?;
+    let e = merkle::poseidon(compiler, &e, r, span)?;
+    let e = merkle::poseidon(compiler, &e, message, span)?;
+
+    // R = [s] G - [e] P
+    let s_bits = field::to_bits_strict(compiler, s, span);
+    let e_bits = field::to_bits_strict(compiler, &e, span);
+    let generator = weierstrass::constant::<B>(curve.generator);
+    let lhs = weierstrass::scalar_mul(compiler, curve, &generator, &s_bits.cvars, span)?;
+    let rhs = weierstrass::scalar_mul(compiler, curve, &public_key, &e_bits.cvars, span)?;
+    let rhs = weierstrass::neg(compiler, &rhs, span);
+    let point = weierstrass::add(compiler, &lhs, &rhs, span)?;
+
+    // R.x = r, and R.y is even
+    field::assert_eq_cells(compiler, &point[0], r, span)?;
+    let y_bits = field::to_bits_strict(compiler, &point[1], span);
+    let zero = ConstOrCell::Const(B::Field::zero());
+    field::assert_eq_cells(compiler, &y_bits[0], &zero, span)
+}
@@ -0,0 +1,610 @@
+//! The SHA-256 and SHA-512 hash functions (FIPS 180-4), for messages of a length known at compile time.
+//!
+//! Both functions share the same structure, and only differ in their parameters (see [Params]).
+//! Words are represented as 32 (or 64) boolean cells (see [super::words]),
+//! so that rotations and shifts are free and only XOR, `Ch`, `Maj`, and additions cost constraints.
+//!
+//! If the backend can XOR values with lookups, words are packed in single cells instead (see [super::packed]),
+//! as XOR, `Ch`, and `Maj` then only cost a few rows of lookups per word, and additions are cheaper too.
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    boolean, field, packed,
+    words::{add, constant, rotr, shr, xor, Word},
+};
+
+/// The parameters of a function of the SHA-2 family.
+struct Params {
+    /// The size of a word, in bits.
+    word_bits: usize,
+
+    /// The round constants (there is one per round).
+    k: &'static [u64],
+
+    /// The initial hash value.
+    iv: [u64; 8],
+
+    /// The rotations and the shift of the small sigma functions of the message schedule.
+    small_sigmas: [[usize; 3]; 2],
+
+    /// The rotations of the big sigma functions of the rounds.
+    big_sigmas: [[usize; 3]; 2],
+}
+
+impl Params {
+    /// The size of a block, in bytes.
+    fn block_len(&self) -> usize {
+        2 * self.word_bits
+    }
+
+    /// The size of the encoding of the message length (in the padding), in bytes.
+    fn length_len(&self) -> usize {
+        self.word_bits / 4
+    }
+}
+
+const SHA256: Params = Params {
+    word_bits: 32,
+    k: &[
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ],
+    iv: [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ],
+    small_sigmas: [[7, 18, 3], [17, 19, 10]],
+    big_sigmas: [[2, 13, 22], [6, 11, 25]],
+};
+
+const SHA512: Params = Params {
+    word_bits: 64,
+    k: &[
+        0x428a2f98d728ae22,
+        0x7137449123ef65cd,
+        0xb5c0fbcfec4d3b2f,
+        0xe9b5dba58189dbbc,
+        0x3956c25bf348b538,
+        0x59f111f1b605d019,
+        0x923f82a4af194f9b,
+        0xab1c5ed5da6d8118,
+        0xd807aa98a3030242,
+        0x12835b0145706fbe,
+        0x243185be4ee4b28c,
+        0x550c7dc3d5ffb4e2,
+        0x72be5d74f27b896f,
+        0x80deb1fe3b1696b1,
+        0x9bdc06a725c71235,
+        0xc19bf174cf692694,
+        0xe49b69c19ef14ad2,
+        0xefbe4786384f25e3,
+        0x0fc19dc68b8cd5b5,
+        0x240ca1cc77ac9c65,
+        0x2de92c6f592b0275,
+        0x4a7484aa6ea6e483,
+        0x5cb0a9dcbd41fbd4,
+        0x76f988da831153b5,
+        0x983e5152ee66dfab,
+        0xa831c66d2db43210,
+        0xb00327c898fb213f,
+        0xbf597fc7beef0ee4,
+        0xc6e00bf33da88fc2,
+        0xd5a79147930aa725,
+        0x06ca6351e003826f,
+        0x142929670a0e6e70,
+        0x27b70a8546d22ffc,
+        0x2e1b21385c26c926,
+        0x4d2c6dfc5ac42aed,
+        0x53380d139d95b3df,
+        0x650a73548baf63de,
+        0x766a0abb3c77b2a8,
+        0x81c2c92e47edaee6,
+        0x92722c851482353b,
+        0xa2bfe8a14cf10364,
+        0xa81a664bbc423001,
+        0xc24b8b70d0f89791,
+        0xc76c51a30654be30,
+        0xd192e819d6ef5218,
+        0xd69906245565a910,
+        0xf40e35855771202a,
+        0x106aa07032bbd1b8,
+        0x19a4c116b8d2d0c8,
+        0x1e376c085141ab53,
+        0x2748774cdf8eeb99,
+        0x34b0bcb5e19b48a8,
+        0x391c0cb3c5c95a63,
+        0x4ed8aa4ae3418acb,
+        0x5b9cca4f7763e373,
+        0x682e6ff3d6b2b8a3,
+        0x748f82ee5defb2fc,
+        0x78a5636f43172f60,
+        0x84c87814a1f0ab72,
+        0x8cc702081a6439ec,
+        0x90befffa23631e28,
+        0xa4506cebde82bde9,
+        0xbef9a3f7b2c67915,
+        0xc67178f2e372532b,
+        0xca273eceea26619c,
+        0xd186b8c721c0c207,
+        0xeada7dd6cde0eb1e,
+        0xf57d4f7fee6ed178,
+        0x06f067aa72176fba,
+        0x0a637dc5a2c898a6,
+        0x113f9804bef90dae,
+        0x1b710b35131c471b,
+        0x28db77f523047d84,
+        0x32caab7b40c72493,
+        0x3c9ebe0a15c9bebc,
+        0x431d67c49c100d4c,
+        0x4cc5d4becb3e42b6,
+        0x597f299cfc657e2a,
+        0x5fcb6fab3ad6faec,
+        0x6c44198c4a475817,
+    ],
+    iv: [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ],
+    small_sigmas: [[1, 8, 7], [19, 61, 6]],
+    big_sigmas: [[28, 34, 39], [14, 18, 41]],
+};
+
+/// Hashes a message of bytes with SHA-256, and returns the 32 bytes of the digest.
+/// The bytes of the message are constrained to fit in 8 bits.
+pub fn sha256<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    hash(compiler, &SHA256, message, span)
+}
+
+/// Hashes a message of bytes with SHA-512, and returns the 64 bytes of the digest.
+/// The bytes of the message are constrained to fit in 8 bits.
+pub fn sha512<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    hash(compiler, &SHA512, message, span)
+}
+
+/// Computes HMAC-SHA256 (RFC 2104) of a message of bytes with a key of bytes,
+/// and returns the 32 bytes of the MAC.
+/// The bytes of the key and of the message are constrained to fit in 8 bits.
+pub fn hmac_sha256<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    key: &[ConstOrCell<B::Field, B::Var>],
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let params = &SHA256;
+    let block_len = params.block_len();
+
+    // keys longer than a block are hashed first, and keys are padded with zeros to a block
+    let mut key = to_byte_bits(compiler, key, span);
+    if key.len() > block_len {
+        key = hash_bits(compiler, params, key, span);
+    }
+    key.resize(block_len, constant::<B>(0, 8));
+
+    // H((key ^ ipad) || message)
+    let mut inner: Vec<_> = key
+        .iter()
+        .map(|byte| xor(compiler, byte, &constant::<B>(0x36, 8), span))
+        .collect();
+    inner.extend(to_byte_bits(compiler, message, span));
+    let inner = hash_bits(compiler, params, inner, span);
+
+    // H((key ^ opad) || inner)
+    let mut outer: Vec<_> = key
+        .iter()
+        .map(|byte| xor(compiler, byte, &constant::<B>(0x5c, 8), span))
+        .collect();
+    outer.extend(inner);
+    let mac = hash_bits(compiler, params, outer, span);
+
+    from_byte_bits(compiler, &mac, span)
+}
+
+fn hash<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let bytes = to_byte_bits(compiler, message, span);
+    let digest = hash_bits(compiler, params, bytes, span);
+    from_byte_bits(compiler, &digest, span)
+}
+
+/// Decomposes bytes into bits (in little-endian order), which constrains them to fit in 8 bits.
+fn to_byte_bits<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bytes: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Vec<Vec<ConstOrCell<B::Field, B::Var>>> {
+    bytes
+        .iter()
+        .map(|byte| field::to_bits(compiler, byte, 8, span).cvars)
+        .collect()
+}
+
+/// Packs bytes given as bits (in little-endian order).
+fn from_byte_bits<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bytes: &[Vec<ConstOrCell<B::Field, B::Var>>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let bytes = bytes
+        .iter()
+        .map(|byte| field::from_bits(compiler, byte, span)[0].clone())
+        .collect();
+    Var::new(bytes, span)
+}
+
+/// Hashes a message of bytes (each as 8 bits in little-endian order),
+/// and returns the bytes of the digest in the same representation.
+fn hash_bits<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params,
+    mut bytes: Vec<Vec<ConstOrCell<B::Field, B::Var>>>,
+    span: Span,
+) -> Vec<Vec<ConstOrCell<B::Field, B::Var>>> {
+    // pad the message
+    let padding = padding(params, bytes.len());
+    bytes.extend(
+        padding
+            .into_iter()
+            .map(|byte| constant::<B>(byte as u64, 8)),
+    );
+
+    // words are packed if the backend can XOR them with lookups
+    if packed::is_supported(compiler, params.word_bits) {
+        return hash_blocks_packed(compiler, params, &bytes, span);
+    }
+
+    // process the blocks
+    let mut state: Vec<Word<B>> = params
+        .iv
+        .iter()
+        .map(|iv| constant::<B>(*iv, params.word_bits))
+        .collect();
+    for block in bytes.chunks(params.block_len()) {
+        state = compress(compiler, params, &state, block, span);
+    }
+
+    // the digest is the state in big-endian order
+    state
+        .iter()
+        .flat_map(|word| word.chunks(8).rev().map(<[_]>::to_vec))
+        .collect()
+}
+
+/// Processes the blocks of a padded message like [hash_bits],
+/// but with words packed in single cells (see [super::packed]).
+fn hash_blocks_packed<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params,
+    bytes: &[Vec<ConstOrCell<B::Field, B::Var>>],
+    span: Span,
+) -> Vec<Vec<ConstOrCell<B::Field, B::Var>>> {
+    let mut state: Vec<packed::Word<B>> = params
+        .iv
+        .iter()
+        .map(|iv| packed::constant::<B>(*iv))
+        .collect();
+    for block in bytes.chunks(params.block_len()) {
+        state = compress_packed(compiler, params, &state, block, span);
+    }
+
+    // the digest is the state in big-endian order
+    let mut digest = Vec::with_capacity(state.len() * params.word_bits / 8);
+    for word in &state {
+        let bits = packed::to_bits(compiler, word, params.word_bits, span);
+        digest.extend(bits.chunks(8).rev().map(<[_]>::to_vec));
+    }
+    digest
+}
+
+/// Returns the bytes appended to a message of `len` bytes:
+/// a single 1 bit, zeros, and the length of the message in bits (as a big-endian integer).
+fn padding(params: &Params, len: usize) -> Vec<u8> {
+    let block_len = params.block_len();
+    let length_len = params.length_len();
+    let num_zeros = (block_len - (len + 1 + length_len) % block_len) % block_len;
+
+    let mut padding = vec![0x80];
+    padding.extend(std::iter::repeat(0).take(num_zeros));
+
+    // the length is encoded on 64 or 128 bits, but always fits in 64 bits
+    let len_bits = (8 * len as u64).to_be_bytes();
+    padding.extend(std::iter::repeat(0).take(length_len - len_bits.len()));
+    padding.extend(len_bits);
+    padding
+}
+
+/// Applies the compression function to a block of bytes (each as 8 bits in little-endian order).
+fn compress<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params,
+    state: &[Word<B>],
+    block: &[Vec<ConstOrCell<B::Field, B::Var>>],
+    span: Span,
+) -> Vec<Word<B>> {
+    // the message schedule starts with the block, read as big-endian words
+    let word_len = params.word_bits / 8;
+    let mut schedule: Vec<Word<B>> = block
+        .chunks(word_len)
+        .map(|bytes| bytes.iter().rev().flatten().cloned().collect())
+        .collect();
+
+    let [sigma0, sigma1] = params.small_sigmas;
+    for tt in 16..params.k.len() {
+        let s0 = small_sigma(compiler, &schedule[tt - 15], sigma0, span);
+        let s1 = small_sigma(compiler, &schedule[tt - 2], sigma1, span);
+        let words = [&s1, &schedule[tt - 7], &s0, &schedule[tt - 16]];
+        let word = add(compiler, &words, span);
+        schedule.push(word);
+    }
+
+    // the rounds
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h]: [Word<B>; 8] = state
+        .to_vec()
+        .try_into()
+        .unwrap_or_else(|_| unreachable!("the state has 8 words"));
+
+    let [sigma0, sigma1] = params.big_sigmas;
+    for (tt, k) in params.k.iter().enumerate() {
+        let s1 = big_sigma(compiler, &e, sigma1, span);
+        let choice = ch(compiler, &e, &f, &g, span);
+        let s0 = big_sigma(compiler, &a, sigma0, span);
+        let majority = maj(compiler, &a, &b, &c, span);
+        let k = constant::<B>(*k, params.word_bits);
+
+        // instead of computing `t1` and `t2` as separate sums,
+        // we only decompose `e = d + t1` and `a = t1 + t2`
+        let new_e = add(compiler, &[&d, &h, &s1, &choice, &k, &schedule[tt]], span);
+        let new_a = add(
+            compiler,
+            &[&h, &s1, &choice, &k, &schedule[tt], &s0, &majority],
+            span,
+        );
+
+        h = g;
+        g = f;
+        f = e;
+        e = new_e;
+        d = c;
+        c = b;
+        b = a;
+        a = new_a;
+    }
+
+    // add the compressed chunk to the state
+    [a, b, c, d, e, f, g, h]
+        .iter()
+        .zip(state)
+        .map(|(word, prev)| add(compiler, &[prev, word], span))
+        .collect()
+}
+
+/// Same as [compress], on packed words.
+fn compress_packed<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    params: &Params,
+    state: &[packed::Word<B>],
+    block: &[Vec<ConstOrCell<B::Field, B::Var>>],
+    span: Span,
+) -> Vec<packed::Word<B>> {
+    let word_bits = params.word_bits;
+
+    // the message schedule starts with the block, read as big-endian words
+    let word_len = word_bits / 8;
+    let mut schedule = Vec::with_capacity(params.k.len());
+    for bytes in block.chunks(word_len) {
+        let bits: Vec<_> = bytes.iter().rev().flatten().cloned().collect();
+        schedule.push(packed::from_bits(compiler, &bits, span));
+    }
+
+    let [sigma0, sigma1] = params.small_sigmas;
+    for tt in 16..params.k.len() {
+        let s0 = small_sigma_packed(compiler, &schedule[tt - 15], sigma0, word_bits, span);
+        let s1 = small_sigma_packed(compiler, &schedule[tt - 2], sigma1, word_bits, span);
+        let words = [&s1, &schedule[tt - 7], &s0, &schedule[tt - 16]];
+        let word = packed::add(compiler, &words, word_bits, span);
+        schedule.push(word);
+    }
+
+    // the rounds
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h]: [packed::Word<B>; 8] = state
+        .to_vec()
+        .try_into()
+        .unwrap_or_else(|_| unreachable!("the state has 8 words"));
+
+    let [sigma0, sigma1] = params.big_sigmas;
+    for (tt, k) in params.k.iter().enumerate() {
+        let s1 = big_sigma_packed(compiler, &e, sigma1, word_bits, span);
+        let choice = ch_packed(compiler, &e, &f, &g, word_bits, span);
+        let s0 = big_sigma_packed(compiler, &a, sigma0, word_bits, span);
+        let majority = maj_packed(compiler, &a, &b, &c, word_bits, span);
+        let k = packed::constant::<B>(*k);
+
+        let new_e = packed::add(
+            compiler,
+            &[&d, &h, &s1, &choice, &k, &schedule[tt]],
+            word_bits,
+            span,
+        );
+        let new_a = packed::add(
+            compiler,
+            &[&h, &s1, &choice, &k, &schedule[tt], &s0, &majority],
+            word_bits,
+            span,
+        );
+
+        h = g;
+        g = f;
+        f = e;
+        e = new_e;
+        d = c;
+        c = b;
+        b = a;
+        a = new_a;
+    }
+
+    // add the compressed chunk to the state
+    [a, b, c, d, e, f, g, h]
+        .iter()
+        .zip(state)
+        .map(|(word, prev)| packed::add(compiler, &[prev, word], word_bits, span))
+        .collect()
+}
+
+/// Returns `rotr(x, r0) ^ rotr(x, r1) ^ shr(x, s)`.
+fn small_sigma<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    x: &Word<B>,
+    [r0, r1, s]: [usize; 3],
+    span: Span,
+) -> Word<B> {
+    let (x0, x1, x2) = (rotr::<B>(x, r0), rotr::<B>(x, r1), shr::<B>(x, s));
+    xor3(compiler, &x0, &x1, &x2, span)
+}
+
+/// Returns `rotr(x, r0) ^ rotr(x, r1) ^ rotr(x, r2)`.
+fn big_sigma<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    x: &Word<B>,
+    [r0, r1, r2]: [usize; 3],
+    span: Span,
+) -> Word<B> {
+    let (x0, x1, x2) = (rotr::<B>(x, r0), rotr::<B>(x, r1), rotr::<B>(x, r2));
+    xor3(compiler, &x0, &x1, &x2, span)
+}
+
+fn xor3<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    x: &Word<B>,
+    y: &Word<B>,
+    z: &Word<B>,
+    span: Span,
+) -> Word<B> {
+    let tmp = xor(compiler, x, y, span);
+    xor(compiler, &tmp, z, span)
+}
+
+/// Returns `(e & f) ^ (!e & g)`, computed as `g + e * (f - g)` for every bit.
+fn ch<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    e: &Word<B>,
+    f: &Word<B>,
+    g: &Word<B>,
+    span: Span,
+) -> Word<B> {
+    let mut res = Vec::with_capacity(e.len());
+    for ii in 0..e.len() {
+        let diff = field::sub(compiler, &f[ii], &g[ii], span);
+        let prod = field::mul(compiler, &e[ii], &diff[0], span);
+        res.push(field::add(compiler, &g[ii], &prod[0], span)[0].clone());
+    }
+    res
+}
+
+/// Returns `(a & b) ^ (a & c) ^ (b & c)`, computed as `a * b + c * (a ^ b)` for every bit.
+fn maj<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    a: &Word<B>,
+    b: &Word<B>,
+    c: &Word<B>,
+    span: Span,
+) -> Word<B> {
+    let mut res = Vec::with_capacity(a.len());
+    for ii in 0..a.len() {
+        let both = boolean::and(compiler, &a[ii], &b[ii], span);
+        let either = boolean::xor(compiler, &a[ii], &b[ii], span);
+        let prod = field::mul(compiler, &c[ii], &either[0], span);
+        res.push(field::add(compiler, &both[0], &prod[0], span)[0].clone());
+    }
+    res
+}
+
+/// Same as [small_sigma], on packed words.
+fn small_sigma_packed<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    x: &packed::Word<B>,
+    [r0, r1, s]: [usize; 3],
+    word_bits: usize,
+    span: Span,
+) -> packed::Word<B> {
+    let x0 = packed::rotr(compiler, x, r0, word_bits, span);
+    let x1 = packed::rotr(compiler, x, r1, word_bits, span);
+    let x2 = packed::shr(compiler, x, s, word_bits, span);
+    let tmp = packed::xor(compiler, &x0, &x1, word_bits, span);
+    packed::xor(compiler, &tmp, &x2, word_bits, span)
+}
+
+/// Same as [big_sigma], on packed words.
+fn big_sigma_packed<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    x: &packed::Word<B>,
+    [r0, r1, r2]: [usize; 3],
+    word_bits: usize,
+    span: Span,
+) -> packed::Word<B> {
+    let x0 = packed::rotr(compiler, x, r0, word_bits, span);
+    let x1 = packed::rotr(compiler, x, r1, word_bits, span);
+    let x2 = packed::rotr(compiler, x, r2, word_bits, span);
+    let tmp = packed::xor(compiler, &x0, &x1, word_bits, span);
+    packed::xor(compiler, &tmp, &x2, word_bits, span)
+}
+
+/// Returns `(e & f) ^ (!e & g)` on packed words, where the XOR is an addition as the two sides have no bit in common.
+fn ch_packed<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    e: &packed::Word<B>,
+    f: &packed::Word<B>,
+    g: &packed::Word<B>,
+    word_bits: usize,
+    span: Span,
+) -> packed::Word<B> {
+    let lhs = packed::and(compiler, e, f, word_bits, span);
+    let not_e = packed::not(compiler, e, word_bits, span);
+    let rhs = packed::and(compiler, &not_e, g, word_bits, span);
+    field::add(compiler, &lhs, &rhs, span)[0].clone()
+}
+
+/// Returns `(a & b) ^ (a & c) ^ (b & c)` on packed words, computed as `(a & b) + (c & (a ^ b))`.
+fn maj_packed<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    a: &packed::Word<B>,
+    b: &packed::Word<B>,
+    c: &packed::Word<B>,
+    word_bits: usize,
+    span: Span,
+) -> packed::Word<B> {
+    let both = packed::and(compiler, a, b, word_bits, span);
+    let either = packed::xor(compiler, a, b, word_bits, span);
+    let rhs = packed::and(compiler, c, &either, word_bits, span);
+    field::add(compiler, &both, &rhs, span)[0].clone()
+}
@@ -0,0 +1,147 @@
+//! Sinsemilla hashes and commitments, as used in Orchard, over Pallas (so for the kimchi backend over Vesta only, see [super::weierstrass]).
+//!
+//! The message is split into chunks of [K] bits, and the hash starts from a point `Q` derived from a domain:
+//! each chunk `m` updates the accumulator to `(acc + S(m)) + acc`, where `S` is a table of `2^K` points.
+//! The hash is the `x` coordinate of the accumulator.
+//!
+//! The points of the table are looked up with a multiplexer on the bits of the chunk (see [field::constant_lookup]).
+//! The generators are derived with Keccak-256 from the domain (see [weierstrass::Curve::hash_to_point])
+//! instead of the `GroupHash` of Zcash, so hashes have the same structure but don't match the ones of Orchard.
+//!
+//! The additions are incomplete: the exceptional cases make the circuit unsatisfiable,
+//! and only happen with a negligible probability.
+
+use ark_ff::Zero;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::{ConstOrCell, Var},
+};
+
+use super::{
+    field,
+    weierstrass::{self, Curve, Point},
+};
+
+/// The number of bits of a chunk.
+pub const K: usize = 10;
+
+/// The maximum number of chunks of a message (as in Orchard).
+pub const MAX_CHUNKS: usize = 253;
+
+/// The generators of Sinsemilla for a domain.
+pub struct Generators<F> {
+    /// The initial value of the accumulator.
+    pub q: (F, F),
+
+    /// The table of points that the chunks select.
+    pub s: Vec<(F, F)>,
+
+    /// The base of the blinding factor of commitments.
+    pub r: (F, F),
+}
+
+impl<F: BackendField> Generators<F> {
+    /// Derives the generators of a domain.
+    pub fn new(curve: &Curve<F>, domain: &str) -> Self {
+        Self {
+            q: curve.hash_to_point(&format!("noname_sinsemilla_q_{domain}")),
+            s: (0..1 << K)
+                .map(|ii| curve.hash_to_point(&format!("noname_sinsemilla_s_{ii}")))
+                .collect(),
+            r: curve.hash_to_point(&format!("noname_sinsemilla_r_{domain}")),
+        }
+    }
+
+    /// Returns the point of a message of bits (in little-endian order) out of the circuit,
+    /// or `None` if the hash hits an exceptional case.
+    pub fn hash_to_point(&self, curve: &Curve<F>, message: &[bool]) -> Option<(F, F)> {
+        let mut acc = self.q;
+        for chunk in message.chunks(K) {
+            let index = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |index, (ii, bit)| index | (usize::from(*bit) << ii));
+
+            // the incomplete formulas can't add points with the same `x`
+            let point = self.s[index];
+            if point.0 == acc.0 {
+                return None;
+            }
+            let sum = curve.add(Some(acc), Some(point))?;
+            if sum.0 == acc.0 {
+                return None;
+            }
+            acc = curve.add(Some(sum), Some(acc))?;
+        }
+        Some(acc)
+    }
+}
+
+/// Returns the `x` coordinate of the Sinsemilla hash of a message of bits.
+pub fn hash<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    domain: &str,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let generators = Generators::new(curve, domain);
+    let point = hash_to_point(compiler, &generators, message, span)?;
+    Ok(Var::new_cvar(point[0].clone(), span))
+}
+
+/// Returns the `x` coordinate of the Sinsemilla commitment to a message of bits:
+/// its hash, plus the blinding factor times an independent generator.
+pub fn commit<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    domain: &str,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    blinding: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let generators = Generators::new(curve, domain);
+    let point = hash_to_point(compiler, &generators, message, span)?;
+
+    // add the blinding factor times an independent generator
+    let bits = field::to_bits_strict(compiler, blinding, span);
+    let base = weierstrass::constant::<B>(generators.r);
+    let blinding = weierstrass::scalar_mul(compiler, curve, &base, &bits.cvars, span)?;
+    let res = weierstrass::add(compiler, &point, &blinding, span)?;
+
+    Ok(Var::new_cvar(res[0].clone(), span))
+}
+
+fn hash_to_point<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    generators: &Generators<B::Field>,
+    message: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Point<B>> {
+    assert!(message.len() <= K * MAX_CHUNKS);
+
+    // pad the message with zeros to a multiple of K bits
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let mut bits = message.to_vec();
+    bits.resize(message.len().div_ceil(K) * K, zero);
+
+    let xs: Vec<_> = generators.s.iter().map(|point| point.0).collect();
+    let ys: Vec<_> = generators.s.iter().map(|point| point.1).collect();
+
+    let mut acc = weierstrass::constant::<B>(generators.q);
+    for chunk in bits.chunks(K) {
+        // S(m)
+        let x = field::constant_lookup(compiler, &xs, chunk, span);
+        let y = field::constant_lookup(compiler, &ys, chunk, span);
+
+        // (acc + S(m)) + acc
+        let sum = weierstrass::add(compiler, &acc, &[x, y], span)?;
+        acc = weierstrass::add(compiler, &sum, &acc, span)?;
+    }
+
+    Ok(acc)
+}
@@ -0,0 +1,133 @@
+//! Sorting with Batcher's odd-even merge sort.
+//!
+//! A sorting network is a fixed sequence of compare-and-swap operations,
+//! which makes it a natural fit for circuits:
+//! sorting `n` values takes `O(n log^2 n)` comparisons, independently of the values.
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::Result,
+    var::{ConstOrCell, Var},
+};
+
+use super::field;
+
+/// Sorts values in increasing order.
+/// Values are compared as signed integers of `signed_bits` bits if set,
+/// and as field elements of [field::comparison_bits] bits otherwise (see [field::min]).
+pub fn sort<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    signed_bits: Option<usize>,
+    values: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let rows = values.iter().map(|value| vec![value.clone()]).collect();
+    let sorted = sort_rows(compiler, signed_bits, rows, span)?;
+
+    Ok(Var::new(
+        sorted.into_iter().map(|row| row[0].clone()).collect(),
+        span,
+    ))
+}
+
+/// Returns the permutation that sorts the values (see [sort]):
+/// the `i`-th element is the index of the `i`-th smallest value.
+/// Note that sorting networks are not stable: equal values can end up in any order.
+pub fn argsort<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    signed_bits: Option<usize>,
+    values: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let rows = values
+        .iter()
+        .enumerate()
+        .map(|(ii, value)| vec![value.clone(), ConstOrCell::Const(B::Field::from(ii as u64))])
+        .collect();
+    let sorted = sort_rows(compiler, signed_bits, rows, span)?;
+
+    Ok(Var::new(
+        sorted.into_iter().map(|row| row[1].clone()).collect(),
+        span,
+    ))
+}
+
+/// Sorts rows according to their first element, with an odd-even merge sorting network.
+fn sort_rows<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    signed_bits: Option<usize>,
+    mut rows: Vec<Vec<ConstOrCell<B::Field, B::Var>>>,
+    span: Span,
+) -> Result<Vec<Vec<ConstOrCell<B::Field, B::Var>>>> {
+    for (lo, hi) in comparators(rows.len()) {
+        // swap if rows[hi] < rows[lo]
+        let swap = field::compare(compiler, signed_bits, &rows[hi][0], &rows[lo][0], span)?;
+
+        let lhs = Var::new(rows[lo].clone(), span);
+        let rhs = Var::new(rows[hi].clone(), span);
+        let smaller = field::if_else(compiler, &swap, &rhs, &lhs, span);
+
+        // the larger row is obtained without a second multiplexer: lhs + rhs - smaller
+        for col in 0..rows[lo].len() {
+            let sum = field::add(compiler, &lhs[col], &rhs[col], span);
+            let larger = field::sub(compiler, &sum[0], &smaller[col], span);
+
+            rows[lo][col] = smaller[col].clone();
+            rows[hi][col] = larger[0].clone();
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Returns the comparators of Batcher's odd-even merge sort for `len` elements,
+/// as pairs of indices `(lo, hi)` with `lo < hi`.
+/// This works for any length, as if the array was padded with infinitely large values.
+fn comparators(len: usize) -> Vec<(usize, usize)> {
+    let mut res = vec![];
+
+    let mut pp = 1;
+    while pp < len {
+        let mut kk = pp;
+        while kk >= 1 {
+            let mut jj = kk % pp;
+            while jj + kk < len {
+                for ii in 0..kk.min(len - jj - kk) {
+                    if (ii + jj) / (2 * pp) == (ii + jj + kk) / (2 * pp) {
+                        res.push((ii + jj, ii + jj + kk));
+                    }
+                }
+                jj += 2 * kk;
+            }
+            kk /= 2;
+        }
+        pp *= 2;
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparators() {
+        // exhaustively check the network on all 0/1 inputs (0-1 principle)
+        for len in 0..12 {
+            let comparators = comparators(len);
+            for input in 0..(1u32 << len) {
+                let mut values: Vec<_> = (0..len).map(|ii| (input >> ii) & 1).collect();
+                for &(lo, hi) in &comparators {
+                    assert!(lo < hi);
+                    if values[hi] < values[lo] {
+                        values.swap(lo, hi);
+                    }
+                }
+                assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+            }
+        }
+    }
+}
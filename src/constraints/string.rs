@@ -0,0 +1,212 @@
+//! Strings of a fixed capacity and a variable length, known only at runtime.
+//!
+//! A string is an array of bytes, where the string is followed by zeros up to the capacity of the array
+//! (like the values extracted from JSON documents, see [super::json]).
+//! The length is not stored: it is recomputed from the bytes when needed (see [length]),
+//! which also checks that a zero byte is never followed by a non-zero one.
+//! As a consequence, strings can't contain zero bytes.
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::{ErrorKind, Result},
+    var::{ConstOrCell, Var},
+};
+
+use super::field;
+
+/// A cell of the circuit.
+type Cell<B> = ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>;
+
+/// The direction bytes are moved to by [shift].
+#[derive(Clone, Copy, Debug)]
+enum Direction {
+    /// Towards the start of the array.
+    Left,
+    /// Towards the end of the array.
+    Right,
+}
+
+/// Returns the number of bits needed to represent `value`.
+fn bits_for(value: usize) -> usize {
+    (usize::BITS - value.leading_zeros()) as usize
+}
+
+/// Returns the length of a string: its number of non-zero bytes,
+/// which are constrained to all come before the zero bytes.
+pub fn length<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bytes: &[Cell<B>],
+    span: Span,
+) -> Result<Cell<B>> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let one = ConstOrCell::Const(B::Field::one());
+
+    let mut len = zero.clone();
+    let mut prev_is_zero = zero.clone();
+    for byte in bytes {
+        let is_zero = field::equal_cells(compiler, byte, &zero, span)[0].clone();
+        let is_non_zero = field::sub(compiler, &one, &is_zero, span)[0].clone();
+
+        // a non-zero byte can't follow a zero byte
+        let gap = field::mul(compiler, &prev_is_zero, &is_non_zero, span);
+        field::assert_eq_cells(compiler, &gap[0], &zero, span)?;
+
+        len = field::add(compiler, &len, &is_non_zero, span)[0].clone();
+        prev_is_zero = is_zero;
+    }
+
+    Ok(len)
+}
+
+/// Returns 1 if two strings are equal, 0 otherwise.
+/// The strings can have different capacities, the shorter one is padded with zeros.
+pub fn equal<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[Cell<B>],
+    rhs: &[Cell<B>],
+    span: Span,
+) -> Var<B::Field, B::Var> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let capacity = lhs.len().max(rhs.len());
+
+    let mut lhs = lhs.to_vec();
+    let mut rhs = rhs.to_vec();
+    lhs.resize(capacity, zero.clone());
+    rhs.resize(capacity, zero);
+
+    field::equal(compiler, &Var::new(lhs, span), &Var::new(rhs, span), span)
+}
+
+/// Returns the `len` bytes of a string starting at `start`, as a string of capacity `capacity`.
+/// The substring is constrained to be within the string, and to fit in the capacity.
+pub fn substring<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bytes: &[Cell<B>],
+    start: &Cell<B>,
+    len: &Cell<B>,
+    capacity: usize,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+
+    // the substring fits in the capacity
+    let len_bits = bits_for(capacity);
+    let capacity_cst = ConstOrCell::Const(B::Field::from(capacity as u64));
+    let too_long = field::less_than(compiler, len_bits, &capacity_cst, len, span)?;
+    field::assert_eq_cells(compiler, &too_long[0], &zero, span)?;
+    field::range_check(compiler, len, len_bits, span)?;
+
+    // the substring ends within the string
+    // (the start is range-checked when shifting the string)
+    let str_len = length(compiler, bytes, span)?;
+    let end = field::add(compiler, start, len, span);
+    let num_bits = bits_for(bytes.len() + capacity);
+    let out_of_bounds = field::less_than(compiler, num_bits, &str_len, &end[0], span)?;
+    field::assert_eq_cells(compiler, &out_of_bounds[0], &zero, span)?;
+
+    // a constant start must fit in the bits of the shift
+    if let ConstOrCell::Const(cst) = start {
+        let cst: BigUint = (*cst).into();
+        if cst > BigUint::from(bytes.len()) {
+            return Err(compiler.error(ErrorKind::AssertionFailed, span));
+        }
+    }
+
+    // the substring is moved to the start, and the bytes after it are cleared
+    let mut shifted = shift(
+        compiler,
+        bytes.to_vec(),
+        start,
+        bits_for(bytes.len()),
+        Direction::Left,
+        span,
+    );
+    shifted.resize(capacity, zero);
+
+    let res = shifted
+        .iter()
+        .enumerate()
+        .map(|(ii, byte)| {
+            let position = ConstOrCell::Const(B::Field::from(ii as u64));
+            let is_kept = field::less_than(compiler, len_bits, &position, len, span)?;
+            Ok(field::mul(compiler, &is_kept[0], byte, span)[0].clone())
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(Var::new(res, span))
+}
+
+/// Returns the concatenation of two strings, as a string of capacity `capacity`.
+/// The result is constrained to fit in the capacity.
+pub fn concat<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[Cell<B>],
+    rhs: &[Cell<B>],
+    capacity: usize,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+
+    // the result fits in the capacity
+    let lhs_len = length(compiler, lhs, span)?;
+    let rhs_len = length(compiler, rhs, span)?;
+    let total = field::add(compiler, &lhs_len, &rhs_len, span);
+    let num_bits = bits_for(lhs.len() + rhs.len()).max(bits_for(capacity));
+    let shift_bits = bits_for(lhs.len());
+    let capacity_cst = ConstOrCell::Const(B::Field::from(capacity as u64));
+    let too_long = field::less_than(compiler, num_bits, &capacity_cst, &total[0], span)?;
+    field::assert_eq_cells(compiler, &too_long[0], &zero, span)?;
+
+    // the bytes past the capacity are zeros, as both strings fit in it
+    let mut lhs = lhs.to_vec();
+    let mut rhs = rhs.to_vec();
+    lhs.resize(capacity, zero.clone());
+    rhs.resize(capacity, zero);
+
+    // the second string is moved right after the first one,
+    // where the first string only has zeros
+    let shifted = shift(compiler, rhs, &lhs_len, shift_bits, Direction::Right, span);
+    let res = lhs
+        .iter()
+        .zip(&shifted)
+        .map(|(lhs, rhs)| field::add(compiler, lhs, rhs, span)[0].clone())
+        .collect();
+
+    Ok(Var::new(res, span))
+}
+
+/// Moves bytes by `amount` positions (filling the array with zeros) with a barrel shifter,
+/// one bit of `amount` at a time.
+/// This constrains `amount` to fit in `num_bits` bits.
+fn shift<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bytes: Vec<Cell<B>>,
+    amount: &Cell<B>,
+    num_bits: usize,
+    direction: Direction,
+    span: Span,
+) -> Vec<Cell<B>> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let amount_bits = field::to_bits(compiler, amount, num_bits, span);
+
+    let mut shifted = bytes;
+    for (ii, bit) in amount_bits.cvars.iter().enumerate() {
+        shifted = (0..shifted.len())
+            .map(|jj| {
+                let moved = match direction {
+                    Direction::Left => shifted.get(jj + (1 << ii)),
+                    Direction::Right => jj.checked_sub(1 << ii).map(|kk| &shifted[kk]),
+                };
+                let moved = moved.unwrap_or(&zero);
+                field::if_else_inner(compiler, bit, moved, &shifted[jj], span)[0].clone()
+            })
+            .collect();
+    }
+
+    shifted
+}
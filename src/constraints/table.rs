@@ -0,0 +1,93 @@
+//! Lookups in constant tables of `(index, value)` pairs,
+//! to evaluate functions that are expensive to compute in a circuit (like approximations of `sigmoid` or `exp`)
+//! on inputs of a small domain.
+//!
+//! Backends with lookup arguments (see [crate::backends::Capabilities::lookup]) check the pair with a single lookup.
+//! Otherwise, the index is compared with every index of the table,
+//! which costs a few constraints per entry.
+
+use std::sync::Arc;
+
+use ark_ff::{One, Zero};
+
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::{ErrorKind, Result},
+    var::{ConstOrCell, Value},
+};
+
+use super::field;
+
+/// Returns the value associated to `index` in a constant table,
+/// and constrains `index` to be one of the indices of the table.
+pub fn lookup<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    table: &[(B::Field, B::Field)],
+    index: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    // the indices must be distinct, for the value to be unique
+    for (ii, (entry, _)) in table.iter().enumerate() {
+        if table[..ii].iter().any(|(other, _)| other == entry) {
+            return Err(compiler.error(ErrorKind::InvalidLookupTable, span));
+        }
+    }
+    if table.is_empty() {
+        return Err(compiler.error(ErrorKind::InvalidLookupTable, span));
+    }
+
+    let index_cvar = match index {
+        ConstOrCell::Const(cst) => {
+            return table
+                .iter()
+                .find(|(entry, _)| entry == cst)
+                .map(|(_, value)| ConstOrCell::Const(*value))
+                .ok_or_else(|| compiler.error(ErrorKind::NotInLookupTable, span));
+        }
+        ConstOrCell::Cell(cvar) => cvar,
+    };
+
+    if compiler.backend.capabilities().lookup {
+        // the value is found out of circuit, and the pair is looked up
+        let entries = table.to_vec();
+        let value = compiler.backend.new_internal_var(
+            Value::Compute(
+                vec![index.clone()],
+                Arc::new(move |vals: &[B::Field]| {
+                    entries
+                        .iter()
+                        .find(|(entry, _)| *entry == vals[0])
+                        .map(|(_, value)| *value)
+                        .unwrap_or_else(B::Field::zero)
+                }),
+            ),
+            span,
+        );
+        compiler.backend.lookup(table, index_cvar, &value, span);
+
+        return Ok(ConstOrCell::Cell(value));
+    }
+
+    // otherwise, exactly one of the indices is equal to `index`,
+    // and selects its value
+    let mut found = ConstOrCell::Const(B::Field::zero());
+    let mut res = ConstOrCell::Const(B::Field::zero());
+    for (entry, value) in table {
+        let is_entry = field::equal_cells(compiler, index, &ConstOrCell::Const(*entry), span);
+        found = field::add(compiler, &found, &is_entry[0], span)[0].clone();
+
+        let term = field::mul(compiler, &is_entry[0], &ConstOrCell::Const(*value), span);
+        res = field::add(compiler, &res, &term[0], span)[0].clone();
+    }
+
+    match &found {
+        ConstOrCell::Const(_) => unreachable!("the index is a variable"),
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(cvar, B::Field::one(), span),
+    }
+
+    Ok(res)
+}
@@ -0,0 +1,77 @@
+//! Pedersen vector commitments over the curve embedded in the field of the backend (see [super::curve]).
+//!
+//! The commitment to the values `v_0, ..., v_(n-1)` with the blinding factor `r` is the point
+//! `[v_0] G_0 + ... + [v_(n-1)] G_(n-1) + [r] H`,
+//! where the generators `G_i` and `H` are derived from fixed seeds, so that their discrete logarithms are unknown.
+//! The commitment is binding (under the discrete logarithm assumption), and hiding if `r` is random.
+
+use crate::{
+    backends::Backend, circuit_writer::CircuitWriter, constants::Span, error::Result,
+    var::ConstOrCell,
+};
+
+use super::{
+    curve::{self, EmbeddedCurve, Point},
+    field,
+};
+
+/// Returns the commitment to values with a blinding factor.
+pub fn commit<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    values: &[ConstOrCell<B::Field, B::Var>],
+    blinding: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Point<B>> {
+    let mut bases: Vec<_> = (0..values.len())
+        .map(|ii| curve.independent_generator(&format!("noname_vector_commitment_{ii}")))
+        .collect();
+    bases.push(curve.independent_generator("noname_vector_commitment_blinding"));
+
+    let mut scalars = values.to_vec();
+    scalars.push(blinding.clone());
+
+    curve::multi_scalar_mul(compiler, curve, &bases, &scalars, span)
+}
+
+/// Verifies the opening of a commitment: the values and the blinding factor must give the same commitment.
+pub fn verify_opening<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    commitment: &Point<B>,
+    values: &[ConstOrCell<B::Field, B::Var>],
+    blinding: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<()> {
+    let expected = commit(compiler, curve, values, blinding, span)?;
+    field::assert_eq_all(
+        compiler,
+        &[
+            (commitment[0].clone(), expected[0].clone()),
+            (commitment[1].clone(), expected[1].clone()),
+        ],
+        span,
+    )
+}
+
+/// Verifies the opening of a commitment to a single value,
+/// and that this value fits in `num_bits` bits (so that it lies in `[0, 2^num_bits)`).
+pub fn verify_range<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &EmbeddedCurve<B::Field>,
+    commitment: &Point<B>,
+    value: &ConstOrCell<B::Field, B::Var>,
+    blinding: &ConstOrCell<B::Field, B::Var>,
+    num_bits: usize,
+    span: Span,
+) -> Result<()> {
+    compiler.constrain_uint(value, num_bits, span)?;
+    verify_opening(
+        compiler,
+        curve,
+        commitment,
+        &[value.clone()],
+        blinding,
+        span,
+    )
+}
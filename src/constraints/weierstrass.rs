@@ -0,0 +1,288 @@
+//! Points of the short Weierstrass curve `y^2 = x^3 + b` embedded in the field of the backend:
+//! Pallas for the kimchi backend over Vesta (whose field is the base field of Pallas).
+//! The fields of the R1CS backends embed twisted Edwards curves instead (see [super::edwards]).
+//!
+//! Points are represented by their affine coordinates `(x, y)`, and the circuit uses the incomplete addition formulas:
+//! adding two points with the same `x` coordinate (or getting the point at infinity) makes the circuit unsatisfiable,
+//! and is an error if both points are constant.
+//! To avoid these cases, scalar multiplications start from an offset point with an unknown discrete logarithm,
+//! which is subtracted at the end.
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{Backend, BackendField},
+    circuit_writer::CircuitWriter,
+    constants::Span,
+    error::{ErrorKind, Result},
+    var::{ConstOrCell, Var},
+};
+
+use super::{field, keccak::keccak256_native};
+
+/// A point of the curve, in affine coordinates.
+pub type Point<B> = [ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>; 2];
+
+/// The modulus of the base field of Pallas, which is the field of the kimchi backend over Vesta.
+const PALLAS_MODULUS: &str =
+    "28948022309329048855892746252171976963363056481941560715954676764349967630337";
+
+/// The order of Pallas (which has a cofactor of 1).
+const PALLAS_ORDER: &str =
+    "28948022309329048855892746252171976963363056481941647379679742748393362948097";
+
+/// The `y` coordinate of the generator of Pallas used by Mina, whose `x` coordinate is 1.
+const PALLAS_GENERATOR_Y: &str =
+    "12418654782883325593414442427049395787963493412651469444558597405572177144507";
+
+/// A short Weierstrass curve `y^2 = x^3 + b`, with a prime order.
+pub struct Curve<F> {
+    pub b: F,
+
+    /// The order of the group.
+    pub order: BigUint,
+
+    /// A generator of the group.
+    pub generator: (F, F),
+}
+
+/// Returns the short Weierstrass curve embedded in the field `F`, if there is one.
+pub fn embedded_curve<F: BackendField>() -> Option<Curve<F>> {
+    let modulus_minus_one: BigUint = (-F::one()).into();
+    let modulus = (modulus_minus_one + 1u8).to_string();
+
+    if modulus == PALLAS_MODULUS {
+        let generator_y: BigUint = PALLAS_GENERATOR_Y.parse().unwrap();
+        Some(Curve {
+            b: F::from(5u64),
+            order: PALLAS_ORDER.parse().unwrap(),
+            generator: (
+                F::one(),
+                F::try_from(generator_y).unwrap_or_else(|_| unreachable!()),
+            ),
+        })
+    } else {
+        None
+    }
+}
+
+impl<F: BackendField> Curve<F> {
+    /// Adds two points out of the circuit, where `None` is the point at infinity.
+    pub fn add(&self, lhs: Option<(F, F)>, rhs: Option<(F, F)>) -> Option<(F, F)> {
+        let (Some((x1, y1)), Some((x2, y2))) = (lhs, rhs) else {
+            return lhs.or(rhs);
+        };
+
+        let lambda = if x1 != x2 {
+            (y2 - y1) / (x2 - x1)
+        } else if y1 == y2 && !y1.is_zero() {
+            (x1.square() * F::from(3u64)) / y1.double()
+        } else {
+            return None;
+        };
+
+        let x3 = lambda.square() - x1 - x2;
+        let y3 = lambda * (x1 - x3) - y1;
+        Some((x3, y3))
+    }
+
+    /// Multiplies a point by a scalar out of the circuit.
+    pub fn mul(&self, point: (F, F), scalar: &BigUint) -> Option<(F, F)> {
+        let mut acc = None;
+        for ii in (0..scalar.bits()).rev() {
+            acc = self.add(acc, acc);
+            if scalar.bit(ii) {
+                acc = self.add(acc, Some(point));
+            }
+        }
+        acc
+    }
+
+    /// Derives a point with an unknown discrete logarithm from a seed:
+    /// candidate `x` coordinates are hashed until one is on the curve.
+    pub fn hash_to_point(&self, seed: &str) -> (F, F) {
+        let modulus_minus_one: BigUint = (-F::one()).into();
+        let modulus = modulus_minus_one + 1u8;
+
+        for counter in 0u32.. {
+            let hash = keccak256_native(format!("{seed}_{counter}").as_bytes());
+            let x = BigUint::from_bytes_be(&hash) % &modulus;
+            let x = F::try_from(x).unwrap_or_else(|_| unreachable!());
+
+            if let Some(y) = field::smallest_root(x.square() * x + self.b) {
+                return (x, y);
+            }
+        }
+
+        unreachable!()
+    }
+}
+
+/// Returns a constant point.
+pub fn constant<B: Backend>((x, y): (B::Field, B::Field)) -> Point<B> {
+    [ConstOrCell::Const(x), ConstOrCell::Const(y)]
+}
+
+/// Constrains a point to be on the curve (which is enough to be in the group, as the cofactor is 1).
+pub fn assert_on_curve<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    [x, y]: &Point<B>,
+    span: Span,
+) -> Result<()> {
+    // y^2 = x^3 + b
+    let y2 = field::mul(compiler, y, y, span);
+    let x2 = field::mul(compiler, x, x, span);
+    let x3 = field::mul(compiler, &x2[0], x, span);
+    let rhs = field::add(compiler, &x3[0], &ConstOrCell::Const(curve.b), span);
+    field::assert_eq_cells(compiler, &y2[0], &rhs[0], span)
+}
+
+/// Negates a point.
+pub fn neg<B: Backend>(compiler: &mut CircuitWriter<B>, [x, y]: &Point<B>, span: Span) -> Point<B> {
+    let y = field::neg(compiler, y, span);
+    [x.clone(), y[0].clone()]
+}
+
+/// Adds two points with the incomplete addition formulas: the `x` coordinates must be different.
+pub fn add<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    [x1, y1]: &Point<B>,
+    [x2, y2]: &Point<B>,
+    span: Span,
+) -> Result<Point<B>> {
+    // lambda = (y2 - y1) / (x2 - x1)
+    let num = field::sub(compiler, y2, y1, span);
+    let den = field::sub(compiler, x2, x1, span);
+    let den = slope_inv(compiler, &den[0], span)?;
+    let lambda = field::mul(compiler, &num[0], &den[0], span);
+
+    Ok(finish_add(compiler, &lambda[0], x1, y1, x2, span))
+}
+
+/// Adds a point to another if a boolean is set, and returns the first point otherwise.
+/// The incomplete addition formulas only constrain the `x` coordinates to be different if the boolean is set.
+fn add_if<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cond: &ConstOrCell<B::Field, B::Var>,
+    [x1, y1]: &Point<B>,
+    [x2, y2]: &Point<B>,
+    span: Span,
+) -> Result<Point<B>> {
+    // lambda = (y2 - y1) / (x2 - x1),
+    // where the denominator is replaced by 1 if the sum is discarded
+    let num = field::sub(compiler, y2, y1, span);
+    let den = field::sub(compiler, x2, x1, span);
+    let one = ConstOrCell::Const(B::Field::one());
+    let den = field::if_else_inner(compiler, cond, &den[0], &one, span);
+    let den = slope_inv(compiler, &den[0], span)?;
+    let lambda = field::mul(compiler, &num[0], &den[0], span);
+
+    let [x3, y3] = finish_add(compiler, &lambda[0], x1, y1, x2, span);
+    let x = field::if_else_inner(compiler, cond, &x3, x1, span);
+    let y = field::if_else_inner(compiler, cond, &y3, y1, span);
+    Ok([x[0].clone(), y[0].clone()])
+}
+
+/// Doubles a point (which can't be of order 2, as the order of the curve is odd).
+pub fn double<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    [x, y]: &Point<B>,
+    span: Span,
+) -> Result<Point<B>> {
+    // lambda = 3 x^2 / 2 y
+    let x2 = field::mul(compiler, x, x, span);
+    let num = field::mul(
+        compiler,
+        &x2[0],
+        &ConstOrCell::Const(B::Field::from(3u64)),
+        span,
+    );
+    let den = field::add(compiler, y, y, span);
+    let den = slope_inv(compiler, &den[0], span)?;
+    let lambda = field::mul(compiler, &num[0], &den[0], span);
+
+    Ok(finish_add(compiler, &lambda[0], x, y, x, span))
+}
+
+/// Inverts the denominator of the slope of an addition,
+/// which can only be zero for constant points that the incomplete formulas can't add.
+fn slope_inv<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    den: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    if matches!(den, ConstOrCell::Const(cst) if cst.is_zero()) {
+        return Err(compiler.error(ErrorKind::IncompleteAddition, span));
+    }
+
+    Ok(field::inv(compiler, den, span))
+}
+
+/// Returns `(x3, y3)` from the slope of the line through the points:
+/// `x3 = lambda^2 - x1 - x2` and `y3 = lambda (x1 - x3) - y1`.
+fn finish_add<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lambda: &ConstOrCell<B::Field, B::Var>,
+    x1: &ConstOrCell<B::Field, B::Var>,
+    y1: &ConstOrCell<B::Field, B::Var>,
+    x2: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Point<B> {
+    let lambda2 = field::mul(compiler, lambda, lambda, span);
+    let x3 = field::sub(compiler, &lambda2[0], x1, span);
+    let x3 = field::sub(compiler, &x3[0], x2, span);
+
+    let diff = field::sub(compiler, x1, &x3[0], span);
+    let y3 = field::mul(compiler, lambda, &diff[0], span);
+    let y3 = field::sub(compiler, &y3[0], y1, span);
+
+    [x3[0].clone(), y3[0].clone()]
+}
+
+/// Multiplies a point by a scalar given as bits (in little-endian order), with double-and-add.
+/// The result can't be the point at infinity (see [multi_scalar_mul]).
+pub fn scalar_mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    point: &Point<B>,
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<Point<B>> {
+    multi_scalar_mul(compiler, curve, &[(point.clone(), bits)], span)
+}
+
+/// Returns the sum of points multiplied by scalars given as bits (in little-endian order),
+/// with a double-and-add that shares the doublings.
+///
+/// The accumulator starts at an offset point `Q`, so that it never is the point at infinity,
+/// and `[2^n] Q` is subtracted at the end (where `n` is the number of bits, which must be the same for all scalars).
+/// The result can't be the point at infinity.
+pub fn multi_scalar_mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    curve: &Curve<B::Field>,
+    terms: &[(Point<B>, &[ConstOrCell<B::Field, B::Var>])],
+    span: Span,
+) -> Result<Point<B>> {
+    let num_bits = terms.first().map_or(0, |(_, bits)| bits.len());
+    assert!(terms.iter().all(|(_, bits)| bits.len() == num_bits));
+
+    let offset = curve.hash_to_point("noname_weierstrass_offset");
+    let mut acc = constant::<B>(offset);
+
+    for ii in (0..num_bits).rev() {
+        acc = double(compiler, &acc, span)?;
+
+        for (point, bits) in terms {
+            acc = add_if(compiler, &bits[ii], &acc, point, span)?;
+        }
+    }
+
+    // subtract [2^n] Q
+    let shifted = curve
+        .mul(offset, &(BigUint::one() << num_bits))
+        .expect("the offset has the order of the group");
+    let (x, y) = shifted;
+    add(compiler, &acc, &constant::<B>((x, -y)), span)
+}
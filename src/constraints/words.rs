@@ -0,0 +1,87 @@
+//! Gadgets on words of bits, as used by the hash functions that work on 32-bit or 64-bit words
+//! (see [super::sha2], [super::keccak], and [super::blake2]).
+//!
+//! A word is represented as boolean cells in little-endian order,
+//! so that rotations and shifts are free, as the bits are simply reordered.
+
+use ark_ff::{One, Zero};
+
+use crate::{backends::Backend, circuit_writer::CircuitWriter, constants::Span, var::ConstOrCell};
+
+use super::{boolean, field};
+
+/// A word, as booleans in little-endian order.
+pub type Word<B> = Vec<ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>>;
+
+/// Returns the `num_bits` least significant bits of a constant.
+pub fn constant<B: Backend>(value: u64, num_bits: usize) -> Word<B> {
+    (0..num_bits)
+        .map(|ii| {
+            let bit = if (value >> ii) & 1 == 1 {
+                B::Field::one()
+            } else {
+                B::Field::zero()
+            };
+            ConstOrCell::Const(bit)
+        })
+        .collect()
+}
+
+/// Rotates a word to the right.
+pub fn rotr<B: Backend>(word: &Word<B>, rot: usize) -> Word<B> {
+    let mut res = word.clone();
+    res.rotate_left(rot);
+    res
+}
+
+/// Rotates a word to the left.
+pub fn rotl<B: Backend>(word: &Word<B>, rot: usize) -> Word<B> {
+    let mut res = word.clone();
+    res.rotate_right(rot);
+    res
+}
+
+/// Shifts a word to the right, filling it with zeros.
+pub fn shr<B: Backend>(word: &Word<B>, shift: usize) -> Word<B> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+    (0..word.len())
+        .map(|ii| {
+            word.get(ii + shift)
+                .cloned()
+                .unwrap_or_else(|| zero.clone())
+        })
+        .collect()
+}
+
+pub fn xor<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[ConstOrCell<B::Field, B::Var>],
+    rhs: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Word<B> {
+    assert_eq!(lhs.len(), rhs.len());
+
+    lhs.iter()
+        .zip(rhs)
+        .map(|(lhs, rhs)| boolean::xor(compiler, lhs, rhs, span)[0].clone())
+        .collect()
+}
+
+/// Adds words modulo `2^n` (where `n` is the size of the words).
+/// The words are packed into field elements, and the sum is decomposed again to drop the carry bits.
+pub fn add<B: Backend>(compiler: &mut CircuitWriter<B>, words: &[&Word<B>], span: Span) -> Word<B> {
+    let word_bits = words[0].len();
+    assert!(words.iter().all(|word| word.len() == word_bits));
+
+    let mut sum = ConstOrCell::Const(B::Field::zero());
+    for word in words {
+        let packed = field::from_bits(compiler, word, span);
+        sum = field::add(compiler, &sum, &packed[0], span)[0].clone();
+    }
+
+    // the carry fits in a few bits, which we drop
+    let carry_bits = (usize::BITS - (words.len() - 1).leading_zeros()) as usize;
+    let mut bits = field::to_bits(compiler, &sum, word_bits + carry_bits, span).cvars;
+    bits.truncate(word_bits);
+    bits
+}
@@ -91,12 +91,21 @@ pub enum ErrorKind {
     #[error("the `pub` keyword is reserved for arguments of the main function")]
     PubArgumentOutsideMain,
 
+    #[error("array sizes can only be generic in the signatures of builtin functions")]
+    GenericSizeOutsideBuiltin,
+
     #[error("the function main is not recursive")]
     RecursiveMain,
 
     #[error("invalid token")]
     InvalidToken,
 
+    #[error("unterminated string literal")]
+    UnterminatedStringLiteral,
+
+    #[error("invalid escape sequence in a string literal")]
+    InvalidEscapeSequence,
+
     #[error("missing type")]
     MissingType,
 
@@ -130,6 +139,9 @@ pub enum ErrorKind {
     #[error("invalid array size, expected [_; x] with x in [0,2^32]")]
     InvalidArraySize,
 
+    #[error("expected a non-empty array")]
+    EmptyArray,
+
     #[error("the value passed could not be converted to a field element")]
     InvalidField(String),
 
@@ -183,6 +195,9 @@ pub enum ErrorKind {
     #[error("you need to pass the following private argument: `{0}`")]
     MissingPrivateArg(String),
 
+    #[error("you need to pass the expected public output")]
+    MissingPublicOutput,
+
     #[error("cannot convert `{0}` to field element")]
     CannotConvertToField(String),
 
@@ -233,9 +248,75 @@ pub enum ErrorKind {
     )]
     NoOneLetterVariable,
 
-    #[error("array indexes must be constants in circuits")]
+    #[error("expected a constant")]
     ExpectedConstant,
 
+    #[error("the number of bits passed is not a valid bit length")]
+    InvalidBitLength,
+
+    #[error("division by zero")]
+    DivisionByZero,
+
+    #[error("cannot compute the inverse of zero")]
+    InverseOfZero,
+
+    #[error("the incomplete addition formulas cannot add two points with the same x coordinate")]
+    IncompleteAddition,
+
+    #[error("cannot compute the square root of a quadratic non-residue")]
+    NotASquare,
+
+    #[error("the value does not fit in a {0}-bit integer")]
+    IntegerOverflow(usize),
+
+    #[error("this operation is not supported on signed integers")]
+    UnsupportedSignedOperation,
+
+    #[error("the modulus must be a non-zero constant")]
+    InvalidModulus,
+
+    #[error("infinities, NaNs, and subnormal numbers are not supported")]
+    UnsupportedFloat,
+
+    #[error("the field of this backend has no embedded curve supporting this operation")]
+    NoEmbeddedCurve,
+
+    #[error("the length of the encoded input doesn't match the length of the decoded output")]
+    InvalidEncodedLength,
+
+    #[error("invalid regular expression: {0}")]
+    InvalidRegex(String),
+
+    #[error("invalid JSON path: {0}")]
+    InvalidJsonPath(String),
+
+    #[error("unsupported Poseidon rate {0} (the supported rates are 1, 2, and 4)")]
+    UnsupportedPoseidonRate(String),
+
+    #[error(
+        "no number of partial rounds is known for the S-box `x^{0}` of the field of this backend"
+    )]
+    UnsupportedSbox(u64),
+
+    #[error(
+        "values of {0} bits can't be range-checked or compared in the field of this backend, which is too small"
+    )]
+    FieldTooSmall(usize),
+
+    #[error("a lookup table must have at least one entry, and distinct indices")]
+    InvalidLookupTable,
+
+    #[error("the index is not in the lookup table")]
+    NotInLookupTable,
+
+    #[error("the backend has no custom gate `{0}`")]
+    UnknownCustomGate(String),
+
+    #[error(
+        "the round constants don't match the ones generated by the Poseidon specification: {0}"
+    )]
+    InvalidRoundConstants(String),
+
     #[error("kimchi setup: {0}")]
     KimchiSetup(#[from] kimchi::error::SetupError),
 
@@ -32,6 +32,9 @@ pub enum ParsingError {
     #[error("couldn't convert given field element `{0}`")]
     InvalidField(String),
 
+    #[error("given integer `{0}` does not fit in a {1}")]
+    IntegerOverflow(String, TyKind),
+
     #[error("mismatch between expected argument format ({0}), and given argument in JSON (`{1}`)")]
     MismatchJsonArgument(TyKind, serde_json::Value),
 }
@@ -82,6 +85,36 @@ impl<B: Backend> CompiledCircuit<B> {
                     B::Field::from_str(&ss).map_err(|_| ParsingError::InvalidField(ss))?;
                 Ok(vec![cell_value])
             }
+            (uint, Value::String(ss)) if uint.uint_bits().is_some() => {
+                let num_bits = uint.uint_bits().unwrap();
+                let bigint =
+                    BigUint::from_str(&ss).map_err(|_| ParsingError::InvalidField(ss.clone()))?;
+                if bigint.bits() as usize > num_bits {
+                    return Err(ParsingError::IntegerOverflow(ss, uint.clone()));
+                }
+                let cell_value =
+                    B::Field::try_from(bigint).map_err(|_| ParsingError::InvalidField(ss))?;
+                Ok(vec![cell_value])
+            }
+            (TyKind::I64, Value::String(ss)) => {
+                let (is_negative, abs) = match ss.strip_prefix('-') {
+                    Some(abs) => (true, abs),
+                    None => (false, ss.as_str()),
+                };
+                let bigint =
+                    BigUint::from_str(abs).map_err(|_| ParsingError::InvalidField(ss.clone()))?;
+
+                // -2^63 <= x < 2^63
+                let bound = BigUint::from(1u64) << 63;
+                if (is_negative && bigint > bound) || (!is_negative && bigint >= bound) {
+                    return Err(ParsingError::IntegerOverflow(ss, TyKind::I64));
+                }
+
+                let cell_value = B::Field::try_from(bigint)
+                    .map_err(|_| ParsingError::InvalidField(ss.clone()))?;
+                let cell_value = if is_negative { -cell_value } else { cell_value };
+                Ok(vec![cell_value])
+            }
             (TyKind::Bool, Value::Bool(bb)) => {
                 let ff = if bb {
                     B::Field::one()
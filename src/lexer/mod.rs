@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, iter::Peekable, str::Chars};
 
 use crate::{
     constants::Span,
@@ -135,7 +135,9 @@ pub enum TokenKind {
     Slash,              // /
     Comment(String),    // // comment
     Greater,            // >
+    GreaterEqual,       // >=
     Less,               // <
+    LessEqual,          // <=
     Equal,              // =
     DoubleEqual,        // ==
     NotEqual,           // !=
@@ -143,13 +145,15 @@ pub enum TokenKind {
     Minus,              // -
     RightArrow,         // ->
     Star,               // *
+    Percent,            // %
     Ampersand,          // &
     DoubleAmpersand,    // &&
     Pipe,               // |
     DoublePipe,         // ||
+    Caret,              // ^
     Exclamation,        // !
     Question,           // ?
-                        //    Literal,               // "thing"
+    Literal(Vec<u8>),   // "thing", b"thing", hex"7468696e67"
 }
 
 impl Display for TokenKind {
@@ -176,7 +180,9 @@ impl Display for TokenKind {
             Slash => "`/`",
             Comment(_) => "`//`",
             Greater => "`>`",
+            GreaterEqual => "`>=`",
             Less => "`<`",
+            LessEqual => "`<=`",
             Equal => "`=`",
             DoubleEqual => "`==`",
             NotEqual => "`!=`",
@@ -184,13 +190,15 @@ impl Display for TokenKind {
             Minus => "`-`",
             RightArrow => "`->`",
             Star => "`*`",
+            Percent => "`%`",
             Ampersand => "`&`",
             DoubleAmpersand => "`&&`",
             Pipe => "`|`",
             DoublePipe => "`||`",
+            Caret => "`^`",
             Exclamation => "`!`",
             Question => "`?`",
-            //            TokenType::Literal => "`\"something\"",
+            Literal(_) => "a string literal (`\"something\"`)",
         };
 
         write!(f, "{}", desc)
@@ -281,6 +289,12 @@ impl Token {
                 break;
             };
 
+            // a string literal might have a prefix (`b"..."` or `hex"..."`)
+            let prefix = match ident_or_number.as_deref() {
+                Some("b") | Some("hex") if c == '"' => ident_or_number.take(),
+                _ => None,
+            };
+
             // where we in the middle of parsing an ident or number?
             if !c.is_alphanumeric() && c != '_' {
                 if let Some(ident_or_number) = ident_or_number.take() {
@@ -361,10 +375,22 @@ impl Token {
                     }
                 }
                 '>' => {
-                    tokens.push(TokenKind::Greater.new_token(ctx, 1));
+                    let next_c = chars.peek();
+                    if matches!(next_c, Some(&'=')) {
+                        tokens.push(TokenKind::GreaterEqual.new_token(ctx, 2));
+                        chars.next();
+                    } else {
+                        tokens.push(TokenKind::Greater.new_token(ctx, 1));
+                    }
                 }
                 '<' => {
-                    tokens.push(TokenKind::Less.new_token(ctx, 1));
+                    let next_c = chars.peek();
+                    if matches!(next_c, Some(&'=')) {
+                        tokens.push(TokenKind::LessEqual.new_token(ctx, 2));
+                        chars.next();
+                    } else {
+                        tokens.push(TokenKind::Less.new_token(ctx, 1));
+                    }
                 }
                 '=' => {
                     let next_c = chars.peek();
@@ -390,6 +416,9 @@ impl Token {
                 '*' => {
                     tokens.push(TokenKind::Star.new_token(ctx, 1));
                 }
+                '%' => {
+                    tokens.push(TokenKind::Percent.new_token(ctx, 1));
+                }
                 '&' => {
                     let next_c = chars.peek();
                     if matches!(next_c, Some(&'&')) {
@@ -408,6 +437,9 @@ impl Token {
                         tokens.push(TokenKind::Pipe.new_token(ctx, 1));
                     }
                 }
+                '^' => {
+                    tokens.push(TokenKind::Caret.new_token(ctx, 1));
+                }
                 '!' => {
                     let next_c = chars.peek();
                     if matches!(next_c, Some(&'=')) {
@@ -421,6 +453,10 @@ impl Token {
                 '?' => {
                     tokens.push(TokenKind::Question.new_token(ctx, 1));
                 }
+                '"' => {
+                    let (bytes, len) = Self::parse_literal(ctx, &mut chars, prefix.as_deref())?;
+                    tokens.push(TokenKind::Literal(bytes).new_token(ctx, len));
+                }
                 ' ' => ctx.offset += 1,
                 _ => {
                     return Err(ctx.error(
@@ -434,6 +470,103 @@ impl Token {
         Ok(tokens)
     }
 
+    /// Parses a string literal, right after its opening quote,
+    /// and returns its bytes and its length (including its prefix and its quotes).
+    ///
+    /// In plain literals, only `\"` and `\\` are escaped,
+    /// other backslashes are kept so that patterns like `\d` don't have to be doubled.
+    /// Byte strings (`b"..."`) also support the `\n`, `\r`, `\t`, `\0`, and `\xNN` escapes,
+    /// and hexadecimal strings (`hex"..."`) contain pairs of hexadecimal digits.
+    fn parse_literal(
+        ctx: &LexerCtx,
+        chars: &mut Peekable<Chars>,
+        prefix: Option<&str>,
+    ) -> Result<(Vec<u8>, usize)> {
+        let mut bytes = vec![];
+        let mut len = prefix.map_or(0, str::len) + 1;
+
+        let unterminated = |len| {
+            ctx.error(
+                ErrorKind::UnterminatedStringLiteral,
+                Span::new(ctx.filename_id, ctx.offset, len),
+            )
+        };
+
+        loop {
+            let Some(c) = chars.next() else {
+                return Err(unterminated(len));
+            };
+            len += c.len_utf8();
+
+            match c {
+                '"' => break,
+                '\\' => {
+                    let Some(escaped) = chars.next() else {
+                        return Err(unterminated(len));
+                    };
+                    len += escaped.len_utf8();
+
+                    let byte = match (prefix, escaped) {
+                        (Some("hex"), _) => None,
+                        (_, '"' | '\\') => Some(escaped as u8),
+                        (Some("b"), 'n') => Some(b'\n'),
+                        (Some("b"), 'r') => Some(b'\r'),
+                        (Some("b"), 't') => Some(b'\t'),
+                        (Some("b"), '0') => Some(0),
+                        (Some("b"), 'x') => {
+                            let digits: String = chars.by_ref().take(2).collect();
+                            len += digits.len();
+                            if digits.len() == 2 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                                u8::from_str_radix(&digits, 16).ok()
+                            } else {
+                                None
+                            }
+                        }
+                        (Some(_), _) => None,
+                        (None, _) => {
+                            bytes.push(b'\\');
+                            let mut buf = [0; 4];
+                            bytes.extend(escaped.encode_utf8(&mut buf).as_bytes());
+                            continue;
+                        }
+                    };
+
+                    match byte {
+                        Some(byte) => bytes.push(byte),
+                        None => {
+                            return Err(ctx.error(
+                                ErrorKind::InvalidEscapeSequence,
+                                Span::new(ctx.filename_id, ctx.offset, len),
+                            ))
+                        }
+                    }
+                }
+                c => {
+                    let mut buf = [0; 4];
+                    bytes.extend(c.encode_utf8(&mut buf).as_bytes());
+                }
+            }
+        }
+
+        // hexadecimal strings are decoded
+        if prefix == Some("hex") {
+            let digits = String::from_utf8(bytes).unwrap_or_default();
+            if digits.len() % 2 != 0 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(ctx.error(
+                    ErrorKind::InvalidHexLiteral(digits),
+                    Span::new(ctx.filename_id, ctx.offset, len),
+                ));
+            }
+
+            bytes = (0..digits.len())
+                .step_by(2)
+                .map(|ii| u8::from_str_radix(&digits[ii..ii + 2], 16).unwrap())
+                .collect();
+        }
+
+        Ok((bytes, len))
+    }
+
     pub fn parse(filename_id: usize, code: &str) -> Result<Tokens> {
         let mut ctx = LexerCtx::new(filename_id);
         let mut tokens = vec![];
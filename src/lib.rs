@@ -3,6 +3,7 @@
 //!
 
 pub mod backends;
+pub mod circom;
 pub mod circuit_writer;
 pub mod cli;
 pub mod compiler;
@@ -14,6 +15,7 @@ pub mod inputs;
 pub mod lexer;
 pub mod name_resolution;
 pub mod parser;
+pub mod proof_file;
 pub mod serialization;
 pub mod stdlib;
 pub mod syntax;
@@ -40,7 +42,7 @@ pub mod helpers {
     };
 
     use crate::backends::{
-        kimchi::VestaField,
+        kimchi::{PallasField, VestaField},
         r1cs::{R1csBls12381Field, R1csBn254Field},
     };
 
@@ -59,6 +61,7 @@ pub mod helpers {
     }
 
     impl PrettyField for VestaField {}
+    impl PrettyField for PallasField {}
     impl PrettyField for R1csBls12381Field {}
     impl PrettyField for R1csBn254Field {}
 
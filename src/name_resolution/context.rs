@@ -130,8 +130,11 @@ impl NameResCtx {
                 self.resolve(module, false)?;
             }
             TyKind::BigInt => (),
-            TyKind::Array(typ_kind, _) => self.resolve_typ_kind(typ_kind)?,
+            TyKind::Array(typ_kind, _) | TyKind::GenericArray(typ_kind, _) => {
+                self.resolve_typ_kind(typ_kind)?
+            }
             TyKind::Bool => (),
+            TyKind::U8 | TyKind::U16 | TyKind::U32 | TyKind::U64 | TyKind::I64 => (),
         };
 
         Ok(())
@@ -1,5 +1,5 @@
 use crate::{
-    backends::kimchi::KimchiVesta,
+    backends::{kimchi::KimchiVesta, plonky2::field::R1csGoldilocksField, r1cs::R1CS},
     compiler::{typecheck_next_file_inner, Sources},
     error::ErrorKind,
     type_checker::TypeChecker,
@@ -73,3 +73,41 @@ fn test_return_mismatch() {
         ErrorKind::ReturnTypeMismatch(..)
     ));
 }
+
+#[test]
+fn test_literal_too_large_for_field() {
+    // the modulus of Goldilocks doesn't fit in it
+    let code = r#"
+        fn thing(xx: Field) {
+            assert_eq(xx, 18446744069414584321);
+        }
+        "#;
+
+    let mut tast = TypeChecker::<R1CS<R1csGoldilocksField>>::new();
+    let res = typecheck_next_file_inner(
+        &mut tast,
+        None,
+        &mut Sources::new(),
+        "example.no".to_string(),
+        code.to_string(),
+        0,
+    );
+
+    assert!(matches!(
+        res.unwrap_err().kind,
+        ErrorKind::CannotConvertToField(..)
+    ));
+
+    // but it fits in larger fields
+    let mut tast = TypeChecker::<KimchiVesta>::new();
+    let res = typecheck_next_file_inner(
+        &mut tast,
+        None,
+        &mut Sources::new(),
+        "example.no".to_string(),
+        code.to_string(),
+        0,
+    );
+
+    assert!(res.is_ok());
+}
@@ -26,7 +26,7 @@ use super::{
 //~     | ident
 //~     | fn_call
 //~     | array_access
-//~ bin_op ::= "+" | "-" | "/" | "*" | "=="
+//~ bin_op ::= "+" | "-" | "/" | "%" | "*" | "==" | "!=" | "<" | "<=" | ">" | ">=" | "&" | "|" | "^"
 //~ numeric ::= /[0-9]+/
 //~ ident ::= /[A-Za-z_][A-Za-z_0-9]*/
 //~ fn_call ::= ident "(" expr { "," expr } ")"
@@ -126,10 +126,18 @@ pub enum Op2 {
     Subtraction,
     Multiplication,
     Division,
+    Modulo,
     Equality,
     Inequality,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
     BoolAnd,
     BoolOr,
+    BitAnd,
+    BitOr,
+    BitXor,
 }
 
 impl Expr {
@@ -142,6 +150,20 @@ impl Expr {
             // numeric
             TokenKind::BigUInt(b) => Expr::new(ctx, ExprKind::BigUInt(b), span),
 
+            // string literal, as an array of its bytes
+            TokenKind::Literal(bytes) => {
+                if bytes.is_empty() {
+                    return Err(ctx.error(ErrorKind::UnexpectedError("empty string literal"), span));
+                }
+
+                let items = bytes
+                    .into_iter()
+                    .map(|byte| Expr::new(ctx, ExprKind::BigUInt(byte.into()), span))
+                    .collect();
+
+                Expr::new(ctx, ExprKind::ArrayDeclaration(items), span)
+            }
+
             // identifier
             TokenKind::Identifier(value) => {
                 let maybe_module = Ident::new(value, span);
@@ -392,10 +414,18 @@ impl Expr {
                     | TokenKind::Minus
                     | TokenKind::Star
                     | TokenKind::Slash
+                    | TokenKind::Percent
                     | TokenKind::DoubleEqual
                     | TokenKind::NotEqual
+                    | TokenKind::Less
+                    | TokenKind::LessEqual
+                    | TokenKind::Greater
+                    | TokenKind::GreaterEqual
                     | TokenKind::DoubleAmpersand
                     | TokenKind::DoublePipe
+                    | TokenKind::Ampersand
+                    | TokenKind::Pipe
+                    | TokenKind::Caret
                     | TokenKind::Exclamation,
                 ..
             }) => {
@@ -406,10 +436,18 @@ impl Expr {
                     TokenKind::Minus => Op2::Subtraction,
                     TokenKind::Star => Op2::Multiplication,
                     TokenKind::Slash => Op2::Division,
+                    TokenKind::Percent => Op2::Modulo,
                     TokenKind::DoubleEqual => Op2::Equality,
                     TokenKind::NotEqual => Op2::Inequality,
+                    TokenKind::Less => Op2::LessThan,
+                    TokenKind::LessEqual => Op2::LessThanOrEqual,
+                    TokenKind::Greater => Op2::GreaterThan,
+                    TokenKind::GreaterEqual => Op2::GreaterThanOrEqual,
                     TokenKind::DoubleAmpersand => Op2::BoolAnd,
                     TokenKind::DoublePipe => Op2::BoolOr,
+                    TokenKind::Ampersand => Op2::BitAnd,
+                    TokenKind::Pipe => Op2::BitOr,
+                    TokenKind::Caret => Op2::BitXor,
                     _ => unreachable!(),
                 };
 
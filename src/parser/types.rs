@@ -1,5 +1,6 @@
 use educe::Educe;
 use std::{
+    collections::HashMap,
     fmt::Display,
     hash::{Hash, Hasher},
     str::FromStr,
@@ -178,7 +179,10 @@ pub enum TyKind {
     Field,
 
     /// Custom / user-defined types
-    Custom { module: ModulePath, name: String },
+    Custom {
+        module: ModulePath,
+        name: String,
+    },
 
     /// This could be the same as Field, but we use this to also track the fact that it's a constant.
     // TODO: get rid of this type tho no?
@@ -187,23 +191,110 @@ pub enum TyKind {
     /// An array of a fixed size.
     Array(Box<TyKind>, u32),
 
+    /// An array whose size is named (e.g. `[Field; Len]`),
+    /// and only known when the function is called
+    /// (from the sizes of the arguments, or from a `const` argument of the same name).
+    /// This is only allowed in the signatures of builtin functions.
+    GenericArray(Box<TyKind>, String),
+
     /// A boolean (`true` or `false`).
     Bool,
+
+    /// Unsigned integers, constrained to fit in their number of bits.
+    U8,
+    U16,
+    U32,
+    U64,
+
+    /// A signed integer, encoded as a field element (negative values `-x` are encoded as `p - x`),
+    /// and constrained to fit in 64 bits (in two's complement).
+    I64,
     // Tuple(Vec<TyKind>),
-    // Bool,
-    // U8,
-    // U16,
-    // U32,
-    // U64,
 }
 
 impl TyKind {
+    /// Returns the number of bits of an unsigned integer type,
+    /// or `None` if the type is not an unsigned integer.
+    pub fn uint_bits(&self) -> Option<usize> {
+        match self {
+            TyKind::U8 => Some(8),
+            TyKind::U16 => Some(16),
+            TyKind::U32 => Some(32),
+            TyKind::U64 => Some(64),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of bits of a signed integer type,
+    /// or `None` if the type is not a signed integer.
+    pub fn int_bits(&self) -> Option<usize> {
+        match self {
+            TyKind::I64 => Some(64),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the type is a signed or unsigned integer.
+    pub fn is_integer(&self) -> bool {
+        self.uint_bits().is_some() || self.int_bits().is_some()
+    }
+
+    /// Returns true if the type is backed by a single field element that can be used in arithmetic.
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, TyKind::Field | TyKind::BigInt) || self.is_integer()
+    }
+
+    /// Returns true if the type contains an array of a generic size.
+    pub fn is_generic(&self) -> bool {
+        match self {
+            TyKind::GenericArray(..) => true,
+            TyKind::Array(typ, _) => typ.is_generic(),
+            _ => false,
+        }
+    }
+
+    /// Records the sizes of the generic arrays of `self` (an expected type) as they appear in `observed`.
+    /// Returns false if a generic size is observed with two different values.
+    pub fn bind_generic_sizes(&self, observed: &TyKind, sizes: &mut HashMap<String, u32>) -> bool {
+        match (self, observed) {
+            (TyKind::GenericArray(expected, name), TyKind::Array(observed, size)) => {
+                *sizes.entry(name.clone()).or_insert(*size) == *size
+                    && expected.bind_generic_sizes(observed, sizes)
+            }
+            (TyKind::Array(expected, _), TyKind::Array(observed, _)) => {
+                expected.bind_generic_sizes(observed, sizes)
+            }
+            _ => true,
+        }
+    }
+
+    /// Replaces the generic sizes of arrays with the values recorded by [Self::bind_generic_sizes].
+    pub fn resolve_generic_sizes(&self, sizes: &HashMap<String, u32>) -> TyKind {
+        match self {
+            TyKind::GenericArray(typ, name) => {
+                let size = sizes.get(name).unwrap_or_else(|| {
+                    panic!("generic size `{name}` is not bound by the arguments")
+                });
+                TyKind::Array(Box::new(typ.resolve_generic_sizes(sizes)), *size)
+            }
+            TyKind::Array(typ, size) => {
+                TyKind::Array(Box::new(typ.resolve_generic_sizes(sizes)), *size)
+            }
+            typ => typ.clone(),
+        }
+    }
+
     pub fn match_expected(&self, expected: &TyKind) -> bool {
         match (self, expected) {
             (TyKind::BigInt, TyKind::Field) => true,
+            // constants can be used as integers (they get range-checked when used),
+            // and integers can be used as field elements
+            (TyKind::BigInt, expected) if expected.is_integer() => true,
+            (int, TyKind::Field) if int.is_integer() => true,
             (TyKind::Array(lhs, lhs_size), TyKind::Array(rhs, rhs_size)) => {
                 lhs_size == rhs_size && lhs.match_expected(rhs)
             }
+            (TyKind::Array(lhs, _), TyKind::GenericArray(rhs, _)) => lhs.match_expected(rhs),
             (
                 TyKind::Custom { module, name },
                 TyKind::Custom {
@@ -219,6 +310,7 @@ impl TyKind {
     pub fn same_as(&self, other: &TyKind) -> bool {
         match (self, other) {
             (TyKind::BigInt, TyKind::Field) | (TyKind::Field, TyKind::BigInt) => true,
+            (TyKind::BigInt, int) | (int, TyKind::BigInt) if int.is_integer() => true,
             (TyKind::Array(lhs, lhs_size), TyKind::Array(rhs, rhs_size)) => {
                 lhs_size == rhs_size && lhs.match_expected(rhs)
             }
@@ -257,7 +349,13 @@ impl Display for TyKind {
             TyKind::Field => write!(f, "Field"),
             TyKind::BigInt => write!(f, "BigInt"),
             TyKind::Array(ty, size) => write!(f, "[{}; {}]", ty, size),
+            TyKind::GenericArray(ty, size) => write!(f, "[{}; {}]", ty, size),
             TyKind::Bool => write!(f, "Bool"),
+            TyKind::U8 => write!(f, "U8"),
+            TyKind::U16 => write!(f, "U16"),
+            TyKind::U32 => write!(f, "U32"),
+            TyKind::U64 => write!(f, "U64"),
+            TyKind::I64 => write!(f, "I64"),
         }
     }
 }
@@ -265,11 +363,18 @@ impl Display for TyKind {
 impl Ty {
     pub fn reserved_types(module: ModulePath, name: Ident) -> TyKind {
         match name.value.as_ref() {
-            "Field" | "Bool" if !matches!(module, ModulePath::Local) => {
+            "Field" | "Bool" | "U8" | "U16" | "U32" | "U64" | "I64"
+                if !matches!(module, ModulePath::Local) =>
+            {
                 panic!("reserved types cannot be in a module (TODO: better error)")
             }
             "Field" => TyKind::Field,
             "Bool" => TyKind::Bool,
+            "U8" => TyKind::U8,
+            "U16" => TyKind::U16,
+            "U32" => TyKind::U32,
+            "U64" => TyKind::U64,
+            "I64" => TyKind::I64,
             _ => TyKind::Custom {
                 module,
                 name: name.value,
@@ -277,10 +382,61 @@ impl Ty {
         }
     }
 
+    /// Parses the size of an array of `ty`, which is either a number or the name of a generic size (e.g. `Len`),
+    /// and returns the type of the array.
+    fn parse_array_size(ctx: &mut ParserCtx, tokens: &mut Tokens, ty: TyKind) -> Result<TyKind> {
+        let siz = tokens.bump_err(ctx, ErrorKind::InvalidToken)?;
+        match siz.kind {
+            TokenKind::BigUInt(b) => {
+                let siz: u32 = b
+                    .try_into()
+                    .map_err(|_e| ctx.error(ErrorKind::InvalidArraySize, siz.span))?;
+                Ok(TyKind::Array(Box::new(ty), siz))
+            }
+            TokenKind::Identifier(name) if is_type(&name) => {
+                Ok(TyKind::GenericArray(Box::new(ty), name))
+            }
+            _ => Err(ctx.error(
+                ErrorKind::ExpectedToken(TokenKind::BigUInt(num_bigint::BigUint::zero())),
+                siz.span,
+            )),
+        }
+    }
+
     pub fn parse(ctx: &mut ParserCtx, tokens: &mut Tokens) -> Result<Self> {
         let token = tokens.bump_err(ctx, ErrorKind::MissingType)?;
 
         match token.kind {
+            // an array of bytes, or a string (an array of bytes padded with zeros)
+            // Bytes<size> or Str<size>
+            // ^^^^^          ^^^
+            TokenKind::Identifier(ty_name) if ty_name == "Bytes" || ty_name == "Str" => {
+                // Bytes<size>
+                //      ^
+                tokens.bump_expected(ctx, TokenKind::Less)?;
+
+                // Bytes<size>
+                //       ^^^^
+                let kind = Self::parse_array_size(ctx, tokens, TyKind::U8)?;
+
+                // Bytes<size>
+                //           ^
+                let greater = tokens.bump_expected(ctx, TokenKind::Greater)?;
+
+                Ok(Ty {
+                    kind,
+                    span: token.span.merge_with(greater.span),
+                })
+            }
+
+            // a Fiat-Shamir transcript, which is the state of a Poseidon2 sponge (see `std::transcript`)
+            // Transcript
+            // ^^^^^^^^^^
+            TokenKind::Identifier(ty_name) if ty_name == "Transcript" => Ok(Ty {
+                kind: TyKind::Array(Box::new(TyKind::Field), 3),
+                span: token.span,
+            }),
+
             // module::Type or Type
             // ^^^^^^^^^^^^    ^^^^
             TokenKind::Identifier(ty_name) => {
@@ -334,30 +490,15 @@ impl Ty {
 
                 // [type; size]
                 //         ^
-                let siz = tokens.bump_err(ctx, ErrorKind::InvalidToken)?;
-                let siz: u32 = match siz.kind {
-                    TokenKind::BigUInt(b) => b
-                        .try_into()
-                        .map_err(|_e| ctx.error(ErrorKind::InvalidArraySize, siz.span))?,
-                    _ => {
-                        return Err(ctx.error(
-                            ErrorKind::ExpectedToken(TokenKind::BigUInt(
-                                num_bigint::BigUint::zero(),
-                            )),
-                            siz.span,
-                        ));
-                    }
-                };
+                let kind = Self::parse_array_size(ctx, tokens, ty.kind)?;
 
                 // [type; size]
                 //            ^
                 let right_paren = tokens.bump_expected(ctx, TokenKind::RightBracket)?;
 
-                let span = span.merge_with(right_paren.span);
-
                 Ok(Ty {
-                    kind: TyKind::Array(Box::new(ty.kind), siz),
-                    span,
+                    kind,
+                    span: span.merge_with(right_paren.span),
                 })
             }
 
@@ -0,0 +1,248 @@
+//! The container of the proofs written by `noname prove` and read by `noname verify`,
+//! so that a proof describes the backend, the commitment scheme, and the circuit it was created for, along with its public inputs.
+//!
+//! A proof file starts with the magic bytes [MAGIC] and the version of the format (a little-endian `u32`),
+//! followed by the MessagePack encoding of a [ProofFile].
+
+use miette::Diagnostic;
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, Bytes};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::backends::BackendField;
+
+/// The magic bytes of proof files.
+pub const MAGIC: &[u8; 8] = b"NONAMEPF";
+
+/// The version of the format, which is bumped on incompatible changes.
+pub const VERSION: u32 = 2;
+
+#[derive(Diagnostic, Debug, Error)]
+pub enum ProofFileError {
+    #[error("this is not a noname proof file")]
+    #[diagnostic(help("proof files are created by `noname prove`"))]
+    NotAProofFile,
+
+    #[error("the proof file has version {0}, but this version of noname reads version {VERSION}")]
+    UnsupportedVersion(u32),
+
+    #[error("the proof file is corrupted")]
+    Corrupted(#[source] rmp_serde::decode::Error),
+
+    #[error("the proof was created with the backend `{found}`, not `{expected}`")]
+    BackendMismatch { expected: String, found: String },
+
+    #[error("the proof was created with {found} commitments, not {expected} commitments")]
+    CommitmentMismatch {
+        expected: CommitmentScheme,
+        found: CommitmentScheme,
+    },
+
+    #[error(
+        "the proof was created for another circuit (with hash `{found}`, instead of `{expected}`)"
+    )]
+    #[diagnostic(help(
+        "the package, its dependencies, or the setup differ from the ones of the prover"
+    ))]
+    CircuitMismatch { expected: String, found: String },
+
+    #[error("the public input `{0}` of the proof file is not a field element")]
+    InvalidPublicInput(String),
+}
+
+/// The polynomial commitment scheme of a proof, which trades the size of proofs against the assumptions of the setup.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitmentScheme {
+    /// The inner product argument, with a transparent setup and proofs of logarithmic size.
+    Ipa,
+
+    /// KZG, with proofs of constant size but a trusted setup over a pairing-friendly curve.
+    Kzg,
+}
+
+impl std::fmt::Display for CommitmentScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ipa => write!(f, "IPA"),
+            Self::Kzg => write!(f, "KZG"),
+        }
+    }
+}
+
+/// A proof, with what is needed to check that it matches the verifier.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProofFile {
+    /// The backend that created the proof (e.g. `kimchi-vesta`).
+    pub backend: String,
+
+    pub commitment: CommitmentScheme,
+
+    /// The hash of the verifier index of the circuit (see [circuit_hash]).
+    pub circuit_hash: [u8; 32],
+
+    /// The public inputs of the verifier (the public output, then the public inputs), as decimal strings.
+    pub public_inputs: Vec<String>,
+
+    /// The proof, in the serialization of the backend.
+    #[serde_as(as = "Bytes")]
+    pub proof: Vec<u8>,
+}
+
+/// Hashes the serialization of a verifier index, to identify a circuit and its setup.
+pub fn circuit_hash(verifier_index: &[u8]) -> [u8; 32] {
+    Sha256::digest(verifier_index).into()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+impl ProofFile {
+    pub fn new<F: BackendField>(
+        backend: &str,
+        commitment: CommitmentScheme,
+        circuit_hash: [u8; 32],
+        public_inputs: &[F],
+        proof: Vec<u8>,
+    ) -> Self {
+        Self {
+            backend: backend.to_string(),
+            commitment,
+            circuit_hash,
+            public_inputs: public_inputs
+                .iter()
+                .map(|value| Into::<BigUint>::into(*value).to_string())
+                .collect(),
+            proof,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(VERSION.to_le_bytes());
+        bytes.extend(rmp_serde::to_vec_named(self).expect("serializing a proof file can't fail"));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFileError> {
+        let rest = bytes
+            .strip_prefix(MAGIC)
+            .ok_or(ProofFileError::NotAProofFile)?;
+        if rest.len() < 4 {
+            return Err(ProofFileError::NotAProofFile);
+        }
+        let (version, body) = rest.split_at(4);
+        let version = u32::from_le_bytes(version.try_into().unwrap());
+        if version != VERSION {
+            return Err(ProofFileError::UnsupportedVersion(version));
+        }
+
+        rmp_serde::from_slice(body).map_err(ProofFileError::Corrupted)
+    }
+
+    /// Checks that the proof was created with the given backend and commitment scheme, for the circuit of the given hash.
+    pub fn check(
+        &self,
+        backend: &str,
+        commitment: CommitmentScheme,
+        circuit_hash: &[u8; 32],
+    ) -> Result<(), ProofFileError> {
+        if self.backend != backend {
+            return Err(ProofFileError::BackendMismatch {
+                expected: backend.to_string(),
+                found: self.backend.clone(),
+            });
+        }
+
+        if self.commitment != commitment {
+            return Err(ProofFileError::CommitmentMismatch {
+                expected: commitment,
+                found: self.commitment,
+            });
+        }
+
+        if &self.circuit_hash != circuit_hash {
+            return Err(ProofFileError::CircuitMismatch {
+                expected: to_hex(circuit_hash),
+                found: to_hex(&self.circuit_hash),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the public inputs of the verifier.
+    pub fn public_inputs<F: BackendField>(&self) -> Result<Vec<F>, ProofFileError> {
+        self.public_inputs
+            .iter()
+            .map(|value| {
+                F::from_str(value).map_err(|_| ProofFileError::InvalidPublicInput(value.clone()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backends::kimchi::VestaField;
+
+    use super::{circuit_hash, CommitmentScheme, ProofFile, ProofFileError, MAGIC};
+
+    #[test]
+    fn test_proof_file() {
+        let hash = circuit_hash(b"verifier index");
+        let public_inputs = [VestaField::from(8u64), VestaField::from(2u64)];
+        let proof_file = ProofFile::new(
+            "kimchi-vesta",
+            CommitmentScheme::Ipa,
+            hash,
+            &public_inputs,
+            vec![1, 2, 3],
+        );
+
+        let bytes = proof_file.to_bytes();
+        assert!(bytes.starts_with(MAGIC));
+        let decoded = ProofFile::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof_file);
+        assert_eq!(
+            decoded.public_inputs::<VestaField>().unwrap(),
+            public_inputs.to_vec()
+        );
+        decoded
+            .check("kimchi-vesta", CommitmentScheme::Ipa, &hash)
+            .unwrap();
+
+        // mismatched artifacts
+        assert!(matches!(
+            decoded.check("r1cs-bn254", CommitmentScheme::Ipa, &hash),
+            Err(ProofFileError::BackendMismatch { .. })
+        ));
+        assert!(matches!(
+            decoded.check("kimchi-vesta", CommitmentScheme::Kzg, &hash),
+            Err(ProofFileError::CommitmentMismatch { .. })
+        ));
+        assert!(matches!(
+            decoded.check(
+                "kimchi-vesta",
+                CommitmentScheme::Ipa,
+                &circuit_hash(b"another circuit")
+            ),
+            Err(ProofFileError::CircuitMismatch { .. })
+        ));
+        assert!(matches!(
+            ProofFile::from_bytes(&bytes[8..]),
+            Err(ProofFileError::NotAProofFile)
+        ));
+
+        let mut other_version = bytes;
+        other_version[8] = 1;
+        assert!(matches!(
+            ProofFile::from_bytes(&other_version),
+            Err(ProofFileError::UnsupportedVersion(1))
+        ));
+    }
+}
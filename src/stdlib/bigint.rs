@@ -0,0 +1,183 @@
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::bigint::{self, LIMB_BITS, NUM_LIMBS};
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Var};
+
+const ADD_FN: &str =
+    "add(lhs: [Field; 4], rhs: [Field; 4], const modulus: [Field; 4]) -> [Field; 4]";
+const MUL_FN: &str =
+    "mul(lhs: [Field; 4], rhs: [Field; 4], const modulus: [Field; 4]) -> [Field; 4]";
+const REDUCE_FN: &str = "reduce(val: [Field; 4], const modulus: [Field; 4]) -> [Field; 4]";
+
+pub const BIGINT_SIGS: &[&str] = &[ADD_FN, MUL_FN, REDUCE_FN];
+
+pub fn get_bigint_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        ADD_FN => add,
+        MUL_FN => mul,
+        REDUCE_FN => reduce,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns bigint functions
+pub fn bigint_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    BIGINT_SIGS
+        .iter()
+        .map(|sig| get_bigint_fn(sig).unwrap())
+        .collect()
+}
+
+/// Computes `(lhs + rhs) mod modulus`.
+fn add<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get three vars
+    assert_eq!(vars.len(), 3);
+
+    let lhs = limbs(compiler, &vars[0], span)?;
+    let rhs = limbs(compiler, &vars[1], span)?;
+    let modulus = modulus(compiler, &vars[2], span)?;
+
+    // lhs * 1 + rhs
+    let res = bigint::mul_add_mod(
+        compiler,
+        lhs,
+        &constant_limbs::<B>(B::Field::one()),
+        rhs,
+        &modulus,
+        span,
+    )?;
+
+    Ok(Some(res))
+}
+
+/// Computes `(lhs * rhs) mod modulus`.
+fn mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get three vars
+    assert_eq!(vars.len(), 3);
+
+    let lhs = limbs(compiler, &vars[0], span)?;
+    let rhs = limbs(compiler, &vars[1], span)?;
+    let modulus = modulus(compiler, &vars[2], span)?;
+
+    // lhs * rhs + 0
+    let res = bigint::mul_add_mod(
+        compiler,
+        lhs,
+        rhs,
+        &constant_limbs::<B>(B::Field::zero()),
+        &modulus,
+        span,
+    )?;
+
+    Ok(Some(res))
+}
+
+/// Computes `val mod modulus`.
+fn reduce<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+
+    let val = limbs(compiler, &vars[0], span)?;
+    let modulus = modulus(compiler, &vars[1], span)?;
+
+    // val * 1 + 0
+    let res = bigint::mul_add_mod(
+        compiler,
+        val,
+        &constant_limbs::<B>(B::Field::one()),
+        &constant_limbs::<B>(B::Field::zero()),
+        &modulus,
+        span,
+    )?;
+
+    Ok(Some(res))
+}
+
+/// Returns the limbs of a big integer, checking that constant limbs fit in [LIMB_BITS] bits.
+fn limbs<'a, B: Backend>(
+    compiler: &CircuitWriter<B>,
+    var_info: &'a VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<&'a [ConstOrCell<B::Field, B::Var>]> {
+    // of type [Field; NUM_LIMBS]
+    match &var_info.typ {
+        Some(TyKind::Array(typ, len)) if typ.is_numeric() && *len as usize == NUM_LIMBS => (),
+        typ => panic!(
+            "a big integer must be of type [Field; {NUM_LIMBS}]. It was of type {:?}",
+            typ
+        ),
+    }
+
+    let limbs = var_info.var.range(0, NUM_LIMBS);
+
+    for limb in limbs {
+        if let ConstOrCell::Const(cst) = limb {
+            let cst: BigUint = (*cst).into();
+            if cst.bits() as usize > LIMB_BITS {
+                return Err(compiler.error(ErrorKind::IntegerOverflow(LIMB_BITS), span));
+            }
+        }
+    }
+
+    Ok(limbs)
+}
+
+/// Extracts a non-zero compile-time modulus from its limbs.
+fn modulus<B: Backend>(
+    compiler: &CircuitWriter<B>,
+    var_info: &VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<BigUint> {
+    let limbs = limbs(compiler, var_info, span)?;
+
+    let limbs = limbs
+        .iter()
+        .map(|limb| limb.cst())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?;
+
+    let modulus = bigint::from_limbs(&limbs);
+    if modulus.is_zero() {
+        return Err(compiler.error(ErrorKind::InvalidModulus, span));
+    }
+
+    Ok(modulus)
+}
+
+/// Returns the limbs of a constant that fits in a single limb.
+fn constant_limbs<B: Backend>(val: B::Field) -> Vec<ConstOrCell<B::Field, B::Var>> {
+    let mut limbs = vec![ConstOrCell::Const(B::Field::zero()); NUM_LIMBS];
+    limbs[0] = ConstOrCell::Const(val);
+    limbs
+}
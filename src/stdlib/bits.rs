@@ -0,0 +1,352 @@
+use ark_ff::Zero;
+use num_bigint::BigUint;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::field;
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Var};
+
+const SHL_FN: &str = "shl(val: Field, const shift: Field, const width: Field) -> Field";
+const SHR_FN: &str = "shr(val: Field, const shift: Field, const width: Field) -> Field";
+const ROTL_FN: &str = "rotl(val: Field, const shift: Field, const width: Field) -> Field";
+const ROTR_FN: &str = "rotr(val: Field, const shift: Field, const width: Field) -> Field";
+const TO_BITS_STRICT_FN: &str = "to_bits_strict(val: Field) -> [Bool; 255]";
+const PACK_LE_FN: &str = "pack_le(bits: [Bool; Len]) -> Field";
+const PACK_BE_FN: &str = "pack_be(bits: [Bool; Len]) -> Field";
+const UNPACK_LE_FN: &str = "unpack_le(val: Field, const Len: Field) -> [Bool; Len]";
+const UNPACK_BE_FN: &str = "unpack_be(val: Field, const Len: Field) -> [Bool; Len]";
+
+/// The number of bits returned by `to_bits_strict`.
+/// This is enough to fit any of the fields supported by our backends,
+/// the most significant bits are set to zero for smaller fields.
+const STRICT_BITS: usize = 255;
+
+pub const BITS_SIGS: &[&str] = &[
+    SHL_FN,
+    SHR_FN,
+    ROTL_FN,
+    ROTR_FN,
+    TO_BITS_STRICT_FN,
+    PACK_LE_FN,
+    PACK_BE_FN,
+    UNPACK_LE_FN,
+    UNPACK_BE_FN,
+];
+
+pub fn get_bits_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        SHL_FN => shl,
+        SHR_FN => shr,
+        ROTL_FN => rotl,
+        ROTR_FN => rotr,
+        TO_BITS_STRICT_FN => to_bits_strict,
+        PACK_LE_FN => pack_le,
+        PACK_BE_FN => pack_be,
+        UNPACK_LE_FN => unpack_le,
+        UNPACK_BE_FN => unpack_be,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns bits functions
+pub fn bits_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    BITS_SIGS
+        .iter()
+        .map(|sig| get_bits_fn(sig).unwrap())
+        .collect()
+}
+
+/// The different ways of moving bits around.
+#[derive(Debug, Clone, Copy)]
+enum Shift {
+    Left,
+    Right,
+    RotateLeft,
+    RotateRight,
+}
+
+/// Shifts a `width`-bit value by `shift` bits to the left, dropping the bits that overflow.
+fn shl<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    shift_bits(compiler, vars, Shift::Left, span)
+}
+
+/// Shifts a `width`-bit value by `shift` bits to the right.
+fn shr<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    shift_bits(compiler, vars, Shift::Right, span)
+}
+
+/// Rotates a `width`-bit value by `shift` bits to the left.
+fn rotl<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    shift_bits(compiler, vars, Shift::RotateLeft, span)
+}
+
+/// Rotates a `width`-bit value by `shift` bits to the right.
+fn rotr<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    shift_bits(compiler, vars, Shift::RotateRight, span)
+}
+
+fn shift_bits<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    kind: Shift,
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get three vars
+    assert_eq!(vars.len(), 3);
+    let val_info = &vars[0];
+
+    // the value is a single field element
+    if !matches!(&val_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value to shift must be of type Field or BigInt. It was of type {:?}",
+            val_info.typ
+        );
+    }
+
+    let val_var = &val_info.var;
+    assert_eq!(val_var.len(), 1);
+    let val = &val_var[0];
+
+    // the shift and the width must be known at compile time
+    let shift = constant_usize(compiler, &vars[1], span)?;
+    let width = constant_usize(compiler, &vars[2], span)?;
+
+    if width == 0 || width > field::bitwise_bits::<B::Field>() {
+        return Err(compiler.error(ErrorKind::InvalidBitLength, span));
+    }
+
+    // a constant must fit in the width
+    if let ConstOrCell::Const(cst) = val {
+        let cst: BigUint = (*cst).into();
+        if cst.bits() as usize > width {
+            return Err(compiler.error(ErrorKind::AssertionFailed, span));
+        }
+    }
+
+    // decompose the value (this also constrains it to fit in `width` bits)
+    let bits = field::to_bits(compiler, val, width, span);
+
+    // move the bits around (remember, they are in little-endian order)
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let mut bits = bits.cvars;
+    match kind {
+        Shift::Left => {
+            let shift = shift.min(width);
+            bits.truncate(width - shift);
+            bits = [vec![zero; shift], bits].concat();
+        }
+        Shift::Right => {
+            let shift = shift.min(width);
+            bits.drain(..shift);
+            bits.extend(vec![zero; shift]);
+        }
+        Shift::RotateLeft => bits.rotate_right(shift % width),
+        Shift::RotateRight => bits.rotate_left(shift % width),
+    }
+
+    // and repack them
+    Ok(Some(field::from_bits(compiler, &bits, span)))
+}
+
+/// Decomposes a field element into its canonical bit representation (in little-endian order).
+fn to_bits_strict<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+    let val_info = &vars[0];
+
+    // of type field
+    if !matches!(&val_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value passed to to_bits_strict must be of type Field or BigInt. It was of type {:?}",
+            val_info.typ
+        );
+    }
+
+    let val_var = &val_info.var;
+    assert_eq!(val_var.len(), 1);
+    let val = &val_var[0];
+
+    let mut bits = field::to_bits_strict(compiler, val, span);
+    assert!(bits.len() <= STRICT_BITS);
+
+    // pad with zeros to get a fixed-size array
+    bits.cvars
+        .resize(STRICT_BITS, ConstOrCell::Const(B::Field::zero()));
+
+    Ok(Some(bits))
+}
+
+/// The order of bits when packing them into a field element.
+#[derive(Debug, Clone, Copy)]
+enum Endianness {
+    /// The first bit is the least significant one.
+    Little,
+    /// The first bit is the most significant one.
+    Big,
+}
+
+/// Packs bits into a field element, starting from the least significant bit.
+fn pack_le<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    pack(compiler, vars, Endianness::Little, span)
+}
+
+/// Packs bits into a field element, starting from the most significant bit.
+fn pack_be<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    pack(compiler, vars, Endianness::Big, span)
+}
+
+/// Decomposes a field element into a (constant) number of bits, starting from the least significant bit.
+fn unpack_le<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    unpack(compiler, vars, Endianness::Little, span)
+}
+
+/// Decomposes a field element into a (constant) number of bits, starting from the most significant bit.
+fn unpack_be<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    unpack(compiler, vars, Endianness::Big, span)
+}
+
+fn pack<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    endianness: Endianness,
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+    let bits_info = &vars[0];
+
+    // of type array of booleans
+    if !matches!(&bits_info.typ, Some(TyKind::Array(typ, _)) if matches!(**typ, TyKind::Bool)) {
+        panic!(
+            "the value to pack must be an array of booleans. It was of type {:?}",
+            bits_info.typ
+        );
+    }
+
+    // more bits than the capacity of the field would wrap around the modulus
+    let mut bits = bits_info.var.cvars.clone();
+    if bits.len() > field::bitwise_bits::<B::Field>() {
+        return Err(compiler.error(ErrorKind::InvalidBitLength, span));
+    }
+
+    // remember, from_bits expects the bits in little-endian order
+    if let Endianness::Big = endianness {
+        bits.reverse();
+    }
+
+    Ok(Some(field::from_bits(compiler, &bits, span)))
+}
+
+fn unpack<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    endianness: Endianness,
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+    let val_info = &vars[0];
+
+    // the value is a single field element
+    if !matches!(&val_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value to unpack must be of type Field or BigInt. It was of type {:?}",
+            val_info.typ
+        );
+    }
+
+    let val_var = &val_info.var;
+    assert_eq!(val_var.len(), 1);
+    let val = &val_var[0];
+
+    // the number of bits must be known at compile time,
+    // and small enough for the decomposition to be unique (see `to_bits_strict` otherwise)
+    let num_bits = constant_usize(compiler, &vars[1], span)?;
+    if num_bits == 0 || num_bits > field::bitwise_bits::<B::Field>() {
+        return Err(compiler.error(ErrorKind::InvalidBitLength, span));
+    }
+
+    // a constant must fit in the number of bits
+    if let ConstOrCell::Const(cst) = val {
+        let cst: BigUint = (*cst).into();
+        if cst.bits() as usize > num_bits {
+            return Err(compiler.error(ErrorKind::AssertionFailed, span));
+        }
+    }
+
+    // this constrains the bits to recompose to the value
+    let mut bits = field::to_bits(compiler, val, num_bits, span);
+
+    if let Endianness::Big = endianness {
+        bits.cvars.reverse();
+    }
+
+    Ok(Some(bits))
+}
+
+/// Extracts a compile-time constant that fits in a [usize].
+fn constant_usize<B: Backend>(
+    compiler: &CircuitWriter<B>,
+    var_info: &VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<usize> {
+    let cst = var_info
+        .var
+        .constant()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?;
+    let cst: BigUint = cst.into();
+    cst.try_into()
+        .map_err(|_| compiler.error(ErrorKind::InvalidBitLength, span))
+}
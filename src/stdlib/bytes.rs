@@ -0,0 +1,299 @@
+//! Conversions between arrays of bytes (`Bytes<Len>`, or `[U8; Len]`) and field elements.
+
+use std::sync::Arc;
+
+use num_bigint::BigUint;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::field;
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Value, Var};
+
+const FROM_FIELDS_FN: &str = "from_fields(vals: [Field; Len]) -> Bytes<Len>";
+const TO_FIELDS_FN: &str = "to_fields(bytes: Bytes<Len>) -> [Field; Len]";
+const PACK_LE_FN: &str = "pack_le(bytes: Bytes<Len>) -> Field";
+const PACK_BE_FN: &str = "pack_be(bytes: Bytes<Len>) -> Field";
+const UNPACK_LE_FN: &str = "unpack_le(val: Field, const Len: Field) -> Bytes<Len>";
+const UNPACK_BE_FN: &str = "unpack_be(val: Field, const Len: Field) -> Bytes<Len>";
+
+pub const BYTES_SIGS: &[&str] = &[
+    FROM_FIELDS_FN,
+    TO_FIELDS_FN,
+    PACK_LE_FN,
+    PACK_BE_FN,
+    UNPACK_LE_FN,
+    UNPACK_BE_FN,
+];
+
+pub fn get_bytes_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        FROM_FIELDS_FN => from_fields,
+        TO_FIELDS_FN => to_fields,
+        PACK_LE_FN => pack_le,
+        PACK_BE_FN => pack_be,
+        UNPACK_LE_FN => unpack_le,
+        UNPACK_BE_FN => unpack_be,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns bytes functions
+pub fn bytes_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    BYTES_SIGS
+        .iter()
+        .map(|sig| get_bytes_fn(sig).unwrap())
+        .collect()
+}
+
+/// Converts field elements to bytes, by constraining each of them to fit in 8 bits.
+fn from_fields<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type array of field elements
+    let vals = &vars[0];
+    if !matches!(&vals.typ, Some(TyKind::Array(typ, _)) if typ.is_numeric()) {
+        panic!(
+            "the values converted to bytes must be an array of field elements. It was of type {:?}",
+            vals.typ
+        );
+    }
+
+    for val in &vals.var.cvars {
+        compiler.constrain_uint(val, 8, span)?;
+    }
+
+    Ok(Some(vals.var.clone()))
+}
+
+/// Converts bytes to field elements, which doesn't cost any constraint.
+fn to_fields<B: Backend>(
+    _compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    _span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type array of bytes
+    let bytes = &vars[0];
+    if !matches!(&bytes.typ, Some(TyKind::Array(typ, _)) if typ.match_expected(&TyKind::U8)) {
+        panic!(
+            "the value converted to field elements must be an array of bytes. It was of type {:?}",
+            bytes.typ
+        );
+    }
+
+    Ok(Some(bytes.var.clone()))
+}
+
+/// The order of bytes when packing them into a field element.
+#[derive(Debug, Clone, Copy)]
+enum Endianness {
+    /// The first byte is the least significant one.
+    Little,
+    /// The first byte is the most significant one.
+    Big,
+}
+
+/// Packs bytes into a field element, starting from the least significant byte.
+fn pack_le<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    pack(compiler, vars, Endianness::Little, span)
+}
+
+/// Packs bytes into a field element, starting from the most significant byte.
+fn pack_be<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    pack(compiler, vars, Endianness::Big, span)
+}
+
+/// Decomposes a field element into a (constant) number of bytes, starting from the least significant byte.
+fn unpack_le<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    unpack(compiler, vars, Endianness::Little, span)
+}
+
+/// Decomposes a field element into a (constant) number of bytes, starting from the most significant byte.
+fn unpack_be<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    unpack(compiler, vars, Endianness::Big, span)
+}
+
+fn pack<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    endianness: Endianness,
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+    let bytes_info = &vars[0];
+
+    // of type array of bytes
+    if !matches!(&bytes_info.typ, Some(TyKind::Array(typ, _)) if typ.match_expected(&TyKind::U8)) {
+        panic!(
+            "the value to pack must be an array of bytes. It was of type {:?}",
+            bytes_info.typ
+        );
+    }
+
+    // more bytes than the capacity of the field would wrap around the modulus
+    let mut bytes = bytes_info.var.cvars.clone();
+    if 8 * bytes.len() > field::bitwise_bits::<B::Field>() {
+        return Err(compiler.error(ErrorKind::InvalidBitLength, span));
+    }
+
+    // constants are not range-checked yet
+    for byte in &bytes {
+        compiler.constrain_uint(byte, 8, span)?;
+    }
+
+    if let Endianness::Little = endianness {
+        bytes.reverse();
+    }
+
+    // acc = acc * 256 + byte, from the most significant byte
+    let base = ConstOrCell::Const(B::Field::from(256u64));
+    let mut acc = ConstOrCell::Const(B::Field::from(0u64));
+    for byte in &bytes {
+        let shifted = field::mul(compiler, &acc, &base, span);
+        acc = field::add(compiler, &shifted[0], byte, span)[0].clone();
+    }
+
+    Ok(Some(Var::new_cvar(acc, span)))
+}
+
+fn unpack<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    endianness: Endianness,
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+    let val_info = &vars[0];
+
+    // the value is a single field element
+    if !matches!(&val_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value to unpack must be of type Field or BigInt. It was of type {:?}",
+            val_info.typ
+        );
+    }
+
+    let val_var = &val_info.var;
+    assert_eq!(val_var.len(), 1);
+    let val = &val_var[0];
+
+    // the number of bytes must be known at compile time,
+    // and small enough for the decomposition to be unique
+    let len = vars[1]
+        .var
+        .constant()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?;
+    let len: BigUint = len.into();
+    let len: usize = len
+        .try_into()
+        .map_err(|_| compiler.error(ErrorKind::InvalidBitLength, span))?;
+    if len == 0 || 8 * len > field::bitwise_bits::<B::Field>() {
+        return Err(compiler.error(ErrorKind::InvalidBitLength, span));
+    }
+
+    // a constant must fit in the number of bytes
+    if let ConstOrCell::Const(cst) = val {
+        let cst: BigUint = (*cst).into();
+        if cst.bits() as usize > 8 * len {
+            return Err(compiler.error(ErrorKind::AssertionFailed, span));
+        }
+    }
+
+    let mut bytes = match val {
+        // with lookups, the bytes are computed out of circuit and range-checked with a single lookup each
+        ConstOrCell::Cell(cvar) if compiler.backend.capabilities().lookup => {
+            let bytes: Vec<_> = (0..len)
+                .map(|ii| {
+                    let byte = compiler.backend.new_internal_var(
+                        Value::Compute(
+                            vec![val.clone()],
+                            Arc::new(move |vals: &[B::Field]| {
+                                let val: BigUint = vals[0].into();
+                                let byte = (val >> (8 * ii)) & BigUint::from(255u8);
+                                B::Field::try_from(byte).unwrap_or_else(|_| {
+                                    unreachable!("a byte is smaller than the field")
+                                })
+                            }),
+                        ),
+                        span,
+                    );
+                    ConstOrCell::Cell(byte)
+                })
+                .collect();
+
+            for byte in &bytes {
+                field::range_check(compiler, byte, 8, span)?;
+            }
+
+            // the bytes must recompose to the value
+            let base = ConstOrCell::Const(B::Field::from(256u64));
+            let mut acc = ConstOrCell::Const(B::Field::from(0u64));
+            for byte in bytes.iter().rev() {
+                let shifted = field::mul(compiler, &acc, &base, span);
+                acc = field::add(compiler, &shifted[0], byte, span)[0].clone();
+            }
+            match acc {
+                ConstOrCell::Cell(acc) => compiler.backend.assert_eq_var(&acc, cvar, span),
+                ConstOrCell::Const(_) => unreachable!("the bytes are cells"),
+            }
+
+            bytes
+        }
+
+        // otherwise, this constrains the bits to recompose to the value, and the bytes to fit in 8 bits
+        _ => {
+            let bits = field::to_bits(compiler, val, 8 * len, span);
+            bits.cvars
+                .chunks(8)
+                .map(|byte| field::from_bits(compiler, byte, span)[0].clone())
+                .collect()
+        }
+    };
+
+    if let Endianness::Big = endianness {
+        bytes.reverse();
+    }
+
+    Ok(Some(Var::new(bytes, span)))
+}
@@ -1,14 +1,124 @@
+use ark_ff::Zero;
+use num_bigint::BigUint;
+
 use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
 use crate::constants::Span;
+use crate::constraints::{
+    aes, anemoi,
+    bigint::LIMB_BITS,
+    blake2, chacha20,
+    curve::{self, EmbeddedCurve, Point},
+    ecdh, ecdsa, eddsa, edwards, field, hash_to_curve, keccak, mimc, pedersen, poseidon, poseidon2,
+    rescue, rsa, schnorr, sha2, sinsemilla, vector_commitment, weierstrass,
+};
+use crate::error::{ErrorKind, Result};
 use crate::imports::FnKind;
 use crate::lexer::Token;
-use crate::parser::types::FnSig;
+use crate::parser::types::{FnSig, TyKind};
 use crate::parser::ParserCtx;
 use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Var};
 
 const POSEIDON_FN: &str = "poseidon(input: [Field; 2]) -> [Field; 3]";
+const POSEIDON_HASH_FN: &str = "poseidon_hash(input: [Field; Len], const Rate: Field) -> Field";
+const POSEIDON_HASH_WITH_FN: &str = "poseidon_hash_with(input: [Field; Len], const Rate: Field, round_constants: [Field; NumConstants]) -> Field";
+const POSEIDON2_FN: &str = "poseidon2(input: [Field; 2]) -> [Field; 3]";
+const POSEIDON2_VAR_FN: &str = "poseidon2_var(input: [Field; Len], length: Field) -> Field";
+const RESCUE_FN: &str = "rescue(input: [Field; 2]) -> [Field; 3]";
+const ANEMOI_FN: &str = "anemoi(input: [Field; 2]) -> [Field; 2]";
+const ANEMOI_JIVE_FN: &str = "anemoi_jive(input: [Field; 2]) -> Field";
+const MIMC7_FN: &str = "mimc7(x: Field, key: Field) -> Field";
+const MIMC_FEISTEL_FN: &str = "mimc_feistel(left: Field, right: Field, key: Field) -> [Field; 2]";
+const PEDERSEN_HASH_FN: &str = "pedersen_hash(input: [Bool; Len]) -> Field";
+const PEDERSEN_COMMIT_FN: &str = "pedersen_commit(input: [Bool; Len], blinding: Field) -> Field";
+const SINSEMILLA_HASH_FN: &str =
+    "sinsemilla_hash(domain: [U8; DomainLen], input: [Bool; Len]) -> Field";
+const SINSEMILLA_COMMIT_FN: &str =
+    "sinsemilla_commit(domain: [U8; DomainLen], input: [Bool; Len], blinding: Field) -> Field";
+const EDDSA_VERIFY_FN: &str =
+    "eddsa_verify(public_key: [Field; 2], signature: [Field; 3], message: Field)";
+const SCHNORR_VERIFY_FN: &str =
+    "schnorr_verify(public_key: [Field; 2], signature: [Field; 2], message: Field)";
+const ECDSA_SECP256K1_VERIFY_FN: &str = "ecdsa_secp256k1_verify(public_key: [[Field; 4]; 2], message_hash: [Field; 4], signature: [[Field; 4]; 2])";
+const RSA2048_PKCS1V15_VERIFY_FN: &str =
+    "rsa2048_pkcs1v15_verify(modulus: [Field; 32], signature: [Field; 32], message_hash: [U8; 32])";
+const RSA2048_PSS_VERIFY_FN: &str =
+    "rsa2048_pss_verify(modulus: [Field; 32], signature: [Field; 32], message_hash: [U8; 32])";
+const POINT_GENERATOR_FN: &str = "point_generator() -> [Field; 2]";
+const POINT_ADD_FN: &str = "point_add(lhs: [Field; 2], rhs: [Field; 2]) -> [Field; 2]";
+const POINT_DOUBLE_FN: &str = "point_double(point: [Field; 2]) -> [Field; 2]";
+const POINT_SCALAR_MUL_FN: &str =
+    "point_scalar_mul(point: [Field; 2], scalar: Field) -> [Field; 2]";
+const POINT_ASSERT_ON_CURVE_FN: &str = "point_assert_on_curve(point: [Field; 2])";
+const POINT_ASSERT_IN_SUBGROUP_FN: &str = "point_assert_in_subgroup(point: [Field; 2])";
+const ECDH_FN: &str = "ecdh(public_key: [Field; 2], secret_key: Field) -> Field";
+const HASH_TO_CURVE_FN: &str = "hash_to_curve(input: [Field; Len]) -> [Field; 2]";
+const VECTOR_COMMIT_FN: &str = "vector_commit(values: [Field; Len], blinding: Field) -> [Field; 2]";
+const VECTOR_COMMIT_VERIFY_FN: &str =
+    "vector_commit_verify(commitment: [Field; 2], values: [Field; Len], blinding: Field)";
+const VECTOR_COMMIT_RANGE_VERIFY_FN: &str = "vector_commit_range_verify(commitment: [Field; 2], value: Field, blinding: Field, const num_bits: Field)";
+const SHA256_FN: &str = "sha256(input: [U8; Len]) -> [U8; 32]";
+const HMAC_SHA256_FN: &str = "hmac_sha256(key: [U8; KeyLen], message: [U8; Len]) -> [U8; 32]";
+const SHA512_FN: &str = "sha512(input: [U8; Len]) -> [U8; 64]";
+const KECCAK256_FN: &str = "keccak256(input: [U8; Len]) -> [U8; 32]";
+const BLAKE2S_FN: &str = "blake2s(input: [U8; Len]) -> [U8; 32]";
+const BLAKE2B_FN: &str = "blake2b(input: [U8; Len]) -> [U8; 64]";
+const CHACHA20_FN: &str =
+    "chacha20(key: [U8; 32], nonce: [U8; 12], counter: Field, input: [U8; Len]) -> [U8; Len]";
+const AES128_ENCRYPT_BLOCK_FN: &str =
+    "aes128_encrypt_block(key: [U8; 16], block: [U8; 16]) -> [U8; 16]";
+const AES128_CTR_FN: &str =
+    "aes128_ctr(key: [U8; 16], iv: [U8; 16], input: [U8; Len]) -> [U8; Len]";
+const AES128_GCM_ENCRYPT_FN: &str =
+    "aes128_gcm_encrypt(key: [U8; 16], iv: [U8; 12], plaintext: [U8; Len]) -> [U8; Len]";
+const AES128_GCM_TAG_FN: &str = "aes128_gcm_tag(key: [U8; 16], iv: [U8; 12], aad: [U8; AadLen], ciphertext: [U8; Len]) -> [U8; 16]";
+const AES128_GCM_DECRYPT_FN: &str = "aes128_gcm_decrypt(key: [U8; 16], iv: [U8; 12], aad: [U8; AadLen], ciphertext: [U8; Len], tag: [U8; 16]) -> [U8; Len]";
 
-pub const CRYPTO_SIGS: &[&str] = &[POSEIDON_FN];
+pub const CRYPTO_SIGS: &[&str] = &[
+    POSEIDON_FN,
+    POSEIDON_HASH_FN,
+    POSEIDON_HASH_WITH_FN,
+    POSEIDON2_FN,
+    POSEIDON2_VAR_FN,
+    RESCUE_FN,
+    ANEMOI_FN,
+    ANEMOI_JIVE_FN,
+    MIMC7_FN,
+    MIMC_FEISTEL_FN,
+    PEDERSEN_HASH_FN,
+    PEDERSEN_COMMIT_FN,
+    SINSEMILLA_HASH_FN,
+    SINSEMILLA_COMMIT_FN,
+    EDDSA_VERIFY_FN,
+    SCHNORR_VERIFY_FN,
+    ECDSA_SECP256K1_VERIFY_FN,
+    RSA2048_PKCS1V15_VERIFY_FN,
+    RSA2048_PSS_VERIFY_FN,
+    POINT_GENERATOR_FN,
+    POINT_ADD_FN,
+    POINT_DOUBLE_FN,
+    POINT_SCALAR_MUL_FN,
+    POINT_ASSERT_ON_CURVE_FN,
+    POINT_ASSERT_IN_SUBGROUP_FN,
+    ECDH_FN,
+    HASH_TO_CURVE_FN,
+    VECTOR_COMMIT_FN,
+    VECTOR_COMMIT_VERIFY_FN,
+    VECTOR_COMMIT_RANGE_VERIFY_FN,
+    SHA256_FN,
+    HMAC_SHA256_FN,
+    SHA512_FN,
+    KECCAK256_FN,
+    BLAKE2S_FN,
+    BLAKE2B_FN,
+    AES128_ENCRYPT_BLOCK_FN,
+    AES128_CTR_FN,
+    AES128_GCM_ENCRYPT_FN,
+    AES128_GCM_TAG_FN,
+    AES128_GCM_DECRYPT_FN,
+    CHACHA20_FN,
+];
 
 pub fn get_crypto_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
     let ctx = &mut ParserCtx::default();
@@ -17,6 +127,47 @@ pub fn get_crypto_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
 
     let fn_handle = match name {
         POSEIDON_FN => B::poseidon(),
+        POSEIDON_HASH_FN => poseidon_hash,
+        POSEIDON_HASH_WITH_FN => poseidon_hash_with,
+        POSEIDON2_FN => poseidon2,
+        POSEIDON2_VAR_FN => poseidon2_var,
+        RESCUE_FN => rescue,
+        ANEMOI_FN => anemoi,
+        ANEMOI_JIVE_FN => anemoi_jive,
+        MIMC7_FN => mimc7,
+        MIMC_FEISTEL_FN => mimc_feistel,
+        PEDERSEN_HASH_FN => pedersen_hash,
+        PEDERSEN_COMMIT_FN => pedersen_commit,
+        SINSEMILLA_HASH_FN => sinsemilla_hash,
+        SINSEMILLA_COMMIT_FN => sinsemilla_commit,
+        EDDSA_VERIFY_FN => eddsa_verify,
+        SCHNORR_VERIFY_FN => schnorr_verify,
+        ECDSA_SECP256K1_VERIFY_FN => ecdsa_secp256k1_verify,
+        RSA2048_PKCS1V15_VERIFY_FN => rsa2048_pkcs1v15_verify,
+        RSA2048_PSS_VERIFY_FN => rsa2048_pss_verify,
+        POINT_GENERATOR_FN => point_generator,
+        POINT_ADD_FN => point_add,
+        POINT_DOUBLE_FN => point_double,
+        POINT_SCALAR_MUL_FN => point_scalar_mul,
+        POINT_ASSERT_ON_CURVE_FN => point_assert_on_curve,
+        POINT_ASSERT_IN_SUBGROUP_FN => point_assert_in_subgroup,
+        ECDH_FN => ecdh,
+        HASH_TO_CURVE_FN => hash_to_curve,
+        VECTOR_COMMIT_FN => vector_commit,
+        VECTOR_COMMIT_VERIFY_FN => vector_commit_verify,
+        VECTOR_COMMIT_RANGE_VERIFY_FN => vector_commit_range_verify,
+        SHA256_FN => sha256,
+        HMAC_SHA256_FN => hmac_sha256,
+        SHA512_FN => sha512,
+        KECCAK256_FN => keccak256,
+        BLAKE2S_FN => blake2s,
+        BLAKE2B_FN => blake2b,
+        AES128_ENCRYPT_BLOCK_FN => aes128_encrypt_block,
+        AES128_CTR_FN => aes128_ctr,
+        AES128_GCM_ENCRYPT_FN => aes128_gcm_encrypt,
+        AES128_GCM_TAG_FN => aes128_gcm_tag,
+        AES128_GCM_DECRYPT_FN => aes128_gcm_decrypt,
+        CHACHA20_FN => chacha20,
         _ => return None,
     };
 
@@ -33,3 +184,907 @@ pub fn crypto_fns<B: Backend>() -> Vec<FnInfo<B>> {
         .map(|sig| get_crypto_fn(sig).unwrap())
         .collect()
 }
+
+/// Hashes an array of field elements with a Poseidon sponge of a (constant) rate of 1, 2, or 4,
+/// with parameters derived for the field of the backend.
+fn poseidon_hash<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get an array of field elements, and the rate
+    assert_eq!(vars.len(), 2);
+    let input = poseidon_input(&vars[0]);
+    let rate = poseidon_rate(compiler, &vars[1], span)?;
+
+    let res = poseidon::hash(compiler, rate, input, span)?;
+    Ok(Some(Var::new_cvar(res, span)))
+}
+
+/// Same as `poseidon_hash`, with constant round constants given by the user,
+/// which are checked against the ones generated by the procedure of the Poseidon specification.
+fn poseidon_hash_with<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get an array of field elements, the rate, and the round constants
+    assert_eq!(vars.len(), 3);
+    let input = poseidon_input(&vars[0]);
+    let rate = poseidon_rate(compiler, &vars[1], span)?;
+
+    let round_constants = vars[2]
+        .var
+        .cvars
+        .iter()
+        .map(|cst| {
+            cst.cst()
+                .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let res = poseidon::hash_with_constants(compiler, rate, input, &round_constants, span)?;
+    Ok(Some(Var::new_cvar(res, span)))
+}
+
+/// Returns the input of a Poseidon sponge, which is an array of field elements.
+fn poseidon_input<B: Backend>(
+    input_info: &VarInfo<B::Field, B::Var>,
+) -> &[ConstOrCell<B::Field, B::Var>] {
+    if !matches!(&input_info.typ, Some(TyKind::Array(typ, _)) if typ.is_numeric()) {
+        panic!(
+            "the input of a Poseidon hash must be an array of field elements. It was of type {:?}",
+            input_info.typ
+        );
+    }
+
+    &input_info.var.cvars
+}
+
+/// Returns the rate of a Poseidon sponge, which must be a supported constant.
+fn poseidon_rate<B: Backend>(
+    compiler: &CircuitWriter<B>,
+    rate_info: &VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<usize> {
+    let rate: BigUint = rate_info
+        .var
+        .constant()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?
+        .into();
+
+    usize::try_from(rate.clone())
+        .ok()
+        .filter(|rate| poseidon::RATES.contains(rate))
+        .ok_or_else(|| compiler.error(ErrorKind::UnsupportedPoseidonRate(rate.to_string()), span))
+}
+
+/// Applies the Poseidon2 permutation to the input (and a zero capacity element), and returns the state.
+/// This is the same interface as `poseidon`, but with parameters derived for the field of the backend.
+fn poseidon2<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    permute(compiler, vars, "poseidon2", poseidon2::permutation, span)
+}
+
+/// Hashes the first `length` elements of an array with a Poseidon2 sponge,
+/// where the length can be a variable.
+fn poseidon2_var<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get an array of field elements, and its length
+    assert_eq!(vars.len(), 2);
+    let input_info = &vars[0];
+    let length = &vars[1].var[0];
+
+    if !matches!(&input_info.typ, Some(TyKind::Array(typ, _)) if typ.is_numeric()) {
+        panic!(
+            "the input of poseidon2_var must be an array of field elements. It was of type {:?}",
+            input_info.typ
+        );
+    }
+
+    // a constant length must fit in the array
+    let max_len = input_info.var.len();
+    if let Some(cst) = length.cst() {
+        let cst: BigUint = cst.into();
+        if cst > BigUint::from(max_len) {
+            return Err(compiler.error(ErrorKind::AssertionFailed, span));
+        }
+    }
+
+    Ok(Some(poseidon2::hash_var_len(
+        compiler,
+        &input_info.var.cvars,
+        length,
+        span,
+    )?))
+}
+
+fn rescue<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    permute(
+        compiler,
+        vars,
+        "rescue",
+        |compiler, state, span| Ok(rescue::permutation(compiler, state, span)),
+        span,
+    )
+}
+
+/// Applies the Anemoi permutation to two field elements.
+fn anemoi<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    let input = anemoi_input(vars);
+    Ok(Some(anemoi::permutation(compiler, input, span)))
+}
+
+/// Compresses two field elements into one, with Anemoi in the Jive mode.
+fn anemoi_jive<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    let input = anemoi_input(vars);
+    Ok(Some(anemoi::jive(compiler, input, span)))
+}
+
+fn anemoi_input<B: Backend>(
+    vars: &[VarInfo<B::Field, B::Var>],
+) -> &[ConstOrCell<B::Field, B::Var>] {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+    let input_info = &vars[0];
+
+    // of type array of two field elements
+    if !matches!(&input_info.typ, Some(TyKind::Array(typ, 2)) if typ.is_numeric()) {
+        panic!(
+            "the input of anemoi must be an array of two field elements. It was of type {:?}",
+            input_info.typ
+        );
+    }
+
+    &input_info.var.cvars
+}
+
+/// Applies a permutation to two field elements, padded with a zero (for the capacity).
+fn permute<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    name: &str,
+    permutation: Permutation<B>,
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+    let input_info = &vars[0];
+
+    // of type array of two field elements
+    if !matches!(&input_info.typ, Some(TyKind::Array(typ, 2)) if typ.is_numeric()) {
+        panic!(
+            "the input of {name} must be an array of two field elements. It was of type {:?}",
+            input_info.typ
+        );
+    }
+
+    // pad the input (for the capacity)
+    let mut state = input_info.var.cvars.clone();
+    state.push(ConstOrCell::Const(B::Field::zero()));
+
+    Ok(Some(permutation(compiler, &state, span)?))
+}
+
+/// Hashes an array of bytes with SHA-256, and returns the 32 bytes of the digest.
+fn sha256<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    hash_bytes(compiler, vars, sha2::sha256, span)
+}
+
+/// Hashes an array of bytes with SHA-512, and returns the 64 bytes of the digest.
+fn sha512<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    hash_bytes(compiler, vars, sha2::sha512, span)
+}
+
+/// Hashes an array of bytes with Keccak-256 (as used by Ethereum), and returns the 32 bytes of the digest.
+fn keccak256<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    hash_bytes(compiler, vars, keccak::keccak256, span)
+}
+
+/// Hashes an array of bytes with BLAKE2s, and returns the 32 bytes of the digest.
+fn blake2s<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    hash_bytes(compiler, vars, blake2::blake2s, span)
+}
+
+/// Computes the HMAC-SHA256 of an array of bytes with a key, and returns the 32 bytes of the MAC.
+fn hmac_sha256<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the key and the message
+    assert_eq!(vars.len(), 2);
+    let key = bytes_input(compiler, &vars[0], span)?;
+    let message = bytes_input(compiler, &vars[1], span)?;
+
+    Ok(Some(sha2::hmac_sha256(compiler, key, message, span)))
+}
+
+/// Hashes an array of bytes with BLAKE2b, and returns the 64 bytes of the digest.
+fn blake2b<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    hash_bytes(compiler, vars, blake2::blake2b, span)
+}
+
+/// Encrypts a block of 16 bytes with AES-128.
+fn aes128_encrypt_block<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the key and the block
+    assert_eq!(vars.len(), 2);
+    let key = bytes_input(compiler, &vars[0], span)?;
+    let block = bytes_input(compiler, &vars[1], span)?;
+
+    Ok(Some(aes::encrypt_block(compiler, key, block, span)))
+}
+
+/// Encrypts (or decrypts) an array of bytes with AES-128 in CTR mode.
+fn aes128_ctr<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the key, the first counter block, and the input
+    assert_eq!(vars.len(), 3);
+    let key = bytes_input(compiler, &vars[0], span)?;
+    let iv = bytes_input(compiler, &vars[1], span)?;
+    let input = bytes_input(compiler, &vars[2], span)?;
+
+    Ok(Some(aes::ctr(compiler, key, iv, input, span)))
+}
+
+/// Encrypts an array of bytes with AES-128 in GCM mode, and returns the ciphertext without the tag.
+fn aes128_gcm_encrypt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the key, the IV, and the plaintext
+    assert_eq!(vars.len(), 3);
+    let key = bytes_input(compiler, &vars[0], span)?;
+    let iv = bytes_input(compiler, &vars[1], span)?;
+    let plaintext = bytes_input(compiler, &vars[2], span)?;
+
+    Ok(Some(aes::gcm_encrypt(compiler, key, iv, plaintext, span)))
+}
+
+/// Returns the 16-byte tag of AES-128-GCM for additional authenticated data and a ciphertext.
+fn aes128_gcm_tag<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the key, the IV, the additional data, and the ciphertext
+    assert_eq!(vars.len(), 4);
+    let key = bytes_input(compiler, &vars[0], span)?;
+    let iv = bytes_input(compiler, &vars[1], span)?;
+    let aad = bytes_input(compiler, &vars[2], span)?;
+    let ciphertext = bytes_input(compiler, &vars[3], span)?;
+
+    Ok(Some(aes::gcm_tag(compiler, key, iv, aad, ciphertext, span)))
+}
+
+/// Decrypts an array of bytes with AES-128 in GCM mode, after asserting that the tag is valid.
+fn aes128_gcm_decrypt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the key, the IV, the additional data, the ciphertext, and the tag
+    assert_eq!(vars.len(), 5);
+    let key = bytes_input(compiler, &vars[0], span)?;
+    let iv = bytes_input(compiler, &vars[1], span)?;
+    let aad = bytes_input(compiler, &vars[2], span)?;
+    let ciphertext = bytes_input(compiler, &vars[3], span)?;
+    let tag = bytes_input(compiler, &vars[4], span)?;
+
+    let res = aes::gcm_decrypt(compiler, key, iv, aad, ciphertext, tag, span)?;
+    Ok(Some(res))
+}
+
+/// Encrypts (or decrypts) an array of bytes with ChaCha20, starting at a block counter.
+fn chacha20<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the key, the nonce, the counter, and the input
+    assert_eq!(vars.len(), 4);
+    let key = bytes_input(compiler, &vars[0], span)?;
+    let nonce = bytes_input(compiler, &vars[1], span)?;
+    let counter = &vars[2].var[0];
+    let input = bytes_input(compiler, &vars[3], span)?;
+
+    // a constant counter must fit in 32 bits
+    if let ConstOrCell::Const(cst) = counter {
+        let cst: BigUint = (*cst).into();
+        if cst.bits() > 32 {
+            return Err(compiler.error(ErrorKind::IntegerOverflow(32), span));
+        }
+    }
+
+    Ok(Some(chacha20::chacha20(
+        compiler, key, nonce, counter, input, span,
+    )))
+}
+
+fn mimc7<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two field elements
+    assert_eq!(vars.len(), 2);
+    let x = &vars[0].var[0];
+    let key = &vars[1].var[0];
+
+    Ok(Some(mimc::mimc7(compiler, x, key, span)))
+}
+
+fn mimc_feistel<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get three field elements
+    assert_eq!(vars.len(), 3);
+    let left = &vars[0].var[0];
+    let right = &vars[1].var[0];
+    let key = &vars[2].var[0];
+
+    Ok(Some(mimc::feistel(compiler, left, right, key, span)))
+}
+
+/// Returns the x coordinate of the Pedersen hash of an array of booleans.
+fn pedersen_hash<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+    let message = pedersen_message(&vars[0]);
+
+    let Some(curve) = edwards::embedded_curve::<B::Field>() else {
+        return Err(compiler.error(ErrorKind::NoEmbeddedCurve, span));
+    };
+
+    Ok(Some(pedersen::hash(compiler, &curve, message, span)))
+}
+
+/// Returns the x coordinate of the Pedersen commitment to an array of booleans, with a blinding factor.
+fn pedersen_commit<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the message and the blinding factor
+    assert_eq!(vars.len(), 2);
+    let message = pedersen_message(&vars[0]);
+    let blinding = &vars[1].var[0];
+
+    let Some(curve) = edwards::embedded_curve::<B::Field>() else {
+        return Err(compiler.error(ErrorKind::NoEmbeddedCurve, span));
+    };
+
+    Ok(Some(pedersen::commit(
+        compiler, &curve, message, blinding, span,
+    )))
+}
+
+/// Returns the x coordinate of the Sinsemilla hash of an array of booleans, in a domain given as a constant string.
+fn sinsemilla_hash<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the domain and the message
+    assert_eq!(vars.len(), 2);
+    let domain = sinsemilla_domain(compiler, &vars[0], span)?;
+    let message = sinsemilla_message(compiler, &vars[1], span)?;
+
+    let Some(curve) = weierstrass::embedded_curve::<B::Field>() else {
+        return Err(compiler.error(ErrorKind::NoEmbeddedCurve, span));
+    };
+
+    Ok(Some(sinsemilla::hash(
+        compiler, &curve, &domain, message, span,
+    )?))
+}
+
+/// Returns the x coordinate of the Sinsemilla commitment to an array of booleans, with a blinding factor,
+/// in a domain given as a constant string.
+fn sinsemilla_commit<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the domain, the message, and the blinding factor
+    assert_eq!(vars.len(), 3);
+    let domain = sinsemilla_domain(compiler, &vars[0], span)?;
+    let message = sinsemilla_message(compiler, &vars[1], span)?;
+    let blinding = &vars[2].var[0];
+
+    let Some(curve) = weierstrass::embedded_curve::<B::Field>() else {
+        return Err(compiler.error(ErrorKind::NoEmbeddedCurve, span));
+    };
+
+    Ok(Some(sinsemilla::commit(
+        compiler, &curve, &domain, message, blinding, span,
+    )?))
+}
+
+/// Verifies an EdDSA signature of a field element.
+/// The public key is `[y, x is odd]`, and the signature is `[r_y, r_x is odd, s]` (see [eddsa]).
+fn eddsa_verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the public key, the signature, and the message
+    assert_eq!(vars.len(), 3);
+    let public_key = &vars[0].var.cvars;
+    let signature = &vars[1].var.cvars;
+    let message = &vars[2].var[0];
+
+    let Some(curve) = edwards::embedded_curve::<B::Field>() else {
+        return Err(compiler.error(ErrorKind::NoEmbeddedCurve, span));
+    };
+
+    eddsa::verify(compiler, &curve, public_key, signature, message, span)?;
+
+    Ok(None)
+}
+
+/// Verifies a Schnorr signature of a field element.
+/// The public key is `[x, y]`, and the signature is `[r, s]` (see [schnorr]).
+fn schnorr_verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the public key, the signature, and the message
+    assert_eq!(vars.len(), 3);
+    let public_key = &vars[0].var.cvars;
+    let signature = &vars[1].var.cvars;
+    let message = &vars[2].var[0];
+
+    let Some(curve) = weierstrass::embedded_curve::<B::Field>() else {
+        return Err(compiler.error(ErrorKind::NoEmbeddedCurve, span));
+    };
+
+    schnorr::verify(compiler, &curve, public_key, signature, message, span)?;
+
+    Ok(None)
+}
+
+/// Verifies an ECDSA signature over secp256k1 (see [ecdsa]).
+/// The public key is `[x, y]`, the signature is `[r, s]`, and all the integers are given as 4 limbs of 64 bits.
+fn ecdsa_secp256k1_verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the public key, the message hash, and the signature
+    assert_eq!(vars.len(), 3);
+    let public_key = &vars[0].var.cvars;
+    let message_hash = &vars[1].var.cvars;
+    let signature = &vars[2].var.cvars;
+
+    for limbs in [public_key, message_hash, signature] {
+        check_limbs(compiler, limbs, span)?;
+    }
+
+    ecdsa::verify(compiler, public_key, message_hash, signature, span)?;
+
+    Ok(None)
+}
+
+/// Verifies a PKCS#1 v1.5 signature of a SHA-256 hash, under a 2048-bit RSA public key.
+fn rsa2048_pkcs1v15_verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the modulus, the signature, and the message hash
+    assert_eq!(vars.len(), 3);
+    let modulus = &vars[0].var.cvars;
+    let signature = &vars[1].var.cvars;
+    let message_hash = bytes_input(compiler, &vars[2], span)?;
+    check_limbs(compiler, modulus, span)?;
+    check_limbs(compiler, signature, span)?;
+
+    rsa::verify_pkcs1v15(compiler, modulus, signature, message_hash, span)?;
+
+    Ok(None)
+}
+
+/// Verifies a PSS signature of a SHA-256 hash (with MGF1-SHA-256 and a 32-byte salt), under a 2048-bit RSA public key.
+fn rsa2048_pss_verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the modulus, the signature, and the message hash
+    assert_eq!(vars.len(), 3);
+    let modulus = &vars[0].var.cvars;
+    let signature = &vars[1].var.cvars;
+    let message_hash = bytes_input(compiler, &vars[2], span)?;
+    check_limbs(compiler, modulus, span)?;
+    check_limbs(compiler, signature, span)?;
+
+    rsa::verify_pss(compiler, modulus, signature, message_hash, span)?;
+
+    Ok(None)
+}
+
+/// Checks that the constant limbs of a big integer fit in a limb.
+fn check_limbs<B: Backend>(
+    compiler: &CircuitWriter<B>,
+    limbs: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> Result<()> {
+    for limb in limbs {
+        if let ConstOrCell::Const(cst) = limb {
+            let cst: BigUint = (*cst).into();
+            if cst.bits() as usize > LIMB_BITS {
+                return Err(compiler.error(ErrorKind::IntegerOverflow(LIMB_BITS), span));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the generator of the curve embedded in the field of the backend (see [curve]).
+fn point_generator<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    assert!(vars.is_empty());
+    let curve = embedded_curve(compiler, span)?;
+
+    let (x, y) = curve.generator();
+    let generator = vec![ConstOrCell::Const(x), ConstOrCell::Const(y)];
+    Ok(Some(Var::new(generator, span)))
+}
+
+/// Adds two points of the embedded curve.
+fn point_add<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two points
+    assert_eq!(vars.len(), 2);
+    let curve = embedded_curve(compiler, span)?;
+
+    let res = curve::add(compiler, &curve, &point(&vars[0]), &point(&vars[1]), span)?;
+    Ok(Some(Var::new(res.to_vec(), span)))
+}
+
+/// Doubles a point of the embedded curve.
+fn point_double<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single point
+    assert_eq!(vars.len(), 1);
+    let curve = embedded_curve(compiler, span)?;
+
+    let res = curve::double(compiler, &curve, &point(&vars[0]), span)?;
+    Ok(Some(Var::new(res.to_vec(), span)))
+}
+
+/// Multiplies a point of the embedded curve by a scalar.
+fn point_scalar_mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a point and a scalar
+    assert_eq!(vars.len(), 2);
+    let curve = embedded_curve(compiler, span)?;
+    let scalar = &vars[1].var[0];
+
+    let res = curve::scalar_mul(compiler, &curve, &point(&vars[0]), scalar, span)?;
+    Ok(Some(Var::new(res.to_vec(), span)))
+}
+
+/// Asserts that a point is on the embedded curve.
+fn point_assert_on_curve<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single point
+    assert_eq!(vars.len(), 1);
+    let curve = embedded_curve(compiler, span)?;
+
+    curve::assert_on_curve(compiler, &curve, &point(&vars[0]), span)?;
+    Ok(None)
+}
+
+/// Asserts that a point is in the prime-order subgroup of the embedded curve.
+fn point_assert_in_subgroup<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single point
+    assert_eq!(vars.len(), 1);
+    let curve = embedded_curve(compiler, span)?;
+
+    curve::assert_in_subgroup(compiler, &curve, &point(&vars[0]), span)?;
+    Ok(None)
+}
+
+/// Returns the shared secret of a public key and a secret key (see [ecdh]).
+fn ecdh<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a public key and a secret key
+    assert_eq!(vars.len(), 2);
+    let curve = embedded_curve(compiler, span)?;
+    let secret_key = &vars[1].var[0];
+
+    let res = ecdh::shared_secret(compiler, &curve, &point(&vars[0]), secret_key, span)?;
+    Ok(Some(Var::new_cvar(res, span)))
+}
+
+/// Hashes an array of field elements to a point of the embedded curve (see [hash_to_curve]).
+fn hash_to_curve<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+    let curve = embedded_curve(compiler, span)?;
+    let input = &vars[0].var.cvars;
+
+    let res = hash_to_curve::hash_to_curve(compiler, &curve, input, span)?;
+    Ok(Some(Var::new(res.to_vec(), span)))
+}
+
+/// Returns the Pedersen commitment to an array of field elements (see [vector_commitment]).
+fn vector_commit<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the values and a blinding factor
+    assert_eq!(vars.len(), 2);
+    let curve = embedded_curve(compiler, span)?;
+    let values = &vars[0].var.cvars;
+    let blinding = &vars[1].var[0];
+
+    let res = vector_commitment::commit(compiler, &curve, values, blinding, span)?;
+    Ok(Some(Var::new(res.to_vec(), span)))
+}
+
+/// Asserts that a Pedersen commitment opens to an array of field elements (see [vector_commitment]).
+fn vector_commit_verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a commitment, the values, and a blinding factor
+    assert_eq!(vars.len(), 3);
+    let curve = embedded_curve(compiler, span)?;
+    let values = &vars[1].var.cvars;
+    let blinding = &vars[2].var[0];
+
+    vector_commitment::verify_opening(compiler, &curve, &point(&vars[0]), values, blinding, span)?;
+    Ok(None)
+}
+
+/// Asserts that a Pedersen commitment opens to a single value, which lies in `[0, 2^num_bits)`:
+/// this is a range proof on the committed value.
+fn vector_commit_range_verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a commitment, the value, a blinding factor, and the number of bits
+    assert_eq!(vars.len(), 4);
+    let curve = embedded_curve(compiler, span)?;
+    let value = &vars[1].var[0];
+    let blinding = &vars[2].var[0];
+
+    // the number of bits must be known at compile time,
+    // and small enough for the range not to wrap around the modulus
+    let num_bits: BigUint = vars[3]
+        .var
+        .constant()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?
+        .into();
+    let num_bits = usize::try_from(num_bits)
+        .ok()
+        .filter(|num_bits| (1..=field::bitwise_bits::<B::Field>()).contains(num_bits))
+        .ok_or_else(|| compiler.error(ErrorKind::InvalidBitLength, span))?;
+
+    vector_commitment::verify_range(
+        compiler,
+        &curve,
+        &point(&vars[0]),
+        value,
+        blinding,
+        num_bits,
+        span,
+    )?;
+    Ok(None)
+}
+
+fn embedded_curve<B: Backend>(
+    compiler: &CircuitWriter<B>,
+    span: Span,
+) -> Result<EmbeddedCurve<B::Field>> {
+    EmbeddedCurve::get().ok_or_else(|| compiler.error(ErrorKind::NoEmbeddedCurve, span))
+}
+
+fn point<B: Backend>(var_info: &VarInfo<B::Field, B::Var>) -> Point<B> {
+    // of type [Field; 2]
+    let [x, y] = &var_info.var.cvars[..] else {
+        panic!("a point must be of type [Field; 2]");
+    };
+    [x.clone(), y.clone()]
+}
+
+fn pedersen_message<B: Backend>(
+    input_info: &VarInfo<B::Field, B::Var>,
+) -> &[ConstOrCell<B::Field, B::Var>] {
+    // of type array of booleans
+    if !matches!(&input_info.typ, Some(TyKind::Array(typ, _)) if typ.match_expected(&TyKind::Bool))
+    {
+        panic!(
+            "the input of a Pedersen hash must be an array of booleans. It was of type {:?}",
+            input_info.typ
+        );
+    }
+
+    &input_info.var.cvars
+}
+
+/// Returns the domain of a Sinsemilla hash, which must be a constant string.
+fn sinsemilla_domain<B: Backend>(
+    compiler: &CircuitWriter<B>,
+    domain_info: &VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<String> {
+    let domain = domain_info
+        .var
+        .cvars
+        .iter()
+        .map(|byte| {
+            let byte: BigUint = byte
+                .cst()
+                .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?
+                .into();
+            u8::try_from(byte).map_err(|_| compiler.error(ErrorKind::AssertionFailed, span))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(String::from_utf8_lossy(&domain).into_owned())
+}
+
+/// Returns the message of a Sinsemilla hash, which has at most [sinsemilla::MAX_CHUNKS] chunks.
+fn sinsemilla_message<'a, B: Backend>(
+    compiler: &CircuitWriter<B>,
+    input_info: &'a VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<&'a [ConstOrCell<B::Field, B::Var>]> {
+    // of type array of booleans
+    if !matches!(&input_info.typ, Some(TyKind::Array(typ, _)) if typ.match_expected(&TyKind::Bool))
+    {
+        panic!(
+            "the input of a Sinsemilla hash must be an array of booleans. It was of type {:?}",
+            input_info.typ
+        );
+    }
+
+    let message = &input_info.var.cvars;
+    if message.len() > sinsemilla::K * sinsemilla::MAX_CHUNKS {
+        return Err(compiler.error(ErrorKind::InvalidBitLength, span));
+    }
+
+    Ok(message)
+}
+
+/// The signature of the hash gadgets, which take a list of cells.
+type Gadget<B> = fn(
+    &mut CircuitWriter<B>,
+    &[ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>],
+    Span,
+) -> Var<<B as Backend>::Field, <B as Backend>::Var>;
+
+/// The signature of the permutation gadgets, which can fail for fields they have no parameters for.
+type Permutation<B> = fn(
+    &mut CircuitWriter<B>,
+    &[ConstOrCell<<B as Backend>::Field, <B as Backend>::Var>],
+    Span,
+) -> Result<Var<<B as Backend>::Field, <B as Backend>::Var>>;
+
+fn hash_bytes<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    hash: Gadget<B>,
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+    let input = bytes_input(compiler, &vars[0], span)?;
+
+    Ok(Some(hash(compiler, input, span)))
+}
+
+/// Returns the cells of an array of bytes, after checking that its constant bytes fit in 8 bits.
+fn bytes_input<'a, B: Backend>(
+    compiler: &CircuitWriter<B>,
+    input_info: &'a VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<&'a [ConstOrCell<B::Field, B::Var>]> {
+    // of type array of bytes
+    if !matches!(&input_info.typ, Some(TyKind::Array(typ, _)) if typ.match_expected(&TyKind::U8)) {
+        panic!(
+            "the input of a byte-oriented function must be an array of bytes. It was of type {:?}",
+            input_info.typ
+        );
+    }
+
+    // constants are not range-checked yet
+    let input = &input_info.var.cvars;
+    for byte in input {
+        if let Some(cst) = byte.cst() {
+            let cst: BigUint = cst.into();
+            if cst.bits() > 8 {
+                return Err(compiler.error(ErrorKind::AssertionFailed, span));
+            }
+        }
+    }
+
+    Ok(input)
+}
@@ -0,0 +1,79 @@
+//! Decoding of text encodings, as found in JWTs and email headers.
+
+use num_bigint::BigUint;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::base64;
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::Var;
+
+const BASE64URL_DECODE_FN: &str =
+    "base64url_decode(input: [U8; Len], const OutLen: Field) -> [U8; OutLen]";
+
+pub const ENCODING_SIGS: &[&str] = &[BASE64URL_DECODE_FN];
+
+pub fn get_encoding_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        BASE64URL_DECODE_FN => base64url_decode,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns encoding functions
+pub fn encoding_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    ENCODING_SIGS
+        .iter()
+        .map(|sig| get_encoding_fn(sig).unwrap())
+        .collect()
+}
+
+/// Decodes base64url characters (without padding) into `OutLen` bytes,
+/// where `OutLen` must be the number of bytes encoded by the characters.
+fn base64url_decode<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the input and the output length
+    assert_eq!(vars.len(), 2);
+    let input_info = &vars[0];
+
+    // of type array of bytes
+    if !matches!(&input_info.typ, Some(TyKind::Array(typ, _)) if typ.match_expected(&TyKind::U8)) {
+        panic!(
+            "the input of base64url_decode must be an array of bytes. It was of type {:?}",
+            input_info.typ
+        );
+    }
+    let input = &input_info.var.cvars;
+
+    // the output length is known at compile time, and must match the input length
+    let out_len = vars[1]
+        .var
+        .constant()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?;
+    let out_len: BigUint = out_len.into();
+    let expected = base64::decoded_len(input.len())
+        .ok_or_else(|| compiler.error(ErrorKind::InvalidEncodedLength, span))?;
+    if out_len != BigUint::from(expected) {
+        return Err(compiler.error(ErrorKind::InvalidEncodedLength, span));
+    }
+
+    Ok(Some(base64::decode(compiler, input, span)?))
+}
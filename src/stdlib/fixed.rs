@@ -0,0 +1,387 @@
+//! Signed fixed-point arithmetic.
+//!
+//! A fixed-point number `x` with `frac_bits` fractional bits is represented
+//! as the field element `x * 2^frac_bits` (negative values `-y` being encoded as `p - y`).
+//! The representation must fit in a signed integer of [FIXED_BITS] bits,
+//! which is enforced on every input and output of the functions of this module.
+
+use std::sync::Arc;
+
+use ark_ff::{Field, One, Zero};
+use num_bigint::{BigInt, BigUint, Sign};
+
+use crate::backends::{Backend, BackendField};
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::{boolean, field};
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::FnSig;
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Value, Var};
+
+const MUL_FN: &str = "mul(lhs: Field, rhs: Field, const frac_bits: Field) -> Field";
+const DIV_FN: &str = "div(lhs: Field, rhs: Field, const frac_bits: Field) -> Field";
+const LT_FN: &str = "lt(lhs: Field, rhs: Field) -> Bool";
+const LE_FN: &str = "le(lhs: Field, rhs: Field) -> Bool";
+
+/// The number of bits of the (signed) representation of a fixed-point number.
+pub const FIXED_BITS: usize = 64;
+
+/// The maximum number of fractional bits,
+/// we keep at least one integer bit on top of the sign bit.
+const MAX_FRAC_BITS: usize = FIXED_BITS - 2;
+
+pub const FIXED_SIGS: &[&str] = &[MUL_FN, DIV_FN, LT_FN, LE_FN];
+
+pub fn get_fixed_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        MUL_FN => mul,
+        DIV_FN => div,
+        LT_FN => lt,
+        LE_FN => le,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns fixed-point functions
+pub fn fixed_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    FIXED_SIGS
+        .iter()
+        .map(|sig| get_fixed_fn(sig).unwrap())
+        .collect()
+}
+
+/// Multiplies two fixed-point numbers, rounding the result to the nearest representable value
+/// (ties are rounded up).
+fn mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get three vars
+    assert_eq!(vars.len(), 3);
+
+    let lhs = fixed_arg(compiler, &vars[0], span)?;
+    let rhs = fixed_arg(compiler, &vars[1], span)?;
+    let frac_bits = frac_bits(compiler, &vars[2], span)?;
+
+    // the product has `2 * frac_bits` fractional bits, and fits in `2 * FIXED_BITS - 1` signed bits
+    let prod = field::mul(compiler, lhs, rhs, span);
+    let res = rescale(compiler, &prod[0], frac_bits, 2 * FIXED_BITS, span)?;
+
+    Ok(Some(Var::new_cvar(res, span)))
+}
+
+/// Rounds a value with `2 * frac_bits` fractional bits (like a product, or a sum of products)
+/// to the nearest fixed-point number with `frac_bits` fractional bits (ties are rounded up).
+/// The value must fit in `num_bits` signed bits, and the result is constrained to fit in [FIXED_BITS] signed bits.
+pub fn rescale<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    val: &ConstOrCell<B::Field, B::Var>,
+    frac_bits: usize,
+    num_bits: usize,
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    // To round it, we add `2^(frac_bits - 1)` and drop the `frac_bits` least significant bits.
+    // As this only works on unsigned values, we first add an offset of `2^(num_bits - 1)`
+    // (which becomes `2^(num_bits - 1 - frac_bits)` after the shift).
+    let two = B::Field::from(2u64);
+    let half = two.pow([frac_bits as u64 - 1]);
+    let offset = field::signed_offset::<B>(num_bits);
+    let shifted_offset = two.pow([(num_bits - 1 - frac_bits) as u64]);
+
+    let shifted = field::add(compiler, val, &ConstOrCell::Const(half + offset), span);
+    let res = shift_right(compiler, &shifted[0], frac_bits, num_bits, span)?;
+    let res = field::sub(compiler, &res, &ConstOrCell::Const(shifted_offset), span)[0].clone();
+
+    constrain_fixed(compiler, &res, span)?;
+
+    Ok(res)
+}
+
+/// Divides two fixed-point numbers, rounding the result to the nearest representable value
+/// (ties are rounded up).
+fn div<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get three vars
+    assert_eq!(vars.len(), 3);
+
+    let lhs = fixed_arg(compiler, &vars[0], span)?;
+    let rhs = fixed_arg(compiler, &vars[1], span)?;
+    let frac_bits = frac_bits(compiler, &vars[2], span)?;
+
+    if matches!(rhs, ConstOrCell::Const(cst) if cst.is_zero()) {
+        return Err(compiler.error(ErrorKind::DivisionByZero, span));
+    }
+
+    // we compute `round(lhs * 2^frac_bits / rhs)`
+    let power_of_two = B::Field::from(2u64).pow([frac_bits as u64]);
+    let num = field::mul(compiler, lhs, &ConstOrCell::Const(power_of_two), span);
+
+    // two constants
+    if let (ConstOrCell::Const(num), ConstOrCell::Const(rhs)) = (&num[0], rhs) {
+        let res = ConstOrCell::Const(round_div(*num, *rhs));
+        constrain_fixed(compiler, &res, span)?;
+        return Ok(Some(Var::new_cvar(res, span)));
+    }
+
+    // otherwise, the quotient is computed out of circuit
+    let quotient = compiler.backend.new_internal_var(
+        Value::Compute(
+            vec![num[0].clone(), rhs.clone()],
+            Arc::new(|values: &[B::Field]| round_div(values[0], values[1])),
+        ),
+        span,
+    );
+    let quotient = ConstOrCell::Cell(quotient);
+    constrain_fixed(compiler, &quotient, span)?;
+
+    // and constrained to be the rounded quotient.
+    // For a positive divisor, this means that `0 <= 2 * (num - quotient * rhs) + rhs < 2 * rhs`,
+    // for a negative divisor we negate both sides of the division.
+    // Note that this can't wrap around the field modulus, as all values fit in `2 * FIXED_BITS` bits.
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let is_negative = field::less_than_signed(compiler, FIXED_BITS, rhs, &zero, span)?;
+    let neg_rhs = field::neg(compiler, rhs, span);
+    let abs_rhs = field::if_else_inner(compiler, &is_negative[0], &neg_rhs[0], rhs, span);
+
+    let prod = field::mul(compiler, &quotient, rhs, span);
+    let error = field::sub(compiler, &num[0], &prod[0], span);
+    let neg_error = field::neg(compiler, &error[0], span);
+    let error = field::if_else_inner(compiler, &is_negative[0], &neg_error[0], &error[0], span);
+
+    let double_error = field::add(compiler, &error[0], &error[0], span);
+    let bound = field::add(compiler, &double_error[0], &abs_rhs[0], span);
+    let double_abs_rhs = field::add(compiler, &abs_rhs[0], &abs_rhs[0], span);
+
+    // 0 <= bound (this also ensures that the divisor is not zero)
+    field::range_check(compiler, &bound[0], FIXED_BITS + 2, span)?;
+
+    // bound < 2 * |rhs|
+    let is_lt = field::less_than(
+        compiler,
+        FIXED_BITS + 2,
+        &bound[0],
+        &double_abs_rhs[0],
+        span,
+    )?;
+    match &is_lt[0] {
+        ConstOrCell::Const(cst) => assert!(cst.is_one()),
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(cvar, B::Field::one(), span),
+    }
+
+    Ok(Some(Var::new_cvar(quotient, span)))
+}
+
+/// Returns true if lhs < rhs.
+fn lt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+
+    let lhs = fixed_arg(compiler, &vars[0], span)?;
+    let rhs = fixed_arg(compiler, &vars[1], span)?;
+
+    Ok(Some(field::less_than_signed(
+        compiler, FIXED_BITS, lhs, rhs, span,
+    )?))
+}
+
+/// Returns true if lhs <= rhs.
+fn le<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+
+    let lhs = fixed_arg(compiler, &vars[0], span)?;
+    let rhs = fixed_arg(compiler, &vars[1], span)?;
+
+    // lhs <= rhs <=> !(rhs < lhs)
+    let is_gt = field::less_than_signed(compiler, FIXED_BITS, rhs, lhs, span)?;
+    Ok(Some(boolean::not(compiler, &is_gt[0], span)))
+}
+
+/// Returns a fixed-point argument, constrained to fit in [FIXED_BITS] signed bits.
+pub fn fixed_arg<'a, B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    var_info: &'a VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<&'a ConstOrCell<B::Field, B::Var>> {
+    // of type field
+    if !matches!(&var_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "a fixed-point number must be of type Field or BigInt. It was of type {:?}",
+            var_info.typ
+        );
+    }
+
+    assert_eq!(var_info.var.len(), 1);
+    let val = &var_info.var[0];
+
+    constrain_fixed(compiler, val, span)?;
+
+    Ok(val)
+}
+
+/// Extracts the (compile-time) number of fractional bits.
+pub fn frac_bits<B: Backend>(
+    compiler: &CircuitWriter<B>,
+    var_info: &VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<usize> {
+    let cst = var_info
+        .var
+        .constant()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?;
+    let cst: BigUint = cst.into();
+
+    match usize::try_from(cst) {
+        Ok(frac_bits) if (1..=MAX_FRAC_BITS).contains(&frac_bits) => Ok(frac_bits),
+        _ => Err(compiler.error(ErrorKind::InvalidBitLength, span)),
+    }
+}
+
+/// Constrains a value to fit in [FIXED_BITS] signed bits.
+pub fn constrain_fixed<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    val: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<()> {
+    let offset = ConstOrCell::Const(field::signed_offset::<B>(FIXED_BITS));
+    let shifted = field::add(compiler, val, &offset, span);
+
+    field::range_check(compiler, &shifted[0], FIXED_BITS, span)
+}
+
+/// Computes `val >> shift` for a value that fits in `num_bits` bits.
+fn shift_right<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    val: &ConstOrCell<B::Field, B::Var>,
+    shift: usize,
+    num_bits: usize,
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    let cvar = match val {
+        ConstOrCell::Const(cst) => {
+            let cst: BigUint = (*cst).into();
+            return Ok(ConstOrCell::Const(from_biguint(cst >> shift)));
+        }
+        ConstOrCell::Cell(cvar) => cvar,
+    };
+
+    // the quotient and the remainder are computed out of circuit
+    let quotient = compiler.backend.new_internal_var(
+        Value::Compute(
+            vec![val.clone()],
+            Arc::new(move |values: &[B::Field]| {
+                let val: BigUint = values[0].into();
+                from_biguint(val >> shift)
+            }),
+        ),
+        span,
+    );
+    let remainder = compiler.backend.new_internal_var(
+        Value::Compute(
+            vec![val.clone()],
+            Arc::new(move |values: &[B::Field]| {
+                let val: BigUint = values[0].into();
+                let mask = (BigUint::one() << shift) - 1u8;
+                from_biguint(val & mask)
+            }),
+        ),
+        span,
+    );
+    let quotient = ConstOrCell::Cell(quotient);
+    let remainder = ConstOrCell::Cell(remainder);
+
+    // and constrained
+    field::range_check(compiler, &quotient, num_bits - shift, span)?;
+    field::range_check(compiler, &remainder, shift, span)?;
+
+    // val = quotient * 2^shift + remainder
+    let power_of_two = B::Field::from(2u64).pow([shift as u64]);
+    let shifted = field::mul(compiler, &quotient, &ConstOrCell::Const(power_of_two), span);
+    let recomposed = field::add(compiler, &shifted[0], &remainder, span);
+    let recomposed = recomposed[0]
+        .cvar()
+        .expect("the recomposed value can't be a constant");
+    compiler.backend.assert_eq_var(cvar, recomposed, span);
+
+    Ok(quotient)
+}
+
+/// Computes `round(num / den)` on signed values (ties are rounded up),
+/// where negative values `-x` are encoded as `p - x`.
+fn round_div<F: BackendField>(num: F, den: F) -> F {
+    let num = to_signed(num);
+    let den = to_signed(den);
+
+    // the constraints can't be satisfied in this case anyway
+    if den.sign() == Sign::NoSign {
+        return F::zero();
+    }
+
+    // round(num / den) = floor((2 * num + den) / (2 * den)) for a positive divisor
+    let (num, den) = if den.sign() == Sign::Minus {
+        (-num, -den)
+    } else {
+        (num, den)
+    };
+    let num = num * 2 + &den;
+    let den = den * 2;
+
+    // the division of big integers is truncated, we want it floored
+    let mut res = &num / &den;
+    if (&num % &den).sign() == Sign::Minus {
+        res -= 1;
+    }
+
+    from_signed(res)
+}
+
+fn to_signed<F: BackendField>(val: F) -> BigInt {
+    let pos: BigUint = val.into();
+    let neg: BigUint = (-val).into();
+    if pos <= neg {
+        BigInt::from(pos)
+    } else {
+        -BigInt::from(neg)
+    }
+}
+
+fn from_signed<F: BackendField>(val: BigInt) -> F {
+    let (sign, magnitude) = val.into_parts();
+    let res = from_biguint::<F>(magnitude);
+    if sign == Sign::Minus {
+        -res
+    } else {
+        res
+    }
+}
+
+fn from_biguint<F: BackendField>(val: BigUint) -> F {
+    F::try_from(val).unwrap_or_else(|_| unreachable!("the value is smaller than the field modulus"))
+}
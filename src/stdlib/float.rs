@@ -0,0 +1,649 @@
+//! Experimental emulation of IEEE-754 single precision floating-point numbers.
+//!
+//! A float is represented by its 32-bit encoding (sign, exponent, and fraction bits) stored in a field element.
+//! Results are rounded to the nearest representable value (ties to even), like in IEEE-754.
+//! Infinities, NaNs, and subnormal numbers are not supported:
+//! they can't be used as inputs, and operations that would produce them make the circuit unsatisfiable.
+
+use std::sync::Arc;
+
+use ark_ff::{Field, One, Zero};
+use num_bigint::BigUint;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::{boolean, field};
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::FnSig;
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Value, Var};
+
+const ADD_FN: &str = "add(lhs: Field, rhs: Field) -> Field";
+const MUL_FN: &str = "mul(lhs: Field, rhs: Field) -> Field";
+const LT_FN: &str = "lt(lhs: Field, rhs: Field) -> Bool";
+
+/// The number of bits in the encoding of a float.
+const FLOAT_BITS: usize = 32;
+
+/// The number of (explicit) fraction bits.
+const FRACTION_BITS: usize = 23;
+
+/// The number of bits of a significand, including the implicit leading one.
+const SIGNIFICAND_BITS: usize = FRACTION_BITS + 1;
+
+/// The number of exponent bits.
+const EXPONENT_BITS: usize = 8;
+
+/// The bias of the exponent.
+const EXPONENT_BIAS: u64 = 127;
+
+/// The biased exponent reserved for infinities and NaNs.
+const SPECIAL_EXPONENT: u64 = 255;
+
+/// The number of extra bits used when aligning the significands of an addition.
+/// If the exponents differ by more than that, the smallest operand only matters for rounding.
+const ALIGN_BITS: usize = SIGNIFICAND_BITS + 2;
+
+/// The number of bits of the (aligned) sum of two significands.
+const SUM_BITS: usize = SIGNIFICAND_BITS + ALIGN_BITS + 2;
+
+pub const FLOAT_SIGS: &[&str] = &[ADD_FN, MUL_FN, LT_FN];
+
+pub fn get_float_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        ADD_FN => add,
+        MUL_FN => mul,
+        LT_FN => lt,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns float functions
+pub fn float_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    FLOAT_SIGS
+        .iter()
+        .map(|sig| get_float_fn(sig).unwrap())
+        .collect()
+}
+
+/// The components of a float.
+struct Unpacked<B: Backend> {
+    /// The sign bit.
+    sign: ConstOrCell<B::Field, B::Var>,
+
+    /// The biased exponent.
+    exponent: ConstOrCell<B::Field, B::Var>,
+
+    /// The significand, including the implicit leading one (or zero if the float is zero).
+    significand: ConstOrCell<B::Field, B::Var>,
+
+    /// The encoding of the absolute value of the float (all bits but the sign).
+    magnitude: ConstOrCell<B::Field, B::Var>,
+
+    /// Set if the float is (positive or negative) zero.
+    is_zero: ConstOrCell<B::Field, B::Var>,
+}
+
+/// Adds two floats.
+fn add<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+
+    let lhs = float_arg(compiler, &vars[0], span)?;
+    let rhs = float_arg(compiler, &vars[1], span)?;
+    let lhs = unpack(compiler, lhs, span)?;
+    let rhs = unpack(compiler, rhs, span)?;
+
+    // we order the operands so that the first one has the largest magnitude
+    // (the order of the magnitudes is the order of their encodings)
+    let swap = field::less_than(
+        compiler,
+        FLOAT_BITS - 1,
+        &lhs.magnitude,
+        &rhs.magnitude,
+        span,
+    )?;
+    let swap = &swap[0];
+    let (large_sign, small_sign) = order(compiler, swap, &lhs.sign, &rhs.sign, span);
+    let (large_exp, small_exp) = order(compiler, swap, &lhs.exponent, &rhs.exponent, span);
+    let (large_sig, small_sig) = order(compiler, swap, &lhs.significand, &rhs.significand, span);
+    let (_, small_is_zero) = order(compiler, swap, &lhs.is_zero, &rhs.is_zero, span);
+
+    // we align the small significand on the large one, by shifting it to the right by the difference of exponents
+    let diff = field::sub(compiler, &large_exp, &small_exp, span);
+    let is_far = field::less_than(
+        compiler,
+        EXPONENT_BITS,
+        &ConstOrCell::Const(B::Field::from(ALIGN_BITS as u64)),
+        &diff[0],
+        span,
+    )?;
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let shift = select(compiler, &is_far[0], &zero, &diff[0], span);
+    let shift_bits = field::to_bits(compiler, &shift, 5, span);
+    let divisor = pow2(compiler, &shift_bits.cvars, span);
+
+    // small * 2^ALIGN_BITS = quotient * 2^shift + remainder, with remainder < 2^shift
+    let scale = B::Field::from(2u64).pow([ALIGN_BITS as u64]);
+    let scaled_small = field::mul(compiler, &small_sig, &ConstOrCell::Const(scale), span);
+    let (quotient, remainder) = align_hints(compiler, &small_sig, &shift, span);
+
+    field::range_check(compiler, &quotient, SIGNIFICAND_BITS + ALIGN_BITS, span)?;
+    field::range_check(compiler, &remainder, ALIGN_BITS + 1, span)?;
+    let is_lt = field::less_than(compiler, ALIGN_BITS + 1, &remainder, &divisor, span)?;
+    assert_eq_cst(compiler, &is_lt[0], B::Field::one(), span)?;
+
+    let prod = field::mul(compiler, &quotient, &divisor, span);
+    let recomposed = field::add(compiler, &prod[0], &remainder, span);
+    let diff = field::sub(compiler, &recomposed[0], &scaled_small[0], span);
+    assert_eq_cst(compiler, &diff[0], B::Field::zero(), span)?;
+
+    // the bits shifted out are kept as a single sticky bit below the aligned significand
+    let sticky = is_non_zero(compiler, &remainder, span);
+    let aligned = field::add(compiler, &quotient, &quotient, span);
+    let aligned = field::add(compiler, &aligned[0], &sticky, span);
+
+    // if the small operand is too far, it only contributes to the sticky bit
+    let far_aligned = boolean::not(compiler, &small_is_zero, span);
+    let aligned = select(compiler, &is_far[0], &far_aligned[0], &aligned[0], span);
+
+    // we add (or subtract, if the signs differ) the significands,
+    // as the large operand has the largest magnitude, the result is positive
+    let scale = B::Field::from(2u64).pow([ALIGN_BITS as u64 + 1]);
+    let scaled_large = field::mul(compiler, &large_sig, &ConstOrCell::Const(scale), span);
+    let is_sub = boolean::xor(compiler, &large_sign, &small_sign, span);
+    let neg_aligned = field::neg(compiler, &aligned, span);
+    let aligned = select(compiler, &is_sub[0], &neg_aligned[0], &aligned, span);
+    let sum = field::add(compiler, &scaled_large[0], &aligned, span);
+    let sum = &sum[0];
+
+    // we normalize the sum, by shifting it to the left until its most significant bit is set
+    let is_zero = field::equal_cells(compiler, sum, &zero, span);
+    let is_zero = &is_zero[0];
+    let leading_zeros = leading_zeros_hint(compiler, sum, span);
+    let leading_zeros_bits = field::to_bits(compiler, &leading_zeros, 6, span);
+    let factor = pow2(compiler, &leading_zeros_bits.cvars, span);
+    let normalized = field::mul(compiler, sum, &factor, span);
+
+    let leading_one = B::Field::from(2u64).pow([SUM_BITS as u64 - 1]);
+    let normalized = select(
+        compiler,
+        is_zero,
+        &ConstOrCell::Const(leading_one),
+        &normalized[0],
+        span,
+    );
+    let bits = field::to_bits(compiler, &normalized, SUM_BITS, span).cvars;
+    assert_eq_cst(compiler, &bits[SUM_BITS - 1], B::Field::one(), span)?;
+
+    // and round it
+    let low = SUM_BITS - SIGNIFICAND_BITS;
+    let truncated = field::from_bits(compiler, &bits[low..], span);
+    let rest = field::from_bits(compiler, &bits[..low - 1], span);
+    let (fraction, carry) = round(
+        compiler,
+        &truncated[0],
+        &bits[low],
+        &bits[low - 1],
+        &rest[0],
+        span,
+    );
+
+    // exponent = large_exp + 1 - leading_zeros + carry
+    let exponent = field::add(compiler, &large_exp, &carry, span);
+    let exponent = field::sub(compiler, &exponent[0], &leading_zeros, span);
+    let exponent = field::add(
+        compiler,
+        &exponent[0],
+        &ConstOrCell::Const(B::Field::one()),
+        span,
+    );
+
+    // an exact zero is positive, unless both operands are negative
+    let zero_sign = boolean::and(compiler, &lhs.sign, &rhs.sign, span);
+    let sign = select(compiler, is_zero, &zero_sign[0], &large_sign, span);
+
+    let res = pack(compiler, &sign, &exponent[0], &fraction, is_zero, span)?;
+    Ok(Some(res))
+}
+
+/// Multiplies two floats.
+fn mul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+
+    let lhs = float_arg(compiler, &vars[0], span)?;
+    let rhs = float_arg(compiler, &vars[1], span)?;
+    let lhs = unpack(compiler, lhs, span)?;
+    let rhs = unpack(compiler, rhs, span)?;
+
+    let sign = boolean::xor(compiler, &lhs.sign, &rhs.sign, span);
+    let is_zero = boolean::or(compiler, &lhs.is_zero, &rhs.is_zero, span);
+
+    // the product of the significands has its most significant bit at position `2 * SIGNIFICAND_BITS - 1` or below
+    let prod = field::mul(compiler, &lhs.significand, &rhs.significand, span);
+    let bits = field::to_bits(compiler, &prod[0], 2 * SIGNIFICAND_BITS, span).cvars;
+    let top = &bits[2 * SIGNIFICAND_BITS - 1];
+
+    // we keep the significand that starts at the leading one, and round it
+    let high = field::from_bits(compiler, &bits[SIGNIFICAND_BITS..], span);
+    let low = field::from_bits(
+        compiler,
+        &bits[FRACTION_BITS..2 * SIGNIFICAND_BITS - 1],
+        span,
+    );
+    let truncated = select(compiler, top, &high[0], &low[0], span);
+    let lsb = select(
+        compiler,
+        top,
+        &bits[SIGNIFICAND_BITS],
+        &bits[FRACTION_BITS],
+        span,
+    );
+    let guard = select(
+        compiler,
+        top,
+        &bits[FRACTION_BITS],
+        &bits[FRACTION_BITS - 1],
+        span,
+    );
+    let high_rest = field::from_bits(compiler, &bits[..FRACTION_BITS], span);
+    let low_rest = field::from_bits(compiler, &bits[..FRACTION_BITS - 1], span);
+    let rest = select(compiler, top, &high_rest[0], &low_rest[0], span);
+    let (fraction, carry) = round(compiler, &truncated, &lsb, &guard, &rest, span);
+
+    // exponent = lhs_exp + rhs_exp - bias + top + carry
+    let exponent = field::add(compiler, &lhs.exponent, &rhs.exponent, span);
+    let exponent = field::add(compiler, &exponent[0], top, span);
+    let exponent = field::add(compiler, &exponent[0], &carry, span);
+    let exponent = field::sub(
+        compiler,
+        &exponent[0],
+        &ConstOrCell::Const(B::Field::from(EXPONENT_BIAS)),
+        span,
+    );
+
+    let res = pack(
+        compiler,
+        &sign[0],
+        &exponent[0],
+        &fraction,
+        &is_zero[0],
+        span,
+    )?;
+    Ok(Some(res))
+}
+
+/// Returns true if lhs < rhs (note that positive and negative zeros are equal).
+fn lt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+
+    let lhs = float_arg(compiler, &vars[0], span)?;
+    let rhs = float_arg(compiler, &vars[1], span)?;
+    let lhs = unpack(compiler, lhs, span)?;
+    let rhs = unpack(compiler, rhs, span)?;
+
+    let mag_lt = field::less_than(
+        compiler,
+        FLOAT_BITS - 1,
+        &lhs.magnitude,
+        &rhs.magnitude,
+        span,
+    )?;
+    let mag_gt = field::less_than(
+        compiler,
+        FLOAT_BITS - 1,
+        &rhs.magnitude,
+        &lhs.magnitude,
+        span,
+    )?;
+    let both_zero = boolean::and(compiler, &lhs.is_zero, &rhs.is_zero, span);
+
+    let lhs_pos = boolean::not(compiler, &lhs.sign, span);
+    let rhs_pos = boolean::not(compiler, &rhs.sign, span);
+
+    // both positive: |lhs| < |rhs|
+    let both_pos = boolean::and(compiler, &lhs_pos[0], &rhs_pos[0], span);
+    let case_pos = boolean::and(compiler, &both_pos[0], &mag_lt[0], span);
+
+    // both negative: |lhs| > |rhs|
+    let both_neg = boolean::and(compiler, &lhs.sign, &rhs.sign, span);
+    let case_neg = boolean::and(compiler, &both_neg[0], &mag_gt[0], span);
+
+    // lhs negative and rhs positive: unless they're both zero
+    let mixed = boolean::and(compiler, &lhs.sign, &rhs_pos[0], span);
+    let not_both_zero = boolean::not(compiler, &both_zero[0], span);
+    let case_mixed = boolean::and(compiler, &mixed[0], &not_both_zero[0], span);
+
+    let res = boolean::or(compiler, &case_pos[0], &case_neg[0], span);
+    Ok(Some(boolean::or(compiler, &res[0], &case_mixed[0], span)))
+}
+
+/// Returns a float argument, checking that a constant fits in [FLOAT_BITS] bits.
+fn float_arg<'a, B: Backend>(
+    compiler: &CircuitWriter<B>,
+    var_info: &'a VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<&'a ConstOrCell<B::Field, B::Var>> {
+    // of type field
+    if !matches!(&var_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "a float must be of type Field or BigInt. It was of type {:?}",
+            var_info.typ
+        );
+    }
+
+    assert_eq!(var_info.var.len(), 1);
+    let val = &var_info.var[0];
+
+    if let ConstOrCell::Const(cst) = val {
+        let cst: BigUint = (*cst).into();
+        if cst.bits() as usize > FLOAT_BITS {
+            return Err(compiler.error(ErrorKind::IntegerOverflow(FLOAT_BITS), span));
+        }
+    }
+
+    Ok(val)
+}
+
+/// Decomposes a float into its components,
+/// and constrains it to be a normal number or zero.
+fn unpack<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    val: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Unpacked<B>> {
+    let bits = field::to_bits(compiler, val, FLOAT_BITS, span).cvars;
+
+    let fraction = field::from_bits(compiler, &bits[..FRACTION_BITS], span);
+    let exponent = field::from_bits(compiler, &bits[FRACTION_BITS..FLOAT_BITS - 1], span);
+    let magnitude = field::from_bits(compiler, &bits[..FLOAT_BITS - 1], span);
+    let sign = bits[FLOAT_BITS - 1].clone();
+
+    // infinities and NaNs are not supported
+    let special = ConstOrCell::Const(B::Field::from(SPECIAL_EXPONENT));
+    let is_special = field::equal_cells(compiler, &exponent[0], &special, span);
+    assert_eq_cst(compiler, &is_special[0], B::Field::zero(), span)?;
+
+    // subnormal numbers are not supported either, so a zero exponent means that the float is zero
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let is_zero = field::equal_cells(compiler, &exponent[0], &zero, span);
+    let masked = field::mul(compiler, &is_zero[0], &fraction[0], span);
+    assert_eq_cst(compiler, &masked[0], B::Field::zero(), span)?;
+
+    // add the implicit leading one to non-zero floats
+    let is_non_zero = boolean::not(compiler, &is_zero[0], span);
+    let leading_one = B::Field::from(2u64).pow([FRACTION_BITS as u64]);
+    let leading_one = field::mul(
+        compiler,
+        &is_non_zero[0],
+        &ConstOrCell::Const(leading_one),
+        span,
+    );
+    let significand = field::add(compiler, &fraction[0], &leading_one[0], span);
+
+    Ok(Unpacked {
+        sign,
+        exponent: exponent[0].clone(),
+        significand: significand[0].clone(),
+        magnitude: magnitude[0].clone(),
+        is_zero: is_zero[0].clone(),
+    })
+}
+
+/// Packs the components of a float, or returns a zero (with the given sign) if `is_zero` is set.
+/// The exponent is constrained to be the one of a normal number (unless the result is zero).
+fn pack<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    sign: &ConstOrCell<B::Field, B::Var>,
+    exponent: &ConstOrCell<B::Field, B::Var>,
+    fraction: &ConstOrCell<B::Field, B::Var>,
+    is_zero: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    // 1 <= exponent < SPECIAL_EXPONENT
+    let one = ConstOrCell::Const(B::Field::one());
+    let checked = select(compiler, is_zero, &one, exponent, span);
+    let shifted = field::sub(compiler, &checked, &one, span);
+    let max = B::Field::from(SPECIAL_EXPONENT - 1);
+
+    match &shifted[0] {
+        ConstOrCell::Const(cst) => {
+            let cst: BigUint = (*cst).into();
+            let max: BigUint = max.into();
+            if cst >= max {
+                return Err(compiler.error(ErrorKind::UnsupportedFloat, span));
+            }
+        }
+        shifted => {
+            field::range_check(compiler, shifted, EXPONENT_BITS + 1, span)?;
+            let is_lt = field::less_than(
+                compiler,
+                EXPONENT_BITS + 1,
+                shifted,
+                &ConstOrCell::Const(max),
+                span,
+            )?;
+            assert_eq_cst(compiler, &is_lt[0], B::Field::one(), span)?;
+        }
+    }
+
+    // sign * 2^31 + exponent * 2^23 + fraction
+    let two = B::Field::from(2u64);
+    let sign = field::mul(
+        compiler,
+        sign,
+        &ConstOrCell::Const(two.pow([FLOAT_BITS as u64 - 1])),
+        span,
+    );
+    let exponent = field::mul(
+        compiler,
+        exponent,
+        &ConstOrCell::Const(two.pow([FRACTION_BITS as u64])),
+        span,
+    );
+    let packed = field::add(compiler, &sign[0], &exponent[0], span);
+    let packed = field::add(compiler, &packed[0], fraction, span);
+
+    let res = select(compiler, is_zero, &sign[0], &packed[0], span);
+    Ok(Var::new_cvar(res, span))
+}
+
+/// Rounds a truncated significand (with its leading one set) to the nearest value, ties to even,
+/// given its least significant bit, the first bit dropped (`guard`), and the value of the other bits dropped (`rest`).
+/// Returns the rounded fraction (without its leading one),
+/// and a bit set if rounding overflowed to the next power of two (in which case the exponent must be incremented).
+fn round<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    truncated: &ConstOrCell<B::Field, B::Var>,
+    lsb: &ConstOrCell<B::Field, B::Var>,
+    guard: &ConstOrCell<B::Field, B::Var>,
+    rest: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> (ConstOrCell<B::Field, B::Var>, ConstOrCell<B::Field, B::Var>) {
+    // round up if above the halfway point, or exactly on it with an odd significand
+    let sticky = is_non_zero(compiler, rest, span);
+    let tie_breaker = boolean::or(compiler, &sticky, lsb, span);
+    let round_up = boolean::and(compiler, guard, &tie_breaker[0], span);
+    let rounded = field::add(compiler, truncated, &round_up[0], span);
+
+    // the significand can overflow to 2^SIGNIFICAND_BITS, whose fraction is zero
+    let two = B::Field::from(2u64);
+    let overflow = ConstOrCell::Const(two.pow([SIGNIFICAND_BITS as u64]));
+    let carry = field::equal_cells(compiler, &rounded[0], &overflow, span);
+
+    let leading_one = ConstOrCell::Const(two.pow([FRACTION_BITS as u64]));
+    let fraction = field::sub(compiler, &rounded[0], &leading_one, span);
+    let no_carry = boolean::not(compiler, &carry[0], span);
+    let fraction = field::mul(compiler, &fraction[0], &no_carry[0], span);
+
+    (fraction[0].clone(), carry[0].clone())
+}
+
+/// Returns `(then_, else_)` if `cond` is set, `(else_, then_)` otherwise.
+fn order<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cond: &ConstOrCell<B::Field, B::Var>,
+    then_: &ConstOrCell<B::Field, B::Var>,
+    else_: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> (ConstOrCell<B::Field, B::Var>, ConstOrCell<B::Field, B::Var>) {
+    let first = select(compiler, cond, then_, else_, span);
+
+    // the second one is the other one: then_ + else_ - first
+    let sum = field::add(compiler, then_, else_, span);
+    let second = field::sub(compiler, &sum[0], &first, span);
+
+    (first, second[0].clone())
+}
+
+fn select<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    cond: &ConstOrCell<B::Field, B::Var>,
+    then_: &ConstOrCell<B::Field, B::Var>,
+    else_: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    field::if_else_inner(compiler, cond, then_, else_, span)[0].clone()
+}
+
+/// Returns 1 if the value is not zero, 0 otherwise.
+fn is_non_zero<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    val: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let is_zero = field::equal_cells(compiler, val, &zero, span);
+    boolean::not(compiler, &is_zero[0], span)[0].clone()
+}
+
+/// Computes `2^x` from the bits of `x` (in little-endian order).
+fn pow2<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    bits: &[ConstOrCell<B::Field, B::Var>],
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    let one = ConstOrCell::Const(B::Field::one());
+    let mut acc = one.clone();
+
+    for (ii, bit) in bits.iter().enumerate() {
+        // acc *= 1 + bit * (2^(2^ii) - 1)
+        let power = B::Field::from(2u64).pow([1 << ii]);
+        let factor = field::mul(
+            compiler,
+            bit,
+            &ConstOrCell::Const(power - B::Field::one()),
+            span,
+        );
+        let factor = field::add(compiler, &factor[0], &one, span);
+        acc = field::mul(compiler, &acc, &factor[0], span)[0].clone();
+    }
+
+    acc
+}
+
+/// Computes the quotient and the remainder of `significand * 2^ALIGN_BITS / 2^shift` out of circuit.
+fn align_hints<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    significand: &ConstOrCell<B::Field, B::Var>,
+    shift: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> (ConstOrCell<B::Field, B::Var>, ConstOrCell<B::Field, B::Var>) {
+    let inputs = vec![significand.clone(), shift.clone()];
+
+    let mut res = vec![];
+    for is_remainder in [false, true] {
+        let var = compiler.backend.new_internal_var(
+            Value::Compute(
+                inputs.clone(),
+                Arc::new(move |values: &[B::Field]| {
+                    let significand: BigUint = values[0].into();
+                    let shift: BigUint = values[1].into();
+                    let shift = usize::try_from(shift).unwrap_or(0).min(ALIGN_BITS);
+
+                    let scaled = significand << ALIGN_BITS;
+                    let res = if is_remainder {
+                        scaled & ((BigUint::one() << shift) - 1u8)
+                    } else {
+                        scaled >> shift
+                    };
+                    B::Field::try_from(res).unwrap_or_else(|_| unreachable!())
+                }),
+            ),
+            span,
+        );
+        res.push(ConstOrCell::Cell(var));
+    }
+
+    let remainder = res.pop().unwrap();
+    let quotient = res.pop().unwrap();
+    (quotient, remainder)
+}
+
+/// Computes the number of leading zeros of a value of [SUM_BITS] bits out of circuit.
+fn leading_zeros_hint<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    val: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    let var = compiler.backend.new_internal_var(
+        Value::Compute(
+            vec![val.clone()],
+            Arc::new(|values: &[B::Field]| {
+                let val: BigUint = values[0].into();
+                let leading_zeros = SUM_BITS.saturating_sub(val.bits() as usize);
+                // zero is special-cased by the caller
+                let leading_zeros = if val.is_zero() { 0 } else { leading_zeros };
+                B::Field::from(leading_zeros as u64)
+            }),
+        ),
+        span,
+    );
+    ConstOrCell::Cell(var)
+}
+
+/// Constrains a value to be equal to a constant.
+fn assert_eq_cst<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    var: &ConstOrCell<B::Field, B::Var>,
+    cst: B::Field,
+    span: Span,
+) -> Result<()> {
+    match var {
+        ConstOrCell::Const(val) => {
+            if *val != cst {
+                return Err(compiler.error(ErrorKind::UnsupportedFloat, span));
+            }
+        }
+        ConstOrCell::Cell(cvar) => compiler.backend.assert_eq_const(cvar, cst, span),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+//! Extraction of values from JSON documents.
+
+use num_bigint::BigUint;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::json;
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::Var;
+
+const JSON_EXTRACT_FN: &str =
+    "json_extract(input: [U8; Len], path: [U8; PathLen], const OutLen: Field) -> [U8; OutLen]";
+
+pub const JSON_SIGS: &[&str] = &[JSON_EXTRACT_FN];
+
+pub fn get_json_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        JSON_EXTRACT_FN => json_extract,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns json functions
+pub fn json_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    JSON_SIGS
+        .iter()
+        .map(|sig| get_json_fn(sig).unwrap())
+        .collect()
+}
+
+/// Returns the value at a path of keys separated by dots (usually a string literal) in a JSON document,
+/// as the bytes that represent it in the document followed by zeros.
+fn json_extract<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the document, the path, and the output length
+    assert_eq!(vars.len(), 3);
+
+    // of type array of bytes
+    for info in &vars[..2] {
+        if !matches!(&info.typ, Some(TyKind::Array(typ, _)) if typ.match_expected(&TyKind::U8)) {
+            panic!(
+                "the input and the path of json_extract must be arrays of bytes. It was of type {:?}",
+                info.typ
+            );
+        }
+    }
+
+    // the path is known at compile time
+    let path = vars[1]
+        .var
+        .cvars
+        .iter()
+        .map(|byte| {
+            let byte: BigUint = byte
+                .cst()
+                .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?
+                .into();
+            u8::try_from(byte).map_err(|_| compiler.error(ErrorKind::AssertionFailed, span))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let path = json::parse_path(&path)
+        .map_err(|reason| compiler.error(ErrorKind::InvalidJsonPath(reason), span))?;
+
+    let out_len = vars[2]
+        .var
+        .constant()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?;
+    let out_len: BigUint = out_len.into();
+    let out_len: usize = out_len
+        .try_into()
+        .map_err(|_| compiler.error(ErrorKind::InvalidArraySize, span))?;
+
+    // constants are not range-checked yet
+    let input = &vars[0].var.cvars;
+    for byte in input {
+        if let Some(cst) = byte.cst() {
+            let cst: BigUint = cst.into();
+            if cst.bits() > 8 {
+                return Err(compiler.error(ErrorKind::AssertionFailed, span));
+            }
+        }
+    }
+
+    Ok(Some(json::extract(compiler, input, &path, out_len, span)?))
+}
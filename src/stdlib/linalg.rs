@@ -0,0 +1,203 @@
+//! Linear algebra over fixed-point numbers (see [super::fixed]), for the layers of neural networks.
+//!
+//! Vectors are arrays of fixed-point numbers, and matrices are arrays of rows.
+//! The products of a dot product are accumulated exactly (with `2 * frac_bits` fractional bits),
+//! and only the sum is rounded: this costs a single rounding (and range check) per output
+//! instead of one per product, and is more precise than rounding each product.
+
+use ark_ff::{Field, Zero};
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::field;
+use crate::error::Result;
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Var};
+
+use super::fixed::{self, FIXED_BITS};
+
+const DOT_FN: &str = "dot(lhs: [Field; Len], rhs: [Field; Len], const frac_bits: Field) -> Field";
+const MATMUL_FN: &str = "matmul(lhs: [[Field; Inner]; Rows], rhs: [[Field; Cols]; Inner], const frac_bits: Field) -> [[Field; Cols]; Rows]";
+const AFFINE_FN: &str = "affine(weights: [[Field; In]; Out], input: [Field; In], bias: [Field; Out], const frac_bits: Field) -> [Field; Out]";
+
+pub const LINALG_SIGS: &[&str] = &[DOT_FN, MATMUL_FN, AFFINE_FN];
+
+pub fn get_linalg_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        DOT_FN => dot,
+        MATMUL_FN => matmul,
+        AFFINE_FN => affine,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns linear algebra functions
+pub fn linalg_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    LINALG_SIGS
+        .iter()
+        .map(|sig| get_linalg_fn(sig).unwrap())
+        .collect()
+}
+
+/// Returns the dot product of two vectors.
+fn dot<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vectors, and the number of fractional bits
+    assert_eq!(vars.len(), 3);
+    let lhs = fixed_vector(compiler, &vars[0], span)?;
+    let rhs = fixed_vector(compiler, &vars[1], span)?;
+    let frac_bits = fixed::frac_bits(compiler, &vars[2], span)?;
+
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let res = accumulate(compiler, &lhs, &rhs, &zero, frac_bits, span)?;
+
+    Ok(Some(Var::new_cvar(res, span)))
+}
+
+/// Returns the product of two matrices.
+fn matmul<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two matrices, and the number of fractional bits
+    assert_eq!(vars.len(), 3);
+    let (lhs, _) = fixed_matrix(compiler, &vars[0], span)?;
+    let (rhs, cols) = fixed_matrix(compiler, &vars[1], span)?;
+    let frac_bits = fixed::frac_bits(compiler, &vars[2], span)?;
+
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let columns: Vec<Vec<_>> = (0..cols)
+        .map(|col| rhs.iter().map(|row| row[col].clone()).collect())
+        .collect();
+
+    let mut res = Vec::with_capacity(lhs.len() * cols);
+    for row in &lhs {
+        for column in &columns {
+            res.push(accumulate(compiler, row, column, &zero, frac_bits, span)?);
+        }
+    }
+
+    Ok(Some(Var::new(res, span)))
+}
+
+/// Returns `weights * input + bias`, the output of a dense layer (before its activation).
+fn affine<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a matrix, two vectors, and the number of fractional bits
+    assert_eq!(vars.len(), 4);
+    let (weights, _) = fixed_matrix(compiler, &vars[0], span)?;
+    let input = fixed_vector(compiler, &vars[1], span)?;
+    let bias = fixed_vector(compiler, &vars[2], span)?;
+    let frac_bits = fixed::frac_bits(compiler, &vars[3], span)?;
+
+    // the bias is added to the exact sum, so it is scaled to `2 * frac_bits` fractional bits
+    let scale = ConstOrCell::Const(B::Field::from(2u64).pow([frac_bits as u64]));
+
+    let mut res = Vec::with_capacity(weights.len());
+    for (row, bias) in weights.iter().zip(&bias) {
+        let bias = field::mul(compiler, bias, &scale, span);
+        res.push(accumulate(
+            compiler, row, &input, &bias[0], frac_bits, span,
+        )?);
+    }
+
+    Ok(Some(Var::new(res, span)))
+}
+
+/// Returns `init + sum(lhs[i] * rhs[i])`, rounded to `frac_bits` fractional bits,
+/// where `init` has `2 * frac_bits` fractional bits and fits in `2 * FIXED_BITS - 1` signed bits.
+fn accumulate<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    lhs: &[ConstOrCell<B::Field, B::Var>],
+    rhs: &[ConstOrCell<B::Field, B::Var>],
+    init: &ConstOrCell<B::Field, B::Var>,
+    frac_bits: usize,
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    assert_eq!(lhs.len(), rhs.len());
+
+    let mut acc = init.clone();
+    for (lhs, rhs) in lhs.iter().zip(rhs) {
+        let prod = field::mul(compiler, lhs, rhs, span);
+        acc = field::add(compiler, &acc, &prod[0], span)[0].clone();
+    }
+
+    // each product (and the initial value) fits in `2 * FIXED_BITS - 1` signed bits,
+    // so the sum of `len + 1` of them fits in `log2(len + 1)` more bits
+    let extra_bits = (usize::BITS - (lhs.len() + 1).leading_zeros()) as usize;
+    fixed::rescale(compiler, &acc, frac_bits, 2 * FIXED_BITS + extra_bits, span)
+}
+
+/// Returns a vector of fixed-point numbers, which are constrained to fit in [FIXED_BITS] signed bits.
+fn fixed_vector<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    var_info: &VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<Vec<ConstOrCell<B::Field, B::Var>>> {
+    if !matches!(&var_info.typ, Some(TyKind::Array(typ, _)) if typ.is_numeric()) {
+        panic!(
+            "a vector must be an array of fixed-point numbers. It was of type {:?}",
+            var_info.typ
+        );
+    }
+
+    for val in &var_info.var.cvars {
+        fixed::constrain_fixed(compiler, val, span)?;
+    }
+
+    Ok(var_info.var.cvars.clone())
+}
+
+/// Returns the rows of a matrix of fixed-point numbers (which are constrained to fit in [FIXED_BITS] signed bits),
+/// and its number of columns.
+fn fixed_matrix<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    var_info: &VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<(Vec<Vec<ConstOrCell<B::Field, B::Var>>>, usize)> {
+    let (rows, cols) = match &var_info.typ {
+        Some(TyKind::Array(row, rows)) => match row.as_ref() {
+            TyKind::Array(typ, cols) if typ.is_numeric() => (*rows as usize, *cols as usize),
+            _ => panic!(
+                "a matrix must be an array of arrays of fixed-point numbers. It was of type {:?}",
+                var_info.typ
+            ),
+        },
+        _ => panic!(
+            "a matrix must be an array of arrays of fixed-point numbers. It was of type {:?}",
+            var_info.typ
+        ),
+    };
+
+    let vals = &var_info.var.cvars;
+    for val in vals {
+        fixed::constrain_fixed(compiler, val, span)?;
+    }
+
+    let rows = (0..rows)
+        .map(|ii| vals[ii * cols..(ii + 1) * cols].to_vec())
+        .collect();
+
+    Ok((rows, cols))
+}
@@ -0,0 +1,123 @@
+use ark_ff::Zero;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::mem;
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Var};
+
+const READ_FN: &str = "read(memory: [Field; Len], indices: [Field; Num]) -> [Field; Num]";
+const ACCESS_FN: &str = "access(memory: [Field; Len], indices: [Field; Num], values: [Field; Num], is_write: [Bool; Num]) -> [Field; Num]";
+
+pub const MEM_SIGS: &[&str] = &[READ_FN, ACCESS_FN];
+
+pub fn get_mem_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        READ_FN => read,
+        ACCESS_FN => access,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns memory functions
+pub fn mem_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    MEM_SIGS
+        .iter()
+        .map(|sig| get_mem_fn(sig).unwrap())
+        .collect()
+}
+
+/// Reads the memory at each of the indices.
+fn read<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+
+    let memory = memory(compiler, &vars[0], span)?;
+    let indices = numeric_array(&vars[1]);
+
+    // reads ignore the values
+    let zeros = vec![ConstOrCell::Const(B::Field::zero()); indices.len()];
+
+    let res = mem::access(compiler, memory, indices, &zeros, &zeros, span)?;
+
+    Ok(Some(res))
+}
+
+/// Performs a list of reads and writes to the memory,
+/// and returns the value read or written by each access.
+fn access<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get four vars
+    assert_eq!(vars.len(), 4);
+
+    let memory = memory(compiler, &vars[0], span)?;
+    let indices = numeric_array(&vars[1]);
+    let values = numeric_array(&vars[2]);
+
+    // of type [Bool; _]
+    match &vars[3].typ {
+        Some(TyKind::Array(typ, _)) if matches!(**typ, TyKind::Bool) => (),
+        typ => panic!(
+            "write flags must be of type [Bool; _]. It was of type {:?}",
+            typ
+        ),
+    }
+    let is_write = &vars[3].var.cvars;
+
+    let res = mem::access(compiler, memory, indices, values, is_write, span)?;
+
+    Ok(Some(res))
+}
+
+/// Returns the slots of a memory, which must not be empty.
+fn memory<'a, B: Backend>(
+    compiler: &CircuitWriter<B>,
+    var_info: &'a VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<&'a [ConstOrCell<B::Field, B::Var>]> {
+    let memory = numeric_array(var_info);
+
+    if memory.is_empty() {
+        return Err(compiler.error(ErrorKind::EmptyArray, span));
+    }
+
+    Ok(memory)
+}
+
+/// Returns the elements of an array of numbers.
+fn numeric_array<B: Backend>(
+    var_info: &VarInfo<B::Field, B::Var>,
+) -> &[ConstOrCell<B::Field, B::Var>] {
+    // of type [Field; _]
+    match &var_info.typ {
+        Some(TyKind::Array(typ, _)) if typ.is_numeric() => (),
+        typ => panic!(
+            "expected an array of type [Field; _]. It was of type {:?}",
+            typ
+        ),
+    }
+
+    &var_info.var.cvars
+}
@@ -0,0 +1,243 @@
+//! Merkle inclusion proofs, and sparse Merkle trees.
+//!
+//! Paths are given from the bottom of the tree, along with the bits of the index of the leaf
+//! (in little-endian order, so that a set bit means that the node is a right child).
+//! By default nodes are compressed with the Poseidon of the backend,
+//! and the `_poseidon2` variants compress them with Poseidon2 instead (see [merkle]).
+//!
+//! In a sparse Merkle tree, the leaf of a key is at the index given by the bits of the key,
+//! and contains its value, or zero if the key is absent.
+//! This allows to prove non-membership (the leaf is zero),
+//! as well as insertions and updates (the leaf changes, and the same path leads to the new root).
+//!
+//! Incremental Merkle trees (as in Tornado Cash) are filled from left to right,
+//! and only need the roots of the filled subtrees on the left of the next leaf to append a leaf.
+//! They are compressed with Poseidon2, so that the empty subtrees are computed at compile time.
+
+use ark_ff::Zero;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::{
+    field,
+    merkle::{self, Compress},
+};
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Var};
+
+const VERIFY_PATH_FN: &str =
+    "verify_path(root: Field, leaf: Field, path: [Field; Depth], index_bits: [Bool; Depth])";
+const VERIFY_PATH_POSEIDON2_FN: &str = "verify_path_poseidon2(root: Field, leaf: Field, path: [Field; Depth], index_bits: [Bool; Depth])";
+const SMT_VERIFY_FN: &str =
+    "smt_verify(root: Field, key_bits: [Bool; Depth], value: Field, path: [Field; Depth])";
+const SMT_VERIFY_ABSENT_FN: &str =
+    "smt_verify_absent(root: Field, key_bits: [Bool; Depth], path: [Field; Depth])";
+const SMT_UPDATE_FN: &str = "smt_update(root: Field, key_bits: [Bool; Depth], old_value: Field, new_value: Field, path: [Field; Depth]) -> Field";
+const IMT_APPEND_FN: &str = "imt_append(root: Field, index_bits: [Bool; Depth], leaf: Field, filled_subtrees: [Field; Depth]) -> Field";
+
+pub const MERKLE_SIGS: &[&str] = &[
+    VERIFY_PATH_FN,
+    VERIFY_PATH_POSEIDON2_FN,
+    SMT_VERIFY_FN,
+    SMT_VERIFY_ABSENT_FN,
+    SMT_UPDATE_FN,
+    IMT_APPEND_FN,
+];
+
+pub fn get_merkle_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        VERIFY_PATH_FN => verify_path,
+        VERIFY_PATH_POSEIDON2_FN => verify_path_poseidon2,
+        SMT_VERIFY_FN => smt_verify,
+        SMT_VERIFY_ABSENT_FN => smt_verify_absent,
+        SMT_UPDATE_FN => smt_update,
+        IMT_APPEND_FN => imt_append,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns merkle functions
+pub fn merkle_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    MERKLE_SIGS
+        .iter()
+        .map(|sig| get_merkle_fn(sig).unwrap())
+        .collect()
+}
+
+/// Asserts that a leaf is in the tree of the given root, with the Poseidon of the backend.
+fn verify_path<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    verify_path_with(compiler, vars, merkle::poseidon, span)
+}
+
+/// Asserts that a leaf is in the tree of the given root, with Poseidon2.
+fn verify_path_poseidon2<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    verify_path_with(compiler, vars, merkle::poseidon2, span)
+}
+
+fn verify_path_with<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    compress: Compress<B>,
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the root, the leaf, the path, and the index bits
+    assert_eq!(vars.len(), 4);
+    let expected_root = &vars[0].var[0];
+    let leaf = &vars[1].var[0];
+
+    let path = path_cells(&vars[2]);
+    let index_bits = index_cells(&vars[3]);
+
+    let root = merkle::root(compiler, compress, leaf, path, index_bits, span)?;
+    field::assert_eq_cells(compiler, &root, expected_root, span)?;
+
+    Ok(None)
+}
+
+/// Asserts that a key is in the sparse Merkle tree of the given root, with a (non-zero) value.
+fn smt_verify<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the root, the key bits, the value, and the path
+    assert_eq!(vars.len(), 4);
+    let expected_root = &vars[0].var[0];
+    let key_bits = index_cells(&vars[1]);
+    let value = &vars[2].var[0];
+    let path = path_cells(&vars[3]);
+
+    // a zero value would mean that the key is absent
+    if matches!(value.cst(), Some(cst) if cst.is_zero()) {
+        return Err(compiler.error(ErrorKind::AssertionFailed, span));
+    }
+    field::inv(compiler, value, span);
+
+    let root = merkle::root(compiler, merkle::poseidon, value, path, key_bits, span)?;
+    field::assert_eq_cells(compiler, &root, expected_root, span)?;
+
+    Ok(None)
+}
+
+/// Asserts that a key is not in the sparse Merkle tree of the given root (its leaf is zero).
+fn smt_verify_absent<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the root, the key bits, and the path
+    assert_eq!(vars.len(), 3);
+    let expected_root = &vars[0].var[0];
+    let key_bits = index_cells(&vars[1]);
+    let path = path_cells(&vars[2]);
+
+    let empty = ConstOrCell::Const(B::Field::zero());
+    let root = merkle::root(compiler, merkle::poseidon, &empty, path, key_bits, span)?;
+    field::assert_eq_cells(compiler, &root, expected_root, span)?;
+
+    Ok(None)
+}
+
+/// Asserts that a key has the old value in the sparse Merkle tree of the given root,
+/// and returns the root of the tree where the key has the new value.
+/// An old value of zero proves an insertion, and a new value of zero proves a removal.
+fn smt_update<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the root, the key bits, the old and new values, and the path
+    assert_eq!(vars.len(), 5);
+    let expected_root = &vars[0].var[0];
+    let key_bits = index_cells(&vars[1]);
+    let old_value = &vars[2].var[0];
+    let new_value = &vars[3].var[0];
+    let path = path_cells(&vars[4]);
+
+    // the old leaf is in the tree
+    let old_root = merkle::root(compiler, merkle::poseidon, old_value, path, key_bits, span)?;
+    field::assert_eq_cells(compiler, &old_root, expected_root, span)?;
+
+    // and the same path leads to the new root
+    let new_root = merkle::root(compiler, merkle::poseidon, new_value, path, key_bits, span)?;
+
+    Ok(Some(Var::new_cvar(new_root, span)))
+}
+
+/// Asserts that the incremental Merkle tree of the given root has its next leaf at the given index,
+/// and returns the root of the tree after appending a leaf there.
+fn imt_append<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the root, the index bits, the leaf, and the filled subtrees
+    assert_eq!(vars.len(), 4);
+    let expected_root = &vars[0].var[0];
+    let index_bits = index_cells(&vars[1]);
+    let leaf = &vars[2].var[0];
+    let filled_subtrees = path_cells(&vars[3]);
+
+    let [old_root, new_root] = merkle::append(
+        compiler,
+        merkle::poseidon2,
+        leaf,
+        index_bits,
+        filled_subtrees,
+        span,
+    )?;
+    field::assert_eq_cells(compiler, &old_root, expected_root, span)?;
+
+    Ok(Some(Var::new_cvar(new_root, span)))
+}
+
+/// Returns the cells of the path of a Merkle proof.
+fn path_cells<B: Backend>(
+    path_info: &VarInfo<B::Field, B::Var>,
+) -> &[ConstOrCell<B::Field, B::Var>] {
+    if !matches!(&path_info.typ, Some(TyKind::Array(typ, _)) if typ.is_numeric()) {
+        panic!(
+            "the path of a Merkle proof must be an array of field elements. It was of type {:?}",
+            path_info.typ
+        );
+    }
+
+    &path_info.var.cvars
+}
+
+/// Returns the cells of the index (or key) bits of a Merkle proof.
+fn index_cells<B: Backend>(
+    index_info: &VarInfo<B::Field, B::Var>,
+) -> &[ConstOrCell<B::Field, B::Var>] {
+    if !matches!(&index_info.typ, Some(TyKind::Array(typ, _)) if **typ == TyKind::Bool) {
+        panic!(
+            "the index of a Merkle proof must be an array of booleans. It was of type {:?}",
+            index_info.typ
+        );
+    }
+
+    &index_info.var.cvars
+}
@@ -0,0 +1,132 @@
+//! Activation functions for the inference of quantized neural networks.
+//!
+//! The values are fixed-point numbers (see [super::fixed]), or quantized integers
+//! (which are fixed-point numbers without fractional bits):
+//! none of these functions depend on the number of fractional bits.
+
+use ark_ff::Zero;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::field;
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Var};
+
+use super::fixed::{self, FIXED_BITS};
+
+const RELU_FN: &str = "relu(val: Field) -> Field";
+const CLIPPED_RELU_FN: &str = "clipped_relu(val: Field, ceiling: Field) -> Field";
+const ARGMAX_FN: &str = "argmax(vals: [Field; Len]) -> Field";
+
+pub const ML_SIGS: &[&str] = &[RELU_FN, CLIPPED_RELU_FN, ARGMAX_FN];
+
+pub fn get_ml_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        RELU_FN => relu,
+        CLIPPED_RELU_FN => clipped_relu,
+        ARGMAX_FN => argmax,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns machine learning functions
+pub fn ml_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    ML_SIGS.iter().map(|sig| get_ml_fn(sig).unwrap()).collect()
+}
+
+/// Returns `max(val, 0)`.
+fn relu<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+    let val = fixed::fixed_arg(compiler, &vars[0], span)?;
+
+    let res = relu_inner(compiler, val, span)?;
+    Ok(Some(Var::new_cvar(res, span)))
+}
+
+/// Returns `min(max(val, 0), ceiling)`, like ReLU6 for a ceiling of 6.
+fn clipped_relu<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the value and the ceiling
+    assert_eq!(vars.len(), 2);
+    let val = fixed::fixed_arg(compiler, &vars[0], span)?;
+    let ceiling = fixed::fixed_arg(compiler, &vars[1], span)?;
+
+    let res = relu_inner(compiler, val, span)?;
+    let is_above = field::less_than_signed(compiler, FIXED_BITS, ceiling, &res, span)?;
+    let res = field::if_else_inner(compiler, &is_above[0], ceiling, &res, span);
+
+    Ok(Some(res))
+}
+
+/// Returns the index of the largest value (the first one, if several values are the largest).
+fn argmax<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type array of fixed-point numbers
+    let vals_info = &vars[0];
+    if !matches!(&vals_info.typ, Some(TyKind::Array(typ, _)) if typ.is_numeric()) {
+        panic!(
+            "the values of argmax must be an array of fixed-point numbers. It was of type {:?}",
+            vals_info.typ
+        );
+    }
+
+    let vals = &vals_info.var.cvars;
+    if vals.is_empty() {
+        return Err(compiler.error(ErrorKind::InvalidArraySize, span));
+    }
+    for val in vals {
+        fixed::constrain_fixed(compiler, val, span)?;
+    }
+
+    // a single comparison per value: only a strictly larger value replaces the current maximum
+    let mut max = vals[0].clone();
+    let mut index = ConstOrCell::Const(B::Field::zero());
+    for (ii, val) in vals.iter().enumerate().skip(1) {
+        let is_larger = field::less_than_signed(compiler, FIXED_BITS, &max, val, span)?;
+        let position = ConstOrCell::Const(B::Field::from(ii as u64));
+        max = field::if_else_inner(compiler, &is_larger[0], val, &max, span)[0].clone();
+        index = field::if_else_inner(compiler, &is_larger[0], &position, &index, span)[0].clone();
+    }
+
+    Ok(Some(Var::new_cvar(index, span)))
+}
+
+/// Returns `max(val, 0)` for a value that fits in [FIXED_BITS] signed bits.
+fn relu_inner<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    val: &ConstOrCell<B::Field, B::Var>,
+    span: Span,
+) -> Result<ConstOrCell<B::Field, B::Var>> {
+    let zero = ConstOrCell::Const(B::Field::zero());
+    let is_negative = field::less_than_signed(compiler, FIXED_BITS, val, &zero, span)?;
+    Ok(field::if_else_inner(compiler, &is_negative[0], &zero, val, span)[0].clone())
+}
@@ -1,12 +1,14 @@
 use std::collections::HashSet;
 
-use ark_ff::One;
+use ark_ff::{One, SquareRootField, Zero};
+use num_bigint::BigUint;
 use once_cell::sync::Lazy;
 
 use crate::{
     backends::Backend,
     circuit_writer::{CircuitWriter, VarInfo},
     constants::Span,
+    constraints::{boolean, field, sort},
     error::{Error, ErrorKind, Result},
     imports::FnKind,
     lexer::Token,
@@ -18,7 +20,24 @@ use crate::{
     var::{ConstOrCell, Var},
 };
 
+pub mod bigint;
+pub mod bits;
+pub mod bytes;
 pub mod crypto;
+pub mod encoding;
+pub mod fixed;
+pub mod float;
+pub mod json;
+pub mod linalg;
+pub mod mem;
+pub mod merkle;
+pub mod ml;
+pub mod nullifier;
+pub mod regex;
+pub mod set;
+pub mod string;
+pub mod table;
+pub mod transcript;
 
 //
 // Builtins or utils (imported by default)
@@ -29,9 +48,52 @@ pub const QUALIFIED_BUILTINS: &str = "std/builtins";
 
 const ASSERT_FN: &str = "assert(condition: Bool)";
 const ASSERT_EQ_FN: &str = "assert_eq(lhs: Field, rhs: Field)";
+const ASSERT_NOT_EQ_FN: &str = "assert_not_eq(lhs: Field, rhs: Field)";
+const ASSERT_IF_FN: &str = "assert_if(condition: Bool, lhs: Field, rhs: Field)";
+const RANGE_CHECK_FN: &str = "range_check(val: Field, const num_bits: Field)";
+const INV_FN: &str = "inv(val: Field) -> Field";
+const POW_FN: &str = "pow(base: Field, const exp: Field) -> Field";
+const TO_U8_FN: &str = "to_u8(val: Field) -> U8";
+const TO_U16_FN: &str = "to_u16(val: Field) -> U16";
+const TO_U32_FN: &str = "to_u32(val: Field) -> U32";
+const TO_U64_FN: &str = "to_u64(val: Field) -> U64";
+const TO_I64_FN: &str = "to_i64(val: Field) -> I64";
+const MIN_FN: &str = "min(lhs: Field, rhs: Field) -> Field";
+const MAX_FN: &str = "max(lhs: Field, rhs: Field) -> Field";
+const ABS_FN: &str = "abs(val: Field) -> Field";
+const SQRT_FN: &str = "sqrt(val: Field) -> Field";
+const TRY_SQRT_FN: &str = "try_sqrt(val: Field) -> [Field; 2]";
+const IS_SQUARE_FN: &str = "is_square(val: Field) -> Bool";
+const SORT_FN: &str = "sort(values: [Field; Len]) -> [Field; Len]";
+const ARGSORT_FN: &str = "argsort(values: [Field; Len]) -> [Field; Len]";
+const ALL_FN: &str = "all(values: [Bool; Len]) -> Bool";
+const ANY_FN: &str = "any(values: [Bool; Len]) -> Bool";
 
 /// List of builtin function signatures.
-pub const BUILTIN_SIGS: &[&str] = &[ASSERT_FN, ASSERT_EQ_FN];
+pub const BUILTIN_SIGS: &[&str] = &[
+    ASSERT_FN,
+    ASSERT_EQ_FN,
+    ASSERT_NOT_EQ_FN,
+    ASSERT_IF_FN,
+    RANGE_CHECK_FN,
+    INV_FN,
+    POW_FN,
+    TO_U8_FN,
+    TO_U16_FN,
+    TO_U32_FN,
+    TO_U64_FN,
+    TO_I64_FN,
+    MIN_FN,
+    MAX_FN,
+    ABS_FN,
+    SQRT_FN,
+    TRY_SQRT_FN,
+    IS_SQUARE_FN,
+    SORT_FN,
+    ARGSORT_FN,
+    ALL_FN,
+    ANY_FN,
+];
 
 // Unique set of builtin function names, derived from function signatures.
 pub static BUILTIN_FN_NAMES: Lazy<HashSet<String>> = Lazy::new(|| {
@@ -54,6 +116,26 @@ pub fn get_builtin_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
     let fn_handle = match name {
         ASSERT_FN => assert,
         ASSERT_EQ_FN => assert_eq,
+        ASSERT_NOT_EQ_FN => assert_not_eq,
+        ASSERT_IF_FN => assert_if,
+        RANGE_CHECK_FN => range_check,
+        INV_FN => inv,
+        POW_FN => pow,
+        TO_U8_FN => to_u8,
+        TO_U16_FN => to_u16,
+        TO_U32_FN => to_u32,
+        TO_U64_FN => to_u64,
+        TO_I64_FN => to_i64,
+        MIN_FN => min,
+        MAX_FN => max,
+        ABS_FN => abs,
+        SQRT_FN => sqrt,
+        TRY_SQRT_FN => try_sqrt,
+        IS_SQUARE_FN => is_square,
+        SORT_FN => sort,
+        ARGSORT_FN => argsort,
+        ALL_FN => all,
+        ANY_FN => any,
         _ => return None,
     };
 
@@ -83,20 +165,63 @@ fn assert_eq<B: Backend>(
     let rhs_info = &vars[1];
 
     // they are both of type field
-    if !matches!(lhs_info.typ, Some(TyKind::Field | TyKind::BigInt)) {
+    if !matches!(&lhs_info.typ, Some(typ) if typ.is_numeric()) {
         panic!(
             "the lhs of assert_eq must be of type Field or BigInt. It was of type {:?}",
             lhs_info.typ
         );
     }
 
-    if !matches!(rhs_info.typ, Some(TyKind::Field | TyKind::BigInt)) {
+    if !matches!(&rhs_info.typ, Some(typ) if typ.is_numeric()) {
         panic!(
             "the rhs of assert_eq must be of type Field or BigInt. It was of type {:?}",
             rhs_info.typ
         );
     }
 
+    // retrieve the values
+    let lhs_var = &lhs_info.var;
+    let rhs_var = &rhs_info.var;
+    assert_eq!(lhs_var.len(), rhs_var.len());
+
+    // the equalities between variables are asserted together (which is free on backends that wire them)
+    let pairs: Vec<_> = lhs_var
+        .iter()
+        .cloned()
+        .zip(rhs_var.iter().cloned())
+        .collect();
+    field::assert_eq_all(compiler, &pairs, span)?;
+
+    Ok(None)
+}
+
+/// Asserts that two field elements are different,
+/// by constraining their difference to have an inverse.
+fn assert_not_eq<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+    let lhs_info = &vars[0];
+    let rhs_info = &vars[1];
+
+    // they are both of type field
+    if !matches!(&lhs_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the lhs of assert_not_eq must be of type Field or BigInt. It was of type {:?}",
+            lhs_info.typ
+        );
+    }
+
+    if !matches!(&rhs_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the rhs of assert_not_eq must be of type Field or BigInt. It was of type {:?}",
+            rhs_info.typ
+        );
+    }
+
     // retrieve the values
     let lhs_var = &lhs_info.var;
     assert_eq!(lhs_var.len(), 1);
@@ -106,26 +231,84 @@ fn assert_eq<B: Backend>(
     assert_eq!(rhs_var.len(), 1);
     let rhs_cvar = &rhs_var[0];
 
-    match (lhs_cvar, rhs_cvar) {
+    let diff = field::sub(compiler, lhs_cvar, rhs_cvar, span);
+
+    match &diff[0] {
         // two constants
-        (ConstOrCell::Const(a), ConstOrCell::Const(b)) => {
-            if a != b {
-                return Err(Error::new(
-                    "constraint-generation",
-                    ErrorKind::AssertionFailed,
-                    span,
-                ));
+        ConstOrCell::Const(cst) => {
+            if cst.is_zero() {
+                return Err(compiler.error(ErrorKind::AssertionFailed, span));
             }
         }
 
-        // a const and a var
-        (ConstOrCell::Const(cst), ConstOrCell::Cell(cvar))
-        | (ConstOrCell::Cell(cvar), ConstOrCell::Const(cst)) => {
-            compiler.backend.assert_eq_const(cvar, *cst, span)
+        // the difference is non-zero if it has an inverse
+        diff => {
+            let _inverse = field::inv(compiler, diff, span);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Asserts that two field elements are equal if a condition is true,
+/// by constraining `condition * (lhs - rhs) = 0`.
+fn assert_if<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get three vars
+    assert_eq!(vars.len(), 3);
+
+    // the condition is a bool
+    let cond_info = &vars[0];
+    assert!(matches!(cond_info.typ, Some(TyKind::Bool)));
+
+    let cond_var = &cond_info.var;
+    assert_eq!(cond_var.len(), 1);
+    let cond = &cond_var[0];
+
+    // a constant condition is either a regular assertion, or nothing
+    if let ConstOrCell::Const(cst) = cond {
+        if cst.is_one() {
+            return assert_eq(compiler, &vars[1..], span);
         }
-        (ConstOrCell::Cell(lhs), ConstOrCell::Cell(rhs)) => {
-            compiler.backend.assert_eq_var(lhs, rhs, span)
+        return Ok(None);
+    }
+
+    // the compared values are both of type field
+    let lhs_info = &vars[1];
+    let rhs_info = &vars[2];
+
+    if !matches!(&lhs_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the lhs of assert_if must be of type Field or BigInt. It was of type {:?}",
+            lhs_info.typ
+        );
+    }
+
+    if !matches!(&rhs_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the rhs of assert_if must be of type Field or BigInt. It was of type {:?}",
+            rhs_info.typ
+        );
+    }
+
+    assert_eq!(lhs_info.var.len(), 1);
+    assert_eq!(rhs_info.var.len(), 1);
+
+    // condition * (lhs - rhs) = 0
+    let diff = field::sub(compiler, &lhs_info.var[0], &rhs_info.var[0], span);
+    let res = field::mul(compiler, cond, &diff[0], span);
+
+    match &res[0] {
+        ConstOrCell::Const(cst) => {
+            // the condition is not constant, so this can only happen if lhs = rhs
+            assert!(cst.is_zero());
         }
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(cvar, B::Field::zero(), span),
     }
 
     Ok(None)
@@ -161,3 +344,502 @@ fn assert<B: Backend>(
 
     Ok(None)
 }
+
+/// Asserts that a field element fits in a given number of bits.
+fn range_check<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+    let val_info = &vars[0];
+    let num_bits_info = &vars[1];
+
+    // the value is a single field element
+    if !matches!(&val_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value passed to range_check must be of type Field or BigInt. It was of type {:?}",
+            val_info.typ
+        );
+    }
+
+    let val_var = &val_info.var;
+    assert_eq!(val_var.len(), 1);
+    let val = &val_var[0];
+
+    // the number of bits must be known at compile time
+    let num_bits = num_bits_info
+        .var
+        .constant()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?;
+    let num_bits: BigUint = num_bits.into();
+    let num_bits: usize = num_bits
+        .try_into()
+        .map_err(|_| compiler.error(ErrorKind::InvalidBitLength, span))?;
+    field::check_range_bits(compiler, num_bits, span)?;
+
+    // a constant can be checked right away
+    if let ConstOrCell::Const(cst) = val {
+        let cst: BigUint = (*cst).into();
+        if cst.bits() as usize > num_bits {
+            return Err(Error::new(
+                "constraint-generation",
+                ErrorKind::AssertionFailed,
+                span,
+            ));
+        }
+
+        return Ok(None);
+    }
+
+    field::range_check(compiler, val, num_bits, span)?;
+
+    Ok(None)
+}
+
+/// Returns the inverse of a field element.
+fn inv<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type field
+    let var_info = &vars[0];
+    if !matches!(&var_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value passed to inv must be of type Field or BigInt. It was of type {:?}",
+            var_info.typ
+        );
+    }
+
+    // of only one field element
+    let var = &var_info.var;
+    assert_eq!(var.len(), 1);
+    let val = &var[0];
+
+    // zero has no inverse
+    if matches!(val, ConstOrCell::Const(cst) if cst.is_zero()) {
+        return Err(compiler.error(ErrorKind::InverseOfZero, span));
+    }
+
+    Ok(Some(field::inv(compiler, val, span)))
+}
+
+/// Raises a field element to a constant power.
+fn pow<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+    let base_info = &vars[0];
+    let exp_info = &vars[1];
+
+    // the base is a single field element
+    if !matches!(&base_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the base passed to pow must be of type Field or BigInt. It was of type {:?}",
+            base_info.typ
+        );
+    }
+
+    let base_var = &base_info.var;
+    assert_eq!(base_var.len(), 1);
+    let base = &base_var[0];
+
+    // the exponent must be known at compile time
+    let exp = exp_info
+        .var
+        .constant()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?;
+    let exp: BigUint = exp.into();
+
+    Ok(Some(field::pow(compiler, base, &exp, span)))
+}
+
+/// Converts a field element to an 8-bit unsigned integer.
+fn to_u8<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    to_uint(compiler, vars, 8, span)
+}
+
+/// Converts a field element to a 16-bit unsigned integer.
+fn to_u16<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    to_uint(compiler, vars, 16, span)
+}
+
+/// Converts a field element to a 32-bit unsigned integer.
+fn to_u32<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    to_uint(compiler, vars, 32, span)
+}
+
+/// Converts a field element to a 64-bit unsigned integer.
+fn to_u64<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    to_uint(compiler, vars, 64, span)
+}
+
+/// Converts a field element to a 64-bit signed integer.
+/// Field elements `p - x` are interpreted as the negative value `-x`.
+fn to_i64<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type field
+    let var_info = &vars[0];
+    if !matches!(&var_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value converted to an integer must be of type Field or BigInt. It was of type {:?}",
+            var_info.typ
+        );
+    }
+
+    // of only one field element
+    let var = &var_info.var;
+    assert_eq!(var.len(), 1);
+    let val = &var[0];
+
+    compiler.constrain_int(val, 64, span)?;
+
+    Ok(Some(Var::new_cvar(val.clone(), span)))
+}
+
+/// Converts a field element to an unsigned integer of `num_bits` bits,
+/// by constraining it to fit in that many bits.
+fn to_uint<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    num_bits: usize,
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type field
+    let var_info = &vars[0];
+    if !matches!(&var_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value converted to an integer must be of type Field or BigInt. It was of type {:?}",
+            var_info.typ
+        );
+    }
+
+    // of only one field element
+    let var = &var_info.var;
+    assert_eq!(var.len(), 1);
+    let val = &var[0];
+
+    compiler.constrain_uint(val, num_bits, span)?;
+
+    Ok(Some(Var::new_cvar(val.clone(), span)))
+}
+
+/// Returns the smallest of two values.
+fn min<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    let (lhs, rhs, signed_bits) = min_max_args(compiler, vars, span)?;
+    Ok(Some(field::min(compiler, signed_bits, lhs, rhs, span)?))
+}
+
+/// Returns the largest of two values.
+fn max<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    let (lhs, rhs, signed_bits) = min_max_args(compiler, vars, span)?;
+    Ok(Some(field::max(compiler, signed_bits, lhs, rhs, span)?))
+}
+
+/// Extracts the two values passed to `min` or `max`,
+/// as well as their bit length if they must be compared as signed integers.
+/// Field elements are constrained to fit in [field::comparison_bits] bits.
+#[allow(clippy::type_complexity)]
+fn min_max_args<'a, B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &'a [VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<(
+    &'a ConstOrCell<B::Field, B::Var>,
+    &'a ConstOrCell<B::Field, B::Var>,
+    Option<usize>,
+)> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+
+    // of type field
+    for var_info in vars {
+        if !matches!(&var_info.typ, Some(typ) if typ.is_numeric()) {
+            panic!(
+                "the values compared must be of type Field or BigInt. It was of type {:?}",
+                var_info.typ
+            );
+        }
+
+        // of only one field element
+        assert_eq!(var_info.var.len(), 1);
+    }
+
+    // signed integers are compared differently
+    let signed_bits = vars
+        .iter()
+        .find_map(|var_info| var_info.typ.as_ref().and_then(TyKind::int_bits));
+
+    if signed_bits.is_none() {
+        for var_info in vars {
+            compiler.constrain_comparable(var_info.typ.as_ref(), &var_info.var[0], span)?;
+        }
+    }
+
+    Ok((&vars[0].var[0], &vars[1].var[0], signed_bits))
+}
+
+/// Returns the absolute value of a signed value.
+/// Field elements are interpreted as signed integers of [field::comparison_bits] bits
+/// (`p - x` being the negative value `-x`).
+fn abs<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type field
+    let var_info = &vars[0];
+    let typ = match &var_info.typ {
+        Some(typ) if typ.is_numeric() => typ,
+        typ => panic!(
+            "the value passed to abs must be of type Field or BigInt. It was of type {:?}",
+            typ
+        ),
+    };
+
+    // of only one field element
+    let var = &var_info.var;
+    assert_eq!(var.len(), 1);
+    let val = &var[0];
+
+    // unsigned integers are already positive
+    if typ.uint_bits().is_some() {
+        return Ok(Some(Var::new_cvar(val.clone(), span)));
+    }
+
+    // field elements must fit in the bits of the comparison gadget
+    let num_bits = match typ.int_bits() {
+        Some(num_bits) => num_bits,
+        None => {
+            let num_bits = field::comparison_bits::<B::Field>();
+            compiler.constrain_int(val, num_bits, span)?;
+            num_bits
+        }
+    };
+    Ok(Some(field::abs(compiler, num_bits, val, span)?))
+}
+
+/// Returns a square root of a field element, which must be a square.
+fn sqrt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type field
+    let var_info = &vars[0];
+    if !matches!(&var_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value passed to sqrt must be of type Field or BigInt. It was of type {:?}",
+            var_info.typ
+        );
+    }
+
+    // of only one field element
+    let var = &var_info.var;
+    assert_eq!(var.len(), 1);
+    let val = &var[0];
+
+    // non-residues have no square root
+    if matches!(val, ConstOrCell::Const(cst) if cst.legendre().is_qnr()) {
+        return Err(compiler.error(ErrorKind::NotASquare, span));
+    }
+
+    Ok(Some(field::sqrt(compiler, val, span)))
+}
+
+/// Returns `[is_square, root]` where `is_square` is 1 if the field element is a square and 0 otherwise,
+/// and `root` is a square root of the field element if it is a square
+/// (or of the field element multiplied by the smallest non-residue of the field otherwise).
+fn try_sqrt<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type field
+    let var_info = &vars[0];
+    if !matches!(&var_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value passed to try_sqrt must be of type Field or BigInt. It was of type {:?}",
+            var_info.typ
+        );
+    }
+
+    // of only one field element
+    let var = &var_info.var;
+    assert_eq!(var.len(), 1);
+    let val = &var[0];
+
+    let (is_square, root) = field::try_sqrt(compiler, val, span);
+
+    Ok(Some(Var::new(
+        vec![is_square[0].clone(), root[0].clone()],
+        span,
+    )))
+}
+
+/// Returns true if the field element is a quadratic residue (or zero).
+fn is_square<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type field
+    let var_info = &vars[0];
+    if !matches!(&var_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value passed to is_square must be of type Field or BigInt. It was of type {:?}",
+            var_info.typ
+        );
+    }
+
+    // of only one field element
+    let var = &var_info.var;
+    assert_eq!(var.len(), 1);
+    let val = &var[0];
+
+    Ok(Some(field::is_square(compiler, val, span)))
+}
+
+/// Sorts an array in increasing order (signed integers are compared as such).
+fn sort<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    let (values, signed_bits) = sort_args(compiler, vars, span)?;
+    Ok(Some(sort::sort(compiler, signed_bits, values, span)?))
+}
+
+/// Returns the permutation that sorts an array: the indices of its elements in increasing order.
+fn argsort<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    let (values, signed_bits) = sort_args(compiler, vars, span)?;
+    Ok(Some(sort::argsort(compiler, signed_bits, values, span)?))
+}
+
+/// Extracts the array passed to `sort` or `argsort`,
+/// as well as the bit length of its elements if they must be compared as signed integers.
+/// Field elements are constrained to fit in [field::comparison_bits] bits.
+#[allow(clippy::type_complexity)]
+fn sort_args<'a, B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &'a [VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<(&'a [ConstOrCell<B::Field, B::Var>], Option<usize>)> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type [Field; _]
+    let var_info = &vars[0];
+    let typ = match &var_info.typ {
+        Some(TyKind::Array(typ, _)) if typ.is_numeric() => typ,
+        typ => panic!(
+            "the values sorted must be of type [Field; _]. It was of type {:?}",
+            typ
+        ),
+    };
+
+    // each value is checked once, instead of at every comparison
+    let signed_bits = typ.int_bits();
+    if signed_bits.is_none() {
+        for value in &var_info.var.cvars {
+            compiler.constrain_comparable(Some(typ), value, span)?;
+        }
+    }
+
+    Ok((&var_info.var.cvars, signed_bits))
+}
+
+/// Returns true if all the booleans of an array are true.
+fn all<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    let values = bool_array::<B>(vars);
+    Ok(Some(boolean::all(compiler, values, span)))
+}
+
+/// Returns true if at least one of the booleans of an array is true.
+fn any<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    let values = bool_array::<B>(vars);
+    Ok(Some(boolean::any(compiler, values, span)))
+}
+
+/// Extracts the array passed to `all` or `any`.
+fn bool_array<B: Backend>(vars: &[VarInfo<B::Field, B::Var>]) -> &[ConstOrCell<B::Field, B::Var>] {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // of type [Bool; _]
+    let var_info = &vars[0];
+    match &var_info.typ {
+        Some(TyKind::Array(typ, _)) if matches!(**typ, TyKind::Bool) => (),
+        typ => panic!(
+            "the values must be of type [Bool; _]. It was of type {:?}",
+            typ
+        ),
+    }
+
+    &var_info.var.cvars
+}
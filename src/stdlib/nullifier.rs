@@ -0,0 +1,115 @@
+//! Nullifiers, as used by privacy protocols to prevent double-spending without revealing which leaf is spent.
+//!
+//! All the values are Poseidon2 hashes (see [poseidon2::hash]) starting with a domain tag,
+//! so that a nullifier can't be confused with a commitment, or with a nullifier of another scope:
+//!
+//! - `commitment(secret_key) = H("noname_commitment", secret_key)`, the leaf inserted in the tree,
+//! - `derive(secret_key, leaf_index) = H("noname_nullifier", secret_key, leaf_index)`,
+//! - `derive_scoped(secret_key, leaf_index, scope) = H("noname_scoped_nullifier", secret_key, leaf_index, scope)`,
+//!   for applications that need a different nullifier per scope (e.g. one vote per poll).
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::poseidon2;
+use crate::error::Result;
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::FnSig;
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Var};
+
+const COMMITMENT_FN: &str = "commitment(secret_key: Field) -> Field";
+const DERIVE_FN: &str = "derive(secret_key: Field, leaf_index: Field) -> Field";
+const DERIVE_SCOPED_FN: &str =
+    "derive_scoped(secret_key: Field, leaf_index: Field, scope: Field) -> Field";
+
+pub const NULLIFIER_SIGS: &[&str] = &[COMMITMENT_FN, DERIVE_FN, DERIVE_SCOPED_FN];
+
+pub fn get_nullifier_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        COMMITMENT_FN => commitment,
+        DERIVE_FN => derive,
+        DERIVE_SCOPED_FN => derive_scoped,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns nullifier functions
+pub fn nullifier_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    NULLIFIER_SIGS
+        .iter()
+        .map(|sig| get_nullifier_fn(sig).unwrap())
+        .collect()
+}
+
+/// Returns the commitment to a secret key.
+fn commitment<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the secret key
+    assert_eq!(vars.len(), 1);
+    Ok(Some(tagged_hash(
+        compiler,
+        "noname_commitment",
+        vars,
+        span,
+    )?))
+}
+
+/// Returns the nullifier of a leaf.
+fn derive<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the secret key and the index of the leaf
+    assert_eq!(vars.len(), 2);
+    Ok(Some(tagged_hash(compiler, "noname_nullifier", vars, span)?))
+}
+
+/// Returns the nullifier of a leaf in a scope.
+fn derive_scoped<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the secret key, the index of the leaf, and the scope
+    assert_eq!(vars.len(), 3);
+    Ok(Some(tagged_hash(
+        compiler,
+        "noname_scoped_nullifier",
+        vars,
+        span,
+    )?))
+}
+
+/// Hashes the domain tag followed by the (field element) arguments.
+fn tagged_hash<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    domain: &str,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let mut input = vec![ConstOrCell::Const(poseidon2::domain_tag::<B::Field>(
+        domain,
+    ))];
+    input.extend(vars.iter().map(|var_info| var_info.var[0].clone()));
+
+    Ok(Var::new_cvar(
+        poseidon2::hash(compiler, &input, span)?,
+        span,
+    ))
+}
@@ -0,0 +1,94 @@
+//! Regular expressions, compiled into constraints at compile time.
+
+use num_bigint::BigUint;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::regex::{self, Dfa};
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::Var;
+
+const REGEX_MATCH_FN: &str = "regex_match(pattern: [U8; PatLen], input: [U8; Len]) -> Bool";
+
+pub const REGEX_SIGS: &[&str] = &[REGEX_MATCH_FN];
+
+pub fn get_regex_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        REGEX_MATCH_FN => regex_match,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns regex functions
+pub fn regex_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    REGEX_SIGS
+        .iter()
+        .map(|sig| get_regex_fn(sig).unwrap())
+        .collect()
+}
+
+/// Returns whether the input matches a pattern (as a whole),
+/// where the pattern is known at compile time (usually a string literal).
+fn regex_match<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the pattern and the input
+    assert_eq!(vars.len(), 2);
+
+    // of type array of bytes
+    for info in vars {
+        if !matches!(&info.typ, Some(TyKind::Array(typ, _)) if typ.match_expected(&TyKind::U8)) {
+            panic!(
+                "the arguments of regex_match must be arrays of bytes. It was of type {:?}",
+                info.typ
+            );
+        }
+    }
+
+    // the pattern is compiled into an automaton
+    let pattern = vars[0]
+        .var
+        .cvars
+        .iter()
+        .map(|byte| {
+            let byte: BigUint = byte
+                .cst()
+                .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?
+                .into();
+            u8::try_from(byte).map_err(|_| compiler.error(ErrorKind::AssertionFailed, span))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let dfa = Dfa::compile(&pattern)
+        .map_err(|reason| compiler.error(ErrorKind::InvalidRegex(reason), span))?;
+
+    // constants are not range-checked yet
+    let input = &vars[1].var.cvars;
+    for byte in input {
+        if let Some(cst) = byte.cst() {
+            let cst: BigUint = cst.into();
+            if cst.bits() > 8 {
+                return Err(compiler.error(ErrorKind::AssertionFailed, span));
+            }
+        }
+    }
+
+    let res = regex::is_match(compiler, &dfa, input, span);
+    Ok(Some(Var::new_cvar(res, span)))
+}
@@ -0,0 +1,250 @@
+//! Set membership, based on the product of the differences between an element and the elements of the set:
+//! `(needle - haystack[0]) * (needle - haystack[1]) * ...` is zero if and only if the needle is in the set.
+//! This costs one multiplication per element of the set.
+//!
+//! On backends with lookup tables (like kimchi), asserting that a needle is in a constant set
+//! is done with a single lookup instead, whatever the size of the set.
+//! This doesn't apply to [contains], which must also constrain the needle when it is not in the set,
+//! and a lookup can only show that an entry is in a table.
+//!
+//! This module also checks multiset equality (i.e. that two arrays are permutations of each other),
+//! see [permutation].
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::{field, permutation};
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Var};
+
+const CONTAINS_FN: &str = "contains(needle: Field, haystack: [Field; Len]) -> Bool";
+const ASSERT_CONTAINS_FN: &str = "assert_contains(needle: Field, haystack: [Field; Len])";
+const ASSERT_NOT_CONTAINS_FN: &str = "assert_not_contains(needle: Field, haystack: [Field; Len])";
+const ASSERT_PERMUTATION_FN: &str = "assert_permutation(lhs: [Field; Len], rhs: [Field; Len])";
+
+pub const SET_SIGS: &[&str] = &[
+    CONTAINS_FN,
+    ASSERT_CONTAINS_FN,
+    ASSERT_NOT_CONTAINS_FN,
+    ASSERT_PERMUTATION_FN,
+];
+
+pub fn get_set_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        CONTAINS_FN => contains,
+        ASSERT_CONTAINS_FN => assert_contains,
+        ASSERT_NOT_CONTAINS_FN => assert_not_contains,
+        ASSERT_PERMUTATION_FN => assert_permutation,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns set functions
+pub fn set_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    SET_SIGS
+        .iter()
+        .map(|sig| get_set_fn(sig).unwrap())
+        .collect()
+}
+
+/// Returns true if the needle is one of the elements of the haystack.
+fn contains<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    let prod = product_of_differences(compiler, vars, span);
+
+    let zero = ConstOrCell::Const(B::Field::zero());
+    Ok(Some(field::equal_cells(compiler, &prod, &zero, span)))
+}
+
+/// Asserts that the needle is one of the elements of the haystack.
+fn assert_contains<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    if compiler.backend.capabilities().lookup {
+        if let Some((needle, table)) = constant_haystack(vars) {
+            // the pair (needle, 0) is in the table iff the needle is in the haystack
+            let zero = compiler.backend.add_constant(
+                Some("the value of a set membership lookup"),
+                B::Field::zero(),
+                span,
+            );
+            compiler.backend.lookup(&table, needle, &zero, span);
+            return Ok(None);
+        }
+    }
+
+    let prod = product_of_differences(compiler, vars, span);
+
+    match &prod {
+        ConstOrCell::Const(cst) => {
+            if !cst.is_zero() {
+                return Err(compiler.error(ErrorKind::AssertionFailed, span));
+            }
+        }
+        ConstOrCell::Cell(cvar) => compiler
+            .backend
+            .assert_eq_const(cvar, B::Field::zero(), span),
+    }
+
+    Ok(None)
+}
+
+/// Asserts that the needle is none of the elements of the haystack.
+fn assert_not_contains<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    let prod = product_of_differences(compiler, vars, span);
+
+    match &prod {
+        ConstOrCell::Const(cst) => {
+            if cst.is_zero() {
+                return Err(compiler.error(ErrorKind::AssertionFailed, span));
+            }
+        }
+        // a value is non-zero if it has an inverse
+        ConstOrCell::Cell(_) => {
+            let _inverse = field::inv(compiler, &prod, span);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Asserts that two arrays contain the same elements, potentially in a different order.
+fn assert_permutation<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+
+    // of type [Field; _]
+    let mut lists = Vec::with_capacity(2);
+    for var_info in vars {
+        match &var_info.typ {
+            Some(TyKind::Array(typ, _)) if typ.is_numeric() => (),
+            typ => panic!(
+                "the arrays compared must be of type [Field; _]. It was of type {:?}",
+                typ
+            ),
+        }
+
+        let list: Vec<_> = var_info
+            .var
+            .cvars
+            .iter()
+            .map(|elem| vec![elem.clone()])
+            .collect();
+        lists.push(list);
+    }
+
+    // two constant arrays
+    let constants: Option<Vec<Vec<BigUint>>> = vars
+        .iter()
+        .map(|var_info| {
+            var_info
+                .var
+                .cvars
+                .iter()
+                .map(|elem| elem.cst().map(Into::into))
+                .collect()
+        })
+        .collect();
+
+    if let Some(mut constants) = constants {
+        constants.iter_mut().for_each(|list| list.sort());
+        if constants[0] != constants[1] {
+            return Err(compiler.error(ErrorKind::AssertionFailed, span));
+        }
+        return Ok(None);
+    }
+
+    permutation::assert_permutation(compiler, &lists[0], &lists[1], span)?;
+
+    Ok(None)
+}
+
+/// If the needle is a cell and the haystack is constant,
+/// returns the needle and a lookup table with a `(element, 0)` pair for each distinct element of the haystack.
+fn constant_haystack<'a, B: Backend>(
+    vars: &'a [VarInfo<B::Field, B::Var>],
+) -> Option<(&'a B::Var, Vec<(B::Field, B::Field)>)> {
+    let ConstOrCell::Cell(needle) = vars.first()?.var.cvars.first()? else {
+        return None;
+    };
+
+    let mut table: Vec<(B::Field, B::Field)> = vec![];
+    for elem in &vars.get(1)?.var.cvars {
+        let elem = elem.cst()?;
+        if table.iter().all(|(entry, _)| *entry != elem) {
+            table.push((elem, B::Field::zero()));
+        }
+    }
+
+    // an empty haystack contains nothing
+    (!table.is_empty()).then_some((needle, table))
+}
+
+/// Returns the product of the differences between the needle and the elements of the haystack.
+fn product_of_differences<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> ConstOrCell<B::Field, B::Var> {
+    // we get two vars
+    assert_eq!(vars.len(), 2);
+    let needle_info = &vars[0];
+    let haystack_info = &vars[1];
+
+    // of type field and [Field; _]
+    if !matches!(&needle_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the needle must be of type Field or BigInt. It was of type {:?}",
+            needle_info.typ
+        );
+    }
+
+    match &haystack_info.typ {
+        Some(TyKind::Array(typ, _)) if typ.is_numeric() => (),
+        typ => panic!(
+            "the haystack must be of type [Field; _]. It was of type {:?}",
+            typ
+        ),
+    }
+
+    assert_eq!(needle_info.var.len(), 1);
+    let needle = &needle_info.var[0];
+
+    let mut prod = ConstOrCell::Const(B::Field::one());
+    for elem in &haystack_info.var.cvars {
+        let diff = field::sub(compiler, needle, elem, span);
+        prod = field::mul(compiler, &prod, &diff[0], span)[0].clone();
+    }
+
+    prod
+}
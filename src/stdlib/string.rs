@@ -0,0 +1,171 @@
+//! Strings (`Str<Cap>`), which are arrays of bytes followed by zeros up to their capacity.
+
+use num_bigint::BigUint;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::string;
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::Var;
+
+const LEN_FN: &str = "len(text: Str<Cap>) -> Field";
+const EQUAL_FN: &str = "equal(lhs: Str<LhsCap>, rhs: Str<RhsCap>) -> Bool";
+const SUBSTRING_FN: &str =
+    "substring(text: Str<Cap>, start: Field, len: Field, const SubCap: Field) -> Str<SubCap>";
+const CONCAT_FN: &str = "concat(lhs: Str<LhsCap>, rhs: Str<RhsCap>, const Cap: Field) -> Str<Cap>";
+
+pub const STRING_SIGS: &[&str] = &[LEN_FN, EQUAL_FN, SUBSTRING_FN, CONCAT_FN];
+
+pub fn get_string_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        LEN_FN => len,
+        EQUAL_FN => equal,
+        SUBSTRING_FN => substring,
+        CONCAT_FN => concat,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns string functions
+pub fn string_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    STRING_SIGS
+        .iter()
+        .map(|sig| get_string_fn(sig).unwrap())
+        .collect()
+}
+
+/// Checks that the arguments are strings, whose constant bytes fit in 8 bits.
+fn check_strings<B: Backend>(
+    compiler: &CircuitWriter<B>,
+    strings: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<()> {
+    for info in strings {
+        if !matches!(&info.typ, Some(TyKind::Array(typ, _)) if typ.match_expected(&TyKind::U8)) {
+            panic!(
+                "the arguments of string functions must be strings. It was of type {:?}",
+                info.typ
+            );
+        }
+
+        // constants are not range-checked yet
+        for byte in &info.var.cvars {
+            if let Some(cst) = byte.cst() {
+                let cst: BigUint = cst.into();
+                if cst.bits() > 8 {
+                    return Err(compiler.error(ErrorKind::AssertionFailed, span));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the capacity given as a constant argument.
+fn constant_capacity<B: Backend>(
+    compiler: &CircuitWriter<B>,
+    var: &VarInfo<B::Field, B::Var>,
+    span: Span,
+) -> Result<usize> {
+    let cap: BigUint = var
+        .var
+        .constant()
+        .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?
+        .into();
+    cap.try_into()
+        .map_err(|_| compiler.error(ErrorKind::InvalidArraySize, span))
+}
+
+/// Returns the length of a string.
+fn len<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single string
+    assert_eq!(vars.len(), 1);
+    check_strings(compiler, vars, span)?;
+
+    let res = string::length(compiler, &vars[0].var.cvars, span)?;
+    Ok(Some(Var::new_cvar(res, span)))
+}
+
+/// Returns whether two strings (of possibly different capacities) are equal.
+fn equal<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two strings
+    assert_eq!(vars.len(), 2);
+    check_strings(compiler, vars, span)?;
+
+    let res = string::equal(compiler, &vars[0].var.cvars, &vars[1].var.cvars, span);
+    Ok(Some(res))
+}
+
+/// Returns the `len` bytes of a string starting at `start`,
+/// as a string of a capacity known at compile time.
+fn substring<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the string, the start, the length, and the capacity of the result
+    assert_eq!(vars.len(), 4);
+    check_strings(compiler, &vars[..1], span)?;
+
+    let start = &vars[1].var;
+    let len = &vars[2].var;
+    assert_eq!(start.len(), 1);
+    assert_eq!(len.len(), 1);
+
+    let capacity = constant_capacity(compiler, &vars[3], span)?;
+    let res = string::substring(
+        compiler,
+        &vars[0].var.cvars,
+        &start[0],
+        &len[0],
+        capacity,
+        span,
+    )?;
+    Ok(Some(res))
+}
+
+/// Returns the concatenation of two strings,
+/// as a string of a capacity known at compile time.
+fn concat<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get two strings and the capacity of the result
+    assert_eq!(vars.len(), 3);
+    check_strings(compiler, &vars[..2], span)?;
+
+    let capacity = constant_capacity(compiler, &vars[2], span)?;
+    let res = string::concat(
+        compiler,
+        &vars[0].var.cvars,
+        &vars[1].var.cvars,
+        capacity,
+        span,
+    )?;
+    Ok(Some(res))
+}
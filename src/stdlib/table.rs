@@ -0,0 +1,78 @@
+//! Lookups in constant tables of `(index, value)` pairs (see [table]).
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::table;
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::Var;
+
+const LOOKUP_FN: &str = "lookup(table: [[Field; 2]; Len], index: Field) -> Field";
+
+pub const TABLE_SIGS: &[&str] = &[LOOKUP_FN];
+
+pub fn get_table_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        LOOKUP_FN => lookup,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns table functions
+pub fn table_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    TABLE_SIGS
+        .iter()
+        .map(|sig| get_table_fn(sig).unwrap())
+        .collect()
+}
+
+/// Returns the value associated to an index in a table of `[index, value]` pairs known at compile time.
+fn lookup<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the table and the index
+    assert_eq!(vars.len(), 2);
+
+    // the table is an array of constant pairs
+    let table_info = &vars[0];
+    if !matches!(&table_info.typ, Some(TyKind::Array(pair, _)) if matches!(pair.as_ref(), TyKind::Array(typ, 2) if typ.is_numeric()))
+    {
+        panic!(
+            "a lookup table must be an array of [index, value] pairs. It was of type {:?}",
+            table_info.typ
+        );
+    }
+
+    let entries = table_info
+        .var
+        .cvars
+        .iter()
+        .map(|cvar| {
+            cvar.cst()
+                .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let entries: Vec<_> = entries.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+
+    let index = &vars[1].var;
+    assert_eq!(index.len(), 1);
+
+    let res = table::lookup(compiler, &entries, &index[0], span)?;
+    Ok(Some(Var::new_cvar(res, span)))
+}
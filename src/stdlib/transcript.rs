@@ -0,0 +1,214 @@
+//! Fiat–Shamir transcripts (`Transcript`), to derive the challenges of interactive protocols
+//! (like sigma protocols or folding schemes) from the messages of the prover.
+//!
+//! A transcript is the state of a duplex sponge over the Poseidon2 permutation (see [poseidon2]),
+//! which is an array of 3 field elements: 2 for the rate, and 1 for the capacity.
+//! The Poseidon builtin is not used, as it only hashes 2 elements with a zero capacity,
+//! which can't carry the state of a sponge.
+//!
+//! The capacity starts with a tag derived from the label of the protocol,
+//! and each operation adds its own tag to the capacity before permuting the state,
+//! so that absorbing a field element, absorbing a point, and squeezing a challenge are all separated.
+//! Transcripts are values: each operation returns the updated transcript.
+
+use num_bigint::BigUint;
+
+use crate::backends::Backend;
+use crate::circuit_writer::{CircuitWriter, VarInfo};
+use crate::constants::Span;
+use crate::constraints::{field, poseidon2};
+use crate::error::{ErrorKind, Result};
+use crate::imports::FnKind;
+use crate::lexer::Token;
+use crate::parser::types::{FnSig, TyKind};
+use crate::parser::ParserCtx;
+use crate::type_checker::FnInfo;
+use crate::var::{ConstOrCell, Var};
+
+const NEW_FN: &str = "new(label: [U8; Len]) -> Transcript";
+const ABSORB_FN: &str = "absorb(transcript: Transcript, value: Field) -> Transcript";
+const ABSORB_POINT_FN: &str =
+    "absorb_point(transcript: Transcript, point: [Field; 2]) -> Transcript";
+const SQUEEZE_FN: &str = "squeeze(transcript: Transcript) -> Transcript";
+const CHALLENGE_FN: &str = "challenge(transcript: Transcript) -> Field";
+
+pub const TRANSCRIPT_SIGS: &[&str] =
+    &[NEW_FN, ABSORB_FN, ABSORB_POINT_FN, SQUEEZE_FN, CHALLENGE_FN];
+
+/// The tag added to the capacity when absorbing a field element.
+const ABSORB_TAG: u64 = 1;
+
+/// The tag added to the capacity when absorbing a point.
+const ABSORB_POINT_TAG: u64 = 2;
+
+/// The tag added to the capacity when squeezing a challenge.
+const SQUEEZE_TAG: u64 = 3;
+
+pub fn get_transcript_fn<B: Backend>(name: &str) -> Option<FnInfo<B>> {
+    let ctx = &mut ParserCtx::default();
+    let mut tokens = Token::parse(0, name).unwrap();
+    let sig = FnSig::parse(ctx, &mut tokens).unwrap();
+
+    let fn_handle = match name {
+        NEW_FN => new,
+        ABSORB_FN => absorb,
+        ABSORB_POINT_FN => absorb_point,
+        SQUEEZE_FN => squeeze,
+        CHALLENGE_FN => challenge,
+        _ => return None,
+    };
+
+    Some(FnInfo {
+        kind: FnKind::BuiltIn(sig, fn_handle),
+        span: Span::default(),
+    })
+}
+
+/// a function returns transcript functions
+pub fn transcript_fns<B: Backend>() -> Vec<FnInfo<B>> {
+    TRANSCRIPT_SIGS
+        .iter()
+        .map(|sig| get_transcript_fn(sig).unwrap())
+        .collect()
+}
+
+/// Returns the state of a transcript.
+fn transcript_state<'a, B: Backend>(
+    info: &'a VarInfo<B::Field, B::Var>,
+) -> &'a [ConstOrCell<B::Field, B::Var>] {
+    if !matches!(&info.typ, Some(TyKind::Array(typ, size)) if typ.match_expected(&TyKind::Field) && *size as usize == poseidon2::WIDTH)
+    {
+        panic!(
+            "the transcript must be of type Transcript. It was of type {:?}",
+            info.typ
+        );
+    }
+
+    &info.var.cvars
+}
+
+/// Adds `input` to the rate and `tag` to the capacity, then permutes the state.
+fn duplex<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    state: &[ConstOrCell<B::Field, B::Var>],
+    input: &[ConstOrCell<B::Field, B::Var>],
+    tag: u64,
+    span: Span,
+) -> Result<Var<B::Field, B::Var>> {
+    let mut state = state.to_vec();
+    for (elem, absorbed) in state.iter_mut().zip(input) {
+        *elem = field::add(compiler, elem, absorbed, span)[0].clone();
+    }
+
+    let capacity = &state[poseidon2::WIDTH - 1];
+    let tag = ConstOrCell::Const(B::Field::from(tag));
+    state[poseidon2::WIDTH - 1] = field::add(compiler, capacity, &tag, span)[0].clone();
+
+    poseidon2::permutation(compiler, &state, span)
+}
+
+/// Starts a transcript for a protocol, with a constant label.
+fn new<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get a single var
+    assert_eq!(vars.len(), 1);
+
+    // the label is a constant array of bytes
+    let label_info = &vars[0];
+    if !matches!(&label_info.typ, Some(TyKind::Array(typ, _)) if typ.match_expected(&TyKind::U8)) {
+        panic!(
+            "the label of a transcript must be an array of bytes. It was of type {:?}",
+            label_info.typ
+        );
+    }
+
+    let label = label_info
+        .var
+        .cvars
+        .iter()
+        .map(|byte| {
+            let byte: BigUint = byte
+                .cst()
+                .ok_or_else(|| compiler.error(ErrorKind::ExpectedConstant, span))?
+                .into();
+            u8::try_from(byte).map_err(|_| compiler.error(ErrorKind::AssertionFailed, span))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let label = String::from_utf8_lossy(&label);
+
+    let tag = poseidon2::domain_tag::<B::Field>(&format!("noname_transcript_{label}"));
+    let mut state = vec![ConstOrCell::Const(B::Field::from(0u64)); poseidon2::WIDTH - 1];
+    state.push(ConstOrCell::Const(tag));
+
+    Ok(Some(Var::new(state, span)))
+}
+
+/// Absorbs a field element into a transcript.
+fn absorb<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the transcript and the value
+    assert_eq!(vars.len(), 2);
+    let state = transcript_state(&vars[0]);
+
+    let value_info = &vars[1];
+    if !matches!(&value_info.typ, Some(typ) if typ.is_numeric()) {
+        panic!(
+            "the value absorbed by a transcript must be a field element. It was of type {:?}",
+            value_info.typ
+        );
+    }
+    assert_eq!(value_info.var.len(), 1);
+
+    let res = duplex(compiler, state, &value_info.var.cvars, ABSORB_TAG, span)?;
+    Ok(Some(res))
+}
+
+/// Absorbs a point (its two coordinates) into a transcript.
+fn absorb_point<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the transcript and the point
+    assert_eq!(vars.len(), 2);
+    let state = transcript_state(&vars[0]);
+
+    let point = &vars[1].var;
+    assert_eq!(point.len(), 2);
+
+    let res = duplex(compiler, state, &point.cvars, ABSORB_POINT_TAG, span)?;
+    Ok(Some(res))
+}
+
+/// Squeezes a transcript: the next challenge is then returned by [challenge].
+fn squeeze<B: Backend>(
+    compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the transcript
+    assert_eq!(vars.len(), 1);
+    let state = transcript_state(&vars[0]);
+
+    let res = duplex(compiler, state, &[], SQUEEZE_TAG, span)?;
+    Ok(Some(res))
+}
+
+/// Returns the challenge of a squeezed transcript: the first element of its rate.
+fn challenge<B: Backend>(
+    _compiler: &mut CircuitWriter<B>,
+    vars: &[VarInfo<B::Field, B::Var>],
+    span: Span,
+) -> Result<Option<Var<B::Field, B::Var>>> {
+    // we get the transcript
+    assert_eq!(vars.len(), 1);
+    let state = transcript_state(&vars[0]);
+
+    Ok(Some(Var::new_cvar(state[0].clone(), span)))
+}
@@ -0,0 +1,282 @@
+use num_bigint::BigUint;
+
+use crate::{
+    backends::{
+        kimchi::{KimchiVesta, VestaField},
+        plonky2::field::R1csGoldilocksField,
+        r1cs::{R1csBls12381Field, R1CS},
+        Backend,
+    },
+    constraints::weierstrass,
+    error::{Error, ErrorKind},
+    inputs::parse_inputs,
+};
+
+use super::compile_code;
+
+/// Type checks and compiles a program that must be rejected, and returns the error.
+fn compile_error<B: Backend>(code: &str, backend: B) -> Error {
+    match compile_code(code, backend) {
+        Ok(_) => panic!("the program should not compile"),
+        Err(err) => err,
+    }
+}
+
+/// Compiles a program, and returns the error of its witness generation with the given inputs.
+fn witness_error<B: Backend>(
+    code: &str,
+    backend: B,
+    public_inputs: &str,
+    private_inputs: &str,
+) -> Error {
+    let compiled_circuit = compile_code(code, backend).expect("the program should compile");
+    let public_inputs = parse_inputs(public_inputs).unwrap();
+    let private_inputs = parse_inputs(private_inputs).unwrap();
+
+    match compiled_circuit.generate_witness(public_inputs, private_inputs) {
+        Ok(_) => panic!("the witness generation should fail"),
+        Err(err) => err,
+    }
+}
+
+#[test]
+fn test_division_by_wide_constant() {
+    // 2^127 doesn't fit in the 126 bits of a divisor
+    let code = "
+fn main(pub xx: Field) -> Field {
+    return xx / 170141183460469231731687303715884105728;
+}
+";
+
+    let err = compile_error(code, KimchiVesta::new(false));
+    assert!(matches!(err.kind, ErrorKind::IntegerOverflow(126)));
+
+    let err = compile_error(code, R1CS::<R1csBls12381Field>::new());
+    assert!(matches!(err.kind, ErrorKind::IntegerOverflow(126)));
+}
+
+#[test]
+fn test_inverse_of_zero() {
+    let code = "
+fn main(pub xx: Field) -> Field {
+    return inv(xx);
+}
+";
+    // the error points to the call to `inv`
+    let start = code.find("inv(xx)").unwrap();
+
+    let err = witness_error(code, KimchiVesta::new(false), r#"{"xx": "0"}"#, "{}");
+    assert!(matches!(err.kind, ErrorKind::InverseOfZero));
+    assert_eq!(err.span.start, start);
+
+    let err = witness_error(
+        code,
+        R1CS::<R1csBls12381Field>::new(),
+        r#"{"xx": "0"}"#,
+        "{}",
+    );
+    assert!(matches!(err.kind, ErrorKind::InverseOfZero));
+    assert_eq!(err.span.start, start);
+}
+
+#[test]
+fn test_bitwise_wide_constant() {
+    // 2^252 doesn't fit in the 252 bits of a bitwise operand
+    let code = "
+fn main(pub xx: Field) -> Field {
+    return xx & 7237005577332262213973186563042994240829374041602535252466099000494570602496;
+}
+";
+
+    let err = compile_error(code, KimchiVesta::new(false));
+    assert!(matches!(err.kind, ErrorKind::IntegerOverflow(252)));
+
+    let err = compile_error(code, R1CS::<R1csBls12381Field>::new());
+    assert!(matches!(err.kind, ErrorKind::IntegerOverflow(252)));
+}
+
+#[test]
+fn test_sqrt_of_non_residue() {
+    // 5 is not a square in any of our fields
+    let code = "
+fn main(pub xx: Field) -> Field {
+    return sqrt(xx);
+}
+";
+    let start = code.find("sqrt(xx)").unwrap();
+
+    let err = witness_error(code, KimchiVesta::new(false), r#"{"xx": "5"}"#, "{}");
+    assert!(matches!(err.kind, ErrorKind::NotASquare));
+    assert_eq!(err.span.start, start);
+
+    let err = witness_error(
+        code,
+        R1CS::<R1csBls12381Field>::new(),
+        r#"{"xx": "5"}"#,
+        "{}",
+    );
+    assert!(matches!(err.kind, ErrorKind::NotASquare));
+    assert_eq!(err.span.start, start);
+
+    // a constant non-residue is rejected at compile time
+    let code = "
+fn main(pub xx: Field) -> Field {
+    return xx + sqrt(5);
+}
+";
+    let err = compile_error(code, KimchiVesta::new(false));
+    assert!(matches!(err.kind, ErrorKind::NotASquare));
+}
+
+#[test]
+fn test_assert_not_eq_same_value() {
+    // a value is never different from itself
+    let code = "
+fn main(pub xx: Field) {
+    assert_not_eq(xx, xx);
+}
+";
+    let err = witness_error(code, KimchiVesta::new(false), r#"{"xx": "1"}"#, "{}");
+    assert!(matches!(err.kind, ErrorKind::InverseOfZero));
+
+    let err = witness_error(
+        code,
+        R1CS::<R1csBls12381Field>::new(),
+        r#"{"xx": "1"}"#,
+        "{}",
+    );
+    assert!(matches!(err.kind, ErrorKind::InverseOfZero));
+
+    // and the same goes for constants
+    let code = "
+fn main(pub xx: Field) {
+    assert_not_eq(3, 3);
+}
+";
+    let err = compile_error(code, KimchiVesta::new(false));
+    assert!(matches!(err.kind, ErrorKind::AssertionFailed));
+}
+
+#[test]
+fn test_u64_on_goldilocks() {
+    // every element of the 64-bit field of Goldilocks fits in 64 bits, so a U64 can't be range-checked
+    let code = "
+fn main(pub xx: U64, yy: U64) -> Bool {
+    return xx < yy;
+}
+";
+    let start = code.find("U64").unwrap();
+
+    let err = compile_error(code, R1CS::<R1csGoldilocksField>::new());
+    assert!(matches!(err.kind, ErrorKind::FieldTooSmall(64)));
+    assert_eq!(err.span.start, start);
+
+    // smaller integers can still be range-checked and compared
+    let code = code.replace("U64", "U32");
+    assert!(compile_code(&code, R1CS::<R1csGoldilocksField>::new()).is_ok());
+}
+
+#[test]
+fn test_u32_overflow_on_goldilocks() {
+    let code = "
+fn main(pub xx: U32, yy: U32) -> U32 {
+    return xx * yy;
+}
+";
+    // 2^20 * 2^20 doesn't fit in 32 bits, but it doesn't wrap around the modulus either
+    let err = witness_error(
+        code,
+        R1CS::<R1csGoldilocksField>::new(),
+        r#"{"xx": "1048576"}"#,
+        r#"{"yy": "1048576"}"#,
+    );
+    assert!(matches!(err.kind, ErrorKind::InvalidWitness(_)));
+}
+
+#[test]
+fn test_range_check_on_goldilocks() {
+    let code = "
+fn main(pub xx: Field) {
+    range_check(xx, 63);
+}
+";
+    let start = code.find("range_check").unwrap();
+
+    // 63 bits cover half of the 64-bit field of Goldilocks
+    let err = compile_error(code, R1CS::<R1csGoldilocksField>::new());
+    assert!(matches!(err.kind, ErrorKind::FieldTooSmall(63)));
+    assert_eq!(err.span.start, start);
+
+    let code = code.replace("63", "62");
+    assert!(compile_code(&code, R1CS::<R1csGoldilocksField>::new()).is_ok());
+}
+
+#[test]
+fn test_unsatisfied_wired_equality() {
+    // on kimchi, the equality of two vars is wired instead of being a gate
+    let code = "
+fn main(pub xx: Field, yy: Field) {
+    let zz = yy * yy;
+    assert_eq(xx, zz);
+}
+";
+    // the error points to the assertion
+    let start = code.find("assert_eq(xx, zz)").unwrap();
+
+    let err = witness_error(
+        code,
+        KimchiVesta::new(false),
+        r#"{"xx": "5"}"#,
+        r#"{"yy": "2"}"#,
+    );
+    assert!(matches!(err.kind, ErrorKind::AssertionFailed));
+    assert_eq!(err.span.start, start);
+}
+
+#[test]
+fn test_addition_of_constant_points_with_same_x() {
+    // the incomplete addition formulas can't double a point
+    let code = "
+use std::crypto;
+
+fn main(pub xx: Field) -> Field {
+    let generator = crypto::point_generator();
+    let res = crypto::point_add(generator, generator);
+    return res[0] + xx;
+}
+";
+    let start = code.find("crypto::point_add").unwrap();
+
+    let err = compile_error(code, KimchiVesta::new(false));
+    assert!(matches!(err.kind, ErrorKind::IncompleteAddition));
+    assert_eq!(err.span.start, start);
+}
+
+#[test]
+fn test_scalar_mul_ignores_additions_of_unset_bits() {
+    let code = "
+use std::crypto;
+
+fn main(pub point: [Field; 2], scalar: Field) -> [Field; 2] {
+    return crypto::point_scalar_mul(point, scalar);
+}
+";
+    // the accumulator starts at twice the offset point after the first doubling,
+    // so adding that same point for the (unset) first bit would divide by zero
+    let curve = weierstrass::embedded_curve::<VestaField>().unwrap();
+    let offset = curve.hash_to_point("noname_weierstrass_offset");
+    let (x, y) = curve.add(Some(offset), Some(offset)).unwrap();
+    let public_inputs = format!(
+        r#"{{"point": ["{}", "{}"]}}"#,
+        BigUint::from(x),
+        BigUint::from(y)
+    );
+
+    let compiled_circuit =
+        compile_code(code, KimchiVesta::new(false)).expect("the program should compile");
+    let public_inputs = parse_inputs(&public_inputs).unwrap();
+    let private_inputs = parse_inputs(r#"{"scalar": "1"}"#).unwrap();
+    assert!(compiled_circuit
+        .generate_witness(public_inputs, private_inputs)
+        .is_ok());
+}
@@ -1,15 +1,28 @@
 use std::{path::Path, str::FromStr};
 
+use ark_ff::{One, Zero};
+use kimchi::{
+    circuits::polynomials::poseidon::{POS_ROWS_PER_HASH, ROUNDS_PER_ROW},
+    mina_curves::pasta::{Pallas, Vesta},
+    mina_poseidon::{constants::PlonkSpongeConstantsKimchi, permutation::full_round},
+};
+use num_bigint::BigUint;
 use rstest::rstest;
 
+#[cfg(feature = "bulletproofs")]
+use crate::backends::bulletproofs::field::R1csRistrettoField;
 use crate::{
     backends::{
-        kimchi::{KimchiVesta, VestaField},
+        kimchi::{
+            prover::ProofSystem, Kimchi, KimchiPallas, KimchiVesta, PallasField, PastaCurve,
+            VestaField,
+        },
         r1cs::R1CS,
-        BackendKind,
+        BackendField, BackendKind,
     },
-    compiler::{compile, typecheck_next_file, Sources},
-    inputs::{parse_inputs, ExtField},
+    compiler::{compile, generate_witness, typecheck_next_file, Sources},
+    constraints::{sinsemilla, weierstrass},
+    inputs::{parse_inputs, ExtField, JsonInputs},
     type_checker::TypeChecker,
 };
 
@@ -33,133 +46,399 @@ fn test_file(
 
     match backend {
         BackendKind::KimchiVesta(kimchi_vesta) => {
-            // compile
-            let mut sources = Sources::new();
+            let asm_path = prefix_examples.join(format!("fixture/asm/kimchi/{file_name}.asm"));
+            test_kimchi_file(
+                kimchi_vesta,
+                file_name,
+                code,
+                public_inputs,
+                private_inputs,
+                expected_public_output,
+                Some(&asm_path),
+            )?;
+        }
+        BackendKind::KimchiPallas(kimchi_pallas) => {
+            // the ASM fixtures are over vesta
+            test_kimchi_file(
+                kimchi_pallas,
+                file_name,
+                code,
+                public_inputs,
+                private_inputs,
+                expected_public_output,
+                None,
+            )?;
+        }
+        BackendKind::R1csBls12_381(r1cs) => {
+            let asm_path = prefix_examples.join(format!("fixture/asm/r1cs/{file_name}.asm"));
+            test_r1cs_file(
+                r1cs,
+                file_name,
+                code,
+                public_inputs,
+                private_inputs,
+                expected_public_output,
+                Some(&asm_path),
+            )?;
+        }
+        BackendKind::R1csPallas(r1cs) => {
+            // the ASM fixtures are over bls12-381
+            test_r1cs_file(
+                r1cs,
+                file_name,
+                code,
+                public_inputs,
+                private_inputs,
+                expected_public_output,
+                None,
+            )?;
+        }
+        BackendKind::R1csBn254(_) => todo!(),
+        #[cfg(feature = "bulletproofs")]
+        BackendKind::Bulletproofs(r1cs) => {
+            // the ASM fixtures are over bls12-381
+            test_bulletproofs_file(
+                r1cs,
+                file_name,
+                code,
+                public_inputs,
+                private_inputs,
+                expected_public_output,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Compiles an example with a kimchi backend, checks its output, and proves and verifies it,
+/// as well as its ASM against the fixture at `asm_path` if it is set and the circuit is small.
+fn test_kimchi_file<C: ProofSystem>(
+    kimchi: Kimchi<C>,
+    file_name: &str,
+    code: String,
+    public_inputs: JsonInputs,
+    private_inputs: JsonInputs,
+    expected_public_output: Vec<&str>,
+    asm_path: Option<&Path>,
+) -> miette::Result<()> {
+    // compile
+    let mut sources = Sources::new();
+    let mut tast = TypeChecker::new();
+    let this_module = None;
+    let _node_id = typecheck_next_file(
+        &mut tast,
+        this_module,
+        &mut sources,
+        file_name.to_string(),
+        code,
+        0,
+    )
+    .unwrap();
+
+    let compiled_circuit = compile(&sources, tast, kimchi)?;
+
+    let (prover_index, verifier_index) = compiled_circuit.compile_to_indexes().unwrap();
+
+    // check compiled ASM only if it's not too large
+    if let Some(asm_path) = asm_path.filter(|_| prover_index.len() < 100) {
+        let expected_asm = read_asm_fixture(asm_path);
+
+        let obtained_asm = prover_index.asm(&Sources::new(), false);
+        if obtained_asm != expected_asm {
+            eprintln!("obtained:");
+            eprintln!("{obtained_asm}");
+            eprintln!("expected:");
+            eprintln!("{expected_asm}");
+            panic!("Obtained ASM does not match expected ASM");
+        }
+    }
+
+    // create proof
+    let (proof, full_public_inputs, public_output) =
+        prover_index.prove(&sources, public_inputs, private_inputs, false)?;
+
+    let expected_public_output = expected_public_output
+        .iter()
+        .map(|x| C::Field::from_str(x).unwrap_or_else(|_| panic!("invalid expected output `{x}`")))
+        .collect::<Vec<_>>();
+
+    if public_output != expected_public_output {
+        eprintln!("obtained by executing the circuit:");
+        public_output.iter().for_each(|x| eprintln!("- {x}"));
+        eprintln!("passed as output by the verifier:");
+        expected_public_output
+            .iter()
+            .for_each(|x| eprintln!("- {x}"));
+        panic!("Obtained output does not match expected output");
+    }
+
+    // verify proof
+    verifier_index.verify(full_public_inputs, proof).unwrap();
+
+    Ok(())
+}
+
+/// Compiles an example with an R1CS backend, and checks its output,
+/// as well as its ASM against the fixture at `asm_path` if it is set and the circuit is small.
+fn test_r1cs_file<F: BackendField>(
+    r1cs: R1CS<F>,
+    file_name: &str,
+    code: String,
+    public_inputs: JsonInputs,
+    private_inputs: JsonInputs,
+    expected_public_output: Vec<&str>,
+    asm_path: Option<&Path>,
+) -> miette::Result<()> {
+    // compile
+    let mut sources = Sources::new();
+    let mut tast = TypeChecker::new();
+    let this_module = None;
+    let _node_id = typecheck_next_file(
+        &mut tast,
+        this_module,
+        &mut sources,
+        file_name.to_string(),
+        code,
+        0,
+    )
+    .unwrap();
+
+    let compiled_circuit = compile(&sources, tast, r1cs)?;
+
+    // this should check the constraints
+    let generated_witness = compiled_circuit
+        .generate_witness(public_inputs, private_inputs)
+        .unwrap();
+
+    // check the ASM only if it's not too large
+    let num_constraints = compiled_circuit.circuit.backend.num_constraints();
+    if let Some(asm_path) = asm_path.filter(|_| num_constraints < 100) {
+        let expected_asm = read_asm_fixture(asm_path);
+        let obtained_asm = compiled_circuit.asm(&Sources::new(), false);
+
+        if obtained_asm != expected_asm {
+            eprintln!("obtained:");
+            eprintln!("{obtained_asm}");
+            eprintln!("expected:");
+            eprintln!("{expected_asm}");
+            panic!("Obtained ASM does not match expected ASM");
+        }
+    }
+
+    let expected_public_output = expected_public_output
+        .iter()
+        .map(|x| F::from_str(x).unwrap_or_else(|_| panic!("invalid expected output `{x}`")))
+        .collect::<Vec<_>>();
+
+    if generated_witness.outputs != expected_public_output {
+        eprintln!("obtained by executing the circuit:");
+        generated_witness
+            .outputs
+            .iter()
+            .for_each(|x| eprintln!("- {x}"));
+        eprintln!("passed as output by the verifier:");
+        expected_public_output
+            .iter()
+            .for_each(|x| eprintln!("- {x}"));
+        panic!("Obtained output does not match expected output");
+    }
+
+    Ok(())
+}
+
+/// Reads the expected ASM of an example, which small circuits must have.
+fn read_asm_fixture(asm_path: &Path) -> String {
+    std::fs::read_to_string(asm_path).unwrap_or_else(|err| {
+        panic!(
+            "missing ASM fixture for a small circuit at {}: {err}",
+            asm_path.display()
+        )
+    })
+}
+
+/// Compiles an example with the Bulletproofs backend, checks its output, and proves and verifies it.
+#[cfg(feature = "bulletproofs")]
+fn test_bulletproofs_file(
+    r1cs: R1CS<R1csRistrettoField>,
+    file_name: &str,
+    code: String,
+    public_inputs: JsonInputs,
+    private_inputs: JsonInputs,
+    expected_public_output: Vec<&str>,
+) -> miette::Result<()> {
+    // compile
+    let mut sources = Sources::new();
+    let mut tast = TypeChecker::new();
+    let this_module = None;
+    let _node_id = typecheck_next_file(
+        &mut tast,
+        this_module,
+        &mut sources,
+        file_name.to_string(),
+        code,
+        0,
+    )
+    .unwrap();
+
+    let compiled_circuit = compile(&sources, tast, r1cs)?;
+
+    let (prover_index, verifier_index) = compiled_circuit.compile_to_bulletproofs_indexes()?;
+
+    // create proof
+    let (proof, full_public_inputs, public_output) =
+        prover_index.prove(&sources, public_inputs, private_inputs)?;
+
+    let expected_public_output = expected_public_output
+        .iter()
+        .map(|x| {
+            R1csRistrettoField::from_str(x)
+                .unwrap_or_else(|_| panic!("invalid expected output `{x}`"))
+        })
+        .collect::<Vec<_>>();
+
+    if public_output != expected_public_output {
+        eprintln!("obtained by executing the circuit:");
+        public_output.iter().for_each(|x| eprintln!("- {x}"));
+        eprintln!("passed as output by the verifier:");
+        expected_public_output
+            .iter()
+            .for_each(|x| eprintln!("- {x}"));
+        panic!("Obtained output does not match expected output");
+    }
+
+    // verify proof
+    verifier_index.verify(&full_public_inputs, &proof).unwrap();
+
+    Ok(())
+}
+
+/// Checks that an example can't be proven with the given inputs:
+/// either it doesn't compile, or the inputs don't satisfy its constraints.
+fn test_file_fails(
+    file_name: &str,
+    public_inputs: &str,
+    private_inputs: &str,
+    backend: BackendKind,
+) {
+    let version = env!("CARGO_MANIFEST_DIR");
+    let prefix_examples = Path::new(version).join("examples");
+
+    // read noname file
+    let code = std::fs::read_to_string(prefix_examples.join(format!("{file_name}.no"))).unwrap();
+
+    // parse inputs
+    let public_inputs = parse_inputs(public_inputs).unwrap();
+    let private_inputs = parse_inputs(private_inputs).unwrap();
+
+    // compile
+    let mut sources = Sources::new();
+    let res = match backend {
+        BackendKind::KimchiVesta(kimchi_vesta) => {
             let mut tast = TypeChecker::new();
-            let this_module = None;
-            let _node_id = typecheck_next_file(
+            typecheck_next_file(
                 &mut tast,
-                this_module,
+                None,
                 &mut sources,
                 file_name.to_string(),
-                code.clone(),
+                code,
                 0,
             )
-            .unwrap();
-
-            let compiled_circuit = compile(&sources, tast, kimchi_vesta)?;
-
-            let (prover_index, verifier_index) = compiled_circuit.compile_to_indexes().unwrap();
-
-            // check compiled ASM only if it's not too large
-            let prefix_asm = prefix_examples.join("fixture/asm/kimchi");
-            if prover_index.len() < 100 {
-                let expected_asm =
-                    std::fs::read_to_string(prefix_asm.clone().join(format!("{file_name}.asm")))
-                        .unwrap();
-
-                let obtained_asm = prover_index.asm(&mut Sources::new(), false);
-                if obtained_asm != expected_asm {
-                    eprintln!("obtained:");
-                    eprintln!("{obtained_asm}");
-                    eprintln!("expected:");
-                    eprintln!("{expected_asm}");
-                    panic!("Obtained ASM does not match expected ASM");
-                }
-            }
-
-            // create proof
-            let (proof, full_public_inputs, public_output) = prover_index.prove(
-                &sources,
-                public_inputs.clone(),
-                private_inputs.clone(),
-                false,
-            )?;
-
-            let expected_public_output = expected_public_output
-                .iter()
-                .map(|x| VestaField::from_str(x).unwrap())
-                .collect::<Vec<_>>();
-
-            if public_output != expected_public_output {
-                eprintln!("obtained by executing the circuit:");
-                public_output.iter().for_each(|x| eprintln!("- {x}"));
-                eprintln!("passed as output by the verifier:");
-                expected_public_output
-                    .iter()
-                    .for_each(|x| eprintln!("- {x}"));
-                panic!("Obtained output does not match expected output");
-            }
-
-            // verify proof
-            verifier_index.verify(full_public_inputs, proof).unwrap();
+            .and_then(|_| compile(&sources, tast, kimchi_vesta))
+            .and_then(|compiled_circuit| compiled_circuit.compile_to_indexes())
+            .and_then(|(prover_index, verifier_index)| {
+                // the prover catches the constraints that the witness generation doesn't check
+                let (proof, full_public_inputs, _) =
+                    prover_index.prove(&sources, public_inputs, private_inputs, false)?;
+                verifier_index.verify(full_public_inputs, proof)
+            })
+        }
+        BackendKind::KimchiPallas(kimchi_pallas) => {
+            let mut tast = TypeChecker::new();
+            typecheck_next_file(
+                &mut tast,
+                None,
+                &mut sources,
+                file_name.to_string(),
+                code,
+                0,
+            )
+            .and_then(|_| compile(&sources, tast, kimchi_pallas))
+            .and_then(|compiled_circuit| compiled_circuit.compile_to_indexes())
+            .and_then(|(prover_index, verifier_index)| {
+                // the prover catches the constraints that the witness generation doesn't check
+                let (proof, full_public_inputs, _) =
+                    prover_index.prove(&sources, public_inputs, private_inputs, false)?;
+                verifier_index.verify(full_public_inputs, proof)
+            })
         }
         BackendKind::R1csBls12_381(r1cs) => {
-            // compile
-            let mut sources = Sources::new();
             let mut tast = TypeChecker::new();
-            let this_module = None;
-            let _node_id = typecheck_next_file(
+            typecheck_next_file(
+                &mut tast,
+                None,
+                &mut sources,
+                file_name.to_string(),
+                code,
+                0,
+            )
+            .and_then(|_| compile(&sources, tast, r1cs))
+            .and_then(|compiled_circuit| {
+                // this checks the constraints
+                generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs)
+            })
+            .map(|_| ())
+        }
+        BackendKind::R1csPallas(r1cs) => {
+            let mut tast = TypeChecker::new();
+            typecheck_next_file(
                 &mut tast,
-                this_module,
+                None,
                 &mut sources,
                 file_name.to_string(),
-                code.clone(),
+                code,
                 0,
             )
-            .unwrap();
-
-            let compiled_circuit = compile(&sources, tast, r1cs)?;
-
-            // this should check the constraints
-            let generated_witness = compiled_circuit
-                .generate_witness(public_inputs.clone(), private_inputs.clone())
-                .unwrap();
-
-            // check the ASM
-            if compiled_circuit.circuit.backend.num_constraints() < 100 {
-                let prefix_asm = prefix_examples.join("fixture/asm/r1cs");
-                let expected_asm =
-                    std::fs::read_to_string(prefix_asm.clone().join(format!("{file_name}.asm")))
-                        .unwrap();
-                let obtained_asm = compiled_circuit.asm(&Sources::new(), false);
-
-                if obtained_asm != expected_asm {
-                    eprintln!("obtained:");
-                    eprintln!("{obtained_asm}");
-                    eprintln!("expected:");
-                    eprintln!("{expected_asm}");
-                    panic!("Obtained ASM does not match expected ASM");
-                }
-            }
-
-            let expected_public_output = expected_public_output
-                .iter()
-                .map(|x| crate::backends::r1cs::R1csBls12381Field::from_str(x).unwrap())
-                .collect::<Vec<_>>();
-
-            if generated_witness.outputs != expected_public_output {
-                eprintln!("obtained by executing the circuit:");
-                generated_witness
-                    .outputs
-                    .iter()
-                    .for_each(|x| eprintln!("- {x}"));
-                eprintln!("passed as output by the verifier:");
-                expected_public_output
-                    .iter()
-                    .for_each(|x| eprintln!("- {x}"));
-                panic!("Obtained output does not match expected output");
-            }
+            .and_then(|_| compile(&sources, tast, r1cs))
+            .and_then(|compiled_circuit| {
+                // this checks the constraints
+                generate_witness(&compiled_circuit, &sources, public_inputs, private_inputs)
+            })
+            .map(|_| ())
         }
         BackendKind::R1csBn254(_) => todo!(),
-    }
+        #[cfg(feature = "bulletproofs")]
+        BackendKind::Bulletproofs(r1cs) => {
+            let mut tast = TypeChecker::new();
+            typecheck_next_file(
+                &mut tast,
+                None,
+                &mut sources,
+                file_name.to_string(),
+                code,
+                0,
+            )
+            .and_then(|_| compile(&sources, tast, r1cs))
+            .and_then(|compiled_circuit| compiled_circuit.compile_to_bulletproofs_indexes())
+            .and_then(|(prover_index, verifier_index)| {
+                let (proof, full_public_inputs, _) =
+                    prover_index.prove(&sources, public_inputs, private_inputs)?;
+                verifier_index.verify(&full_public_inputs, &proof)
+            })
+        }
+    };
 
-    Ok(())
+    assert!(
+        res.is_err(),
+        "`{file_name}` should not be provable with these inputs"
+    );
 }
 
 #[rstest]
 #[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
 #[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+#[case::r1cs_pallas(BackendKind::R1csPallas(R1CS::new()))]
+#[case::kimchi_pallas(BackendKind::KimchiPallas(KimchiPallas::new(false)))]
 fn test_arithmetic(#[case] backend: BackendKind) -> miette::Result<()> {
     let public_inputs = r#"{"public_input": "2"}"#;
     let private_inputs = r#"{"private_input": "2"}"#;
@@ -172,6 +451,8 @@ fn test_arithmetic(#[case] backend: BackendKind) -> miette::Result<()> {
 #[rstest]
 #[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
 #[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+#[case::r1cs_pallas(BackendKind::R1csPallas(R1CS::new()))]
+#[case::kimchi_pallas(BackendKind::KimchiPallas(KimchiPallas::new(false)))]
 fn test_public_output(#[case] backend: BackendKind) -> miette::Result<()> {
     let public_inputs = r#"{"public_input": "1"}"#;
     let private_inputs = r#"{"private_input": "1"}"#;
@@ -207,16 +488,27 @@ fn test_lc_return(#[case] backend: BackendKind) -> miette::Result<()> {
 
 #[rstest]
 #[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::kimchi_pallas(BackendKind::KimchiPallas(KimchiPallas::new(false)))]
 //todo: #[case::r1cs(BackendKind::R1CS(R1CS::new()))]
 fn test_poseidon(#[case] backend: BackendKind) -> miette::Result<()> {
     let private_inputs = r#"{"private_input": ["1", "1"]}"#;
-    let private_input = [1.into(), 1.into()];
-    let digest = crate::helpers::poseidon(private_input.clone());
-    let digest_dec = digest.to_dec_string();
-    assert_eq!(
-        "3654913405619483358804575553468071097765421484960111776885779739261304758583",
-        digest_dec
-    );
+    let digest_dec = match backend {
+        // the parameters of Poseidon depend on the field
+        BackendKind::KimchiPallas(_) => {
+            let one = PallasField::one();
+            BigUint::from(kimchi_poseidon::<Pallas>(one, one)).to_string()
+        }
+        _ => {
+            let private_input = [1.into(), 1.into()];
+            let digest = crate::helpers::poseidon(private_input.clone());
+            let digest_dec = digest.to_dec_string();
+            assert_eq!(
+                "3654913405619483358804575553468071097765421484960111776885779739261304758583",
+                digest_dec
+            );
+            digest_dec
+        }
+    };
 
     let public_inputs = &format!(r#"{{"public_input": "{digest_dec}"}}"#);
 
@@ -440,6 +732,7 @@ fn test_literals(#[case] backend: BackendKind) -> miette::Result<()> {
 #[rstest]
 #[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
 #[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+#[case::r1cs_pallas(BackendKind::R1csPallas(R1CS::new()))]
 fn test_public_output_array(#[case] backend: BackendKind) -> miette::Result<()> {
     let public_inputs = r#"{"public_input": "1"}"#;
     let private_inputs = r#"{"private_input": "1"}"#;
@@ -472,3 +765,1426 @@ fn test_types_array_output(#[case] backend: BackendKind) -> miette::Result<()> {
 
     Ok(())
 }
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_comparator(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "2"}"#;
+    let private_inputs = r#"{"yy": "3"}"#;
+
+    test_file("comparator", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_comparator_modulus_minus_one(#[case] backend: BackendKind) {
+    let modulus_minus_one: BigUint = match backend {
+        BackendKind::KimchiVesta(_) => (-VestaField::one()).into(),
+        _ => (-crate::backends::r1cs::R1csBls12381Field::one()).into(),
+    };
+
+    // without a range check, `p - 1 < 0` would hold (as `p - 1 - 0 + 2^252` wraps around the modulus)
+    let public_inputs = format!(r#"{{"xx": "{modulus_minus_one}"}}"#);
+    let private_inputs = r#"{"yy": "0"}"#;
+
+    test_file_fails("comparator", &public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_comparator_too_large(#[case] backend: BackendKind) {
+    // values that don't fit in the bits of the comparison are rejected, even if they are ordered
+    let two_pow_252 = BigUint::from(1u8) << 252;
+    let public_inputs = format!(r#"{{"xx": "{two_pow_252}"}}"#);
+    let private_inputs = format!(r#"{{"yy": "{}"}}"#, &two_pow_252 + 1u8);
+
+    test_file_fails("comparator", &public_inputs, &private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_range_check(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "255"}"#;
+    let private_inputs = format!(
+        r#"{{"yy": "{}", "zz": "{}"}}"#,
+        u64::MAX,
+        (BigUint::from(1u8) << 250) - 1u8
+    );
+
+    test_file(
+        "range_check",
+        public_inputs,
+        &private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta_lookup(BackendKind::KimchiVesta(KimchiVesta::new(false)), "256", "0", "0")]
+#[case::kimchi_vesta_chunks(
+    BackendKind::KimchiVesta(KimchiVesta::new(false)),
+    "0",
+    "18446744073709551616",
+    "0"
+)]
+#[case::kimchi_vesta_bits(
+    BackendKind::KimchiVesta(KimchiVesta::new(false)),
+    "0",
+    "0",
+    "1809251394333065553493296640760748560207343510400633813116524750123642650624"
+)]
+#[case::r1cs_small(BackendKind::R1csBls12_381(R1CS::new()), "256", "0", "0")]
+#[case::r1cs_large(
+    BackendKind::R1csBls12_381(R1CS::new()),
+    "0",
+    "0",
+    "1809251394333065553493296640760748560207343510400633813116524750123642650624"
+)]
+fn test_range_check_fails(
+    #[case] backend: BackendKind,
+    #[case] xx: &str,
+    #[case] yy: &str,
+    #[case] zz: &str,
+) {
+    // the values are one more than the largest values that fit (2^8, 2^64, and 2^250)
+    let public_inputs = format!(r#"{{"xx": "{xx}"}}"#);
+    let private_inputs = format!(r#"{{"yy": "{yy}", "zz": "{zz}"}}"#);
+
+    test_file_fails("range_check", &public_inputs, &private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_inv(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "2"}"#;
+    let private_inputs = r#"{"yy": "4"}"#;
+
+    // 6 / 2
+    test_file("inv", public_inputs, private_inputs, vec!["3"], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_inv_of_zero(#[case] backend: BackendKind) {
+    let public_inputs = r#"{"xx": "0"}"#;
+    let private_inputs = r#"{"yy": "4"}"#;
+
+    test_file_fails("inv", public_inputs, private_inputs, backend);
+}
+
+// Bulletproofs is meant for small statements, so only the small examples are proven with it
+#[cfg(feature = "bulletproofs")]
+#[test]
+fn test_bulletproofs() -> miette::Result<()> {
+    test_file(
+        "arithmetic",
+        r#"{"public_input": "2"}"#,
+        r#"{"private_input": "2"}"#,
+        vec![],
+        BackendKind::new_bulletproofs(),
+    )?;
+    test_file(
+        "public_output",
+        r#"{"public_input": "1"}"#,
+        r#"{"private_input": "1"}"#,
+        vec!["8"],
+        BackendKind::new_bulletproofs(),
+    )?;
+    test_file(
+        "inv",
+        r#"{"xx": "2"}"#,
+        r#"{"yy": "4"}"#,
+        vec!["3"],
+        BackendKind::new_bulletproofs(),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(feature = "bulletproofs")]
+#[test]
+fn test_bulletproofs_fails() {
+    test_file_fails(
+        "inv",
+        r#"{"xx": "0"}"#,
+        r#"{"yy": "4"}"#,
+        BackendKind::new_bulletproofs(),
+    );
+    test_file_fails(
+        "public_output",
+        r#"{"public_input": "1"}"#,
+        r#"{"private_input": "2"}"#,
+        BackendKind::new_bulletproofs(),
+    );
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_pow(#[case] backend: BackendKind) -> miette::Result<()> {
+    // 2^5 = 32, and 32^13 = 2^65
+    let public_inputs = r#"{"xx": "32"}"#;
+    let private_inputs = r#"{"root": "2"}"#;
+
+    test_file(
+        "pow",
+        public_inputs,
+        private_inputs,
+        vec!["36893488147419103232"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_pow_wrong_root(#[case] backend: BackendKind) {
+    let public_inputs = r#"{"xx": "32"}"#;
+    let private_inputs = r#"{"root": "3"}"#;
+
+    test_file_fails("pow", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_shift(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "11"}"#;
+    let private_inputs = r#"{}"#;
+
+    // 0b0110, 0b0101, 0b0111, 0b1101
+    test_file(
+        "shift",
+        public_inputs,
+        private_inputs,
+        vec!["6", "5", "7", "13"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_shift_too_wide(#[case] backend: BackendKind) {
+    // 16 doesn't fit in 4 bits
+    let public_inputs = r#"{"xx": "16"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file_fails("shift", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_to_bits_strict(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "6"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file(
+        "to_bits_strict",
+        public_inputs,
+        private_inputs,
+        vec!["6"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_sqrt(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "9"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file("sqrt", public_inputs, private_inputs, vec!["3"], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+#[case::r1cs_pallas(BackendKind::R1csPallas(R1CS::new()))]
+fn test_sqrt_non_residue(#[case] backend: BackendKind) {
+    // 5 is not a square
+    let public_inputs = r#"{"xx": "5"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file_fails("sqrt", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_try_sqrt_square(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "9"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file(
+        "try_sqrt",
+        public_inputs,
+        private_inputs,
+        vec!["1", "3"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_try_sqrt_non_residue(#[case] backend: BackendKind) -> miette::Result<()> {
+    // 5 is not a square, but 5 * 5 is
+    let public_inputs = r#"{"xx": "5"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file(
+        "try_sqrt",
+        public_inputs,
+        private_inputs,
+        vec!["0", "5"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_is_square(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "9", "yy": "5"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file("is_square", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_is_square_swapped(#[case] backend: BackendKind) {
+    let public_inputs = r#"{"xx": "5", "yy": "9"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file_fails("is_square", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_assert_if_unset(#[case] backend: BackendKind) -> miette::Result<()> {
+    // the values differ, but the condition is not set
+    let public_inputs = r#"{"cond": false, "xx": "1"}"#;
+    let private_inputs = r#"{"yy": "2"}"#;
+
+    test_file("assert_if", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_assert_if_set(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"cond": true, "xx": "2"}"#;
+    let private_inputs = r#"{"yy": "2"}"#;
+
+    test_file("assert_if", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_assert_if_set_fails(#[case] backend: BackendKind) {
+    // the condition is set, so the values must be equal
+    let public_inputs = r#"{"cond": true, "xx": "1"}"#;
+    let private_inputs = r#"{"yy": "2"}"#;
+
+    test_file_fails("assert_if", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_assert_not_eq(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "1"}"#;
+    let private_inputs = r#"{"yy": "2"}"#;
+
+    test_file(
+        "assert_not_eq",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+#[case::r1cs_pallas(BackendKind::R1csPallas(R1CS::new()))]
+fn test_assert_not_eq_equal(#[case] backend: BackendKind) {
+    let public_inputs = r#"{"xx": "2"}"#;
+    let private_inputs = r#"{"yy": "2"}"#;
+
+    test_file_fails("assert_not_eq", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_dynamic_index(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"idx": "2"}"#;
+    let private_inputs = r#"{"arr": ["1", "2", "3", "4"]}"#;
+
+    test_file(
+        "dynamic_index",
+        public_inputs,
+        private_inputs,
+        vec!["3"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_dynamic_index_out_of_range(#[case] backend: BackendKind) {
+    // the array only has 4 elements
+    let public_inputs = r#"{"idx": "4"}"#;
+    let private_inputs = r#"{"arr": ["1", "2", "3", "4"]}"#;
+
+    test_file_fails("dynamic_index", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_set(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"needle": "2"}"#;
+    let private_inputs = r#"{"allowed": ["1", "2", "3"], "denied": ["4", "5"]}"#;
+
+    test_file("set", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_set_constant(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"needle": "5"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file(
+        "set_constant",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_set_constant_not_allowed(#[case] backend: BackendKind) {
+    // on kimchi, the lookup fails
+    let public_inputs = r#"{"needle": "4"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file_fails("set_constant", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_set_denied(#[case] backend: BackendKind) {
+    // the needle is denied, and not allowed
+    let public_inputs = r#"{"needle": "4"}"#;
+    let private_inputs = r#"{"allowed": ["1", "2", "3"], "denied": ["4", "5"]}"#;
+
+    test_file_fails("set", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_bool_ops(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"aa": true}"#;
+    let private_inputs = r#"{"bb": false}"#;
+
+    test_file("bool_ops", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_bool_ops_both_set(#[case] backend: BackendKind) {
+    let public_inputs = r#"{"aa": true}"#;
+    let private_inputs = r#"{"bb": true}"#;
+
+    test_file_fails("bool_ops", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_all_any(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"all_set": [true, true, true]}"#;
+    let private_inputs = r#"{"some_set": [false, true, false]}"#;
+
+    test_file("all_any", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_all_any_none_set(#[case] backend: BackendKind) {
+    // any() fails on an array of false values
+    let public_inputs = r#"{"all_set": [true, true, true]}"#;
+    let private_inputs = r#"{"some_set": [false, false, false]}"#;
+
+    test_file_fails("all_any", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_all_any_one_unset(#[case] backend: BackendKind) {
+    // all() fails as soon as one value is false
+    let public_inputs = r#"{"all_set": [true, false, true]}"#;
+    let private_inputs = r#"{"some_set": [false, true, false]}"#;
+
+    test_file_fails("all_any", public_inputs, private_inputs, backend);
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_division(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "17"}"#;
+    let private_inputs = r#"{"yy": "5"}"#;
+
+    test_file(
+        "division",
+        public_inputs,
+        private_inputs,
+        vec!["3"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_bitwise(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "12"}"#;
+    let private_inputs = r#"{"yy": "10"}"#;
+
+    test_file("bitwise", public_inputs, private_inputs, vec!["6"], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_uint_bitwise(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "4042322160"}"#;
+    let private_inputs = r#"{"yy": "2863311530"}"#;
+
+    test_file(
+        "uint_bitwise",
+        public_inputs,
+        private_inputs,
+        vec!["1515870810"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_uint(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "18446744073709551610"}"#;
+    let private_inputs = r#"{"yy": "5"}"#;
+
+    test_file(
+        "uint",
+        public_inputs,
+        private_inputs,
+        vec!["18446744073709551615"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_signed(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "-5"}"#;
+    let private_inputs = r#"{"yy": "3"}"#;
+
+    test_file("signed", public_inputs, private_inputs, vec!["8"], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_bigint(#[case] backend: BackendKind) -> miette::Result<()> {
+    // xx = p - 1 and yy = 2, so (xx + yy) * yy = 2 mod p
+    let public_inputs = r#"{"xx": ["18446744069414583342", "18446744073709551615", "18446744073709551615", "18446744073709551615"]}"#;
+    let private_inputs = r#"{"yy": ["2", "0", "0", "0"]}"#;
+
+    test_file(
+        "bigint",
+        public_inputs,
+        private_inputs,
+        vec!["2", "0", "0", "0"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_fixed(#[case] backend: BackendKind) -> miette::Result<()> {
+    // 1.5 * 2.25 = 3.375
+    let public_inputs = r#"{"xx": "98304"}"#;
+    let private_inputs = r#"{"yy": "147456"}"#;
+
+    test_file(
+        "fixed",
+        public_inputs,
+        private_inputs,
+        vec!["221184"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_float(#[case] backend: BackendKind) -> miette::Result<()> {
+    // 1.5 and 2.25
+    let public_inputs = r#"{"xx": "1069547520"}"#;
+    let private_inputs = r#"{"yy": "1074790400"}"#;
+
+    test_file(
+        "float",
+        public_inputs,
+        private_inputs,
+        vec!["1079508992"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_min_max(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "3"}"#;
+    let private_inputs = r#"{"yy": "-5"}"#;
+
+    test_file("min_max", public_inputs, private_inputs, vec!["7"], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_dynamic_array(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"idx": "5"}"#;
+    let private_inputs = r#"{"arr": ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15"]}"#;
+
+    test_file(
+        "dynamic_array",
+        public_inputs,
+        private_inputs,
+        vec!["6"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_mem(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"idx": "2"}"#;
+    let private_inputs = r#"{"memory": ["10", "11", "12", "13"]}"#;
+
+    test_file("mem", public_inputs, private_inputs, vec!["9"], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_sort(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": ["5", "3", "8", "1"]}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file("sort", public_inputs, private_inputs, vec!["3"], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_permutation(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": ["1", "2", "3"]}"#;
+    let private_inputs = r#"{"yy": ["3", "1", "2"]}"#;
+
+    test_file(
+        "permutation",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_pack(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"xx": "1"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file(
+        "pack",
+        public_inputs,
+        private_inputs,
+        vec!["9223372036854775808"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_bytes(#[case] backend: BackendKind) -> miette::Result<()> {
+    // 0x04030201
+    let public_inputs = r#"{"xx": "67305985"}"#;
+    let private_inputs = r#"{"data": ["4", "3", "2", "1"]}"#;
+
+    test_file("bytes", public_inputs, private_inputs, vec!["513"], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_sha256(#[case] backend: BackendKind) -> miette::Result<()> {
+    // "abc"
+    let public_inputs = r#"{"message": ["97", "98", "99"]}"#;
+    let private_inputs = r#"{}"#;
+
+    let digest = [
+        186, 120, 22, 191, 143, 1, 207, 234, 65, 65, 64, 222, 93, 174, 34, 35, 176, 3, 97, 163,
+        150, 23, 122, 156, 180, 16, 255, 97, 242, 0, 21, 173,
+    ];
+    let digest: Vec<_> = digest.iter().map(|byte: &u8| byte.to_string()).collect();
+    let digest = digest.iter().map(String::as_str).collect();
+
+    test_file("sha256", public_inputs, private_inputs, digest, backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_hmac(#[case] backend: BackendKind) -> miette::Result<()> {
+    // RFC 4231, test case 2: "what do ya want for nothing?" with the key "Jefe"
+    let public_inputs = r#"{"message": ["119", "104", "97", "116", "32", "100", "111", "32", "121", "97", "32", "119", "97", "110", "116", "32", "102", "111", "114", "32", "110", "111", "116", "104", "105", "110", "103", "63"]}"#;
+    let private_inputs = r#"{"key": ["74", "101", "102", "101"]}"#;
+
+    let mac = [
+        91, 220, 193, 70, 191, 96, 117, 78, 106, 4, 36, 38, 8, 149, 117, 199, 90, 0, 63, 8, 157,
+        39, 57, 131, 157, 236, 88, 185, 100, 236, 56, 67,
+    ];
+    let mac: Vec<_> = mac.iter().map(|byte: &u8| byte.to_string()).collect();
+    let mac = mac.iter().map(String::as_str).collect();
+
+    test_file("hmac", public_inputs, private_inputs, mac, backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_sha512(#[case] backend: BackendKind) -> miette::Result<()> {
+    // "abc"
+    let public_inputs = r#"{"message": ["97", "98", "99"]}"#;
+    let private_inputs = r#"{}"#;
+
+    let digest = [
+        221, 175, 53, 161, 147, 97, 122, 186, 204, 65, 115, 73, 174, 32, 65, 49, 18, 230, 250, 78,
+        137, 169, 126, 162, 10, 158, 238, 230, 75, 85, 211, 154, 33, 146, 153, 42, 39, 79, 193,
+        168, 54, 186, 60, 35, 163, 254, 235, 189, 69, 77, 68, 35, 100, 60, 232, 14, 42, 154, 201,
+        79, 165, 76, 164, 159,
+    ];
+    let digest: Vec<_> = digest.iter().map(|byte: &u8| byte.to_string()).collect();
+    let digest = digest.iter().map(String::as_str).collect();
+
+    test_file("sha512", public_inputs, private_inputs, digest, backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_keccak(#[case] backend: BackendKind) -> miette::Result<()> {
+    // "abc"
+    let public_inputs = r#"{"message": ["97", "98", "99"]}"#;
+    let private_inputs = r#"{}"#;
+
+    let digest = [
+        78, 3, 101, 122, 234, 69, 169, 79, 199, 212, 123, 168, 38, 200, 214, 103, 192, 209, 230,
+        227, 58, 100, 160, 54, 236, 68, 245, 143, 161, 45, 108, 69,
+    ];
+    let digest: Vec<_> = digest.iter().map(|byte: &u8| byte.to_string()).collect();
+    let digest = digest.iter().map(String::as_str).collect();
+
+    test_file("keccak", public_inputs, private_inputs, digest, backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_blake2(#[case] backend: BackendKind) -> miette::Result<()> {
+    // "abc"
+    let public_inputs = r#"{"message": ["97", "98", "99"]}"#;
+    let private_inputs = r#"{}"#;
+
+    let digest = [
+        80, 140, 94, 140, 50, 124, 20, 226, 225, 167, 43, 163, 78, 235, 69, 47, 55, 69, 139, 32,
+        158, 214, 58, 41, 77, 153, 155, 76, 134, 103, 89, 130,
+    ];
+    let digest: Vec<_> = digest.iter().map(|byte: &u8| byte.to_string()).collect();
+    let digest = digest.iter().map(String::as_str).collect();
+
+    test_file("blake2", public_inputs, private_inputs, digest, backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_aes_ctr(#[case] backend: BackendKind) -> miette::Result<()> {
+    // NIST SP 800-38A initial counter block, with the key 000102...0f
+    let public_inputs = r#"{"input": ["100", "101", "102", "103", "104", "105", "106", "107", "108", "109", "110", "111", "112", "113", "114", "115", "116", "117", "118", "119"]}"#;
+    let private_inputs = r#"{"key": ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15"], "iv": ["240", "241", "242", "243", "244", "245", "246", "247", "248", "249", "250", "251", "252", "253", "254", "255"]}"#;
+
+    let output = [
+        2, 194, 161, 143, 92, 59, 91, 35, 251, 60, 176, 104, 67, 103, 223, 222, 198, 244, 161, 119,
+    ];
+    let output: Vec<_> = output.iter().map(|byte: &u8| byte.to_string()).collect();
+    let output = output.iter().map(String::as_str).collect();
+
+    test_file("aes_ctr", public_inputs, private_inputs, output, backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_aes_gcm(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"ciphertext": ["82", "77", "242", "217", "102", "90", "65", "66", "178", "250", "198", "45", "177", "16", "17", "213", "4", "101", "139", "126"], "tag": ["127", "51", "72", "32", "5", "58", "233", "140", "48", "94", "175", "234", "209", "155", "65", "38"]}"#;
+    let private_inputs = r#"{"key": ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15"], "iv": ["200", "201", "202", "203", "204", "205", "206", "207", "208", "209", "210", "211"], "aad": ["0", "1", "2", "3", "4"]}"#;
+
+    let plaintext: Vec<_> = (100..120u8).map(|byte| byte.to_string()).collect();
+    let plaintext = plaintext.iter().map(String::as_str).collect();
+
+    test_file("aes", public_inputs, private_inputs, plaintext, backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_chacha20(#[case] backend: BackendKind) -> miette::Result<()> {
+    // RFC 8439, section 2.4.2 (the first 90 bytes of the plaintext)
+    let public_inputs = r#"{"input": ["76", "97", "100", "105", "101", "115", "32", "97", "110", "100", "32", "71", "101", "110", "116", "108", "101", "109", "101", "110", "32", "111", "102", "32", "116", "104", "101", "32", "99", "108", "97", "115", "115", "32", "111", "102", "32", "39", "57", "57", "58", "32", "73", "102", "32", "73", "32", "99", "111", "117", "108", "100", "32", "111", "102", "102", "101", "114", "32", "121", "111", "117", "32", "111", "110", "108", "121", "32", "111", "110", "101", "32", "116", "105", "112", "32", "102", "111", "114", "32", "116", "104", "101", "32", "102", "117", "116", "117", "114", "101"]}"#;
+    let private_inputs = r#"{"key": ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "21", "22", "23", "24", "25", "26", "27", "28", "29", "30", "31"], "nonce": ["0", "0", "0", "0", "0", "0", "0", "74", "0", "0", "0", "0"]}"#;
+
+    let output = [
+        110, 46, 53, 154, 37, 104, 249, 128, 65, 186, 7, 40, 221, 13, 105, 129, 233, 126, 122, 236,
+        29, 67, 96, 194, 10, 39, 175, 204, 253, 159, 174, 11, 249, 27, 101, 197, 82, 71, 51, 171,
+        143, 89, 61, 171, 205, 98, 179, 87, 22, 57, 214, 36, 230, 81, 82, 171, 143, 83, 12, 53,
+        159, 8, 97, 216, 7, 202, 13, 191, 80, 13, 106, 97, 86, 163, 142, 8, 138, 34, 182, 94, 82,
+        188, 81, 77, 22, 204, 248, 6, 129, 140,
+    ];
+    let output: Vec<_> = output.iter().map(|byte: &u8| byte.to_string()).collect();
+    let output = output.iter().map(String::as_str).collect();
+
+    test_file("chacha20", public_inputs, private_inputs, output, backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_base64(#[case] backend: BackendKind) -> miette::Result<()> {
+    // the header of a JWT: "eyJhbGciOiJIUzI1NiJ9"
+    let public_inputs = r#"{"header": ["101", "121", "74", "104", "98", "71", "99", "105", "79", "105", "74", "73", "85", "122", "73", "49", "78", "105", "74", "57"]}"#;
+    let private_inputs = r#"{}"#;
+
+    // {"alg":"HS256"}
+    let decoded = [
+        123, 34, 97, 108, 103, 34, 58, 34, 72, 83, 50, 53, 54, 34, 125,
+    ];
+    let decoded: Vec<_> = decoded.iter().map(|byte: &u8| byte.to_string()).collect();
+    let decoded = decoded.iter().map(String::as_str).collect();
+
+    test_file("base64", public_inputs, private_inputs, decoded, backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_poseidon2(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"public_input": "1"}"#;
+    let private_inputs = r#"{"private_input": ["1", "2"]}"#;
+
+    test_file("poseidon2", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_poseidon_rates(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"public_input": "1"}"#;
+    let private_inputs = r#"{"private_input": ["1", "2", "3"]}"#;
+
+    test_file(
+        "poseidon_rates",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_rescue(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"public_input": "1"}"#;
+    let private_inputs = r#"{"private_input": ["1", "2"]}"#;
+
+    test_file("rescue", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_anemoi(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"public_input": "1"}"#;
+    let private_inputs = r#"{"private_input": ["1", "2"]}"#;
+
+    test_file("anemoi", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_linalg(#[case] backend: BackendKind) -> miette::Result<()> {
+    // 5.5 and 7 with 8 fractional bits
+    let public_inputs = r#"{"output": ["1408", "1792"]}"#;
+    let private_inputs = r#"{"weights": [["384", "128"], ["64", "512"]], "input": ["512", "768"], "bias": ["256", "128"]}"#;
+
+    test_file("linalg", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_ml(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"label": "2"}"#;
+    let private_inputs = r#"{"logits": ["3", "700", "1792", "1792"]}"#;
+
+    test_file("ml", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_lookup_table(#[case] backend: BackendKind) -> miette::Result<()> {
+    // sigmoid(-2) with 8 fractional bits
+    let public_inputs = r#"{"index": "2"}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file(
+        "lookup_table",
+        public_inputs,
+        private_inputs,
+        vec!["31"],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_transcript(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"values": ["1", "2", "3"]}"#;
+    let private_inputs = r#"{"shuffled": ["3", "1", "2"]}"#;
+
+    test_file("transcript", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_mimc(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"public_input": "1"}"#;
+    let private_inputs = r#"{"private_input": ["1", "2"]}"#;
+
+    test_file("mimc", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_pedersen(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"public_input": "1"}"#;
+    let private_inputs =
+        r#"{"private_input": [true, false, true, true, false, false, true, false]}"#;
+
+    test_file("pedersen", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_poseidon2_var(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"length": "2"}"#;
+    let private_inputs = r#"{"private_input": ["1", "2", "3", "4"]}"#;
+
+    test_file(
+        "poseidon2_var",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+fn test_merkle(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"leaf": "1"}"#;
+    let private_inputs = r#"{"path": ["2", "3"], "index_bits": [true, false]}"#;
+
+    test_file("merkle", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+fn test_sparse_merkle(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"value": "5"}"#;
+    let private_inputs = r#"{"key_bits": [true, false], "path": ["2", "3"]}"#;
+
+    test_file(
+        "sparse_merkle",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_incremental_merkle(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"leaves": ["1", "2", "3"]}"#;
+    let private_inputs = r#"{}"#;
+
+    test_file(
+        "incremental_merkle",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_eddsa(#[case] backend: BackendKind) -> miette::Result<()> {
+    // a signature of 42 under the secret key 123456789, with the nonce 987654321
+    let public_inputs = r#"{"public_key": ["18268781798937852278940252563387690642195059852576912302022209030813721505748", "1"], "message": "42"}"#;
+    let private_inputs = r#"{"signature": ["15394317046282916970288861287358707643270127720502628075222298056027989288352", "1", "4162343149248539639264794879665845127843145523712500603588475730615212061978"]}"#;
+
+    test_file("eddsa", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+/// Returns the first element of the output of the Poseidon builtin of the kimchi backend over the curve `C`.
+fn kimchi_poseidon<C: PastaCurve>(left: C::Field, right: C::Field) -> C::Field {
+    let params = C::poseidon_params();
+    let mut state = vec![left, right, C::Field::zero()];
+    for round in 0..POS_ROWS_PER_HASH * ROUNDS_PER_ROW {
+        full_round::<C::Field, PlonkSpongeConstantsKimchi>(&params, &mut state, round);
+    }
+    state[0]
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+fn test_schnorr(#[case] backend: BackendKind) -> miette::Result<()> {
+    let curve = weierstrass::embedded_curve::<VestaField>().unwrap();
+    let to_string = |elem: VestaField| BigUint::from(elem).to_string();
+
+    // sign 42 with the secret key 123456789
+    let secret_key = BigUint::from(123456789u64);
+    let (public_key_x, public_key_y) = curve.mul(curve.generator, &secret_key).unwrap();
+    let message = VestaField::from(42u64);
+
+    // the nonce is negated if needed, so that R has an even y coordinate
+    let mut nonce = BigUint::from(987654321u64);
+    let (r, r_y) = curve.mul(curve.generator, &nonce).unwrap();
+    if BigUint::from(r_y).bit(0) {
+        nonce = &curve.order - nonce;
+    }
+
+    let challenge = kimchi_poseidon::<Vesta>(public_key_x, public_key_y);
+    let challenge = kimchi_poseidon::<Vesta>(challenge, r);
+    let challenge = BigUint::from(kimchi_poseidon::<Vesta>(challenge, message));
+    let s = (nonce + challenge * secret_key) % &curve.order;
+    let s = VestaField::try_from(s).expect("s doesn't fit in the field");
+
+    let public_inputs = format!(
+        r#"{{"public_key": ["{}", "{}"], "message": "{}"}}"#,
+        to_string(public_key_x),
+        to_string(public_key_y),
+        to_string(message),
+    );
+    let private_inputs = format!(
+        r#"{{"signature": ["{}", "{}"]}}"#,
+        to_string(r),
+        to_string(s),
+    );
+
+    test_file("schnorr", &public_inputs, &private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+fn test_sinsemilla(#[case] backend: BackendKind) -> miette::Result<()> {
+    let curve = weierstrass::embedded_curve::<VestaField>().unwrap();
+    let to_string = |elem: VestaField| BigUint::from(elem).to_string();
+
+    // 0xa53c, in little-endian order
+    let message: Vec<_> = (0..16).map(|ii| (0xa53cu16 >> ii) & 1 == 1).collect();
+    let generators = sinsemilla::Generators::new(&curve, "noname:example");
+    let hash = generators.hash_to_point(&curve, &message).unwrap();
+
+    let blinding = BigUint::from(123456789u64);
+    let blinding_point = curve.mul(generators.r, &blinding);
+    let (commitment, _) = curve.add(Some(hash), blinding_point).unwrap();
+
+    let public_inputs = format!(
+        r#"{{"hash": "{}", "commitment": "{}"}}"#,
+        to_string(hash.0),
+        to_string(commitment),
+    );
+    let private_inputs = format!(r#"{{"message": {message:?}, "blinding": "{blinding}"}}"#);
+
+    test_file(
+        "sinsemilla",
+        &public_inputs,
+        &private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_ecdsa(#[case] backend: BackendKind) -> miette::Result<()> {
+    // a signature of SHA-256("noname") under the secret key 123456789, with the nonce 987654321
+    let public_inputs = r#"{"public_key": [["13224444549907771595", "9512441936170785989", "10095821173921469938", "645408369876628705"], ["1389195532981263150", "14182975036131991897", "5938434390217562176", "14045786586442023102"]], "message_hash": ["6657912691300275155", "8980472608146750634", "5767506416270179312", "93272712278292674"]}"#;
+    let private_inputs = r#"{"signature": [["2345786117927033838", "9354695549020908257", "16882914158131572023", "6544416625939925917"], ["14165870287645984043", "5617645657273618639", "3740151124148426234", "8158890636216445208"]]}"#;
+
+    test_file("ecdsa", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_rsa(#[case] backend: BackendKind) -> miette::Result<()> {
+    // signatures of SHA-256("noname"), generated with a random key
+    let public_inputs = r#"{"modulus": ["8531648986078068243", "15147050157677719579", "4921631935979011236", "2836186297562465773", "11178634740533996045", "956567013936000505", "2096872249661234991", "12876484915600269794", "17022455047041726895", "7622770783512373786", "4478006616982416724", "77367762391571412", "4562467348896521888", "8217054371135218734", "14862298294622706027", "2165685095243744010", "5139529900674958886", "4352596234261607489", "2021123942774847766", "11241959198067305115", "16085868395129143670", "3355566412557575132", "14135961674635489876", "18221423538084481684", "3872191428823119962", "12373689694661911320", "7263436407953085508", "18143127242125316293", "14532098847566603037", "14430669480100526451", "6230654949180801502", "16369042294506072243"], "message_hash": ["1", "75", "95", "9", "162", "149", "184", "194", "80", "10", "76", "135", "45", "145", "251", "240", "124", "161", "12", "65", "171", "182", "124", "170", "92", "101", "168", "81", "78", "132", "147", "211"]}"#;
+    let private_inputs = r#"{"pkcs1v15_signature": ["17546544919035353455", "17069876289975682826", "7369433139078514229", "3960103787223458521", "15407738259961626124", "10051839050351931404", "14212974918606070138", "7192179004427455850", "14094311264167095396", "17975454941270423040", "3304447018441458796", "8717716801258075035", "17127030215736556792", "11156705201000255623", "1635211610248673354", "17693267628614172758", "1789820211866858838", "10226862196233835637", "12887271087762778267", "8446331680798751179", "14157352488196157871", "9319431302866689211", "13589345895172360141", "15198568322232427704", "8418449780911273384", "9054433329590218554", "6871716712716619921", "1879583944269975428", "6253696701928271445", "16393402803058151372", "17032699864618283287", "2937888986903526791"], "pss_signature": ["12828044592363593899", "2388565873867306712", "6948082037148887761", "2555376600000416054", "5549426381502170214", "8094955190478841704", "8213334650960346455", "358902555172397205", "12631367774267659847", "3679425015582340097", "6237116101296072388", "8963874314446817759", "7911770269830054071", "14270404597632841249", "5118988338515562519", "3965683099137779695", "10930459457843106675", "17601249913495584291", "5554804495868375850", "12339324154537420245", "17402693249095403039", "16255647572504060512", "5773072253879486903", "4634568101470907477", "3885147151751232584", "12798658381259814037", "12283391496947829517", "14569891410059038675", "13274819205895759854", "4330979236242652949", "17932361016688744103", "16210333034968084919"]}"#;
+
+    test_file("rsa", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_curve_points(#[case] backend: BackendKind) -> miette::Result<()> {
+    // [5] G on Pallas (kimchi), and on Jubjub (BLS12-381)
+    let public_inputs = match backend {
+        BackendKind::KimchiVesta(_) => {
+            r#"{"public_key": ["2043704922874314040385013091576698457103021424623870194379792173147242419946", "5363959817269906935062331974892998553523781697031104933152007068461890921147"]}"#
+        }
+        _ => {
+            r#"{"public_key": ["26042326206629601463699717420618261050154083680893397040855758342051074128072", "46143779382191562437463724380678129206571673732394720498344010292386074520727"]}"#
+        }
+    };
+    let private_inputs = r#"{"secret_key": "5"}"#;
+
+    test_file(
+        "curve_points",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_ecdh(#[case] backend: BackendKind) -> miette::Result<()> {
+    // [5] G on Pallas (kimchi), and on Jubjub (BLS12-381)
+    let public_inputs = match backend {
+        BackendKind::KimchiVesta(_) => {
+            r#"{"alice_public": ["2043704922874314040385013091576698457103021424623870194379792173147242419946", "5363959817269906935062331974892998553523781697031104933152007068461890921147"]}"#
+        }
+        _ => {
+            r#"{"alice_public": ["26042326206629601463699717420618261050154083680893397040855758342051074128072", "46143779382191562437463724380678129206571673732394720498344010292386074520727"]}"#
+        }
+    };
+    let private_inputs = r#"{"alice_secret": "5", "bob_secret": "7"}"#;
+
+    test_file("ecdh", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_hash_to_curve(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"public_input": "3"}"#;
+    let private_inputs = r#"{"private_input": ["1", "2"]}"#;
+
+    test_file(
+        "hash_to_curve",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_vector_commitment(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"sum": ["4", "6"]}"#;
+    let private_inputs = r#"{"values": ["1", "2"], "other_values": ["3", "4"], "blinding": "5", "other_blinding": "6"}"#;
+
+    test_file(
+        "vector_commitment",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_range_proof(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"deposit": "250"}"#;
+    let private_inputs = r#"{"balance": "1000", "blinding": "7"}"#;
+
+    test_file(
+        "range_proof",
+        public_inputs,
+        private_inputs,
+        vec![],
+        backend,
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_nullifier(#[case] backend: BackendKind) -> miette::Result<()> {
+    let public_inputs = r#"{"leaf_index": "3"}"#;
+    let private_inputs = r#"{"secret_key": "42"}"#;
+
+    test_file("nullifier", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_regex(#[case] backend: BackendKind) -> miette::Result<()> {
+    // "alice.b@mail.com"
+    let public_inputs = r#"{"email": ["97", "108", "105", "99", "101", "46", "98", "64", "109", "97", "105", "108", "46", "99", "111", "109"]}"#;
+    // "hello 123"
+    let private_inputs =
+        r#"{"subject": ["104", "101", "108", "108", "111", "32", "49", "50", "51"]}"#;
+
+    test_file("regex", public_inputs, private_inputs, vec![], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_string(#[case] backend: BackendKind) -> miette::Result<()> {
+    // "mail.com"
+    let public_inputs = r#"{"domain": ["109", "97", "105", "108", "46", "99", "111", "109", "0", "0", "0", "0", "0", "0", "0", "0"]}"#;
+    // "alice@mail.com"
+    let private_inputs = r#"{"email": ["97", "108", "105", "99", "101", "64", "109", "97", "105", "108", "46", "99", "111", "109", "0", "0", "0", "0", "0", "0", "0", "0", "0", "0"], "at": "5"}"#;
+
+    test_file("string", public_inputs, private_inputs, vec!["14"], backend)?;
+
+    Ok(())
+}
+
+#[rstest]
+#[case::kimchi_vesta(BackendKind::KimchiVesta(KimchiVesta::new(false)))]
+#[case::r1cs(BackendKind::R1csBls12_381(R1CS::new()))]
+fn test_json(#[case] backend: BackendKind) -> miette::Result<()> {
+    // {"claims": {"email": "alice@example.com", "sub": 42}}
+    let public_inputs = r#"{"document": ["123", "34", "99", "108", "97", "105", "109", "115", "34", "58", "32", "123", "34", "101", "109", "97", "105", "108", "34", "58", "32", "34", "97", "108", "105", "99", "101", "64", "101", "120", "97", "109", "112", "108", "101", "46", "99", "111", "109", "34", "44", "32", "34", "115", "117", "98", "34", "58", "32", "52", "50", "125", "125"]}"#;
+    let private_inputs = r#"{}"#;
+
+    // "alice@example.com", followed by zeros
+    let mut value = vec![
+        34, 97, 108, 105, 99, 101, 64, 101, 120, 97, 109, 112, 108, 101, 46, 99, 111, 109, 34,
+    ];
+    value.resize(24, 0);
+    let value: Vec<_> = value.iter().map(|byte: &u8| byte.to_string()).collect();
+    let value = value.iter().map(String::as_str).collect();
+
+    test_file("json", public_inputs, private_inputs, value, backend)?;
+
+    Ok(())
+}
@@ -1,2 +1,68 @@
+use crate::{
+    backends::Backend,
+    circuit_writer::CircuitWriter,
+    compiler::{compile, typecheck_next_file, typecheck_next_file_inner, Sources},
+    error::Error,
+    type_checker::TypeChecker,
+    witness::CompiledCircuit,
+};
+
+mod errors;
 mod examples;
 mod modules;
+
+/// The program shared by the tests of the proof systems and the exporters.
+/// With the inputs below it returns 8.
+pub(crate) const CODE: &str = "
+fn main(pub xx: Field, yy: Field) -> Field {
+    let zz = xx * yy;
+    assert_eq(zz, 6);
+    return zz + xx;
+}
+";
+
+pub(crate) const PUBLIC_INPUTS: &str = r#"{"xx": "2"}"#;
+
+pub(crate) const PRIVATE_INPUTS: &str = r#"{"yy": "3"}"#;
+
+/// Private inputs for which the assertion of [CODE] fails.
+pub(crate) const WRONG_PRIVATE_INPUTS: &str = r#"{"yy": "4"}"#;
+
+/// Type checks and compiles a program.
+pub(crate) fn compile_code<B: Backend>(
+    code: &str,
+    backend: B,
+) -> Result<CompiledCircuit<B>, Error> {
+    let mut sources = Sources::new();
+    let mut tast = TypeChecker::new();
+    typecheck_next_file_inner(
+        &mut tast,
+        None,
+        &mut sources,
+        "main.no".to_string(),
+        code.to_string(),
+        0,
+    )?;
+
+    CircuitWriter::generate_circuit(tast, backend)
+}
+
+/// Type checks and compiles a program, returning the sources needed by the provers to report errors.
+pub(crate) fn compile_with_sources<B: Backend>(
+    code: &str,
+    backend: B,
+) -> miette::Result<(CompiledCircuit<B>, Sources)> {
+    let mut sources = Sources::new();
+    let mut tast = TypeChecker::new();
+    typecheck_next_file(
+        &mut tast,
+        None,
+        &mut sources,
+        "main.no".to_string(),
+        code.to_string(),
+        0,
+    )?;
+    let compiled_circuit = compile(&sources, tast, backend)?;
+
+    Ok((compiled_circuit, sources))
+}
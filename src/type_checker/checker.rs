@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use num_bigint::BigUint;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -253,9 +254,10 @@ impl<B: Backend> TypeChecker<B> {
                     .expect("type-checker bug");
 
                 if lhs_node.typ != rhs_node.typ {
-                    // only allow bigint mixed with field
+                    // only allow bigint mixed with field or integers
                     match (&lhs_node.typ, &rhs_node.typ) {
                         (TyKind::BigInt, TyKind::Field) | (TyKind::Field, TyKind::BigInt) => (),
+                        (TyKind::BigInt, int) | (int, TyKind::BigInt) if int.is_integer() => (),
                         _ => {
                             return Err(self.error(
                                 ErrorKind::MismatchType(lhs_node.typ.clone(), rhs_node.typ.clone()),
@@ -265,15 +267,66 @@ impl<B: Backend> TypeChecker<B> {
                     }
                 }
 
+                // comparisons and integer divisions only make sense on field elements,
+                // and bitwise operations on field elements or booleans
+                let is_bitwise = matches!(op, Op2::BitAnd | Op2::BitOr | Op2::BitXor);
+                let is_bool = matches!(lhs_node.typ, TyKind::Bool);
+                if matches!(
+                    op,
+                    Op2::Division
+                        | Op2::Modulo
+                        | Op2::BitAnd
+                        | Op2::BitOr
+                        | Op2::BitXor
+                        | Op2::LessThan
+                        | Op2::LessThanOrEqual
+                        | Op2::GreaterThan
+                        | Op2::GreaterThanOrEqual
+                ) && !lhs_node.typ.is_numeric()
+                    && !(is_bitwise && is_bool)
+                {
+                    return Err(self.error(
+                        ErrorKind::MismatchType(TyKind::Field, lhs_node.typ.clone()),
+                        expr.span,
+                    ));
+                }
+
+                // integer divisions and bitwise operations are not defined on signed integers
+                let is_signed =
+                    lhs_node.typ.int_bits().is_some() || rhs_node.typ.int_bits().is_some();
+                if is_signed
+                    && matches!(
+                        op,
+                        Op2::Division | Op2::Modulo | Op2::BitAnd | Op2::BitOr | Op2::BitXor
+                    )
+                {
+                    return Err(self.error(ErrorKind::UnsupportedSignedOperation, expr.span));
+                }
+
                 let typ = match op {
                     Op2::Equality => TyKind::Bool,
                     Op2::Inequality => TyKind::Bool,
+                    Op2::LessThan
+                    | Op2::LessThanOrEqual
+                    | Op2::GreaterThan
+                    | Op2::GreaterThanOrEqual => TyKind::Bool,
                     Op2::Addition
                     | Op2::Subtraction
                     | Op2::Multiplication
                     | Op2::Division
+                    | Op2::Modulo
                     | Op2::BoolAnd
-                    | Op2::BoolOr => lhs_node.typ,
+                    | Op2::BoolOr
+                    | Op2::BitAnd
+                    | Op2::BitOr
+                    | Op2::BitXor => {
+                        // a constant takes the type of the other operand
+                        if matches!(lhs_node.typ, TyKind::BigInt) {
+                            rhs_node.typ
+                        } else {
+                            lhs_node.typ
+                        }
+                    }
                 };
 
                 Some(ExprTyInfo::new_anon(typ))
@@ -281,14 +334,14 @@ impl<B: Backend> TypeChecker<B> {
 
             ExprKind::Negated(inner) => {
                 let inner_typ = self.compute_type(inner, typed_fn_env)?.unwrap();
-                if !matches!(inner_typ.typ, TyKind::Field | TyKind::BigInt) {
+                if !matches!(inner_typ.typ, TyKind::Field | TyKind::BigInt | TyKind::I64) {
                     return Err(self.error(
                         ErrorKind::MismatchType(TyKind::Field, inner_typ.typ),
                         expr.span,
                     ));
                 }
 
-                Some(ExprTyInfo::new_anon(TyKind::Field))
+                Some(ExprTyInfo::new_anon(inner_typ.typ))
             }
 
             ExprKind::Not(inner) => {
@@ -303,7 +356,17 @@ impl<B: Backend> TypeChecker<B> {
                 Some(ExprTyInfo::new_anon(TyKind::Bool))
             }
 
-            ExprKind::BigUInt(_) => Some(ExprTyInfo::new_anon(TyKind::BigInt)),
+            ExprKind::BigUInt(value) => {
+                // literals must fit in the field of the backend (e.g. in 64 bits for Goldilocks)
+                if B::Field::try_from(value.clone()).is_err() {
+                    return Err(self.error(
+                        ErrorKind::CannotConvertToField(value.to_string()),
+                        expr.span,
+                    ));
+                }
+
+                Some(ExprTyInfo::new_anon(TyKind::BigInt))
+            }
 
             ExprKind::Bool(_) => Some(ExprTyInfo::new_anon(TyKind::Bool)),
 
@@ -361,11 +424,17 @@ impl<B: Backend> TypeChecker<B> {
                     return Err(self.error(ErrorKind::ArrayAccessOnNonArray, expr.span));
                 }
 
-                // check that expression is a bigint
+                // check that the index is a number
+                // (it doesn't have to be a constant, see the circuit writer)
                 let idx_typ = self.compute_type(idx, typed_fn_env)?;
                 match idx_typ.map(|t| t.typ) {
-                    Some(TyKind::BigInt) => (),
-                    _ => return Err(self.error(ErrorKind::ExpectedConstant, expr.span)),
+                    Some(typ) if typ.is_numeric() => (),
+                    Some(typ) => {
+                        return Err(
+                            self.error(ErrorKind::MismatchType(typ, TyKind::Field), expr.span)
+                        )
+                    }
+                    None => return Err(self.error(ErrorKind::ExpectedConstant, expr.span)),
                 };
 
                 // get type of element
@@ -442,6 +511,15 @@ impl<B: Backend> TypeChecker<B> {
                     .compute_type(else_, typed_fn_env)?
                     .expect("can't compute type of first branch of `if/else`");
 
+                // array accesses with a runtime index create constraints (see the circuit writer)
+                for branch in [then_, else_] {
+                    if let ExprKind::ArrayAccess { idx, .. } = &branch.kind {
+                        if !matches!(self.expr_type(idx), Some(TyKind::BigInt)) {
+                            panic!("array accesses in `if` branches must use constant indexes. They can't create constraints.");
+                        }
+                    }
+                }
+
                 // make sure that the type of then_ and else_ match
                 if then_node.typ != else_node.typ {
                     panic!("`if` branch and `else` branch must have matching types");
@@ -614,6 +692,21 @@ impl<B: Backend> TypeChecker<B> {
         Ok(None)
     }
 
+    /// Returns the value of an expression if it is a literal or a global constant.
+    fn constant_value(&self, expr: &Expr) -> Option<BigUint> {
+        match &expr.kind {
+            ExprKind::BigUInt(value) => Some(value.clone()),
+            ExprKind::Variable { module, name } => {
+                let qualified = FullyQualified::new(module, &name.value);
+                let cst = self.const_info(&qualified)?;
+                match &cst.value[..] {
+                    [value] => Some((*value).into()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
     /// type checks a function call.
     /// Note that this can also be a method call.
     pub fn check_fn_call(
@@ -665,8 +758,15 @@ impl<B: Backend> TypeChecker<B> {
         }
 
         // compare argument types with the function signature
+        // (and record the sizes of generic arrays along the way)
+        let mut generic_sizes = HashMap::new();
         for (sig_arg, (typ, span)) in expected.iter().zip(observed) {
-            if !typ.match_expected(&sig_arg.typ.kind) {
+            if !typ.match_expected(&sig_arg.typ.kind)
+                || !sig_arg
+                    .typ
+                    .kind
+                    .bind_generic_sizes(&typ, &mut generic_sizes)
+            {
                 return Err(self.error(
                     ErrorKind::ArgumentTypeMismatch(sig_arg.typ.kind.clone(), typ),
                     span,
@@ -674,7 +774,31 @@ impl<B: Backend> TypeChecker<B> {
             }
         }
 
+        // a const argument can also give its value to the generic size of the same name
+        // (e.g. `unpack_le(val: Field, const Len: Field) -> [Bool; Len]`)
+        for (sig_arg, arg) in expected.iter().zip(args) {
+            if !sig_arg.is_constant() || !is_type(&sig_arg.name.value) {
+                continue;
+            }
+
+            let size = self
+                .constant_value(arg)
+                .and_then(|size| u32::try_from(size).ok())
+                .ok_or_else(|| self.error(ErrorKind::ExpectedConstant, arg.span))?;
+
+            let bound = generic_sizes.insert(sig_arg.name.value.clone(), size);
+            if matches!(bound, Some(bound) if bound != size) {
+                return Err(self.error(
+                    ErrorKind::ArgumentTypeMismatch(sig_arg.typ.kind.clone(), TyKind::BigInt),
+                    arg.span,
+                ));
+            }
+        }
+
         // return the return type of the function
-        Ok(fn_sig.return_type.as_ref().map(|ty| ty.kind.clone()))
+        Ok(fn_sig
+            .return_type
+            .as_ref()
+            .map(|ty| ty.kind.resolve_generic_sizes(&generic_sizes)))
     }
 }
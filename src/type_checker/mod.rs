@@ -11,7 +11,13 @@ use crate::{
         types::{FuncOrMethod, FunctionDef, ModulePath, RootKind, Ty, TyKind},
         CustomType, Expr, StructDef,
     },
-    stdlib::{builtin_fns, crypto::crypto_fns, QUALIFIED_BUILTINS},
+    stdlib::{
+        bigint::bigint_fns, bits::bits_fns, builtin_fns, bytes::bytes_fns, crypto::crypto_fns,
+        encoding::encoding_fns, fixed::fixed_fns, float::float_fns, json::json_fns,
+        linalg::linalg_fns, mem::mem_fns, merkle::merkle_fns, ml::ml_fns, nullifier::nullifier_fns,
+        regex::regex_fns, set::set_fns, string::string_fns, table::table_fns,
+        transcript::transcript_fns, QUALIFIED_BUILTINS,
+    },
 };
 
 use ark_ff::Field;
@@ -127,7 +133,11 @@ impl<B: Backend> TypeChecker<B> {
             }
             TyKind::BigInt => 1,
             TyKind::Array(typ, len) => (*len as usize) * self.size_of(typ),
+            TyKind::GenericArray(..) => {
+                unreachable!("generic arrays only appear in the signatures of builtin functions")
+            }
             TyKind::Bool => 1,
+            TyKind::U8 | TyKind::U16 | TyKind::U32 | TyKind::U64 | TyKind::I64 => 1,
         }
     }
 }
@@ -142,29 +152,36 @@ impl<B: Backend> TypeChecker<B> {
             node_types: HashMap::new(),
         };
 
-        // initialize it with the builtins
-        let builtin_module = ModulePath::Absolute(UserRepo::new(QUALIFIED_BUILTINS));
-        for fn_info in builtin_fns() {
-            let qualified = FullyQualified::new(&builtin_module, &fn_info.sig().name.value);
-            if type_checker
-                .functions
-                .insert(qualified, fn_info.clone())
-                .is_some()
-            {
-                panic!("type-checker bug: global imports conflict");
-            }
-        }
-
-        // initialize it with the standard library
-        let crypto_module = ModulePath::Absolute(UserRepo::new("std/crypto"));
-        for fn_info in crypto_fns() {
-            let qualified = FullyQualified::new(&crypto_module, &fn_info.sig().name.value);
-            if type_checker
-                .functions
-                .insert(qualified, fn_info.clone())
-                .is_some()
-            {
-                panic!("type-checker bug: global imports conflict");
+        // initialize it with the builtins and the standard library
+        let modules: [(&str, Vec<FnInfo<B>>); 19] = [
+            (QUALIFIED_BUILTINS, builtin_fns()),
+            ("std/crypto", crypto_fns()),
+            ("std/bits", bits_fns()),
+            ("std/bytes", bytes_fns()),
+            ("std/bigint", bigint_fns()),
+            ("std/encoding", encoding_fns()),
+            ("std/fixed", fixed_fns()),
+            ("std/float", float_fns()),
+            ("std/json", json_fns()),
+            ("std/mem", mem_fns()),
+            ("std/merkle", merkle_fns()),
+            ("std/nullifier", nullifier_fns()),
+            ("std/regex", regex_fns()),
+            ("std/set", set_fns()),
+            ("std/string", string_fns()),
+            ("std/linalg", linalg_fns()),
+            ("std/ml", ml_fns()),
+            ("std/table", table_fns()),
+            ("std/transcript", transcript_fns()),
+        ];
+
+        for (module_name, fns) in modules {
+            let module = ModulePath::Absolute(UserRepo::new(module_name));
+            for fn_info in fns {
+                let qualified = FullyQualified::new(&module, &fn_info.sig().name.value);
+                if type_checker.functions.insert(qualified, fn_info).is_some() {
+                    panic!("type-checker bug: global imports conflict");
+                }
             }
         }
 
@@ -245,6 +262,15 @@ impl<B: Backend> TypeChecker<B> {
                         ..
                     } = struct_def;
 
+                    // generic array sizes are reserved to builtin functions
+                    if let Some((_, typ)) = fields.iter().find(|(_, typ)| typ.kind.is_generic()) {
+                        return Err(Error::new(
+                            "type-checker",
+                            ErrorKind::GenericSizeOutsideBuiltin,
+                            typ.span,
+                        ));
+                    }
+
                     let fields: Vec<_> = fields
                         .iter()
                         .map(|field| {
@@ -330,6 +356,22 @@ impl<B: Backend> TypeChecker<B> {
                         }
                     };
 
+                    // generic array sizes are reserved to builtin functions
+                    let generic_typ = function
+                        .sig
+                        .arguments
+                        .iter()
+                        .map(|arg| &arg.typ)
+                        .chain(&function.sig.return_type)
+                        .find(|typ| typ.kind.is_generic());
+                    if let Some(typ) = generic_typ {
+                        return Err(Error::new(
+                            "type-checker",
+                            ErrorKind::GenericSizeOutsideBuiltin,
+                            typ.span,
+                        ));
+                    }
+
                     // store variables and their types in the fn_env
                     for arg in &function.sig.arguments {
                         // public_output is a reserved name,
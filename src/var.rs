@@ -15,6 +15,12 @@ use crate::{
 /// The signature of a hint function
 pub type HintFn<B: Backend> = dyn Fn(&B, &mut WitnessEnv<B::Field>) -> Result<B::Field>;
 
+/// The signature of a function computing a value from the values of other cells (see [Value::Compute]).
+pub type ComputeFn<F> = dyn Fn(&[F]) -> F;
+
+/// The signature of a function computing several values at once from the values of other cells (see [Value::ComputeNth]).
+pub type ComputeManyFn<F> = dyn Fn(&[F]) -> Vec<F>;
+
 /// A variable's actual value in the witness can be computed in different ways.
 #[derive(Clone, Serialize, Deserialize)]
 pub enum Value<B>
@@ -46,6 +52,37 @@ where
     /// Note that it will potentially return 0 if the given variable is 0.
     Inverse(B::Var),
 
+    /// Returns the inverse of the given variable.
+    /// Unlike [Value::Inverse], this fails if the given variable is 0.
+    NonZeroInverse(B::Var),
+
+    /// Returns the smallest square root of the given variable.
+    /// This fails if the given variable is not a square.
+    Sqrt(B::Var),
+
+    /// Returns the n-th bit of the given variable (in little-endian order).
+    NthBit(B::Var, usize),
+
+    /// Computes a value by applying a function on the values of some cells.
+    /// Unlike [Value::Hint], the function does not have access to the backend,
+    /// which makes it usable from backend-agnostic gadgets.
+    #[serde(skip)]
+    Compute(Vec<ConstOrCell<B::Field, B::Var>>, Arc<ComputeFn<B::Field>>),
+
+    /// Returns the n-th value computed by a function on the values of some cells.
+    /// The vars created with the same function (the same [Arc]) share its computation,
+    /// which only runs once per witness instead of once per var.
+    #[serde(skip)]
+    ComputeNth(
+        Vec<ConstOrCell<B::Field, B::Var>>,
+        Arc<ComputeManyFn<B::Field>>,
+        usize,
+    ),
+
+    /// Returns the quotient (or the remainder if the flag is set)
+    /// of the integer division of the first variable by the second one.
+    DivMod(B::Var, B::Var, bool /* remainder */),
+
     /// A public or private input to the function
     /// There's an index associated to a variable name, as the variable could be composed of several field elements.
     External(String, usize),
@@ -63,6 +100,12 @@ impl<B: Backend> std::fmt::Debug for Value<B> {
             Value::LinearCombination(..) => write!(f, "LinearCombination"),
             Value::Mul(..) => write!(f, "Mul"),
             Value::Inverse(_) => write!(f, "Inverse"),
+            Value::NonZeroInverse(_) => write!(f, "NonZeroInverse"),
+            Value::Sqrt(_) => write!(f, "Sqrt"),
+            Value::NthBit(..) => write!(f, "NthBit"),
+            Value::DivMod(..) => write!(f, "DivMod"),
+            Value::Compute(..) => write!(f, "Compute"),
+            Value::ComputeNth(..) => write!(f, "ComputeNth"),
             Value::External(..) => write!(f, "External"),
             Value::PublicOutput(..) => write!(f, "PublicOutput"),
             Value::Scale(..) => write!(f, "Scaling"),
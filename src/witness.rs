@@ -21,6 +21,10 @@ where
     pub var_values: HashMap<String, Vec<F>>,
 
     pub cached_values: HashMap<usize, F>,
+
+    /// The values of the functions shared by several vars (see [crate::var::Value::ComputeNth]),
+    /// by the address of the function.
+    pub shared_values: HashMap<usize, Vec<F>>,
 }
 
 impl<F: Field> WitnessEnv<F> {
@@ -56,10 +60,20 @@ impl<B: Backend> CompiledCircuit<B> {
     }
 
     pub fn generate_witness(
+        &self,
+        public_inputs: JsonInputs,
+        private_inputs: JsonInputs,
+    ) -> Result<B::GeneratedWitness> {
+        let mut env = self.witness_env(public_inputs, private_inputs)?;
+        self.circuit.generate_witness(&mut env)
+    }
+
+    /// Creates the witness environment of the circuit, out of the values of the arguments of main.
+    pub(crate) fn witness_env(
         &self,
         mut public_inputs: JsonInputs,
         mut private_inputs: JsonInputs,
-    ) -> Result<B::GeneratedWitness> {
+    ) -> Result<WitnessEnv<B::Field>> {
         let mut env = WitnessEnv::default();
 
         // get info on main
@@ -107,6 +121,54 @@ impl<B: Backend> CompiledCircuit<B> {
             ));
         }
 
-        self.circuit.generate_witness(&mut env)
+        Ok(env)
+    }
+
+    /// Returns the public inputs of the verifier: the public output (if main returns one), then the public arguments.
+    pub fn full_public_inputs(
+        &self,
+        mut public_inputs: JsonInputs,
+        public_output: Option<serde_json::Value>,
+    ) -> Result<Vec<B::Field>> {
+        let main_info = self.main_info();
+        let main_sig = match &main_info.kind {
+            crate::imports::FnKind::BuiltIn(_, _) => unreachable!(),
+            crate::imports::FnKind::Native(fn_sig) => &fn_sig.sig,
+        };
+
+        let mut res = vec![];
+        if let Some(typ) = &main_sig.return_type {
+            let output = public_output
+                .ok_or_else(|| Error::new("runtime", ErrorKind::MissingPublicOutput, typ.span))?;
+            res.extend(
+                self.parse_single_input(output, &typ.kind)
+                    .map_err(|e| Error::new("runtime", ErrorKind::ParsingError(e), typ.span))?,
+            );
+        }
+
+        for arg in main_sig.arguments.iter().filter(|arg| arg.is_public()) {
+            let name = &arg.name.value;
+            let input = public_inputs.0.remove(name).ok_or_else(|| {
+                Error::new(
+                    "runtime",
+                    ErrorKind::MissingPublicArg(name.clone()),
+                    arg.span,
+                )
+            })?;
+            res.extend(
+                self.parse_single_input(input, &arg.typ.kind)
+                    .map_err(|e| Error::new("runtime", ErrorKind::ParsingError(e), arg.span))?,
+            );
+        }
+
+        if let Some(name) = public_inputs.0.keys().next() {
+            return Err(Error::new(
+                "runtime",
+                ErrorKind::UnusedInput(name.clone()),
+                main_info.span,
+            ));
+        }
+
+        Ok(res)
     }
 }